@@ -0,0 +1,9 @@
+pub mod aberration;
+pub mod apparent;
+pub mod deflection;
+pub mod earth;
+pub mod nutation;
+pub mod precession;
+pub mod propermotion;
+pub mod refraction;
+pub mod sidereal;
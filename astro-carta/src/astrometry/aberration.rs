@@ -0,0 +1,83 @@
+use crate::constants::SCHWARZSCHILD_RADIUS_SUN;
+use crate::math::Vector3;
+
+/// Applies stellar aberration to a natural direction using the relativistic formulation of SOFA `iauAb`.
+///
+/// # Arguments
+///
+/// * `direction` - Natural direction to the source (unit vector).
+/// * `velocity` - Barycentric velocity of the observer in units of the speed of light.
+/// * `sun_distance` - Distance between the Sun and the observer in AU.
+/// * `inverse_lorentz_factor` - sqrt(1 - |v|²), the reciprocal of the Lorentz factor.
+///
+/// # Returns
+///
+/// The proper direction to the source (unit vector).
+pub fn aberration(
+    direction: &Vector3,
+    velocity: &Vector3,
+    sun_distance: f64,
+    inverse_lorentz_factor: f64,
+) -> Vector3 {
+    let bm1 = inverse_lorentz_factor;
+    let pdv = direction.dot(velocity);
+    let w1 = 1.0 + pdv / (1.0 + bm1);
+    let w2 = SCHWARZSCHILD_RADIUS_SUN / sun_distance;
+
+    let p = *direction * bm1 + *velocity * w1 + (*velocity - *direction * pdv) * w2;
+    p.normalize()
+}
+
+/// Applies stellar aberration for an observer moving with the given velocity, computing the
+/// Lorentz factor from the velocity.
+///
+/// # Arguments
+///
+/// * `direction` - Natural direction to the source (unit vector).
+/// * `velocity` - Barycentric velocity of the observer in units of the speed of light.
+/// * `sun_distance` - Distance between the Sun and the observer in AU.
+pub fn aberration_for_velocity(
+    direction: &Vector3,
+    velocity: &Vector3,
+    sun_distance: f64,
+) -> Vector3 {
+    let inverse_lorentz_factor = (1.0 - velocity.dot(velocity)).sqrt();
+    aberration(direction, velocity, sun_distance, inverse_lorentz_factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sofa_ab_test() {
+        // SOFA t_sofa_c test case for iauAb
+        let pnat = Vector3::new(
+            -0.763_219_685_467_379_5,
+            -0.608_694_539_830_603_8,
+            -0.216_764_085_806_398_8,
+        );
+        let v = Vector3::new(
+            2.104_401_889_365_378_6e-5,
+            -8.910_892_330_442_932e-5,
+            -3.863_371_479_771_657e-5,
+        );
+        let s = 0.999_809_213_957_087_9;
+        let bm1 = 0.999_999_995_062_092_6;
+
+        let ppr = aberration(&pnat, &v, s, bm1);
+        assert!((ppr.x + 0.763_163_109_421_955_6).abs() < 1e-12);
+        assert!((ppr.y + 0.608_755_308_250_559_1).abs() < 1e-12);
+        assert!((ppr.z + 0.216_792_626_936_847_1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn classical_limit_test() {
+        // For a source at right angles to the motion the displacement is about v/c towards the apex
+        let pnat = Vector3::new(1.0, 0.0, 0.0);
+        let v = Vector3::new(0.0, 1e-4, 0.0);
+        let ppr = aberration_for_velocity(&pnat, &v, 1e9);
+        assert!((ppr.y.asin() - 1e-4).abs() < 1e-12);
+        assert!((ppr.norm() - 1.0).abs() < 1e-15);
+    }
+}
@@ -0,0 +1,262 @@
+use super::aberration;
+use super::deflection;
+use super::earth::EarthState;
+use super::precession;
+use super::propermotion::CatalogStar;
+use super::refraction::Refraction;
+use crate::constants::{ASTRONOMICAL_UNIT, SECONDS_PER_DAY, SPEED_OF_LIGHT};
+use crate::coordinates::{Equatorial, Horizontal, Observer};
+use crate::datetime::DateTime;
+use crate::math::Vector3;
+
+/// Speed of light in AU/day
+const SPEED_OF_LIGHT_AU_PER_DAY: f64 = SPEED_OF_LIGHT * SECONDS_PER_DAY / ASTRONOMICAL_UNIT;
+
+/// Computes the proper direction (GCRS axes) to a star for an observer with the given state.
+///
+/// Applies space motion and parallax, light deflection by the Sun and relativistic aberration.
+///
+/// # Arguments
+///
+/// * `star` - The catalogue entry.
+/// * `dt` - The instant of observation.
+/// * `barycentric_position` - Barycentric position of the observer in AU.
+/// * `barycentric_velocity` - Barycentric velocity of the observer in AU/day.
+/// * `heliocentric_position` - Heliocentric position of the observer in AU.
+pub fn proper_direction(
+    star: &CatalogStar,
+    dt: &DateTime,
+    barycentric_position: &Vector3,
+    barycentric_velocity: &Vector3,
+    heliocentric_position: &Vector3,
+) -> Vector3 {
+    let astrometric = star.direction(dt, barycentric_position);
+
+    let sun_distance = heliocentric_position.norm();
+    let sun_direction = *heliocentric_position / sun_distance;
+    let deflected =
+        deflection::light_deflection_sun(&astrometric, &sun_direction, sun_distance).normalize();
+
+    let velocity = *barycentric_velocity / SPEED_OF_LIGHT_AU_PER_DAY;
+    aberration::aberration_for_velocity(&deflected, &velocity, sun_distance)
+}
+
+/// Converts a GCRS direction into right ascension and declination on the true equator and equinox of date.
+fn true_of_date(direction: &Vector3, dt: &DateTime) -> Equatorial {
+    let (right_ascension, declination) =
+        (precession::bias_precession_nutation_matrix(dt) * *direction).to_spherical();
+    Equatorial::new(right_ascension, declination)
+}
+
+/// Computes the geocentric apparent place of a star, referred to the true equator and equinox of date.
+///
+/// # Arguments
+///
+/// * `star` - The catalogue entry.
+/// * `dt` - The instant of observation.
+/// * `earth` - Position and velocity of the Earth at the instant.
+pub fn apparent_place(star: &CatalogStar, dt: &DateTime, earth: &EarthState) -> Equatorial {
+    let direction = proper_direction(
+        star,
+        dt,
+        &earth.barycentric_position,
+        &earth.barycentric_velocity,
+        &earth.heliocentric_position,
+    );
+    true_of_date(&direction, dt)
+}
+
+/// Computes the topocentric apparent place of a star, referred to the true equator and equinox of date.
+///
+/// In addition to the geocentric corrections this includes diurnal parallax and diurnal aberration.
+///
+/// # Arguments
+///
+/// * `star` - The catalogue entry.
+/// * `dt` - The instant of observation.
+/// * `earth` - Position and velocity of the Earth at the instant.
+/// * `observer` - The observing site.
+/// * `dut1` - UT1 - UTC in seconds.
+pub fn topocentric_place(
+    star: &CatalogStar,
+    dt: &DateTime,
+    earth: &EarthState,
+    observer: &Observer,
+    dut1: f64,
+) -> Equatorial {
+    let (position, velocity) = observer.gcrs_position_velocity(dt, dut1);
+    let position = position / ASTRONOMICAL_UNIT;
+    let velocity = velocity * SECONDS_PER_DAY / ASTRONOMICAL_UNIT;
+
+    let direction = proper_direction(
+        star,
+        dt,
+        &(earth.barycentric_position + position),
+        &(earth.barycentric_velocity + velocity),
+        &(earth.heliocentric_position + position),
+    );
+    true_of_date(&direction, dt)
+}
+
+/// Computes the observed azimuth and elevation of a star, including refraction.
+///
+/// # Arguments
+///
+/// * `star` - The catalogue entry.
+/// * `dt` - The instant of observation.
+/// * `earth` - Position and velocity of the Earth at the instant.
+/// * `observer` - The observing site.
+/// * `dut1` - UT1 - UTC in seconds.
+/// * `refraction` - The refraction model to apply.
+pub fn observed_place(
+    star: &CatalogStar,
+    dt: &DateTime,
+    earth: &EarthState,
+    observer: &Observer,
+    dut1: f64,
+    refraction: &dyn Refraction,
+) -> Horizontal {
+    let topocentric = topocentric_place(star, dt, earth, observer, dut1);
+    observer.observed_horizontal(&topocentric, dt, dut1, refraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::astrometry::refraction::{NoRefraction, RefractionConstants};
+    use crate::datetime::TimeScale;
+    use crate::math;
+
+    fn theta_persei() -> CatalogStar {
+        let j2000 = DateTime::gregorian_with_scale(2000, 1, 1, 12, 0, 0.0, TimeScale::TT).unwrap();
+        CatalogStar::new(
+            ((2.0 + 44.0 / 60.0 + 11.986 / 3600.0) * 15.0_f64).to_radians(),
+            (49.0 + 13.0 / 60.0 + 42.48 / 3600.0_f64).to_radians(),
+            j2000,
+        )
+        .with_proper_motion(
+            math::arcseconds_to_radians(0.034_25 * 15.0),
+            math::arcseconds_to_radians(-0.089_5),
+        )
+    }
+
+    #[test]
+    fn meeus_example_23a_test() {
+        // Meeus, Astronomical Algorithms, Example 23.a: apparent place of θ Persei at 2028 Nov 13.19 TD.
+        // Meeus works in FK5 and neglects light deflection, so agreement is at the 0.1" level.
+        let dt = DateTime::from_julian_date(2_462_088.69, TimeScale::TT);
+        let earth = EarthState::approximate(&dt);
+        let place = apparent_place(&theta_persei(), &dt, &earth);
+
+        let ra_expected = ((2.0 + 46.0 / 60.0 + 14.390 / 3600.0) * 15.0_f64).to_radians();
+        let dec_expected = (49.0 + 21.0 / 60.0 + 7.45 / 3600.0_f64).to_radians();
+        let dra = (place.right_ascension - ra_expected) * dec_expected.cos();
+        assert!((dra * math::ARCSECONDS_PER_RADIAN).abs() < 0.1);
+        assert!(((place.declination - dec_expected) * math::ARCSECONDS_PER_RADIAN).abs() < 0.1);
+    }
+
+    #[test]
+    fn earth_state_test() {
+        // Barycentric Earth of the SOFA t_sofa_c test case for iauEpv00 (2005 February 12)
+        let dt = DateTime::from_julian_date(2_400_000.5 + 53_411.525_011_61, TimeScale::TDB);
+        let earth = EarthState {
+            heliocentric_position: Vector3::new(
+                -0.775_723_880_929_770_7,
+                0.559_805_224_136_334,
+                0.242_699_846_648_168_7,
+            ),
+            barycentric_position: Vector3::new(
+                -0.771_410_444_049_111_2,
+                0.559_841_206_182_417_1,
+                0.242_599_627_772_245_2,
+            ),
+            barycentric_velocity: Vector3::new(
+                -0.109_187_426_811_682_3e-1,
+                -0.124_652_546_173_286_2e-1,
+                -0.540_477_318_096_623_1e-2,
+            ),
+        };
+
+        // The approximate Earth state moves apparent places by less than a milliarcsecond,
+        // whereas neglecting the motion of the Sun about the barycenter costs 6 mas here
+        let star = theta_persei();
+        let direction = |earth: &EarthState| {
+            let place = apparent_place(&star, &dt, earth);
+            Vector3::from_spherical(place.right_ascension, place.declination)
+        };
+        let reference = direction(&earth);
+        let approximate = direction(&EarthState::approximate(&dt));
+        assert!(approximate.angle(&reference) * math::ARCSECONDS_PER_RADIAN < 1e-3);
+
+        let heliocentric = EarthState {
+            barycentric_velocity: Vector3::new(
+                -0.109_189_182_414_731_4e-1,
+                -0.124_718_726_844_084_5e-1,
+                -0.540_756_941_806_503_9e-2,
+            ),
+            ..earth
+        };
+        let error = direction(&heliocentric).angle(&reference) * math::ARCSECONDS_PER_RADIAN;
+        assert!(error > 5e-3, "{error}");
+    }
+
+    #[test]
+    fn topocentric_place_test() {
+        let dt = DateTime::gregorian_with_scale(2024, 11, 13, 22, 0, 0.0, TimeScale::UTC).unwrap();
+        let earth = EarthState::approximate(&dt);
+        let observer = Observer::from_degrees(52.2, 0.1, 30.0);
+        let star = theta_persei();
+
+        let geocentric = apparent_place(&star, &dt, &earth);
+        let topocentric = topocentric_place(&star, &dt, &earth, &observer, 0.0);
+
+        // Diurnal aberration is at most 0.32"; diurnal parallax of a star is negligible
+        let separation =
+            Vector3::from_spherical(geocentric.right_ascension, geocentric.declination).angle(
+                &Vector3::from_spherical(topocentric.right_ascension, topocentric.declination),
+            );
+        let arcseconds = separation * math::ARCSECONDS_PER_RADIAN;
+        assert!(arcseconds > 0.01 && arcseconds < 0.33);
+
+        let airless = observed_place(&star, &dt, &earth, &observer, 0.0, &NoRefraction);
+        let refracted = observed_place(
+            &star,
+            &dt,
+            &earth,
+            &observer,
+            0.0,
+            &RefractionConstants::for_observer(&observer, 0.55),
+        );
+        assert_eq!(airless, observer.horizontal(&topocentric, &dt, 0.0));
+        assert!(refracted.elevation > airless.elevation);
+    }
+
+    #[test]
+    fn annual_aberration_test() {
+        // Annual aberration displaces a star near the ecliptic pole by about 20.5"
+        let dt = DateTime::gregorian(2024, 3, 20, 0, 0, 0.0).unwrap();
+        let earth = EarthState::approximate(&dt);
+        let star = CatalogStar::new(1.5 * std::f64::consts::PI, 66.56_f64.to_radians(), dt);
+
+        let at_rest = EarthState {
+            barycentric_velocity: Vector3::zeros(),
+            ..earth
+        };
+        let moving = proper_direction(
+            &star,
+            &dt,
+            &earth.barycentric_position,
+            &earth.barycentric_velocity,
+            &earth.heliocentric_position,
+        );
+        let still = proper_direction(
+            &star,
+            &dt,
+            &at_rest.barycentric_position,
+            &at_rest.barycentric_velocity,
+            &at_rest.heliocentric_position,
+        );
+        let arcseconds = moving.angle(&still) * math::ARCSECONDS_PER_RADIAN;
+        assert!((arcseconds - 20.5).abs() < 0.5);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::constants::SCHWARZSCHILD_RADIUS_SUN;
+use crate::math::Vector3;
+
+/// Applies light deflection by a solar system body, as in SOFA `iauLd`.
+///
+/// # Arguments
+///
+/// * `mass` - Mass of the deflecting body in solar masses.
+/// * `direction` - Direction from the observer to the source (unit vector).
+/// * `source_direction` - Direction from the deflector to the source (unit vector).
+/// * `observer_direction` - Direction from the deflector to the observer (unit vector).
+/// * `observer_distance` - Distance from the deflector to the observer in AU.
+/// * `limit` - Deflection limiter, bounding the correction for sources close to the body.
+///
+/// # Returns
+///
+/// The deflected direction from the observer to the source. The result is not renormalized.
+pub fn light_deflection(
+    mass: f64,
+    direction: &Vector3,
+    source_direction: &Vector3,
+    observer_direction: &Vector3,
+    observer_distance: f64,
+    limit: f64,
+) -> Vector3 {
+    let qpe = *source_direction + *observer_direction;
+    let qdqpe = source_direction.dot(&qpe);
+
+    let w = mass * SCHWARZSCHILD_RADIUS_SUN / observer_distance / qdqpe.max(limit);
+    let eq = observer_direction.cross(source_direction);
+    let peq = direction.cross(&eq);
+
+    *direction + peq * w
+}
+
+/// Applies light deflection by the Sun to a source at infinite distance, as in SOFA `iauLdsun`.
+///
+/// # Arguments
+///
+/// * `direction` - Direction from the observer to the source (unit vector).
+/// * `observer_direction` - Direction from the Sun to the observer (unit vector).
+/// * `observer_distance` - Distance from the Sun to the observer in AU.
+pub fn light_deflection_sun(
+    direction: &Vector3,
+    observer_direction: &Vector3,
+    observer_distance: f64,
+) -> Vector3 {
+    // Deflection limiter, smaller inside the Earth's orbit
+    let em2 = (observer_distance * observer_distance).max(1.0);
+    let limit = 1e-6 / em2;
+
+    light_deflection(
+        1.0,
+        direction,
+        direction,
+        observer_direction,
+        observer_distance,
+        limit,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sofa_ld_test() {
+        // SOFA t_sofa_c test case for iauLd
+        let p = Vector3::new(-0.763_276_255, -0.608_633_767, -0.216_735_543);
+        let q = Vector3::new(-0.763_276_255, -0.608_633_767, -0.216_735_543);
+        let e = Vector3::new(0.767_004_21, 0.605_629_598, 0.211_937_094);
+
+        let p1 = light_deflection(0.000_285_74, &p, &q, &e, 8.912_769_83, 3e-10);
+        assert!((p1.x + 0.763_276_254_896_815_9).abs() < 1e-12);
+        assert!((p1.y + 0.608_633_767_082_376_3).abs() < 1e-12);
+        assert!((p1.z + 0.216_735_543_132_054_7).abs() < 1e-12);
+    }
+
+    #[test]
+    fn solar_limb_test() {
+        // Starlight grazing the solar limb is deflected by about 1.75"
+        let sun_radius = 696_000.0 / 149_597_870.7;
+        let e = Vector3::new(-1.0, 0.0, 0.0);
+        let p = Vector3::new(1.0, sun_radius, 0.0).normalize();
+
+        let p1 = light_deflection_sun(&p, &e, 1.0).normalize();
+        let deflection = p.angle(&p1).to_degrees() * 3600.0;
+        assert!((deflection - 1.75).abs() < 0.01);
+
+        // The source is pushed away from the Sun
+        assert!(p1.y > p.y);
+    }
+}
@@ -0,0 +1,251 @@
+use super::{nutation, precession};
+use crate::constants::{ASTRONOMICAL_UNIT, SECONDS_PER_DAY};
+use crate::datetime::{DateTime, TimeDelta, TimeScale};
+use crate::ephemeris::vsop87::{Planet, Vsop87};
+use crate::ephemeris::Ephemeris;
+use crate::math::{self, Matrix3, Vector3};
+
+/// Mean obliquity of the ecliptic at J2000.0 in degrees, used to rotate ecliptic elements
+const OBLIQUITY_J2000_DEGREES: f64 = 23.439_279_444;
+
+// Ratios of the mass of the Sun to those of the giant planets (DE405), with the mean elements
+// of Standish (valid 1800-2050) referred to the ecliptic and equinox of J2000: the semi-major
+// axis in AU, the eccentricity, the inclination, the mean longitude, the longitude of
+// perihelion and the longitude of the ascending node in degrees, each as a value at J2000.0
+// and a rate per Julian century
+#[rustfmt::skip]
+const GIANT_PLANETS: [(f64, [[f64; 2]; 6]); 4] = [
+    // Jupiter
+    (1_047.348_6, [
+        [5.202_887_00, -0.000_116_07], [0.048_386_24, -0.000_132_53], [1.304_396_95, -0.001_837_14],
+        [34.396_440_51, 3_034.746_127_75], [14.728_479_83, 0.212_526_68], [100.473_909_09, 0.204_691_06],
+    ]),
+    // Saturn
+    (3_497.898, [
+        [9.536_675_94, -0.001_250_60], [0.053_861_79, -0.000_509_91], [2.485_991_87, 0.001_936_09],
+        [49.954_244_23, 1_222.493_622_01], [92.598_878_31, -0.418_972_16], [113.662_424_48, -0.288_677_94],
+    ]),
+    // Uranus
+    (22_902.98, [
+        [19.189_164_64, -0.001_961_76], [0.047_257_44, -0.000_043_97], [0.772_637_83, -0.002_429_39],
+        [313.238_104_51, 428.482_027_85], [170.954_276_30, 0.408_052_81], [74.016_925_03, 0.042_405_89],
+    ]),
+    // Neptune
+    (19_412.24, [
+        [30.069_922_76, 0.000_262_91], [0.008_590_48, 0.000_051_05], [1.770_043_47, 0.000_353_72],
+        [-55.120_029_69, 218.459_453_25], [44.964_762_27, -0.322_414_64], [131.784_225_74, -0.005_086_64],
+    ]),
+];
+
+/// NAIF codes of the bodies read from an ephemeris
+const SOLAR_SYSTEM_BARYCENTER: i32 = 0;
+const SUN: i32 = 10;
+const EARTH: i32 = 399;
+
+/// Position and velocity of the Earth needed for aberration, light deflection and parallax
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EarthState {
+    /// Heliocentric position of the Earth in AU (BCRS axes)
+    pub heliocentric_position: Vector3,
+    /// Barycentric position of the Earth in AU (BCRS axes)
+    pub barycentric_position: Vector3,
+    /// Barycentric velocity of the Earth in AU/day (BCRS axes)
+    pub barycentric_velocity: Vector3,
+}
+
+impl EarthState {
+    /// Computes an approximate Earth state from the abridged VSOP87 theory of the Earth and the
+    /// mean Keplerian elements of the giant planets (Standish, valid 1800-2050).
+    ///
+    /// The offset of the Sun from the solar system barycenter is that due to the giant planets.
+    /// Positions are good to about 1e-5 AU and velocities to about 1e-7 AU/day, which limits
+    /// the resulting apparent places to a few milliarcseconds. Use
+    /// [`EarthState::from_ephemeris`] with a JPL ephemeris where more is needed.
+    pub fn approximate(dt: &DateTime) -> Self {
+        let t = dt.centuries_since_j2000(TimeScale::TDB);
+
+        // The abridged Earth is always embedded; the velocity is differenced over two hours, in
+        // axes that do not precess
+        let earth = Vsop87::abridged(Planet::Earth).unwrap();
+        let step = TimeDelta::hours(1.0);
+        let position = heliocentric_earth(&earth, dt);
+        let velocity = (heliocentric_earth(&earth, &(*dt + step))
+            - heliocentric_earth(&earth, &(*dt - step)))
+            * 12.0;
+
+        // The Sun and the planets balance about the barycenter
+        let mut sun_position = Vector3::zeros();
+        let mut sun_velocity = Vector3::zeros();
+        let mut total_mass = 1.0;
+        for (mass_ratio, elements) in GIANT_PLANETS {
+            let (planet_position, planet_velocity) = heliocentric_state(&elements, t);
+            sun_position -= planet_position / mass_ratio;
+            sun_velocity -= planet_velocity / mass_ratio;
+            total_mass += 1.0 / mass_ratio;
+        }
+
+        EarthState {
+            heliocentric_position: position,
+            barycentric_position: position + sun_position / total_mass,
+            barycentric_velocity: velocity + sun_velocity / total_mass,
+        }
+    }
+
+    /// Reads the Earth state from an ephemeris, such as a JPL development ephemeris, whose axes
+    /// are those of the ICRF.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the ephemeris if it does not cover the Earth, the Sun or the
+    /// instant.
+    pub fn from_ephemeris<E: Ephemeris + ?Sized>(
+        ephemeris: &E,
+        dt: &DateTime,
+    ) -> Result<Self, E::Error> {
+        let barycentric = ephemeris.state(EARTH, SOLAR_SYSTEM_BARYCENTER, dt)?;
+        let heliocentric = ephemeris.position(EARTH, SUN, dt)?;
+
+        Ok(EarthState {
+            heliocentric_position: heliocentric / ASTRONOMICAL_UNIT,
+            barycentric_position: barycentric.position / ASTRONOMICAL_UNIT,
+            barycentric_velocity: barycentric.velocity * SECONDS_PER_DAY / ASTRONOMICAL_UNIT,
+        })
+    }
+}
+
+/// Computes the heliocentric position of the Earth in AU in the axes of the GCRS.
+///
+/// VSOP87D is referred to the ecliptic and equinox of date, which the IAU 1976 precession
+/// takes back to J2000.0; the offset of 0.09" between the dynamical and FK5 equinoxes is
+/// neglected.
+fn heliocentric_earth(earth: &Vsop87, dt: &DateTime) -> Vector3 {
+    let [longitude, latitude, radius] = earth.coordinates(dt);
+    let ecliptic = Vector3::from_spherical(longitude, latitude) * radius;
+    let equatorial = Matrix3::rot_x(-nutation::mean_obliquity(dt)) * ecliptic;
+    (precession::precession_matrix(dt) * precession::frame_bias_matrix()).transpose() * equatorial
+}
+
+/// Computes the heliocentric position in AU and velocity in AU/day, in the axes of the mean
+/// equator and equinox of J2000, from mean elements at `t` Julian centuries since J2000.0.
+fn heliocentric_state(elements: &[[f64; 2]; 6], t: f64) -> (Vector3, Vector3) {
+    let [a, e, inclination, mean_longitude, perihelion_longitude, node] =
+        elements.map(|[value, rate]| value + rate * t);
+    let [inclination, mean_longitude, perihelion_longitude, node] =
+        [inclination, mean_longitude, perihelion_longitude, node].map(f64::to_radians);
+
+    // Mean motion in rad/day
+    let n = elements[3][1].to_radians() / 36_525.0;
+
+    let mean_anomaly = math::normalize_angle_signed(mean_longitude - perihelion_longitude);
+    let mut ecc_anomaly = mean_anomaly + e * mean_anomaly.sin();
+    for _ in 0..10 {
+        let delta =
+            (ecc_anomaly - e * ecc_anomaly.sin() - mean_anomaly) / (1.0 - e * ecc_anomaly.cos());
+        ecc_anomaly -= delta;
+        if delta.abs() < 1e-15 {
+            break;
+        }
+    }
+
+    let (sin_e, cos_e) = ecc_anomaly.sin_cos();
+    let beta = (1.0 - e * e).sqrt();
+    let e_dot = n / (1.0 - e * cos_e);
+    let position = Vector3::new(a * (cos_e - e), a * beta * sin_e, 0.0);
+    let velocity = Vector3::new(-a * sin_e * e_dot, a * beta * cos_e * e_dot, 0.0);
+
+    // Orbital plane to ecliptic to equatorial axes
+    let rotation = Matrix3::rot_x(-OBLIQUITY_J2000_DEGREES.to_radians())
+        * Matrix3::rot_z(-node)
+        * Matrix3::rot_x(-inclination)
+        * Matrix3::rot_z(-(perihelion_longitude - node));
+
+    (rotation * position, rotation * velocity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ephemeris::StateVector;
+    use std::io;
+
+    /// Epoch of the SOFA t_sofa_c test case for iauEpv00
+    fn epoch() -> DateTime {
+        DateTime::from_julian_date(2_400_000.5 + 53_411.525_011_61, TimeScale::TDB)
+    }
+
+    /// Heliocentric and barycentric states of the Earth computed by iauEpv00 at the epoch, in AU
+    /// and AU/day
+    fn sofa_states() -> (Vector3, Vector3, Vector3) {
+        let heliocentric = Vector3::new(
+            -0.775_723_880_929_770_7,
+            0.559_805_224_136_334,
+            0.242_699_846_648_168_7,
+        );
+        let barycentric = Vector3::new(
+            -0.771_410_444_049_111_2,
+            0.559_841_206_182_417_1,
+            0.242_599_627_772_245_2,
+        );
+        let velocity = Vector3::new(
+            -0.109_187_426_811_682_3e-1,
+            -0.124_652_546_173_286_2e-1,
+            -0.540_477_318_096_623_1e-2,
+        );
+        (heliocentric, barycentric, velocity)
+    }
+
+    /// Barycentric states of the Sun and the Earth at any instant, in meters and m/s
+    struct Fixed {
+        sun: StateVector,
+        earth: StateVector,
+    }
+
+    impl Ephemeris for Fixed {
+        type Error = io::Error;
+
+        fn state(&self, target: i32, center: i32, _dt: &DateTime) -> io::Result<StateVector> {
+            let state = |body| match body {
+                SOLAR_SYSTEM_BARYCENTER => Ok(StateVector {
+                    position: Vector3::zeros(),
+                    velocity: Vector3::zeros(),
+                }),
+                SUN => Ok(self.sun),
+                EARTH => Ok(self.earth),
+                _ => Err(io::Error::other(format!("no body {body}"))),
+            };
+            Ok(state(target)? - state(center)?)
+        }
+    }
+
+    #[test]
+    fn approximate_state_test() {
+        let state = EarthState::approximate(&epoch());
+        let (heliocentric, barycentric, velocity) = sofa_states();
+
+        // The velocity sets the aberration, here to within 0.2 mas
+        assert!((state.heliocentric_position - heliocentric).norm() < 2e-6);
+        assert!((state.barycentric_position - barycentric).norm() < 2e-5);
+        assert!((state.barycentric_velocity - velocity).norm() < 2e-7);
+    }
+
+    #[test]
+    fn ephemeris_state_test() {
+        let (heliocentric, barycentric, velocity) = sofa_states();
+        let earth = StateVector {
+            position: barycentric * ASTRONOMICAL_UNIT,
+            velocity: velocity * ASTRONOMICAL_UNIT / SECONDS_PER_DAY,
+        };
+        let ephemeris = Fixed {
+            sun: StateVector {
+                position: (barycentric - heliocentric) * ASTRONOMICAL_UNIT,
+                velocity: Vector3::zeros(),
+            },
+            earth,
+        };
+
+        let state = EarthState::from_ephemeris(&ephemeris, &epoch()).unwrap();
+        assert!((state.heliocentric_position - heliocentric).norm() < 1e-15);
+        assert!((state.barycentric_position - barycentric).norm() < 1e-15);
+        assert!((state.barycentric_velocity - velocity).norm() < 1e-17);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::datetime::{DateTime, TimeScale};
+use crate::math::{self, Matrix3};
+
+/// Nutation in longitude and obliquity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Nutation {
+    /// Nutation in longitude (Δψ) in radians
+    pub longitude: f64,
+    /// Nutation in obliquity (Δε) in radians
+    pub obliquity: f64,
+}
+
+/// Periodic terms of the IAU 1980 nutation theory with amplitudes of at least 0.0003".
+///
+/// Each row holds the multiples of the fundamental arguments (D, M, M', F, Ω), followed by the
+/// coefficients of the sine series for Δψ and of the cosine series for Δε, both as a constant
+/// and a rate per Julian century, in units of 0.0001".
+#[rustfmt::skip]
+const NUTATION_TERMS: [([i8; 5], f64, f64, f64, f64); 63] = [
+    ([ 0,  0,  0,  0,  1], -171996.0, -174.2, 92025.0,  8.9),
+    ([-2,  0,  0,  2,  2],  -13187.0,   -1.6,  5736.0, -3.1),
+    ([ 0,  0,  0,  2,  2],   -2274.0,   -0.2,   977.0, -0.5),
+    ([ 0,  0,  0,  0,  2],    2062.0,    0.2,  -895.0,  0.5),
+    ([ 0,  1,  0,  0,  0],    1426.0,   -3.4,    54.0, -0.1),
+    ([ 0,  0,  1,  0,  0],     712.0,    0.1,    -7.0,  0.0),
+    ([-2,  1,  0,  2,  2],    -517.0,    1.2,   224.0, -0.6),
+    ([ 0,  0,  0,  2,  1],    -386.0,   -0.4,   200.0,  0.0),
+    ([ 0,  0,  1,  2,  2],    -301.0,    0.0,   129.0, -0.1),
+    ([-2, -1,  0,  2,  2],     217.0,   -0.5,   -95.0,  0.3),
+    ([-2,  0,  1,  0,  0],    -158.0,    0.0,     0.0,  0.0),
+    ([-2,  0,  0,  2,  1],     129.0,    0.1,   -70.0,  0.0),
+    ([ 0,  0, -1,  2,  2],     123.0,    0.0,   -53.0,  0.0),
+    ([ 2,  0,  0,  0,  0],      63.0,    0.0,     0.0,  0.0),
+    ([ 0,  0,  1,  0,  1],      63.0,    0.1,   -33.0,  0.0),
+    ([ 2,  0, -1,  2,  2],     -59.0,    0.0,    26.0,  0.0),
+    ([ 0,  0, -1,  0,  1],     -58.0,   -0.1,    32.0,  0.0),
+    ([ 0,  0,  1,  2,  1],     -51.0,    0.0,    27.0,  0.0),
+    ([-2,  0,  2,  0,  0],      48.0,    0.0,     0.0,  0.0),
+    ([ 0,  0, -2,  2,  1],      46.0,    0.0,   -24.0,  0.0),
+    ([ 2,  0,  0,  2,  2],     -38.0,    0.0,    16.0,  0.0),
+    ([ 0,  0,  2,  2,  2],     -31.0,    0.0,    13.0,  0.0),
+    ([ 0,  0,  2,  0,  0],      29.0,    0.0,     0.0,  0.0),
+    ([-2,  0,  1,  2,  2],      29.0,    0.0,   -12.0,  0.0),
+    ([ 0,  0,  0,  2,  0],      26.0,    0.0,     0.0,  0.0),
+    ([-2,  0,  0,  2,  0],     -22.0,    0.0,     0.0,  0.0),
+    ([ 0,  0, -1,  2,  1],      21.0,    0.0,   -10.0,  0.0),
+    ([ 0,  2,  0,  0,  0],      17.0,   -0.1,     0.0,  0.0),
+    ([ 2,  0, -1,  0,  1],      16.0,    0.0,    -8.0,  0.0),
+    ([-2,  2,  0,  2,  2],     -16.0,    0.1,     7.0,  0.0),
+    ([ 0,  1,  0,  0,  1],     -15.0,    0.0,     9.0,  0.0),
+    ([-2,  0,  1,  0,  1],     -13.0,    0.0,     7.0,  0.0),
+    ([ 0, -1,  0,  0,  1],     -12.0,    0.0,     6.0,  0.0),
+    ([ 0,  0,  2, -2,  0],      11.0,    0.0,     0.0,  0.0),
+    ([ 2,  0, -1,  2,  1],     -10.0,    0.0,     5.0,  0.0),
+    ([ 2,  0,  1,  2,  2],      -8.0,    0.0,     3.0,  0.0),
+    ([ 0,  1,  0,  2,  2],       7.0,    0.0,    -3.0,  0.0),
+    ([-2,  1,  1,  0,  0],      -7.0,    0.0,     0.0,  0.0),
+    ([ 0, -1,  0,  2,  2],      -7.0,    0.0,     3.0,  0.0),
+    ([ 2,  0,  0,  2,  1],      -7.0,    0.0,     3.0,  0.0),
+    ([ 2,  0,  1,  0,  0],       6.0,    0.0,     0.0,  0.0),
+    ([-2,  0,  2,  2,  2],       6.0,    0.0,    -3.0,  0.0),
+    ([-2,  0,  1,  2,  1],       6.0,    0.0,    -3.0,  0.0),
+    ([ 2,  0, -2,  0,  1],      -6.0,    0.0,     3.0,  0.0),
+    ([ 2,  0,  0,  0,  1],      -6.0,    0.0,     3.0,  0.0),
+    ([ 0, -1,  1,  0,  0],       5.0,    0.0,     0.0,  0.0),
+    ([-2, -1,  0,  2,  1],      -5.0,    0.0,     3.0,  0.0),
+    ([-2,  0,  0,  0,  1],      -5.0,    0.0,     3.0,  0.0),
+    ([ 0,  0,  2,  2,  1],      -5.0,    0.0,     3.0,  0.0),
+    ([-2,  0,  2,  0,  1],       4.0,    0.0,     0.0,  0.0),
+    ([-2,  1,  0,  2,  1],       4.0,    0.0,     0.0,  0.0),
+    ([ 0,  0,  1, -2,  0],       4.0,    0.0,     0.0,  0.0),
+    ([-1,  0,  1,  0,  0],      -4.0,    0.0,     0.0,  0.0),
+    ([-2,  1,  0,  0,  0],      -4.0,    0.0,     0.0,  0.0),
+    ([ 1,  0,  0,  0,  0],      -4.0,    0.0,     0.0,  0.0),
+    ([ 0,  0,  1,  2,  0],       3.0,    0.0,     0.0,  0.0),
+    ([ 0,  0, -2,  2,  2],      -3.0,    0.0,     0.0,  0.0),
+    ([-1, -1,  1,  0,  0],      -3.0,    0.0,     0.0,  0.0),
+    ([ 0,  1,  1,  0,  0],      -3.0,    0.0,     0.0,  0.0),
+    ([ 0, -1,  1,  2,  2],      -3.0,    0.0,     0.0,  0.0),
+    ([ 2, -1, -1,  2,  2],      -3.0,    0.0,     0.0,  0.0),
+    ([ 0,  0,  3,  2,  2],      -3.0,    0.0,     0.0,  0.0),
+    ([ 2, -1,  0,  2,  2],      -3.0,    0.0,     0.0,  0.0),
+];
+
+/// Computes the Delaunay fundamental arguments (D, M, M', F, Ω) in radians.
+///
+/// # Arguments
+///
+/// * `t` - Julian centuries of TT since J2000.0.
+///
+/// # Returns
+///
+/// The mean elongation of the Moon from the Sun, the mean anomaly of the Sun, the mean anomaly
+/// of the Moon, the Moon's argument of latitude and the longitude of the Moon's ascending node.
+pub fn fundamental_arguments(t: f64) -> [f64; 5] {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    [
+        297.850_36 + 445_267.111_48 * t - 0.001_914_2 * t2 + t3 / 189_474.0,
+        357.527_72 + 35_999.050_34 * t - 0.000_160_3 * t2 - t3 / 300_000.0,
+        134.962_98 + 477_198.867_398 * t + 0.008_697_2 * t2 + t3 / 56_250.0,
+        93.271_91 + 483_202.017_538 * t - 0.003_682_5 * t2 + t3 / 327_270.0,
+        125.044_52 - 1_934.136_261 * t + 0.002_070_8 * t2 + t3 / 450_000.0,
+    ]
+    .map(|degrees| math::normalize_angle(degrees.to_radians()))
+}
+
+/// Computes the nutation in longitude and obliquity using the IAU 1980 theory.
+///
+/// The series is truncated to terms of at least 0.0003", giving an accuracy of about 0.001"
+/// relative to the full theory.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::astrometry::nutation;
+/// use astro_carta::datetime::{DateTime, TimeScale};
+///
+/// let dt = DateTime::gregorian_with_scale(1987, 4, 10, 0, 0, 0.0, TimeScale::TT).unwrap();
+/// let n = nutation::nutation(&dt);
+/// assert!((n.longitude.to_degrees() * 3600.0 + 3.788).abs() < 1e-3);
+/// assert!((n.obliquity.to_degrees() * 3600.0 - 9.443).abs() < 1e-3);
+/// ```
+pub fn nutation(dt: &DateTime) -> Nutation {
+    let t = dt.centuries_since_j2000(TimeScale::TT);
+    let args = fundamental_arguments(t);
+
+    let mut dpsi = 0.0;
+    let mut deps = 0.0;
+    for (multiples, psi, psi_rate, eps, eps_rate) in NUTATION_TERMS.iter() {
+        let argument: f64 = multiples
+            .iter()
+            .zip(args.iter())
+            .map(|(m, a)| *m as f64 * a)
+            .sum();
+        dpsi += (psi + psi_rate * t) * argument.sin();
+        deps += (eps + eps_rate * t) * argument.cos();
+    }
+
+    Nutation {
+        longitude: math::arcseconds_to_radians(dpsi * 1e-4),
+        obliquity: math::arcseconds_to_radians(deps * 1e-4),
+    }
+}
+
+/// Computes the mean obliquity of the ecliptic (IAU 1980) in radians.
+pub fn mean_obliquity(dt: &DateTime) -> f64 {
+    let t = dt.centuries_since_j2000(TimeScale::TT);
+    math::arcseconds_to_radians(84_381.448 + t * (-46.815_0 + t * (-0.000_59 + t * 0.001_813)))
+}
+
+/// Computes the true obliquity of the ecliptic (mean obliquity plus nutation in obliquity) in radians.
+pub fn true_obliquity(dt: &DateTime) -> f64 {
+    mean_obliquity(dt) + nutation(dt).obliquity
+}
+
+/// Computes the equation of the equinoxes (Δψ cos ε) in radians.
+///
+/// This is the difference between apparent and mean sidereal time.
+pub fn equation_of_the_equinoxes(dt: &DateTime) -> f64 {
+    let n = nutation(dt);
+    n.longitude * (mean_obliquity(dt) + n.obliquity).cos()
+}
+
+/// Computes the nutation matrix that rotates vectors from the mean equator and equinox of date
+/// to the true equator and equinox of date.
+pub fn nutation_matrix(dt: &DateTime) -> Matrix3 {
+    let n = nutation(dt);
+    let eps = mean_obliquity(dt);
+    Matrix3::rot_x(-(eps + n.obliquity)) * Matrix3::rot_z(-n.longitude) * Matrix3::rot_x(eps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_arcseconds(value: f64) -> f64 {
+        value * math::ARCSECONDS_PER_RADIAN
+    }
+
+    #[test]
+    fn meeus_example_22a_test() {
+        // Meeus, Astronomical Algorithms, Example 22.a
+        let dt = DateTime::gregorian_with_scale(1987, 4, 10, 0, 0, 0.0, TimeScale::TT).unwrap();
+
+        let n = nutation(&dt);
+        assert!((to_arcseconds(n.longitude) + 3.788).abs() < 1e-3);
+        assert!((to_arcseconds(n.obliquity) - 9.443).abs() < 1e-3);
+
+        let eps0 = to_arcseconds(mean_obliquity(&dt)) - 23.0 * 3600.0;
+        assert!((eps0 - (26.0 * 60.0 + 27.407)).abs() < 1e-3);
+
+        let eps = to_arcseconds(true_obliquity(&dt)) - 23.0 * 3600.0;
+        assert!((eps - (26.0 * 60.0 + 36.850)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn nutation_matrix_test() {
+        let dt = DateTime::gregorian(2010, 6, 1, 0, 0, 0.0).unwrap();
+        let m = nutation_matrix(&dt);
+        assert!((m.determinant() - 1.0).abs() < 1e-14);
+
+        // The rotation is at most a few tens of arcseconds
+        let angle = ((m[(0, 0)] + m[(1, 1)] + m[(2, 2)] - 1.0) / 2.0)
+            .min(1.0)
+            .acos();
+        assert!(to_arcseconds(angle) < 30.0);
+    }
+}
@@ -0,0 +1,98 @@
+use super::nutation;
+use crate::datetime::{DateTime, TimeScale};
+use crate::math::{self, Matrix3};
+
+/// Frame bias in longitude (IAU 2000) in arcseconds
+const BIAS_LONGITUDE: f64 = -0.041_775;
+
+/// Frame bias in obliquity (IAU 2000) in arcseconds
+const BIAS_OBLIQUITY: f64 = -0.006_819_2;
+
+/// ICRS right ascension of the J2000.0 mean equinox in arcseconds
+const BIAS_RIGHT_ASCENSION: f64 = -0.014_6;
+
+/// Mean obliquity of the ecliptic at J2000.0 in arcseconds
+pub(crate) const OBLIQUITY_J2000: f64 = 84_381.448;
+
+/// Returns the frame bias matrix that rotates vectors from the GCRS to the mean equator and
+/// equinox of J2000.0 (IAU 2000).
+pub fn frame_bias_matrix() -> Matrix3 {
+    let dpsi = math::arcseconds_to_radians(BIAS_LONGITUDE);
+    let deps = math::arcseconds_to_radians(BIAS_OBLIQUITY);
+    let dra = math::arcseconds_to_radians(BIAS_RIGHT_ASCENSION);
+    let eps0 = math::arcseconds_to_radians(OBLIQUITY_J2000);
+
+    Matrix3::rot_x(-deps) * Matrix3::rot_y(dpsi * eps0.sin()) * Matrix3::rot_z(dra)
+}
+
+/// Computes the IAU 1976 precession angles (ζ, z, θ) in radians from J2000.0 to the given instant.
+pub fn precession_angles(dt: &DateTime) -> (f64, f64, f64) {
+    let t = dt.centuries_since_j2000(TimeScale::TT);
+    let zeta = t * (2_306.218_1 + t * (0.301_88 + t * 0.017_998));
+    let z = t * (2_306.218_1 + t * (1.094_68 + t * 0.018_203));
+    let theta = t * (2_004.310_9 + t * (-0.426_65 - t * 0.041_833));
+    (
+        math::arcseconds_to_radians(zeta),
+        math::arcseconds_to_radians(z),
+        math::arcseconds_to_radians(theta),
+    )
+}
+
+/// Returns the IAU 1976 precession matrix that rotates vectors from the mean equator and equinox
+/// of J2000.0 to the mean equator and equinox of date.
+pub fn precession_matrix(dt: &DateTime) -> Matrix3 {
+    let (zeta, z, theta) = precession_angles(dt);
+    Matrix3::rot_z(-z) * Matrix3::rot_y(theta) * Matrix3::rot_z(-zeta)
+}
+
+/// Returns the combined bias-precession-nutation matrix that rotates vectors from the GCRS to
+/// the true equator and equinox of date.
+pub fn bias_precession_nutation_matrix(dt: &DateTime) -> Matrix3 {
+    nutation::nutation_matrix(dt) * precession_matrix(dt) * frame_bias_matrix()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vector3;
+
+    #[test]
+    fn frame_bias_test() {
+        // SOFA t_sofa_c test case for iauBp00
+        let b = frame_bias_matrix();
+        assert!((b[(0, 0)] - 0.999_999_999_999_994_2).abs() < 1e-15);
+        assert!((b[(0, 1)] + 0.707_827_974_419_919_7e-7).abs() < 1e-16);
+        assert!((b[(0, 2)] - 0.805_621_714_697_613_4e-7).abs() < 1e-16);
+        assert!((b[(1, 0)] - 0.707_827_947_785_733_7e-7).abs() < 1e-16);
+        assert!((b[(1, 2)] - 0.330_604_145_422_213_6e-7).abs() < 1e-16);
+        assert!((b[(2, 0)] + 0.805_621_738_098_697_2e-7).abs() < 1e-16);
+        assert!((b[(2, 1)] + 0.330_604_088_398_055_3e-7).abs() < 1e-16);
+    }
+
+    #[test]
+    fn meeus_example_21b_test() {
+        // Meeus, Astronomical Algorithms, Example 21.b: θ Persei (with proper motion already
+        // applied) precessed from J2000.0 to 2028 Nov 13.19 TD
+        let dt = DateTime::from_julian_date(2_462_088.69, TimeScale::TT);
+        let ra0 = ((2.0 + 44.0 / 60.0 + 12.975 / 3600.0) * 15.0_f64).to_radians();
+        let dec0 = (49.0 + 13.0 / 60.0 + 39.90 / 3600.0_f64).to_radians();
+        let v = precession_matrix(&dt) * Vector3::from_spherical(ra0, dec0);
+        let (ra, dec) = v.to_spherical();
+
+        let ra_expected = ((2.0 + 46.0 / 60.0 + 11.331 / 3600.0) * 15.0_f64).to_radians();
+        let dec_expected = (49.0 + 20.0 / 60.0 + 54.54 / 3600.0_f64).to_radians();
+        assert!(((ra - ra_expected).to_degrees() * 3600.0).abs() < 0.03);
+        assert!(((dec - dec_expected).to_degrees() * 3600.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn bias_precession_nutation_test() {
+        let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+        let m = bias_precession_nutation_matrix(&dt);
+        assert!((m.determinant() - 1.0).abs() < 1e-14);
+
+        let identity = m * m.transpose();
+        assert!((identity[(0, 0)] - 1.0).abs() < 1e-15);
+        assert!(identity[(0, 1)].abs() < 1e-15);
+    }
+}
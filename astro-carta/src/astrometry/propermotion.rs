@@ -0,0 +1,275 @@
+use crate::constants::{
+    ASTRONOMICAL_UNIT, DAYS_PER_JULIAN_YEAR, LIGHT_TIME_ASTRONOMICAL_UNIT, SECONDS_PER_DAY,
+};
+use crate::datetime::{DateTime, TimeScale};
+use crate::math::{self, Vector3};
+
+/// Conversion factor from km/s to AU per Julian year
+const KM_PER_S_TO_AU_PER_YEAR: f64 =
+    1_000.0 * SECONDS_PER_DAY * DAYS_PER_JULIAN_YEAR / ASTRONOMICAL_UNIT;
+
+/// Light time for one AU in Julian years
+const LIGHT_TIME_AU_YEARS: f64 =
+    LIGHT_TIME_ASTRONOMICAL_UNIT / SECONDS_PER_DAY / DAYS_PER_JULIAN_YEAR;
+
+/// Smallest parallax used when propagating space motion, in arcseconds
+const MIN_PARALLAX: f64 = 1e-7;
+
+/// A catalogue entry for a star in the ICRS
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CatalogStar {
+    /// Right ascension at the catalogue epoch in radians
+    pub right_ascension: f64,
+    /// Declination at the catalogue epoch in radians
+    pub declination: f64,
+    /// Proper motion in right ascension as dα/dt in radians per Julian year (not multiplied by cos δ)
+    pub proper_motion_ra: f64,
+    /// Proper motion in declination as dδ/dt in radians per Julian year
+    pub proper_motion_dec: f64,
+    /// Parallax in arcseconds
+    pub parallax: f64,
+    /// Radial velocity in km/s, positive when receding
+    pub radial_velocity: f64,
+    /// Catalogue epoch
+    pub epoch: DateTime,
+}
+
+impl CatalogStar {
+    /// Constructs a new `CatalogStar` at rest with no parallax.
+    ///
+    /// # Arguments
+    ///
+    /// * `right_ascension` - ICRS right ascension in radians.
+    /// * `declination` - ICRS declination in radians.
+    /// * `epoch` - Catalogue epoch.
+    pub fn new(right_ascension: f64, declination: f64, epoch: DateTime) -> Self {
+        CatalogStar {
+            right_ascension,
+            declination,
+            proper_motion_ra: 0.0,
+            proper_motion_dec: 0.0,
+            parallax: 0.0,
+            radial_velocity: 0.0,
+            epoch,
+        }
+    }
+
+    /// Returns the star with the given proper motion, dα/dt and dδ/dt in radians per Julian year.
+    pub fn with_proper_motion(mut self, proper_motion_ra: f64, proper_motion_dec: f64) -> Self {
+        self.proper_motion_ra = proper_motion_ra;
+        self.proper_motion_dec = proper_motion_dec;
+        self
+    }
+
+    /// Returns the star with the given parallax in arcseconds.
+    pub fn with_parallax(mut self, parallax: f64) -> Self {
+        self.parallax = parallax;
+        self
+    }
+
+    /// Returns the star with the given radial velocity in km/s.
+    pub fn with_radial_velocity(mut self, radial_velocity: f64) -> Self {
+        self.radial_velocity = radial_velocity;
+        self
+    }
+
+    /// Returns the number of Julian years of TDB from the catalogue epoch to the given instant.
+    pub fn years_since_epoch(&self, dt: &DateTime) -> f64 {
+        (dt.days_since_j2000(TimeScale::TDB) - self.epoch.days_since_j2000(TimeScale::TDB))
+            / DAYS_PER_JULIAN_YEAR
+    }
+
+    /// Computes the direction to the star as seen by an observer, applying proper motion and
+    /// parallax, as in SOFA `iauPmpx`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - The instant of observation.
+    /// * `observer_position` - Barycentric position of the observer in AU.
+    ///
+    /// # Returns
+    ///
+    /// The coordinate direction to the star (unit vector, BCRS axes).
+    pub fn direction(&self, dt: &DateTime, observer_position: &Vector3) -> Vector3 {
+        proper_motion_parallax(
+            self.right_ascension,
+            self.declination,
+            self.proper_motion_ra,
+            self.proper_motion_dec,
+            self.parallax,
+            self.radial_velocity,
+            self.years_since_epoch(dt),
+            observer_position,
+        )
+    }
+
+    /// Propagates the catalogue entry to a new epoch assuming uniform rectilinear space motion.
+    ///
+    /// Light-time effects across the interval are neglected. Stars with zero parallax are
+    /// placed at a distance corresponding to a parallax of 1e-7 arcseconds.
+    pub fn propagate(&self, epoch: &DateTime) -> CatalogStar {
+        let years = self.years_since_epoch(epoch);
+
+        let parallax = self.parallax.max(MIN_PARALLAX);
+        let distance = 1.0 / math::arcseconds_to_radians(parallax);
+
+        let (sin_ra, cos_ra) = self.right_ascension.sin_cos();
+        let (sin_dec, cos_dec) = self.declination.sin_cos();
+        let u = Vector3::new(cos_ra * cos_dec, sin_ra * cos_dec, sin_dec);
+        let east = Vector3::new(-sin_ra, cos_ra, 0.0);
+        let north = Vector3::new(-cos_ra * sin_dec, -sin_ra * sin_dec, cos_dec);
+
+        let velocity = (east * (self.proper_motion_ra * cos_dec) + north * self.proper_motion_dec)
+            * distance
+            + u * (self.radial_velocity * KM_PER_S_TO_AU_PER_YEAR);
+
+        let position = u * distance + velocity * years;
+        let new_distance = position.norm();
+        let new_u = position / new_distance;
+        let (right_ascension, declination) = new_u.to_spherical();
+
+        let (sin_ra, cos_ra) = right_ascension.sin_cos();
+        let (sin_dec, cos_dec) = declination.sin_cos();
+        let east = Vector3::new(-sin_ra, cos_ra, 0.0);
+        let north = Vector3::new(-cos_ra * sin_dec, -sin_ra * sin_dec, cos_dec);
+
+        let new_parallax = if self.parallax > 0.0 {
+            math::ARCSECONDS_PER_RADIAN / new_distance
+        } else {
+            0.0
+        };
+        let radial_velocity = if self.parallax > 0.0 || self.radial_velocity != 0.0 {
+            velocity.dot(&new_u) / KM_PER_S_TO_AU_PER_YEAR
+        } else {
+            0.0
+        };
+
+        CatalogStar {
+            right_ascension,
+            declination,
+            proper_motion_ra: if cos_dec != 0.0 {
+                velocity.dot(&east) / new_distance / cos_dec
+            } else {
+                0.0
+            },
+            proper_motion_dec: velocity.dot(&north) / new_distance,
+            parallax: new_parallax,
+            radial_velocity,
+            epoch: *epoch,
+        }
+    }
+}
+
+/// Applies proper motion and parallax to catalogue coordinates, as in SOFA `iauPmpx`.
+///
+/// # Arguments
+///
+/// * `right_ascension` - ICRS right ascension at the catalogue epoch in radians.
+/// * `declination` - ICRS declination at the catalogue epoch in radians.
+/// * `proper_motion_ra` - dα/dt in radians per Julian year.
+/// * `proper_motion_dec` - dδ/dt in radians per Julian year.
+/// * `parallax` - Parallax in arcseconds.
+/// * `radial_velocity` - Radial velocity in km/s, positive when receding.
+/// * `years` - Julian years of TDB since the catalogue epoch.
+/// * `observer_position` - Barycentric position of the observer in AU.
+///
+/// # Returns
+///
+/// The coordinate direction to the star (unit vector, BCRS axes).
+#[allow(clippy::too_many_arguments)]
+pub fn proper_motion_parallax(
+    right_ascension: f64,
+    declination: f64,
+    proper_motion_ra: f64,
+    proper_motion_dec: f64,
+    parallax: f64,
+    radial_velocity: f64,
+    years: f64,
+    observer_position: &Vector3,
+) -> Vector3 {
+    let (sr, cr) = right_ascension.sin_cos();
+    let (sd, cd) = declination.sin_cos();
+    let p = Vector3::new(cr * cd, sr * cd, sd);
+
+    // Proper motion time interval including the Roemer effect
+    let dt = years + p.dot(observer_position) * LIGHT_TIME_AU_YEARS;
+
+    // Space motion in radians per year
+    let pxr = math::arcseconds_to_radians(parallax);
+    let w = KM_PER_S_TO_AU_PER_YEAR * radial_velocity * pxr;
+    let pdz = proper_motion_dec * p.z;
+    let pm = Vector3::new(
+        -proper_motion_ra * p.y - pdz * cr + w * p.x,
+        proper_motion_ra * p.x - pdz * sr + w * p.y,
+        proper_motion_dec * cd + w * p.z,
+    );
+
+    (p + pm * dt - *observer_position * pxr).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sofa_pmpx_test() {
+        // SOFA t_sofa_c test case for iauPmpx
+        let pob = Vector3::new(0.9, 0.4, 0.1);
+        let pco = proper_motion_parallax(1.234, 0.789, 1e-5, -2e-5, 1e-2, 10.0, 8.75, &pob);
+        assert!((pco.x - 0.232_813_762_396_030_8).abs() < 1e-12);
+        assert!((pco.y - 0.665_109_708_539_785_5).abs() < 1e-12);
+        assert!((pco.z - 0.709_525_776_589_636).abs() < 1e-12);
+    }
+
+    #[test]
+    fn sofa_starpm_test() {
+        // SOFA t_sofa_c test case for iauStarpm; SOFA also corrects for the change in light time
+        // over the interval, which this propagation neglects (about 1e-8 rad here)
+        let epoch1 = DateTime::from_julian_date(2_400_000.5 + 50_083.0, TimeScale::TDB);
+        let epoch2 = DateTime::from_julian_date(2_400_000.5 + 53_736.0, TimeScale::TDB);
+        let star = CatalogStar::new(0.016_867_56, -1.093_989_828, epoch1)
+            .with_proper_motion(-1.783_235_16e-5, 2.336_024_047e-6)
+            .with_parallax(0.747_23)
+            .with_radial_velocity(-21.6);
+
+        let moved = star.propagate(&epoch2);
+        assert!((moved.right_ascension - 0.016_689_190_694_142_56).abs() < 3e-8);
+        assert!((moved.declination + 1.093_966_454_217_128).abs() < 3e-8);
+        assert!((moved.proper_motion_ra + 0.178_366_268_215_317_7e-4).abs() < 1e-10);
+        assert!((moved.proper_motion_dec - 0.233_809_291_598_399e-5).abs() < 1e-10);
+        assert!((moved.parallax - 0.747_353_383_531_772).abs() < 1e-6);
+        assert!((moved.radial_velocity + 21.599_051_704_764_17).abs() < 1e-3);
+        assert_eq!(moved.epoch, epoch2);
+    }
+
+    #[test]
+    fn propagate_round_trip_test() {
+        let epoch1 = DateTime::gregorian(2000, 1, 1, 12, 0, 0.0).unwrap();
+        let epoch2 = DateTime::gregorian(2100, 1, 1, 12, 0, 0.0).unwrap();
+        let star = CatalogStar::new(4.7, 0.08, epoch1)
+            .with_proper_motion(-3.5e-6, 5.0e-5)
+            .with_parallax(0.55)
+            .with_radial_velocity(-110.0);
+
+        let back = star.propagate(&epoch2).propagate(&epoch1);
+        assert!((back.right_ascension - star.right_ascension).abs() < 1e-12);
+        assert!((back.declination - star.declination).abs() < 1e-12);
+        assert!((back.proper_motion_ra - star.proper_motion_ra).abs() < 1e-15);
+        assert!((back.proper_motion_dec - star.proper_motion_dec).abs() < 1e-15);
+        assert!((back.parallax - star.parallax).abs() < 1e-12);
+        assert!((back.radial_velocity - star.radial_velocity).abs() < 1e-9);
+    }
+
+    #[test]
+    fn direction_matches_propagation_test() {
+        // With the observer at the barycenter the first-order pmpx direction agrees with rigorous propagation
+        let epoch1 = DateTime::gregorian(2000, 1, 1, 12, 0, 0.0).unwrap();
+        let epoch2 = DateTime::gregorian(2010, 1, 1, 12, 0, 0.0).unwrap();
+        let star = CatalogStar::new(1.0, 0.5, epoch1).with_proper_motion(2e-6, -1e-6);
+
+        let direction = star.direction(&epoch2, &Vector3::zeros());
+        let moved = star.propagate(&epoch2);
+        let expected = Vector3::from_spherical(moved.right_ascension, moved.declination);
+        assert!(direction.angle(&expected) < 1e-12);
+    }
+}
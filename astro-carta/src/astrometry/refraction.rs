@@ -0,0 +1,376 @@
+use crate::coordinates::Observer;
+use std::f64::consts::FRAC_PI_2;
+
+/// Standard pressure assumed when an observer has no pressure reading, in hPa
+pub const STANDARD_PRESSURE: f64 = 1010.0;
+
+/// Standard temperature assumed when an observer has no temperature reading, in degrees Celsius
+pub const STANDARD_TEMPERATURE: f64 = 10.0;
+
+/// Standard relative humidity assumed when an observer has no humidity reading
+pub const STANDARD_HUMIDITY: f64 = 0.5;
+
+/// Wavelengths above this value (in micrometers) are treated as radio rather than optical
+const RADIO_WAVELENGTH_THRESHOLD: f64 = 100.0;
+
+/// Elevation below which the A tan z + B tan³ z model is evaluated at the limit (sine of elevation)
+const MIN_SIN_ELEVATION: f64 = 0.05;
+
+/// A model of atmospheric refraction.
+///
+/// Refraction raises the observed elevation of an object above its topocentric (airless)
+/// elevation. Both methods return the amount of refraction in radians, which is always
+/// added to a topocentric elevation to obtain the observed elevation.
+pub trait Refraction {
+    /// Computes the refraction for an observed (refracted) elevation in radians.
+    fn at_observed(&self, observed_elevation: f64) -> f64;
+
+    /// Computes the refraction for a topocentric (unrefracted) elevation in radians.
+    fn at_topocentric(&self, topocentric_elevation: f64) -> f64;
+}
+
+/// Solves `observed = topocentric + refraction(observed)` for the observed elevation by fixed point iteration.
+fn invert_at_observed<R: Refraction + ?Sized>(model: &R, topocentric_elevation: f64) -> f64 {
+    let mut observed = topocentric_elevation;
+    for _ in 0..20 {
+        let next = topocentric_elevation + model.at_observed(observed);
+        if (next - observed).abs() < 1e-12 {
+            observed = next;
+            break;
+        }
+        observed = next;
+    }
+    observed - topocentric_elevation
+}
+
+/// Solves `observed = topocentric + refraction(topocentric)` for the topocentric elevation by fixed point iteration.
+fn invert_at_topocentric<R: Refraction + ?Sized>(model: &R, observed_elevation: f64) -> f64 {
+    let mut topocentric = observed_elevation;
+    for _ in 0..20 {
+        let next = observed_elevation - model.at_topocentric(topocentric);
+        if (next - topocentric).abs() < 1e-12 {
+            topocentric = next;
+            break;
+        }
+        topocentric = next;
+    }
+    observed_elevation - topocentric
+}
+
+/// Scales a refraction computed for standard conditions to the given pressure and temperature.
+fn meteorological_factor(pressure: f64, temperature: f64) -> f64 {
+    (pressure / STANDARD_PRESSURE) * (283.0 / (273.0 + temperature))
+}
+
+/// Bennett's formula for refraction as a function of observed elevation.
+///
+/// Accurate to about 0.07' for elevations between 0 and 90 degrees under standard conditions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bennett {
+    /// Pressure in hPa
+    pub pressure: f64,
+    /// Temperature in degrees Celsius
+    pub temperature: f64,
+}
+
+impl Bennett {
+    /// Constructs the model for standard conditions of 1010 hPa and 10 °C.
+    pub fn standard() -> Self {
+        Bennett {
+            pressure: STANDARD_PRESSURE,
+            temperature: STANDARD_TEMPERATURE,
+        }
+    }
+
+    /// Constructs the model for the meteorological conditions of an observer, falling back to standard values.
+    pub fn for_observer(observer: &Observer) -> Self {
+        Bennett {
+            pressure: observer.pressure.unwrap_or(STANDARD_PRESSURE),
+            temperature: observer.temperature.unwrap_or(STANDARD_TEMPERATURE),
+        }
+    }
+}
+
+impl Refraction for Bennett {
+    fn at_observed(&self, observed_elevation: f64) -> f64 {
+        let h = observed_elevation.to_degrees().max(-1.0);
+        let arcminutes = 1.0 / (h + 7.31 / (h + 4.4)).to_radians().tan();
+        (arcminutes / 60.0).to_radians() * meteorological_factor(self.pressure, self.temperature)
+    }
+
+    fn at_topocentric(&self, topocentric_elevation: f64) -> f64 {
+        invert_at_observed(self, topocentric_elevation)
+    }
+}
+
+/// Sæmundsson's formula for refraction as a function of topocentric (true) elevation.
+///
+/// Consistent with Bennett's formula to about 0.07' under standard conditions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Saemundsson {
+    /// Pressure in hPa
+    pub pressure: f64,
+    /// Temperature in degrees Celsius
+    pub temperature: f64,
+}
+
+impl Saemundsson {
+    /// Constructs the model for standard conditions of 1010 hPa and 10 °C.
+    pub fn standard() -> Self {
+        Saemundsson {
+            pressure: STANDARD_PRESSURE,
+            temperature: STANDARD_TEMPERATURE,
+        }
+    }
+
+    /// Constructs the model for the meteorological conditions of an observer, falling back to standard values.
+    pub fn for_observer(observer: &Observer) -> Self {
+        Saemundsson {
+            pressure: observer.pressure.unwrap_or(STANDARD_PRESSURE),
+            temperature: observer.temperature.unwrap_or(STANDARD_TEMPERATURE),
+        }
+    }
+}
+
+impl Refraction for Saemundsson {
+    fn at_observed(&self, observed_elevation: f64) -> f64 {
+        invert_at_topocentric(self, observed_elevation)
+    }
+
+    fn at_topocentric(&self, topocentric_elevation: f64) -> f64 {
+        let h = topocentric_elevation.to_degrees().max(-1.5);
+        let arcminutes = 1.02 / (h + 10.3 / (h + 5.11)).to_radians().tan();
+        (arcminutes / 60.0).to_radians() * meteorological_factor(self.pressure, self.temperature)
+    }
+}
+
+/// The A tan z + B tan³ z refraction model with constants computed as in SOFA `iauRefco`.
+///
+/// The model is accurate to a few milliarcseconds down to elevations of about 15 degrees and
+/// degrades towards the horizon; below an elevation of about 2.9 degrees it is evaluated at
+/// that limit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RefractionConstants {
+    /// The tan z coefficient in radians
+    pub a: f64,
+    /// The tan³ z coefficient in radians
+    pub b: f64,
+}
+
+impl RefractionConstants {
+    /// Computes the refraction constants for the given conditions.
+    ///
+    /// # Arguments
+    ///
+    /// * `pressure` - Pressure at the observer in hPa.
+    /// * `temperature` - Ambient temperature at the observer in degrees Celsius.
+    /// * `humidity` - Relative humidity at the observer as a fraction in the range [0, 1].
+    /// * `wavelength` - Effective wavelength in micrometers; values above 100 select the radio model.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use astro_carta::astrometry::refraction::RefractionConstants;
+    ///
+    /// let constants = RefractionConstants::new(800.0, 10.0, 0.9, 0.4);
+    /// assert!((constants.a - 0.226_494_995_624_141_5e-3).abs() < 1e-15);
+    /// assert!((constants.b + 0.259_865_826_172_934_4e-6).abs() < 1e-18);
+    /// ```
+    pub fn new(pressure: f64, temperature: f64, humidity: f64, wavelength: f64) -> Self {
+        let optical = wavelength <= RADIO_WAVELENGTH_THRESHOLD;
+
+        let t = temperature.clamp(-150.0, 100.0);
+        let p = pressure.clamp(0.0, 10_000.0);
+        let r = humidity.clamp(0.0, 1.0);
+        let w = wavelength.clamp(0.1, 1e6);
+
+        // Water vapour pressure at the observer
+        let pw = if p > 0.0 {
+            let ps = 10.0_f64.powf((0.7859 + 0.03477 * t) / (1.0 + 0.00412 * t))
+                * (1.0 + p * (4.5e-6 + 6e-10 * t * t));
+            r * ps / (1.0 - (1.0 - r) * ps / p)
+        } else {
+            0.0
+        };
+
+        // Refractive index minus one at the observer
+        let tk = t + 273.15;
+        let gamma = if optical {
+            let wlsq = w * w;
+            ((77.534_84e-6 + (4.391_08e-7 + 3.666e-9 / wlsq) / wlsq) * p - 11.2684e-6 * pw) / tk
+        } else {
+            (77.6890e-6 * p - (6.3938e-6 - 0.375_463 / tk) * pw) / tk
+        };
+
+        // Formula for beta from Stone, with empirical adjustments
+        let mut beta = 4.4474e-6 * tk;
+        if !optical {
+            beta -= 0.0074 * pw * beta;
+        }
+
+        RefractionConstants {
+            a: gamma * (1.0 - beta),
+            b: -gamma * (beta - gamma / 2.0),
+        }
+    }
+
+    /// Computes the refraction constants for the meteorological conditions of an observer, falling back to standard values.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - The observing site.
+    /// * `wavelength` - Effective wavelength in micrometers; values above 100 select the radio model.
+    pub fn for_observer(observer: &Observer, wavelength: f64) -> Self {
+        RefractionConstants::new(
+            observer.pressure.unwrap_or(STANDARD_PRESSURE),
+            observer.temperature.unwrap_or(STANDARD_TEMPERATURE),
+            observer.humidity.unwrap_or(STANDARD_HUMIDITY),
+            wavelength,
+        )
+    }
+
+    /// Returns tan z for an elevation, limited near the horizon.
+    fn tan_zenith_distance(elevation: f64) -> f64 {
+        let (s, c) = elevation.sin_cos();
+        c / s.max(MIN_SIN_ELEVATION)
+    }
+}
+
+impl Refraction for RefractionConstants {
+    fn at_observed(&self, observed_elevation: f64) -> f64 {
+        let tz = RefractionConstants::tan_zenith_distance(observed_elevation);
+        (self.a + self.b * tz * tz) * tz
+    }
+
+    fn at_topocentric(&self, topocentric_elevation: f64) -> f64 {
+        invert_at_observed(self, topocentric_elevation)
+    }
+}
+
+/// No refraction; observed and topocentric elevations coincide.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NoRefraction;
+
+impl Refraction for NoRefraction {
+    fn at_observed(&self, _observed_elevation: f64) -> f64 {
+        0.0
+    }
+
+    fn at_topocentric(&self, _topocentric_elevation: f64) -> f64 {
+        0.0
+    }
+}
+
+/// Converts a topocentric elevation into an observed elevation using the given model.
+pub fn topocentric_to_observed<R: Refraction + ?Sized>(
+    model: &R,
+    topocentric_elevation: f64,
+) -> f64 {
+    (topocentric_elevation + model.at_topocentric(topocentric_elevation)).min(FRAC_PI_2)
+}
+
+/// Converts an observed elevation into a topocentric elevation using the given model.
+pub fn observed_to_topocentric<R: Refraction + ?Sized>(model: &R, observed_elevation: f64) -> f64 {
+    observed_elevation - model.at_observed(observed_elevation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arcminutes(angle: f64) -> f64 {
+        angle.to_degrees() * 60.0
+    }
+
+    #[test]
+    fn bennett_test() {
+        let model = Bennett::standard();
+
+        // About 34.5' at the horizon and vanishing at the zenith
+        assert!((arcminutes(model.at_observed(0.0)) - 34.48).abs() < 0.01);
+        assert!(arcminutes(model.at_observed(FRAC_PI_2)).abs() < 0.01);
+
+        // Refraction decreases monotonically with elevation
+        let mut previous = f64::INFINITY;
+        for degrees in 0..=90 {
+            let r = model.at_observed((degrees as f64).to_radians());
+            assert!(r <= previous);
+            previous = r;
+        }
+    }
+
+    #[test]
+    fn bennett_saemundsson_consistency_test() {
+        let bennett = Bennett::standard();
+        let saemundsson = Saemundsson::standard();
+
+        for degrees in [0.5_f64, 2.0, 5.0, 10.0, 20.0, 45.0, 80.0] {
+            let h = degrees.to_radians();
+            let r1 = saemundsson.at_topocentric(h);
+            let r2 = bennett.at_topocentric(h);
+            assert!((arcminutes(r1 - r2)).abs() < 0.1);
+        }
+    }
+
+    #[test]
+    fn inversion_test() {
+        let models: [&dyn Refraction; 3] = [
+            &Bennett::standard(),
+            &Saemundsson::standard(),
+            &RefractionConstants::new(1013.25, 15.0, 0.5, 0.55),
+        ];
+        for model in models {
+            for degrees in [3.0_f64, 10.0, 30.0, 60.0, 89.0] {
+                let topocentric = degrees.to_radians();
+                let observed = topocentric_to_observed(model, topocentric);
+                assert!(observed > topocentric);
+
+                let round_trip = observed_to_topocentric(model, observed);
+                assert!(arcminutes(round_trip - topocentric).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn meteorological_scaling_test() {
+        let standard = Bennett::standard().at_observed(0.2);
+        let thin = Bennett {
+            pressure: STANDARD_PRESSURE / 2.0,
+            temperature: STANDARD_TEMPERATURE,
+        }
+        .at_observed(0.2);
+        assert!((thin - standard / 2.0).abs() < 1e-15);
+
+        let observer = Observer::new(0.0, 0.0, 0.0).with_pressure(505.0);
+        assert_eq!(
+            Bennett::for_observer(&observer),
+            Bennett {
+                pressure: 505.0,
+                temperature: STANDARD_TEMPERATURE
+            }
+        );
+    }
+
+    #[test]
+    fn refco_test() {
+        // SOFA t_sofa_c test case for iauRefco
+        let c = RefractionConstants::new(800.0, 10.0, 0.9, 0.4);
+        assert!((c.a - 0.226_494_995_624_141_5e-3).abs() < 1e-15);
+        assert!((c.b + 0.259_865_826_172_934_4e-6).abs() < 1e-18);
+
+        // Radio refraction is larger than optical in humid air
+        let radio = RefractionConstants::new(800.0, 10.0, 0.9, 1e5);
+        assert!(radio.a > c.a);
+
+        // About 58" at 45 degrees under sea level conditions
+        let sea_level = RefractionConstants::new(1013.25, 15.0, 0.0, 0.55);
+        let r = sea_level.at_observed(std::f64::consts::FRAC_PI_4);
+        assert!((r.to_degrees() * 3600.0 - 58.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn no_refraction_test() {
+        assert_eq!(topocentric_to_observed(&NoRefraction, 0.3), 0.3);
+        assert_eq!(observed_to_topocentric(&NoRefraction, 0.3), 0.3);
+    }
+}
@@ -0,0 +1,130 @@
+use super::nutation;
+use crate::datetime::{DateTime, TimeScale};
+use crate::math;
+
+/// Returns the number of days of UT1 elapsed since J2000.0.
+///
+/// # Arguments
+///
+/// * `dt` - The instant.
+/// * `dut1` - UT1 - UTC in seconds, as published by the IERS.
+pub fn ut1_days_since_j2000(dt: &DateTime, dut1: f64) -> f64 {
+    dt.days_since_j2000(TimeScale::UTC) + dut1 / 86_400.0
+}
+
+/// Computes the Earth rotation angle (IAU 2000) in radians.
+///
+/// # Arguments
+///
+/// * `dt` - The instant.
+/// * `dut1` - UT1 - UTC in seconds.
+pub fn earth_rotation_angle(dt: &DateTime, dut1: f64) -> f64 {
+    let days = ut1_days_since_j2000(dt, dut1);
+    math::normalize_angle(
+        math::TWO_PI * (0.779_057_273_264_0 + 0.002_737_811_911_354_48 * days + days.fract()),
+    )
+}
+
+/// Computes the Greenwich mean sidereal time (IAU 1982) in radians.
+///
+/// # Arguments
+///
+/// * `dt` - The instant.
+/// * `dut1` - UT1 - UTC in seconds.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::astrometry::sidereal;
+/// use astro_carta::datetime::{DateTime, TimeScale};
+///
+/// // Meeus, Astronomical Algorithms, Example 12.a
+/// let dt = DateTime::gregorian_with_scale(1987, 4, 10, 0, 0, 0.0, TimeScale::UTC).unwrap();
+/// let gmst = sidereal::greenwich_mean_sidereal_time(&dt, 0.0);
+/// assert!((gmst.to_degrees() - 197.693_195).abs() < 1e-6);
+/// ```
+pub fn greenwich_mean_sidereal_time(dt: &DateTime, dut1: f64) -> f64 {
+    let days = ut1_days_since_j2000(dt, dut1);
+    let t = days / 36_525.0;
+    let degrees = 280.460_618_37 + 360.985_647_366_29 * days + 0.000_387_933 * t * t
+        - t * t * t / 38_710_000.0;
+    math::normalize_angle(degrees.to_radians())
+}
+
+/// Computes the Greenwich apparent sidereal time in radians.
+///
+/// # Arguments
+///
+/// * `dt` - The instant.
+/// * `dut1` - UT1 - UTC in seconds.
+pub fn greenwich_apparent_sidereal_time(dt: &DateTime, dut1: f64) -> f64 {
+    math::normalize_angle(
+        greenwich_mean_sidereal_time(dt, dut1) + nutation::equation_of_the_equinoxes(dt),
+    )
+}
+
+/// Computes the local apparent sidereal time in radians.
+///
+/// # Arguments
+///
+/// * `dt` - The instant.
+/// * `dut1` - UT1 - UTC in seconds.
+/// * `longitude` - East longitude of the observer in radians.
+pub fn local_apparent_sidereal_time(dt: &DateTime, dut1: f64, longitude: f64) -> f64 {
+    math::normalize_angle(greenwich_apparent_sidereal_time(dt, dut1) + longitude)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Converts hours, minutes and seconds of time into radians.
+    fn hms(hours: f64, minutes: f64, seconds: f64) -> f64 {
+        ((hours + minutes / 60.0 + seconds / 3600.0) * 15.0).to_radians()
+    }
+
+    /// Converts an angle in radians into seconds of time.
+    fn seconds_of_time(angle: f64) -> f64 {
+        angle.to_degrees() / 15.0 * 3600.0
+    }
+
+    #[test]
+    fn meeus_example_12a_test() {
+        let dt = DateTime::gregorian_with_scale(1987, 4, 10, 0, 0, 0.0, TimeScale::UTC).unwrap();
+
+        let gmst = greenwich_mean_sidereal_time(&dt, 0.0);
+        assert!(seconds_of_time(gmst - hms(13.0, 10.0, 46.3668)).abs() < 1e-4);
+
+        let gast = greenwich_apparent_sidereal_time(&dt, 0.0);
+        assert!(seconds_of_time(gast - hms(13.0, 10.0, 46.1351)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn meeus_example_12b_test() {
+        let dt = DateTime::gregorian_with_scale(1987, 4, 10, 19, 21, 0.0, TimeScale::UTC).unwrap();
+
+        let gmst = greenwich_mean_sidereal_time(&dt, 0.0);
+        assert!(seconds_of_time(gmst - hms(8.0, 34.0, 57.0896)).abs() < 1e-4);
+
+        // UT1 - UTC shifts sidereal time by about 1.0027 times as much
+        let shifted = greenwich_mean_sidereal_time(&dt, 1.0);
+        assert!((seconds_of_time(shifted - gmst) - 1.002_737_9).abs() < 1e-4);
+    }
+
+    #[test]
+    fn earth_rotation_angle_test() {
+        // At J2000.0 UT1 the ERA is 0.7790572732640 turns
+        let dt = DateTime::from_julian_date(2_451_545.0, TimeScale::UTC);
+        let era = earth_rotation_angle(&dt, 0.0);
+        assert!((era - 0.779_057_273_264_0 * math::TWO_PI).abs() < 1e-12);
+
+        // GMST runs ahead of the ERA by the accumulated precession in right ascension
+        let dt = DateTime::gregorian_with_scale(2024, 3, 16, 5, 0, 0.0, TimeScale::UTC).unwrap();
+        let t = dt.centuries_since_j2000(TimeScale::TT);
+        let diff = math::normalize_angle_signed(
+            greenwich_mean_sidereal_time(&dt, 0.0) - earth_rotation_angle(&dt, 0.0),
+        );
+        let precession = math::arcseconds_to_radians(0.014_506 + 4_612.157_4 * t);
+        assert!(seconds_of_time(diff - precession).abs() < 0.01);
+    }
+}
@@ -0,0 +1,65 @@
+/// Speed of light in vacuum in m/s
+pub const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+
+/// Astronomical unit in meters (IAU 2012)
+pub const ASTRONOMICAL_UNIT: f64 = 149_597_870_700.0;
+
+/// Light time for one astronomical unit in seconds
+pub const LIGHT_TIME_ASTRONOMICAL_UNIT: f64 = ASTRONOMICAL_UNIT / SPEED_OF_LIGHT;
+
+/// Schwarzschild radius of the Sun in astronomical units (2GM/c²)
+pub const SCHWARZSCHILD_RADIUS_SUN: f64 = 1.974_125_743_36e-8;
+
+/// Number of seconds in a day
+pub const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Number of days in a Julian year
+pub const DAYS_PER_JULIAN_YEAR: f64 = 365.25;
+
+/// Nominal mean angular velocity of the Earth in rad/s (IERS Conventions 2010)
+pub const EARTH_ANGULAR_VELOCITY: f64 = 7.292_115e-5;
+
+/// Geocentric gravitational constant in m³/s² (IERS Conventions 2010, TT-compatible)
+pub const EARTH_GRAVITATIONAL_PARAMETER: f64 = 3.986_004_418e14;
+
+/// Heliocentric gravitational constant in m³/s² (IAU 2009, TDB-compatible)
+pub const SUN_GRAVITATIONAL_PARAMETER: f64 = 1.327_124_400_41e20;
+
+/// Selenocentric gravitational constant in m³/s² (DE430)
+pub const MOON_GRAVITATIONAL_PARAMETER: f64 = 4.902_800_066e12;
+
+/// Unnormalized second zonal harmonic of the Earth's gravity field (EGM2008)
+pub const EARTH_J2: f64 = 1.082_626_68e-3;
+
+/// Equatorial radius of the Earth in meters associated with `EARTH_J2` (EGM2008)
+pub const EARTH_EQUATORIAL_RADIUS: f64 = 6_378_136.3;
+
+/// Gravitational parameter of Mercury in m³/s² (DE440)
+pub const MERCURY_GRAVITATIONAL_PARAMETER: f64 = 2.203_186_855_1e13;
+
+/// Gravitational parameter of Venus in m³/s² (DE440)
+pub const VENUS_GRAVITATIONAL_PARAMETER: f64 = 3.248_585_92e14;
+
+/// Gravitational parameter of the Mars system in m³/s² (DE440)
+pub const MARS_SYSTEM_GRAVITATIONAL_PARAMETER: f64 = 4.282_837_581_6e13;
+
+/// Gravitational parameter of the Jupiter system in m³/s² (DE440)
+pub const JUPITER_SYSTEM_GRAVITATIONAL_PARAMETER: f64 = 1.267_127_641e17;
+
+/// Gravitational parameter of the Saturn system in m³/s² (DE440)
+pub const SATURN_SYSTEM_GRAVITATIONAL_PARAMETER: f64 = 3.794_058_484_18e16;
+
+/// Gravitational parameter of the Uranus system in m³/s² (DE440)
+pub const URANUS_SYSTEM_GRAVITATIONAL_PARAMETER: f64 = 5.794_556_4e15;
+
+/// Gravitational parameter of the Neptune system in m³/s² (DE440)
+pub const NEPTUNE_SYSTEM_GRAVITATIONAL_PARAMETER: f64 = 6.836_527_100_58e15;
+
+/// Nominal radius of the Sun in meters (IAU 2015 Resolution B3)
+pub const SUN_RADIUS: f64 = 6.957e8;
+
+/// Nominal total solar irradiance at one astronomical unit in W/m² (IAU 2015 Resolution B3)
+pub const SOLAR_IRRADIANCE: f64 = 1361.0;
+
+/// Standard acceleration of gravity in m/s², relating specific impulse to exhaust velocity
+pub const STANDARD_GRAVITY: f64 = 9.806_65;
@@ -0,0 +1,87 @@
+pub mod horizontal;
+pub mod observer;
+
+pub use observer::Observer;
+
+use crate::math::{Matrix3, Vector3};
+
+/// Equatorial spherical coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Equatorial {
+    /// Right ascension in radians
+    pub right_ascension: f64,
+    /// Declination in radians
+    pub declination: f64,
+}
+
+impl Equatorial {
+    /// Constructs new equatorial coordinates from a right ascension and declination in radians.
+    pub fn new(right_ascension: f64, declination: f64) -> Self {
+        Equatorial {
+            right_ascension,
+            declination,
+        }
+    }
+}
+
+/// Ecliptic spherical coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ecliptic {
+    /// Ecliptic longitude in radians
+    pub longitude: f64,
+    /// Ecliptic latitude in radians
+    pub latitude: f64,
+}
+
+impl Ecliptic {
+    /// Constructs new ecliptic coordinates from a longitude and latitude in radians.
+    pub fn new(longitude: f64, latitude: f64) -> Self {
+        Ecliptic {
+            longitude,
+            latitude,
+        }
+    }
+
+    /// Converts to equatorial coordinates using the given obliquity of the ecliptic in radians.
+    pub fn to_equatorial(&self, obliquity: f64) -> Equatorial {
+        let direction = Vector3::from_spherical(self.longitude, self.latitude);
+        let (right_ascension, declination) =
+            (Matrix3::rot_x(-obliquity) * direction).to_spherical();
+        Equatorial::new(right_ascension, declination)
+    }
+}
+
+/// Horizontal (topocentric) spherical coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Horizontal {
+    /// Azimuth in radians, measured from north towards east
+    pub azimuth: f64,
+    /// Elevation (altitude) above the horizon in radians
+    pub elevation: f64,
+}
+
+impl Horizontal {
+    /// Constructs new horizontal coordinates from an azimuth and elevation in radians.
+    pub fn new(azimuth: f64, elevation: f64) -> Self {
+        Horizontal { azimuth, elevation }
+    }
+
+    /// Returns the zenith distance in radians.
+    pub fn zenith_distance(&self) -> f64 {
+        std::f64::consts::FRAC_PI_2 - self.elevation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ecliptic_to_equatorial_test() {
+        // Meeus, Astronomical Algorithms, Example 13.a (Pollux)
+        let ecliptic = Ecliptic::new(113.215_630_f64.to_radians(), 6.684_170_f64.to_radians());
+        let equatorial = ecliptic.to_equatorial(23.439_281_1_f64.to_radians());
+        assert!((equatorial.right_ascension.to_degrees() - 116.328_942).abs() < 1e-5);
+        assert!((equatorial.declination.to_degrees() - 28.026_183).abs() < 1e-5);
+    }
+}
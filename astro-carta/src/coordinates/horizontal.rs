@@ -0,0 +1,135 @@
+use super::Horizontal;
+use crate::math;
+
+/// Converts a local hour angle and declination into horizontal coordinates.
+///
+/// # Arguments
+///
+/// * `hour_angle` - Local hour angle in radians, positive west of the meridian.
+/// * `declination` - Declination in radians.
+/// * `latitude` - Latitude of the observer in radians.
+///
+/// # Returns
+///
+/// The azimuth (north through east) in the range [0, 2π) and the elevation.
+pub fn hour_angle_to_horizontal(hour_angle: f64, declination: f64, latitude: f64) -> Horizontal {
+    let (sh, ch) = hour_angle.sin_cos();
+    let (sd, cd) = declination.sin_cos();
+    let (sp, cp) = latitude.sin_cos();
+
+    let x = -ch * cd * sp + sd * cp;
+    let y = -sh * cd;
+    let z = ch * cd * cp + sd * sp;
+
+    let r = x.hypot(y);
+    let azimuth = if r != 0.0 {
+        math::normalize_angle(y.atan2(x))
+    } else {
+        0.0
+    };
+
+    Horizontal::new(azimuth, z.atan2(r))
+}
+
+/// Converts horizontal coordinates into a local hour angle and declination.
+///
+/// # Arguments
+///
+/// * `horizontal` - Azimuth (north through east) and elevation in radians.
+/// * `latitude` - Latitude of the observer in radians.
+///
+/// # Returns
+///
+/// The local hour angle in the range [-π, π] and the declination, both in radians.
+pub fn horizontal_to_hour_angle(horizontal: &Horizontal, latitude: f64) -> (f64, f64) {
+    let (sa, ca) = horizontal.azimuth.sin_cos();
+    let (se, ce) = horizontal.elevation.sin_cos();
+    let (sp, cp) = latitude.sin_cos();
+
+    let x = -ca * ce * sp + se * cp;
+    let y = -sa * ce;
+    let z = ca * ce * cp + se * sp;
+
+    let r = x.hypot(y);
+    let hour_angle = if r != 0.0 { y.atan2(x) } else { 0.0 };
+
+    (hour_angle, z.atan2(r))
+}
+
+/// Computes the parallactic angle, the position angle of the zenith measured from north through east.
+///
+/// # Arguments
+///
+/// * `hour_angle` - Local hour angle in radians, positive west of the meridian.
+/// * `declination` - Declination in radians.
+/// * `latitude` - Latitude of the observer in radians.
+pub fn parallactic_angle(hour_angle: f64, declination: f64, latitude: f64) -> f64 {
+    let (sp, cp) = latitude.sin_cos();
+    let (sd, cd) = declination.sin_cos();
+    let sqsz = cp * hour_angle.sin();
+    let cqsz = sp * cd - cp * sd * hour_angle.cos();
+
+    if sqsz != 0.0 || cqsz != 0.0 {
+        sqsz.atan2(cqsz)
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn meridian_test() {
+        // An object on the meridian south of the zenith
+        let latitude = 0.7;
+        let declination = 0.2;
+        let h = hour_angle_to_horizontal(0.0, declination, latitude);
+        assert!((h.azimuth - PI).abs() < 1e-15);
+        assert!((h.elevation - (FRAC_PI_2 - latitude + declination)).abs() < 1e-15);
+        assert_eq!(parallactic_angle(0.0, declination, latitude), 0.0);
+
+        // The celestial pole sits due north at an elevation equal to the latitude
+        let h = hour_angle_to_horizontal(1.3, FRAC_PI_2, latitude);
+        assert!((h.elevation - latitude).abs() < 1e-15);
+        assert!(math::normalize_angle_signed(h.azimuth).abs() < 1e-15);
+    }
+
+    #[test]
+    fn round_trip_test() {
+        for latitude in [-1.2, -0.3, 0.0, 0.5, 1.4] {
+            for hour_angle in [-3.0, -1.0, -0.1, 0.4, 2.0, 3.1] {
+                for declination in [-1.5, -0.6, 0.0, 0.3, 1.2] {
+                    let h = hour_angle_to_horizontal(hour_angle, declination, latitude);
+                    assert!((0.0..math::TWO_PI).contains(&h.azimuth));
+
+                    let (ha, dec) = horizontal_to_hour_angle(&h, latitude);
+                    assert!(math::normalize_angle_signed(ha - hour_angle).abs() < 1e-12);
+                    assert!((dec - declination).abs() < 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parallactic_angle_test() {
+        // West of the meridian the zenith lies to the west of north (positive angle) in the north
+        let latitude = 0.8;
+        assert!(parallactic_angle(0.5, 0.1, latitude) > 0.0);
+        assert!(parallactic_angle(-0.5, 0.1, latitude) < 0.0);
+
+        // Symmetric about the meridian
+        let q1 = parallactic_angle(1.1, -0.2, latitude);
+        let q2 = parallactic_angle(-1.1, -0.2, latitude);
+        assert!((q1 + q2).abs() < 1e-15);
+
+        // The angle subtended at the object between the pole and zenith satisfies the sine rule
+        let (ha, dec) = (1.1_f64, -0.2_f64);
+        let h = hour_angle_to_horizontal(ha, dec, latitude);
+        let lhs = q1.sin() / latitude.cos();
+        let rhs = ha.sin() / h.elevation.cos();
+        assert!((lhs - rhs).abs() < 1e-12);
+    }
+}
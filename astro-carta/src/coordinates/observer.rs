@@ -0,0 +1,330 @@
+use super::horizontal;
+use super::{Equatorial, Horizontal};
+use crate::astrometry::refraction::{self, Refraction};
+use crate::astrometry::{precession, sidereal};
+use crate::constants::EARTH_ANGULAR_VELOCITY;
+use crate::datetime::DateTime;
+use crate::geodesy::{Ellipsoid, Geodetic};
+use crate::math::{self, Matrix3, Vector3};
+
+/// A geodetic observing site on the Earth
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observer {
+    /// Geodetic latitude in radians, positive north
+    pub latitude: f64,
+    /// Longitude in radians, positive east
+    pub longitude: f64,
+    /// Height above the reference ellipsoid in meters
+    pub height: f64,
+    /// Ambient pressure at the site in hPa
+    pub pressure: Option<f64>,
+    /// Ambient temperature at the site in degrees Celsius
+    pub temperature: Option<f64>,
+    /// Relative humidity at the site as a fraction in the range [0, 1]
+    pub humidity: Option<f64>,
+    /// Reference ellipsoid of the geodetic coordinates
+    pub ellipsoid: Ellipsoid,
+}
+
+impl Observer {
+    /// Constructs a new `Observer` without meteorological data.
+    ///
+    /// # Arguments
+    ///
+    /// * `latitude` - Geodetic latitude in radians, positive north.
+    /// * `longitude` - Longitude in radians, positive east.
+    /// * `height` - Height above the reference ellipsoid in meters.
+    pub fn new(latitude: f64, longitude: f64, height: f64) -> Self {
+        Observer {
+            latitude,
+            longitude,
+            height,
+            pressure: None,
+            temperature: None,
+            humidity: None,
+            ellipsoid: Ellipsoid::WGS84,
+        }
+    }
+
+    /// Constructs a new `Observer` from a latitude and longitude in degrees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use astro_carta::coordinates::Observer;
+    ///
+    /// let greenwich = Observer::from_degrees(51.4769, 0.0, 46.0);
+    /// assert!((greenwich.latitude - 51.4769_f64.to_radians()).abs() < 1e-15);
+    /// ```
+    pub fn from_degrees(latitude: f64, longitude: f64, height: f64) -> Self {
+        Observer::new(latitude.to_radians(), longitude.to_radians(), height)
+    }
+
+    /// Returns the observer with the given ambient pressure in hPa.
+    pub fn with_pressure(mut self, pressure: f64) -> Self {
+        self.pressure = Some(pressure);
+        self
+    }
+
+    /// Returns the observer with the given ambient temperature in degrees Celsius.
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Returns the observer with the given relative humidity as a fraction in the range [0, 1].
+    pub fn with_humidity(mut self, humidity: f64) -> Self {
+        self.humidity = Some(humidity);
+        self
+    }
+
+    /// Returns the observer with its geodetic coordinates referred to the given ellipsoid.
+    pub fn with_ellipsoid(mut self, ellipsoid: Ellipsoid) -> Self {
+        self.ellipsoid = ellipsoid;
+        self
+    }
+
+    /// Returns the geodetic coordinates of the site.
+    pub fn geodetic(&self) -> Geodetic {
+        Geodetic::new(self.latitude, self.longitude, self.height)
+    }
+
+    /// Computes the Earth-fixed (ITRS) position of the site in meters.
+    pub fn geocentric_position(&self) -> Vector3 {
+        self.ellipsoid.geodetic_to_cartesian(&self.geodetic())
+    }
+
+    /// Computes the geocentric position (m) and velocity (m/s) of the site in the GCRS.
+    ///
+    /// Polar motion is neglected.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - The instant.
+    /// * `dut1` - UT1 - UTC in seconds.
+    pub fn gcrs_position_velocity(&self, dt: &DateTime, dut1: f64) -> (Vector3, Vector3) {
+        let true_of_date = Matrix3::rot_z(-sidereal::greenwich_apparent_sidereal_time(dt, dut1))
+            * self.geocentric_position();
+        let velocity = Vector3::new(0.0, 0.0, EARTH_ANGULAR_VELOCITY).cross(&true_of_date);
+
+        let to_gcrs = precession::bias_precession_nutation_matrix(dt).transpose();
+        (to_gcrs * true_of_date, to_gcrs * velocity)
+    }
+
+    /// Computes the local apparent sidereal time at the site in radians.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - The instant.
+    /// * `dut1` - UT1 - UTC in seconds.
+    pub fn local_apparent_sidereal_time(&self, dt: &DateTime, dut1: f64) -> f64 {
+        sidereal::local_apparent_sidereal_time(dt, dut1, self.longitude)
+    }
+
+    /// Computes the local hour angle of a right ascension in radians, in the range [-π, π).
+    ///
+    /// # Arguments
+    ///
+    /// * `right_ascension` - Apparent right ascension in radians.
+    /// * `dt` - The instant.
+    /// * `dut1` - UT1 - UTC in seconds.
+    pub fn hour_angle(&self, right_ascension: f64, dt: &DateTime, dut1: f64) -> f64 {
+        math::normalize_angle_signed(self.local_apparent_sidereal_time(dt, dut1) - right_ascension)
+    }
+
+    /// Converts apparent equatorial coordinates into horizontal coordinates at the site.
+    ///
+    /// The equatorial coordinates must be referred to the true equator and equinox of date and
+    /// already be topocentric; no refraction is applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `equatorial` - Apparent right ascension and declination.
+    /// * `dt` - The instant.
+    /// * `dut1` - UT1 - UTC in seconds.
+    pub fn horizontal(&self, equatorial: &Equatorial, dt: &DateTime, dut1: f64) -> Horizontal {
+        let hour_angle = self.hour_angle(equatorial.right_ascension, dt, dut1);
+        horizontal::hour_angle_to_horizontal(hour_angle, equatorial.declination, self.latitude)
+    }
+
+    /// Converts horizontal coordinates at the site into apparent equatorial coordinates.
+    ///
+    /// # Arguments
+    ///
+    /// * `horizontal` - Azimuth and elevation, without refraction.
+    /// * `dt` - The instant.
+    /// * `dut1` - UT1 - UTC in seconds.
+    pub fn equatorial(&self, horizontal: &Horizontal, dt: &DateTime, dut1: f64) -> Equatorial {
+        let (hour_angle, declination) =
+            horizontal::horizontal_to_hour_angle(horizontal, self.latitude);
+        Equatorial::new(
+            math::normalize_angle(self.local_apparent_sidereal_time(dt, dut1) - hour_angle),
+            declination,
+        )
+    }
+
+    /// Converts apparent equatorial coordinates into observed horizontal coordinates, applying refraction.
+    ///
+    /// # Arguments
+    ///
+    /// * `equatorial` - Apparent topocentric right ascension and declination.
+    /// * `dt` - The instant.
+    /// * `dut1` - UT1 - UTC in seconds.
+    /// * `refraction` - The refraction model to apply.
+    pub fn observed_horizontal(
+        &self,
+        equatorial: &Equatorial,
+        dt: &DateTime,
+        dut1: f64,
+        refraction: &dyn Refraction,
+    ) -> Horizontal {
+        let topocentric = self.horizontal(equatorial, dt, dut1);
+        Horizontal::new(
+            topocentric.azimuth,
+            refraction::topocentric_to_observed(refraction, topocentric.elevation),
+        )
+    }
+
+    /// Converts observed horizontal coordinates into apparent equatorial coordinates, removing refraction.
+    ///
+    /// # Arguments
+    ///
+    /// * `observed` - Observed azimuth and refracted elevation.
+    /// * `dt` - The instant.
+    /// * `dut1` - UT1 - UTC in seconds.
+    /// * `refraction` - The refraction model to remove.
+    pub fn equatorial_from_observed(
+        &self,
+        observed: &Horizontal,
+        dt: &DateTime,
+        dut1: f64,
+        refraction: &dyn Refraction,
+    ) -> Equatorial {
+        let topocentric = Horizontal::new(
+            observed.azimuth,
+            refraction::observed_to_topocentric(refraction, observed.elevation),
+        );
+        self.equatorial(&topocentric, dt, dut1)
+    }
+
+    /// Computes the parallactic angle of an object at the site in radians.
+    ///
+    /// # Arguments
+    ///
+    /// * `equatorial` - Apparent right ascension and declination.
+    /// * `dt` - The instant.
+    /// * `dut1` - UT1 - UTC in seconds.
+    pub fn parallactic_angle(&self, equatorial: &Equatorial, dt: &DateTime, dut1: f64) -> f64 {
+        let hour_angle = self.hour_angle(equatorial.right_ascension, dt, dut1);
+        horizontal::parallactic_angle(hour_angle, equatorial.declination, self.latitude)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::astrometry::refraction::RefractionConstants;
+    use crate::datetime::TimeScale;
+
+    fn dms(degrees: f64, minutes: f64, seconds: f64) -> f64 {
+        (degrees.signum() * (degrees.abs() + minutes / 60.0 + seconds / 3600.0)).to_radians()
+    }
+
+    /// Meeus, Astronomical Algorithms, Example 13.b: Venus seen from the US Naval Observatory
+    fn meeus_example_13b() -> (Observer, Equatorial, DateTime) {
+        let observer = Observer::new(dms(38.0, 55.0, 17.0), -dms(77.0, 3.0, 56.0), 0.0);
+        let venus = Equatorial::new(
+            ((23.0 + 9.0 / 60.0 + 16.641 / 3600.0) * 15.0_f64).to_radians(),
+            -dms(6.0, 43.0, 11.61),
+        );
+        let dt = DateTime::gregorian_with_scale(1987, 4, 10, 19, 21, 0.0, TimeScale::UTC).unwrap();
+        (observer, venus, dt)
+    }
+
+    #[test]
+    fn meeus_example_13b_test() {
+        let (observer, venus, dt) = meeus_example_13b();
+
+        // Apparent sidereal time 8h34m56.853s less the longitude and right ascension
+        let hour_angle = observer.hour_angle(venus.right_ascension, &dt, 0.0);
+        assert!((hour_angle.to_degrees() - 64.351_992).abs() < 1e-4);
+
+        // Meeus measures azimuth from the south, 68.0337 degrees, and rounds the hour angle
+        // used in the worked example, so agreement is limited to about half an arcsecond
+        let h = observer.horizontal(&venus, &dt, 0.0);
+        assert!((h.azimuth.to_degrees() - 248.0337).abs() < 2e-4);
+        assert!((h.elevation.to_degrees() - 15.1249).abs() < 2e-4);
+
+        let round_trip = observer.equatorial(&h, &dt, 0.0);
+        assert!((round_trip.right_ascension - venus.right_ascension).abs() < 1e-12);
+        assert!((round_trip.declination - venus.declination).abs() < 1e-12);
+    }
+
+    #[test]
+    fn observed_horizontal_test() {
+        let (observer, venus, dt) = meeus_example_13b();
+        let observer = observer
+            .with_pressure(1013.0)
+            .with_temperature(12.0)
+            .with_humidity(0.4);
+        let model = RefractionConstants::for_observer(&observer, 0.55);
+
+        let topocentric = observer.horizontal(&venus, &dt, 0.0);
+        let observed = observer.observed_horizontal(&venus, &dt, 0.0, &model);
+        assert_eq!(observed.azimuth, topocentric.azimuth);
+
+        // About 3.5' of refraction at 15 degrees elevation
+        let refraction = (observed.elevation - topocentric.elevation).to_degrees() * 60.0;
+        assert!((refraction - 3.5).abs() < 0.2);
+
+        let round_trip = observer.equatorial_from_observed(&observed, &dt, 0.0, &model);
+        assert!((round_trip.right_ascension - venus.right_ascension).abs() < 1e-9);
+        assert!((round_trip.declination - venus.declination).abs() < 1e-9);
+    }
+
+    #[test]
+    fn parallactic_angle_test() {
+        let (observer, venus, dt) = meeus_example_13b();
+
+        // Venus is west of the meridian, so the zenith lies west of north
+        let q = observer.parallactic_angle(&venus, &dt, 0.0);
+        assert!(q > 0.0 && q < std::f64::consts::PI);
+    }
+
+    #[test]
+    fn geocentric_position_test() {
+        // On the equator and at the pole
+        let p = Observer::new(0.0, 0.0, 0.0).geocentric_position();
+        assert!((p - Vector3::new(Ellipsoid::WGS84.semi_major_axis, 0.0, 0.0)).norm() < 1e-9);
+
+        let p = Observer::from_degrees(90.0, 0.0, 100.0).geocentric_position();
+        assert!((p.z - 6_356_852.314_245).abs() < 1e-6);
+
+        let observer = Observer::from_degrees(-33.9, 18.4, 20.0);
+        let (position, velocity) = observer
+            .gcrs_position_velocity(&DateTime::gregorian(2024, 1, 1, 0, 0, 0.0).unwrap(), 0.0);
+        assert!((position.norm() - observer.geocentric_position().norm()).abs() < 1e-6);
+
+        // Rotational speed at the latitude of Cape Town
+        let itrs = observer.geocentric_position();
+        let expected = EARTH_ANGULAR_VELOCITY * itrs.x.hypot(itrs.y);
+        assert!((velocity.norm() - expected).abs() < 1e-9);
+        assert!(velocity.dot(&position).abs() < 1e-3);
+    }
+
+    #[test]
+    fn builder_test() {
+        let observer = Observer::from_degrees(-24.6272, -70.4042, 2635.0)
+            .with_pressure(743.0)
+            .with_temperature(10.0)
+            .with_humidity(0.2);
+        assert_eq!(observer.pressure, Some(743.0));
+        assert_eq!(observer.temperature, Some(10.0));
+        assert_eq!(observer.humidity, Some(0.2));
+        assert_eq!(Observer::new(0.1, 0.2, 3.0).pressure, None);
+        assert_eq!(observer.ellipsoid, Ellipsoid::WGS84);
+
+        let grs80 = observer.with_ellipsoid(Ellipsoid::GRS80);
+        assert!((grs80.geocentric_position() - observer.geocentric_position()).norm() < 1e-3);
+    }
+}
@@ -1,10 +1,42 @@
+pub mod interval;
 mod month;
+pub mod range;
+#[cfg_attr(test, allow(clippy::approx_constant))]
 pub mod timedelta;
+pub mod timescale;
+// The calendar helpers predate the lint gate and are kept as written
+#[allow(
+    clippy::manual_is_multiple_of,
+    clippy::manual_range_contains,
+    clippy::manual_map,
+    clippy::needless_return,
+    clippy::unnecessary_cast
+)]
+#[cfg_attr(test, allow(clippy::bool_assert_comparison))]
 mod utils;
 
+use std::ops;
+
+pub use interval::{Interval, IntervalSet};
+pub use range::DateTimeRange;
 pub use timedelta::TimeDelta;
+pub use timescale::TimeScale;
+
+/// Julian date of the J2000.0 epoch (2000-01-01 12:00:00)
+pub const J2000_JULIAN_DATE: f64 = 2_451_545.0;
+
+/// Julian date of the implicit epoch of 0001-01-01 00:00:00
+pub const EPOCH_JULIAN_DATE: f64 = 1_721_425.5;
+
+/// Number of days in a Julian century
+pub const DAYS_PER_JULIAN_CENTURY: f64 = 36_525.0;
+
+/// Nanoseconds from the implicit epoch to J2000.0 (2000-01-01 12:00:00)
+const J2000_NANOSECONDS: i128 =
+    730_119 * timedelta::NANOSECONDS_PER_DAY + 12 * timedelta::NANOSECONDS_PER_HOUR;
 
 /// Represents an instant in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DateTime {
     /// Duration since the implicit epoch of 0001-01-01 00:00:00 TAI
     duration: TimeDelta,
@@ -22,17 +54,13 @@ impl DateTime {
         if !utils::is_valid_year_month_day(year, month, day)
             || hour > 23
             || minute > 59
-            || second < 0.0
-            || second >= 60.0
+            || !(0.0..60.0).contains(&second)
         {
             return None;
         }
 
         // Compute number of integer days since the implicit epoch of 0001-01-01 00:00:00 TAI
-        let prev_year = (year - 1) as i128;
-        let doy = utils::day_of_year(year, month, day)? as i128;
-        let abs_days =
-            doy - 1 + 365 * prev_year + prev_year / 4 - prev_year / 100 + prev_year / 400;
+        let abs_days = utils::days_since_epoch(year, month, day)?;
 
         Some(DateTime {
             duration: TimeDelta::new(
@@ -43,4 +71,241 @@ impl DateTime {
             ),
         })
     }
+
+    /// Constructs a `DateTime` from a proleptic Gregorian calendar date and time of day read in the given time scale.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use astro_carta::datetime::{DateTime, TimeDelta, TimeScale};
+    ///
+    /// let utc = DateTime::gregorian_with_scale(2020, 1, 1, 0, 0, 0.0, TimeScale::UTC).unwrap();
+    /// let tai = DateTime::gregorian(2020, 1, 1, 0, 0, 37.0).unwrap();
+    /// assert_eq!(utc, tai);
+    /// ```
+    pub fn gregorian_with_scale(
+        year: u64,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: f64,
+        scale: TimeScale,
+    ) -> Option<Self> {
+        let reading = DateTime::gregorian(year, month, day, hour, minute, second)?.duration;
+        Some(DateTime::from_reading(reading, scale))
+    }
+
+    /// Constructs a `DateTime` from a year and a fractional day of the year read in the given
+    /// time scale, where 1.0 is January 1 at 00:00:00, as used by two-line element sets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use astro_carta::datetime::{DateTime, TimeScale};
+    ///
+    /// let dt = DateTime::from_day_of_year(2024, 60.75, TimeScale::UTC).unwrap();
+    /// assert_eq!(dt, DateTime::gregorian_with_scale(2024, 2, 29, 18, 0, 0.0, TimeScale::UTC).unwrap());
+    /// ```
+    pub fn from_day_of_year(year: u64, day: f64, scale: TimeScale) -> Option<Self> {
+        let days_in_year = if utils::is_leap_year(year) {
+            366.0
+        } else {
+            365.0
+        };
+        if !utils::is_valid_year(year) || !(1.0..days_in_year + 1.0).contains(&day) {
+            return None;
+        }
+
+        let start = utils::days_since_epoch(year, 1, 1)? * timedelta::NANOSECONDS_PER_DAY;
+        let reading = TimeDelta::new(
+            start + ((day - 1.0) * timedelta::NANOSECONDS_PER_DAY as f64).round() as i128,
+        );
+        Some(DateTime::from_reading(reading, scale))
+    }
+
+    /// Returns the proleptic Gregorian calendar date and time of day of the instant read in the given time scale.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of `(year, month, day, hour, minute, second)`, or `None` if the instant precedes the implicit epoch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use astro_carta::datetime::{DateTime, TimeScale};
+    ///
+    /// let dt = DateTime::gregorian(2017, 1, 1, 0, 0, 35.5).unwrap();
+    /// assert_eq!(dt.to_gregorian(TimeScale::UTC), Some((2016, 12, 31, 23, 59, 59.5)));
+    /// ```
+    pub fn to_gregorian(&self, scale: TimeScale) -> Option<(u64, u8, u8, u8, u8, f64)> {
+        let nanoseconds = self.reading(scale).total_nanoseconds();
+        let days = nanoseconds.div_euclid(timedelta::NANOSECONDS_PER_DAY);
+        let time_of_day = nanoseconds.rem_euclid(timedelta::NANOSECONDS_PER_DAY);
+        let (year, month, day) = utils::date_from_days_since_epoch(days)?;
+
+        let hour = time_of_day / timedelta::NANOSECONDS_PER_HOUR;
+        let minute =
+            (time_of_day % timedelta::NANOSECONDS_PER_HOUR) / timedelta::NANOSECONDS_PER_MINUTE;
+        let second = (time_of_day % timedelta::NANOSECONDS_PER_MINUTE) as f64
+            / timedelta::NANOSECONDS_PER_SECOND as f64;
+
+        Some((year, month, day, hour as u8, minute as u8, second))
+    }
+
+    /// Constructs a `DateTime` from a Julian date in the given time scale.
+    ///
+    /// The conversion is limited by the precision of `f64` to roughly 20 microseconds for
+    /// present-day dates; use `from_days_since_j2000` where more precision is required.
+    pub fn from_julian_date(julian_date: f64, scale: TimeScale) -> Self {
+        DateTime::from_days_since_j2000(julian_date - J2000_JULIAN_DATE, scale)
+    }
+
+    /// Constructs a `DateTime` from the number of days since J2000.0 in the given time scale.
+    pub fn from_days_since_j2000(days: f64, scale: TimeScale) -> Self {
+        let reading = TimeDelta::new(
+            J2000_NANOSECONDS + (days * timedelta::NANOSECONDS_PER_DAY as f64).round() as i128,
+        );
+        DateTime::from_reading(reading, scale)
+    }
+
+    /// Constructs a `DateTime` from a duration since the implicit epoch read in the given time scale.
+    fn from_reading(reading: TimeDelta, scale: TimeScale) -> Self {
+        DateTime {
+            duration: reading - scale.offset_from_tai_at_reading(reading),
+        }
+    }
+
+    /// Returns the duration since the implicit epoch of 0001-01-01 00:00:00 read in the given time scale.
+    fn reading(&self, scale: TimeScale) -> TimeDelta {
+        self.duration + scale.offset_from_tai(self.duration)
+    }
+
+    /// Returns the number of days elapsed since J2000.0 (2000-01-01 12:00:00) in the given time scale.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use astro_carta::datetime::{DateTime, TimeScale};
+    ///
+    /// let dt = DateTime::gregorian_with_scale(2000, 1, 2, 12, 0, 0.0, TimeScale::TT).unwrap();
+    /// assert_eq!(dt.days_since_j2000(TimeScale::TT), 1.0);
+    /// ```
+    pub fn days_since_j2000(&self, scale: TimeScale) -> f64 {
+        (self.reading(scale) - TimeDelta::new(J2000_NANOSECONDS)).total_days()
+    }
+
+    /// Returns the number of Julian centuries elapsed since J2000.0 in the given time scale.
+    pub fn centuries_since_j2000(&self, scale: TimeScale) -> f64 {
+        self.days_since_j2000(scale) / DAYS_PER_JULIAN_CENTURY
+    }
+
+    /// Returns the Julian date of the instant in the given time scale.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use astro_carta::datetime::{DateTime, TimeScale};
+    ///
+    /// let dt = DateTime::gregorian(1957, 10, 4, 19, 26, 24.0).unwrap();
+    /// assert!((dt.julian_date(TimeScale::TAI) - 2436116.31).abs() < 1e-9);
+    /// ```
+    pub fn julian_date(&self, scale: TimeScale) -> f64 {
+        J2000_JULIAN_DATE + self.days_since_j2000(scale)
+    }
+}
+
+impl ops::Add<TimeDelta> for DateTime {
+    type Output = DateTime;
+
+    fn add(self, rhs: TimeDelta) -> DateTime {
+        DateTime {
+            duration: self.duration + rhs,
+        }
+    }
+}
+
+impl ops::Sub<TimeDelta> for DateTime {
+    type Output = DateTime;
+
+    fn sub(self, rhs: TimeDelta) -> DateTime {
+        DateTime {
+            duration: self.duration - rhs,
+        }
+    }
+}
+
+impl ops::Sub<DateTime> for DateTime {
+    type Output = TimeDelta;
+
+    fn sub(self, rhs: DateTime) -> TimeDelta {
+        self.duration - rhs.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn julian_date_test() {
+        let dt = DateTime::gregorian(2000, 1, 1, 12, 0, 0.0).unwrap();
+        assert_eq!(dt.julian_date(TimeScale::TAI), J2000_JULIAN_DATE);
+        assert!((dt.days_since_j2000(TimeScale::TT) - 32.184 / 86400.0).abs() < 1e-15);
+
+        let dt = DateTime::gregorian(1, 1, 1, 0, 0, 0.0).unwrap();
+        assert_eq!(dt.julian_date(TimeScale::TAI), EPOCH_JULIAN_DATE);
+
+        let dt = DateTime::from_julian_date(2_446_895.5, TimeScale::TAI);
+        assert_eq!(dt, DateTime::gregorian(1987, 4, 10, 0, 0, 0.0).unwrap());
+    }
+
+    #[test]
+    fn time_scale_round_trip_test() {
+        let dt = DateTime::gregorian(2024, 3, 16, 7, 8, 9.5).unwrap();
+        for scale in [
+            TimeScale::TAI,
+            TimeScale::TT,
+            TimeScale::TDB,
+            TimeScale::UTC,
+            TimeScale::GPS,
+        ] {
+            let days = dt.days_since_j2000(scale);
+            let round_trip = DateTime::from_days_since_j2000(days, scale);
+            assert!((round_trip - dt).total_seconds().abs() < 1e-6);
+        }
+
+        let utc = DateTime::gregorian_with_scale(2024, 3, 16, 7, 8, 9.5, TimeScale::UTC).unwrap();
+        assert_eq!(dt - utc, TimeDelta::seconds(-37.0));
+    }
+
+    #[test]
+    fn to_gregorian_test() {
+        let dt = DateTime::gregorian(2024, 3, 16, 7, 8, 9.5).unwrap();
+        assert_eq!(
+            dt.to_gregorian(TimeScale::TAI),
+            Some((2024, 3, 16, 7, 8, 9.5))
+        );
+        assert_eq!(
+            dt.to_gregorian(TimeScale::UTC),
+            Some((2024, 3, 16, 7, 7, 32.5))
+        );
+        assert_eq!(
+            dt.to_gregorian(TimeScale::GPS),
+            Some((2024, 3, 16, 7, 7, 50.5))
+        );
+
+        let dt = DateTime::gregorian(1, 1, 1, 0, 0, 0.0).unwrap();
+        assert_eq!(dt.to_gregorian(TimeScale::UTC), None);
+    }
+
+    #[test]
+    fn arithmetic_test() {
+        let dt = DateTime::gregorian(2024, 2, 28, 23, 0, 0.0).unwrap();
+        let later = dt + TimeDelta::hours(2.0);
+        assert_eq!(later, DateTime::gregorian(2024, 2, 29, 1, 0, 0.0).unwrap());
+        assert_eq!(later - dt, TimeDelta::hours(2.0));
+        assert_eq!(later - TimeDelta::hours(2.0), dt);
+        assert!(dt < later);
+    }
 }
@@ -0,0 +1,240 @@
+use super::{DateTime, TimeDelta};
+
+/// A closed span of time between two instants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval {
+    pub start: DateTime,
+    pub end: DateTime,
+}
+
+impl Interval {
+    /// Constructs an interval, or returns `None` if the end precedes the start.
+    pub fn new(start: DateTime, end: DateTime) -> Option<Self> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    pub fn duration(&self) -> TimeDelta {
+        self.end - self.start
+    }
+
+    pub fn contains(&self, dt: &DateTime) -> bool {
+        self.start <= *dt && *dt <= self.end
+    }
+
+    /// Returns the common part of two intervals, if they overlap.
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+}
+
+/// A set of instants made of disjoint intervals, such as the windows of visibility of a
+/// satellite
+///
+/// The intervals are kept sorted, and overlapping or touching intervals are merged.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::{DateTime, Interval, IntervalSet, TimeDelta};
+///
+/// let start = DateTime::gregorian(2024, 1, 1, 0, 0, 0.0).unwrap();
+/// let hours = |from: f64, to: f64| {
+///     Interval::new(start + TimeDelta::hours(from), start + TimeDelta::hours(to)).unwrap()
+/// };
+/// let visible = IntervalSet::from_intervals([hours(1.0, 3.0), hours(2.0, 4.0), hours(6.0, 7.0)]);
+/// assert_eq!(visible.intervals(), &[hours(1.0, 4.0), hours(6.0, 7.0)]);
+///
+/// let sunlit = IntervalSet::from_intervals([hours(0.0, 2.0), hours(5.0, 8.0)]);
+/// let both = visible.intersection(&sunlit);
+/// assert_eq!(both.intervals(), &[hours(1.0, 2.0), hours(6.0, 7.0)]);
+/// assert_eq!(both.duration(), TimeDelta::hours(2.0));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    /// Constructs an empty set.
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    /// Constructs the union of intervals given in any order.
+    pub fn from_intervals<I: IntoIterator<Item = Interval>>(intervals: I) -> Self {
+        let mut intervals: Vec<Interval> = intervals.into_iter().collect();
+        intervals.sort_by_key(|interval| interval.start);
+        let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+        IntervalSet { intervals: merged }
+    }
+
+    /// Returns the disjoint intervals in chronological order.
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Interval> {
+        self.intervals.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Adds an interval, merging it with those it overlaps.
+    pub fn insert(&mut self, interval: Interval) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval);
+        *self = IntervalSet::from_intervals(intervals);
+    }
+
+    /// Returns whether an instant lies in one of the intervals.
+    pub fn contains(&self, dt: &DateTime) -> bool {
+        let index = self
+            .intervals
+            .partition_point(|interval| interval.end < *dt);
+        self.intervals
+            .get(index)
+            .is_some_and(|interval| interval.contains(dt))
+    }
+
+    /// Returns the total duration of the intervals.
+    pub fn duration(&self) -> TimeDelta {
+        self.intervals
+            .iter()
+            .fold(TimeDelta::new(0), |total, interval| {
+                total + interval.duration()
+            })
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        IntervalSet::from_intervals(self.iter().chain(other.iter()).copied())
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (&self.intervals[i], &other.intervals[j]);
+            if let Some(common) = a.intersection(b) {
+                intervals.push(common);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet::from_intervals(intervals)
+    }
+
+    /// Returns the instants of a span that are not in the set, such as the gaps between
+    /// windows.
+    pub fn complement(&self, span: &Interval) -> IntervalSet {
+        let mut intervals = Vec::new();
+        let mut start = span.start;
+        for interval in &self.intervals {
+            if interval.start > start && start < span.end {
+                intervals.push(Interval {
+                    start,
+                    end: interval.start.min(span.end),
+                });
+            }
+            start = start.max(interval.end);
+        }
+        if start < span.end {
+            intervals.push(Interval {
+                start,
+                end: span.end,
+            });
+        }
+        IntervalSet::from_intervals(intervals)
+    }
+}
+
+impl<'a> IntoIterator for &'a IntervalSet {
+    type Item = &'a Interval;
+    type IntoIter = std::slice::Iter<'a, Interval>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.intervals.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hours(from: f64, to: f64) -> Interval {
+        let start = DateTime::gregorian(2024, 1, 1, 0, 0, 0.0).unwrap();
+        Interval::new(start + TimeDelta::hours(from), start + TimeDelta::hours(to)).unwrap()
+    }
+
+    #[test]
+    fn interval_test() {
+        let a = hours(1.0, 3.0);
+        assert_eq!(a.duration(), TimeDelta::hours(2.0));
+        assert!(a.contains(&a.start) && a.contains(&a.end));
+        assert_eq!(a.intersection(&hours(2.0, 5.0)), Some(hours(2.0, 3.0)));
+        assert_eq!(a.intersection(&hours(4.0, 5.0)), None);
+        assert_eq!(Interval::new(a.end, a.start), None);
+    }
+
+    #[test]
+    fn set_test() {
+        let mut set = IntervalSet::from_intervals([hours(5.0, 6.0), hours(1.0, 2.0)]);
+        set.insert(hours(2.0, 3.0));
+        set.insert(hours(8.0, 9.0));
+        assert_eq!(
+            set.intervals(),
+            &[hours(1.0, 3.0), hours(5.0, 6.0), hours(8.0, 9.0)]
+        );
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.duration(), TimeDelta::hours(4.0));
+        assert!(set.contains(&hours(5.5, 6.0).start));
+        assert!(!set.contains(&hours(4.0, 6.0).start));
+        assert!(!set.contains(&hours(10.0, 11.0).start));
+
+        let other = IntervalSet::from_intervals([hours(0.0, 1.5), hours(2.5, 8.5)]);
+        assert_eq!(
+            set.intersection(&other).intervals(),
+            &[
+                hours(1.0, 1.5),
+                hours(2.5, 3.0),
+                hours(5.0, 6.0),
+                hours(8.0, 8.5)
+            ]
+        );
+        assert_eq!(set.union(&other).intervals(), &[hours(0.0, 9.0)]);
+
+        let gaps = set.complement(&hours(0.0, 10.0));
+        assert_eq!(
+            gaps.intervals(),
+            &[
+                hours(0.0, 1.0),
+                hours(3.0, 5.0),
+                hours(6.0, 8.0),
+                hours(9.0, 10.0)
+            ]
+        );
+        assert_eq!(
+            set.complement(&hours(1.5, 5.5)).intervals(),
+            &[hours(3.0, 5.0)]
+        );
+        assert!(set.complement(&hours(1.5, 2.5)).is_empty());
+        assert_eq!(
+            IntervalSet::new().complement(&hours(1.0, 2.0)).intervals(),
+            &[hours(1.0, 2.0)]
+        );
+    }
+}
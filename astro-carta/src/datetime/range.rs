@@ -0,0 +1,96 @@
+use super::{DateTime, TimeDelta};
+
+/// An iterator over evenly spaced instants
+///
+/// Each instant is computed from the start rather than accumulated, so long ranges do not
+/// drift. The end is included when it falls on a step.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::{DateTime, DateTimeRange, TimeDelta};
+///
+/// let start = DateTime::gregorian(2024, 1, 1, 0, 0, 0.0).unwrap();
+/// let end = DateTime::gregorian(2024, 1, 1, 1, 0, 0.0).unwrap();
+/// let epochs: Vec<_> = DateTimeRange::new(start, end, TimeDelta::minutes(20.0)).collect();
+/// assert_eq!(epochs.len(), 4);
+/// assert_eq!(epochs[3], end);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateTimeRange {
+    start: DateTime,
+    end: DateTime,
+    step: TimeDelta,
+    index: i128,
+}
+
+impl DateTimeRange {
+    /// Constructs a range from `start` towards `end` in increments of `step`.
+    ///
+    /// A negative step runs backwards in time. The range is empty if the step is zero or points
+    /// away from the end.
+    pub fn new(start: DateTime, end: DateTime, step: TimeDelta) -> Self {
+        DateTimeRange {
+            start,
+            end,
+            step,
+            index: 0,
+        }
+    }
+}
+
+impl Iterator for DateTimeRange {
+    type Item = DateTime;
+
+    fn next(&mut self) -> Option<DateTime> {
+        let step = self.step.total_nanoseconds();
+        let instant = self.start + TimeDelta::new(step * self.index);
+        let within = match step.signum() {
+            1 => instant <= self.end,
+            -1 => instant >= self.end,
+            _ => false,
+        };
+        if within {
+            self.index += 1;
+            Some(instant)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_test() {
+        let start = DateTime::gregorian(2024, 3, 1, 0, 0, 0.0).unwrap();
+        let end = start + TimeDelta::seconds(10.5);
+
+        let forward: Vec<_> = DateTimeRange::new(start, end, TimeDelta::seconds(3.0)).collect();
+        assert_eq!(forward.len(), 4);
+        assert_eq!(forward[3] - start, TimeDelta::seconds(9.0));
+
+        let backward: Vec<_> = DateTimeRange::new(end, start, TimeDelta::seconds(-3.5)).collect();
+        assert_eq!(
+            backward,
+            [
+                end,
+                end - TimeDelta::seconds(3.5),
+                end - TimeDelta::seconds(7.0),
+                start
+            ]
+        );
+
+        assert_eq!(DateTimeRange::new(start, end, TimeDelta::new(0)).count(), 0);
+        assert_eq!(
+            DateTimeRange::new(end, start, TimeDelta::seconds(1.0)).count(),
+            0
+        );
+        assert_eq!(
+            DateTimeRange::new(start, start, TimeDelta::seconds(1.0)).count(),
+            1
+        );
+    }
+}
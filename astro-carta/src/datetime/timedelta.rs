@@ -10,7 +10,7 @@ pub const NANOSECONDS_PER_HOUR: i128 = 60 * NANOSECONDS_PER_MINUTE;
 pub const NANOSECONDS_PER_DAY: i128 = 24 * NANOSECONDS_PER_HOUR;
 
 /// Represents a time difference or interval measured in nanoseconds.
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct TimeDelta {
     nanoseconds: i128,
 }
@@ -0,0 +1,218 @@
+use super::timedelta::{NANOSECONDS_PER_DAY, NANOSECONDS_PER_SECOND};
+use super::utils;
+use super::TimeDelta;
+
+/// Offset of Terrestrial Time from International Atomic Time in nanoseconds (TT - TAI)
+pub const TT_MINUS_TAI_NANOSECONDS: i128 = 32_184_000_000;
+
+/// Offset of GPS time from International Atomic Time in nanoseconds (GPS - TAI)
+pub const GPS_MINUS_TAI_NANOSECONDS: i128 = -19 * NANOSECONDS_PER_SECOND;
+
+/// The time scales supported by `DateTime`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeScale {
+    /// International Atomic Time
+    TAI,
+    /// Terrestrial Time
+    TT,
+    /// Barycentric Dynamical Time
+    TDB,
+    /// Coordinated Universal Time
+    UTC,
+    /// GPS system time
+    GPS,
+}
+
+/// Dates (year, month) at which a new value of TAI - UTC (in seconds) took effect
+const LEAP_SECONDS: [(u64, u8, i128); 28] = [
+    (1972, 1, 10),
+    (1972, 7, 11),
+    (1973, 1, 12),
+    (1974, 1, 13),
+    (1975, 1, 14),
+    (1976, 1, 15),
+    (1977, 1, 16),
+    (1978, 1, 17),
+    (1979, 1, 18),
+    (1980, 1, 19),
+    (1981, 7, 20),
+    (1982, 7, 21),
+    (1983, 7, 22),
+    (1985, 7, 23),
+    (1988, 1, 24),
+    (1990, 1, 25),
+    (1991, 1, 26),
+    (1992, 7, 27),
+    (1993, 7, 28),
+    (1994, 7, 29),
+    (1996, 1, 30),
+    (1997, 7, 31),
+    (1999, 1, 32),
+    (2006, 1, 33),
+    (2009, 1, 34),
+    (2012, 7, 35),
+    (2015, 7, 36),
+    (2017, 1, 37),
+];
+
+/// Returns the nanoseconds since the implicit epoch at which the given leap second entry starts (in UTC).
+fn leap_second_start(entry: &(u64, u8, i128)) -> i128 {
+    utils::days_since_epoch(entry.0, entry.1, 1).unwrap_or(0) * NANOSECONDS_PER_DAY
+}
+
+/// Returns TAI - UTC in nanoseconds for an instant given as UTC nanoseconds since the implicit epoch.
+///
+/// Instants before 1972 use the initial offset of 10 seconds; the pre-1972 "rubber second" offsets are
+/// not modelled.
+fn tai_minus_utc_from_utc(utc: i128) -> i128 {
+    LEAP_SECONDS
+        .iter()
+        .rev()
+        .find(|entry| utc >= leap_second_start(entry))
+        .map_or(LEAP_SECONDS[0].2, |entry| entry.2)
+        * NANOSECONDS_PER_SECOND
+}
+
+/// Returns TAI - UTC in nanoseconds for an instant given as TAI nanoseconds since the implicit epoch.
+fn tai_minus_utc_from_tai(tai: i128) -> i128 {
+    LEAP_SECONDS
+        .iter()
+        .rev()
+        .find(|entry| tai >= leap_second_start(entry) + entry.2 * NANOSECONDS_PER_SECOND)
+        .map_or(LEAP_SECONDS[0].2, |entry| entry.2)
+        * NANOSECONDS_PER_SECOND
+}
+
+/// Returns TDB - TT in nanoseconds for an instant given as TT nanoseconds since the implicit epoch.
+///
+/// Uses the two-term periodic approximation from the Astronomical Almanac, which is accurate to
+/// about 30 microseconds over several centuries around J2000.
+fn tdb_minus_tt(tt: i128) -> i128 {
+    let days = (tt - super::J2000_NANOSECONDS) as f64 / NANOSECONDS_PER_DAY as f64;
+    let g = (357.53 + 0.985_600_28 * days).to_radians();
+    let seconds = 0.001_657 * g.sin() + 0.000_014 * (2.0 * g).sin();
+    (seconds * NANOSECONDS_PER_SECOND as f64).round() as i128
+}
+
+impl TimeScale {
+    /// Returns the offset of this time scale from TAI (scale - TAI) at the given TAI instant.
+    ///
+    /// # Arguments
+    ///
+    /// * `tai` - Duration since the implicit epoch of 0001-01-01 00:00:00 TAI.
+    pub fn offset_from_tai(&self, tai: TimeDelta) -> TimeDelta {
+        let tai = tai.total_nanoseconds();
+        TimeDelta::new(match self {
+            TimeScale::TAI => 0,
+            TimeScale::TT => TT_MINUS_TAI_NANOSECONDS,
+            TimeScale::TDB => {
+                TT_MINUS_TAI_NANOSECONDS + tdb_minus_tt(tai + TT_MINUS_TAI_NANOSECONDS)
+            }
+            TimeScale::UTC => -tai_minus_utc_from_tai(tai),
+            TimeScale::GPS => GPS_MINUS_TAI_NANOSECONDS,
+        })
+    }
+
+    /// Returns the offset of this time scale from TAI (scale - TAI) given a reading of this time scale.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Duration since the implicit epoch of 0001-01-01 00:00:00 in this time scale.
+    pub fn offset_from_tai_at_reading(&self, value: TimeDelta) -> TimeDelta {
+        let value = value.total_nanoseconds();
+        TimeDelta::new(match self {
+            TimeScale::TAI => 0,
+            TimeScale::TT => TT_MINUS_TAI_NANOSECONDS,
+            TimeScale::TDB => {
+                // TDB - TT varies slowly enough that evaluating it at TDB instead of TT is sub-nanosecond
+                TT_MINUS_TAI_NANOSECONDS + tdb_minus_tt(value)
+            }
+            TimeScale::UTC => -tai_minus_utc_from_utc(value),
+            TimeScale::GPS => GPS_MINUS_TAI_NANOSECONDS,
+        })
+    }
+
+    /// Returns the conventional abbreviation of the time scale.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeScale::TAI => "TAI",
+            TimeScale::TT => "TT",
+            TimeScale::TDB => "TDB",
+            TimeScale::UTC => "UTC",
+            TimeScale::GPS => "GPS",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tai_at(year: u64, month: u8, day: u8) -> TimeDelta {
+        TimeDelta::new(utils::days_since_epoch(year, month, day).unwrap() * NANOSECONDS_PER_DAY)
+    }
+
+    #[test]
+    fn fixed_offsets_test() {
+        let tai = tai_at(2020, 1, 1);
+        assert_eq!(TimeScale::TAI.offset_from_tai(tai), TimeDelta::new(0));
+        assert_eq!(
+            TimeScale::TT.offset_from_tai(tai),
+            TimeDelta::milliseconds(32_184.0)
+        );
+        assert_eq!(
+            TimeScale::GPS.offset_from_tai(tai),
+            TimeDelta::seconds(-19.0)
+        );
+    }
+
+    #[test]
+    fn utc_offset_test() {
+        assert_eq!(
+            TimeScale::UTC.offset_from_tai(tai_at(2020, 1, 1)),
+            TimeDelta::seconds(-37.0)
+        );
+        assert_eq!(
+            TimeScale::UTC.offset_from_tai(tai_at(1987, 4, 10)),
+            TimeDelta::seconds(-23.0)
+        );
+        assert_eq!(
+            TimeScale::UTC.offset_from_tai(tai_at(1960, 1, 1)),
+            TimeDelta::seconds(-10.0)
+        );
+
+        // The 2017-01-01 leap second: 2016-12-31 23:59:60 UTC is 2017-01-01 00:00:36 TAI
+        let boundary = tai_at(2017, 1, 1) + TimeDelta::seconds(36.0);
+        assert_eq!(
+            TimeScale::UTC.offset_from_tai(boundary - TimeDelta::new(1)),
+            TimeDelta::seconds(-36.0)
+        );
+        assert_eq!(
+            TimeScale::UTC.offset_from_tai(boundary + TimeDelta::seconds(1.0)),
+            TimeDelta::seconds(-37.0)
+        );
+
+        // Readings of UTC just either side of the leap second
+        assert_eq!(
+            TimeScale::UTC.offset_from_tai_at_reading(tai_at(2017, 1, 1) - TimeDelta::new(1)),
+            TimeDelta::seconds(-36.0)
+        );
+        assert_eq!(
+            TimeScale::UTC.offset_from_tai_at_reading(tai_at(2017, 1, 1)),
+            TimeDelta::seconds(-37.0)
+        );
+    }
+
+    #[test]
+    fn tdb_offset_test() {
+        // TDB - TT never exceeds about 1.7 ms
+        for year in [1900, 1950, 2000, 2024, 2100] {
+            for month in 1..=12 {
+                let tai = tai_at(year, month, 1);
+                let offset =
+                    TimeScale::TDB.offset_from_tai(tai) - TimeDelta::new(TT_MINUS_TAI_NANOSECONDS);
+                assert!(offset.total_seconds().abs() < 0.0017);
+            }
+        }
+    }
+}
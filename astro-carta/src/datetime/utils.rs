@@ -154,6 +154,79 @@ pub fn day_of_year(year: u64, month: u8, day: u8) -> Option<u16> {
     }
 }
 
+/// Calculates the number of whole days between the implicit epoch of 0001-01-01 and the given
+/// date in the proleptic Gregorian calendar.
+///
+/// # Arguments
+///
+/// * `year` - The year (e.g., 2024).
+/// * `month` - The month as an integer (1 for January, 2 for February, etc.).
+/// * `day` - The day of the month.
+///
+/// # Returns
+///
+/// * `Some(days)` - The number of days since 0001-01-01 if the input is valid.
+/// * `None` - If the input is not a valid date.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(days_since_epoch(1, 1, 1), Some(0));
+/// assert_eq!(days_since_epoch(2000, 1, 1), Some(730119));
+/// ```
+pub fn days_since_epoch(year: u64, month: u8, day: u8) -> Option<i128> {
+    let doy = day_of_year(year, month, day)? as i128;
+    let prev_year = (year - 1) as i128;
+    Some(doy - 1 + 365 * prev_year + prev_year / 4 - prev_year / 100 + prev_year / 400)
+}
+
+/// Converts a number of whole days since the implicit epoch of 0001-01-01 into a
+/// `(year, month, day)` date in the proleptic Gregorian calendar.
+///
+/// # Arguments
+///
+/// * `days` - The number of days since 0001-01-01. Must be non-negative.
+///
+/// # Returns
+///
+/// * `Some((year, month, day))` - The calendar date.
+/// * `None` - If `days` is negative.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(date_from_days_since_epoch(730119), Some((2000, 1, 1)));
+/// ```
+pub fn date_from_days_since_epoch(days: i128) -> Option<(u64, u8, u8)> {
+    if days < 0 {
+        return None;
+    }
+
+    // Count whole 400, 100, 4 and 1 year cycles
+    let n400 = days / 146_097;
+    let rem = days % 146_097;
+    let n100 = (rem / 36_524).min(3);
+    let rem = rem - n100 * 36_524;
+    let n4 = rem / 1_461;
+    let rem = rem % 1_461;
+    let n1 = (rem / 365).min(3);
+    let rem = rem - n1 * 365;
+
+    let year = (400 * n400 + 100 * n100 + 4 * n4 + n1 + 1) as u64;
+    let is_leap_year = is_leap_year(year);
+    let doy = rem as u16 + 1;
+
+    let mut month = 12;
+    while let Some(cumul_days) = super::month::cummulative_days_for_month(month, is_leap_year) {
+        if cumul_days < doy {
+            return Some((year, month, (doy - cumul_days) as u8));
+        }
+        month -= 1;
+    }
+
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -207,4 +280,29 @@ mod tests {
         assert_eq!(day_of_year(2024, 1, 32), None);
         assert_eq!(day_of_year(0, 3, 13), None);
     }
+
+    #[test]
+    fn days_since_epoch_test() {
+        assert_eq!(days_since_epoch(1, 1, 1), Some(0));
+        assert_eq!(days_since_epoch(1, 12, 31), Some(364));
+        assert_eq!(days_since_epoch(2000, 1, 1), Some(730119));
+        assert_eq!(days_since_epoch(2024, 2, 30), None);
+        assert_eq!(days_since_epoch(0, 1, 1), None);
+    }
+
+    #[test]
+    fn date_from_days_since_epoch_test() {
+        assert_eq!(date_from_days_since_epoch(0), Some((1, 1, 1)));
+        assert_eq!(date_from_days_since_epoch(730119), Some((2000, 1, 1)));
+        assert_eq!(date_from_days_since_epoch(-1), None);
+
+        // Round trip every day over several 400 year cycles boundaries
+        for year in [1, 4, 100, 399, 400, 401, 1600, 1900, 2000, 2023, 2024] {
+            for (month, day) in [(1, 1), (2, 28), (2, 29), (3, 1), (12, 30), (12, 31)] {
+                if let Some(days) = days_since_epoch(year, month, day) {
+                    assert_eq!(date_from_days_since_epoch(days), Some((year, month, day)));
+                }
+            }
+        }
+    }
 }
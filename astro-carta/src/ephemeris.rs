@@ -0,0 +1,93 @@
+pub mod daf;
+pub mod jpl;
+pub mod moon;
+pub mod pck;
+pub mod spk;
+pub mod sun;
+pub mod vsop87;
+
+use std::ops;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::coordinates::{Ecliptic, Equatorial};
+use crate::datetime::DateTime;
+use crate::math::Vector3;
+
+/// Geocentric position of a solar system body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeocentricPosition {
+    /// Ecliptic coordinates referred to the equinox of date
+    pub ecliptic: Ecliptic,
+    /// Equatorial coordinates referred to the equator and equinox of date
+    pub equatorial: Equatorial,
+    /// Distance from the center of the Earth in meters
+    pub distance: f64,
+}
+
+/// Position and velocity of a body relative to another
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StateVector {
+    /// Position in meters
+    pub position: Vector3,
+    /// Velocity in m/s
+    pub velocity: Vector3,
+}
+
+impl ops::Add for StateVector {
+    type Output = StateVector;
+
+    fn add(self, other: StateVector) -> StateVector {
+        StateVector {
+            position: self.position + other.position,
+            velocity: self.velocity + other.velocity,
+        }
+    }
+}
+
+impl ops::Sub for StateVector {
+    type Output = StateVector;
+
+    fn sub(self, other: StateVector) -> StateVector {
+        StateVector {
+            position: self.position - other.position,
+            velocity: self.velocity - other.velocity,
+        }
+    }
+}
+
+/// A source of positions and velocities of solar system bodies
+///
+/// Bodies are identified by their NAIF codes, such as 0 for the solar system barycenter, 10 for
+/// the Sun, 1 to 9 for the planetary system barycenters, 399 for the Earth and 301 for the Moon.
+pub trait Ephemeris {
+    /// Error raised when a state cannot be computed
+    type Error: std::error::Error;
+
+    /// Computes the state of `target` relative to `center` at the given instant, evaluated in TDB.
+    fn state(&self, target: i32, center: i32, dt: &DateTime) -> Result<StateVector, Self::Error>;
+
+    /// Computes the position of `target` relative to `center` in meters at the given instant,
+    /// evaluated in TDB.
+    fn position(&self, target: i32, center: i32, dt: &DateTime) -> Result<Vector3, Self::Error> {
+        self.state(target, center, dt).map(|state| state.position)
+    }
+}
+
+/// An ephemeris shared between several users, such as the force models of a propagator
+impl<E: Ephemeris + ?Sized> Ephemeris for Rc<E> {
+    type Error = E::Error;
+
+    fn state(&self, target: i32, center: i32, dt: &DateTime) -> Result<StateVector, Self::Error> {
+        (**self).state(target, center, dt)
+    }
+}
+
+/// An ephemeris shared between threads
+impl<E: Ephemeris + ?Sized> Ephemeris for Arc<E> {
+    type Error = E::Error;
+
+    fn state(&self, target: i32, center: i32, dt: &DateTime) -> Result<StateVector, Self::Error> {
+        (**self).state(target, center, dt)
+    }
+}
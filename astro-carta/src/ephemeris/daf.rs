@@ -0,0 +1,472 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::math::interpolation;
+
+/// Size of a DAF physical record in bytes
+const RECORD_SIZE: usize = 1024;
+
+/// Size of a DAF word (one double precision number) in bytes
+const WORD_SIZE: usize = 8;
+
+/// Maximum number of summary records followed before the file is deemed corrupt
+const MAX_SUMMARY_RECORDS: usize = 100_000;
+
+/// Error raised while reading a DAF file
+#[derive(Debug)]
+pub enum DafError {
+    /// The underlying reader failed
+    Io(io::Error),
+    /// The file is not a valid DAF file
+    InvalidFile(&'static str),
+}
+
+impl fmt::Display for DafError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DafError::Io(error) => write!(f, "I/O error: {error}"),
+            DafError::InvalidFile(reason) => write!(f, "invalid DAF file: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for DafError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DafError::Io(error) => Some(error),
+            DafError::InvalidFile(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for DafError {
+    fn from(error: io::Error) -> Self {
+        DafError::Io(error)
+    }
+}
+
+/// The summary of one array stored in a DAF file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    /// Name of the array
+    pub name: String,
+    /// Double precision components of the summary
+    pub doubles: Vec<f64>,
+    /// Integer components of the summary; by convention the last two are the initial and
+    /// final addresses of the array
+    pub integers: Vec<i32>,
+}
+
+/// A NAIF Double precision Array File, the container of SPK and binary PCK kernels
+///
+/// The file record and the array summaries are read when the file is opened; array data are
+/// read from the underlying reader on demand, so that large kernels are never held in memory.
+#[derive(Debug)]
+pub struct Daf<R> {
+    reader: Mutex<R>,
+    /// Length of the file in bytes, which bounds the arrays read
+    length: u64,
+    little_endian: bool,
+    identifier: String,
+    internal_name: String,
+    summaries: Vec<Summary>,
+}
+
+impl Daf<BufReader<File>> {
+    /// Opens the DAF file at the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, DafError> {
+        Daf::from_reader(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> Daf<R> {
+    /// Reads the file record and array summaries from a reader positioned anywhere.
+    pub fn from_reader(mut reader: R) -> Result<Self, DafError> {
+        let file_record = read_record(&mut reader, 1)?;
+
+        let identifier = text(&file_record[0..8]);
+        if !identifier.starts_with("DAF/") && identifier != "NAIF/DAF" {
+            return Err(DafError::InvalidFile("missing DAF identification word"));
+        }
+
+        let little_endian = match &file_record[88..96] {
+            b"LTL-IEEE" => true,
+            b"BIG-IEEE" => false,
+            // Files predating the format word: infer the byte order from ND
+            _ => (1..=124).contains(&i32::from_le_bytes(file_record[8..12].try_into().unwrap())),
+        };
+        let integer = |bytes: &[u8]| {
+            let bytes = bytes.try_into().unwrap();
+            if little_endian {
+                i32::from_le_bytes(bytes)
+            } else {
+                i32::from_be_bytes(bytes)
+            }
+        };
+        let double = |bytes: &[u8]| {
+            let bytes = bytes.try_into().unwrap();
+            if little_endian {
+                f64::from_le_bytes(bytes)
+            } else {
+                f64::from_be_bytes(bytes)
+            }
+        };
+
+        let nd = integer(&file_record[8..12]);
+        let ni = integer(&file_record[12..16]);
+        if !(0..=124).contains(&nd) || !(2..=250).contains(&ni) || nd + (ni + 1) / 2 > 125 {
+            return Err(DafError::InvalidFile("invalid summary format"));
+        }
+        let (nd, ni) = (nd as usize, ni as usize);
+        let internal_name = text(&file_record[16..76]);
+        let summary_words = nd + ni.div_ceil(2);
+        let name_length = summary_words * WORD_SIZE;
+
+        let mut summaries = Vec::new();
+        let mut record = integer(&file_record[76..80]);
+        for _ in 0..MAX_SUMMARY_RECORDS {
+            if record == 0 {
+                break;
+            }
+            if record < 0 {
+                return Err(DafError::InvalidFile("invalid summary record pointer"));
+            }
+
+            let summary_record = read_record(&mut reader, record as usize)?;
+            let name_record = read_record(&mut reader, record as usize + 1)?;
+            let count = double(&summary_record[16..24]);
+            let capacity = (RECORD_SIZE / WORD_SIZE - 3) / summary_words;
+            if !(count >= 0.0 && count <= capacity as f64) {
+                return Err(DafError::InvalidFile("too many summaries in record"));
+            }
+            let count = count as usize;
+
+            for index in 0..count {
+                let offset = 3 * WORD_SIZE + index * summary_words * WORD_SIZE;
+                let doubles = (0..nd)
+                    .map(|i| double(&summary_record[offset + i * 8..offset + i * 8 + 8]))
+                    .collect();
+                let integers_offset = offset + nd * WORD_SIZE;
+                let integers = (0..ni)
+                    .map(|i| {
+                        integer(
+                            &summary_record[integers_offset + i * 4..integers_offset + i * 4 + 4],
+                        )
+                    })
+                    .collect();
+                let name = text(&name_record[index * name_length..(index + 1) * name_length]);
+
+                summaries.push(Summary {
+                    name,
+                    doubles,
+                    integers,
+                });
+            }
+
+            record = double(&summary_record[0..8]) as i32;
+        }
+        if record != 0 {
+            return Err(DafError::InvalidFile(
+                "summary record chain does not terminate",
+            ));
+        }
+
+        let length = reader.seek(SeekFrom::End(0))?;
+        Ok(Daf {
+            reader: Mutex::new(reader),
+            length,
+            little_endian,
+            identifier,
+            internal_name,
+            summaries,
+        })
+    }
+
+    /// Returns the identification word, such as `DAF/SPK` or `DAF/PCK`.
+    pub fn identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    /// Returns the internal file name.
+    pub fn internal_name(&self) -> &str {
+        &self.internal_name
+    }
+
+    /// Returns the summaries of all arrays in file order.
+    pub fn summaries(&self) -> &[Summary] {
+        &self.summaries
+    }
+
+    /// Reads the double precision words between two one-based addresses, inclusive.
+    pub fn read_doubles(&self, start: usize, end: usize) -> Result<Vec<f64>, DafError> {
+        if start == 0 || end < start {
+            return Err(DafError::InvalidFile("invalid array address range"));
+        }
+        // The range comes from the file, so it is checked against its length before allocating
+        let offset = ((start - 1) as u64).checked_mul(WORD_SIZE as u64);
+        let length = ((end - start) as u64 + 1).checked_mul(WORD_SIZE as u64);
+        let (Some(offset), Some(length)) = (offset, length) else {
+            return Err(DafError::InvalidFile(
+                "array address beyond the end of the file",
+            ));
+        };
+        if offset
+            .checked_add(length)
+            .is_none_or(|end| end > self.length)
+        {
+            return Err(DafError::InvalidFile(
+                "array address beyond the end of the file",
+            ));
+        }
+
+        let mut bytes = vec![0; length as usize];
+        {
+            let mut reader = self
+                .reader
+                .lock()
+                .unwrap_or_else(|error| error.into_inner());
+            reader.seek(SeekFrom::Start(offset))?;
+            reader.read_exact(&mut bytes)?;
+        }
+
+        Ok(bytes
+            .chunks_exact(WORD_SIZE)
+            .map(|chunk| {
+                let chunk = chunk.try_into().unwrap();
+                if self.little_endian {
+                    f64::from_le_bytes(chunk)
+                } else {
+                    f64::from_be_bytes(chunk)
+                }
+            })
+            .collect())
+    }
+
+    /// Evaluates an array of fixed-length Chebyshev records, the layout of SPK and PCK types 2
+    /// and 3, at an epoch in seconds.
+    ///
+    /// Each record holds its midpoint and radius followed by the coefficients of each component,
+    /// and the array ends with the initial epoch, record length in seconds, record size and
+    /// record count. Without `with_rates` the records hold three components whose derivatives
+    /// are returned as rates; otherwise they hold six components, the last three being the rates
+    /// of the first three. Returns `None` if the array is inconsistent with this layout.
+    pub(crate) fn evaluate_chebyshev(
+        &self,
+        start_address: usize,
+        end_address: usize,
+        with_rates: bool,
+        epoch: f64,
+    ) -> Result<Option<[f64; 6]>, DafError> {
+        if end_address < start_address + 3 {
+            return Ok(None);
+        }
+        let directory = self.read_doubles(end_address - 3, end_address)?;
+        let [initial, interval, record_size, count] = directory[..] else {
+            return Ok(None);
+        };
+        let components = if with_rates { 6 } else { 3 };
+        // Written as a negation so that NaN words from a malformed file are rejected
+        let valid = initial.is_finite()
+            && interval > 0.0
+            && interval.is_finite()
+            && count >= 1.0
+            && count.is_finite()
+            && record_size >= (2 + components) as f64
+            && record_size <= (end_address - start_address - 3) as f64;
+        if !valid {
+            return Ok(None);
+        }
+        let record_size = record_size as usize;
+        let coefficient_count = (record_size - 2) / components;
+
+        let index =
+            (((epoch - initial) / interval).floor().max(0.0) as usize).min(count as usize - 1);
+        // The record size is below the array length, so only the offset of the record can overflow
+        let last = index
+            .checked_mul(record_size)
+            .and_then(|offset| offset.checked_add(start_address + record_size - 1));
+        let Some(last) = last.filter(|&last| last <= end_address - 4) else {
+            return Ok(None);
+        };
+        let record = self.read_doubles(last + 1 - record_size, last)?;
+        let (midpoint, radius) = (record[0], record[1]);
+        let s = (epoch - midpoint) / radius;
+
+        let mut values = [0.0; 6];
+        for component in 0..components {
+            let start = 2 + component * coefficient_count;
+            let (value, derivative) =
+                interpolation::chebyshev(&record[start..start + coefficient_count], s);
+            values[component] = value;
+            if !with_rates {
+                values[component + 3] = derivative / radius;
+            }
+        }
+
+        Ok(Some(values))
+    }
+}
+
+/// Reads the one-based physical record `number`.
+fn read_record<R: Read + Seek>(reader: &mut R, number: usize) -> Result<Vec<u8>, DafError> {
+    let mut record = vec![0; RECORD_SIZE];
+    reader.seek(SeekFrom::Start(((number - 1) * RECORD_SIZE) as u64))?;
+    reader.read_exact(&mut record)?;
+    Ok(record)
+}
+
+/// Decodes a blank-padded character field.
+fn text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches(['\0', ' '])
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Address of the first word of the array, at the start of the fourth record
+    const ARRAY: usize = 3 * RECORD_SIZE / WORD_SIZE + 1;
+
+    /// Builds a file with one summary record, one name record and an array of four words, in
+    /// the given byte order and with the given format word.
+    fn file(little_endian: bool, format: &[u8; 8]) -> Vec<u8> {
+        let integer = |value: i32| {
+            if little_endian {
+                value.to_le_bytes()
+            } else {
+                value.to_be_bytes()
+            }
+        };
+        let double = |value: f64| {
+            if little_endian {
+                value.to_le_bytes()
+            } else {
+                value.to_be_bytes()
+            }
+        };
+
+        let mut bytes = vec![0; 4 * RECORD_SIZE];
+        bytes[0..8].copy_from_slice(b"DAF/SPK ");
+        bytes[8..12].copy_from_slice(&integer(2));
+        bytes[12..16].copy_from_slice(&integer(6));
+        bytes[16..76].fill(b' ');
+        bytes[16..25].copy_from_slice(b"SYNTHETIC");
+        bytes[76..80].copy_from_slice(&integer(2));
+        bytes[80..84].copy_from_slice(&integer(2));
+        bytes[88..96].copy_from_slice(format);
+
+        let summary = RECORD_SIZE;
+        bytes[summary + 16..summary + 24].copy_from_slice(&double(1.0));
+        bytes[summary + 24..summary + 32].copy_from_slice(&double(-100.0));
+        bytes[summary + 32..summary + 40].copy_from_slice(&double(100.0));
+        let end = ARRAY as i32 + 3;
+        for (index, value) in [399, 3, 1, 2, ARRAY as i32, end].into_iter().enumerate() {
+            let offset = summary + 40 + 4 * index;
+            bytes[offset..offset + 4].copy_from_slice(&integer(value));
+        }
+        bytes[2 * RECORD_SIZE..2 * RECORD_SIZE + 40].fill(b' ');
+        bytes[2 * RECORD_SIZE..2 * RECORD_SIZE + 5].copy_from_slice(b"EARTH");
+        for (index, value) in [1.0, 2.0, 3.0, 4.0].into_iter().enumerate() {
+            let offset = 3 * RECORD_SIZE + 8 * index;
+            bytes[offset..offset + 8].copy_from_slice(&double(value));
+        }
+        bytes
+    }
+
+    fn open(bytes: Vec<u8>) -> Result<Daf<Cursor<Vec<u8>>>, DafError> {
+        Daf::from_reader(Cursor::new(bytes))
+    }
+
+    fn invalid(result: Result<Daf<Cursor<Vec<u8>>>, DafError>) -> &'static str {
+        match result {
+            Err(DafError::InvalidFile(reason)) => reason,
+            other => panic!("expected an invalid file, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn byte_order_test() {
+        // Files predating the format word have their byte order inferred from ND
+        for (little_endian, format) in [
+            (true, b"LTL-IEEE"),
+            (false, b"BIG-IEEE"),
+            (true, &[0; 8]),
+            (false, &[0; 8]),
+        ] {
+            let daf = open(file(little_endian, format)).unwrap();
+            assert_eq!(daf.little_endian, little_endian);
+            assert_eq!(daf.identifier(), "DAF/SPK");
+            assert_eq!(daf.internal_name(), "SYNTHETIC");
+            assert_eq!(
+                daf.summaries(),
+                [Summary {
+                    name: "EARTH".to_string(),
+                    doubles: vec![-100.0, 100.0],
+                    integers: vec![399, 3, 1, 2, ARRAY as i32, ARRAY as i32 + 3],
+                }]
+            );
+            assert_eq!(
+                daf.read_doubles(ARRAY, ARRAY + 3).unwrap(),
+                [1.0, 2.0, 3.0, 4.0]
+            );
+        }
+    }
+
+    #[test]
+    fn corrupt_file_test() {
+        let mut bytes = file(true, b"LTL-IEEE");
+        bytes[0..8].copy_from_slice(b"NAIF/DAS");
+        assert_eq!(invalid(open(bytes)), "missing DAF identification word");
+
+        let mut bytes = file(true, b"LTL-IEEE");
+        bytes[12..16].copy_from_slice(&1i32.to_le_bytes());
+        assert_eq!(invalid(open(bytes)), "invalid summary format");
+
+        let mut bytes = file(true, b"LTL-IEEE");
+        bytes[76..80].copy_from_slice(&(-2i32).to_le_bytes());
+        assert_eq!(invalid(open(bytes)), "invalid summary record pointer");
+
+        // A summary record pointing to itself
+        let mut bytes = file(true, b"LTL-IEEE");
+        bytes[RECORD_SIZE..RECORD_SIZE + 8].copy_from_slice(&2.0f64.to_le_bytes());
+        assert_eq!(
+            invalid(open(bytes)),
+            "summary record chain does not terminate"
+        );
+
+        for count in [1e20, -1.0, f64::NAN] {
+            let mut bytes = file(true, b"LTL-IEEE");
+            bytes[RECORD_SIZE + 16..RECORD_SIZE + 24].copy_from_slice(&count.to_le_bytes());
+            assert_eq!(invalid(open(bytes)), "too many summaries in record");
+        }
+
+        // A chain leading past the end of the file
+        let mut bytes = file(true, b"LTL-IEEE");
+        bytes[RECORD_SIZE..RECORD_SIZE + 8].copy_from_slice(&9.0f64.to_le_bytes());
+        assert!(matches!(open(bytes), Err(DafError::Io(_))));
+    }
+
+    #[test]
+    fn read_doubles_test() {
+        let daf = open(file(true, b"LTL-IEEE")).unwrap();
+        for (start, end) in [
+            (0, 1),
+            (ARRAY + 1, ARRAY),
+            (ARRAY, ARRAY + 128),
+            (ARRAY, usize::MAX),
+            (usize::MAX, usize::MAX),
+        ] {
+            assert!(
+                matches!(daf.read_doubles(start, end), Err(DafError::InvalidFile(_))),
+                "{start}..={end}"
+            );
+        }
+        assert_eq!(daf.read_doubles(ARRAY + 3, ARRAY + 3).unwrap(), [4.0]);
+    }
+}
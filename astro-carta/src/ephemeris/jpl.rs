@@ -0,0 +1,939 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use super::{Ephemeris, StateVector};
+use crate::astrometry::nutation::Nutation;
+use crate::constants::SECONDS_PER_DAY;
+use crate::datetime::{DateTime, TimeScale, J2000_JULIAN_DATE};
+use crate::math::{interpolation, Vector3};
+
+/// Identification word at the start of a binary cache
+const CACHE_MAGIC: &[u8; 8] = b"ACJPLEPH";
+
+/// Version of the binary cache layout
+const CACHE_VERSION: u32 = 1;
+
+/// Number of meters in a kilometer, the length unit of the JPL ephemerides
+const METERS_PER_KILOMETER: f64 = 1_000.0;
+
+/// Index of the geocentric Moon in the coefficient layout
+const MOON: usize = 9;
+
+/// Index of the Sun in the coefficient layout
+const SUN: usize = 10;
+
+/// Index of the nutations in the coefficient layout
+const NUTATIONS: usize = 11;
+
+/// Index of the lunar librations in the coefficient layout
+const LIBRATIONS: usize = 12;
+
+/// Index of TT − TDB at the geocenter in the coefficient layout
+const TT_MINUS_TDB: usize = 14;
+
+/// Error raised while reading or evaluating a JPL ASCII ephemeris
+#[derive(Debug)]
+pub enum JplError {
+    /// The underlying reader or writer failed
+    Io(io::Error),
+    /// The header file could not be parsed
+    InvalidHeader { line: usize },
+    /// A coefficient block could not be parsed
+    InvalidBlock { line: usize },
+    /// A coefficient block does not follow the blocks already loaded
+    Discontinuous { line: usize },
+    /// The binary cache is corrupt or of another version
+    InvalidCache,
+    /// No block covers the requested epoch
+    OutOfRange,
+    /// The ephemeris does not provide the body with the given NAIF identifier
+    UnknownBody(i32),
+    /// The ephemeris does not provide the named quantity
+    MissingItem(&'static str),
+}
+
+impl fmt::Display for JplError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JplError::Io(error) => write!(f, "I/O error: {error}"),
+            JplError::InvalidHeader { line } => write!(f, "line {line}: invalid header"),
+            JplError::InvalidBlock { line } => write!(f, "line {line}: invalid coefficient block"),
+            JplError::Discontinuous { line } => {
+                write!(f, "line {line}: block does not follow the loaded blocks")
+            }
+            JplError::InvalidCache => write!(f, "invalid ephemeris cache"),
+            JplError::OutOfRange => write!(f, "epoch outside the loaded blocks"),
+            JplError::UnknownBody(body) => write!(f, "body {body} not in the ephemeris"),
+            JplError::MissingItem(item) => write!(f, "{item} not in the ephemeris"),
+        }
+    }
+}
+
+impl std::error::Error for JplError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JplError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for JplError {
+    fn from(error: io::Error) -> Self {
+        JplError::Io(error)
+    }
+}
+
+/// Location of one quantity within a coefficient block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Item {
+    /// Zero-based index of the first coefficient in the block
+    offset: usize,
+    /// Number of Chebyshev coefficients per component
+    coefficients: usize,
+    /// Number of sub-intervals the block is divided into
+    subintervals: usize,
+}
+
+/// A JPL planetary and lunar ephemeris in the original ASCII distribution format
+///
+/// The ephemeris is built from a `header.4xx` file with [`JplEphemeris::parse_header`], then
+/// filled with one or more `ascpNNNN.4xx` files of coefficient blocks in chronological order
+/// with [`JplEphemeris::add_blocks`]. The loaded ephemeris can be saved to and restored from a
+/// compact binary cache, which avoids parsing the text again.
+///
+/// Positions are given by the [`Ephemeris`] trait for the solar system barycenter (0), the
+/// planetary system barycenters (1 to 9, with 199 and 299 for Mercury and Venus), the Sun (10),
+/// the Earth (399) and the Moon (301).
+///
+/// # Examples
+///
+/// ```no_run
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::ephemeris::jpl::JplEphemeris;
+/// use astro_carta::ephemeris::Ephemeris;
+///
+/// let mut de440 = JplEphemeris::parse_header(&std::fs::read_to_string("header.440").unwrap()).unwrap();
+/// de440.add_blocks(&std::fs::read_to_string("ascp02000.440").unwrap()).unwrap();
+///
+/// let dt = DateTime::gregorian(2024, 4, 8, 18, 0, 0.0).unwrap();
+/// let moon = de440.state(301, 399, &dt).unwrap();
+/// println!("{:.0} km", moon.position.norm() / 1e3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct JplEphemeris {
+    /// Title lines of the header
+    title: Vec<String>,
+    /// Number of coefficients in each block
+    block_size: usize,
+    /// Length of each block in days
+    interval: f64,
+    /// Julian date (TDB) of the start of the first loaded block
+    start: f64,
+    /// Named constants of the ephemeris
+    constants: Vec<(String, f64)>,
+    /// Layout of the quantities in each block
+    items: Vec<Item>,
+    /// Coefficients of the loaded blocks, concatenated
+    blocks: Vec<f64>,
+}
+
+impl JplEphemeris {
+    /// Parses a `header.4xx` file into an ephemeris without coefficient blocks.
+    pub fn parse_header(text: &str) -> Result<Self, JplError> {
+        let mut block_size = None;
+        let mut groups: Vec<(u32, Vec<(usize, &str)>)> = Vec::new();
+
+        for (index, content) in text.lines().enumerate() {
+            let line = index + 1;
+            let trimmed = content.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            if let Some(group) = trimmed.strip_prefix("GROUP") {
+                let number = group
+                    .trim()
+                    .parse()
+                    .map_err(|_| JplError::InvalidHeader { line })?;
+                groups.push((number, Vec::new()));
+            } else if let Some((_, group)) = groups.last_mut() {
+                group.push((line, trimmed));
+            } else if let Some(ncoeff) = trimmed.split("NCOEFF=").nth(1) {
+                block_size = Some(
+                    ncoeff
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|_| JplError::InvalidHeader { line })?,
+                );
+            }
+        }
+
+        let group = |number: u32| -> Result<&[(usize, &str)], JplError> {
+            groups
+                .iter()
+                .find(|(n, _)| *n == number)
+                .map(|(_, lines)| &lines[..])
+                .ok_or(JplError::InvalidHeader {
+                    line: text.lines().count(),
+                })
+        };
+        let tokens = |lines: &[(usize, &str)]| -> Vec<(usize, String)> {
+            lines
+                .iter()
+                .flat_map(|&(line, content)| {
+                    content
+                        .split_whitespace()
+                        .map(move |token| (line, token.to_string()))
+                })
+                .collect()
+        };
+
+        let title = group(1010)?
+            .iter()
+            .map(|(_, content)| content.to_string())
+            .collect();
+
+        let times = tokens(group(1030)?);
+        let interval = match &times[..] {
+            [_, _, (line, interval), ..] => {
+                parse_float(interval).ok_or(JplError::InvalidHeader { line: *line })?
+            }
+            _ => {
+                return Err(JplError::InvalidHeader {
+                    line: group_line(group(1030)?),
+                })
+            }
+        };
+        if interval <= 0.0 {
+            return Err(JplError::InvalidHeader {
+                line: group_line(group(1030)?),
+            });
+        }
+
+        let names = tokens(group(1040)?);
+        let values = tokens(group(1041)?);
+        let count = |tokens: &[(usize, String)]| -> Result<usize, JplError> {
+            let (line, first) = tokens.first().ok_or(JplError::InvalidHeader { line: 0 })?;
+            let count: usize = first
+                .parse()
+                .map_err(|_| JplError::InvalidHeader { line: *line })?;
+            if tokens.len() < count + 1 {
+                return Err(JplError::InvalidHeader { line: *line });
+            }
+            Ok(count)
+        };
+        let constant_count = count(&names)?;
+        if count(&values)? != constant_count {
+            return Err(JplError::InvalidHeader { line: values[0].0 });
+        }
+        let constants = names[1..=constant_count]
+            .iter()
+            .zip(&values[1..=constant_count])
+            .map(|((_, name), (line, value))| {
+                parse_float(value)
+                    .map(|value| (name.clone(), value))
+                    .ok_or(JplError::InvalidHeader { line: *line })
+            })
+            .collect::<Result<_, _>>()?;
+
+        let layout_lines = group(1050)?;
+        let layout = tokens(layout_lines)
+            .iter()
+            .map(|(line, token)| {
+                token
+                    .parse::<usize>()
+                    .map_err(|_| JplError::InvalidHeader { line: *line })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let item_count = layout.len() / 3;
+        if layout.len() % 3 != 0 || item_count <= SUN {
+            return Err(JplError::InvalidHeader {
+                line: group_line(layout_lines),
+            });
+        }
+        let block_size = block_size.ok_or(JplError::InvalidHeader { line: 1 })?;
+        let items: Vec<Item> = (0..item_count)
+            .map(|index| Item {
+                offset: layout[index].saturating_sub(1),
+                coefficients: layout[item_count + index],
+                subintervals: layout[2 * item_count + index],
+            })
+            .collect();
+
+        if !fits_blocks(&items, block_size) {
+            return Err(JplError::InvalidHeader {
+                line: group_line(layout_lines),
+            });
+        }
+
+        Ok(JplEphemeris {
+            title,
+            block_size,
+            interval,
+            start: 0.0,
+            constants,
+            items,
+            blocks: Vec::new(),
+        })
+    }
+
+    /// Parses an `ascpNNNN.4xx` file and appends its coefficient blocks.
+    ///
+    /// Blocks must follow on from those already loaded. A block repeating the last loaded one,
+    /// as happens at the boundary between consecutive files, is skipped.
+    pub fn add_blocks(&mut self, text: &str) -> Result<(), JplError> {
+        // Header line of the current block and its coefficients so far
+        let mut current: Option<(usize, Vec<f64>)> = None;
+
+        for (index, content) in text.lines().enumerate() {
+            let line = index + 1;
+            let tokens: Vec<&str> = content.split_whitespace().collect();
+            if tokens.is_empty() {
+                continue;
+            }
+
+            match &mut current {
+                None => {
+                    let [number, count] = tokens[..] else {
+                        return Err(JplError::InvalidBlock { line });
+                    };
+                    if number.parse::<u64>().is_err()
+                        || count.parse::<usize>() != Ok(self.block_size)
+                    {
+                        return Err(JplError::InvalidBlock { line });
+                    }
+                    current = Some((line, Vec::with_capacity(self.block_size)));
+                }
+                Some((header, coefficients)) => {
+                    for token in tokens {
+                        // Values beyond the block size pad the last line
+                        if coefficients.len() < self.block_size {
+                            coefficients
+                                .push(parse_float(token).ok_or(JplError::InvalidBlock { line })?);
+                        }
+                    }
+                    if coefficients.len() == self.block_size {
+                        let header = *header;
+                        let coefficients = std::mem::take(coefficients);
+                        self.push_block(coefficients, header)?;
+                        current = None;
+                    }
+                }
+            }
+        }
+
+        match current {
+            Some((line, _)) => Err(JplError::InvalidBlock { line }),
+            None => Ok(()),
+        }
+    }
+
+    fn push_block(&mut self, coefficients: Vec<f64>, line: usize) -> Result<(), JplError> {
+        let (start, end) = (coefficients[0], coefficients[1]);
+        if ((end - start) - self.interval).abs() > 1e-6 {
+            return Err(JplError::InvalidBlock { line });
+        }
+
+        if self.blocks.is_empty() {
+            self.start = start;
+        } else {
+            let index = ((start - self.start) / self.interval).round();
+            let count = self.block_count() as f64;
+            let aligned = (start - (self.start + index * self.interval)).abs() < 1e-6;
+            if index == count - 1.0 && aligned {
+                return Ok(());
+            }
+            if index != count || !aligned {
+                return Err(JplError::Discontinuous { line });
+            }
+        }
+
+        self.blocks.extend(coefficients);
+        Ok(())
+    }
+
+    /// Returns the title lines of the header.
+    pub fn title(&self) -> &[String] {
+        &self.title
+    }
+
+    /// Returns the value of a named constant, such as `AU`, `EMRAT` or `DENUM`.
+    pub fn constant(&self, name: &str) -> Option<f64> {
+        self.constants
+            .iter()
+            .find(|(constant, _)| constant == name)
+            .map(|&(_, value)| value)
+    }
+
+    /// Returns all named constants in header order.
+    pub fn constants(&self) -> &[(String, f64)] {
+        &self.constants
+    }
+
+    /// Returns the number of loaded coefficient blocks.
+    pub fn block_count(&self) -> usize {
+        self.blocks.len() / self.block_size
+    }
+
+    /// Returns the Julian dates (TDB) of the start and end of the loaded blocks, or `None` if
+    /// no block has been loaded.
+    pub fn coverage(&self) -> Option<(f64, f64)> {
+        (!self.blocks.is_empty()).then(|| {
+            (
+                self.start,
+                self.start + self.block_count() as f64 * self.interval,
+            )
+        })
+    }
+
+    /// Computes the IAU 1980 nutation angles given by the ephemeris at the given instant,
+    /// evaluated in TDB.
+    pub fn nutation(&self, dt: &DateTime) -> Result<Nutation, JplError> {
+        let (values, _) = self.evaluate_item(NUTATIONS, dt, "nutations")?;
+        Ok(Nutation {
+            longitude: values[0],
+            obliquity: values[1],
+        })
+    }
+
+    /// Computes the Euler angles (φ, θ, ψ) of the lunar mantle in radians and their rates in
+    /// rad/s at the given instant, evaluated in TDB.
+    pub fn librations(&self, dt: &DateTime) -> Result<(Vector3, Vector3), JplError> {
+        let (values, rates) = self.evaluate_item(LIBRATIONS, dt, "librations")?;
+        Ok((
+            Vector3::new(values[0], values[1], values[2]),
+            Vector3::new(rates[0], rates[1], rates[2]) / SECONDS_PER_DAY,
+        ))
+    }
+
+    /// Writes the ephemeris to a compact binary cache.
+    pub fn write_cache<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(CACHE_MAGIC)?;
+        write_u32(writer, CACHE_VERSION)?;
+
+        write_u32(writer, self.title.len() as u32)?;
+        for line in &self.title {
+            write_string(writer, line)?;
+        }
+
+        write_u32(writer, self.block_size as u32)?;
+        writer.write_all(&self.interval.to_le_bytes())?;
+        writer.write_all(&self.start.to_le_bytes())?;
+
+        write_u32(writer, self.constants.len() as u32)?;
+        for (name, value) in &self.constants {
+            write_string(writer, name)?;
+            writer.write_all(&value.to_le_bytes())?;
+        }
+
+        write_u32(writer, self.items.len() as u32)?;
+        for item in &self.items {
+            write_u32(writer, item.offset as u32)?;
+            write_u32(writer, item.coefficients as u32)?;
+            write_u32(writer, item.subintervals as u32)?;
+        }
+
+        write_u32(writer, self.block_count() as u32)?;
+        for value in &self.blocks {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Reads an ephemeris from a binary cache written by [`JplEphemeris::write_cache`].
+    pub fn read_cache<R: Read>(reader: &mut R) -> Result<Self, JplError> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != CACHE_MAGIC || read_u32(reader)? != CACHE_VERSION {
+            return Err(JplError::InvalidCache);
+        }
+
+        let title = (0..read_u32(reader)?)
+            .map(|_| read_string(reader))
+            .collect::<Result<_, _>>()?;
+
+        let block_size = read_u32(reader)? as usize;
+        let interval = read_f64(reader)?;
+        let start = read_f64(reader)?;
+
+        let constants = (0..read_u32(reader)?)
+            .map(|_| Ok((read_string(reader)?, read_f64(reader)?)))
+            .collect::<Result<_, JplError>>()?;
+
+        let items: Vec<Item> = (0..read_u32(reader)?)
+            .map(|_| {
+                Ok(Item {
+                    offset: read_u32(reader)? as usize,
+                    coefficients: read_u32(reader)? as usize,
+                    subintervals: read_u32(reader)? as usize,
+                })
+            })
+            .collect::<Result<_, JplError>>()?;
+        let valid_interval = interval > 0.0 && interval.is_finite() && start.is_finite();
+        if !fits_blocks(&items, block_size) || items.len() <= SUN || !valid_interval {
+            return Err(JplError::InvalidCache);
+        }
+
+        // The coefficients are read as they come, so that a corrupt count cannot allocate more
+        // than the cache holds
+        let block_count = read_u32(reader)? as usize;
+        let length = block_count
+            .checked_mul(block_size)
+            .and_then(|words| words.checked_mul(8))
+            .ok_or(JplError::InvalidCache)?;
+        let bytes = read_bytes(reader, length)?;
+        let blocks = bytes
+            .chunks_exact(8)
+            .map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+
+        Ok(JplEphemeris {
+            title,
+            block_size,
+            interval,
+            start,
+            constants,
+            items,
+            blocks,
+        })
+    }
+
+    /// Evaluates the components of an item and their rates per day.
+    fn evaluate_item(
+        &self,
+        index: usize,
+        dt: &DateTime,
+        name: &'static str,
+    ) -> Result<(Vec<f64>, Vec<f64>), JplError> {
+        let item = match self.items.get(index) {
+            Some(item) if item.coefficients > 0 => *item,
+            _ => return Err(JplError::MissingItem(name)),
+        };
+        let (start, end) = self.coverage().ok_or(JplError::OutOfRange)?;
+
+        // Days relative to J2000 keep the precision lost in full Julian dates
+        let days = dt.days_since_j2000(TimeScale::TDB);
+        let offset = days - (start - J2000_JULIAN_DATE);
+        if offset < 0.0 || days > end - J2000_JULIAN_DATE {
+            return Err(JplError::OutOfRange);
+        }
+        let block_index = ((offset / self.interval) as usize).min(self.block_count() - 1);
+        let block =
+            &self.blocks[block_index * self.block_size..(block_index + 1) * self.block_size];
+
+        let length = self.interval / item.subintervals as f64;
+        let elapsed = days - (block[0] - J2000_JULIAN_DATE);
+        let subinterval = ((elapsed / length).max(0.0) as usize).min(item.subintervals - 1);
+        let x = 2.0 * (elapsed - subinterval as f64 * length) / length - 1.0;
+
+        let count = components(index);
+        let mut values = Vec::with_capacity(count);
+        let mut rates = Vec::with_capacity(count);
+        for component in 0..count {
+            let first = item.offset + (subinterval * count + component) * item.coefficients;
+            let (value, derivative) =
+                interpolation::chebyshev(&block[first..first + item.coefficients], x);
+            values.push(value);
+            rates.push(derivative * 2.0 / length);
+        }
+
+        Ok((values, rates))
+    }
+
+    /// Evaluates the state of an item holding a position in km, converted to meters and m/s.
+    fn item_state(&self, index: usize, dt: &DateTime) -> Result<StateVector, JplError> {
+        let (values, rates) = self.evaluate_item(index, dt, "body")?;
+        Ok(StateVector {
+            position: Vector3::new(values[0], values[1], values[2]) * METERS_PER_KILOMETER,
+            velocity: Vector3::new(rates[0], rates[1], rates[2])
+                * (METERS_PER_KILOMETER / SECONDS_PER_DAY),
+        })
+    }
+
+    /// Computes the state of a body relative to the solar system barycenter.
+    fn barycentric_state(&self, body: i32, dt: &DateTime) -> Result<StateVector, JplError> {
+        let item = match body {
+            0 => {
+                return Ok(StateVector {
+                    position: Vector3::zeros(),
+                    velocity: Vector3::zeros(),
+                })
+            }
+            199 => 0,
+            299 => 1,
+            1..=9 => body as usize - 1,
+            10 => SUN,
+            399 | 301 => {
+                let emb = self.item_state(2, dt)?;
+                let moon = self.item_state(MOON, dt)?;
+                let emrat = self
+                    .constant("EMRAT")
+                    .ok_or(JplError::MissingItem("EMRAT"))?;
+                let earth = StateVector {
+                    position: emb.position - moon.position / (1.0 + emrat),
+                    velocity: emb.velocity - moon.velocity / (1.0 + emrat),
+                };
+                return Ok(if body == 399 { earth } else { earth + moon });
+            }
+            _ => return Err(JplError::UnknownBody(body)),
+        };
+        self.item_state(item, dt)
+    }
+}
+
+impl Ephemeris for JplEphemeris {
+    type Error = JplError;
+
+    fn state(&self, target: i32, center: i32, dt: &DateTime) -> Result<StateVector, JplError> {
+        Ok(self.barycentric_state(target, dt)? - self.barycentric_state(center, dt)?)
+    }
+}
+
+/// Returns `true` if every item present has subintervals and its coefficients end within a
+/// block of the given number of words, of which the first two hold the dates of the block.
+fn fits_blocks(items: &[Item], block_size: usize) -> bool {
+    block_size >= 2
+        && items.iter().enumerate().all(|(index, item)| {
+            item.coefficients == 0
+                || (item.subintervals > 0
+                    && item
+                        .coefficients
+                        .checked_mul(components(index) * item.subintervals)
+                        .and_then(|words| words.checked_add(item.offset))
+                        .is_some_and(|end| end <= block_size))
+        })
+}
+
+/// Returns the number of components of the item at the given index of the layout.
+fn components(index: usize) -> usize {
+    match index {
+        NUTATIONS => 2,
+        TT_MINUS_TDB => 1,
+        _ => 3,
+    }
+}
+
+/// Parses a Fortran floating point number, which may use `D` as the exponent marker.
+fn parse_float(token: &str) -> Option<f64> {
+    token.replace(['D', 'd'], "E").parse().ok()
+}
+
+/// Returns the line number of the first line of a header group, for error reporting.
+fn group_line(lines: &[(usize, &str)]) -> usize {
+    lines.first().map_or(0, |&(line, _)| line)
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_string<W: Write>(writer: &mut W, value: &str) -> io::Result<()> {
+    write_u32(writer, value.len() as u32)?;
+    writer.write_all(value.as_bytes())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, JplError> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_f64<R: Read>(reader: &mut R) -> Result<f64, JplError> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(f64::from_le_bytes(bytes))
+}
+
+fn read_string<R: Read>(reader: &mut R) -> Result<String, JplError> {
+    let length = read_u32(reader)? as usize;
+    String::from_utf8(read_bytes(reader, length)?).map_err(|_| JplError::InvalidCache)
+}
+
+/// Reads exactly `length` bytes, growing the buffer only as the bytes arrive.
+fn read_bytes<R: Read>(reader: &mut R, length: usize) -> Result<Vec<u8>, JplError> {
+    let mut bytes = Vec::new();
+    reader.take(length as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != length {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "\
+KSIZE=  250    NCOEFF=  125
+
+GROUP   1010
+
+JPL Planetary Ephemeris DE999/LE999
+Start Epoch: JED=  2451536.5 1999 DEC 24 00:00:00
+Final Epoch: JED=  2451632.5 2000 MAR 29 00:00:00
+
+GROUP   1030
+
+  2451536.50  2451632.50         32.
+
+GROUP   1040
+
+     3
+  DENUM   EMRAT   AU
+
+GROUP   1041
+
+     3
+  0.999000000000000000D+03  0.813005682214972154D+02  0.149597870700000000D+09
+
+GROUP   1050
+
+     3    12    21    30    39    48    57    66    75    84   102   111   117   126   126
+     3     3     3     3     3     3     3     3     3     3     3     3     3     0     0
+     1     1     1     1     1     1     1     1     1     2     1     1     1     0     0
+
+GROUP   1070
+
+";
+
+    /// Linear Chebyshev coefficients `(c0, c1)` of a component in the synthetic blocks.
+    fn linear(block: usize, item: usize, component: usize, subinterval: usize) -> (f64, f64) {
+        (
+            1000.0 * (item + 1) as f64
+                + 10.0 * component as f64
+                + subinterval as f64
+                + 0.5 * block as f64,
+            0.25 * ((item + 1) * (component + 1)) as f64,
+        )
+    }
+
+    /// Formats blocks of the synthetic ephemeris as an ascp file.
+    fn blocks(range: std::ops::Range<usize>) -> String {
+        let layout = [(3, 1); 9]
+            .into_iter()
+            .chain([(3, 2), (3, 1), (2, 1), (3, 1)]);
+        let mut text = String::new();
+        for block in range {
+            let start = 2_451_536.5 + 32.0 * block as f64;
+            let mut values = vec![start, start + 32.0];
+            for (item, (components, subintervals)) in layout.clone().enumerate() {
+                for subinterval in 0..subintervals {
+                    for component in 0..components {
+                        let (c0, c1) = linear(block, item, component, subinterval);
+                        values.extend([c0, c1, 0.0]);
+                    }
+                }
+            }
+            values.push(0.0);
+
+            text += &format!("{:6}{:6}\n", block + 1, 125);
+            for chunk in values.chunks(3) {
+                let line: Vec<String> = chunk
+                    .iter()
+                    .map(|v| format!("{v:26.18E}").replace('E', "D"))
+                    .collect();
+                text += &format!("{}\n", line.join(""));
+            }
+        }
+        text
+    }
+
+    fn ephemeris() -> JplEphemeris {
+        let mut ephemeris = JplEphemeris::parse_header(HEADER).unwrap();
+        ephemeris.add_blocks(&blocks(0..2)).unwrap();
+        ephemeris.add_blocks(&blocks(1..3)).unwrap();
+        ephemeris
+    }
+
+    fn tdb(julian_date: f64) -> DateTime {
+        DateTime::from_julian_date(julian_date, TimeScale::TDB)
+    }
+
+    #[test]
+    fn header_test() {
+        let ephemeris = JplEphemeris::parse_header(HEADER).unwrap();
+        assert_eq!(ephemeris.title()[0], "JPL Planetary Ephemeris DE999/LE999");
+        assert_eq!(ephemeris.constant("DENUM"), Some(999.0));
+        assert_eq!(ephemeris.constant("AU"), Some(149_597_870.7));
+        assert_eq!(ephemeris.constants().len(), 3);
+        assert_eq!(ephemeris.items.len(), 15);
+        assert_eq!(
+            ephemeris.items[MOON],
+            Item {
+                offset: 83,
+                coefficients: 3,
+                subintervals: 2
+            }
+        );
+        assert_eq!(ephemeris.coverage(), None);
+
+        assert!(matches!(
+            JplEphemeris::parse_header(&HEADER.replace("    21    30", "    21    3x")),
+            Err(JplError::InvalidHeader { line: 25 })
+        ));
+        assert!(matches!(
+            JplEphemeris::parse_header(&HEADER.replace("NCOEFF=  125", "NCOEFF=  100")),
+            Err(JplError::InvalidHeader { line: 25 })
+        ));
+    }
+
+    #[test]
+    fn blocks_test() {
+        let mut ephemeris = ephemeris();
+        assert_eq!(ephemeris.block_count(), 3);
+        assert_eq!(ephemeris.coverage(), Some((2_451_536.5, 2_451_632.5)));
+
+        // Blocks must be contiguous
+        assert!(matches!(
+            ephemeris.add_blocks(&blocks(4..5)),
+            Err(JplError::Discontinuous { line: 1 })
+        ));
+        let truncated: String = blocks(3..4).lines().take(10).collect::<Vec<_>>().join("\n");
+        assert!(matches!(
+            ephemeris.add_blocks(&truncated),
+            Err(JplError::InvalidBlock { line: 1 })
+        ));
+        let corrupt = blocks(3..4).replacen('D', "X", 1);
+        assert!(matches!(
+            ephemeris.add_blocks(&corrupt),
+            Err(JplError::InvalidBlock { line: 2 })
+        ));
+    }
+
+    #[test]
+    fn state_test() {
+        let ephemeris = ephemeris();
+
+        // Mars barycenter a quarter into the second block
+        let x = -0.5;
+        let state = ephemeris.state(4, 0, &tdb(2_451_568.5 + 8.0)).unwrap();
+        for component in 0..3 {
+            let (c0, c1) = linear(1, 3, component, 0);
+            assert!((state.position[component] - (c0 + c1 * x) * 1e3).abs() < 1e-6);
+            assert!((state.velocity[component] - c1 * 2.0 / 32.0 * 1e3 / 86_400.0).abs() < 1e-12);
+        }
+
+        // The Moon uses two sub-intervals per block
+        let dt = tdb(2_451_600.5 + 28.0);
+        let moon = ephemeris.state(301, 399, &dt).unwrap();
+        let (c0, c1) = linear(2, MOON, 1, 1);
+        assert!((moon.position.y - (c0 + c1 * 0.5) * 1e3).abs() < 1e-6);
+
+        // The Earth and Moon are placed about their barycenter by the mass ratio
+        let emrat = ephemeris.constant("EMRAT").unwrap();
+        let earth = ephemeris.state(399, 3, &dt).unwrap();
+        let barycenter = earth.position * emrat + ephemeris.state(301, 3, &dt).unwrap().position;
+        assert!(barycenter.norm() < 1e-3);
+        assert!((earth.position + moon.position / (1.0 + emrat)).norm() < 1e-6);
+
+        // The state relative to the center is a difference of barycentric states
+        let sun = ephemeris.state(10, 0, &dt).unwrap();
+        let venus = ephemeris.state(299, 0, &dt).unwrap();
+        assert_eq!(ephemeris.state(299, 10, &dt).unwrap(), venus - sun);
+        assert_eq!(ephemeris.state(2, 0, &dt).unwrap(), venus);
+        assert_eq!(ephemeris.position(0, 0, &dt).unwrap(), Vector3::zeros());
+
+        assert!(matches!(
+            ephemeris.state(499, 0, &dt),
+            Err(JplError::UnknownBody(499))
+        ));
+        assert!(matches!(
+            ephemeris.state(4, 0, &tdb(2_451_632.6)),
+            Err(JplError::OutOfRange)
+        ));
+        assert!(ephemeris.state(4, 0, &tdb(2_451_632.5)).is_ok());
+    }
+
+    #[test]
+    fn nutation_libration_test() {
+        let ephemeris = ephemeris();
+        let dt = tdb(2_451_536.5 + 24.0);
+
+        let nutation = ephemeris.nutation(&dt).unwrap();
+        assert!(
+            (nutation.longitude
+                - (linear(0, NUTATIONS, 0, 0).0 + linear(0, NUTATIONS, 0, 0).1 * 0.5))
+                .abs()
+                < 1e-9
+        );
+        assert!(
+            (nutation.obliquity
+                - (linear(0, NUTATIONS, 1, 0).0 + linear(0, NUTATIONS, 1, 0).1 * 0.5))
+                .abs()
+                < 1e-9
+        );
+
+        let (angles, rates) = ephemeris.librations(&dt).unwrap();
+        let (c0, c1) = linear(0, LIBRATIONS, 2, 0);
+        assert!((angles.z - (c0 + c1 * 0.5)).abs() < 1e-9);
+        assert!((rates.z - c1 / 16.0 / 86_400.0).abs() < 1e-15);
+
+        let mut without = ephemeris.clone();
+        without.items[NUTATIONS].coefficients = 0;
+        assert!(matches!(
+            without.nutation(&dt),
+            Err(JplError::MissingItem("nutations"))
+        ));
+    }
+
+    #[test]
+    fn cache_test() {
+        let ephemeris = ephemeris();
+        let mut cache = Vec::new();
+        ephemeris.write_cache(&mut cache).unwrap();
+        assert_eq!(
+            JplEphemeris::read_cache(&mut &cache[..]).unwrap(),
+            ephemeris
+        );
+
+        // The cache is close to the size of the raw coefficients
+        assert!(cache.len() < 3 * 125 * 8 + 512);
+
+        assert!(matches!(
+            JplEphemeris::read_cache(&mut &cache[..10]),
+            Err(JplError::Io(_))
+        ));
+        let truncated = &cache[..cache.len() - 8];
+        assert!(matches!(
+            JplEphemeris::read_cache(&mut &truncated[..]),
+            Err(JplError::Io(_))
+        ));
+
+        // Items overflowing the blocks or without subintervals, as in a corrupt cache
+        let corrupt = |change: &dyn Fn(&mut JplEphemeris)| {
+            let mut ephemeris = ephemeris.clone();
+            change(&mut ephemeris);
+            let mut cache = Vec::new();
+            ephemeris.write_cache(&mut cache).unwrap();
+            JplEphemeris::read_cache(&mut &cache[..])
+        };
+        assert!(matches!(
+            corrupt(&|ephemeris| ephemeris.items[0].subintervals = 0),
+            Err(JplError::InvalidCache)
+        ));
+        assert!(matches!(
+            corrupt(&|ephemeris| ephemeris.items[3].offset = ephemeris.block_size),
+            Err(JplError::InvalidCache)
+        ));
+        assert!(matches!(
+            corrupt(&|ephemeris| ephemeris.block_size = 1),
+            Err(JplError::InvalidCache)
+        ));
+
+        // A block count far beyond the cache fails without allocating it
+        let count = cache.len() - 3 * 125 * 8 - 4;
+        let mut huge = cache.clone();
+        huge[count..count + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            JplEphemeris::read_cache(&mut &huge[..]),
+            Err(JplError::Io(_))
+        ));
+
+        cache[0] = b'X';
+        assert!(matches!(
+            JplEphemeris::read_cache(&mut &cache[..]),
+            Err(JplError::InvalidCache)
+        ));
+    }
+}
@@ -0,0 +1,282 @@
+use super::GeocentricPosition;
+use crate::astrometry::nutation;
+use crate::coordinates::Ecliptic;
+use crate::datetime::{DateTime, TimeScale};
+use crate::math;
+
+/// Mean distance of the Moon in the truncated theory in meters
+const MEAN_DISTANCE: f64 = 385_000_560.0;
+
+/// Periodic terms for the longitude and distance of the Moon (Meeus, Table 47.A).
+///
+/// Each row holds the multiples of the arguments (D, M, M', F), followed by the coefficient of
+/// the sine series for the longitude in 1e-6 degrees and of the cosine series for the distance
+/// in meters.
+#[rustfmt::skip]
+const LONGITUDE_DISTANCE_TERMS: [([i8; 4], f64, f64); 60] = [
+    ([0,  0,  1,  0],  6_288_774.0, -20_905_355.0),
+    ([2,  0, -1,  0],  1_274_027.0,  -3_699_111.0),
+    ([2,  0,  0,  0],    658_314.0,  -2_955_968.0),
+    ([0,  0,  2,  0],    213_618.0,    -569_925.0),
+    ([0,  1,  0,  0],   -185_116.0,      48_888.0),
+    ([0,  0,  0,  2],   -114_332.0,      -3_149.0),
+    ([2,  0, -2,  0],     58_793.0,     246_158.0),
+    ([2, -1, -1,  0],     57_066.0,    -152_138.0),
+    ([2,  0,  1,  0],     53_322.0,    -170_733.0),
+    ([2, -1,  0,  0],     45_758.0,    -204_586.0),
+    ([0,  1, -1,  0],    -40_923.0,    -129_620.0),
+    ([1,  0,  0,  0],    -34_720.0,     108_743.0),
+    ([0,  1,  1,  0],    -30_383.0,     104_755.0),
+    ([2,  0,  0, -2],     15_327.0,      10_321.0),
+    ([0,  0,  1,  2],    -12_528.0,           0.0),
+    ([0,  0,  1, -2],     10_980.0,      79_661.0),
+    ([4,  0, -1,  0],     10_675.0,     -34_782.0),
+    ([0,  0,  3,  0],     10_034.0,     -23_210.0),
+    ([4,  0, -2,  0],      8_548.0,     -21_636.0),
+    ([2,  1, -1,  0],     -7_888.0,      24_208.0),
+    ([2,  1,  0,  0],     -6_766.0,      30_824.0),
+    ([1,  0, -1,  0],     -5_163.0,      -8_379.0),
+    ([1,  1,  0,  0],      4_987.0,     -16_675.0),
+    ([2, -1,  1,  0],      4_036.0,     -12_831.0),
+    ([2,  0,  2,  0],      3_994.0,     -10_445.0),
+    ([4,  0,  0,  0],      3_861.0,     -11_650.0),
+    ([2,  0, -3,  0],      3_665.0,      14_403.0),
+    ([0,  1, -2,  0],     -2_689.0,      -7_003.0),
+    ([2,  0, -1,  2],     -2_602.0,           0.0),
+    ([2, -1, -2,  0],      2_390.0,      10_056.0),
+    ([1,  0,  1,  0],     -2_348.0,       6_322.0),
+    ([2, -2,  0,  0],      2_236.0,      -9_884.0),
+    ([0,  1,  2,  0],     -2_120.0,       5_751.0),
+    ([0,  2,  0,  0],     -2_069.0,           0.0),
+    ([2, -2, -1,  0],      2_048.0,      -4_950.0),
+    ([2,  0,  1, -2],     -1_773.0,       4_130.0),
+    ([2,  0,  0,  2],     -1_595.0,           0.0),
+    ([4, -1, -1,  0],      1_215.0,      -3_958.0),
+    ([0,  0,  2,  2],     -1_110.0,           0.0),
+    ([3,  0, -1,  0],       -892.0,       3_258.0),
+    ([2,  1,  1,  0],       -810.0,       2_616.0),
+    ([4, -1, -2,  0],        759.0,      -1_897.0),
+    ([0,  2, -1,  0],       -713.0,      -2_117.0),
+    ([2,  2, -1,  0],       -700.0,       2_354.0),
+    ([2,  1, -2,  0],        691.0,           0.0),
+    ([2, -1,  0, -2],        596.0,           0.0),
+    ([4,  0,  1,  0],        549.0,      -1_423.0),
+    ([0,  0,  4,  0],        537.0,      -1_117.0),
+    ([4, -1,  0,  0],        520.0,      -1_571.0),
+    ([1,  0, -2,  0],       -487.0,      -1_739.0),
+    ([2,  1,  0, -2],       -399.0,           0.0),
+    ([0,  0,  2, -2],       -381.0,      -4_421.0),
+    ([1,  1,  1,  0],        351.0,           0.0),
+    ([3,  0, -2,  0],       -340.0,           0.0),
+    ([4,  0, -3,  0],        330.0,           0.0),
+    ([2, -1,  2,  0],        327.0,           0.0),
+    ([0,  2,  1,  0],       -323.0,       1_165.0),
+    ([1,  1, -1,  0],        299.0,           0.0),
+    ([2,  0,  3,  0],        294.0,           0.0),
+    ([2,  0, -1, -2],          0.0,       8_752.0),
+];
+
+/// Periodic terms for the latitude of the Moon (Meeus, Table 47.B).
+///
+/// Each row holds the multiples of the arguments (D, M, M', F), followed by the coefficient of
+/// the sine series in 1e-6 degrees.
+#[rustfmt::skip]
+const LATITUDE_TERMS: [([i8; 4], f64); 60] = [
+    ([0,  0,  0,  1], 5_128_122.0),
+    ([0,  0,  1,  1],   280_602.0),
+    ([0,  0,  1, -1],   277_693.0),
+    ([2,  0,  0, -1],   173_237.0),
+    ([2,  0, -1,  1],    55_413.0),
+    ([2,  0, -1, -1],    46_271.0),
+    ([2,  0,  0,  1],    32_573.0),
+    ([0,  0,  2,  1],    17_198.0),
+    ([2,  0,  1, -1],     9_266.0),
+    ([0,  0,  2, -1],     8_822.0),
+    ([2, -1,  0, -1],     8_216.0),
+    ([2,  0, -2, -1],     4_324.0),
+    ([2,  0,  1,  1],     4_200.0),
+    ([2,  1,  0, -1],    -3_359.0),
+    ([2, -1, -1,  1],     2_463.0),
+    ([2, -1,  0,  1],     2_211.0),
+    ([2, -1, -1, -1],     2_065.0),
+    ([0,  1, -1, -1],    -1_870.0),
+    ([4,  0, -1, -1],     1_828.0),
+    ([0,  1,  0,  1],    -1_794.0),
+    ([0,  0,  0,  3],    -1_749.0),
+    ([0,  1, -1,  1],    -1_565.0),
+    ([1,  0,  0,  1],    -1_491.0),
+    ([0,  1,  1,  1],    -1_475.0),
+    ([0,  1,  1, -1],    -1_410.0),
+    ([0,  1,  0, -1],    -1_344.0),
+    ([1,  0,  0, -1],    -1_335.0),
+    ([0,  0,  3,  1],     1_107.0),
+    ([4,  0,  0, -1],     1_021.0),
+    ([4,  0, -1,  1],       833.0),
+    ([0,  0,  1, -3],       777.0),
+    ([4,  0, -2,  1],       671.0),
+    ([2,  0,  0, -3],       607.0),
+    ([2,  0,  2, -1],       596.0),
+    ([2, -1,  1, -1],       491.0),
+    ([2,  0, -2,  1],      -451.0),
+    ([0,  0,  3, -1],       439.0),
+    ([2,  0,  2,  1],       422.0),
+    ([2,  0, -3, -1],       421.0),
+    ([2,  1, -1,  1],      -366.0),
+    ([2,  1,  0,  1],      -351.0),
+    ([4,  0,  0,  1],       331.0),
+    ([2, -1,  1,  1],       315.0),
+    ([2, -2,  0, -1],       302.0),
+    ([0,  0,  1,  3],      -283.0),
+    ([2,  1,  1, -1],      -229.0),
+    ([1,  1,  0, -1],       223.0),
+    ([1,  1,  0,  1],       223.0),
+    ([0,  1, -2, -1],      -220.0),
+    ([2,  1, -1, -1],      -220.0),
+    ([1,  0,  1,  1],      -185.0),
+    ([2, -1, -2, -1],       181.0),
+    ([0,  1,  2,  1],      -177.0),
+    ([4,  0, -2, -1],       176.0),
+    ([4, -1, -1, -1],       166.0),
+    ([1,  0,  1, -1],      -164.0),
+    ([4,  0,  1, -1],       132.0),
+    ([1,  0, -1, -1],      -119.0),
+    ([4, -1,  0, -1],       115.0),
+    ([2, -2,  0,  1],       107.0),
+];
+
+/// Geometric position of the Moon referred to the mean equinox of date
+struct MeanPosition {
+    /// Ecliptic longitude in radians
+    longitude: f64,
+    /// Ecliptic latitude in radians
+    latitude: f64,
+    /// Distance between the centers of the Earth and Moon in meters
+    distance: f64,
+}
+
+/// Evaluates the truncated lunar theory for a number of Julian centuries of TT since J2000.0.
+fn mean_position(t: f64) -> MeanPosition {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let t4 = t3 * t;
+
+    // Mean longitude of the Moon and the Delaunay arguments in degrees (Meeus 47.1 - 47.5)
+    let mean_longitude = 218.316_447_7 + 481_267.881_234_21 * t - 0.001_578_6 * t2 + t3 / 538_841.0
+        - t4 / 65_194_000.0;
+    let elongation = 297.850_192_1 + 445_267.111_403_4 * t - 0.001_881_9 * t2 + t3 / 545_868.0
+        - t4 / 113_065_000.0;
+    let sun_anomaly = 357.529_109_2 + 35_999.050_290_9 * t - 0.000_153_6 * t2 + t3 / 24_490_000.0;
+    let moon_anomaly = 134.963_396_4 + 477_198.867_505_5 * t + 0.008_741_4 * t2 + t3 / 69_699.0
+        - t4 / 14_712_000.0;
+    let latitude_argument =
+        93.272_095_0 + 483_202.017_523_3 * t - 0.003_653_9 * t2 - t3 / 3_526_000.0
+            + t4 / 863_310_000.0;
+
+    // Planetary arguments: Venus, Jupiter and the flattening of the Earth
+    let a1 = (119.75 + 131.849 * t).to_radians();
+    let a2 = (53.09 + 479_264.290 * t).to_radians();
+    let a3 = (313.45 + 481_266.484 * t).to_radians();
+
+    // Decreasing eccentricity of the Earth's orbit
+    let e = 1.0 - 0.002_516 * t - 0.000_007_4 * t2;
+
+    let arguments = [
+        elongation.to_radians(),
+        sun_anomaly.to_radians(),
+        moon_anomaly.to_radians(),
+        latitude_argument.to_radians(),
+    ];
+    let argument = |multiples: &[i8; 4]| -> (f64, f64) {
+        let angle = multiples
+            .iter()
+            .zip(arguments.iter())
+            .map(|(&k, &x)| f64::from(k) * x)
+            .sum::<f64>();
+        (angle, e.powi(i32::from(multiples[1].abs())))
+    };
+
+    let mut sum_l = 0.0;
+    let mut sum_r = 0.0;
+    for (multiples, l, r) in LONGITUDE_DISTANCE_TERMS.iter() {
+        let (angle, factor) = argument(multiples);
+        sum_l += l * factor * angle.sin();
+        sum_r += r * factor * angle.cos();
+    }
+
+    let mut sum_b = 0.0;
+    for (multiples, b) in LATITUDE_TERMS.iter() {
+        let (angle, factor) = argument(multiples);
+        sum_b += b * factor * angle.sin();
+    }
+
+    let l = mean_longitude.to_radians();
+    let f = arguments[3];
+    let m = arguments[2];
+    sum_l += 3_958.0 * a1.sin() + 1_962.0 * (l - f).sin() + 318.0 * a2.sin();
+    sum_b += -2_235.0 * l.sin()
+        + 382.0 * a3.sin()
+        + 175.0 * (a1 - f).sin()
+        + 175.0 * (a1 + f).sin()
+        + 127.0 * (l - m).sin()
+        - 115.0 * (l + m).sin();
+
+    MeanPosition {
+        longitude: (mean_longitude + sum_l * 1e-6).to_radians(),
+        latitude: (sum_b * 1e-6).to_radians(),
+        distance: MEAN_DISTANCE + sum_r,
+    }
+}
+
+/// Computes the geocentric position of the Moon with the truncated ELP-2000/82 theory of
+/// Meeus (Astronomical Algorithms, chapter 47).
+///
+/// The coordinates are apparent, including nutation and referred to the true equinox and
+/// equator of date; light time (about 0.7") is neglected. The accuracy of the underlying
+/// theory is about 10" in longitude, 4" in latitude and 10 km in distance over several
+/// centuries around the present.
+///
+/// # Arguments
+///
+/// * `dt` - The instant, evaluated in TT.
+pub fn position(dt: &DateTime) -> GeocentricPosition {
+    let mean = mean_position(dt.centuries_since_j2000(TimeScale::TT));
+    let nutation = nutation::nutation(dt);
+
+    let ecliptic = Ecliptic::new(
+        math::normalize_angle(mean.longitude + nutation.longitude),
+        mean.latitude,
+    );
+    GeocentricPosition {
+        ecliptic,
+        equatorial: ecliptic.to_equatorial(nutation::true_obliquity(dt)),
+        distance: mean.distance,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meeus_example_47a_test() {
+        // Meeus, Astronomical Algorithms, Example 47.a: 1992 April 12, 0h TD
+        let dt = DateTime::from_julian_date(2_448_724.5, TimeScale::TT);
+
+        let mean = mean_position(dt.centuries_since_j2000(TimeScale::TT));
+        assert!((math::normalize_angle(mean.longitude).to_degrees() - 133.162_655).abs() < 1e-6);
+        assert!((mean.latitude.to_degrees() + 3.229_126).abs() < 1e-6);
+        assert!((mean.distance - 368_409_700.0).abs() < 100.0);
+
+        let moon = position(&dt);
+        assert!((moon.ecliptic.longitude.to_degrees() - 133.167_265).abs() < 1e-5);
+        assert!((moon.equatorial.right_ascension.to_degrees() - 134.688_470).abs() < 1e-5);
+        assert!((moon.equatorial.declination.to_degrees() - 13.768_368).abs() < 1e-5);
+    }
+
+    #[test]
+    fn perigee_test() {
+        // The Moon was at perigee at 356 509 km on 2016 November 14, 11:22 UTC
+        let moon = position(&DateTime::gregorian(2016, 11, 14, 11, 22, 0.0).unwrap());
+        assert!((moon.distance - 356_509_000.0).abs() < 10_000.0);
+    }
+}
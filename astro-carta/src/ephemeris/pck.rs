@@ -0,0 +1,714 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+
+use super::daf::{Daf, DafError};
+use crate::astrometry::precession::OBLIQUITY_J2000;
+use crate::constants::SECONDS_PER_DAY;
+use crate::datetime::{DateTime, TimeScale, DAYS_PER_JULIAN_CENTURY};
+use crate::math::{self, Matrix3, Vector3};
+
+/// NAIF identifier of the J2000 inertial frame
+const J2000: i32 = 1;
+
+/// NAIF identifier of the ecliptic and equinox of J2000 frame
+const ECLIPJ2000: i32 = 17;
+
+/// Number of meters in a kilometer, the length unit of PCK radii
+const METERS_PER_KILOMETER: f64 = 1_000.0;
+
+/// Error raised while reading or evaluating a PCK file
+#[derive(Debug)]
+pub enum PckError {
+    /// The DAF container of a binary PCK could not be read
+    Daf(DafError),
+    /// The file is a DAF file but not a binary PCK
+    NotPck,
+    /// A text kernel assignment could not be parsed
+    InvalidAssignment { line: usize },
+    /// A variable needed for the orientation model is missing or malformed
+    MissingVariable(String),
+    /// The orientation is referred to an inertial frame other than J2000 or ECLIPJ2000
+    UnsupportedFrame(i32),
+    /// A binary PCK segment uses a data type this reader does not support
+    UnsupportedType { frame: i32, data_type: i32 },
+    /// A binary PCK segment's data are inconsistent with its type
+    InvalidSegment { frame: i32 },
+    /// No binary PCK segment covers the frame at the requested epoch
+    NoCoverage { frame: i32 },
+}
+
+impl fmt::Display for PckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PckError::Daf(error) => write!(f, "{error}"),
+            PckError::NotPck => write!(f, "not a binary PCK file"),
+            PckError::InvalidAssignment { line } => write!(f, "line {line}: invalid assignment"),
+            PckError::MissingVariable(name) => write!(f, "missing or invalid variable {name}"),
+            PckError::UnsupportedFrame(frame) => write!(f, "unsupported inertial frame {frame}"),
+            PckError::UnsupportedType { frame, data_type } => write!(
+                f,
+                "unsupported PCK segment type {data_type} for frame {frame}"
+            ),
+            PckError::InvalidSegment { frame } => {
+                write!(f, "invalid PCK segment for frame {frame}")
+            }
+            PckError::NoCoverage { frame } => {
+                write!(f, "no PCK data for frame {frame} at the requested epoch")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PckError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PckError::Daf(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<DafError> for PckError {
+    fn from(error: DafError) -> Self {
+        PckError::Daf(error)
+    }
+}
+
+/// A value of a text kernel variable
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Text(String),
+}
+
+/// Body orientation constants from NAIF text kernels such as `pck00011.tpc`
+///
+/// The kernel pool holds every variable assigned in the `\begindata` sections of the loaded
+/// kernels. The orientation of a body follows the IAU rotation model: the right ascension and
+/// declination of the north pole and the prime meridian angle are polynomials in time plus
+/// trigonometric series in the nutation-precession angles of the body's system barycenter.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::{DateTime, TimeScale};
+/// use astro_carta::ephemeris::pck::TextPck;
+///
+/// let kernel = r"
+/// \begindata
+/// BODY499_POLE_RA  = ( 317.68143  -0.1061     0. )
+/// BODY499_POLE_DEC = (  52.88650  -0.0609     0. )
+/// BODY499_PM       = ( 176.630   350.89198226 0. )
+/// \begintext
+/// ";
+/// let pck = TextPck::parse(kernel).unwrap();
+/// let dt = DateTime::from_julian_date(2_451_545.0, TimeScale::TDB);
+/// let to_mars = pck.rotation_to_body_fixed(499, &dt).unwrap();
+/// assert!((to_mars.determinant() - 1.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextPck {
+    variables: HashMap<String, Vec<Value>>,
+}
+
+impl TextPck {
+    /// Constructs an empty kernel pool.
+    pub fn new() -> Self {
+        TextPck::default()
+    }
+
+    /// Parses a text kernel into a new kernel pool.
+    pub fn parse(text: &str) -> Result<Self, PckError> {
+        let mut pck = TextPck::new();
+        pck.load(text)?;
+        Ok(pck)
+    }
+
+    /// Loads the assignments of a text kernel into the pool.
+    ///
+    /// An assignment with `=` replaces any previous value of the variable and one with `+=`
+    /// appends to it, so kernels loaded later take precedence.
+    pub fn load(&mut self, text: &str) -> Result<(), PckError> {
+        let mut in_data = false;
+        let mut tokens = Vec::new();
+
+        for (index, content) in text.lines().enumerate() {
+            let line = index + 1;
+            match content.trim() {
+                "\\begindata" => in_data = true,
+                "\\begintext" => in_data = false,
+                _ if in_data => tokenize(content, line, &mut tokens)?,
+                _ => {}
+            }
+        }
+
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some((line, token)) = tokens.next() {
+            let Token::Word(name) = token else {
+                return Err(PckError::InvalidAssignment { line });
+            };
+            let append = match tokens.next() {
+                Some((_, Token::Assign)) => false,
+                Some((_, Token::Append)) => true,
+                _ => return Err(PckError::InvalidAssignment { line }),
+            };
+
+            let mut values = Vec::new();
+            match tokens.next() {
+                Some((_, Token::Open)) => loop {
+                    match tokens.next() {
+                        Some((_, Token::Close)) => break,
+                        Some((line, token)) => values.push(value(token, line)?),
+                        None => return Err(PckError::InvalidAssignment { line }),
+                    }
+                },
+                Some((line, token)) => values.push(value(token, line)?),
+                None => return Err(PckError::InvalidAssignment { line }),
+            }
+
+            let variable = self.variables.entry(name).or_default();
+            if !append {
+                variable.clear();
+            }
+            variable.extend(values);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the numeric values of a variable, or `None` if it is absent or holds strings.
+    pub fn numbers(&self, name: &str) -> Option<Vec<f64>> {
+        self.variables
+            .get(name)?
+            .iter()
+            .map(|value| match value {
+                Value::Number(number) => Some(*number),
+                Value::Text(_) => None,
+            })
+            .collect()
+    }
+
+    /// Returns the string values of a variable, or `None` if it is absent or holds numbers.
+    pub fn strings(&self, name: &str) -> Option<Vec<String>> {
+        self.variables
+            .get(name)?
+            .iter()
+            .map(|value| match value {
+                Value::Text(text) => Some(text.clone()),
+                Value::Number(_) => None,
+            })
+            .collect()
+    }
+
+    /// Returns the triaxial radii of a body in meters.
+    pub fn radii(&self, body: i32) -> Option<Vector3> {
+        match self.numbers(&format!("BODY{body}_RADII"))?[..] {
+            [a, b, c] => Some(Vector3::new(a, b, c) * METERS_PER_KILOMETER),
+            _ => None,
+        }
+    }
+
+    /// Returns the right ascension and declination of the north pole and the prime meridian
+    /// angle of a body in radians at the given instant, evaluated in TDB.
+    pub fn pole_and_meridian(&self, body: i32, dt: &DateTime) -> Result<(f64, f64, f64), PckError> {
+        let barycenter = if (100..1000).contains(&body) {
+            body / 100
+        } else {
+            body
+        };
+        for id in [body, barycenter] {
+            if let Some(frame) = self.numbers(&format!("BODY{id}_CONSTANTS_REF_FRAME")) {
+                if frame.first() != Some(&(J2000 as f64)) {
+                    return Err(PckError::UnsupportedFrame(
+                        frame.first().copied().unwrap_or(0.0) as i32,
+                    ));
+                }
+            }
+        }
+
+        let required = |suffix: &str| {
+            let name = format!("BODY{body}_{suffix}");
+            self.numbers(&name)
+                .filter(|values| !values.is_empty())
+                .ok_or(PckError::MissingVariable(name))
+        };
+        let ra = required("POLE_RA")?;
+        let dec = required("POLE_DEC")?;
+        let pm = required("PM")?;
+
+        let days = dt.days_since_j2000(TimeScale::TDB);
+        let t = days / DAYS_PER_JULIAN_CENTURY;
+        let polynomial = |coefficients: &[f64], x: f64| {
+            coefficients.iter().rev().fold(0.0, |sum, c| sum * x + c)
+        };
+
+        let mut right_ascension = polynomial(&ra, t);
+        let mut declination = polynomial(&dec, t);
+        let mut meridian = polynomial(&pm, days);
+
+        // Nutation-precession angles of the system barycenter, each a polynomial in centuries
+        let angles_name = format!("BODY{barycenter}_NUT_PREC_ANGLES");
+        let phases = self.numbers(&angles_name).unwrap_or_default();
+        let degree = self
+            .numbers(&format!("BODY{barycenter}_MAX_PHASE_DEGREE"))
+            .and_then(|values| values.first().copied())
+            .unwrap_or(1.0) as usize;
+        let angles: Vec<f64> = phases
+            .chunks_exact(degree + 1)
+            .map(|coefficients| polynomial(coefficients, t).to_radians())
+            .collect();
+
+        for (suffix, target, function) in [
+            (
+                "NUT_PREC_RA",
+                &mut right_ascension,
+                f64::sin as fn(f64) -> f64,
+            ),
+            ("NUT_PREC_DEC", &mut declination, f64::cos),
+            ("NUT_PREC_PM", &mut meridian, f64::sin),
+        ] {
+            let name = format!("BODY{body}_{suffix}");
+            if let Some(coefficients) = self.numbers(&name) {
+                if coefficients.len() > angles.len() {
+                    return Err(PckError::MissingVariable(angles_name));
+                }
+                *target += coefficients
+                    .iter()
+                    .zip(&angles)
+                    .map(|(c, &angle)| c * function(angle))
+                    .sum::<f64>();
+            }
+        }
+
+        Ok((
+            right_ascension.to_radians(),
+            declination.to_radians(),
+            math::normalize_angle(meridian.to_radians()),
+        ))
+    }
+
+    /// Returns the rotation from J2000 axes to the body-fixed axes of a body at the given
+    /// instant, evaluated in TDB.
+    pub fn rotation_to_body_fixed(&self, body: i32, dt: &DateTime) -> Result<Matrix3, PckError> {
+        let (right_ascension, declination, meridian) = self.pole_and_meridian(body, dt)?;
+        Ok(Matrix3::rot_z(meridian)
+            * Matrix3::rot_x(std::f64::consts::FRAC_PI_2 - declination)
+            * Matrix3::rot_z(std::f64::consts::FRAC_PI_2 + right_ascension))
+    }
+
+    /// Returns the rotation from the body-fixed axes of a body to J2000 axes at the given
+    /// instant, evaluated in TDB.
+    pub fn rotation_to_inertial(&self, body: i32, dt: &DateTime) -> Result<Matrix3, PckError> {
+        self.rotation_to_body_fixed(body, dt)
+            .map(|rotation| rotation.transpose())
+    }
+}
+
+/// A lexical token of a text kernel data section
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Assign,
+    Append,
+    Open,
+    Close,
+}
+
+/// Splits one line of a data section into tokens.
+fn tokenize(content: &str, line: usize, tokens: &mut Vec<(usize, Token)>) -> Result<(), PckError> {
+    let mut chars = content.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            _ if c.is_whitespace() || c == ',' => {
+                chars.next();
+            }
+            '(' | ')' | '=' => {
+                chars.next();
+                tokens.push((
+                    line,
+                    match c {
+                        '(' => Token::Open,
+                        ')' => Token::Close,
+                        _ => Token::Assign,
+                    },
+                ));
+            }
+            '\'' => {
+                // Quoted string, with doubled quotes standing for one
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            text.push('\'');
+                        }
+                        Some('\'') => break,
+                        Some(c) => text.push(c),
+                        None => return Err(PckError::InvalidAssignment { line }),
+                    }
+                }
+                tokens.push((line, Token::Text(text)));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "(),='".contains(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                if word == "+" && chars.peek() == Some(&'=') {
+                    chars.next();
+                    tokens.push((line, Token::Append));
+                } else {
+                    tokens.push((line, Token::Word(word)));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Converts a token on the right-hand side of an assignment into a value.
+fn value(token: Token, line: usize) -> Result<Value, PckError> {
+    match token {
+        Token::Text(text) => Ok(Value::Text(text)),
+        // Calendar dates are kept verbatim
+        Token::Word(word) if word.starts_with('@') => Ok(Value::Text(word)),
+        Token::Word(word) => word
+            .replace(['D', 'd'], "E")
+            .parse()
+            .map(Value::Number)
+            .map_err(|_| PckError::InvalidAssignment { line }),
+        _ => Err(PckError::InvalidAssignment { line }),
+    }
+}
+
+/// A segment of a binary PCK file, giving the orientation of one frame over an interval
+#[derive(Debug, Clone, PartialEq)]
+pub struct PckSegment {
+    /// Segment name
+    pub name: String,
+    /// NAIF frame class identifier of the body-fixed frame, such as 3000 for ITRF93
+    pub frame: i32,
+    /// NAIF identifier of the inertial frame the orientation is relative to
+    pub inertial_frame: i32,
+    /// PCK data type
+    pub data_type: i32,
+    /// Start of coverage in TDB seconds since J2000.0
+    pub start: f64,
+    /// End of coverage in TDB seconds since J2000.0
+    pub end: f64,
+    /// One-based address of the first word of the segment data
+    start_address: usize,
+    /// One-based address of the last word of the segment data
+    end_address: usize,
+}
+
+/// A reader for NAIF binary PCK files such as `earth_latest_high_prec.bpc` and the lunar
+/// `moon_pa_de440_200625.bpc`
+///
+/// Segment types 2 and 3 give the Euler angles (φ, δ, w) of the body-fixed frame as Chebyshev
+/// series, and the rotation from the inertial frame is `R3(w) R1(δ) R3(φ)`. When several
+/// segments cover the same frame and epoch the last one in the file takes precedence.
+#[derive(Debug)]
+pub struct BinaryPck<R> {
+    daf: Daf<R>,
+    segments: Vec<PckSegment>,
+}
+
+impl BinaryPck<BufReader<File>> {
+    /// Opens the binary PCK file at the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, PckError> {
+        BinaryPck::from_daf(Daf::open(path)?)
+    }
+}
+
+impl<R: Read + Seek> BinaryPck<R> {
+    /// Reads a binary PCK file from a reader.
+    pub fn from_reader(reader: R) -> Result<Self, PckError> {
+        BinaryPck::from_daf(Daf::from_reader(reader)?)
+    }
+
+    fn from_daf(daf: Daf<R>) -> Result<Self, PckError> {
+        if daf.identifier() != "DAF/PCK" {
+            return Err(PckError::NotPck);
+        }
+
+        let segments = daf
+            .summaries()
+            .iter()
+            .map(
+                |summary| match (&summary.doubles[..], &summary.integers[..]) {
+                    (
+                        &[start, end],
+                        &[frame, inertial_frame, data_type, start_address, end_address],
+                    ) if start_address > 0 && end_address >= start_address => Ok(PckSegment {
+                        name: summary.name.clone(),
+                        frame,
+                        inertial_frame,
+                        data_type,
+                        start,
+                        end,
+                        start_address: start_address as usize,
+                        end_address: end_address as usize,
+                    }),
+                    _ => Err(PckError::NotPck),
+                },
+            )
+            .collect::<Result<_, _>>()?;
+
+        Ok(BinaryPck { daf, segments })
+    }
+
+    /// Returns the segments in file order.
+    pub fn segments(&self) -> &[PckSegment] {
+        &self.segments
+    }
+
+    /// Returns the Euler angles (φ, δ, w) of a frame in radians and their rates in rad/s at the
+    /// given instant, evaluated in TDB, together with the inertial frame they are relative to.
+    pub fn euler_angles(
+        &self,
+        frame: i32,
+        dt: &DateTime,
+    ) -> Result<([f64; 3], [f64; 3], i32), PckError> {
+        let epoch = dt.days_since_j2000(TimeScale::TDB) * SECONDS_PER_DAY;
+        let segment = self
+            .segments
+            .iter()
+            .rev()
+            .find(|segment| {
+                segment.frame == frame && segment.start <= epoch && epoch <= segment.end
+            })
+            .ok_or(PckError::NoCoverage { frame })?;
+
+        if segment.data_type != 2 && segment.data_type != 3 {
+            return Err(PckError::UnsupportedType {
+                frame,
+                data_type: segment.data_type,
+            });
+        }
+        let [phi, delta, w, phi_rate, delta_rate, w_rate] = self
+            .daf
+            .evaluate_chebyshev(
+                segment.start_address,
+                segment.end_address,
+                segment.data_type == 3,
+                epoch,
+            )?
+            .ok_or(PckError::InvalidSegment { frame })?;
+
+        Ok((
+            [phi, delta, w],
+            [phi_rate, delta_rate, w_rate],
+            segment.inertial_frame,
+        ))
+    }
+
+    /// Returns the rotation from J2000 axes to the axes of a body-fixed frame at the given
+    /// instant, evaluated in TDB.
+    pub fn rotation_to_body_fixed(&self, frame: i32, dt: &DateTime) -> Result<Matrix3, PckError> {
+        let ([phi, delta, w], _, inertial_frame) = self.euler_angles(frame, dt)?;
+        let rotation = Matrix3::rot_z(w) * Matrix3::rot_x(delta) * Matrix3::rot_z(phi);
+
+        match inertial_frame {
+            J2000 => Ok(rotation),
+            ECLIPJ2000 => {
+                Ok(rotation * Matrix3::rot_x(math::arcseconds_to_radians(OBLIQUITY_J2000)))
+            }
+            other => Err(PckError::UnsupportedFrame(other)),
+        }
+    }
+
+    /// Returns the rotation from the axes of a body-fixed frame to J2000 axes at the given
+    /// instant, evaluated in TDB.
+    pub fn rotation_to_inertial(&self, frame: i32, dt: &DateTime) -> Result<Matrix3, PckError> {
+        self.rotation_to_body_fixed(frame, dt)
+            .map(|rotation| rotation.transpose())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KERNEL: &str = r"KPL/PCK
+
+Comments before the first data section are ignored, even BODY1_X = ( 1 ).
+
+\begindata
+
+BODY399_POLE_RA    = (    0.      -0.641         0. )
+BODY399_POLE_DEC   = (  +90.      -0.557         0. )
+BODY399_PM         = (  190.147  +360.9856235    0. )
+BODY399_RADII      = ( 6378.1366   6378.1366   6356.7519 )
+
+BODY3_NUT_PREC_ANGLES  = (  125.045         -1935.5364525000
+                            250.089         -3871.0729050000 )
+BODY3_NUT_PREC_ANGLES += (  260.008        475263.3328725000 )
+
+BODY301_POLE_RA      = (  269.9949        0.0031        0.      )
+BODY301_POLE_DEC     = (   66.5392        0.0130        0.      )
+BODY301_PM           = (   38.3213       13.17635815   -1.4D-12 )
+BODY301_NUT_PREC_RA  = (   -3.8787, -0.1204, 0.0700 )
+BODY301_NUT_PREC_DEC = (    1.5419   0.0239 -0.0278 )
+BODY301_NUT_PREC_PM  = (    3.5610   0.1208 -0.0642 )
+
+BODY499_POLE_RA    = (  317.68143   -0.1061      0.  )
+BODY499_POLE_DEC   = (   52.88650   -0.0609      0.  )
+BODY499_PM         = (  176.630    350.89198226   0.  )
+BODY499_NAME       = 'MARS'
+BODY499_NOTE       = ( 'It''s red' @2000-JAN-01 )
+
+\begintext
+
+Text after the data.
+";
+
+    fn tdb(days: f64) -> DateTime {
+        DateTime::from_days_since_j2000(days, TimeScale::TDB)
+    }
+
+    #[test]
+    fn parse_test() {
+        let pck = TextPck::parse(KERNEL).unwrap();
+        assert_eq!(pck.numbers("BODY1_X"), None);
+        assert_eq!(
+            pck.numbers("BODY399_POLE_DEC"),
+            Some(vec![90.0, -0.557, 0.0])
+        );
+        assert_eq!(pck.numbers("BODY3_NUT_PREC_ANGLES").unwrap().len(), 6);
+        assert_eq!(pck.numbers("BODY301_PM").unwrap()[2], -1.4e-12);
+        assert_eq!(pck.numbers("BODY301_NUT_PREC_RA").unwrap()[1], -0.1204);
+        assert_eq!(pck.strings("BODY499_NAME"), Some(vec!["MARS".to_string()]));
+        assert_eq!(
+            pck.strings("BODY499_NOTE"),
+            Some(vec!["It's red".to_string(), "@2000-JAN-01".to_string()])
+        );
+        assert_eq!(pck.numbers("BODY499_NAME"), None);
+        assert_eq!(
+            pck.radii(399),
+            Some(Vector3::new(6_378_136.6, 6_378_136.6, 6_356_751.9))
+        );
+
+        // Later kernels replace or extend earlier assignments
+        let mut pck = pck;
+        pck.load("\\begindata\nBODY499_PM = ( 176.0 350.9 )\nBODY499_NAME += 'BARSOOM'\n")
+            .unwrap();
+        assert_eq!(pck.numbers("BODY499_PM"), Some(vec![176.0, 350.9]));
+        assert_eq!(pck.strings("BODY499_NAME").unwrap().len(), 2);
+
+        for (kernel, line) in [
+            ("\\begindata\nBODY1_X = ( 1 2\n", 2),
+            ("\\begindata\n\nBODY1_X ( 1 )\n", 3),
+            ("\\begindata\nBODY1_X = ( 1 2x )\n", 2),
+            ("\\begindata\nBODY1_X = 'open\n", 2),
+        ] {
+            assert!(matches!(
+                TextPck::parse(kernel),
+                Err(PckError::InvalidAssignment { line: l }) if l == line
+            ));
+        }
+    }
+
+    #[test]
+    fn iau_rotation_test() {
+        let pck = TextPck::parse(KERNEL).unwrap();
+        let dt = tdb(1234.5);
+        let (alpha, delta, w) = pck.pole_and_meridian(499, &dt).unwrap();
+        let t: f64 = 1234.5 / 36_525.0;
+        assert!((alpha - (317.681_43 - 0.1061 * t).to_radians()).abs() < 1e-14);
+        assert!((delta - (52.8865 - 0.0609 * t).to_radians()).abs() < 1e-14);
+        assert!(
+            (w - math::normalize_angle((176.63 + 350.891_982_26 * 1234.5_f64).to_radians())).abs()
+                < 1e-12
+        );
+
+        // The pole maps onto the body z-axis
+        let rotation = pck.rotation_to_body_fixed(499, &dt).unwrap();
+        let pole = rotation * Vector3::from_spherical(alpha, delta);
+        assert!((pole - Vector3::new(0.0, 0.0, 1.0)).norm() < 1e-14);
+
+        // The prime meridian lies at an angle w east of the node of the body equator
+        let node = Vector3::from_spherical(alpha + std::f64::consts::FRAC_PI_2, 0.0);
+        let x_axis = pck.rotation_to_inertial(499, &dt).unwrap() * Vector3::new(1.0, 0.0, 0.0);
+        let angle = node
+            .cross(&x_axis)
+            .dot(&Vector3::from_spherical(alpha, delta))
+            .atan2(node.dot(&x_axis));
+        assert!((math::normalize_angle(angle) - w).abs() < 1e-12);
+    }
+
+    #[test]
+    fn earth_rotation_test() {
+        // The IAU model of the Earth follows the Earth rotation angle to within a few tenths of
+        // a degree, the difference coming from precession
+        let pck = TextPck::parse(KERNEL).unwrap();
+        for days in [0.0, 3_000.25, 8_900.7] {
+            let dt = tdb(days);
+            let x_axis = pck.rotation_to_inertial(399, &dt).unwrap() * Vector3::new(1.0, 0.0, 0.0);
+            let (longitude, _) = x_axis.to_spherical();
+            let era = crate::astrometry::sidereal::earth_rotation_angle(&dt, 0.0);
+            assert!(math::normalize_angle_signed(longitude - era).abs() < 0.5_f64.to_radians());
+        }
+    }
+
+    #[test]
+    fn nutation_precession_test() {
+        let pck = TextPck::parse(KERNEL).unwrap();
+        let days = -4_321.0;
+        let t = days / 36_525.0;
+        let angles = [
+            125.045 - 1_935.536_452_5 * t,
+            250.089 - 3_871.072_905 * t,
+            260.008 + 475_263.332_872_5 * t,
+        ]
+        .map(|angle: f64| angle.to_radians());
+
+        let (alpha, delta, w) = pck.pole_and_meridian(301, &tdb(days)).unwrap();
+        let expected_alpha =
+            269.9949 + 0.0031 * t - 3.8787 * angles[0].sin() - 0.1204 * angles[1].sin()
+                + 0.07 * angles[2].sin();
+        let expected_delta =
+            66.5392 + 0.013 * t + 1.5419 * angles[0].cos() + 0.0239 * angles[1].cos()
+                - 0.0278 * angles[2].cos();
+        let expected_w = 38.3213 + 13.176_358_15 * days - 1.4e-12 * days * days
+            + 3.561 * angles[0].sin()
+            + 0.1208 * angles[1].sin()
+            - 0.0642 * angles[2].sin();
+        assert!((alpha - expected_alpha.to_radians()).abs() < 1e-13);
+        assert!((delta - expected_delta.to_radians()).abs() < 1e-13);
+        assert!((w - math::normalize_angle(expected_w.to_radians())).abs() < 1e-11);
+    }
+
+    #[test]
+    fn missing_test() {
+        let pck = TextPck::parse(KERNEL).unwrap();
+        assert!(matches!(
+            pck.rotation_to_body_fixed(599, &tdb(0.0)),
+            Err(PckError::MissingVariable(name)) if name == "BODY599_POLE_RA"
+        ));
+
+        let mut pck = pck;
+        pck.load("\\begindata\nBODY301_NUT_PREC_RA += ( 1.0 )\n")
+            .unwrap();
+        assert!(matches!(
+            pck.rotation_to_body_fixed(301, &tdb(0.0)),
+            Err(PckError::MissingVariable(name)) if name == "BODY3_NUT_PREC_ANGLES"
+        ));
+
+        pck.load("\\begindata\nBODY4_CONSTANTS_REF_FRAME = 2\n")
+            .unwrap();
+        assert!(matches!(
+            pck.rotation_to_body_fixed(499, &tdb(0.0)),
+            Err(PckError::UnsupportedFrame(2))
+        ));
+    }
+}
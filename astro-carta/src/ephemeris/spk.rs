@@ -0,0 +1,496 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+
+use super::daf::{Daf, DafError};
+use super::{Ephemeris, StateVector};
+use crate::constants::SECONDS_PER_DAY;
+use crate::datetime::{DateTime, TimeScale};
+use crate::math::{interpolation, Vector3};
+
+/// Number of meters in a kilometer, the length unit of SPK files
+const METERS_PER_KILOMETER: f64 = 1_000.0;
+
+/// Spacing of the epoch directories of segment types 9, 13 and 21
+const DIRECTORY_SPACING: usize = 100;
+
+/// Maximum number of links followed from a body towards the root of the segment tree
+const MAX_CHAIN_LENGTH: usize = 100;
+
+/// Error raised while reading or evaluating an SPK file
+#[derive(Debug)]
+pub enum SpkError {
+    /// The DAF container could not be read
+    Daf(DafError),
+    /// The file is a DAF file but not an SPK file
+    NotSpk,
+    /// A segment uses a data type this reader does not support
+    UnsupportedType { target: i32, data_type: i32 },
+    /// A segment's data are inconsistent with its type
+    InvalidSegment { target: i32, center: i32 },
+    /// No segment links the two bodies at the requested epoch
+    NoPath { target: i32, center: i32 },
+    /// The segments linking the two bodies use different reference frames
+    FrameMismatch { target: i32, center: i32 },
+}
+
+impl fmt::Display for SpkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpkError::Daf(error) => write!(f, "{error}"),
+            SpkError::NotSpk => write!(f, "not an SPK file"),
+            SpkError::UnsupportedType { target, data_type } => write!(
+                f,
+                "unsupported SPK segment type {data_type} for body {target}"
+            ),
+            SpkError::InvalidSegment { target, center } => {
+                write!(
+                    f,
+                    "invalid SPK segment for body {target} relative to {center}"
+                )
+            }
+            SpkError::NoPath { target, center } => write!(
+                f,
+                "no SPK data for body {target} relative to {center} at the requested epoch"
+            ),
+            SpkError::FrameMismatch { target, center } => write!(
+                f,
+                "SPK segments for body {target} relative to {center} use different frames"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SpkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SpkError::Daf(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<DafError> for SpkError {
+    fn from(error: DafError) -> Self {
+        SpkError::Daf(error)
+    }
+}
+
+/// A segment of an SPK file, giving the state of one body relative to another over an interval
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// Segment name
+    pub name: String,
+    /// NAIF identifier of the body whose state is given
+    pub target: i32,
+    /// NAIF identifier of the body the state is relative to
+    pub center: i32,
+    /// NAIF identifier of the reference frame, 1 for J2000
+    pub frame: i32,
+    /// SPK data type
+    pub data_type: i32,
+    /// Start of coverage in TDB seconds since J2000.0
+    pub start: f64,
+    /// End of coverage in TDB seconds since J2000.0
+    pub end: f64,
+    /// One-based address of the first word of the segment data
+    start_address: usize,
+    /// One-based address of the last word of the segment data
+    end_address: usize,
+}
+
+impl Segment {
+    /// Returns the start of coverage.
+    pub fn start_epoch(&self) -> DateTime {
+        DateTime::from_days_since_j2000(self.start / SECONDS_PER_DAY, TimeScale::TDB)
+    }
+
+    /// Returns the end of coverage.
+    pub fn end_epoch(&self) -> DateTime {
+        DateTime::from_days_since_j2000(self.end / SECONDS_PER_DAY, TimeScale::TDB)
+    }
+
+    /// Returns `true` if the segment covers the epoch in TDB seconds since J2000.0.
+    pub fn covers(&self, epoch: f64) -> bool {
+        self.start <= epoch && epoch <= self.end
+    }
+
+    fn invalid(&self) -> SpkError {
+        SpkError::InvalidSegment {
+            target: self.target,
+            center: self.center,
+        }
+    }
+}
+
+/// A reader for NAIF SPK ephemeris files such as the JPL DE440 and DE441 kernels
+///
+/// Segment types 2 and 3 (Chebyshev), 9 (Lagrange), 13 (Hermite) and 21 (extended modified
+/// difference arrays) are supported. The state of any body relative to any other is found by
+/// chaining segments through their common ancestor, usually the solar system barycenter. When
+/// several segments cover the same body and epoch the last one in the file takes precedence,
+/// as in SPICE.
+///
+/// # Examples
+///
+/// ```no_run
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::ephemeris::spk::Spk;
+/// use astro_carta::ephemeris::Ephemeris;
+///
+/// let spk = Spk::open("de440.bsp").unwrap();
+/// let dt = DateTime::gregorian(2024, 4, 8, 18, 0, 0.0).unwrap();
+/// // Geocentric state of the Moon
+/// let moon = spk.state(301, 399, &dt).unwrap();
+/// println!("{:.0} km", moon.position.norm() / 1e3);
+/// ```
+#[derive(Debug)]
+pub struct Spk<R> {
+    daf: Daf<R>,
+    segments: Vec<Segment>,
+}
+
+impl Spk<BufReader<File>> {
+    /// Opens the SPK file at the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, SpkError> {
+        Spk::from_daf(Daf::open(path)?)
+    }
+}
+
+impl<R: Read + Seek> Spk<R> {
+    /// Reads an SPK file from a reader.
+    pub fn from_reader(reader: R) -> Result<Self, SpkError> {
+        Spk::from_daf(Daf::from_reader(reader)?)
+    }
+
+    fn from_daf(daf: Daf<R>) -> Result<Self, SpkError> {
+        if daf.identifier() != "DAF/SPK" && daf.identifier() != "NAIF/DAF" {
+            return Err(SpkError::NotSpk);
+        }
+
+        let segments = daf
+            .summaries()
+            .iter()
+            .map(
+                |summary| match (&summary.doubles[..], &summary.integers[..]) {
+                    (
+                        &[start, end],
+                        &[target, center, frame, data_type, start_address, end_address],
+                    ) if start_address > 0 && end_address >= start_address => Ok(Segment {
+                        name: summary.name.clone(),
+                        target,
+                        center,
+                        frame,
+                        data_type,
+                        start,
+                        end,
+                        start_address: start_address as usize,
+                        end_address: end_address as usize,
+                    }),
+                    _ => Err(SpkError::NotSpk),
+                },
+            )
+            .collect::<Result<_, _>>()?;
+
+        Ok(Spk { daf, segments })
+    }
+
+    /// Returns the segments in file order.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Follows segments from `body` towards the root of the tree, returning each body reached
+    /// with the state of the original body relative to it. The state is accumulated in the
+    /// frame of the segments and converted to meters.
+    fn chain(&self, body: i32, epoch: f64) -> Result<Vec<Link>, SpkError> {
+        let mut links = vec![Link {
+            body,
+            frame: None,
+            state: StateVector {
+                position: Vector3::zeros(),
+                velocity: Vector3::zeros(),
+            },
+        }];
+
+        for _ in 0..MAX_CHAIN_LENGTH {
+            let current = links.last().unwrap();
+            let Some(segment) = self
+                .segments
+                .iter()
+                .rev()
+                .find(|segment| segment.target == current.body && segment.covers(epoch))
+            else {
+                break;
+            };
+
+            let state = current.state + self.evaluate(segment, epoch)?;
+            links.push(Link {
+                body: segment.center,
+                frame: Some(segment.frame),
+                state,
+            });
+        }
+
+        Ok(links)
+    }
+
+    /// Evaluates a segment at an epoch in TDB seconds since J2000.0.
+    fn evaluate(&self, segment: &Segment, epoch: f64) -> Result<StateVector, SpkError> {
+        let [x, y, z, vx, vy, vz] = match segment.data_type {
+            2 | 3 => self.evaluate_chebyshev(segment, epoch)?,
+            9 | 13 => self.evaluate_interpolated(segment, epoch)?,
+            21 => self.evaluate_difference_arrays(segment, epoch)?,
+            data_type => {
+                return Err(SpkError::UnsupportedType {
+                    target: segment.target,
+                    data_type,
+                })
+            }
+        };
+
+        Ok(StateVector {
+            position: Vector3::new(x, y, z) * METERS_PER_KILOMETER,
+            velocity: Vector3::new(vx, vy, vz) * METERS_PER_KILOMETER,
+        })
+    }
+
+    /// Evaluates a type 2 or type 3 segment of fixed-length Chebyshev records.
+    fn evaluate_chebyshev(&self, segment: &Segment, epoch: f64) -> Result<[f64; 6], SpkError> {
+        self.daf
+            .evaluate_chebyshev(
+                segment.start_address,
+                segment.end_address,
+                segment.data_type == 3,
+                epoch,
+            )?
+            .ok_or_else(|| segment.invalid())
+    }
+
+    /// Evaluates a type 9 (Lagrange) or type 13 (Hermite) segment of discrete states.
+    fn evaluate_interpolated(&self, segment: &Segment, epoch: f64) -> Result<[f64; 6], SpkError> {
+        let trailer = self.read(segment, segment.end_address - 1, segment.end_address)?;
+        let count = trailer[1] as usize;
+        let window = (trailer[0] as usize).saturating_add(1);
+        let words = segment.end_address - segment.start_address;
+        if count < window || window < 2 || count > words {
+            return Err(segment.invalid());
+        }
+        let epochs_address = segment.start_address + 6 * count;
+
+        // First epoch not before the requested one
+        let next = self.search_epochs(segment, epochs_address, count, epoch)?;
+        let first = if window.is_multiple_of(2) {
+            // Center the window on the interval containing the epoch
+            next.saturating_sub(window / 2)
+        } else {
+            // Center the window on the nearest epoch
+            let neighbours = self.read(
+                segment,
+                epochs_address + next.saturating_sub(1),
+                epochs_address + next.min(count - 1),
+            )?;
+            let nearest =
+                if next > 0 && epoch - neighbours[0] <= neighbours[neighbours.len() - 1] - epoch {
+                    next - 1
+                } else {
+                    next.min(count - 1)
+                };
+            nearest.saturating_sub((window - 1) / 2)
+        }
+        .min(count - window);
+
+        let times = self.read(
+            segment,
+            epochs_address + first,
+            epochs_address + first + window - 1,
+        )?;
+        let states = self.read(
+            segment,
+            segment.start_address + 6 * first,
+            segment.start_address + 6 * (first + window) - 1,
+        )?;
+        let component =
+            |index: usize| -> Vec<f64> { states.iter().skip(index).step_by(6).copied().collect() };
+
+        let mut state = [0.0; 6];
+        for axis in 0..3 {
+            if segment.data_type == 9 {
+                state[axis] = interpolation::lagrange(&times, &component(axis), epoch);
+                state[axis + 3] = interpolation::lagrange(&times, &component(axis + 3), epoch);
+            } else {
+                (state[axis], state[axis + 3]) =
+                    interpolation::hermite(&times, &component(axis), &component(axis + 3), epoch);
+            }
+        }
+
+        Ok(state)
+    }
+
+    /// Evaluates a type 21 segment of extended modified difference arrays.
+    fn evaluate_difference_arrays(
+        &self,
+        segment: &Segment,
+        epoch: f64,
+    ) -> Result<[f64; 6], SpkError> {
+        let trailer = self.read(segment, segment.end_address - 1, segment.end_address)?;
+        let max_dimension = trailer[0] as usize;
+        let count = trailer[1] as usize;
+        let words = segment.end_address - segment.start_address;
+        if max_dimension < 1 || count < 1 || max_dimension > words || count > words {
+            return Err(segment.invalid());
+        }
+        let record_size = 4 * max_dimension + 11;
+
+        // Each record is valid up to its final epoch
+        let epochs_address = segment.start_address + record_size * count;
+        let index = self
+            .search_epochs(segment, epochs_address, count, epoch)?
+            .min(count - 1);
+        let address = segment.start_address + index * record_size;
+        let record = self.read(segment, address, address + record_size - 1)?;
+
+        let reference_epoch = record[0];
+        let steps = &record[1..=max_dimension];
+        let reference_position = [1, 3, 5].map(|i| record[max_dimension + i]);
+        let reference_velocity = [2, 4, 6].map(|i| record[max_dimension + i]);
+        let differences = &record[max_dimension + 7..4 * max_dimension + 7];
+        let order = record[4 * max_dimension + 7] as usize;
+        let orders = [8, 9, 10].map(|i| record[4 * max_dimension + i] as usize);
+        if order < 2
+            || order > max_dimension + 1
+            || orders.iter().any(|&q| q > order || q > max_dimension)
+        {
+            return Err(segment.invalid());
+        }
+
+        // Integration coefficients of Shampine and Gordon, as in the SPICE routine SPKE21
+        let delta = epoch - reference_epoch;
+        let mut fc = vec![0.0; max_dimension + 1];
+        let mut wc = vec![0.0; max_dimension];
+        let mut w: Vec<f64> = (1..=max_dimension + 2).map(|j| 1.0 / j as f64).collect();
+        fc[0] = 1.0;
+        let mut tp = delta;
+        for j in 0..order - 2 {
+            fc[j + 1] = tp / steps[j];
+            wc[j] = delta / steps[j];
+            tp = delta + steps[j];
+        }
+
+        // One-based indices follow the SPICE implementation
+        let mut ks = order - 1;
+        let mut jx = 0;
+        while ks >= 2 {
+            jx += 1;
+            for j in 1..=jx {
+                w[j + ks - 1] = fc[j] * w[j + ks - 2] - wc[j - 1] * w[j + ks - 1];
+            }
+            ks -= 1;
+        }
+
+        let mut state = [0.0; 6];
+        for axis in 0..3 {
+            let sum: f64 = (1..=orders[axis])
+                .rev()
+                .map(|j| differences[axis * max_dimension + j - 1] * w[j + ks - 1])
+                .sum();
+            state[axis] =
+                reference_position[axis] + delta * (reference_velocity[axis] + delta * sum);
+        }
+
+        for j in 1..=jx {
+            w[j + ks - 1] = fc[j] * w[j + ks - 2] - wc[j - 1] * w[j + ks - 1];
+        }
+        ks -= 1;
+
+        for axis in 0..3 {
+            let sum: f64 = (1..=orders[axis])
+                .rev()
+                .map(|j| differences[axis * max_dimension + j - 1] * w[j + ks - 1])
+                .sum();
+            state[axis + 3] = reference_velocity[axis] + delta * sum;
+        }
+
+        Ok(state)
+    }
+
+    /// Returns the index of the first of `count` sorted epochs stored at `address` that is not
+    /// before `epoch`, or `count` if there is none, using the directory of every hundredth
+    /// epoch that follows them.
+    fn search_epochs(
+        &self,
+        segment: &Segment,
+        address: usize,
+        count: usize,
+        epoch: f64,
+    ) -> Result<usize, SpkError> {
+        let directory_size = (count - 1) / DIRECTORY_SPACING;
+        let bucket = if directory_size > 0 {
+            let directory = self.read(
+                segment,
+                address + count,
+                address + count + directory_size - 1,
+            )?;
+            directory.partition_point(|&value| value < epoch)
+        } else {
+            0
+        };
+
+        let first = bucket * DIRECTORY_SPACING;
+        let last = (first + DIRECTORY_SPACING).min(count);
+        let epochs = self.read(segment, address + first, address + last - 1)?;
+        Ok(first + epochs.partition_point(|&value| value < epoch))
+    }
+
+    /// Reads the words between two one-based addresses, checking they lie within the segment.
+    fn read(&self, segment: &Segment, start: usize, end: usize) -> Result<Vec<f64>, SpkError> {
+        if start < segment.start_address || end > segment.end_address || end < start {
+            return Err(segment.invalid());
+        }
+        Ok(self.daf.read_doubles(start, end)?)
+    }
+}
+
+impl<R: Read + Seek> Ephemeris for Spk<R> {
+    type Error = SpkError;
+
+    fn state(&self, target: i32, center: i32, dt: &DateTime) -> Result<StateVector, SpkError> {
+        let epoch = dt.days_since_j2000(TimeScale::TDB) * SECONDS_PER_DAY;
+
+        let target_chain = self.chain(target, epoch)?;
+        let center_chain = self.chain(center, epoch)?;
+        let no_path = SpkError::NoPath { target, center };
+
+        // Find the first common ancestor and difference the states relative to it
+        let (target_index, center_index) = target_chain
+            .iter()
+            .enumerate()
+            .find_map(|(i, link)| {
+                center_chain
+                    .iter()
+                    .position(|other| other.body == link.body)
+                    .map(|j| (i, j))
+            })
+            .ok_or(no_path)?;
+
+        // Each link carries the frame of the segment that reached it, and the first has none
+        let frames: Vec<i32> = target_chain[1..=target_index]
+            .iter()
+            .chain(&center_chain[1..=center_index])
+            .filter_map(|link| link.frame)
+            .collect();
+        if frames.windows(2).any(|pair| pair[0] != pair[1]) {
+            return Err(SpkError::FrameMismatch { target, center });
+        }
+
+        Ok(target_chain[target_index].state - center_chain[center_index].state)
+    }
+}
+
+/// A body reached while following segments, with the state of the original body relative to it
+struct Link {
+    body: i32,
+    frame: Option<i32>,
+    state: StateVector,
+}
@@ -0,0 +1,73 @@
+use super::GeocentricPosition;
+use crate::constants::ASTRONOMICAL_UNIT;
+use crate::coordinates::Ecliptic;
+use crate::datetime::{DateTime, TimeScale};
+use crate::math;
+
+/// Computes the geocentric position of the Sun with the low-precision formula of the
+/// Astronomical Almanac (section C).
+///
+/// The coordinates are apparent, in that the mean longitude includes the constant of
+/// aberration, and are referred to the mean equinox and ecliptic of date. The accuracy is about
+/// 0.01° in position and 1e-4 AU in distance between 1950 and 2050, degrading slowly outside.
+///
+/// # Arguments
+///
+/// * `dt` - The instant, evaluated in TT.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::ephemeris::sun;
+///
+/// // Near the March equinox the Sun crosses the celestial equator
+/// let dt = DateTime::gregorian(2024, 3, 20, 3, 6, 0.0).unwrap();
+/// let position = sun::position(&dt);
+/// assert!(position.equatorial.declination.to_degrees().abs() < 0.02);
+/// ```
+pub fn position(dt: &DateTime) -> GeocentricPosition {
+    let n = dt.days_since_j2000(TimeScale::TT);
+
+    let mean_longitude = (280.460 + 0.985_647_4 * n).to_radians();
+    let mean_anomaly = (357.528 + 0.985_600_3 * n).to_radians();
+    let longitude = mean_longitude
+        + 1.915_f64.to_radians() * mean_anomaly.sin()
+        + 0.020_f64.to_radians() * (2.0 * mean_anomaly).sin();
+    let obliquity = (23.439 - 0.000_000_4 * n).to_radians();
+    let distance = 1.000_14 - 0.016_71 * mean_anomaly.cos() - 0.000_14 * (2.0 * mean_anomaly).cos();
+
+    let ecliptic = Ecliptic::new(math::normalize_angle(longitude), 0.0);
+    GeocentricPosition {
+        ecliptic,
+        equatorial: ecliptic.to_equatorial(obliquity),
+        distance: distance * ASTRONOMICAL_UNIT,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meeus_example_25a_test() {
+        // Meeus, Astronomical Algorithms, Example 25.a: 1992 October 13.0 TD
+        let dt = DateTime::from_julian_date(2_448_908.5, TimeScale::TT);
+        let sun = position(&dt);
+
+        assert!((sun.ecliptic.longitude.to_degrees() - 199.909_88).abs() < 0.01);
+        assert_eq!(sun.ecliptic.latitude, 0.0);
+        assert!((sun.equatorial.right_ascension.to_degrees() - 198.380_83).abs() < 0.01);
+        assert!((sun.equatorial.declination.to_degrees() + 7.785_07).abs() < 0.01);
+        assert!((sun.distance / ASTRONOMICAL_UNIT - 0.997_607_75).abs() < 1e-4);
+    }
+
+    #[test]
+    fn perihelion_aphelion_test() {
+        // The Earth was at perihelion on 2024 January 3 and aphelion on 2024 July 5
+        let perihelion = position(&DateTime::gregorian(2024, 1, 3, 0, 39, 0.0).unwrap());
+        let aphelion = position(&DateTime::gregorian(2024, 7, 5, 5, 6, 0.0).unwrap());
+        assert!((perihelion.distance / ASTRONOMICAL_UNIT - 0.983_307).abs() < 1e-4);
+        assert!((aphelion.distance / ASTRONOMICAL_UNIT - 1.016_725).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,643 @@
+mod data;
+
+use std::fmt;
+
+use crate::datetime::{DateTime, TimeScale};
+use crate::math::Vector3;
+
+/// Number of days in a Julian millennium, the time unit of the VSOP87 series
+const DAYS_PER_JULIAN_MILLENNIUM: f64 = 365_250.0;
+
+/// Scale of the amplitudes in the embedded abridged tables
+const ABRIDGED_AMPLITUDE_SCALE: f64 = 1e-8;
+
+/// A major planet covered by the VSOP87 theory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Planet {
+    Mercury,
+    Venus,
+    Earth,
+    Mars,
+    Jupiter,
+    Saturn,
+    Uranus,
+    Neptune,
+}
+
+impl Planet {
+    /// Returns the name of the planet.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Planet::Mercury => "Mercury",
+            Planet::Venus => "Venus",
+            Planet::Earth => "Earth",
+            Planet::Mars => "Mars",
+            Planet::Jupiter => "Jupiter",
+            Planet::Saturn => "Saturn",
+            Planet::Uranus => "Uranus",
+            Planet::Neptune => "Neptune",
+        }
+    }
+
+    /// Returns the extension of the official VSOP87 file for the planet, such as `ear` for the
+    /// Earth in `VSOP87D.ear`.
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            Planet::Mercury => "mer",
+            Planet::Venus => "ven",
+            Planet::Earth => "ear",
+            Planet::Mars => "mar",
+            Planet::Jupiter => "jup",
+            Planet::Saturn => "sat",
+            Planet::Uranus => "ura",
+            Planet::Neptune => "nep",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [
+            Planet::Mercury,
+            Planet::Venus,
+            Planet::Earth,
+            Planet::Mars,
+            Planet::Jupiter,
+            Planet::Saturn,
+            Planet::Uranus,
+            Planet::Neptune,
+        ]
+        .into_iter()
+        .find(|planet| planet.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Version of the VSOP87 theory, which fixes the coordinates and the reference frame
+///
+/// All versions are heliocentric and referred to the dynamical ecliptic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Version {
+    /// Rectangular coordinates, equinox J2000
+    A,
+    /// Spherical coordinates, equinox J2000
+    B,
+    /// Rectangular coordinates, equinox of date
+    C,
+    /// Spherical coordinates, equinox of date
+    D,
+}
+
+impl Version {
+    /// Returns `true` if the version gives longitude, latitude and radius rather than Cartesian
+    /// coordinates.
+    pub fn is_spherical(&self) -> bool {
+        matches!(self, Version::B | Version::D)
+    }
+
+    fn from_code(code: char) -> Option<Self> {
+        match code {
+            'A' => Some(Version::A),
+            'B' => Some(Version::B),
+            'C' => Some(Version::C),
+            'D' => Some(Version::D),
+            _ => None,
+        }
+    }
+}
+
+/// A single periodic term `A cos(B + C t)` of a VSOP87 series
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Term {
+    /// Amplitude in AU or radians
+    pub amplitude: f64,
+    /// Phase in radians
+    pub phase: f64,
+    /// Frequency in radians per Julian millennium
+    pub frequency: f64,
+}
+
+/// Heliocentric ecliptic position and velocity of a planet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeliocentricState {
+    /// Position in AU
+    pub position: Vector3,
+    /// Velocity in AU/day
+    pub velocity: Vector3,
+}
+
+/// Error raised while parsing a VSOP87 file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Vsop87Error {
+    /// The file contains no series
+    Empty,
+    /// A block header could not be parsed
+    InvalidHeader { line: usize },
+    /// A term could not be parsed
+    InvalidTerm { line: usize },
+    /// A term appears before the first block header
+    MissingHeader { line: usize },
+    /// The file uses a version other than A, B, C or D
+    UnsupportedVersion { line: usize },
+    /// A block header names a different planet or version from the first one
+    Inconsistent { line: usize },
+    /// A block holds a different number of terms than its header declares
+    TermCount {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for Vsop87Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Vsop87Error::Empty => write!(f, "no VSOP87 series found"),
+            Vsop87Error::InvalidHeader { line } => write!(f, "line {line}: invalid block header"),
+            Vsop87Error::InvalidTerm { line } => write!(f, "line {line}: invalid term"),
+            Vsop87Error::MissingHeader { line } => {
+                write!(f, "line {line}: term found before any block header")
+            }
+            Vsop87Error::UnsupportedVersion { line } => {
+                write!(f, "line {line}: unsupported VSOP87 version")
+            }
+            Vsop87Error::Inconsistent { line } => {
+                write!(f, "line {line}: block belongs to another planet or version")
+            }
+            Vsop87Error::TermCount {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line}: block declares {expected} terms but holds {found}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Vsop87Error {}
+
+/// A VSOP87 planetary theory for one planet and one version
+///
+/// Each of the three coordinates is a Poisson series `Σ t^k Σ A cos(B + C t)` in Julian
+/// millennia of TDB since J2000.0. The series can be read from the official IMCCE files with
+/// [`Vsop87::parse`] or taken from the abridged tables embedded in the crate with
+/// [`Vsop87::abridged`], and either can be cut down with [`Vsop87::truncated`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vsop87 {
+    planet: Planet,
+    version: Version,
+    /// Terms of each coordinate, indexed by power of time
+    series: [Vec<Vec<Term>>; 3],
+}
+
+impl Vsop87 {
+    /// Returns the abridged VSOP87D theory published by Meeus (Astronomical Algorithms,
+    /// Appendix III), or `None` if the crate embeds no table for the planet.
+    ///
+    /// Tables are embedded for Venus and the Earth, accurate to about 1″ in longitude over
+    /// several millennia. Other planets and the full series require the official files, read
+    /// with [`Vsop87::parse`] and cut down to the wanted accuracy with
+    /// [`Vsop87::truncated`]; the integration tests compare them with the official check
+    /// values when `VSOP87_DIR` names the directory holding them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use astro_carta::datetime::{DateTime, TimeScale};
+    /// use astro_carta::ephemeris::vsop87::{Planet, Vsop87};
+    ///
+    /// let earth = Vsop87::abridged(Planet::Earth).unwrap();
+    /// let dt = DateTime::from_julian_date(2_451_545.0, TimeScale::TDB);
+    /// let state = earth.state(&dt);
+    /// assert!((state.position.norm() - 0.983_33).abs() < 1e-5);
+    /// ```
+    pub fn abridged(planet: Planet) -> Option<Self> {
+        let tables = match planet {
+            Planet::Venus => &data::VENUS,
+            Planet::Earth => &data::EARTH,
+            _ => return None,
+        };
+
+        let series = tables.map(|coordinate| {
+            coordinate
+                .iter()
+                .map(|terms| {
+                    terms
+                        .iter()
+                        .map(|&(amplitude, phase, frequency)| Term {
+                            amplitude: amplitude * ABRIDGED_AMPLITUDE_SCALE,
+                            phase,
+                            frequency,
+                        })
+                        .collect()
+                })
+                .collect()
+        });
+
+        Some(Vsop87 {
+            planet,
+            version: Version::D,
+            series,
+        })
+    }
+
+    /// Parses an official VSOP87 file, such as `VSOP87A.ear` or `VSOP87D.nep`.
+    ///
+    /// Each block starts with a header naming the version, planet, coordinate, power of time
+    /// and number of terms. Each term line ends with the amplitude, phase and frequency, which
+    /// are the only fields read.
+    pub fn parse(text: &str) -> Result<Self, Vsop87Error> {
+        let mut identity: Option<(Planet, Version)> = None;
+        let mut series: [Vec<Vec<Term>>; 3] = Default::default();
+        // Coordinate, power, declared count and header line of the current block
+        let mut block: Option<(usize, usize, usize, usize)> = None;
+
+        let check_count =
+            |series: &[Vec<Vec<Term>>; 3], block: Option<(usize, usize, usize, usize)>| match block
+            {
+                Some((coordinate, power, expected, line)) => {
+                    let found = series[coordinate][power].len();
+                    if found == expected {
+                        Ok(())
+                    } else {
+                        Err(Vsop87Error::TermCount {
+                            line,
+                            expected,
+                            found,
+                        })
+                    }
+                }
+                None => Ok(()),
+            };
+
+        for (index, content) in text.lines().enumerate() {
+            let line = index + 1;
+            let tokens: Vec<&str> = content.split_whitespace().collect();
+            if tokens.is_empty() {
+                continue;
+            }
+
+            if tokens[0] == "VSOP87" {
+                check_count(&series, block)?;
+
+                let header = parse_header(&tokens).ok_or(Vsop87Error::InvalidHeader { line })?;
+                let version = header
+                    .version
+                    .ok_or(Vsop87Error::UnsupportedVersion { line })?;
+                if *identity.get_or_insert((header.planet, version)) != (header.planet, version) {
+                    return Err(Vsop87Error::Inconsistent { line });
+                }
+
+                let powers = &mut series[header.coordinate];
+                if powers.len() <= header.power {
+                    powers.resize(header.power + 1, Vec::new());
+                }
+                block = Some((header.coordinate, header.power, header.count, line));
+                continue;
+            }
+
+            let (coordinate, power, _, _) = block.ok_or(Vsop87Error::MissingHeader { line })?;
+            let term = parse_term(&tokens).ok_or(Vsop87Error::InvalidTerm { line })?;
+            series[coordinate][power].push(term);
+        }
+        check_count(&series, block)?;
+
+        let (planet, version) = identity.ok_or(Vsop87Error::Empty)?;
+        Ok(Vsop87 {
+            planet,
+            version,
+            series,
+        })
+    }
+
+    /// Returns the planet described by the theory.
+    pub fn planet(&self) -> Planet {
+        self.planet
+    }
+
+    /// Returns the version of the theory.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the total number of periodic terms over all coordinates and powers of time.
+    pub fn term_count(&self) -> usize {
+        self.series.iter().flatten().map(Vec::len).sum()
+    }
+
+    /// Returns a copy of the theory without the terms whose amplitude is below `threshold`, in
+    /// AU or radians.
+    ///
+    /// Larger thresholds evaluate faster at the cost of accuracy; the error grows roughly as
+    /// the threshold times the square root of the number of dropped terms.
+    pub fn truncated(&self, threshold: f64) -> Self {
+        let series = self.series.clone().map(|powers| {
+            powers
+                .into_iter()
+                .map(|terms| {
+                    terms
+                        .into_iter()
+                        .filter(|term| term.amplitude.abs() >= threshold)
+                        .collect()
+                })
+                .collect()
+        });
+
+        Vsop87 {
+            planet: self.planet,
+            version: self.version,
+            series,
+        }
+    }
+
+    /// Evaluates the three coordinates of the theory at the given instant, evaluated in TDB.
+    ///
+    /// These are `[X, Y, Z]` in AU for the rectangular versions and `[L, B, R]` in radians and
+    /// AU for the spherical ones. The longitude is not reduced to [0, 2π).
+    pub fn coordinates(&self, dt: &DateTime) -> [f64; 3] {
+        self.evaluate(millennia_since_j2000(dt)).0
+    }
+
+    /// Computes the heliocentric ecliptic position and velocity at the given instant, evaluated
+    /// in TDB.
+    ///
+    /// The axes are those of the ecliptic and equinox of J2000 for versions A and B and of
+    /// date for versions C and D. The velocity is the rate of change of the position in those
+    /// axes.
+    pub fn state(&self, dt: &DateTime) -> HeliocentricState {
+        let (values, rates) = self.evaluate(millennia_since_j2000(dt));
+        let rates = rates.map(|rate| rate / DAYS_PER_JULIAN_MILLENNIUM);
+
+        if !self.version.is_spherical() {
+            return HeliocentricState {
+                position: Vector3::from(values),
+                velocity: Vector3::from(rates),
+            };
+        }
+
+        let [longitude, latitude, radius] = values;
+        let [longitude_rate, latitude_rate, radius_rate] = rates;
+        let (sin_lon, cos_lon) = longitude.sin_cos();
+        let (sin_lat, cos_lat) = latitude.sin_cos();
+
+        HeliocentricState {
+            position: Vector3::from_spherical(longitude, latitude) * radius,
+            velocity: Vector3::new(
+                radius_rate * cos_lat * cos_lon
+                    - radius
+                        * (sin_lat * cos_lon * latitude_rate + cos_lat * sin_lon * longitude_rate),
+                radius_rate * cos_lat * sin_lon
+                    - radius
+                        * (sin_lat * sin_lon * latitude_rate - cos_lat * cos_lon * longitude_rate),
+                radius_rate * sin_lat + radius * cos_lat * latitude_rate,
+            ),
+        }
+    }
+
+    /// Returns the coordinates and their rates per Julian millennium at `t` Julian millennia
+    /// since J2000.0.
+    fn evaluate(&self, t: f64) -> ([f64; 3], [f64; 3]) {
+        let mut values = [0.0; 3];
+        let mut rates = [0.0; 3];
+
+        for (coordinate, powers) in self.series.iter().enumerate() {
+            // Horner scheme over the powers of time, for the value and its derivative
+            let mut value = 0.0;
+            let mut rate = 0.0;
+            for terms in powers.iter().rev() {
+                let mut sum = 0.0;
+                let mut sum_rate = 0.0;
+                for term in terms {
+                    let (sin, cos) = (term.phase + term.frequency * t).sin_cos();
+                    sum += term.amplitude * cos;
+                    sum_rate -= term.amplitude * term.frequency * sin;
+                }
+                rate = rate * t + value + sum_rate;
+                value = value * t + sum;
+            }
+            values[coordinate] = value;
+            rates[coordinate] = rate;
+        }
+
+        (values, rates)
+    }
+}
+
+/// Fields of a block header
+struct Header {
+    version: Option<Version>,
+    planet: Planet,
+    coordinate: usize,
+    power: usize,
+    count: usize,
+}
+
+/// Parses a header such as
+/// `VSOP87 VERSION D1    EARTH     VARIABLE 1 (LBR)       *T**0    559 TERMS ...`.
+fn parse_header(tokens: &[&str]) -> Option<Header> {
+    if tokens.len() < 10 || tokens[1] != "VERSION" || tokens[4] != "VARIABLE" {
+        return None;
+    }
+
+    let version = Version::from_code(tokens[2].chars().next()?);
+    let planet = Planet::from_name(tokens[3])?;
+    let coordinate = tokens[5].parse::<usize>().ok()?.checked_sub(1)?;
+    if coordinate > 2 {
+        return None;
+    }
+    let power = tokens[7].strip_prefix("*T**")?.parse().ok()?;
+    let count = tokens[8].parse().ok()?;
+
+    Some(Header {
+        version,
+        planet,
+        coordinate,
+        power,
+        count,
+    })
+}
+
+/// Parses a term line, whose last three fields are the amplitude, phase and frequency.
+fn parse_term(tokens: &[&str]) -> Option<Term> {
+    let [amplitude, phase, frequency] = tokens.get(tokens.len().checked_sub(3)?..)? else {
+        return None;
+    };
+
+    Some(Term {
+        amplitude: amplitude.parse().ok()?,
+        phase: phase.parse().ok()?,
+        frequency: frequency.parse().ok()?,
+    })
+}
+
+/// Returns the number of Julian millennia of TDB since J2000.0.
+fn millennia_since_j2000(dt: &DateTime) -> f64 {
+    dt.days_since_j2000(TimeScale::TDB) / DAYS_PER_JULIAN_MILLENNIUM
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+ VSOP87 VERSION A1    EARTH     VARIABLE 1 (XYZ)       *T**0      2 TERMS    HELIOCENTRIC DYNAMICAL ECLIPTIC AND EQUINOX J2000
+ 1311    1  0  0  0  0  0  0  0  0  0  0  0  0     0.99982928844 1.75348568475     0.99982928844      1.75348568475        6283.07584999140
+ 1311    2  0  0  0  0  0  0  0  0  0  0  0  0     0.00835257300 1.71034539450     0.00835257300      1.71034539450       12566.15169998280
+ VSOP87 VERSION A1    EARTH     VARIABLE 2 (XYZ)       *T**0      1 TERMS    HELIOCENTRIC DYNAMICAL ECLIPTIC AND EQUINOX J2000
+ 1321    1  0  0  0  0  0  0  0  0  0  0  0  0     0.99989211030 0.18265890456     0.99989211030      0.18265890456        6283.07584999140
+ VSOP87 VERSION A1    EARTH     VARIABLE 2 (XYZ)       *T**1      1 TERMS    HELIOCENTRIC DYNAMICAL ECLIPTIC AND EQUINOX J2000
+ 1321    1  0  0  0  0  0  0  0  0  0  0  0  0     0.00030330000 1.23456789012     0.00030330000      1.23456789012           0.00000000000
+ VSOP87 VERSION A1    EARTH     VARIABLE 3 (XYZ)       *T**0      0 TERMS    HELIOCENTRIC DYNAMICAL ECLIPTIC AND EQUINOX J2000
+";
+
+    #[test]
+    fn parse_test() {
+        let theory = Vsop87::parse(SAMPLE).unwrap();
+        assert_eq!(theory.planet(), Planet::Earth);
+        assert_eq!(theory.version(), Version::A);
+        assert_eq!(theory.term_count(), 4);
+        assert_eq!(theory.series[1].len(), 2);
+        assert_eq!(
+            theory.series[1][1][0],
+            Term {
+                amplitude: 0.000_303_3,
+                phase: 1.234_567_890_12,
+                frequency: 0.0,
+            }
+        );
+
+        // At J2000 the rectangular coordinates are the sums of A cos B
+        let dt = DateTime::from_julian_date(2_451_545.0, TimeScale::TDB);
+        let [x, y, z] = theory.coordinates(&dt);
+        let expected_x = 0.999_829_288_44 * 1.753_485_684_75_f64.cos()
+            + 0.008_352_573 * 1.710_345_394_5_f64.cos();
+        assert!((x - expected_x).abs() < 1e-15);
+        assert!((y - 0.999_892_110_3 * 0.182_658_904_56_f64.cos()).abs() < 1e-15);
+        assert_eq!(z, 0.0);
+    }
+
+    #[test]
+    fn parse_error_test() {
+        assert_eq!(Vsop87::parse(""), Err(Vsop87Error::Empty));
+
+        let truncated: String = SAMPLE.lines().take(2).collect::<Vec<_>>().join("\n");
+        assert_eq!(
+            Vsop87::parse(&truncated),
+            Err(Vsop87Error::TermCount {
+                line: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+
+        let orphan = SAMPLE.lines().skip(1).collect::<Vec<_>>().join("\n");
+        assert_eq!(
+            Vsop87::parse(&orphan),
+            Err(Vsop87Error::MissingHeader { line: 1 })
+        );
+
+        let invalid = SAMPLE.replace("12566.15169998280", "12566.1516999828x");
+        assert_eq!(
+            Vsop87::parse(&invalid),
+            Err(Vsop87Error::InvalidTerm { line: 3 })
+        );
+
+        let mixed = SAMPLE.replacen("EARTH     VARIABLE 2", "VENUS     VARIABLE 2", 1);
+        assert_eq!(
+            Vsop87::parse(&mixed),
+            Err(Vsop87Error::Inconsistent { line: 4 })
+        );
+
+        let version_e = SAMPLE.replace("VERSION A1", "VERSION E1");
+        assert_eq!(
+            Vsop87::parse(&version_e),
+            Err(Vsop87Error::UnsupportedVersion { line: 1 })
+        );
+        assert_eq!(
+            Vsop87Error::InvalidHeader { line: 7 }.to_string(),
+            "line 7: invalid block header"
+        );
+    }
+
+    #[test]
+    fn meeus_example_25b_test() {
+        // Meeus, Astronomical Algorithms, Example 25.b: Earth on 1992 October 13.0 TD
+        let earth = Vsop87::abridged(Planet::Earth).unwrap();
+        let dt = DateTime::from_julian_date(2_448_908.5, TimeScale::TDB);
+        let [l, b, r] = earth.coordinates(&dt);
+        assert!((l + 43.634_847_96).abs() < 1e-8);
+        assert!((b + 0.000_003_12).abs() < 1e-8);
+        assert!((r - 0.997_607_75).abs() < 1e-8);
+    }
+
+    #[test]
+    fn meeus_example_32a_test() {
+        // Meeus, Astronomical Algorithms, Example 32.a: Venus on 1992 December 20.0 TD
+        let venus = Vsop87::abridged(Planet::Venus).unwrap();
+        let dt = DateTime::from_julian_date(2_448_976.5, TimeScale::TDB);
+        let [l, b, r] = venus.coordinates(&dt);
+        assert!((l + 68.659_258_2).abs() < 1e-7);
+        assert!((b + 0.045_739_9).abs() < 1e-7);
+        assert!((r - 0.724_603).abs() < 1e-6);
+    }
+
+    #[test]
+    fn check_values_test() {
+        // Official VSOP87 check values for the Earth at JD 2451545.0, reproduced by the
+        // abridged series to within their truncation error
+        let earth = Vsop87::abridged(Planet::Earth).unwrap();
+        let dt = DateTime::from_julian_date(2_451_545.0, TimeScale::TDB);
+        let [l, b, r] = earth.coordinates(&dt);
+        assert!((l - 1.751_923_868_1).abs() < 1e-5);
+        assert!((b + 0.000_003_965_6).abs() < 1e-6);
+        assert!((r - 0.983_327_681_9).abs() < 1e-6);
+
+        // At J2000 the ecliptic of date is that of J2000, so version D matches version A
+        let position = earth.state(&dt).position;
+        assert!(
+            (position - Vector3::new(-0.177_135_458_6, 0.967_241_623_7, -0.000_003_9)).norm()
+                < 1e-5
+        );
+    }
+
+    #[test]
+    fn velocity_test() {
+        let step = 0.01;
+        for planet in [Planet::Venus, Planet::Earth] {
+            let theory = Vsop87::abridged(planet).unwrap();
+            let dt = DateTime::from_julian_date(2_460_000.5, TimeScale::TDB);
+            let before = theory.state(&DateTime::from_julian_date(
+                2_460_000.5 - step,
+                TimeScale::TDB,
+            ));
+            let after = theory.state(&DateTime::from_julian_date(
+                2_460_000.5 + step,
+                TimeScale::TDB,
+            ));
+            let numerical = (after.position - before.position) / (2.0 * step);
+            let state = theory.state(&dt);
+            assert!((state.velocity - numerical).norm() < 1e-9);
+        }
+
+        // The Earth moves at about 2π AU per year
+        let earth = Vsop87::abridged(Planet::Earth).unwrap();
+        let speed = earth
+            .state(&DateTime::from_julian_date(2_451_545.0, TimeScale::TDB))
+            .velocity
+            .norm();
+        assert!((speed - 0.017_5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn truncation_test() {
+        let earth = Vsop87::abridged(Planet::Earth).unwrap();
+        let coarse = earth.truncated(1e-6);
+        assert!(coarse.term_count() < earth.term_count());
+        assert_eq!(earth.truncated(0.0), earth);
+
+        let dt = DateTime::from_julian_date(2_455_000.0, TimeScale::TDB);
+        let difference = (coarse.state(&dt).position - earth.state(&dt).position).norm();
+        assert!(difference > 0.0 && difference < 1e-4);
+
+        assert!(Vsop87::abridged(Planet::Neptune).is_none());
+    }
+}
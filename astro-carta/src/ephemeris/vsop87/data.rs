@@ -0,0 +1,292 @@
+// The published tables round phases of π to a few digits
+#![allow(clippy::approx_constant)]
+
+/// A series of terms for one coordinate, indexed by power of time.
+///
+/// The abridged VSOP87D coefficients below are those published in Meeus, Astronomical
+/// Algorithms, Appendix III. Each term is `(A, B, C)` with the amplitude `A` in units of 1e-8
+/// radians or AU, the phase `B` in radians and the frequency `C` in radians per Julian millennium.
+pub(super) type CoordinateSeries = &'static [&'static [(f64, f64, f64)]];
+
+pub(super) const EARTH: [CoordinateSeries; 3] = [
+    &[
+        &EARTH_L0, &EARTH_L1, &EARTH_L2, &EARTH_L3, &EARTH_L4, &EARTH_L5,
+    ],
+    &[&EARTH_B0, &EARTH_B1],
+    &[&EARTH_R0, &EARTH_R1, &EARTH_R2, &EARTH_R3, &EARTH_R4],
+];
+
+pub(super) const VENUS: [CoordinateSeries; 3] = [
+    &[
+        &VENUS_L0, &VENUS_L1, &VENUS_L2, &VENUS_L3, &VENUS_L4, &VENUS_L5,
+    ],
+    &[&VENUS_B0, &VENUS_B1, &VENUS_B2, &VENUS_B3, &VENUS_B4],
+    &[&VENUS_R0, &VENUS_R1, &VENUS_R2, &VENUS_R3, &VENUS_R4],
+];
+
+#[rustfmt::skip]
+const EARTH_L0: [(f64, f64, f64); 64] = [
+    (175_347_046.0, 0.0, 0.0), (3_341_656.0, 4.669_256_8, 6_283.075_85),
+    (34_894.0, 4.626_1, 12_566.151_7), (3_497.0, 2.744_1, 5_753.384_9),
+    (3_418.0, 2.828_9, 3.523_1), (3_136.0, 3.627_7, 77_713.771_5),
+    (2_676.0, 4.418_1, 7_860.419_4), (2_343.0, 6.135_2, 3_930.209_7),
+    (1_324.0, 0.742_5, 11_506.769_8), (1_273.0, 2.037_1, 529.691),
+    (1_199.0, 1.109_6, 1_577.343_5), (990.0, 5.233, 5_884.927),
+    (902.0, 2.045, 26.298), (857.0, 3.508, 398.149),
+    (780.0, 1.179, 5_223.694), (753.0, 2.533, 5_507.553),
+    (505.0, 4.583, 18_849.228), (492.0, 4.205, 775.523),
+    (357.0, 2.92, 0.067), (317.0, 5.849, 11_790.629),
+    (284.0, 1.899, 796.298), (271.0, 0.315, 10_977.079),
+    (243.0, 0.345, 5_486.778), (206.0, 4.806, 2_544.314),
+    (205.0, 1.869, 5_573.143), (202.0, 2.458, 6_069.777),
+    (156.0, 0.833, 213.299), (132.0, 3.411, 2_942.463),
+    (126.0, 1.083, 20.775), (115.0, 0.645, 0.98),
+    (103.0, 0.636, 4_694.003), (102.0, 0.976, 15_720.839),
+    (102.0, 4.267, 7.114), (99.0, 6.21, 2_146.17),
+    (98.0, 0.68, 155.42), (86.0, 5.98, 161_000.69),
+    (85.0, 1.3, 6_275.96), (85.0, 3.67, 71_430.7),
+    (80.0, 1.81, 17_260.15), (79.0, 3.04, 12_036.46),
+    (75.0, 1.76, 5_088.63), (74.0, 3.5, 3_154.69),
+    (74.0, 4.68, 801.82), (70.0, 0.83, 9_437.76),
+    (62.0, 3.98, 8_827.39), (61.0, 1.82, 7_084.9),
+    (57.0, 2.78, 6_286.6), (56.0, 4.39, 14_143.5),
+    (56.0, 3.47, 6_279.55), (52.0, 0.19, 12_139.55),
+    (52.0, 1.33, 1_748.02), (51.0, 0.28, 5_856.48),
+    (49.0, 0.49, 1_194.45), (41.0, 5.37, 8_429.24),
+    (41.0, 2.4, 19_651.05), (39.0, 6.17, 10_447.39),
+    (37.0, 6.04, 10_213.29), (37.0, 2.57, 1_059.38),
+    (36.0, 1.71, 2_352.87), (36.0, 1.78, 6_812.77),
+    (33.0, 0.59, 17_789.85), (30.0, 0.44, 83_996.85),
+    (30.0, 2.74, 1_349.87), (25.0, 3.16, 4_690.48),
+];
+
+#[rustfmt::skip]
+const EARTH_L1: [(f64, f64, f64); 34] = [
+    (628_331_966_747.0, 0.0, 0.0), (206_059.0, 2.678_235, 6_283.075_85),
+    (4_303.0, 2.635_1, 12_566.151_7), (425.0, 1.59, 3.523),
+    (119.0, 5.796, 26.298), (109.0, 2.966, 1_577.344),
+    (93.0, 2.59, 18_849.23), (72.0, 1.14, 529.69),
+    (68.0, 1.87, 398.15), (67.0, 4.41, 5_507.55),
+    (59.0, 2.89, 5_223.69), (56.0, 2.17, 155.42),
+    (45.0, 0.4, 796.3), (36.0, 0.47, 775.52),
+    (29.0, 2.65, 7.11), (21.0, 5.34, 0.98),
+    (19.0, 1.85, 5_486.78), (19.0, 4.97, 213.3),
+    (17.0, 2.99, 6_275.96), (16.0, 0.03, 2_544.31),
+    (16.0, 1.43, 2_146.17), (15.0, 1.21, 10_977.08),
+    (12.0, 2.83, 1_748.02), (12.0, 3.26, 5_088.63),
+    (12.0, 5.27, 1_194.45), (12.0, 2.08, 4_694.0),
+    (11.0, 0.77, 553.57), (10.0, 1.3, 6_286.6),
+    (10.0, 4.24, 1_349.87), (9.0, 2.7, 242.73),
+    (9.0, 5.64, 951.72), (8.0, 5.3, 2_352.87),
+    (6.0, 2.65, 9_437.76), (6.0, 4.67, 4_690.48),
+];
+
+#[rustfmt::skip]
+const EARTH_L2: [(f64, f64, f64); 20] = [
+    (52_919.0, 0.0, 0.0), (8_720.0, 1.072_1, 6_283.075_8),
+    (309.0, 0.867, 12_566.152), (27.0, 0.05, 3.52),
+    (16.0, 5.19, 26.3), (16.0, 3.68, 155.42),
+    (10.0, 0.76, 18_849.23), (9.0, 2.06, 77_713.77),
+    (7.0, 0.83, 775.52), (5.0, 4.66, 1_577.34),
+    (4.0, 1.03, 7.11), (4.0, 3.44, 5_573.14),
+    (3.0, 5.14, 796.3), (3.0, 6.05, 5_507.55),
+    (3.0, 1.19, 242.73), (3.0, 6.12, 529.69),
+    (3.0, 0.31, 398.15), (3.0, 2.28, 553.57),
+    (2.0, 4.38, 5_223.69), (2.0, 3.75, 0.98),
+];
+
+#[rustfmt::skip]
+const EARTH_L3: [(f64, f64, f64); 7] = [
+    (289.0, 5.844, 6_283.076), (35.0, 0.0, 0.0),
+    (17.0, 5.49, 12_566.15), (3.0, 5.2, 155.42),
+    (1.0, 4.72, 3.52), (1.0, 5.3, 18_849.23),
+    (1.0, 5.97, 242.73),
+];
+
+#[rustfmt::skip]
+const EARTH_L4: [(f64, f64, f64); 3] = [
+    (114.0, 3.142, 0.0), (8.0, 4.13, 6_283.08),
+    (1.0, 3.84, 12_566.15),
+];
+
+#[rustfmt::skip]
+const EARTH_L5: [(f64, f64, f64); 1] = [
+    (1.0, 3.14, 0.0),
+];
+
+#[rustfmt::skip]
+const EARTH_B0: [(f64, f64, f64); 5] = [
+    (280.0, 3.199, 84_334.662), (102.0, 5.422, 5_507.553),
+    (80.0, 3.88, 5_223.69), (44.0, 3.7, 2_352.87),
+    (32.0, 4.0, 1_577.34),
+];
+
+#[rustfmt::skip]
+const EARTH_B1: [(f64, f64, f64); 2] = [
+    (9.0, 3.9, 5_507.55), (6.0, 1.73, 5_223.69),
+];
+
+#[rustfmt::skip]
+const EARTH_R0: [(f64, f64, f64); 40] = [
+    (100_013_989.0, 0.0, 0.0), (1_670_700.0, 3.098_463_5, 6_283.075_85),
+    (13_956.0, 3.055_25, 12_566.151_7), (3_084.0, 5.198_5, 77_713.771_5),
+    (1_628.0, 1.173_9, 5_753.384_9), (1_576.0, 2.846_9, 7_860.419_4),
+    (925.0, 5.453, 11_506.77), (542.0, 4.564, 3_930.21),
+    (472.0, 3.661, 5_884.927), (346.0, 0.964, 5_507.553),
+    (329.0, 5.9, 5_223.694), (307.0, 0.299, 5_573.143),
+    (243.0, 4.273, 11_790.629), (212.0, 5.847, 1_577.344),
+    (186.0, 5.022, 10_977.079), (175.0, 3.012, 18_849.228),
+    (110.0, 5.055, 5_486.778), (98.0, 0.89, 6_069.78),
+    (86.0, 5.69, 15_720.84), (86.0, 1.27, 161_000.69),
+    (65.0, 0.27, 17_260.15), (63.0, 0.92, 529.69),
+    (57.0, 2.01, 83_996.85), (56.0, 5.24, 71_430.7),
+    (49.0, 3.25, 2_544.31), (47.0, 2.58, 775.52),
+    (45.0, 5.54, 9_437.76), (43.0, 6.01, 6_275.96),
+    (39.0, 5.36, 4_694.0), (38.0, 2.39, 8_827.39),
+    (37.0, 0.83, 19_651.05), (37.0, 4.9, 12_139.55),
+    (36.0, 1.67, 12_036.46), (35.0, 1.84, 2_942.46),
+    (33.0, 0.24, 7_084.9), (32.0, 0.18, 5_088.63),
+    (32.0, 1.78, 398.15), (28.0, 1.21, 6_286.6),
+    (28.0, 1.9, 6_279.55), (26.0, 4.59, 10_447.39),
+];
+
+#[rustfmt::skip]
+const EARTH_R1: [(f64, f64, f64); 10] = [
+    (103_019.0, 1.107_49, 6_283.075_85), (1_721.0, 1.064_4, 12_566.151_7),
+    (702.0, 3.142, 0.0), (32.0, 1.02, 18_849.23),
+    (31.0, 2.84, 5_507.55), (25.0, 1.32, 5_223.69),
+    (18.0, 1.42, 1_577.34), (10.0, 5.91, 10_977.08),
+    (9.0, 1.42, 6_275.96), (9.0, 0.27, 5_486.78),
+];
+
+#[rustfmt::skip]
+const EARTH_R2: [(f64, f64, f64); 6] = [
+    (4_359.0, 5.784_6, 6_283.075_8), (124.0, 5.579, 12_566.152),
+    (12.0, 3.14, 0.0), (9.0, 3.63, 77_713.77),
+    (6.0, 1.87, 5_573.14), (3.0, 5.47, 18_849.23),
+];
+
+#[rustfmt::skip]
+const EARTH_R3: [(f64, f64, f64); 2] = [
+    (145.0, 4.273, 6_283.076), (7.0, 3.92, 12_566.15),
+];
+
+#[rustfmt::skip]
+const EARTH_R4: [(f64, f64, f64); 1] = [
+    (4.0, 2.56, 6_283.08),
+];
+
+#[rustfmt::skip]
+const VENUS_L0: [(f64, f64, f64); 24] = [
+    (317_614_667.0, 0.0, 0.0), (1_353_968.0, 5.593_133_2, 10_213.285_546_2),
+    (89_892.0, 5.306_5, 20_426.571_09), (5_477.0, 4.416_3, 7_860.419_4),
+    (3_456.0, 2.699_6, 11_790.629_1), (2_372.0, 2.993_8, 3_930.209_7),
+    (1_664.0, 4.250_2, 1_577.343_5), (1_438.0, 4.157_5, 9_683.594_6),
+    (1_317.0, 5.186_7, 26.298_3), (1_201.0, 6.153_6, 30_639.856_6),
+    (769.0, 0.816, 9_437.763), (761.0, 1.95, 529.691),
+    (708.0, 1.065, 775.523), (585.0, 3.998, 191.448),
+    (500.0, 4.123, 15_720.839), (429.0, 3.586, 19_367.189),
+    (327.0, 5.677, 5_507.553), (326.0, 4.591, 10_404.734),
+    (232.0, 3.163, 9_153.904), (180.0, 4.653, 1_109.379),
+    (155.0, 5.57, 19_651.048), (128.0, 4.226, 20.775),
+    (128.0, 0.962, 5_661.332), (106.0, 1.537, 801.821),
+];
+
+#[rustfmt::skip]
+const VENUS_L1: [(f64, f64, f64); 12] = [
+    (1_021_352_943_053.0, 0.0, 0.0), (95_708.0, 2.464_24, 10_213.285_55),
+    (14_445.0, 0.516_25, 20_426.571_09), (213.0, 1.795, 30_639.857),
+    (174.0, 2.655, 26.298), (152.0, 6.106, 1_577.344),
+    (82.0, 5.7, 191.45), (70.0, 2.68, 9_437.76),
+    (52.0, 3.6, 775.52), (38.0, 1.03, 529.69),
+    (30.0, 1.25, 5_507.55), (25.0, 6.11, 10_404.73),
+];
+
+#[rustfmt::skip]
+const VENUS_L2: [(f64, f64, f64); 8] = [
+    (54_127.0, 0.0, 0.0), (3_891.0, 0.345_1, 10_213.285_5),
+    (1_338.0, 2.020_1, 20_426.571_1), (24.0, 2.05, 26.3),
+    (19.0, 3.54, 30_639.86), (10.0, 3.97, 775.52),
+    (7.0, 1.52, 1_577.34), (6.0, 1.0, 191.45),
+];
+
+#[rustfmt::skip]
+const VENUS_L3: [(f64, f64, f64); 3] = [
+    (136.0, 4.804, 10_213.286), (78.0, 3.67, 20_426.57),
+    (26.0, 0.0, 0.0),
+];
+
+#[rustfmt::skip]
+const VENUS_L4: [(f64, f64, f64); 3] = [
+    (114.0, 3.141_6, 0.0), (3.0, 5.21, 20_426.57),
+    (2.0, 2.51, 10_213.29),
+];
+
+#[rustfmt::skip]
+const VENUS_L5: [(f64, f64, f64); 1] = [
+    (1.0, 3.14, 0.0),
+];
+
+#[rustfmt::skip]
+const VENUS_B0: [(f64, f64, f64); 9] = [
+    (5_923_638.0, 0.267_027_8, 10_213.285_546_2), (40_108.0, 1.147_37, 20_426.571_09),
+    (32_815.0, 3.141_59, 0.0), (1_011.0, 1.089_5, 30_639.856_6),
+    (149.0, 6.254, 18_073.705), (138.0, 0.86, 1_577.344),
+    (130.0, 3.672, 9_437.763), (120.0, 3.705, 2_352.866),
+    (108.0, 4.539, 22_003.915),
+];
+
+#[rustfmt::skip]
+const VENUS_B1: [(f64, f64, f64); 4] = [
+    (513_348.0, 1.803_643, 10_213.285_546), (4_380.0, 3.386_2, 20_426.571_1),
+    (199.0, 0.0, 0.0), (197.0, 2.53, 30_639.857),
+];
+
+#[rustfmt::skip]
+const VENUS_B2: [(f64, f64, f64); 4] = [
+    (22_378.0, 3.385_09, 10_213.285_55), (282.0, 0.0, 0.0),
+    (173.0, 5.256, 20_426.571), (27.0, 3.87, 30_639.86),
+];
+
+#[rustfmt::skip]
+const VENUS_B3: [(f64, f64, f64); 4] = [
+    (647.0, 4.992, 10_213.286), (20.0, 3.14, 0.0),
+    (6.0, 0.77, 20_426.57), (3.0, 5.44, 30_639.86),
+];
+
+#[rustfmt::skip]
+const VENUS_B4: [(f64, f64, f64); 1] = [
+    (14.0, 0.32, 10_213.29),
+];
+
+#[rustfmt::skip]
+const VENUS_R0: [(f64, f64, f64); 12] = [
+    (72_334_821.0, 0.0, 0.0), (489_824.0, 4.021_518, 10_213.285_546),
+    (1_658.0, 4.902_1, 20_426.571_1), (1_632.0, 2.845_5, 7_860.419_4),
+    (1_378.0, 1.128_5, 11_790.629_1), (498.0, 2.587, 9_683.595),
+    (374.0, 1.423, 3_930.21), (264.0, 5.529, 9_437.763),
+    (237.0, 2.551, 15_720.839), (222.0, 2.013, 19_367.189),
+    (126.0, 2.728, 1_577.344), (119.0, 3.02, 10_404.734),
+];
+
+#[rustfmt::skip]
+const VENUS_R1: [(f64, f64, f64); 3] = [
+    (34_551.0, 0.891_99, 10_213.285_55), (234.0, 1.772, 20_426.571),
+    (234.0, 3.142, 0.0),
+];
+
+#[rustfmt::skip]
+const VENUS_R2: [(f64, f64, f64); 3] = [
+    (1_407.0, 5.063_7, 10_213.285_5), (16.0, 5.47, 20_426.57),
+    (13.0, 0.0, 0.0),
+];
+
+#[rustfmt::skip]
+const VENUS_R3: [(f64, f64, f64); 1] = [
+    (50.0, 3.22, 10_213.29),
+];
+
+#[rustfmt::skip]
+const VENUS_R4: [(f64, f64, f64); 1] = [
+    (1.0, 0.92, 10_213.29),
+];
@@ -0,0 +1,34 @@
+pub mod ellipsoid;
+pub mod geodesic;
+pub mod local;
+
+pub use ellipsoid::Ellipsoid;
+pub use geodesic::Geodesic;
+pub use local::LocalFrame;
+
+/// Geodetic coordinates relative to a reference ellipsoid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geodetic {
+    /// Geodetic latitude in radians, positive north
+    pub latitude: f64,
+    /// Longitude in radians, positive east
+    pub longitude: f64,
+    /// Height above the ellipsoid in meters
+    pub height: f64,
+}
+
+impl Geodetic {
+    /// Constructs new geodetic coordinates from a latitude and longitude in radians and a height in meters.
+    pub fn new(latitude: f64, longitude: f64, height: f64) -> Self {
+        Geodetic {
+            latitude,
+            longitude,
+            height,
+        }
+    }
+
+    /// Constructs new geodetic coordinates from a latitude and longitude in degrees and a height in meters.
+    pub fn from_degrees(latitude: f64, longitude: f64, height: f64) -> Self {
+        Geodetic::new(latitude.to_radians(), longitude.to_radians(), height)
+    }
+}
@@ -0,0 +1,268 @@
+use super::Geodetic;
+use crate::math::Vector3;
+
+/// Maximum number of iterations for the iterative Cartesian to geodetic conversion
+const MAX_ITERATIONS: usize = 20;
+
+/// A reference ellipsoid of revolution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ellipsoid {
+    /// Equatorial radius in meters
+    pub semi_major_axis: f64,
+    /// Flattening (a - b) / a
+    pub flattening: f64,
+}
+
+impl Ellipsoid {
+    /// The World Geodetic System 1984 ellipsoid
+    pub const WGS84: Ellipsoid = Ellipsoid {
+        semi_major_axis: 6_378_137.0,
+        flattening: 1.0 / 298.257_223_563,
+    };
+
+    /// The Geodetic Reference System 1980 ellipsoid
+    pub const GRS80: Ellipsoid = Ellipsoid {
+        semi_major_axis: 6_378_137.0,
+        flattening: 1.0 / 298.257_222_101,
+    };
+
+    /// The IERS Conventions (2010) ellipsoid
+    pub const IERS2010: Ellipsoid = Ellipsoid {
+        semi_major_axis: 6_378_136.6,
+        flattening: 1.0 / 298.256_42,
+    };
+
+    /// Constructs a custom ellipsoid from its equatorial radius in meters and flattening.
+    ///
+    /// A flattening of zero gives a sphere; a negative flattening gives a prolate ellipsoid.
+    pub fn new(semi_major_axis: f64, flattening: f64) -> Self {
+        Ellipsoid {
+            semi_major_axis,
+            flattening,
+        }
+    }
+
+    /// Constructs a custom ellipsoid from its equatorial radius in meters and inverse flattening.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use astro_carta::geodesy::Ellipsoid;
+    ///
+    /// let wgs84 = Ellipsoid::from_inverse_flattening(6_378_137.0, 298.257_223_563);
+    /// assert_eq!(wgs84, Ellipsoid::WGS84);
+    /// ```
+    pub fn from_inverse_flattening(semi_major_axis: f64, inverse_flattening: f64) -> Self {
+        Ellipsoid::new(semi_major_axis, 1.0 / inverse_flattening)
+    }
+
+    /// Returns the polar radius in meters.
+    pub fn semi_minor_axis(&self) -> f64 {
+        self.semi_major_axis * (1.0 - self.flattening)
+    }
+
+    /// Returns the square of the first eccentricity.
+    pub fn eccentricity_squared(&self) -> f64 {
+        self.flattening * (2.0 - self.flattening)
+    }
+
+    /// Returns the square of the second eccentricity.
+    pub fn second_eccentricity_squared(&self) -> f64 {
+        self.eccentricity_squared() / ((1.0 - self.flattening) * (1.0 - self.flattening))
+    }
+
+    /// Returns the radius of curvature in the prime vertical at the given geodetic latitude in meters.
+    pub fn prime_vertical_radius(&self, latitude: f64) -> f64 {
+        let sin_lat = latitude.sin();
+        self.semi_major_axis / (1.0 - self.eccentricity_squared() * sin_lat * sin_lat).sqrt()
+    }
+
+    /// Converts geodetic coordinates to Earth-centered, Earth-fixed Cartesian coordinates in meters.
+    pub fn geodetic_to_cartesian(&self, geodetic: &Geodetic) -> Vector3 {
+        let e2 = self.eccentricity_squared();
+        let (sin_lat, cos_lat) = geodetic.latitude.sin_cos();
+        let (sin_lon, cos_lon) = geodetic.longitude.sin_cos();
+        let n = self.prime_vertical_radius(geodetic.latitude);
+
+        Vector3::new(
+            (n + geodetic.height) * cos_lat * cos_lon,
+            (n + geodetic.height) * cos_lat * sin_lon,
+            (n * (1.0 - e2) + geodetic.height) * sin_lat,
+        )
+    }
+
+    /// Converts Earth-centered, Earth-fixed Cartesian coordinates in meters to geodetic coordinates
+    /// using the closed-form solution of Vermeille (2004).
+    ///
+    /// The solution is exact for an oblate ellipsoid at any point outside the evolute of the
+    /// meridian ellipse, which for the Earth lies within about 43 km of the center.
+    pub fn cartesian_to_geodetic(&self, position: &Vector3) -> Geodetic {
+        let a = self.semi_major_axis;
+        let e2 = self.eccentricity_squared();
+        let e4 = e2 * e2;
+        let rho = position.x.hypot(position.y);
+        let longitude = position.y.atan2(position.x);
+
+        if e2 <= 0.0 {
+            let r = rho.hypot(position.z);
+            return Geodetic::new(position.z.atan2(rho), longitude, r - a);
+        }
+
+        let p = rho * rho / (a * a);
+        let q = (1.0 - e2) * position.z * position.z / (a * a);
+        let r = (p + q - e4) / 6.0;
+        let s = e4 * p * q / (4.0 * r * r * r);
+        let t = (1.0 + s + (s * (2.0 + s)).sqrt()).cbrt();
+        let u = r * (1.0 + t + 1.0 / t);
+        let v = (u * u + e4 * q).sqrt();
+        let w = e2 * (u + v - q) / (2.0 * v);
+        let k = (u + v + w * w).sqrt() - w;
+        let d = k * rho / (k + e2);
+        let dz = d.hypot(position.z);
+
+        Geodetic::new(
+            2.0 * position.z.atan2(d + dz),
+            longitude,
+            (k + e2 - 1.0) / k * dz,
+        )
+    }
+
+    /// Converts Earth-centered, Earth-fixed Cartesian coordinates in meters to geodetic coordinates
+    /// by fixed-point iteration on the latitude.
+    ///
+    /// This is slower than [`Ellipsoid::cartesian_to_geodetic`] but valid everywhere except at
+    /// the center, and for prolate as well as oblate ellipsoids.
+    pub fn cartesian_to_geodetic_iterative(&self, position: &Vector3) -> Geodetic {
+        let e2 = self.eccentricity_squared();
+        let rho = position.x.hypot(position.y);
+        let longitude = position.y.atan2(position.x);
+
+        let mut latitude = position.z.atan2(rho * (1.0 - e2));
+        for _ in 0..MAX_ITERATIONS {
+            let n = self.prime_vertical_radius(latitude);
+            let next = (position.z + e2 * n * latitude.sin()).atan2(rho);
+            let delta = next - latitude;
+            latitude = next;
+            if delta.abs() < 1e-15 {
+                break;
+            }
+        }
+
+        // Height formula that is well conditioned at all latitudes
+        let (sin_lat, cos_lat) = latitude.sin_cos();
+        let height = rho * cos_lat + position.z * sin_lat
+            - self.semi_major_axis * (1.0 - e2 * sin_lat * sin_lat).sqrt();
+
+        Geodetic::new(latitude, longitude, height)
+    }
+
+    /// Converts a geodetic latitude to a geocentric latitude, both in radians, on the ellipsoid surface.
+    pub fn geocentric_latitude(&self, geodetic_latitude: f64) -> f64 {
+        let (sin_lat, cos_lat) = geodetic_latitude.sin_cos();
+        let f1 = 1.0 - self.flattening;
+        (f1 * f1 * sin_lat).atan2(cos_lat)
+    }
+
+    /// Converts a geocentric latitude to a geodetic latitude, both in radians, on the ellipsoid surface.
+    pub fn geodetic_latitude(&self, geocentric_latitude: f64) -> f64 {
+        let (sin_lat, cos_lat) = geocentric_latitude.sin_cos();
+        let f1 = 1.0 - self.flattening;
+        sin_lat.atan2(f1 * f1 * cos_lat)
+    }
+}
+
+impl Default for Ellipsoid {
+    fn default() -> Self {
+        Ellipsoid::WGS84
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_test() {
+        assert!((Ellipsoid::WGS84.semi_minor_axis() - 6_356_752.314_245).abs() < 1e-6);
+        assert!((Ellipsoid::GRS80.semi_minor_axis() - 6_356_752.314_140).abs() < 1e-6);
+        assert!((Ellipsoid::WGS84.eccentricity_squared() - 6.694_379_990_14e-3).abs() < 1e-14);
+        assert!(
+            (Ellipsoid::WGS84.second_eccentricity_squared() - 6.739_496_742_28e-3).abs() < 1e-14
+        );
+    }
+
+    #[test]
+    fn sofa_gd2gc_test() {
+        // SOFA t_sofa_c test case for iauGd2gc
+        let xyz = Ellipsoid::WGS84.geodetic_to_cartesian(&Geodetic::new(-0.5, 3.1, 2500.0));
+        assert!((xyz.x + 5_599_000.557_704_995).abs() < 1e-7);
+        assert!((xyz.y - 233_011.672_234_792_03).abs() < 1e-7);
+        assert!((xyz.z + 3_040_909.470_698_336).abs() < 1e-7);
+    }
+
+    #[test]
+    fn sofa_gc2gd_test() {
+        // SOFA t_sofa_c test cases for iauGc2gd
+        let xyz = Vector3::new(2e6, 3e6, 5.244e6);
+
+        let wgs84 = Ellipsoid::WGS84.cartesian_to_geodetic(&xyz);
+        assert!((wgs84.longitude - 0.982_793_723_247_329).abs() < 1e-14);
+        assert!((wgs84.latitude - 0.971_601_848_190_754_6).abs() < 1e-14);
+        assert!((wgs84.height - 331.417_246_142_606).abs() < 1e-8);
+
+        let grs80 = Ellipsoid::GRS80.cartesian_to_geodetic(&xyz);
+        assert!((grs80.latitude - 0.971_601_848_206_078_5).abs() < 1e-14);
+        assert!((grs80.height - 331.417_317_548_443_5).abs() < 1e-8);
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let ellipsoids = [
+            Ellipsoid::WGS84,
+            Ellipsoid::IERS2010,
+            Ellipsoid::new(6_000_000.0, 0.0),
+        ];
+        for ellipsoid in ellipsoids {
+            for &latitude in &[-90.0, -60.0, -1e-9, 0.0, 30.0, 89.999, 90.0] {
+                for &height in &[-5_000.0, 0.0, 400e3, 36_000e3] {
+                    let geodetic = Geodetic::from_degrees(latitude, -120.0, height);
+                    let xyz = ellipsoid.geodetic_to_cartesian(&geodetic);
+
+                    let closed = ellipsoid.cartesian_to_geodetic(&xyz);
+                    let iterative = ellipsoid.cartesian_to_geodetic_iterative(&xyz);
+                    for result in [closed, iterative] {
+                        assert!((result.latitude - geodetic.latitude).abs() < 1e-12);
+                        assert!((result.height - geodetic.height).abs() < 1e-6);
+                        if latitude.abs() < 90.0 {
+                            assert!((result.longitude - geodetic.longitude).abs() < 1e-12);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn prolate_iterative_test() {
+        let prolate = Ellipsoid::new(6_000_000.0, -0.01);
+        let geodetic = Geodetic::from_degrees(47.0, 10.0, 1_000.0);
+        let result =
+            prolate.cartesian_to_geodetic_iterative(&prolate.geodetic_to_cartesian(&geodetic));
+        assert!((result.latitude - geodetic.latitude).abs() < 1e-12);
+        assert!((result.height - geodetic.height).abs() < 1e-6);
+    }
+
+    #[test]
+    fn geocentric_latitude_test() {
+        // The geocentric latitude differs from the geodetic by at most about 11.5' at 45°
+        let wgs84 = Ellipsoid::WGS84;
+        let phi = 45.0_f64.to_radians();
+        let psi = wgs84.geocentric_latitude(phi);
+        assert!(((phi - psi).to_degrees() * 60.0 - 11.54).abs() < 0.01);
+        assert!((wgs84.geodetic_latitude(psi) - phi).abs() < 1e-15);
+
+        // Consistent with the direction of the Cartesian position on the surface
+        let xyz = wgs84.geodetic_to_cartesian(&Geodetic::new(phi, 0.0, 0.0));
+        assert!((xyz.z.atan2(xyz.x) - psi).abs() < 1e-15);
+    }
+}
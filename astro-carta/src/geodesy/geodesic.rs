@@ -0,0 +1,1157 @@
+use super::Ellipsoid;
+use std::f64::consts::PI;
+
+/// Order of the series expansions
+const ORDER: usize = 6;
+const NC1: usize = ORDER;
+const NC1P: usize = ORDER;
+const NC2: usize = ORDER;
+const NA3: usize = ORDER;
+const NA3X: usize = NA3;
+const NC3: usize = ORDER;
+const NC3X: usize = (NC3 * (NC3 - 1)) / 2;
+
+const MAXIT1: usize = 20;
+const MAXIT2: usize = MAXIT1 + f64::MANTISSA_DIGITS as usize + 10;
+
+const TOL0: f64 = f64::EPSILON;
+const TOL1: f64 = 200.0 * TOL0;
+// sqrt(TOL0)
+const TOL2: f64 = 1.490_116_119_384_765_6e-8;
+const TOLB: f64 = TOL0 * TOL2;
+const XTHRESH: f64 = 1000.0 * TOL2;
+// sqrt(f64::MIN_POSITIVE)
+const TINY: f64 = 1.491_668_146_240_041_3e-154;
+
+/// Solution of the inverse geodesic problem
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InverseSolution {
+    /// Length of the shortest geodesic in meters
+    pub distance: f64,
+    /// Azimuth at the first point in radians, measured from north towards east
+    pub initial_azimuth: f64,
+    /// Forward azimuth at the second point in radians, measured from north towards east
+    pub final_azimuth: f64,
+    /// Arc length on the auxiliary sphere in radians
+    pub arc_length: f64,
+}
+
+/// Solution of the direct geodesic problem
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirectSolution {
+    /// Geodetic latitude of the end point in radians
+    pub latitude: f64,
+    /// Longitude of the end point in radians, in the range [-π, π]
+    pub longitude: f64,
+    /// Forward azimuth at the end point in radians, measured from north towards east
+    pub final_azimuth: f64,
+}
+
+/// Solver for geodesic problems on a given ellipsoid
+///
+/// Follows Karney, "Algorithms for geodesics", J. Geodesy 87, 43-55 (2013), with series
+/// expansions to sixth order in the third flattening. Angles are handled in degrees internally
+/// so that the range reductions are exact.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Geodesic {
+    a: f64,
+    f: f64,
+    f1: f64,
+    ep2: f64,
+    n: f64,
+    b: f64,
+    etol2: f64,
+    a3x: [f64; NA3X],
+    c3x: [f64; NC3X],
+}
+
+impl Geodesic {
+    /// Constructs a geodesic solver for the given ellipsoid.
+    pub fn new(ellipsoid: &Ellipsoid) -> Self {
+        let a = ellipsoid.semi_major_axis;
+        let f = ellipsoid.flattening;
+        let f1 = 1.0 - f;
+        let e2 = f * (2.0 - f);
+        let n = f / (2.0 - f);
+
+        let mut geodesic = Geodesic {
+            a,
+            f,
+            f1,
+            ep2: e2 / (f1 * f1),
+            n,
+            b: a * f1,
+            etol2: 0.1 * TOL2 / (f.abs().max(0.001) * (1.0 - f / 2.0).min(1.0) / 2.0).sqrt(),
+            a3x: [0.0; NA3X],
+            c3x: [0.0; NC3X],
+        };
+        geodesic.a3_coefficients();
+        geodesic.c3_coefficients();
+        geodesic
+    }
+
+    /// Solves the inverse problem: the shortest geodesic between two points.
+    ///
+    /// # Arguments
+    ///
+    /// * `latitude1`, `longitude1` - Geodetic coordinates of the first point in radians.
+    /// * `latitude2`, `longitude2` - Geodetic coordinates of the second point in radians.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use astro_carta::geodesy::{Ellipsoid, Geodesic};
+    ///
+    /// // Wellington to Salamanca, nearly antipodal
+    /// let geodesic = Geodesic::new(&Ellipsoid::WGS84);
+    /// let solution = geodesic.inverse(
+    ///     -41.32_f64.to_radians(),
+    ///     174.81_f64.to_radians(),
+    ///     40.96_f64.to_radians(),
+    ///     -5.50_f64.to_radians(),
+    /// );
+    /// assert!((solution.distance - 19_959_679.267).abs() < 1e-3);
+    /// ```
+    pub fn inverse(
+        &self,
+        latitude1: f64,
+        longitude1: f64,
+        latitude2: f64,
+        longitude2: f64,
+    ) -> InverseSolution {
+        let (a12, s12, salp1, calp1, salp2, calp2) = self.inverse_internal(
+            latitude1.to_degrees(),
+            longitude1.to_degrees(),
+            latitude2.to_degrees(),
+            longitude2.to_degrees(),
+        );
+        InverseSolution {
+            distance: s12,
+            initial_azimuth: atan2d(salp1, calp1).to_radians(),
+            final_azimuth: atan2d(salp2, calp2).to_radians(),
+            arc_length: a12.to_radians(),
+        }
+    }
+
+    /// Solves the direct problem: the end point of a geodesic of given length and initial azimuth.
+    ///
+    /// # Arguments
+    ///
+    /// * `latitude`, `longitude` - Geodetic coordinates of the starting point in radians.
+    /// * `azimuth` - Initial azimuth in radians, measured from north towards east.
+    /// * `distance` - Length of the geodesic in meters, which may be negative.
+    pub fn direct(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        azimuth: f64,
+        distance: f64,
+    ) -> DirectSolution {
+        let lat1 = lat_fix(latitude.to_degrees());
+        let lon1 = longitude.to_degrees();
+        let azi1 = ang_normalize(azimuth.to_degrees());
+        let (salp1, calp1) = sincosd(ang_round(azi1));
+
+        // Set up the geodesic line through the first point
+        let (mut sbet1, mut cbet1) = sincosd(ang_round(lat1));
+        sbet1 *= self.f1;
+        norm2(&mut sbet1, &mut cbet1);
+        cbet1 = cbet1.max(TINY);
+
+        let salp0 = salp1 * cbet1;
+        let calp0 = calp1.hypot(salp1 * sbet1);
+
+        let mut ssig1 = sbet1;
+        let somg1 = salp0 * sbet1;
+        let mut csig1 = if sbet1 != 0.0 || calp1 != 0.0 {
+            cbet1 * calp1
+        } else {
+            1.0
+        };
+        let comg1 = csig1;
+        norm2(&mut ssig1, &mut csig1);
+
+        let k2 = calp0 * calp0 * self.ep2;
+        let eps = k2 / (2.0 * (1.0 + (1.0 + k2).sqrt()) + k2);
+
+        let a1m1 = a1m1f(eps);
+        let mut c1a = [0.0; NC1 + 1];
+        c1f(eps, &mut c1a);
+        let b11 = sin_cos_series(true, ssig1, csig1, &c1a);
+        let (s, c) = b11.sin_cos();
+        let stau1 = ssig1 * c + csig1 * s;
+        let ctau1 = csig1 * c - ssig1 * s;
+
+        let mut c1pa = [0.0; NC1P + 1];
+        c1pf(eps, &mut c1pa);
+
+        let mut c3a = [0.0; NC3];
+        self.c3f(eps, &mut c3a);
+        let a3c = -self.f * salp0 * self.a3f(eps);
+        let b31 = sin_cos_series(true, ssig1, csig1, &c3a);
+
+        // Convert the distance to an arc length on the auxiliary sphere
+        let tau12 = distance / (self.b * (1.0 + a1m1));
+        let (s, c) = tau12.sin_cos();
+        let b12 = -sin_cos_series(true, stau1 * c + ctau1 * s, ctau1 * c - stau1 * s, &c1pa);
+        let mut sig12 = tau12 - (b12 - b11);
+        let (mut ssig12, mut csig12) = sig12.sin_cos();
+        if self.f.abs() > 0.01 {
+            // The reverted series is inaccurate for large flattening, so apply one Newton step
+            let ssig2 = ssig1 * csig12 + csig1 * ssig12;
+            let csig2 = csig1 * csig12 - ssig1 * ssig12;
+            let b12 = sin_cos_series(true, ssig2, csig2, &c1a);
+            let serr = (1.0 + a1m1) * (sig12 + (b12 - b11)) - distance / self.b;
+            sig12 -= serr / (1.0 + k2 * ssig2 * ssig2).sqrt();
+            (ssig12, csig12) = sig12.sin_cos();
+        }
+
+        let ssig2 = ssig1 * csig12 + csig1 * ssig12;
+        let mut csig2 = csig1 * csig12 - ssig1 * ssig12;
+        let sbet2 = calp0 * ssig2;
+        let mut cbet2 = salp0.hypot(calp0 * csig2);
+        if cbet2 == 0.0 {
+            cbet2 = TINY;
+            csig2 = TINY;
+        }
+        let salp2 = salp0;
+        let calp2 = calp0 * csig2;
+
+        let somg2 = salp0 * ssig2;
+        let comg2 = csig2;
+        let omg12 = (somg2 * comg1 - comg2 * somg1).atan2(comg2 * comg1 + somg2 * somg1);
+        let lam12 = omg12 + a3c * (sig12 + (sin_cos_series(true, ssig2, csig2, &c3a) - b31));
+        let lon12 = lam12.to_degrees();
+        let lon2 = ang_normalize(ang_normalize(lon1) + ang_normalize(lon12));
+
+        DirectSolution {
+            latitude: atan2d(sbet2, self.f1 * cbet2).to_radians(),
+            longitude: lon2.to_radians(),
+            final_azimuth: atan2d(salp2, calp2).to_radians(),
+        }
+    }
+
+    /// Solves the inverse problem in degrees, returning the arc length, distance and the
+    /// sines and cosines of the azimuths at both ends.
+    fn inverse_internal(
+        &self,
+        lat1: f64,
+        lon1: f64,
+        lat2: f64,
+        lon2: f64,
+    ) -> (f64, f64, f64, f64, f64, f64) {
+        // Longitude difference in [-180, 180] computed carefully
+        let (lon12, lon12s) = ang_diff(lon1, lon2);
+        let mut lonsign = if lon12 >= 0.0 { 1.0 } else { -1.0 };
+        let lon12 = lonsign * ang_round(lon12);
+        let lon12s = ang_round((180.0 - lon12) - lonsign * lon12s);
+        let lam12 = lon12.to_radians();
+        let (slam12, clam12) = if lon12 > 90.0 {
+            let (s, c) = sincosd(lon12s);
+            (s, -c)
+        } else {
+            sincosd(lon12)
+        };
+
+        // Swap points so that the point with the higher absolute latitude is point 1
+        let mut lat1 = ang_round(lat_fix(lat1));
+        let mut lat2 = ang_round(lat_fix(lat2));
+        let swapp = if lat1.abs() < lat2.abs() { -1.0 } else { 1.0 };
+        if swapp < 0.0 {
+            lonsign = -lonsign;
+            std::mem::swap(&mut lat1, &mut lat2);
+        }
+        // Make lat1 <= 0
+        let latsign = if lat1 < 0.0 { 1.0 } else { -1.0 };
+        lat1 *= latsign;
+        lat2 *= latsign;
+
+        let (mut sbet1, mut cbet1) = sincosd(lat1);
+        sbet1 *= self.f1;
+        norm2(&mut sbet1, &mut cbet1);
+        cbet1 = cbet1.max(TINY);
+
+        let (mut sbet2, mut cbet2) = sincosd(lat2);
+        sbet2 *= self.f1;
+        norm2(&mut sbet2, &mut cbet2);
+        cbet2 = cbet2.max(TINY);
+
+        // Force bet2 = ±bet1 exactly when the difference vanishes
+        if cbet1 < -sbet1 {
+            if cbet2 == cbet1 {
+                sbet2 = if sbet2 < 0.0 { sbet1 } else { -sbet1 };
+            }
+        } else if sbet2.abs() == -sbet1 {
+            cbet2 = cbet1;
+        }
+
+        let dn1 = (1.0 + self.ep2 * sbet1 * sbet1).sqrt();
+        let dn2 = (1.0 + self.ep2 * sbet2 * sbet2).sqrt();
+
+        let mut a12 = 0.0;
+        let mut sig12;
+        let mut s12x = 0.0;
+        let mut salp1;
+        let mut calp1;
+        let mut salp2 = 0.0;
+        let mut calp2 = 0.0;
+
+        let mut meridian = lat1 == -90.0 || slam12 == 0.0;
+
+        if meridian {
+            // Both end points lie on a single meridian
+            calp1 = clam12;
+            salp1 = slam12;
+            calp2 = 1.0;
+            salp2 = 0.0;
+
+            let ssig1 = sbet1;
+            let csig1 = calp1 * cbet1;
+            let ssig2 = sbet2;
+            let csig2 = calp2 * cbet2;
+
+            sig12 =
+                non_negative(csig1 * ssig2 - ssig1 * csig2).atan2(csig1 * csig2 + ssig1 * ssig2);
+            let (s12b, m12b, _) = self.lengths(self.n, sig12, ssig1, csig1, dn1, ssig2, csig2, dn2);
+
+            if sig12 < 1.0 || m12b >= 0.0 {
+                if sig12 < 3.0 * TINY || (sig12 < TOL0 && (s12b < 0.0 || m12b < 0.0)) {
+                    sig12 = 0.0;
+                    s12x = 0.0;
+                } else {
+                    s12x = s12b * self.b;
+                }
+                a12 = sig12.to_degrees();
+            } else {
+                // Prolate and too close to antipodal, so the meridian is not the shortest path
+                meridian = false;
+            }
+        } else {
+            salp1 = 0.0;
+            calp1 = 0.0;
+        }
+
+        if !meridian && sbet1 == 0.0 && (self.f <= 0.0 || lon12s >= self.f * 180.0) {
+            // Geodesic runs along the equator
+            calp1 = 0.0;
+            calp2 = 0.0;
+            salp1 = 1.0;
+            salp2 = 1.0;
+            s12x = self.a * lam12;
+            a12 = lon12 / self.f1;
+        } else if !meridian {
+            let start =
+                self.inverse_start(sbet1, cbet1, dn1, sbet2, cbet2, dn2, lam12, slam12, clam12);
+            sig12 = start.sig12;
+            salp1 = start.salp1;
+            calp1 = start.calp1;
+
+            if sig12 >= 0.0 {
+                // Short lines
+                salp2 = start.salp2;
+                calp2 = start.calp2;
+                s12x = sig12 * self.b * start.dnm;
+                a12 = sig12.to_degrees();
+            } else {
+                // Newton's method on lambda12(alp1) - lam12 = 0, keeping a bracket on the root
+                let mut salp1a = TINY;
+                let mut calp1a = 1.0;
+                let mut salp1b = TINY;
+                let mut calp1b = -1.0;
+                let mut tripn = false;
+                let mut tripb = false;
+                let mut numit = 0;
+                let lambda = loop {
+                    let lambda = self.lambda12(
+                        sbet1,
+                        cbet1,
+                        dn1,
+                        sbet2,
+                        cbet2,
+                        dn2,
+                        salp1,
+                        calp1,
+                        slam12,
+                        clam12,
+                        numit < MAXIT1,
+                    );
+                    let v = lambda.lam12;
+                    let dv = lambda.dlam12;
+
+                    // Reversed test to allow escape with NaNs
+                    if tripb
+                        || v.abs() < if tripn { 8.0 } else { 1.0 } * TOL0
+                        || v.is_nan()
+                        || numit == MAXIT2
+                    {
+                        break lambda;
+                    }
+
+                    // Update the bracketing values
+                    if v > 0.0 && (numit > MAXIT1 || calp1 / salp1 > calp1b / salp1b) {
+                        salp1b = salp1;
+                        calp1b = calp1;
+                    } else if v < 0.0 && (numit > MAXIT1 || calp1 / salp1 < calp1a / salp1a) {
+                        salp1a = salp1;
+                        calp1a = calp1;
+                    }
+                    numit += 1;
+
+                    if numit <= MAXIT1 && dv > 0.0 {
+                        let dalp1 = -v / dv;
+                        if dalp1.abs() < PI {
+                            let (sdalp1, cdalp1) = dalp1.sin_cos();
+                            let nsalp1 = salp1 * cdalp1 + calp1 * sdalp1;
+                            if nsalp1 > 0.0 {
+                                calp1 = calp1 * cdalp1 - salp1 * sdalp1;
+                                salp1 = nsalp1;
+                                norm2(&mut salp1, &mut calp1);
+                                // Convergence may be only linear where the slope vanishes
+                                tripn = v.abs() <= 16.0 * TOL0;
+                                continue;
+                            }
+                        }
+                    }
+
+                    // Fall back to bisection of the bracket
+                    salp1 = (salp1a + salp1b) / 2.0;
+                    calp1 = (calp1a + calp1b) / 2.0;
+                    norm2(&mut salp1, &mut calp1);
+                    tripn = false;
+                    tripb = (salp1a - salp1).abs() + (calp1a - calp1) < TOLB
+                        || (salp1 - salp1b).abs() + (calp1 - calp1b) < TOLB;
+                };
+                salp2 = lambda.salp2;
+                calp2 = lambda.calp2;
+                sig12 = lambda.sig12;
+                let (s12b, _, _) = self.lengths(
+                    lambda.eps,
+                    sig12,
+                    lambda.ssig1,
+                    lambda.csig1,
+                    dn1,
+                    lambda.ssig2,
+                    lambda.csig2,
+                    dn2,
+                );
+                s12x = s12b * self.b;
+                a12 = sig12.to_degrees();
+            }
+        }
+
+        // Undo the transformations to the canonical configuration
+        if swapp < 0.0 {
+            std::mem::swap(&mut salp1, &mut salp2);
+            std::mem::swap(&mut calp1, &mut calp2);
+        }
+        salp1 *= swapp * lonsign;
+        calp1 *= swapp * latsign;
+        salp2 *= swapp * lonsign;
+        calp2 *= swapp * latsign;
+
+        (a12, 0.0 + s12x, salp1, calp1, salp2, calp2)
+    }
+
+    /// Computes the distance and reduced length divided by b, and the coefficient of the
+    /// secular term of the reduced length.
+    #[allow(clippy::too_many_arguments)]
+    fn lengths(
+        &self,
+        eps: f64,
+        sig12: f64,
+        ssig1: f64,
+        csig1: f64,
+        dn1: f64,
+        ssig2: f64,
+        csig2: f64,
+        dn2: f64,
+    ) -> (f64, f64, f64) {
+        let mut ca = [0.0; NC1 + 1];
+        let mut cb = [0.0; NC2 + 1];
+        let a1m1 = a1m1f(eps);
+        c1f(eps, &mut ca);
+        let a2m1 = a2m1f(eps);
+        c2f(eps, &mut cb);
+        let m0 = a1m1 - a2m1;
+        let a1 = 1.0 + a1m1;
+        let a2 = 1.0 + a2m1;
+
+        let b1 = sin_cos_series(true, ssig2, csig2, &ca) - sin_cos_series(true, ssig1, csig1, &ca);
+        let b2 = sin_cos_series(true, ssig2, csig2, &cb) - sin_cos_series(true, ssig1, csig1, &cb);
+        let s12b = a1 * (sig12 + b1);
+        let j12 = m0 * sig12 + (a1 * b1 - a2 * b2);
+        let m12b = dn2 * (csig1 * ssig2) - dn1 * (ssig1 * csig2) - csig1 * csig2 * j12;
+        (s12b, m12b, m0)
+    }
+
+    /// Computes a starting point for Newton's method, or the solution directly for short lines.
+    #[allow(clippy::too_many_arguments)]
+    fn inverse_start(
+        &self,
+        sbet1: f64,
+        cbet1: f64,
+        dn1: f64,
+        sbet2: f64,
+        cbet2: f64,
+        dn2: f64,
+        lam12: f64,
+        slam12: f64,
+        clam12: f64,
+    ) -> InverseStart {
+        let mut start = InverseStart {
+            sig12: -1.0,
+            salp1: 0.0,
+            calp1: 0.0,
+            salp2: 0.0,
+            calp2: 0.0,
+            dnm: 0.0,
+        };
+
+        // bet12 = bet2 - bet1 in [0, pi); bet12a = bet2 + bet1 in (-pi, 0]
+        let sbet12 = sbet2 * cbet1 - cbet2 * sbet1;
+        let cbet12 = cbet2 * cbet1 + sbet2 * sbet1;
+        let sbet12a = sbet2 * cbet1 + cbet2 * sbet1;
+        let shortline = cbet12 >= 0.0 && sbet12 < 0.5 && cbet2 * lam12 < 0.5;
+
+        let (mut somg12, mut comg12) = if shortline {
+            let mut sbetm2 = (sbet1 + sbet2) * (sbet1 + sbet2);
+            sbetm2 /= sbetm2 + (cbet1 + cbet2) * (cbet1 + cbet2);
+            start.dnm = (1.0 + self.ep2 * sbetm2).sqrt();
+            (lam12 / (self.f1 * start.dnm)).sin_cos()
+        } else {
+            (slam12, clam12)
+        };
+
+        let mut salp1 = cbet2 * somg12;
+        let mut calp1 = if comg12 >= 0.0 {
+            sbet12 + cbet2 * sbet1 * somg12 * somg12 / (1.0 + comg12)
+        } else {
+            sbet12a - cbet2 * sbet1 * somg12 * somg12 / (1.0 - comg12)
+        };
+
+        let ssig12 = salp1.hypot(calp1);
+        let csig12 = sbet1 * sbet2 + cbet1 * cbet2 * comg12;
+
+        if shortline && ssig12 < self.etol2 {
+            // Really short lines
+            let mut salp2 = cbet1 * somg12;
+            let mut calp2 = sbet12
+                - cbet1
+                    * sbet2
+                    * if comg12 >= 0.0 {
+                        somg12 * somg12 / (1.0 + comg12)
+                    } else {
+                        1.0 - comg12
+                    };
+            norm2(&mut salp2, &mut calp2);
+            start.salp2 = salp2;
+            start.calp2 = calp2;
+            start.sig12 = ssig12.atan2(csig12);
+        } else if self.n.abs() > 0.1
+            || csig12 >= 0.0
+            || ssig12 >= 6.0 * self.n.abs() * PI * cbet1 * cbet1
+        {
+            // The zeroth order spherical approximation is adequate
+        } else {
+            // Scale to coordinates where the antipodal point is at the origin and the
+            // singular point is at y = 0, x = -1
+            let lam12x = (-slam12).atan2(-clam12);
+            let (x, y, lamscale) = if self.f >= 0.0 {
+                let k2 = sbet1 * sbet1 * self.ep2;
+                let eps = k2 / (2.0 * (1.0 + (1.0 + k2).sqrt()) + k2);
+                let lamscale = self.f * cbet1 * self.a3f(eps) * PI;
+                let betscale = lamscale * cbet1;
+                (lam12x / lamscale, sbet12a / betscale, lamscale)
+            } else {
+                let cbet12a = cbet2 * cbet1 - sbet2 * sbet1;
+                let bet12a = sbet12a.atan2(cbet12a);
+                let (_, m12b, m0) =
+                    self.lengths(self.n, PI + bet12a, sbet1, -cbet1, dn1, sbet2, cbet2, dn2);
+                let x = -1.0 + m12b / (cbet1 * cbet2 * m0 * PI);
+                let betscale = if x < -0.01 {
+                    sbet12a / x
+                } else {
+                    -self.f * cbet1 * cbet1 * PI
+                };
+                let lamscale = betscale / cbet1;
+                (x, lam12x / lamscale, lamscale)
+            };
+
+            if y > -TOL1 && x > -1.0 - XTHRESH {
+                // Strip near the cut
+                if self.f >= 0.0 {
+                    salp1 = (-x).min(1.0);
+                    calp1 = -(1.0 - salp1 * salp1).sqrt();
+                } else {
+                    calp1 = x.max(if x > -TOL1 { 0.0 } else { -1.0 });
+                    salp1 = (1.0 - calp1 * calp1).sqrt();
+                }
+            } else {
+                // Estimate omg12 by solving the astroid problem
+                let k = astroid(x, y);
+                let omg12a = lamscale
+                    * if self.f >= 0.0 {
+                        -x * k / (1.0 + k)
+                    } else {
+                        -y * (1.0 + k) / k
+                    };
+                let (s, c) = omg12a.sin_cos();
+                somg12 = s;
+                comg12 = -c;
+                salp1 = cbet2 * somg12;
+                calp1 = sbet12a - cbet2 * sbet1 * somg12 * somg12 / (1.0 - comg12);
+            }
+        }
+
+        // Sanity check on the starting guess; the backwards test lets NaN through
+        if salp1.is_nan() || salp1 > 0.0 {
+            norm2(&mut salp1, &mut calp1);
+        } else {
+            salp1 = 1.0;
+            calp1 = 0.0;
+        }
+        start.salp1 = salp1;
+        start.calp1 = calp1;
+        start
+    }
+
+    /// Computes the longitude difference along the geodesic with initial azimuth alp1, less
+    /// the target difference, and its derivative with respect to alp1.
+    #[allow(clippy::too_many_arguments)]
+    fn lambda12(
+        &self,
+        sbet1: f64,
+        cbet1: f64,
+        dn1: f64,
+        sbet2: f64,
+        cbet2: f64,
+        dn2: f64,
+        salp1: f64,
+        calp1: f64,
+        slam120: f64,
+        clam120: f64,
+        diffp: bool,
+    ) -> Lambda12 {
+        // Break the degeneracy of the equatorial line
+        let calp1 = if sbet1 == 0.0 && calp1 == 0.0 {
+            -TINY
+        } else {
+            calp1
+        };
+
+        let salp0 = salp1 * cbet1;
+        let calp0 = calp1.hypot(salp1 * sbet1);
+
+        let mut ssig1 = sbet1;
+        let somg1 = salp0 * sbet1;
+        let mut csig1 = calp1 * cbet1;
+        let comg1 = csig1;
+        norm2(&mut ssig1, &mut csig1);
+
+        // Enforce symmetries in the case |bet2| = -bet1
+        let salp2 = if cbet2 != cbet1 { salp0 / cbet2 } else { salp1 };
+        let calp2 = if cbet2 != cbet1 || sbet2.abs() != -sbet1 {
+            ((calp1 * cbet1) * (calp1 * cbet1)
+                + if cbet1 < -sbet1 {
+                    (cbet2 - cbet1) * (cbet1 + cbet2)
+                } else {
+                    (sbet1 - sbet2) * (sbet1 + sbet2)
+                })
+            .sqrt()
+                / cbet2
+        } else {
+            calp1.abs()
+        };
+
+        let mut ssig2 = sbet2;
+        let somg2 = salp0 * sbet2;
+        let mut csig2 = calp2 * cbet2;
+        let comg2 = csig2;
+        norm2(&mut ssig2, &mut csig2);
+
+        let sig12 =
+            non_negative(csig1 * ssig2 - ssig1 * csig2).atan2(csig1 * csig2 + ssig1 * ssig2);
+
+        let somg12 = non_negative(comg1 * somg2 - somg1 * comg2);
+        let comg12 = comg1 * comg2 + somg1 * somg2;
+        let eta = (somg12 * clam120 - comg12 * slam120).atan2(comg12 * clam120 + somg12 * slam120);
+
+        let k2 = calp0 * calp0 * self.ep2;
+        let eps = k2 / (2.0 * (1.0 + (1.0 + k2).sqrt()) + k2);
+        let mut c3a = [0.0; NC3];
+        self.c3f(eps, &mut c3a);
+        let b312 =
+            sin_cos_series(true, ssig2, csig2, &c3a) - sin_cos_series(true, ssig1, csig1, &c3a);
+        let domg12 = -self.f * self.a3f(eps) * salp0 * (sig12 + b312);
+        let lam12 = eta + domg12;
+
+        let dlam12 = if diffp {
+            if calp2 == 0.0 {
+                -2.0 * self.f1 * dn1 / sbet1
+            } else {
+                let (_, m12b, _) = self.lengths(eps, sig12, ssig1, csig1, dn1, ssig2, csig2, dn2);
+                m12b * self.f1 / (calp2 * cbet2)
+            }
+        } else {
+            0.0
+        };
+
+        Lambda12 {
+            lam12,
+            dlam12,
+            salp2,
+            calp2,
+            sig12,
+            ssig1,
+            csig1,
+            ssig2,
+            csig2,
+            eps,
+        }
+    }
+
+    /// Evaluates the A3 series in eps.
+    fn a3f(&self, eps: f64) -> f64 {
+        polyval(&self.a3x, eps)
+    }
+
+    /// Evaluates the C3 coefficients in eps, indexed from 1.
+    fn c3f(&self, eps: f64, c: &mut [f64; NC3]) {
+        let mut mult = 1.0;
+        let mut o = 0;
+        for (l, value) in c.iter_mut().enumerate().skip(1) {
+            let m = NC3 - l - 1;
+            mult *= eps;
+            *value = mult * polyval(&self.c3x[o..o + m + 1], eps);
+            o += m + 1;
+        }
+    }
+
+    /// Computes the coefficients of the A3 series as polynomials in the third flattening.
+    fn a3_coefficients(&mut self) {
+        const COEFF: [f64; 18] = [
+            -3.0, 128.0, // eps^5
+            -2.0, -3.0, 64.0, // eps^4
+            -1.0, -3.0, -1.0, 16.0, // eps^3
+            3.0, -1.0, -2.0, 8.0, // eps^2
+            1.0, -1.0, 2.0, // eps^1
+            1.0, 1.0, // eps^0
+        ];
+        let mut o = 0;
+        for (k, j) in (0..NA3).rev().enumerate() {
+            let m = (NA3 - j - 1).min(j);
+            self.a3x[k] = polyval(&COEFF[o..o + m + 1], self.n) / COEFF[o + m + 1];
+            o += m + 2;
+        }
+    }
+
+    /// Computes the coefficients of the C3 series as polynomials in the third flattening.
+    fn c3_coefficients(&mut self) {
+        const COEFF: [f64; 45] = [
+            3.0, 128.0, // C3[1], eps^5
+            2.0, 5.0, 128.0, // C3[1], eps^4
+            -1.0, 3.0, 3.0, 64.0, // C3[1], eps^3
+            -1.0, 0.0, 1.0, 8.0, // C3[1], eps^2
+            -1.0, 1.0, 4.0, // C3[1], eps^1
+            5.0, 256.0, // C3[2], eps^5
+            1.0, 3.0, 128.0, // C3[2], eps^4
+            -3.0, -2.0, 3.0, 64.0, // C3[2], eps^3
+            1.0, -3.0, 2.0, 32.0, // C3[2], eps^2
+            7.0, 512.0, // C3[3], eps^5
+            -10.0, 9.0, 384.0, // C3[3], eps^4
+            5.0, -9.0, 5.0, 192.0, // C3[3], eps^3
+            7.0, 512.0, // C3[4], eps^5
+            -14.0, 7.0, 512.0, // C3[4], eps^4
+            21.0, 2560.0, // C3[5], eps^5
+        ];
+        let mut o = 0;
+        let mut k = 0;
+        for l in 1..NC3 {
+            for j in (l..NC3).rev() {
+                let m = (NC3 - j - 1).min(j);
+                self.c3x[k] = polyval(&COEFF[o..o + m + 1], self.n) / COEFF[o + m + 1];
+                k += 1;
+                o += m + 2;
+            }
+        }
+    }
+}
+
+/// Starting point of the inverse iteration
+struct InverseStart {
+    sig12: f64,
+    salp1: f64,
+    calp1: f64,
+    salp2: f64,
+    calp2: f64,
+    dnm: f64,
+}
+
+/// Intermediate results of the longitude difference evaluation
+struct Lambda12 {
+    lam12: f64,
+    dlam12: f64,
+    salp2: f64,
+    calp2: f64,
+    sig12: f64,
+    ssig1: f64,
+    csig1: f64,
+    ssig2: f64,
+    csig2: f64,
+    eps: f64,
+}
+
+/// Evaluates a polynomial with coefficients given from the highest power down.
+fn polyval(p: &[f64], x: f64) -> f64 {
+    p.iter().fold(0.0, |y, &c| y * x + c)
+}
+
+/// Evaluates Σ c[l] sin(2lx) (sinp) or Σ c[l] cos((2l+1)x) by Clenshaw summation.
+fn sin_cos_series(sinp: bool, sinx: f64, cosx: f64, c: &[f64]) -> f64 {
+    // With sinp the coefficients are c[1..n]; otherwise c[0..n-1]
+    let mut n = c.len() - if sinp { 1 } else { 0 };
+    let mut k = c.len();
+    let ar = 2.0 * (cosx - sinx) * (cosx + sinx);
+    let mut y0 = if n & 1 == 1 {
+        k -= 1;
+        c[k]
+    } else {
+        0.0
+    };
+    let mut y1 = 0.0;
+    n /= 2;
+    for _ in 0..n {
+        k -= 1;
+        y1 = ar * y0 - y1 + c[k];
+        k -= 1;
+        y0 = ar * y1 - y0 + c[k];
+    }
+    if sinp {
+        2.0 * sinx * cosx * y0
+    } else {
+        cosx * (y0 - y1)
+    }
+}
+
+/// Evaluates A1 - 1.
+fn a1m1f(eps: f64) -> f64 {
+    const COEFF: [f64; 5] = [1.0, 4.0, 64.0, 0.0, 256.0];
+    let t = polyval(&COEFF[..4], eps * eps) / COEFF[4];
+    (t + eps) / (1.0 - eps)
+}
+
+/// Evaluates the C1 coefficients, indexed from 1.
+fn c1f(eps: f64, c: &mut [f64; NC1 + 1]) {
+    const COEFF: [f64; 18] = [
+        -1.0, 6.0, -16.0, 32.0, // C1[1]
+        -9.0, 64.0, -128.0, 2048.0, // C1[2]
+        9.0, -16.0, 768.0, // C1[3]
+        3.0, -5.0, 512.0, // C1[4]
+        -7.0, 1280.0, // C1[5]
+        -7.0, 2048.0, // C1[6]
+    ];
+    series_coefficients(&COEFF, eps, c);
+}
+
+/// Evaluates the C1' coefficients, indexed from 1.
+fn c1pf(eps: f64, c: &mut [f64; NC1P + 1]) {
+    const COEFF: [f64; 18] = [
+        205.0, -432.0, 768.0, 1536.0, // C1p[1]
+        4005.0, -4736.0, 3840.0, 12288.0, // C1p[2]
+        -225.0, 116.0, 384.0, // C1p[3]
+        -7173.0, 2695.0, 7680.0, // C1p[4]
+        3467.0, 7680.0, // C1p[5]
+        38081.0, 61440.0, // C1p[6]
+    ];
+    series_coefficients(&COEFF, eps, c);
+}
+
+/// Evaluates A2 - 1.
+fn a2m1f(eps: f64) -> f64 {
+    const COEFF: [f64; 5] = [-11.0, -28.0, -192.0, 0.0, 256.0];
+    let t = polyval(&COEFF[..4], eps * eps) / COEFF[4];
+    (t - eps) / (1.0 + eps)
+}
+
+/// Evaluates the C2 coefficients, indexed from 1.
+fn c2f(eps: f64, c: &mut [f64; NC2 + 1]) {
+    const COEFF: [f64; 18] = [
+        1.0, 2.0, 16.0, 32.0, // C2[1]
+        35.0, 64.0, 384.0, 2048.0, // C2[2]
+        15.0, 80.0, 768.0, // C2[3]
+        7.0, 35.0, 512.0, // C2[4]
+        63.0, 1280.0, // C2[5]
+        77.0, 2048.0, // C2[6]
+    ];
+    series_coefficients(&COEFF, eps, c);
+}
+
+/// Evaluates sixth-order coefficient series whose l-th term is eps^l times a polynomial in eps².
+fn series_coefficients(coeff: &[f64], eps: f64, c: &mut [f64; ORDER + 1]) {
+    let eps2 = eps * eps;
+    let mut d = eps;
+    let mut o = 0;
+    for (l, value) in c.iter_mut().enumerate().skip(1) {
+        let m = (ORDER - l) / 2;
+        *value = d * polyval(&coeff[o..o + m + 1], eps2) / coeff[o + m + 1];
+        o += m + 2;
+        d *= eps;
+    }
+}
+
+/// Solves k⁴ + 2k³ - (x² + y² - 1)k² - 2y²k - y² = 0 for the positive root k.
+fn astroid(x: f64, y: f64) -> f64 {
+    let p = x * x;
+    let q = y * y;
+    let r = (p + q - 1.0) / 6.0;
+    if q == 0.0 && r <= 0.0 {
+        return 0.0;
+    }
+
+    let s = p * q / 4.0;
+    let r2 = r * r;
+    let r3 = r * r2;
+    // Discriminant of the quadratic equation for T3, zero on the evolute
+    let disc = s * (s + 2.0 * r3);
+    let mut u = r;
+    if disc >= 0.0 {
+        let mut t3 = s + r3;
+        // Pick the sign of the square root to minimize cancellation
+        t3 += if t3 < 0.0 { -disc.sqrt() } else { disc.sqrt() };
+        let t = t3.cbrt();
+        u += t + if t != 0.0 { r2 / t } else { 0.0 };
+    } else {
+        // T is complex but u is real
+        let ang = (-disc).sqrt().atan2(-(s + r3));
+        u += 2.0 * r * (ang / 3.0).cos();
+    }
+    let v = (u * u + q).sqrt();
+    let uv = if u < 0.0 { q / (v - u) } else { u + v };
+    let w = (uv - q) / (2.0 * v);
+    uv / ((uv + w * w).sqrt() + w)
+}
+
+/// Normalizes to the sum of a pair of floating point numbers, returning the sum and the rounding error.
+fn sum_error(u: f64, v: f64) -> (f64, f64) {
+    let s = u + v;
+    let up = s - v;
+    let vpp = s - up;
+    (s, -((up - u) + (vpp - v)))
+}
+
+/// Reduces an angle in degrees to the range (-180, 180].
+fn ang_normalize(x: f64) -> f64 {
+    let y = x - 360.0 * (x / 360.0).round();
+    if y == -180.0 {
+        180.0
+    } else if y > 180.0 {
+        y - 360.0
+    } else if y < -180.0 {
+        y + 360.0
+    } else {
+        y
+    }
+}
+
+/// Computes y - x in degrees reduced to [-180, 180] together with the rounding error.
+fn ang_diff(x: f64, y: f64) -> (f64, f64) {
+    let (d, t) = sum_error(ang_normalize(-x), ang_normalize(y));
+    let d = ang_normalize(d);
+    sum_error(if d == 180.0 && t > 0.0 { -180.0 } else { d }, t)
+}
+
+/// Rounds tiny angles in degrees so that small values are represented exactly.
+fn ang_round(x: f64) -> f64 {
+    const Z: f64 = 1.0 / 16.0;
+    let y = x.abs();
+    let y = if y < Z { Z - (Z - y) } else { y };
+    if x < 0.0 {
+        -y
+    } else {
+        y
+    }
+}
+
+/// Replaces latitudes outside [-90, 90] degrees with NaN.
+fn lat_fix(x: f64) -> f64 {
+    if x.abs() > 90.0 {
+        f64::NAN
+    } else {
+        x
+    }
+}
+
+/// Computes the sine and cosine of an angle in degrees, exact for multiples of 90°.
+fn sincosd(x: f64) -> (f64, f64) {
+    let r = x % 360.0;
+    let q = (r / 90.0).round();
+    let r = (r - 90.0 * q).to_radians();
+    let (s, c) = r.sin_cos();
+    let (s, c) = match (q as i64).rem_euclid(4) {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    };
+    // Avoid negative zeros for nonzero angles
+    if x != 0.0 {
+        (s + 0.0, c + 0.0)
+    } else {
+        (s, c)
+    }
+}
+
+/// Computes atan2(y, x) in degrees with exact results for multiples of 45°.
+fn atan2d(y: f64, x: f64) -> f64 {
+    let (mut x, mut y) = (x, y);
+    let mut q = 0;
+    if y.abs() > x.abs() {
+        std::mem::swap(&mut x, &mut y);
+        q = 2;
+    }
+    if x.is_sign_negative() {
+        x = -x;
+        q += 1;
+    }
+    let ang = y.atan2(x).to_degrees();
+    match q {
+        1 => 180.0_f64.copysign(y) - ang,
+        2 => 90.0 - ang,
+        3 => -90.0 + ang,
+        _ => ang,
+    }
+}
+
+/// Clamps a value to be non-negative, mapping negative zero and NaN to positive zero.
+fn non_negative(x: f64) -> f64 {
+    if x > 0.0 {
+        x
+    } else {
+        0.0
+    }
+}
+
+/// Normalizes a sine and cosine pair.
+fn norm2(s: &mut f64, c: &mut f64) {
+    let r = s.hypot(*c);
+    *s /= r;
+    *c /= r;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{self, Vector3};
+
+    fn inverse_degrees(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> (f64, f64, f64) {
+        let solution = Geodesic::new(&Ellipsoid::WGS84).inverse(
+            lat1.to_radians(),
+            lon1.to_radians(),
+            lat2.to_radians(),
+            lon2.to_radians(),
+        );
+        (
+            solution.distance,
+            solution.initial_azimuth.to_degrees(),
+            solution.final_azimuth.to_degrees(),
+        )
+    }
+
+    #[test]
+    fn karney_inverse_test() {
+        // Wellington to Salamanca, from the GeographicLib documentation
+        let (s12, azi1, azi2) = inverse_degrees(-41.32, 174.81, 40.96, -5.50);
+        assert!((s12 - 19_959_679.267_353_8).abs() < 1e-6);
+        assert!((azi1 - 161.067_669_986_158_8).abs() < 1e-10);
+        assert!((azi2 - 18.825_195_123_248_39).abs() < 1e-10);
+    }
+
+    #[test]
+    fn karney_direct_test() {
+        // 20000 km south-west of Perth, from the GeographicLib documentation
+        let solution = Geodesic::new(&Ellipsoid::WGS84).direct(
+            -32.06_f64.to_radians(),
+            115.74_f64.to_radians(),
+            225.0_f64.to_radians(),
+            20_000e3,
+        );
+        assert!((solution.latitude.to_degrees() - 32.111_955_29).abs() < 1e-8);
+        assert!((solution.longitude.to_degrees() + 63.959_252_78).abs() < 1e-8);
+    }
+
+    #[test]
+    fn special_cases_test() {
+        // Coincident points
+        let (s12, _, _) = inverse_degrees(20.001, 0.0, 20.001, 0.0);
+        assert_eq!(s12, 0.0);
+
+        // Antipodal points on the equator are joined by a meridian through the poles
+        let (s12, azi1, azi2) = inverse_degrees(0.0, 0.0, 0.0, 180.0);
+        assert!((s12 - 20_003_931.458_625_6).abs() < 1e-6);
+        assert!(azi1.abs() < 1e-12);
+        assert!((azi2.abs() - 180.0).abs() < 1e-12);
+
+        // Along the equator
+        let (s12, azi1, _) = inverse_degrees(0.0, 0.0, 0.0, 90.0);
+        assert!((s12 - 6_378_137.0 * std::f64::consts::FRAC_PI_2).abs() < 1e-6);
+        assert!((azi1 - 90.0).abs() < 1e-12);
+
+        // Pole to pole
+        let (s12, _, _) = inverse_degrees(90.0, 0.0, -90.0, 0.0);
+        assert!((s12 - 20_003_931.458_625_6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sphere_test() {
+        let radius = 6_371_000.0;
+        let geodesic = Geodesic::new(&Ellipsoid::new(radius, 0.0));
+        let (lat1, lon1, lat2, lon2) = (0.3, -1.2, -0.7, 2.1);
+        let solution = geodesic.inverse(lat1, lon1, lat2, lon2);
+
+        let central =
+            Vector3::from_spherical(lon1, lat1).angle(&Vector3::from_spherical(lon2, lat2));
+        assert!((solution.distance - radius * central).abs() < 1e-6);
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let ellipsoids = [
+            Ellipsoid::WGS84,
+            Ellipsoid::new(6_378_137.0, 1.0 / 50.0),
+            Ellipsoid::new(6_378_137.0, -1.0 / 50.0),
+        ];
+        let points = [
+            (10.0, 20.0, 10.0001, 20.0001),
+            (-30.0, 0.0, 29.9, 179.8),
+            (0.0, 0.0, 0.5, 179.5),
+            (45.0, -120.0, -60.0, 45.0),
+            (-89.0, 0.0, 89.5, 10.0),
+            (5.0, 5.0, 5.0, 175.0),
+        ];
+        for ellipsoid in &ellipsoids {
+            let geodesic = Geodesic::new(ellipsoid);
+            for &(lat1, lon1, lat2, lon2) in &points {
+                let (lat1, lon1, lat2, lon2) = (
+                    f64::to_radians(lat1),
+                    f64::to_radians(lon1),
+                    f64::to_radians(lat2),
+                    f64::to_radians(lon2),
+                );
+                let inverse = geodesic.inverse(lat1, lon1, lat2, lon2);
+                let direct = geodesic.direct(lat1, lon1, inverse.initial_azimuth, inverse.distance);
+                let dlon = math::normalize_angle_signed(direct.longitude - lon2);
+                assert!((direct.latitude - lat2).abs() < 1e-12);
+                assert!(dlon.abs() < 1e-12);
+                assert!(
+                    math::normalize_angle_signed(direct.final_azimuth - inverse.final_azimuth)
+                        .abs()
+                        < 1e-11
+                );
+
+                // The reversed problem has the same length
+                let reverse = geodesic.inverse(lat2, lon2, lat1, lon1);
+                assert!((reverse.distance - inverse.distance).abs() < 1e-6);
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use super::{Ellipsoid, Geodetic};
+use crate::math::{Matrix3, Vector3};
+
+/// A local tangent-plane frame attached to a point on or near the ellipsoid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalFrame {
+    /// Earth-centered, Earth-fixed position of the frame origin in meters
+    pub origin: Vector3,
+    /// Rotation from Earth-fixed axes to local axes
+    pub rotation: Matrix3,
+}
+
+impl LocalFrame {
+    /// Constructs an east-north-up frame at the given geodetic position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use astro_carta::geodesy::{Ellipsoid, Geodetic, LocalFrame};
+    ///
+    /// let site = Geodetic::from_degrees(52.0, 4.0, 0.0);
+    /// let enu = LocalFrame::east_north_up(&Ellipsoid::WGS84, &site);
+    /// let above = Ellipsoid::WGS84.geodetic_to_cartesian(&Geodetic::from_degrees(52.0, 4.0, 100.0));
+    /// assert!((enu.to_local(&above).z - 100.0).abs() < 1e-8);
+    /// ```
+    pub fn east_north_up(ellipsoid: &Ellipsoid, origin: &Geodetic) -> Self {
+        LocalFrame {
+            origin: ellipsoid.geodetic_to_cartesian(origin),
+            rotation: enu_rotation(origin.latitude, origin.longitude),
+        }
+    }
+
+    /// Constructs a north-east-down frame at the given geodetic position.
+    pub fn north_east_down(ellipsoid: &Ellipsoid, origin: &Geodetic) -> Self {
+        LocalFrame {
+            origin: ellipsoid.geodetic_to_cartesian(origin),
+            rotation: ned_rotation(origin.latitude, origin.longitude),
+        }
+    }
+
+    /// Converts an Earth-fixed position in meters to local coordinates.
+    pub fn to_local(&self, position: &Vector3) -> Vector3 {
+        self.rotation * (*position - self.origin)
+    }
+
+    /// Converts local coordinates in meters to an Earth-fixed position.
+    pub fn to_earth_fixed(&self, local: &Vector3) -> Vector3 {
+        self.rotation.transpose() * *local + self.origin
+    }
+
+    /// Rotates an Earth-fixed vector, such as a velocity, into local axes.
+    pub fn rotate_to_local(&self, vector: &Vector3) -> Vector3 {
+        self.rotation * *vector
+    }
+
+    /// Rotates a vector in local axes into Earth-fixed axes.
+    pub fn rotate_to_earth_fixed(&self, vector: &Vector3) -> Vector3 {
+        self.rotation.transpose() * *vector
+    }
+}
+
+/// Returns the rotation from Earth-fixed axes to east-north-up axes.
+///
+/// # Arguments
+///
+/// * `latitude` - Geodetic latitude in radians.
+/// * `longitude` - Longitude in radians, positive east.
+pub fn enu_rotation(latitude: f64, longitude: f64) -> Matrix3 {
+    let (sin_lat, cos_lat) = latitude.sin_cos();
+    let (sin_lon, cos_lon) = longitude.sin_cos();
+
+    Matrix3::from_rows(
+        &Vector3::new(-sin_lon, cos_lon, 0.0),
+        &Vector3::new(-sin_lat * cos_lon, -sin_lat * sin_lon, cos_lat),
+        &Vector3::new(cos_lat * cos_lon, cos_lat * sin_lon, sin_lat),
+    )
+}
+
+/// Returns the rotation from Earth-fixed axes to north-east-down axes.
+///
+/// # Arguments
+///
+/// * `latitude` - Geodetic latitude in radians.
+/// * `longitude` - Longitude in radians, positive east.
+pub fn ned_rotation(latitude: f64, longitude: f64) -> Matrix3 {
+    let enu = enu_rotation(latitude, longitude);
+    Matrix3::from_rows(&enu.row(1), &enu.row(0), &-enu.row(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enu_axes_test() {
+        let wgs84 = Ellipsoid::WGS84;
+        let origin = Geodetic::from_degrees(-35.4, 149.0, 600.0);
+        let enu = LocalFrame::east_north_up(&wgs84, &origin);
+        assert!((enu.rotation.determinant() - 1.0).abs() < 1e-15);
+
+        // A small step in latitude moves north, a small step in longitude moves east
+        let north = wgs84.geodetic_to_cartesian(&Geodetic::from_degrees(-35.3999, 149.0, 600.0));
+        let east = wgs84.geodetic_to_cartesian(&Geodetic::from_degrees(-35.4, 149.0001, 600.0));
+        let n = enu.to_local(&north);
+        let e = enu.to_local(&east);
+        assert!(n.y > 11.0 && n.x.abs() < 1e-6 && n.z.abs() < 1e-3);
+        assert!(e.x > 9.0 && e.y.abs() < 1e-4 && e.z.abs() < 1e-3);
+    }
+
+    #[test]
+    fn ned_test() {
+        let wgs84 = Ellipsoid::WGS84;
+        let origin = Geodetic::from_degrees(10.0, -20.0, 0.0);
+        let enu = LocalFrame::east_north_up(&wgs84, &origin);
+        let ned = LocalFrame::north_east_down(&wgs84, &origin);
+
+        let point = Vector3::new(6.3e6, -2.1e6, 1.2e6);
+        let a = enu.to_local(&point);
+        let b = ned.to_local(&point);
+        assert!((b - Vector3::new(a.y, a.x, -a.z)).norm() < 1e-8);
+        assert!((ned.to_earth_fixed(&b) - point).norm() < 1e-8);
+        assert!((ned.rotate_to_earth_fixed(&ned.rotate_to_local(&point)) - point).norm() < 1e-8);
+    }
+}
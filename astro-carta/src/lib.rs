@@ -1 +1,8 @@
+pub mod astrometry;
+pub mod constants;
+pub mod coordinates;
 pub mod datetime;
+pub mod ephemeris;
+pub mod geodesy;
+pub mod math;
+pub mod orbit;
@@ -0,0 +1,74 @@
+pub mod interpolation;
+pub mod matrix;
+pub mod roots;
+pub mod vector;
+
+pub use matrix::{Matrix3, Matrix6};
+pub use vector::Vector3;
+
+use std::f64::consts::PI;
+
+/// Two pi, one full turn in radians
+pub const TWO_PI: f64 = 2.0 * PI;
+
+/// Number of arcseconds in one radian
+pub const ARCSECONDS_PER_RADIAN: f64 = 180.0 * 3600.0 / PI;
+
+/// Converts an angle in arcseconds to radians.
+pub fn arcseconds_to_radians(value: f64) -> f64 {
+    value / ARCSECONDS_PER_RADIAN
+}
+
+/// Normalizes an angle in radians into the range [0, 2π).
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::math;
+///
+/// let angle = math::normalize_angle(-std::f64::consts::FRAC_PI_2);
+/// assert!((angle - 1.5 * std::f64::consts::PI).abs() < 1e-15);
+/// ```
+pub fn normalize_angle(angle: f64) -> f64 {
+    let wrapped = angle.rem_euclid(TWO_PI);
+    if wrapped >= TWO_PI {
+        0.0
+    } else {
+        wrapped
+    }
+}
+
+/// Normalizes an angle in radians into the range [-π, π).
+pub fn normalize_angle_signed(angle: f64) -> f64 {
+    let wrapped = normalize_angle(angle);
+    if wrapped >= PI {
+        wrapped - TWO_PI
+    } else {
+        wrapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_angle_test() {
+        assert_eq!(normalize_angle(0.0), 0.0);
+        assert!((normalize_angle(3.0 * PI) - PI).abs() < 1e-15);
+        assert!((normalize_angle(-0.5) - (TWO_PI - 0.5)).abs() < 1e-15);
+        assert!(normalize_angle(-1e-18) < TWO_PI);
+    }
+
+    #[test]
+    fn normalize_angle_signed_test() {
+        assert!((normalize_angle_signed(1.5 * PI) + 0.5 * PI).abs() < 1e-15);
+        assert!((normalize_angle_signed(0.25) - 0.25).abs() < 1e-15);
+        assert!((normalize_angle_signed(PI) + PI).abs() < 1e-15);
+    }
+
+    #[test]
+    fn arcseconds_test() {
+        assert!((arcseconds_to_radians(3600.0) - PI / 180.0).abs() < 1e-18);
+    }
+}
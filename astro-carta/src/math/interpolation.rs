@@ -0,0 +1,140 @@
+/// Evaluates a Chebyshev series `Σ c_k T_k(x)` and its derivative with respect to `x`.
+///
+/// # Arguments
+///
+/// * `coefficients` - Coefficients `c_k` in order of increasing degree.
+/// * `x` - Normalized argument, nominally in [-1, 1].
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::math::interpolation;
+///
+/// // 1 + 2 T_1(x) + 3 T_2(x) = 6x² + 2x - 2
+/// let (value, derivative) = interpolation::chebyshev(&[1.0, 2.0, 3.0], 0.5);
+/// assert!((value - 0.5).abs() < 1e-15);
+/// assert!((derivative - 8.0).abs() < 1e-15);
+/// ```
+pub fn chebyshev(coefficients: &[f64], x: f64) -> (f64, f64) {
+    let mut value = 0.0;
+    let mut derivative = 0.0;
+
+    // Forward recurrences for T_k(x) and T_k'(x)
+    let (mut t_previous, mut t) = (0.0, 1.0);
+    let (mut dt_previous, mut dt) = (0.0, 0.0);
+    for (degree, &c) in coefficients.iter().enumerate() {
+        value += c * t;
+        derivative += c * dt;
+
+        let (t_next, dt_next) = if degree == 0 {
+            (x, 1.0)
+        } else {
+            (
+                2.0 * x * t - t_previous,
+                2.0 * t + 2.0 * x * dt - dt_previous,
+            )
+        };
+        (t_previous, t) = (t, t_next);
+        (dt_previous, dt) = (dt, dt_next);
+    }
+
+    (value, derivative)
+}
+
+/// Evaluates the Lagrange polynomial through the points `(times[i], values[i])` at `t`.
+///
+/// # Panics
+///
+/// Panics if `times` and `values` differ in length.
+pub fn lagrange(times: &[f64], values: &[f64], t: f64) -> f64 {
+    assert_eq!(times.len(), values.len());
+
+    times
+        .iter()
+        .zip(values)
+        .enumerate()
+        .map(|(i, (&ti, &value))| {
+            times
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .fold(value, |product, (_, &tj)| product * (t - tj) / (ti - tj))
+        })
+        .sum()
+}
+
+/// Evaluates the Hermite polynomial matching values and first derivatives at the given times,
+/// returning its value and derivative at `t`.
+///
+/// # Panics
+///
+/// Panics if `times`, `values` and `derivatives` differ in length.
+pub fn hermite(times: &[f64], values: &[f64], derivatives: &[f64], t: f64) -> (f64, f64) {
+    assert!(times.len() == values.len() && times.len() == derivatives.len());
+
+    // Newton divided differences over the doubled nodes
+    let nodes: Vec<f64> = times.iter().flat_map(|&ti| [ti, ti]).collect();
+    let mut coefficients: Vec<f64> = values.iter().flat_map(|&v| [v, v]).collect();
+    let count = nodes.len();
+    for order in 1..count {
+        for i in (order..count).rev() {
+            coefficients[i] = if order == 1 && i % 2 == 1 {
+                derivatives[i / 2]
+            } else {
+                (coefficients[i] - coefficients[i - 1]) / (nodes[i] - nodes[i - order])
+            };
+        }
+    }
+
+    let mut value = 0.0;
+    let mut derivative = 0.0;
+    for i in (0..count).rev() {
+        derivative = derivative * (t - nodes[i]) + value;
+        value = value * (t - nodes[i]) + coefficients[i];
+    }
+
+    (value, derivative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chebyshev_test() {
+        // T_3(x) = 4x³ - 3x, T_3'(x) = 12x² - 3
+        for &x in &[-1.0, -0.3, 0.0, 0.7, 1.0] {
+            let (value, derivative) = chebyshev(&[0.0, 0.0, 0.0, 1.0], x);
+            assert!((value - (4.0 * x * x * x - 3.0 * x)).abs() < 1e-15);
+            assert!((derivative - (12.0 * x * x - 3.0)).abs() < 1e-14);
+        }
+        assert_eq!(chebyshev(&[], 0.5), (0.0, 0.0));
+        assert_eq!(chebyshev(&[2.5], 0.5), (2.5, 0.0));
+    }
+
+    #[test]
+    fn lagrange_test() {
+        // Four points reproduce a cubic exactly
+        let f = |t: f64| 2.0 - t + 0.5 * t * t - 0.25 * t * t * t;
+        let times = [-1.0, 0.5, 2.0, 3.5];
+        let values = times.map(f);
+        for &t in &[-2.0, 0.0, 1.2, 4.0] {
+            assert!((lagrange(&times, &values, t) - f(t)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn hermite_test() {
+        // Three points with derivatives reproduce a quintic exactly
+        let f = |t: f64| 1.0 + t - 2.0 * t.powi(3) + 0.1 * t.powi(5);
+        let df = |t: f64| 1.0 - 6.0 * t * t + 0.5 * t.powi(4);
+        let times = [0.0, 1.0, 2.5];
+        let values = times.map(f);
+        let derivatives = times.map(df);
+        for &t in &[-0.5, 0.3, 1.7, 3.0] {
+            let (value, derivative) = hermite(&times, &values, &derivatives, t);
+            assert!((value - f(t)).abs() < 1e-11);
+            assert!((derivative - df(t)).abs() < 1e-11);
+        }
+    }
+}
@@ -0,0 +1,461 @@
+use super::Vector3;
+use std::ops;
+
+/// A 3x3 matrix stored in row-major order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix3 {
+    pub elements: [[f64; 3]; 3],
+}
+
+impl Matrix3 {
+    /// Constructs a new `Matrix3` from rows.
+    pub const fn new(elements: [[f64; 3]; 3]) -> Self {
+        Matrix3 { elements }
+    }
+
+    /// Returns the identity matrix.
+    pub const fn identity() -> Self {
+        Matrix3::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    /// Constructs a matrix whose rows are the given vectors.
+    pub fn from_rows(r0: &Vector3, r1: &Vector3, r2: &Vector3) -> Self {
+        Matrix3::new([r0.to_array(), r1.to_array(), r2.to_array()])
+    }
+
+    /// Constructs a matrix whose columns are the given vectors.
+    pub fn from_columns(c0: &Vector3, c1: &Vector3, c2: &Vector3) -> Self {
+        Matrix3::from_rows(c0, c1, c2).transpose()
+    }
+
+    /// Returns the matrix that rotates the coordinate frame about the x-axis by `angle` radians.
+    ///
+    /// Applied to a vector, a positive angle rotates the vector clockwise when viewed from
+    /// the positive x-axis towards the origin (the passive convention used by SOFA `iauRx`).
+    pub fn rot_x(angle: f64) -> Self {
+        let (s, c) = angle.sin_cos();
+        Matrix3::new([[1.0, 0.0, 0.0], [0.0, c, s], [0.0, -s, c]])
+    }
+
+    /// Returns the matrix that rotates the coordinate frame about the y-axis by `angle` radians.
+    pub fn rot_y(angle: f64) -> Self {
+        let (s, c) = angle.sin_cos();
+        Matrix3::new([[c, 0.0, -s], [0.0, 1.0, 0.0], [s, 0.0, c]])
+    }
+
+    /// Returns the matrix that rotates the coordinate frame about the z-axis by `angle` radians.
+    pub fn rot_z(angle: f64) -> Self {
+        let (s, c) = angle.sin_cos();
+        Matrix3::new([[c, s, 0.0], [-s, c, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    /// Returns the transpose of the matrix.
+    pub fn transpose(&self) -> Self {
+        let e = &self.elements;
+        Matrix3::new([
+            [e[0][0], e[1][0], e[2][0]],
+            [e[0][1], e[1][1], e[2][1]],
+            [e[0][2], e[1][2], e[2][2]],
+        ])
+    }
+
+    /// Returns the given row as a vector.
+    pub fn row(&self, index: usize) -> Vector3 {
+        Vector3::from(self.elements[index])
+    }
+
+    /// Returns the given column as a vector.
+    pub fn column(&self, index: usize) -> Vector3 {
+        Vector3::new(
+            self.elements[0][index],
+            self.elements[1][index],
+            self.elements[2][index],
+        )
+    }
+
+    /// Returns the determinant of the matrix.
+    pub fn determinant(&self) -> f64 {
+        self.row(0).dot(&self.row(1).cross(&self.row(2)))
+    }
+
+    /// Returns the inverse of the matrix, or `None` if it is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let (r0, r1, r2) = (self.row(0), self.row(1), self.row(2));
+        Some(Matrix3::from_columns(&r1.cross(&r2), &r2.cross(&r0), &r0.cross(&r1)) * (1.0 / det))
+    }
+}
+
+impl Default for Matrix3 {
+    fn default() -> Self {
+        Matrix3::identity()
+    }
+}
+
+impl ops::Index<(usize, usize)> for Matrix3 {
+    type Output = f64;
+
+    fn index(&self, index: (usize, usize)) -> &f64 {
+        &self.elements[index.0][index.1]
+    }
+}
+
+impl ops::IndexMut<(usize, usize)> for Matrix3 {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut f64 {
+        &mut self.elements[index.0][index.1]
+    }
+}
+
+impl ops::Mul<Matrix3> for Matrix3 {
+    type Output = Matrix3;
+
+    fn mul(self, rhs: Matrix3) -> Matrix3 {
+        let mut result = [[0.0; 3]; 3];
+        for (i, row) in result.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..3)
+                    .map(|k| self.elements[i][k] * rhs.elements[k][j])
+                    .sum();
+            }
+        }
+        Matrix3::new(result)
+    }
+}
+
+impl ops::Mul<Vector3> for Matrix3 {
+    type Output = Vector3;
+
+    fn mul(self, rhs: Vector3) -> Vector3 {
+        Vector3::new(
+            self.row(0).dot(&rhs),
+            self.row(1).dot(&rhs),
+            self.row(2).dot(&rhs),
+        )
+    }
+}
+
+impl ops::Mul<f64> for Matrix3 {
+    type Output = Matrix3;
+
+    fn mul(self, rhs: f64) -> Matrix3 {
+        let mut result = self;
+        for row in result.elements.iter_mut() {
+            for value in row.iter_mut() {
+                *value *= rhs;
+            }
+        }
+        result
+    }
+}
+
+impl ops::Add<Matrix3> for Matrix3 {
+    type Output = Matrix3;
+
+    fn add(self, rhs: Matrix3) -> Matrix3 {
+        let mut result = self;
+        for i in 0..3 {
+            for j in 0..3 {
+                result.elements[i][j] += rhs.elements[i][j];
+            }
+        }
+        result
+    }
+}
+
+impl ops::Sub<Matrix3> for Matrix3 {
+    type Output = Matrix3;
+
+    fn sub(self, rhs: Matrix3) -> Matrix3 {
+        self + rhs * -1.0
+    }
+}
+
+/// A 6x6 matrix stored in row-major order, such as the Jacobian or the covariance of a state
+/// of position and velocity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix6 {
+    pub elements: [[f64; 6]; 6],
+}
+
+impl Matrix6 {
+    /// Constructs a new `Matrix6` from rows.
+    pub const fn new(elements: [[f64; 6]; 6]) -> Self {
+        Matrix6 { elements }
+    }
+
+    /// Returns the zero matrix.
+    pub const fn zeros() -> Self {
+        Matrix6::new([[0.0; 6]; 6])
+    }
+
+    /// Returns the identity matrix.
+    pub fn identity() -> Self {
+        Matrix6::diagonal(&[1.0; 6])
+    }
+
+    /// Returns the diagonal matrix with the given diagonal.
+    pub fn diagonal(diagonal: &[f64; 6]) -> Self {
+        let mut result = Matrix6::zeros();
+        for (i, value) in diagonal.iter().enumerate() {
+            result.elements[i][i] = *value;
+        }
+        result
+    }
+
+    /// Constructs a matrix from its four 3x3 blocks, `[[a, b], [c, d]]`.
+    pub fn from_blocks(a: &Matrix3, b: &Matrix3, c: &Matrix3, d: &Matrix3) -> Self {
+        let mut result = Matrix6::zeros();
+        for i in 0..3 {
+            for j in 0..3 {
+                result.elements[i][j] = a.elements[i][j];
+                result.elements[i][j + 3] = b.elements[i][j];
+                result.elements[i + 3][j] = c.elements[i][j];
+                result.elements[i + 3][j + 3] = d.elements[i][j];
+            }
+        }
+        result
+    }
+
+    /// Returns the 3x3 block at the given block row and column, each 0 or 1.
+    pub fn block(&self, row: usize, column: usize) -> Matrix3 {
+        let mut result = Matrix3::new([[0.0; 3]; 3]);
+        for i in 0..3 {
+            for j in 0..3 {
+                result.elements[i][j] = self.elements[3 * row + i][3 * column + j];
+            }
+        }
+        result
+    }
+
+    /// Returns the diagonal.
+    pub fn diagonal_elements(&self) -> [f64; 6] {
+        std::array::from_fn(|i| self.elements[i][i])
+    }
+
+    pub fn transpose(&self) -> Self {
+        Matrix6::new(std::array::from_fn(|i| {
+            std::array::from_fn(|j| self.elements[j][i])
+        }))
+    }
+
+    /// Returns the product with a vector.
+    pub fn mul_vector(&self, vector: &[f64; 6]) -> [f64; 6] {
+        std::array::from_fn(|i| (0..6).map(|k| self.elements[i][k] * vector[k]).sum())
+    }
+
+    /// Returns the lower triangular factor `L` of the Cholesky decomposition `L Lᵀ`, or `None`
+    /// if the matrix is not symmetric positive definite.
+    pub fn cholesky(&self) -> Option<Self> {
+        let mut l = Matrix6::zeros();
+        for i in 0..6 {
+            for j in 0..=i {
+                let sum: f64 = (0..j).map(|k| l.elements[i][k] * l.elements[j][k]).sum();
+                if i == j {
+                    let value = self.elements[i][i] - sum;
+                    if value <= 0.0 || !value.is_finite() {
+                        return None;
+                    }
+                    l.elements[i][i] = value.sqrt();
+                } else {
+                    l.elements[i][j] = (self.elements[i][j] - sum) / l.elements[j][j];
+                }
+            }
+        }
+        Some(l)
+    }
+
+    /// Returns the inverse of the matrix, or `None` if it is singular, by Gauss-Jordan
+    /// elimination with partial pivoting.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut a = self.elements;
+        let mut result = Matrix6::identity().elements;
+        for column in 0..6 {
+            let pivot = (column..6)
+                .max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs()))
+                .unwrap();
+            if a[pivot][column] == 0.0 || !a[pivot][column].is_finite() {
+                return None;
+            }
+            a.swap(column, pivot);
+            result.swap(column, pivot);
+            let scale = 1.0 / a[column][column];
+            for j in 0..6 {
+                a[column][j] *= scale;
+                result[column][j] *= scale;
+            }
+            for i in 0..6 {
+                if i != column {
+                    let factor = a[i][column];
+                    for j in 0..6 {
+                        a[i][j] -= factor * a[column][j];
+                        result[i][j] -= factor * result[column][j];
+                    }
+                }
+            }
+        }
+        Some(Matrix6::new(result))
+    }
+
+    /// Returns the symmetric part `(M + Mᵀ) / 2`, removing the rounding errors that accumulate
+    /// in covariance matrices.
+    pub fn symmetrize(&self) -> Self {
+        (*self + self.transpose()) * 0.5
+    }
+}
+
+impl Default for Matrix6 {
+    fn default() -> Self {
+        Matrix6::identity()
+    }
+}
+
+impl ops::Index<(usize, usize)> for Matrix6 {
+    type Output = f64;
+
+    fn index(&self, index: (usize, usize)) -> &f64 {
+        &self.elements[index.0][index.1]
+    }
+}
+
+impl ops::IndexMut<(usize, usize)> for Matrix6 {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut f64 {
+        &mut self.elements[index.0][index.1]
+    }
+}
+
+impl ops::Mul<Matrix6> for Matrix6 {
+    type Output = Matrix6;
+
+    fn mul(self, rhs: Matrix6) -> Matrix6 {
+        let mut result = [[0.0; 6]; 6];
+        for (i, row) in result.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..6)
+                    .map(|k| self.elements[i][k] * rhs.elements[k][j])
+                    .sum();
+            }
+        }
+        Matrix6::new(result)
+    }
+}
+
+impl ops::Mul<f64> for Matrix6 {
+    type Output = Matrix6;
+
+    fn mul(self, rhs: f64) -> Matrix6 {
+        let mut result = self;
+        for row in result.elements.iter_mut() {
+            for value in row.iter_mut() {
+                *value *= rhs;
+            }
+        }
+        result
+    }
+}
+
+impl ops::Add<Matrix6> for Matrix6 {
+    type Output = Matrix6;
+
+    fn add(self, rhs: Matrix6) -> Matrix6 {
+        let mut result = self;
+        for i in 0..6 {
+            for j in 0..6 {
+                result.elements[i][j] += rhs.elements[i][j];
+            }
+        }
+        result
+    }
+}
+
+impl ops::Sub<Matrix6> for Matrix6 {
+    type Output = Matrix6;
+
+    fn sub(self, rhs: Matrix6) -> Matrix6 {
+        self + rhs * -1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    fn assert_vector_eq(a: Vector3, b: Vector3) {
+        assert!((a - b).norm() < 1e-14, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn rotation_test() {
+        let x = Vector3::new(1.0, 0.0, 0.0);
+        let y = Vector3::new(0.0, 1.0, 0.0);
+        let z = Vector3::new(0.0, 0.0, 1.0);
+
+        // Rotating the frame by +90 degrees about z moves the x-axis onto the old y-axis
+        assert_vector_eq(Matrix3::rot_z(FRAC_PI_2) * y, x);
+        assert_vector_eq(Matrix3::rot_x(FRAC_PI_2) * z, y);
+        assert_vector_eq(Matrix3::rot_y(FRAC_PI_2) * x, z);
+    }
+
+    #[test]
+    fn product_and_inverse_test() {
+        let m = Matrix3::rot_x(0.3) * Matrix3::rot_z(-1.2) * Matrix3::rot_y(2.0);
+        assert!((m.determinant() - 1.0).abs() < 1e-14);
+
+        let p = m * m.transpose();
+        for i in 0..3 {
+            for j in 0..3 {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((p[(i, j)] - expected).abs() < 1e-14);
+            }
+        }
+
+        let a = Matrix3::new([[2.0, 1.0, 0.0], [0.0, 3.0, 1.0], [1.0, 0.0, 4.0]]);
+        let v = Vector3::new(1.0, -2.0, 0.5);
+        assert_vector_eq(a.inverse().unwrap() * (a * v), v);
+
+        assert!(
+            Matrix3::new([[1.0, 2.0, 3.0], [2.0, 4.0, 6.0], [0.0, 0.0, 1.0]])
+                .inverse()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn matrix6_test() {
+        let r = Matrix3::rot_x(0.3) * Matrix3::rot_z(-1.2);
+        let m = Matrix6::from_blocks(&r, &Matrix3::identity(), &(r * 2.0), &r.transpose());
+        assert_eq!(m.block(1, 0), r * 2.0);
+        assert_eq!(m.block(0, 1), Matrix3::identity());
+        let p = m.inverse().unwrap() * m;
+        for i in 0..6 {
+            for j in 0..6 {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((p[(i, j)] - expected).abs() < 1e-13);
+            }
+        }
+
+        let covariance = m * m.transpose() + Matrix6::identity();
+        let l = covariance.cholesky().unwrap();
+        let difference = l * l.transpose() - covariance;
+        for i in 0..6 {
+            assert!(l[(i, i)] > 0.0);
+            for j in 0..6 {
+                assert!(difference[(i, j)].abs() < 1e-13);
+                if j > i {
+                    assert_eq!(l[(i, j)], 0.0);
+                }
+            }
+        }
+        assert!(Matrix6::diagonal(&[1.0, 1.0, -1.0, 1.0, 1.0, 1.0])
+            .cholesky()
+            .is_none());
+        assert!(Matrix6::zeros().inverse().is_none());
+        assert_eq!(
+            Matrix6::diagonal(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).mul_vector(&[1.0; 6]),
+            [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+        );
+    }
+}
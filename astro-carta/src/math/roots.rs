@@ -0,0 +1,119 @@
+/// Finds a root of `f` in a bracket with Brent's method, combining bisection, secant and inverse
+/// quadratic interpolation.
+///
+/// The function may fail, in which case the error is returned. The values `fa` and `fb` of the
+/// function at the ends of the bracket must have opposite signs, or one of them be zero.
+///
+/// # Arguments
+///
+/// * `f` - Function whose root is sought.
+/// * `a`, `fa` - One end of the bracket and the value of the function there.
+/// * `b`, `fb` - The other end of the bracket and the value of the function there.
+/// * `tolerance` - Width of the final bracket around the root.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::math::roots;
+///
+/// let f = |x: f64| Ok::<_, std::convert::Infallible>(x * x - 2.0);
+/// let root = roots::brent(f, 0.0, -2.0, 2.0, 2.0, 1e-12).unwrap();
+/// assert!((root - 2f64.sqrt()).abs() < 1e-12);
+/// ```
+pub fn brent<F, E>(mut f: F, a: f64, fa: f64, b: f64, fb: f64, tolerance: f64) -> Result<f64, E>
+where
+    F: FnMut(f64) -> Result<f64, E>,
+{
+    let (mut a, mut fa, mut b, mut fb) = (a, fa, b, fb);
+    if fa == 0.0 {
+        return Ok(a);
+    }
+    if fb == 0.0 {
+        return Ok(b);
+    }
+    // The root lies between b, the best estimate, and c
+    let (mut c, mut fc) = (a, fa);
+    let mut d = b - a;
+    let mut e = d;
+    loop {
+        if fb.signum() == fc.signum() {
+            (c, fc) = (a, fa);
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            (a, fa) = (b, fb);
+            (b, fb) = (c, fc);
+            (c, fc) = (a, fa);
+        }
+        let limit = 2.0 * f64::EPSILON * b.abs() + 0.5 * tolerance;
+        let middle = 0.5 * (c - b);
+        if middle.abs() <= limit || fb == 0.0 {
+            return Ok(b);
+        }
+        if e.abs() >= limit && fa.abs() > fb.abs() {
+            // Secant step, or inverse quadratic interpolation through three distinct points
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (2.0 * middle * s, 1.0 - s)
+            } else {
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (2.0 * middle * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            } else {
+                p = -p;
+            }
+            if 2.0 * p < (3.0 * middle * q - (limit * q).abs()).min((e * q).abs()) {
+                e = d;
+                d = p / q;
+            } else {
+                d = middle;
+                e = d;
+            }
+        } else {
+            d = middle;
+            e = d;
+        }
+        (a, fa) = (b, fb);
+        b += if d.abs() > limit {
+            d
+        } else {
+            limit.copysign(middle)
+        };
+        fb = f(b)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brent_test() {
+        let mut calls = 0;
+        let mut f = |x: f64| {
+            calls += 1;
+            Ok::<_, ()>(x.cos() - x)
+        };
+        let (fa, fb) = (f(0.0).unwrap(), f(1.0).unwrap());
+        let root = brent(&mut f, 0.0, fa, 1.0, fb, 1e-14).unwrap();
+        assert!((root - 0.739_085_133_215_160_6).abs() < 1e-14);
+        assert!(calls < 12);
+
+        // A steep function defeating interpolation
+        let f = |x: f64| Ok::<_, ()>((x - 0.3).powi(9));
+        let root = brent(f, -1.0, f(-1.0).unwrap(), 1.0, f(1.0).unwrap(), 1e-10).unwrap();
+        assert!((root - 0.3).abs() < 1e-9);
+
+        // Roots at the ends and failing functions
+        assert_eq!(brent(f, 0.3, 0.0, 1.0, 1.0, 1e-10), Ok(0.3));
+        let failing = |_: f64| Err::<f64, _>("failed");
+        assert_eq!(brent(failing, 0.0, -1.0, 1.0, 1.0, 1e-10), Err("failed"));
+    }
+}
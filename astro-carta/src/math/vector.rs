@@ -0,0 +1,232 @@
+use std::ops;
+
+/// A three-dimensional Cartesian vector.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vector3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vector3 {
+    /// Constructs a new `Vector3` from its components.
+    pub const fn new(x: f64, y: f64, z: f64) -> Self {
+        Vector3 { x, y, z }
+    }
+
+    /// Returns the zero vector.
+    pub const fn zeros() -> Self {
+        Vector3::new(0.0, 0.0, 0.0)
+    }
+
+    /// Constructs a unit vector from spherical angles.
+    ///
+    /// # Arguments
+    ///
+    /// * `longitude` - Angle in the x-y plane measured from the x-axis towards the y-axis, in radians.
+    /// * `latitude` - Angle above the x-y plane, in radians.
+    pub fn from_spherical(longitude: f64, latitude: f64) -> Self {
+        let (sin_lat, cos_lat) = latitude.sin_cos();
+        let (sin_lon, cos_lon) = longitude.sin_cos();
+        Vector3::new(cos_lat * cos_lon, cos_lat * sin_lon, sin_lat)
+    }
+
+    /// Returns the spherical angles `(longitude, latitude)` of the vector in radians.
+    ///
+    /// The longitude is in the range [0, 2π) and the latitude in [-π/2, π/2].
+    pub fn to_spherical(&self) -> (f64, f64) {
+        let rho = self.x.hypot(self.y);
+        let longitude = if rho == 0.0 {
+            0.0
+        } else {
+            super::normalize_angle(self.y.atan2(self.x))
+        };
+        let latitude = if rho == 0.0 && self.z == 0.0 {
+            0.0
+        } else {
+            self.z.atan2(rho)
+        };
+        (longitude, latitude)
+    }
+
+    /// Computes the dot product with another vector.
+    pub fn dot(&self, other: &Vector3) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Computes the cross product with another vector.
+    pub fn cross(&self, other: &Vector3) -> Vector3 {
+        Vector3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// Returns the Euclidean norm of the vector.
+    pub fn norm(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    /// Returns the vector scaled to unit length, or the zero vector if the norm is zero.
+    pub fn normalize(&self) -> Vector3 {
+        let norm = self.norm();
+        if norm == 0.0 {
+            Vector3::zeros()
+        } else {
+            *self / norm
+        }
+    }
+
+    /// Returns the angle between two vectors in radians.
+    pub fn angle(&self, other: &Vector3) -> f64 {
+        self.cross(other).norm().atan2(self.dot(other))
+    }
+
+    /// Returns the components as an array.
+    pub fn to_array(&self) -> [f64; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+impl From<[f64; 3]> for Vector3 {
+    fn from(value: [f64; 3]) -> Self {
+        Vector3::new(value[0], value[1], value[2])
+    }
+}
+
+impl ops::Index<usize> for Vector3 {
+    type Output = f64;
+
+    fn index(&self, index: usize) -> &f64 {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Vector3 index out of range: {}", index),
+        }
+    }
+}
+
+impl ops::IndexMut<usize> for Vector3 {
+    fn index_mut(&mut self, index: usize) -> &mut f64 {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("Vector3 index out of range: {}", index),
+        }
+    }
+}
+
+impl ops::Add<Vector3> for Vector3 {
+    type Output = Vector3;
+
+    fn add(self, rhs: Vector3) -> Vector3 {
+        Vector3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl ops::AddAssign<Vector3> for Vector3 {
+    fn add_assign(&mut self, rhs: Vector3) {
+        *self = *self + rhs;
+    }
+}
+
+impl ops::Sub<Vector3> for Vector3 {
+    type Output = Vector3;
+
+    fn sub(self, rhs: Vector3) -> Vector3 {
+        Vector3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl ops::SubAssign<Vector3> for Vector3 {
+    fn sub_assign(&mut self, rhs: Vector3) {
+        *self = *self - rhs;
+    }
+}
+
+impl ops::Neg for Vector3 {
+    type Output = Vector3;
+
+    fn neg(self) -> Vector3 {
+        Vector3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl ops::Mul<f64> for Vector3 {
+    type Output = Vector3;
+
+    fn mul(self, rhs: f64) -> Vector3 {
+        Vector3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl ops::Mul<Vector3> for f64 {
+    type Output = Vector3;
+
+    fn mul(self, rhs: Vector3) -> Vector3 {
+        rhs * self
+    }
+}
+
+impl ops::Div<f64> for Vector3 {
+    type Output = Vector3;
+
+    fn div(self, rhs: f64) -> Vector3 {
+        Vector3::new(self.x / rhs, self.y / rhs, self.z / rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    #[test]
+    fn arithmetic_test() {
+        let a = Vector3::new(1.0, 2.0, 3.0);
+        let b = Vector3::new(-4.0, 5.0, 0.5);
+
+        assert_eq!(a + b, Vector3::new(-3.0, 7.0, 3.5));
+        assert_eq!(a - b, Vector3::new(5.0, -3.0, 2.5));
+        assert_eq!(-a, Vector3::new(-1.0, -2.0, -3.0));
+        assert_eq!(a * 2.0, Vector3::new(2.0, 4.0, 6.0));
+        assert_eq!(2.0 * a, a * 2.0);
+        assert_eq!(a / 2.0, Vector3::new(0.5, 1.0, 1.5));
+        assert_eq!(a.dot(&b), 7.5);
+        assert_eq!(a[0], 1.0);
+        assert_eq!(a[2], 3.0);
+    }
+
+    #[test]
+    fn cross_test() {
+        let x = Vector3::new(1.0, 0.0, 0.0);
+        let y = Vector3::new(0.0, 1.0, 0.0);
+        assert_eq!(x.cross(&y), Vector3::new(0.0, 0.0, 1.0));
+        assert_eq!(y.cross(&x), Vector3::new(0.0, 0.0, -1.0));
+        assert!((x.angle(&y) - FRAC_PI_2).abs() < 1e-15);
+    }
+
+    #[test]
+    fn spherical_test() {
+        let v = Vector3::from_spherical(FRAC_PI_4, 0.3);
+        assert!((v.norm() - 1.0).abs() < 1e-15);
+
+        let (lon, lat) = v.to_spherical();
+        assert!((lon - FRAC_PI_4).abs() < 1e-15);
+        assert!((lat - 0.3).abs() < 1e-15);
+
+        let (lon, lat) = Vector3::new(0.0, 0.0, -2.0).to_spherical();
+        assert_eq!(lon, 0.0);
+        assert!((lat + FRAC_PI_2).abs() < 1e-15);
+    }
+
+    #[test]
+    fn normalize_test() {
+        let v = Vector3::new(3.0, 0.0, 4.0).normalize();
+        assert!((v.norm() - 1.0).abs() < 1e-15);
+        assert_eq!(Vector3::zeros().normalize(), Vector3::zeros());
+    }
+}
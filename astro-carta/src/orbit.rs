@@ -0,0 +1,59 @@
+pub mod covariance;
+pub mod cr3bp;
+pub mod determination;
+pub mod elements;
+pub mod equinoctial;
+pub mod events;
+pub mod force;
+pub mod integrator;
+pub mod kepler;
+pub mod lambert;
+pub mod link;
+pub mod maneuver;
+pub mod numerical;
+pub mod odm;
+pub mod pass;
+pub mod propagation;
+pub mod sgp4;
+pub mod station;
+pub mod tle;
+
+pub use covariance::Covariance;
+pub use elements::{Anomaly, KeplerianElements};
+pub use equinoctial::{EquinoctialElements, ModifiedEquinoctialElements};
+pub use numerical::NumericalPropagator;
+pub use propagation::{J2Secular, Propagator, TwoBody};
+pub use sgp4::Sgp4;
+pub use tle::Tle;
+
+use std::fmt;
+
+/// Error raised when orbital elements are invalid or cannot be derived from a state
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrbitError {
+    /// The elements do not describe a conic section, or the anomaly lies outside it
+    InvalidElements(&'static str),
+    /// The gravitational parameter is not positive
+    InvalidGravitationalParameter,
+    /// The state has zero position or zero angular momentum, a rectilinear or degenerate orbit
+    DegenerateState,
+    /// An iterative solution failed to converge
+    NoConvergence,
+}
+
+impl fmt::Display for OrbitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrbitError::InvalidElements(reason) => write!(f, "invalid orbital elements: {reason}"),
+            OrbitError::InvalidGravitationalParameter => {
+                write!(f, "gravitational parameter must be positive")
+            }
+            OrbitError::DegenerateState => {
+                write!(f, "state has no angular momentum, the orbit is degenerate")
+            }
+            OrbitError::NoConvergence => write!(f, "iteration failed to converge"),
+        }
+    }
+}
+
+impl std::error::Error for OrbitError {}
@@ -0,0 +1,498 @@
+use std::fmt;
+
+use super::numerical::{from_slice, to_array};
+use crate::datetime::DateTime;
+use crate::ephemeris::StateVector;
+use crate::math::{Matrix3, Matrix6, Vector3};
+
+/// Error raised when a covariance cannot be transformed or propagated
+#[derive(Debug, Clone, PartialEq)]
+pub enum CovarianceError {
+    /// The covariance matrix has a negative variance along some direction
+    NotPositiveDefinite,
+    /// The reference state has no angular momentum, so that its RTN axes are undefined
+    DegenerateState,
+    /// The propagation of a sigma point failed with the given message
+    Propagation(String),
+}
+
+impl fmt::Display for CovarianceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CovarianceError::NotPositiveDefinite => {
+                write!(f, "covariance matrix is not positive semidefinite")
+            }
+            CovarianceError::DegenerateState => {
+                write!(
+                    f,
+                    "state has no angular momentum, the RTN axes are undefined"
+                )
+            }
+            CovarianceError::Propagation(message) => {
+                write!(f, "propagation of a sigma point failed: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CovarianceError {}
+
+/// Returns the rotation from the inertial axes of a state to its RTN axes.
+///
+/// The radial axis R points along the position, the normal axis N along the angular momentum
+/// and the transverse axis T completes the right-handed triad, close to the velocity on a
+/// near-circular orbit. These are also known as the RIC (radial, in-track, cross-track) or RSW
+/// axes.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::covariance::rtn_rotation;
+///
+/// let state = StateVector {
+///     position: Vector3::new(0.0, 7e6, 0.0),
+///     velocity: Vector3::new(-7.5e3, 0.0, 0.0),
+/// };
+/// let rotation = rtn_rotation(&state).unwrap();
+/// assert_eq!(rotation * Vector3::new(0.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+/// assert_eq!(rotation * Vector3::new(-1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+/// ```
+pub fn rtn_rotation(state: &StateVector) -> Result<Matrix3, CovarianceError> {
+    let momentum = state.position.cross(&state.velocity);
+    let (r, h) = (state.position.norm(), momentum.norm());
+    if r == 0.0 || h == 0.0 {
+        return Err(CovarianceError::DegenerateState);
+    }
+    let radial = state.position / r;
+    let normal = momentum / h;
+    Ok(Matrix3::from_rows(&radial, &normal.cross(&radial), &normal))
+}
+
+/// Returns the block-diagonal matrix rotating both the position and the velocity.
+fn block_rotation(rotation: &Matrix3) -> Matrix6 {
+    let zero = Matrix3::new([[0.0; 3]; 3]);
+    Matrix6::from_blocks(rotation, &zero, &zero, rotation)
+}
+
+/// Covariance of the position and velocity of an object about a reference state
+///
+/// The matrix is in m², m²/s and m²/s², in the inertial axes of the state. In RTN axes, the
+/// position and velocity deviations are both rotated into the axes of the reference state, the
+/// convention of the CCSDS messages, without the velocity of the rotating axes.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::{Matrix6, Vector3};
+/// use astro_carta::orbit::covariance::Covariance;
+///
+/// let epoch = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let state = StateVector {
+///     position: Vector3::new(0.0, 7e6, 0.0),
+///     velocity: Vector3::new(-7.5e3, 0.0, 0.0),
+/// };
+/// // 100 m in-track and 10 m in the other directions
+/// let rtn = Matrix6::diagonal(&[1e2, 1e4, 1e2, 1e-4, 1e-4, 1e-4]);
+/// let covariance = Covariance::from_rtn(epoch, state, &rtn).unwrap();
+/// assert!((covariance.standard_deviations()[0] - 100.0).abs() < 1e-9);
+/// assert!((covariance.rtn().unwrap()[(1, 1)] - 1e4).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Covariance {
+    /// Epoch of the state and the covariance
+    pub epoch: DateTime,
+    /// Reference state, with position in meters and velocity in m/s
+    pub state: StateVector,
+    /// Covariance matrix in inertial axes
+    pub matrix: Matrix6,
+}
+
+impl Covariance {
+    /// Constructs a covariance from its matrix in the inertial axes of the state.
+    pub fn new(epoch: DateTime, state: StateVector, matrix: Matrix6) -> Self {
+        Covariance {
+            epoch,
+            state,
+            matrix,
+        }
+    }
+
+    /// Constructs a covariance from its matrix in the RTN axes of the state.
+    pub fn from_rtn(
+        epoch: DateTime,
+        state: StateVector,
+        matrix: &Matrix6,
+    ) -> Result<Self, CovarianceError> {
+        let rotation = block_rotation(&rtn_rotation(&state)?);
+        Ok(Covariance::new(
+            epoch,
+            state,
+            (rotation.transpose() * *matrix * rotation).symmetrize(),
+        ))
+    }
+
+    /// Returns the matrix in the RTN axes of the state.
+    pub fn rtn(&self) -> Result<Matrix6, CovarianceError> {
+        let rotation = block_rotation(&rtn_rotation(&self.state)?);
+        Ok((rotation * self.matrix * rotation.transpose()).symmetrize())
+    }
+
+    /// Returns the covariance in other inertial axes, given the rotation from the current axes.
+    pub fn rotate(&self, rotation: &Matrix3) -> Covariance {
+        let block = block_rotation(rotation);
+        Covariance::new(
+            self.epoch,
+            StateVector {
+                position: *rotation * self.state.position,
+                velocity: *rotation * self.state.velocity,
+            },
+            (block * self.matrix * block.transpose()).symmetrize(),
+        )
+    }
+
+    /// Maps the covariance linearly to another epoch, `Φ P Φᵀ`, given the state at that epoch
+    /// and the state transition matrix `Φ` from the current epoch.
+    pub fn transition(&self, epoch: DateTime, state: StateVector, transition: &Matrix6) -> Self {
+        Covariance::new(
+            epoch,
+            state,
+            (*transition * self.matrix * transition.transpose()).symmetrize(),
+        )
+    }
+
+    /// Returns the standard deviations of the position in meters and the velocity in m/s along
+    /// the inertial axes.
+    pub fn standard_deviations(&self) -> [f64; 6] {
+        self.matrix
+            .diagonal_elements()
+            .map(|variance| variance.max(0.0).sqrt())
+    }
+}
+
+/// Propagation of a covariance through a nonlinear propagation by the unscented transform
+///
+/// The mean state and the covariance are sampled by 13 sigma points, the mean and `±√(n + λ)`
+/// times the columns of a square root of the covariance, with `λ = α² (n + κ) - n` and `n = 6`.
+/// Each point is propagated, and the weighted mean and covariance of the propagated points
+/// estimate those of the propagated distribution, capturing the nonlinearity that a state
+/// transition matrix neglects over long spans.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::constants::EARTH_GRAVITATIONAL_PARAMETER;
+/// use astro_carta::datetime::{DateTime, TimeDelta};
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::{Matrix6, Vector3};
+/// use astro_carta::orbit::covariance::{Covariance, UnscentedTransform};
+/// use astro_carta::orbit::propagation;
+///
+/// let epoch = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let state = StateVector {
+///     position: Vector3::new(7e6, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, 7.5e3, 0.0),
+/// };
+/// let matrix = Matrix6::diagonal(&[1e6, 1e6, 1e6, 1.0, 1.0, 1.0]);
+/// let covariance = Covariance::new(epoch, state, matrix);
+/// let duration = TimeDelta::days(1.0);
+/// let propagated = UnscentedTransform::default()
+///     .propagate(&covariance, epoch + duration, |state| {
+///         propagation::universal(state, EARTH_GRAVITATIONAL_PARAMETER, duration)
+///     })
+///     .unwrap();
+/// // The in-track uncertainty grows to hundreds of kilometers
+/// assert!(propagated.rtn().unwrap()[(1, 1)].sqrt() > 1e5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnscentedTransform {
+    /// Spread of the sigma points, between 0 and 1
+    pub alpha: f64,
+    /// Prior knowledge of the distribution, 2 for a Gaussian
+    pub beta: f64,
+    /// Secondary scaling of the spread, usually 0 or `3 - n`
+    pub kappa: f64,
+}
+
+impl UnscentedTransform {
+    pub fn new(alpha: f64, beta: f64, kappa: f64) -> Self {
+        UnscentedTransform { alpha, beta, kappa }
+    }
+
+    fn lambda(&self) -> f64 {
+        self.alpha * self.alpha * (6.0 + self.kappa) - 6.0
+    }
+
+    /// Returns the weight of the central point in the covariance, and the weight of the other
+    /// points in both the mean and the covariance.
+    fn weights(&self) -> (f64, f64) {
+        let lambda = self.lambda();
+        let center = lambda / (6.0 + lambda) + 1.0 - self.alpha * self.alpha + self.beta;
+        (center, 0.5 / (6.0 + lambda))
+    }
+
+    /// Returns the 13 sigma points of a covariance, the reference state first.
+    pub fn sigma_points(
+        &self,
+        covariance: &Covariance,
+    ) -> Result<Vec<StateVector>, CovarianceError> {
+        let root = square_root(&covariance.matrix).ok_or(CovarianceError::NotPositiveDefinite)?;
+        let scale = (6.0 + self.lambda()).sqrt();
+        let mut points = vec![covariance.state];
+        for sign in [1.0, -1.0] {
+            for j in 0..6 {
+                let column = |offset: usize| {
+                    Vector3::new(
+                        root[(offset, j)],
+                        root[(offset + 1, j)],
+                        root[(offset + 2, j)],
+                    ) * (sign * scale)
+                };
+                points.push(StateVector {
+                    position: covariance.state.position + column(0),
+                    velocity: covariance.state.velocity + column(3),
+                });
+            }
+        }
+        Ok(points)
+    }
+
+    /// Returns the weighted mean and covariance of 13 propagated sigma points at an epoch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are not 13 points.
+    pub fn combine(&self, epoch: DateTime, points: &[StateVector]) -> Covariance {
+        assert_eq!(
+            points.len(),
+            13,
+            "the unscented transform has 13 sigma points"
+        );
+        let (center_weight, weight) = self.weights();
+        let points: Vec<[f64; 6]> = points.iter().map(to_array).collect();
+
+        // The weights sum to one, so that the mean is accumulated relative to the central
+        // point, whose weight can be large and negative, without the cancellation of the large
+        // positions
+        let center = points[0];
+        let mut mean = center;
+        for point in &points[1..] {
+            for i in 0..6 {
+                mean[i] += weight * (point[i] - center[i]);
+            }
+        }
+
+        let mut matrix = Matrix6::zeros();
+        for (index, point) in points.iter().enumerate() {
+            let w = if index == 0 { center_weight } else { weight };
+            let deviation: [f64; 6] = std::array::from_fn(|i| point[i] - mean[i]);
+            for i in 0..6 {
+                for j in 0..6 {
+                    matrix[(i, j)] += w * deviation[i] * deviation[j];
+                }
+            }
+        }
+        Covariance::new(epoch, from_slice(&mean), matrix.symmetrize())
+    }
+
+    /// Propagates a covariance to an epoch, given a function that propagates a state from the
+    /// epoch of the covariance to that epoch.
+    pub fn propagate<F, E>(
+        &self,
+        covariance: &Covariance,
+        epoch: DateTime,
+        mut propagate: F,
+    ) -> Result<Covariance, CovarianceError>
+    where
+        F: FnMut(&StateVector) -> Result<StateVector, E>,
+        E: std::error::Error,
+    {
+        let points = self
+            .sigma_points(covariance)?
+            .iter()
+            .map(|point| {
+                propagate(point).map_err(|error| CovarianceError::Propagation(error.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.combine(epoch, &points))
+    }
+}
+
+/// The scaling of Wan and van der Merwe, with sigma points at `√6` standard deviations
+impl Default for UnscentedTransform {
+    fn default() -> Self {
+        UnscentedTransform::new(1.0, 2.0, 0.0)
+    }
+}
+
+/// Returns a lower triangular square root of a positive semidefinite matrix, by a Cholesky
+/// decomposition whose columns of zero variance are left null.
+fn square_root(matrix: &Matrix6) -> Option<Matrix6> {
+    let trace: f64 = matrix.diagonal_elements().iter().sum();
+    let tolerance = 1e-14 * trace.abs();
+    let mut l = Matrix6::zeros();
+    for j in 0..6 {
+        let sum: f64 = (0..j).map(|k| l[(j, k)] * l[(j, k)]).sum();
+        let pivot = matrix[(j, j)] - sum;
+        if pivot < -tolerance || !pivot.is_finite() {
+            return None;
+        }
+        if pivot <= tolerance {
+            continue;
+        }
+        l[(j, j)] = pivot.sqrt();
+        for i in j + 1..6 {
+            let sum: f64 = (0..j).map(|k| l[(i, k)] * l[(j, k)]).sum();
+            l[(i, j)] = (matrix[(i, j)] - sum) / l[(j, j)];
+        }
+    }
+    Some(l)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::EARTH_GRAVITATIONAL_PARAMETER;
+    use crate::datetime::TimeDelta;
+    use crate::orbit::force::PointMass;
+    use crate::orbit::integrator::{Integrator, StepControl};
+    use crate::orbit::numerical::NumericalPropagator;
+    use crate::orbit::propagation;
+
+    fn epoch() -> DateTime {
+        DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap()
+    }
+
+    fn state() -> StateVector {
+        StateVector {
+            position: Vector3::new(5e6, 4e6, 1e6),
+            velocity: Vector3::new(-4.5e3, 5e3, 2.5e3),
+        }
+    }
+
+    fn assert_close(a: &Matrix6, b: &Matrix6, tolerance: f64) {
+        for i in 0..6 {
+            for j in 0..6 {
+                let scale = (a[(i, i)] * a[(j, j)]).sqrt();
+                assert!(
+                    (a[(i, j)] - b[(i, j)]).abs() <= tolerance * scale,
+                    "({i}, {j}): {} != {}",
+                    a[(i, j)],
+                    b[(i, j)]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rtn_test() {
+        let state = state();
+        let rotation = rtn_rotation(&state).unwrap();
+        let identity = rotation * rotation.transpose();
+        for i in 0..3 {
+            for j in 0..3 {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((identity[(i, j)] - expected).abs() < 1e-15);
+            }
+        }
+        let velocity = rotation * state.velocity;
+        assert!(velocity.y > 0.0 && velocity.z.abs() < 1e-12);
+
+        let rtn = Matrix6::diagonal(&[4.0, 400.0, 1.0, 1e-4, 1e-6, 1e-6]);
+        let covariance = Covariance::from_rtn(epoch(), state, &rtn).unwrap();
+        assert_close(&covariance.rtn().unwrap(), &rtn, 1e-12);
+        // The radial variance is that along the position
+        let u = state.position.normalize();
+        let radial: f64 = (0..3)
+            .flat_map(|i| (0..3).map(move |j| (i, j)))
+            .map(|(i, j)| u[i] * covariance.matrix[(i, j)] * u[j])
+            .sum();
+        assert!((radial - 4.0).abs() < 1e-12);
+
+        // Rotating the inertial axes leaves the RTN matrix unchanged
+        let rotated = covariance.rotate(&(Matrix3::rot_z(0.7) * Matrix3::rot_x(-0.2)));
+        assert_close(&rotated.rtn().unwrap(), &rtn, 1e-12);
+        assert!((rotated.state.position.norm() - state.position.norm()).abs() < 1e-8);
+
+        let degenerate = StateVector {
+            position: Vector3::new(7e6, 0.0, 0.0),
+            velocity: Vector3::new(1e3, 0.0, 0.0),
+        };
+        assert_eq!(
+            rtn_rotation(&degenerate),
+            Err(CovarianceError::DegenerateState)
+        );
+    }
+
+    #[test]
+    fn sigma_points_test() {
+        let matrix = Matrix6::diagonal(&[1e4, 4e4, 1e4, 1.0, 0.0, 1.0]);
+        let covariance = Covariance::new(epoch(), state(), matrix);
+        for transform in [
+            UnscentedTransform::default(),
+            UnscentedTransform::new(1e-3, 2.0, 0.0),
+            UnscentedTransform::new(0.5, 2.0, -3.0),
+        ] {
+            // Without propagation, the points give back the mean and covariance
+            let points = transform.sigma_points(&covariance).unwrap();
+            assert_eq!(points.len(), 13);
+            assert_eq!(points[0], covariance.state);
+            let combined = transform.combine(epoch(), &points);
+            assert!((combined.state.position - covariance.state.position).norm() < 1e-6);
+            assert_close(&combined.matrix, &matrix, 1e-6);
+            assert!(combined.matrix[(4, 4)].abs() < 1e-12);
+        }
+
+        let indefinite = Covariance::new(
+            epoch(),
+            state(),
+            Matrix6::diagonal(&[1.0, -1.0, 1.0, 1.0, 1.0, 1.0]),
+        );
+        assert_eq!(
+            UnscentedTransform::default().sigma_points(&indefinite),
+            Err(CovarianceError::NotPositiveDefinite)
+        );
+    }
+
+    #[test]
+    fn propagation_test() {
+        let rtn = Matrix6::diagonal(&[1.0, 25.0, 1.0, 1e-6, 1e-6, 1e-6]);
+        let covariance = Covariance::from_rtn(epoch(), state(), &rtn).unwrap();
+        let propagator = NumericalPropagator::new(
+            epoch(),
+            state(),
+            Integrator::DormandPrince853(StepControl::new(1e-12, 1e-8)),
+        )
+        .with_force(PointMass::new(EARTH_GRAVITATIONAL_PARAMETER));
+        let end = epoch() + TimeDelta::hours(6.0);
+        let (final_state, transition) = propagator.propagate_with_transition(&end).unwrap();
+        let linear = covariance.transition(end, final_state, &transition);
+
+        // For a small covariance the dynamics are nearly linear over the span, and the
+        // unscented transform agrees with the transition matrix
+        let duration = end - epoch();
+        let unscented = UnscentedTransform::default()
+            .propagate(&covariance, end, |state| {
+                propagation::universal(state, EARTH_GRAVITATIONAL_PARAMETER, duration)
+            })
+            .unwrap();
+        assert!((unscented.state.position - final_state.position).norm() < 1e-3);
+        assert_close(&unscented.matrix, &linear.matrix, 1e-4);
+
+        // The in-track uncertainty grows fastest
+        let sigmas = linear.rtn().unwrap().diagonal_elements().map(f64::sqrt);
+        assert!(sigmas[1] > sigmas[0] && sigmas[1] > sigmas[2] && sigmas[1] > 5.0);
+        assert!(linear
+            .standard_deviations()
+            .iter()
+            .all(|sigma| *sigma > 0.0));
+
+        let failing = UnscentedTransform::default().propagate(&covariance, end, |_| {
+            Err::<StateVector, _>(crate::orbit::OrbitError::NoConvergence)
+        });
+        assert!(matches!(failing, Err(CovarianceError::Propagation(_))));
+    }
+}
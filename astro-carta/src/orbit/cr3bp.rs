@@ -0,0 +1,829 @@
+use std::f64::consts::PI;
+use std::fmt;
+
+use super::integrator::{Integrator, Solution};
+use super::numerical::{from_slice, to_array, PropagationError};
+use crate::constants::{
+    ASTRONOMICAL_UNIT, EARTH_GRAVITATIONAL_PARAMETER, MOON_GRAVITATIONAL_PARAMETER,
+    SUN_GRAVITATIONAL_PARAMETER,
+};
+use crate::datetime::DateTime;
+use crate::ephemeris::{Ephemeris, StateVector};
+use crate::math::{roots, Matrix3, Matrix6, Vector3};
+
+/// Mean distance between the Earth and the Moon in meters
+const EARTH_MOON_DISTANCE: f64 = 384_400e3;
+
+/// Spacing of the samples in which a crossing of the x-z plane is sought, in units of time
+const CROSSING_STEP: f64 = 1e-2;
+
+/// Error raised by the circular restricted three-body problem
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cr3bpError {
+    /// A gravitational parameter or the distance between the primaries is not positive
+    InvalidMassRatio,
+    /// The integration of the equations of motion failed
+    Propagation(PropagationError),
+    /// The trajectory does not cross the x-z plane within one revolution of the primaries
+    NoCrossing,
+    /// The differential correction did not converge
+    NoConvergence,
+    /// The primaries coincide or move along a line, so the synodic frame is undefined
+    DegenerateGeometry,
+    /// The ephemeris failed to give the state of a primary
+    Ephemeris(String),
+}
+
+impl fmt::Display for Cr3bpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Cr3bpError::InvalidMassRatio => {
+                write!(f, "gravitational parameters and distance must be positive")
+            }
+            Cr3bpError::Propagation(error) => write!(f, "{error}"),
+            Cr3bpError::NoCrossing => write!(f, "trajectory does not cross the x-z plane"),
+            Cr3bpError::NoConvergence => write!(f, "differential correction did not converge"),
+            Cr3bpError::DegenerateGeometry => write!(f, "synodic frame is undefined"),
+            Cr3bpError::Ephemeris(message) => write!(f, "ephemeris failed: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Cr3bpError {}
+
+impl From<PropagationError> for Cr3bpError {
+    fn from(error: PropagationError) -> Self {
+        Cr3bpError::Propagation(error)
+    }
+}
+
+/// One of the five equilibrium points of the rotating frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LagrangePoint {
+    /// Between the primaries
+    L1,
+    /// Beyond the smaller primary
+    L2,
+    /// Beyond the larger primary
+    L3,
+    /// Leading the smaller primary by 60°
+    L4,
+    /// Trailing the smaller primary by 60°
+    L5,
+}
+
+/// A circular restricted three-body problem, in which a massless object moves under the
+/// gravity of two primaries on circular orbits about their barycenter
+///
+/// States are nondimensional in the synodic frame, centered on the barycenter and rotating
+/// with the primaries: the unit of length is the distance between the primaries, the unit of
+/// time makes their angular velocity 1, the larger primary lies at `(-μ, 0, 0)` and the
+/// smaller one at `(1 - μ, 0, 0)`, with `μ` the mass ratio.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::orbit::cr3bp::{LagrangePoint, System};
+///
+/// let system = System::earth_moon();
+/// let l1 = system.lagrange_point(LagrangePoint::L1);
+/// // L1 lies about 326 400 km from the Earth
+/// assert!(((l1.x + system.mass_ratio) * system.length / 1e3 - 326_400.0).abs() < 100.0);
+/// assert!((system.time_unit() / 86_400.0 - 4.34).abs() < 0.01);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct System {
+    /// Mass of the smaller primary over the total mass
+    pub mass_ratio: f64,
+    /// Distance between the primaries in meters
+    pub length: f64,
+    /// Sum of the gravitational parameters of the primaries in m³/s²
+    pub gravitational_parameter: f64,
+}
+
+impl System {
+    /// Constructs a system from the gravitational parameters of the primaries in m³/s², in
+    /// any order, and their distance in meters.
+    pub fn new(primary: f64, secondary: f64, length: f64) -> Result<Self, Cr3bpError> {
+        let gravitational_parameter = primary + secondary;
+        let valid = primary > 0.0 && secondary > 0.0 && gravitational_parameter.is_finite();
+        if !(valid && length > 0.0 && length.is_finite()) {
+            return Err(Cr3bpError::InvalidMassRatio);
+        }
+        Ok(System {
+            mass_ratio: primary.min(secondary) / gravitational_parameter,
+            length,
+            gravitational_parameter,
+        })
+    }
+
+    /// Returns the Earth–Moon system at their mean distance.
+    pub fn earth_moon() -> Self {
+        System::new(
+            EARTH_GRAVITATIONAL_PARAMETER,
+            MOON_GRAVITATIONAL_PARAMETER,
+            EARTH_MOON_DISTANCE,
+        )
+        .unwrap()
+    }
+
+    /// Returns the Sun–Earth system, the smaller primary being the Earth and the Moon at their
+    /// barycenter, one astronomical unit apart.
+    pub fn sun_earth() -> Self {
+        System::new(
+            SUN_GRAVITATIONAL_PARAMETER,
+            EARTH_GRAVITATIONAL_PARAMETER + MOON_GRAVITATIONAL_PARAMETER,
+            ASTRONOMICAL_UNIT,
+        )
+        .unwrap()
+    }
+
+    /// Returns the unit of time in seconds, the inverse of the mean motion of the primaries.
+    pub fn time_unit(&self) -> f64 {
+        (self.length.powi(3) / self.gravitational_parameter).sqrt()
+    }
+
+    /// Returns the unit of velocity in m/s.
+    pub fn velocity_unit(&self) -> f64 {
+        self.length / self.time_unit()
+    }
+
+    /// Returns the positions of the larger and the smaller primary.
+    pub fn primaries(&self) -> [Vector3; 2] {
+        let mu = self.mass_ratio;
+        [
+            Vector3::new(-mu, 0.0, 0.0),
+            Vector3::new(1.0 - mu, 0.0, 0.0),
+        ]
+    }
+
+    /// Computes the effective potential `U = (x² + y²)/2 + (1 - μ)/r₁ + μ/r₂` at a position.
+    pub fn potential(&self, position: &Vector3) -> f64 {
+        let [primary, secondary] = self.primaries();
+        0.5 * (position.x.powi(2) + position.y.powi(2))
+            + (1.0 - self.mass_ratio) / (*position - primary).norm()
+            + self.mass_ratio / (*position - secondary).norm()
+    }
+
+    /// Computes the Jacobi constant `C = 2U - v²`, conserved along a trajectory.
+    pub fn jacobi(&self, state: &StateVector) -> f64 {
+        2.0 * self.potential(&state.position) - state.velocity.norm().powi(2)
+    }
+
+    /// Computes the acceleration in the synodic frame, including the Coriolis and centrifugal
+    /// accelerations.
+    pub fn acceleration(&self, state: &StateVector) -> Vector3 {
+        let (r, v) = (state.position, state.velocity);
+        let mut acceleration = Vector3::new(r.x + 2.0 * v.y, r.y - 2.0 * v.x, 0.0);
+        for (mass, center) in self.masses() {
+            let d = r - center;
+            acceleration -= d * (mass / d.norm().powi(3));
+        }
+        acceleration
+    }
+
+    /// Computes the partial derivatives of the time derivative of the state with respect to
+    /// the state, `[[0, I], [∂²U/∂r², 2Ω]]`.
+    pub fn jacobian(&self, position: &Vector3) -> Matrix6 {
+        let mut hessian = Matrix3::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 0.0]]);
+        for (mass, center) in self.masses() {
+            let d = (*position - center).to_array();
+            let distance = (*position - center).norm();
+            for i in 0..3 {
+                for j in 0..3 {
+                    let diagonal = if i == j { 1.0 } else { 0.0 };
+                    hessian[(i, j)] +=
+                        mass * (3.0 * d[i] * d[j] / distance.powi(5) - diagonal / distance.powi(3));
+                }
+            }
+        }
+        let coriolis = Matrix3::new([[0.0, 2.0, 0.0], [-2.0, 0.0, 0.0], [0.0, 0.0, 0.0]]);
+        Matrix6::from_blocks(
+            &Matrix3::new([[0.0; 3]; 3]),
+            &Matrix3::identity(),
+            &hessian,
+            &coriolis,
+        )
+    }
+
+    /// Returns the location of a Lagrange point.
+    pub fn lagrange_point(&self, point: LagrangePoint) -> Vector3 {
+        let mu = self.mass_ratio;
+        let hill = (mu / 3.0).cbrt();
+        let guess = match point {
+            LagrangePoint::L1 => 1.0 - mu - hill,
+            LagrangePoint::L2 => 1.0 - mu + hill,
+            LagrangePoint::L3 => -1.0 - 5.0 * mu / 12.0,
+            LagrangePoint::L4 => return Vector3::new(0.5 - mu, 0.75f64.sqrt(), 0.0),
+            LagrangePoint::L5 => return Vector3::new(0.5 - mu, -(0.75f64.sqrt()), 0.0),
+        };
+        // Newton's method on the acceleration along the x axis, monotonic between the poles
+        let mut x = guess;
+        for _ in 0..50 {
+            let (d1, d2) = (x + mu, x - 1.0 + mu);
+            let f = x - (1.0 - mu) * d1 / d1.abs().powi(3) - mu * d2 / d2.abs().powi(3);
+            let df = 1.0 + 2.0 * (1.0 - mu) / d1.abs().powi(3) + 2.0 * mu / d2.abs().powi(3);
+            let step = f / df;
+            x -= step;
+            if step.abs() <= 1e-15 * x.abs().max(1.0) {
+                break;
+            }
+        }
+        Vector3::new(x, 0.0, 0.0)
+    }
+
+    /// Computes a planar Lyapunov orbit about a collinear Lagrange point from the linearized
+    /// motion, to be refined with [`lyapunov`](System::lyapunov).
+    ///
+    /// The orbit starts on the x axis at the given amplitude, positive away from the larger
+    /// primary, and the period is that of the linear oscillation. Returns `None` for the
+    /// triangular points.
+    pub fn linear_lyapunov(&self, point: LagrangePoint, amplitude: f64) -> Option<PeriodicOrbit> {
+        if matches!(point, LagrangePoint::L4 | LagrangePoint::L5) {
+            return None;
+        }
+        let position = self.lagrange_point(point);
+        let c2 = self
+            .masses()
+            .iter()
+            .map(|(mass, center)| mass / (position - *center).norm().powi(3))
+            .sum::<f64>();
+        let frequency = (0.5 * (2.0 - c2 + (9.0 * c2 * c2 - 8.0 * c2).sqrt())).sqrt();
+        let ratio = (frequency * frequency + 1.0 + 2.0 * c2) / (2.0 * frequency);
+        Some(PeriodicOrbit {
+            state: StateVector {
+                position: position + Vector3::new(amplitude, 0.0, 0.0),
+                velocity: Vector3::new(0.0, -ratio * frequency * amplitude, 0.0),
+            },
+            period: 2.0 * PI / frequency,
+        })
+    }
+
+    /// Integrates a state over the given nondimensional time, backwards if it is negative.
+    ///
+    /// The times of the integrator, such as a fixed step, are read as nondimensional times.
+    pub fn propagate(
+        &self,
+        integrator: &Integrator,
+        state: &StateVector,
+        time: f64,
+    ) -> Result<StateVector, PropagationError> {
+        Ok(from_slice(
+            self.integrate(integrator, state, time, false, false)?
+                .state(),
+        ))
+    }
+
+    /// Integrates a state with the variational equations over the given nondimensional time,
+    /// returning the state and the state transition matrix.
+    pub fn propagate_with_transition(
+        &self,
+        integrator: &Integrator,
+        state: &StateVector,
+        time: f64,
+    ) -> Result<(StateVector, Matrix6), PropagationError> {
+        let solution = self.integrate(integrator, state, time, false, true)?;
+        Ok((from_slice(solution.state()), transition(solution.state())))
+    }
+
+    /// Corrects a guess of a planar Lyapunov orbit into a periodic orbit.
+    ///
+    /// The guess starts on the x axis with a velocity along y. Keeping the position, the
+    /// velocity is corrected until the orbit crosses the x axis again perpendicularly, so that
+    /// it is symmetric about this axis.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use astro_carta::orbit::cr3bp::{LagrangePoint, System};
+    /// use astro_carta::orbit::integrator::{Integrator, StepControl};
+    ///
+    /// let system = System::earth_moon();
+    /// let integrator = Integrator::DormandPrince853(StepControl::new(1e-12, 1e-12));
+    /// let guess = system.linear_lyapunov(LagrangePoint::L1, 0.01).unwrap();
+    /// let orbit = system.lyapunov(&integrator, &guess.state).unwrap();
+    /// let end = system.propagate(&integrator, &orbit.state, orbit.period).unwrap();
+    /// assert!((end.position - orbit.state.position).norm() < 1e-8);
+    /// ```
+    pub fn lyapunov(
+        &self,
+        integrator: &Integrator,
+        guess: &StateVector,
+    ) -> Result<PeriodicOrbit, Cr3bpError> {
+        self.correct(integrator, guess, &[4], &[3])
+    }
+
+    /// Corrects a guess of a halo orbit into a periodic orbit.
+    ///
+    /// The guess starts in the x-z plane with a velocity along y. Keeping the height z, the
+    /// position along x and the velocity are corrected until the orbit crosses the x-z plane
+    /// again perpendicularly, so that it is symmetric about this plane.
+    pub fn halo(
+        &self,
+        integrator: &Integrator,
+        guess: &StateVector,
+    ) -> Result<PeriodicOrbit, Cr3bpError> {
+        self.correct(integrator, guess, &[0, 4], &[3, 5])
+    }
+
+    /// Single shooting from a state in the x-z plane to the next crossing of this plane,
+    /// varying the given components of the initial state to cancel the given components of
+    /// the velocity at the crossing.
+    fn correct(
+        &self,
+        integrator: &Integrator,
+        guess: &StateVector,
+        free: &[usize],
+        targets: &[usize],
+    ) -> Result<PeriodicOrbit, Cr3bpError> {
+        let mut y = to_array(guess);
+        y[1] = 0.0;
+        for _ in 0..50 {
+            let state = from_slice(&y);
+            let (time, crossing) = self.crossing(integrator, &state)?;
+            let end = from_slice(&crossing);
+            let residuals: Vec<f64> = targets.iter().map(|&i| crossing[i]).collect();
+            if residuals.iter().all(|residual| residual.abs() <= 1e-11) {
+                return Ok(PeriodicOrbit {
+                    state,
+                    period: 2.0 * time,
+                });
+            }
+
+            // The crossing time moves with the initial state so that y stays zero
+            let phi = transition(&crossing);
+            let acceleration = self.acceleration(&end).to_array();
+            let velocity = end.velocity.y;
+            let partial =
+                |i: usize, j: usize| phi[(i, j)] - acceleration[i - 3] / velocity * phi[(1, j)];
+            let corrections = match free {
+                [j] => vec![-residuals[0] / partial(targets[0], *j)],
+                [j, k] => {
+                    let (a, b) = (partial(targets[0], *j), partial(targets[0], *k));
+                    let (c, d) = (partial(targets[1], *j), partial(targets[1], *k));
+                    let determinant = a * d - b * c;
+                    if determinant == 0.0 {
+                        return Err(Cr3bpError::NoConvergence);
+                    }
+                    vec![
+                        -(d * residuals[0] - b * residuals[1]) / determinant,
+                        -(a * residuals[1] - c * residuals[0]) / determinant,
+                    ]
+                }
+                _ => unreachable!(),
+            };
+            for (&j, correction) in free.iter().zip(corrections) {
+                y[j] += correction;
+            }
+            if !y.iter().all(|value| value.is_finite()) {
+                return Err(Cr3bpError::NoConvergence);
+            }
+        }
+        Err(Cr3bpError::NoConvergence)
+    }
+
+    /// Integrates a state with the variational equations to the next crossing of the x-z
+    /// plane, within one revolution of the primaries.
+    fn crossing(
+        &self,
+        integrator: &Integrator,
+        state: &StateVector,
+    ) -> Result<(f64, Vec<f64>), Cr3bpError> {
+        let solution = self.integrate(integrator, state, 2.0 * PI, true, true)?;
+        let y = |time: f64| {
+            solution
+                .evaluate(time)
+                .map(|y| y[1])
+                .ok_or(Cr3bpError::NoCrossing)
+        };
+        let (mut a, mut fa) = (CROSSING_STEP, y(CROSSING_STEP)?);
+        while a < 2.0 * PI {
+            let b = (a + CROSSING_STEP).min(2.0 * PI);
+            let fb = y(b)?;
+            if fa * fb <= 0.0 {
+                let time = roots::brent(y, a, fa, b, fb, 1e-14)?;
+                return Ok((time, solution.evaluate(time).unwrap()));
+            }
+            (a, fa) = (b, fb);
+        }
+        Err(Cr3bpError::NoCrossing)
+    }
+
+    /// Integrates the state, followed by the 36 elements of the state transition matrix in
+    /// row-major order if `variational` is set.
+    fn integrate(
+        &self,
+        integrator: &Integrator,
+        state: &StateVector,
+        time: f64,
+        dense: bool,
+        variational: bool,
+    ) -> Result<Solution, PropagationError> {
+        let mut y = to_array(state).to_vec();
+        if variational {
+            y.extend(Matrix6::identity().elements.iter().flatten());
+        }
+        integrator.integrate(0.0, &y, time, dense, |_, y, dy| {
+            let state = from_slice(y);
+            dy[..3].copy_from_slice(&y[3..6]);
+            dy[3..6].copy_from_slice(&self.acceleration(&state).to_array());
+            if variational {
+                let derivative = self.jacobian(&state.position) * transition(y);
+                for (dy, value) in dy[6..].iter_mut().zip(derivative.elements.iter().flatten()) {
+                    *dy = *value;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns the mass fractions and positions of the primaries.
+    fn masses(&self) -> [(f64, Vector3); 2] {
+        let [primary, secondary] = self.primaries();
+        [
+            (1.0 - self.mass_ratio, primary),
+            (self.mass_ratio, secondary),
+        ]
+    }
+}
+
+/// A periodic orbit of the synodic frame, starting in the x-z plane
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeriodicOrbit {
+    /// Nondimensional initial state
+    pub state: StateVector,
+    /// Nondimensional period
+    pub period: f64,
+}
+
+/// Converts states between the synodic frame of a circular restricted three-body problem and
+/// the inertial frame of an ephemeris, following the actual motion of the primaries
+///
+/// At each instant, the x axis points from the larger to the smaller primary, the z axis along
+/// their relative angular momentum, and the origin is at their barycenter. The unit of length
+/// is their instantaneous distance and the unit of time the inverse of the mean motion at this
+/// distance, so that the primaries stay at `(-μ, 0, 0)` and `(1 - μ, 0, 0)` even on eccentric
+/// orbits.
+///
+/// # Examples
+///
+/// ```no_run
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::ephemeris::spk::Spk;
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::orbit::cr3bp::{LagrangePoint, SynodicFrame};
+/// use astro_carta::math::Vector3;
+///
+/// let spk = Spk::open("de440.bsp").unwrap();
+/// let frame = SynodicFrame::earth_moon(spk);
+/// let l2 = StateVector {
+///     position: frame.system.lagrange_point(LagrangePoint::L2),
+///     velocity: Vector3::zeros(),
+/// };
+/// let dt = DateTime::gregorian(2026, 1, 1, 0, 0, 0.0).unwrap();
+/// // Geocentric state of the Earth–Moon L2 point
+/// let state = frame.to_inertial(&dt, &l2).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SynodicFrame<E> {
+    ephemeris: E,
+    pub system: System,
+    /// NAIF identifier of the larger primary
+    pub primary: i32,
+    /// NAIF identifier of the smaller primary
+    pub secondary: i32,
+    /// NAIF identifier of the origin of the inertial states
+    pub center: i32,
+}
+
+impl<E: Ephemeris> SynodicFrame<E> {
+    /// Constructs a frame whose inertial states are relative to the larger primary.
+    pub fn new(ephemeris: E, system: System, primary: i32, secondary: i32) -> Self {
+        SynodicFrame {
+            ephemeris,
+            system,
+            primary,
+            secondary,
+            center: primary,
+        }
+    }
+
+    /// Returns the frame of the Earth and the Moon, with geocentric inertial states.
+    pub fn earth_moon(ephemeris: E) -> Self {
+        SynodicFrame::new(ephemeris, System::earth_moon(), 399, 301)
+    }
+
+    /// Returns the frame of the Sun and the Earth–Moon barycenter, with heliocentric inertial
+    /// states.
+    pub fn sun_earth(ephemeris: E) -> Self {
+        SynodicFrame::new(ephemeris, System::sun_earth(), 10, 3)
+    }
+
+    pub fn with_center(mut self, center: i32) -> Self {
+        self.center = center;
+        self
+    }
+
+    pub fn ephemeris(&self) -> &E {
+        &self.ephemeris
+    }
+
+    /// Converts a nondimensional synodic state into an inertial state in meters and m/s.
+    pub fn to_inertial(
+        &self,
+        dt: &DateTime,
+        state: &StateVector,
+    ) -> Result<StateVector, Cr3bpError> {
+        let axes = self.axes(dt)?;
+        let offset = axes.rotation * state.position * axes.length;
+        Ok(StateVector {
+            position: axes.barycenter.position + offset,
+            velocity: axes.barycenter.velocity
+                + offset * (axes.rate / axes.length)
+                + axes.angular_velocity.cross(&offset)
+                + axes.rotation * state.velocity * (axes.length * axes.motion),
+        })
+    }
+
+    /// Converts an inertial state in meters and m/s into a nondimensional synodic state.
+    pub fn from_inertial(
+        &self,
+        dt: &DateTime,
+        state: &StateVector,
+    ) -> Result<StateVector, Cr3bpError> {
+        let axes = self.axes(dt)?;
+        let offset = state.position - axes.barycenter.position;
+        let velocity = state.velocity
+            - axes.barycenter.velocity
+            - offset * (axes.rate / axes.length)
+            - axes.angular_velocity.cross(&offset);
+        let inverse = axes.rotation.transpose();
+        Ok(StateVector {
+            position: inverse * offset / axes.length,
+            velocity: inverse * velocity / (axes.length * axes.motion),
+        })
+    }
+
+    fn axes(&self, dt: &DateTime) -> Result<Axes, Cr3bpError> {
+        let ephemeris = |target, center| {
+            self.ephemeris
+                .state(target, center, dt)
+                .map_err(|error| Cr3bpError::Ephemeris(error.to_string()))
+        };
+        let primary = if self.center == self.primary {
+            StateVector {
+                position: Vector3::zeros(),
+                velocity: Vector3::zeros(),
+            }
+        } else {
+            ephemeris(self.primary, self.center)?
+        };
+        let relative = ephemeris(self.secondary, self.primary)?;
+        let (r, v) = (relative.position, relative.velocity);
+        let momentum = r.cross(&v);
+        let (length, h) = (r.norm(), momentum.norm());
+        if length == 0.0 || h == 0.0 {
+            return Err(Cr3bpError::DegenerateGeometry);
+        }
+        let (x, z) = (r / length, momentum / h);
+        let mu = self.system.mass_ratio;
+        Ok(Axes {
+            rotation: Matrix3::from_columns(&x, &z.cross(&x), &z),
+            barycenter: StateVector {
+                position: primary.position + r * mu,
+                velocity: primary.velocity + v * mu,
+            },
+            length,
+            rate: r.dot(&v) / length,
+            angular_velocity: momentum / (length * length),
+            motion: (self.system.gravitational_parameter / length.powi(3)).sqrt(),
+        })
+    }
+}
+
+/// Instantaneous synodic axes of the primaries
+struct Axes {
+    /// Rotation from the synodic to the inertial axes
+    rotation: Matrix3,
+    barycenter: StateVector,
+    /// Distance between the primaries in meters
+    length: f64,
+    /// Rate of change of the distance in m/s
+    rate: f64,
+    /// Angular velocity of the axes in rad/s
+    angular_velocity: Vector3,
+    /// Mean motion in rad/s, the inverse of the unit of time
+    motion: f64,
+}
+
+fn transition(y: &[f64]) -> Matrix6 {
+    Matrix6::new(std::array::from_fn(|i| {
+        std::array::from_fn(|j| y[6 + 6 * i + j])
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orbit::integrator::StepControl;
+
+    fn integrator() -> Integrator {
+        Integrator::DormandPrince853(StepControl::new(1e-12, 1e-12))
+    }
+
+    #[test]
+    fn lagrange_points_test() {
+        let system = System::earth_moon();
+        assert!((system.mass_ratio - 0.012_150_58).abs() < 1e-8);
+        let points = [
+            (LagrangePoint::L1, 0.836_915, 3.188_34),
+            (LagrangePoint::L2, 1.155_682, 3.172_16),
+            (LagrangePoint::L3, -1.005_063, 3.012_15),
+            (LagrangePoint::L4, 0.487_849, 2.987_997),
+        ];
+        for (point, x, jacobi) in points {
+            let position = system.lagrange_point(point);
+            assert!((position.x - x).abs() < 1e-6, "{point:?}: {}", position.x);
+            let state = StateVector {
+                position,
+                velocity: Vector3::zeros(),
+            };
+            assert!(system.acceleration(&state).norm() < 1e-12, "{point:?}");
+            assert!((system.jacobi(&state) - jacobi).abs() < 1e-5, "{point:?}");
+        }
+        let l5 = system.lagrange_point(LagrangePoint::L5);
+        assert_eq!(l5.y, -system.lagrange_point(LagrangePoint::L4).y);
+
+        let sun_earth = System::sun_earth();
+        let l1 = sun_earth.lagrange_point(LagrangePoint::L1);
+        // About 1.5 million km sunwards of the Earth
+        let distance = (1.0 - sun_earth.mass_ratio - l1.x) * sun_earth.length;
+        assert!((distance / 1e9 - 1.49).abs() < 0.01);
+        assert_eq!(
+            System::new(1.0, 0.0, 1.0),
+            Err(Cr3bpError::InvalidMassRatio)
+        );
+    }
+
+    #[test]
+    fn propagation_test() {
+        let system = System::earth_moon();
+        let state = StateVector {
+            position: Vector3::new(0.8, 0.1, 0.05),
+            velocity: Vector3::new(0.05, -0.2, 0.1),
+        };
+        let (end, phi) = system
+            .propagate_with_transition(&integrator(), &state, 1.5)
+            .unwrap();
+        assert!((system.jacobi(&end) - system.jacobi(&state)).abs() < 1e-10);
+        let back = system.propagate(&integrator(), &end, -1.5).unwrap();
+        assert!((back.position - state.position).norm() < 1e-9);
+
+        // Columns of the transition matrix against central differences
+        for j in 0..6 {
+            let h = 1e-6;
+            let shifted = |sign: f64| {
+                let mut y = to_array(&state);
+                y[j] += sign * h;
+                to_array(
+                    &system
+                        .propagate(&integrator(), &from_slice(&y), 1.5)
+                        .unwrap(),
+                )
+            };
+            let (forward, backward) = (shifted(1.0), shifted(-1.0));
+            for i in 0..6 {
+                let difference = (forward[i] - backward[i]) / (2.0 * h);
+                assert!((phi[(i, j)] - difference).abs() < 1e-5 * difference.abs().max(1.0));
+            }
+        }
+    }
+
+    #[test]
+    fn lyapunov_test() {
+        let system = System::earth_moon();
+        for point in [LagrangePoint::L1, LagrangePoint::L2] {
+            let guess = system.linear_lyapunov(point, 0.005).unwrap();
+            let orbit = system.lyapunov(&integrator(), &guess.state).unwrap();
+            assert_eq!(orbit.state.position, guess.state.position);
+            assert!((orbit.period / guess.period - 1.0).abs() < 0.05);
+            let (end, monodromy) = system
+                .propagate_with_transition(&integrator(), &orbit.state, orbit.period)
+                .unwrap();
+            assert!((end.position - orbit.state.position).norm() < 1e-9);
+            assert!((end.velocity - orbit.state.velocity).norm() < 1e-9);
+            // The orbits about the collinear points are unstable
+            let trace: f64 = (0..6).map(|i| monodromy[(i, i)]).sum();
+            assert!(trace > 100.0);
+        }
+        assert!(system.linear_lyapunov(LagrangePoint::L4, 0.01).is_none());
+    }
+
+    #[test]
+    fn halo_test() {
+        let system = System::earth_moon();
+        // An L1 halo orbit of about 8 600 km out of plane
+        let guess = StateVector {
+            position: Vector3::new(0.8234, 0.0, 0.0224),
+            velocity: Vector3::new(0.0, 0.1343, 0.0),
+        };
+        let orbit = system.halo(&integrator(), &guess).unwrap();
+        assert_eq!(orbit.state.position.z, 0.0224);
+        assert!((orbit.period - 2.746_37).abs() < 1e-4, "{}", orbit.period);
+        let end = system
+            .propagate(&integrator(), &orbit.state, orbit.period)
+            .unwrap();
+        assert!((end.position - orbit.state.position).norm() < 1e-8);
+        assert!((end.velocity - orbit.state.velocity).norm() < 1e-8);
+    }
+
+    /// A secondary on an eccentric orbit about the primary, and a primary moving uniformly
+    /// relative to a third body
+    struct Primaries;
+
+    impl Ephemeris for Primaries {
+        type Error = Cr3bpError;
+
+        fn state(
+            &self,
+            target: i32,
+            center: i32,
+            dt: &DateTime,
+        ) -> Result<StateVector, Cr3bpError> {
+            let t = (*dt - DateTime::gregorian(2026, 1, 1, 0, 0, 0.0).unwrap()).total_seconds();
+            match (target, center) {
+                (301, 399) => {
+                    let angle = 2.66e-6 * t;
+                    let (sin, cos) = angle.sin_cos();
+                    let radius = 384_400e3 * (1.0 + 0.05 * angle.sin());
+                    let rate = 384_400e3 * 0.05 * angle.cos() * 2.66e-6;
+                    let direction = Vector3::new(cos, sin * 0.9, sin * 0.1);
+                    let derivative = Vector3::new(-sin, cos * 0.9, cos * 0.1);
+                    Ok(StateVector {
+                        position: direction * radius,
+                        velocity: direction * rate + derivative * (radius * 2.66e-6),
+                    })
+                }
+                (399, 10) => Ok(StateVector {
+                    position: Vector3::new(1.5e11, 1e9, 0.0) + Vector3::new(0.0, 3e4, 0.0) * t,
+                    velocity: Vector3::new(0.0, 3e4, 0.0),
+                }),
+                _ => Err(Cr3bpError::Ephemeris(format!("no body {target}"))),
+            }
+        }
+    }
+
+    #[test]
+    fn synodic_frame_test() {
+        let dt = DateTime::gregorian(2026, 1, 3, 0, 0, 0.0).unwrap();
+        for center in [399, 10] {
+            let frame = SynodicFrame::earth_moon(Primaries).with_center(center);
+            let mu = frame.system.mass_ratio;
+            let earth = frame.ephemeris().state(399, 10, &dt).unwrap();
+            let moon = frame.ephemeris().state(301, 399, &dt).unwrap();
+            let offset = if center == 10 {
+                earth
+            } else {
+                StateVector {
+                    position: Vector3::zeros(),
+                    velocity: Vector3::zeros(),
+                }
+            };
+
+            // The primaries at rest in the synodic frame follow the ephemeris
+            let [primary, secondary] = frame.system.primaries();
+            let at_rest = |position| StateVector {
+                position,
+                velocity: Vector3::zeros(),
+            };
+            let state = frame.to_inertial(&dt, &at_rest(primary)).unwrap();
+            assert!((state.position - offset.position).norm() < 1e-3);
+            assert!((state.velocity - offset.velocity).norm() < 1e-9);
+            let state = frame.to_inertial(&dt, &at_rest(secondary)).unwrap();
+            assert!((state.position - offset.position - moon.position).norm() < 1e-3);
+            assert!((state.velocity - offset.velocity - moon.velocity).norm() < 1e-9);
+            let state = frame.to_inertial(&dt, &at_rest(Vector3::zeros())).unwrap();
+            assert!((state.position - offset.position - moon.position * mu).norm() < 1e-3);
+
+            let synodic = StateVector {
+                position: Vector3::new(1.15, 0.02, 0.1),
+                velocity: Vector3::new(0.01, -0.2, 0.03),
+            };
+            let inertial = frame.to_inertial(&dt, &synodic).unwrap();
+            let back = frame.from_inertial(&dt, &inertial).unwrap();
+            assert!((back.position - synodic.position).norm() < 1e-14);
+            assert!((back.velocity - synodic.velocity).norm() < 1e-14);
+        }
+        let frame = SynodicFrame::sun_earth(Primaries);
+        assert!(matches!(
+            frame.to_inertial(
+                &dt,
+                &StateVector {
+                    position: Vector3::zeros(),
+                    velocity: Vector3::zeros(),
+                }
+            ),
+            Err(Cr3bpError::Ephemeris(_))
+        ));
+    }
+}
@@ -0,0 +1,333 @@
+use std::fmt;
+use std::rc::Rc;
+
+use super::numerical::{NumericalPropagator, PropagationError};
+use crate::datetime::DateTime;
+use crate::ephemeris::StateVector;
+use crate::math::Matrix6;
+
+pub mod batch;
+pub mod kalman;
+pub mod measurement;
+
+pub use batch::{BatchLeastSquares, BatchSolution};
+pub use kalman::ExtendedKalmanFilter;
+
+/// Error raised by an orbit determination
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeterminationError {
+    /// The propagation of the state or its transition matrix failed
+    Propagation(PropagationError),
+    /// The measurement cannot be computed, such as a range from the station to itself
+    Geometry,
+    /// The observations do not determine the state, their normal matrix being singular
+    Singular,
+    /// No observation was accepted
+    NoObservations,
+    /// The estimate did not converge within the largest number of iterations
+    NoConvergence,
+}
+
+impl fmt::Display for DeterminationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeterminationError::Propagation(error) => write!(f, "{error}"),
+            DeterminationError::Geometry => write!(f, "degenerate measurement geometry"),
+            DeterminationError::Singular => {
+                write!(f, "the observations do not determine the state")
+            }
+            DeterminationError::NoObservations => write!(f, "no observation accepted"),
+            DeterminationError::NoConvergence => write!(f, "orbit determination did not converge"),
+        }
+    }
+}
+
+impl std::error::Error for DeterminationError {}
+
+impl From<PropagationError> for DeterminationError {
+    fn from(error: PropagationError) -> Self {
+        DeterminationError::Propagation(error)
+    }
+}
+
+/// A measurement computed from a state, with its partial derivatives
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prediction {
+    pub values: Vec<f64>,
+    /// Partial derivatives of each component with respect to the position and velocity
+    pub partials: Vec<[f64; 6]>,
+}
+
+/// A model of the measurements of an orbiting object
+///
+/// The measurements are computed from the geocentric state of the object in the GCRS at their
+/// time tag, the instant of reception for the measurements of a station.
+pub trait MeasurementModel: fmt::Debug {
+    /// Returns the number of components of a measurement.
+    fn dimension(&self) -> usize;
+
+    /// Returns the standard deviations of the noise of the components.
+    fn noise(&self) -> Vec<f64>;
+
+    /// Computes the measurement of an object in the given state at the time tag.
+    fn measure(&self, dt: &DateTime, state: &StateVector)
+        -> Result<Prediction, DeterminationError>;
+
+    /// Returns the observed minus computed values, wrapping angles where needed.
+    fn residual(&self, observed: &[f64], computed: &[f64]) -> Vec<f64> {
+        observed.iter().zip(computed).map(|(o, c)| o - c).collect()
+    }
+}
+
+/// A time-tagged measurement
+#[derive(Debug, Clone)]
+pub struct Observation {
+    pub dt: DateTime,
+    pub values: Vec<f64>,
+    pub model: Rc<dyn MeasurementModel>,
+}
+
+impl Observation {
+    pub fn new(dt: DateTime, values: Vec<f64>, model: Rc<dyn MeasurementModel>) -> Self {
+        Observation { dt, values, model }
+    }
+
+    /// Simulates a noiseless observation of an object in the given state at the time tag.
+    pub fn simulate(
+        dt: DateTime,
+        state: &StateVector,
+        model: Rc<dyn MeasurementModel>,
+    ) -> Result<Self, DeterminationError> {
+        let prediction = model.measure(&dt, state)?;
+        Ok(Observation::new(dt, prediction.values, model))
+    }
+}
+
+/// Residual of an observation after an estimation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Residual {
+    pub dt: DateTime,
+    /// Observed minus computed values
+    pub values: Vec<f64>,
+    /// Residuals divided by their standard deviations
+    pub normalized: Vec<f64>,
+    /// Whether the observation was rejected as an outlier
+    pub rejected: bool,
+}
+
+/// Statistics of the residuals of the accepted observations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResidualStatistics {
+    pub accepted: usize,
+    pub rejected: usize,
+    /// Root mean square of the normalized residuals, close to one when the noise is well
+    /// modeled
+    pub weighted_rms: f64,
+    /// Largest normalized residual in absolute value
+    pub max_normalized: f64,
+}
+
+impl ResidualStatistics {
+    pub fn new(residuals: &[Residual]) -> Self {
+        let accepted: Vec<_> = residuals.iter().filter(|r| !r.rejected).collect();
+        let normalized: Vec<f64> = accepted
+            .iter()
+            .flat_map(|residual| residual.normalized.iter().copied())
+            .collect();
+        let sum: f64 = normalized.iter().map(|value| value * value).sum();
+        ResidualStatistics {
+            accepted: accepted.len(),
+            rejected: residuals.len() - accepted.len(),
+            weighted_rms: if normalized.is_empty() {
+                0.0
+            } else {
+                (sum / normalized.len() as f64).sqrt()
+            },
+            max_normalized: normalized
+                .iter()
+                .fold(0.0, |max, value| value.abs().max(max)),
+        }
+    }
+}
+
+/// Returns the states and state transition matrices from the epoch of the propagator at the
+/// instants of the observations.
+fn transitions(
+    propagator: &NumericalPropagator,
+    observations: &[Observation],
+) -> Result<Vec<(StateVector, Matrix6)>, PropagationError> {
+    let epoch = propagator.epoch;
+    let last = observations
+        .iter()
+        .map(|o| o.dt)
+        .filter(|dt| *dt > epoch)
+        .max();
+    let first = observations
+        .iter()
+        .map(|o| o.dt)
+        .filter(|dt| *dt < epoch)
+        .min();
+    let forward = last
+        .map(|end| propagator.variational_trajectory(&end))
+        .transpose()?;
+    let backward = first
+        .map(|end| propagator.variational_trajectory(&end))
+        .transpose()?;
+    observations
+        .iter()
+        .map(|observation| {
+            let trajectory = match observation.dt {
+                dt if dt > epoch => forward.as_ref(),
+                dt if dt < epoch => backward.as_ref(),
+                _ => return Ok((propagator.state, Matrix6::identity())),
+            };
+            // The trajectories span every observation on their side of the epoch
+            let trajectory = trajectory.unwrap();
+            Ok((
+                super::Propagator::propagate(trajectory, &observation.dt)?,
+                trajectory.transition(&observation.dt)?,
+            ))
+        })
+        .collect()
+}
+
+/// Returns the partial derivatives of a measurement with respect to the state at the epoch.
+fn epoch_partials(partials: &[[f64; 6]], transition: &Matrix6) -> Vec<[f64; 6]> {
+    partials
+        .iter()
+        .map(|row| std::array::from_fn(|j| (0..6).map(|k| row[k] * transition[(k, j)]).sum()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::measurement::{AzimuthElevation, Range, RangeRate};
+    use super::*;
+    use crate::constants::EARTH_GRAVITATIONAL_PARAMETER;
+    use crate::coordinates::Observer;
+    use crate::datetime::TimeDelta;
+    use crate::math::Vector3;
+    use crate::orbit::force::PointMass;
+    use crate::orbit::integrator::{Integrator, StepControl};
+    use crate::orbit::propagation;
+    use crate::orbit::station::GroundStation;
+
+    /// Deterministic Gaussian noise, from a xorshift generator and the Box-Muller transform
+    pub(super) struct Noise(u64);
+
+    impl Noise {
+        pub(super) fn new() -> Self {
+            Noise(0x2545_f491_4f6c_dd1d)
+        }
+
+        fn uniform(&mut self) -> f64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            ((self.0 >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+        }
+
+        pub(super) fn sample(&mut self) -> f64 {
+            let (u, v) = (self.uniform(), self.uniform());
+            (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+        }
+    }
+
+    pub(super) fn epoch() -> DateTime {
+        DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap()
+    }
+
+    /// A circular orbit at 1500 km inclined at 60°
+    pub(super) fn truth() -> StateVector {
+        let radius = 7_878e3;
+        let speed = (EARTH_GRAVITATIONAL_PARAMETER / radius).sqrt();
+        let inclination = 60f64.to_radians();
+        StateVector {
+            position: Vector3::new(radius, 0.0, 0.0),
+            velocity: Vector3::new(0.0, inclination.cos(), inclination.sin()) * speed,
+        }
+    }
+
+    pub(super) fn propagator(state: StateVector) -> NumericalPropagator {
+        NumericalPropagator::new(
+            epoch(),
+            state,
+            Integrator::DormandPrince853(StepControl::new(1e-12, 1e-6)),
+        )
+        .with_force(PointMass::new(EARTH_GRAVITATIONAL_PARAMETER))
+    }
+
+    /// Simulates the range, range rate and angles of the object every minute over six hours
+    /// from the stations that see it above 10°, with noise if given.
+    pub(super) fn simulate(mut noise: Option<&mut Noise>) -> Vec<Observation> {
+        let stations = [
+            (40.4314, -4.2481, 834.0),
+            (35.4267, -116.89, 1_000.0),
+            (-35.4014, 148.9817, 680.0),
+            (5.2514, -52.8047, 14.0),
+        ];
+        let mut observations = Vec::new();
+        for minutes in 0..360 {
+            let dt = epoch() + TimeDelta::minutes(minutes as f64);
+            let state =
+                propagation::universal(&truth(), EARTH_GRAVITATIONAL_PARAMETER, dt - epoch())
+                    .unwrap();
+            for &(latitude, longitude, height) in &stations {
+                let station =
+                    GroundStation::new(Observer::from_degrees(latitude, longitude, height));
+                let angles = AzimuthElevation::new(station, 1e-4);
+                if angles.measure(&dt, &state).unwrap().values[1] < 10f64.to_radians() {
+                    continue;
+                }
+                let models: [Rc<dyn MeasurementModel>; 3] = [
+                    Rc::new(Range::new(station, 5.0)),
+                    Rc::new(RangeRate::new(station, 1e-2)),
+                    Rc::new(angles),
+                ];
+                for model in models {
+                    let mut observation = Observation::simulate(dt, &state, model).unwrap();
+                    if let Some(noise) = noise.as_mut() {
+                        for (value, sigma) in
+                            observation.values.iter_mut().zip(observation.model.noise())
+                        {
+                            *value += sigma * noise.sample();
+                        }
+                    }
+                    observations.push(observation);
+                }
+            }
+        }
+        observations
+    }
+
+    /// Returns the guess of the state, a few kilometers and meters per second off.
+    pub(super) fn guess() -> StateVector {
+        let truth = truth();
+        StateVector {
+            position: truth.position + Vector3::new(2e3, -3e3, 1e3),
+            velocity: truth.velocity + Vector3::new(-1.0, 2.0, 1.5),
+        }
+    }
+
+    #[test]
+    fn statistics_test() {
+        let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+        let residual = |normalized: Vec<f64>, rejected| Residual {
+            dt,
+            values: normalized.clone(),
+            normalized,
+            rejected,
+        };
+        let residuals = [
+            residual(vec![1.0, -1.0], false),
+            residual(vec![2.0, 0.0], false),
+            residual(vec![50.0, 3.0], true),
+        ];
+        let statistics = ResidualStatistics::new(&residuals);
+        assert_eq!(statistics.accepted, 2);
+        assert_eq!(statistics.rejected, 1);
+        assert!((statistics.weighted_rms - 1.5f64.sqrt()).abs() < 1e-15);
+        assert_eq!(statistics.max_normalized, 2.0);
+        assert_eq!(ResidualStatistics::new(&[]).weighted_rms, 0.0);
+    }
+}
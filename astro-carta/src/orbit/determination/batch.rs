@@ -0,0 +1,309 @@
+use super::{
+    epoch_partials, transitions, DeterminationError, Observation, Residual, ResidualStatistics,
+};
+use crate::math::Matrix6;
+use crate::orbit::covariance::Covariance;
+use crate::orbit::numerical::{from_slice, to_array, NumericalPropagator};
+
+/// Estimation of the state at an epoch from a batch of observations by weighted least squares
+///
+/// Each iteration linearizes the measurements about the current estimate with the state
+/// transition matrix, and solves the normal equations for a correction, each residual being
+/// weighted by the inverse variance of its noise. The iterations stop when the weighted RMS of
+/// the residuals changes by less than the relative tolerance, or when the correction is below
+/// the tolerance times its standard deviation.
+///
+/// With an outlier threshold `k`, an observation is rejected from the second iteration on when
+/// one of its normalized residuals exceeds `k` times the weighted RMS of the previous
+/// iteration, or `k` if that RMS is below one.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+///
+/// use astro_carta::constants::EARTH_GRAVITATIONAL_PARAMETER;
+/// use astro_carta::datetime::{DateTime, TimeDelta};
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::determination::measurement::GnssPosition;
+/// use astro_carta::orbit::determination::{BatchLeastSquares, Observation};
+/// use astro_carta::orbit::force::PointMass;
+/// use astro_carta::orbit::integrator::{Integrator, StepControl};
+/// use astro_carta::orbit::numerical::NumericalPropagator;
+/// use astro_carta::orbit::propagation;
+///
+/// let epoch = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let truth = StateVector {
+///     position: Vector3::new(7e6, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, 6.5e3, 3.5e3),
+/// };
+/// let model = Rc::new(GnssPosition::new(10.0));
+/// let observations: Vec<_> = (0..10)
+///     .map(|minutes| {
+///         let duration = TimeDelta::minutes(5.0 * minutes as f64);
+///         let state =
+///             propagation::universal(&truth, EARTH_GRAVITATIONAL_PARAMETER, duration).unwrap();
+///         Observation::simulate(epoch + duration, &state, model.clone()).unwrap()
+///     })
+///     .collect();
+///
+/// let guess = StateVector {
+///     position: truth.position + Vector3::new(1e3, -2e3, 500.0),
+///     velocity: truth.velocity + Vector3::new(1.0, 0.0, -2.0),
+/// };
+/// let mut propagator = NumericalPropagator::new(
+///     epoch,
+///     guess,
+///     Integrator::DormandPrince853(StepControl::new(1e-12, 1e-6)),
+/// )
+/// .with_force(PointMass::new(EARTH_GRAVITATIONAL_PARAMETER));
+/// let solution = BatchLeastSquares::new()
+///     .estimate(&mut propagator, &observations)
+///     .unwrap();
+/// assert!((solution.covariance.state.position - truth.position).norm() < 1e-3);
+/// assert_eq!(propagator.state, solution.covariance.state);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchLeastSquares {
+    pub max_iterations: usize,
+    pub tolerance: f64,
+    /// Threshold of the normalized residuals of outliers, in multiples of the weighted RMS
+    pub outlier_threshold: Option<f64>,
+    /// Covariance of the initial state, weighting it as an observation of the state
+    pub a_priori: Option<Matrix6>,
+}
+
+/// Estimated state and covariance at the epoch, with the residuals of the last iteration
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchSolution {
+    pub covariance: Covariance,
+    pub iterations: usize,
+    pub residuals: Vec<Residual>,
+    pub statistics: ResidualStatistics,
+}
+
+impl BatchLeastSquares {
+    /// Constructs an estimator of 20 iterations at most with a tolerance of 1e-4, without
+    /// outlier rejection or a priori covariance.
+    pub fn new() -> Self {
+        BatchLeastSquares {
+            max_iterations: 20,
+            tolerance: 1e-4,
+            outlier_threshold: None,
+            a_priori: None,
+        }
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Rejects the observations whose normalized residuals exceed the given multiple of the
+    /// weighted RMS, usually 3.
+    pub fn with_outlier_threshold(mut self, threshold: f64) -> Self {
+        self.outlier_threshold = Some(threshold);
+        self
+    }
+
+    /// Sets the covariance of the initial state.
+    pub fn with_a_priori(mut self, covariance: Matrix6) -> Self {
+        self.a_priori = Some(covariance);
+        self
+    }
+
+    /// Estimates the state at the epoch of the propagator, starting from its initial state,
+    /// which is updated with the estimate.
+    pub fn estimate(
+        &self,
+        propagator: &mut NumericalPropagator,
+        observations: &[Observation],
+    ) -> Result<BatchSolution, DeterminationError> {
+        let guess = to_array(&propagator.state);
+        let information = self
+            .a_priori
+            .map(|covariance| covariance.inverse().ok_or(DeterminationError::Singular))
+            .transpose()?;
+        let mut previous_rms: Option<f64> = None;
+
+        for iteration in 1..=self.max_iterations {
+            let states = transitions(propagator, observations)?;
+            let threshold = self
+                .outlier_threshold
+                .zip(previous_rms)
+                .map(|(threshold, rms)| threshold * rms.max(1.0));
+
+            let mut normal = Matrix6::zeros();
+            let mut right = [0.0; 6];
+            let state = to_array(&propagator.state);
+            if let Some(information) = &information {
+                normal = *information;
+                let offset: [f64; 6] = std::array::from_fn(|i| guess[i] - state[i]);
+                right = information.mul_vector(&offset);
+            }
+            let mut residuals = Vec::with_capacity(observations.len());
+            for (observation, (state, transition)) in observations.iter().zip(&states) {
+                let model = &observation.model;
+                let prediction = model.measure(&observation.dt, state)?;
+                let values = model.residual(&observation.values, &prediction.values);
+                let noise = model.noise();
+                let normalized: Vec<f64> = values.iter().zip(&noise).map(|(r, s)| r / s).collect();
+                let rejected = threshold.is_some_and(|threshold| {
+                    normalized.iter().any(|value| value.abs() > threshold)
+                });
+                if !rejected {
+                    let partials = epoch_partials(&prediction.partials, transition);
+                    for ((row, residual), sigma) in partials.iter().zip(&values).zip(&noise) {
+                        let weight = 1.0 / (sigma * sigma);
+                        for i in 0..6 {
+                            right[i] += weight * row[i] * residual;
+                            for j in 0..6 {
+                                normal[(i, j)] += weight * row[i] * row[j];
+                            }
+                        }
+                    }
+                }
+                residuals.push(Residual {
+                    dt: observation.dt,
+                    values,
+                    normalized,
+                    rejected,
+                });
+            }
+
+            let statistics = ResidualStatistics::new(&residuals);
+            if statistics.accepted == 0 {
+                return Err(DeterminationError::NoObservations);
+            }
+            let covariance = normal.inverse().ok_or(DeterminationError::Singular)?;
+            let correction = covariance.mul_vector(&right);
+            let significance: f64 = normal
+                .mul_vector(&correction)
+                .iter()
+                .zip(&correction)
+                .map(|(a, b)| a * b)
+                .sum();
+            let converged = significance.sqrt() < self.tolerance
+                || previous_rms.is_some_and(|rms| {
+                    (rms - statistics.weighted_rms).abs()
+                        <= self.tolerance * statistics.weighted_rms
+                });
+
+            let estimate: [f64; 6] = std::array::from_fn(|i| state[i] + correction[i]);
+            propagator.state = from_slice(&estimate);
+            if converged {
+                return Ok(BatchSolution {
+                    covariance: Covariance::new(
+                        propagator.epoch,
+                        propagator.state,
+                        covariance.symmetrize(),
+                    ),
+                    iterations: iteration,
+                    residuals,
+                    statistics,
+                });
+            }
+            previous_rms = Some(statistics.weighted_rms);
+        }
+        Err(DeterminationError::NoConvergence)
+    }
+}
+
+impl Default for BatchLeastSquares {
+    fn default() -> Self {
+        BatchLeastSquares::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{guess, propagator, simulate, truth, Noise};
+    use super::*;
+
+    #[test]
+    fn noiseless_test() {
+        let observations = simulate(None);
+        assert!(observations.len() > 100, "{}", observations.len());
+        let mut propagator = propagator(guess());
+        let solution = BatchLeastSquares::new()
+            .estimate(&mut propagator, &observations)
+            .unwrap();
+        let truth = truth();
+        assert!((propagator.state.position - truth.position).norm() < 1e-3);
+        assert!((propagator.state.velocity - truth.velocity).norm() < 1e-6);
+        assert!(solution.iterations < 10);
+        assert!(solution.statistics.weighted_rms < 1e-3);
+        assert_eq!(solution.statistics.rejected, 0);
+        assert_eq!(solution.residuals.len(), observations.len());
+    }
+
+    #[test]
+    fn noisy_test() {
+        let mut observations = simulate(Some(&mut Noise::new()));
+        // A gross error of 1 km on a range
+        let outlier = observations.len() / 2 / 3 * 3;
+        observations[outlier].values[0] += 1e3;
+
+        let mut propagator = propagator(guess());
+        let solution = BatchLeastSquares::new()
+            .with_outlier_threshold(4.0)
+            .estimate(&mut propagator, &observations)
+            .unwrap();
+        assert!(solution.residuals[outlier].rejected);
+        assert!(solution.statistics.rejected < 5);
+        let rms = solution.statistics.weighted_rms;
+        assert!((0.8..1.2).contains(&rms), "{rms}");
+
+        // The errors are consistent with the covariance
+        let truth = truth();
+        let error = [
+            propagator.state.position - truth.position,
+            propagator.state.velocity - truth.velocity,
+        ];
+        let sigmas = solution.covariance.standard_deviations();
+        for i in 0..6 {
+            let error = error[i / 3][i % 3];
+            assert!(error.abs() < 4.0 * sigmas[i], "{i}: {error} {}", sigmas[i]);
+        }
+        assert!(sigmas[0] < 10.0);
+
+        // Without rejection the outlier biases the estimate
+        let mut propagator = super::super::tests::propagator(guess());
+        let biased = BatchLeastSquares::new()
+            .estimate(&mut propagator, &observations)
+            .unwrap();
+        assert!(biased.statistics.weighted_rms > 2.0 * rms);
+    }
+
+    #[test]
+    fn a_priori_test() {
+        // A single pass of angles leaves the state poorly determined, which a tight a priori
+        // covariance holds near the guess
+        let observations: Vec<_> = simulate(None)
+            .into_iter()
+            .skip(2)
+            .step_by(3)
+            .take(5)
+            .collect();
+        let mut propagator = propagator(guess());
+        let a_priori = Matrix6::diagonal(&[1.0, 1.0, 1.0, 1e-6, 1e-6, 1e-6]);
+        let solution = BatchLeastSquares::new()
+            .with_a_priori(a_priori)
+            .estimate(&mut propagator, &observations)
+            .unwrap();
+        assert!((propagator.state.position - guess().position).norm() < 10.0);
+        assert!(solution.covariance.standard_deviations()[0] < 1.0);
+
+        let mut propagator = super::super::tests::propagator(guess());
+        assert_eq!(
+            BatchLeastSquares::new().estimate(&mut propagator, &[]),
+            Err(DeterminationError::NoObservations)
+        );
+    }
+}
@@ -0,0 +1,348 @@
+use super::{DeterminationError, Observation, Residual, ResidualStatistics};
+use crate::datetime::DateTime;
+use crate::ephemeris::StateVector;
+use crate::math::{Matrix3, Matrix6};
+use crate::orbit::covariance::Covariance;
+use crate::orbit::numerical::{from_slice, to_array, NumericalPropagator};
+
+/// Sequential estimation of the state by an extended Kalman filter
+///
+/// Between observations the state is propagated by the numerical propagator, and its
+/// covariance by the state transition matrix, increased by the process noise of a white
+/// acceleration of spectral density `q` in m²/s³:
+///
+/// ```text
+/// Q = q [[Δt³/3 I, Δt²/2 I], [Δt²/2 I, Δt I]]
+/// ```
+///
+/// Each observation then updates the state with the Kalman gain, and the covariance in the
+/// Joseph form. With an outlier threshold `k`, an observation is rejected without updating the
+/// state when one of its innovations exceeds `k` times its predicted standard deviation.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+///
+/// use astro_carta::constants::EARTH_GRAVITATIONAL_PARAMETER;
+/// use astro_carta::datetime::{DateTime, TimeDelta};
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::{Matrix6, Vector3};
+/// use astro_carta::orbit::determination::measurement::GnssPosition;
+/// use astro_carta::orbit::determination::{ExtendedKalmanFilter, Observation};
+/// use astro_carta::orbit::force::PointMass;
+/// use astro_carta::orbit::integrator::{Integrator, StepControl};
+/// use astro_carta::orbit::numerical::NumericalPropagator;
+/// use astro_carta::orbit::propagation;
+///
+/// let epoch = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let truth = StateVector {
+///     position: Vector3::new(7e6, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, 6.5e3, 3.5e3),
+/// };
+/// let guess = StateVector {
+///     position: truth.position + Vector3::new(100.0, -200.0, 50.0),
+///     velocity: truth.velocity + Vector3::new(0.1, 0.0, -0.2),
+/// };
+/// let propagator = NumericalPropagator::new(
+///     epoch,
+///     guess,
+///     Integrator::DormandPrince853(StepControl::new(1e-12, 1e-6)),
+/// )
+/// .with_force(PointMass::new(EARTH_GRAVITATIONAL_PARAMETER));
+/// let covariance = Matrix6::diagonal(&[1e6, 1e6, 1e6, 1.0, 1.0, 1.0]);
+/// let mut filter = ExtendedKalmanFilter::new(propagator, covariance);
+///
+/// let model = Rc::new(GnssPosition::new(1.0));
+/// for minutes in 1..=20 {
+///     let duration = TimeDelta::minutes(minutes as f64);
+///     let state = propagation::universal(&truth, EARTH_GRAVITATIONAL_PARAMETER, duration).unwrap();
+///     let observation = Observation::simulate(epoch + duration, &state, model.clone()).unwrap();
+///     filter.process(&observation).unwrap();
+///     if minutes == 20 {
+///         assert!((filter.state().position - state.position).norm() < 1.0);
+///     }
+/// }
+/// assert_eq!(filter.statistics().accepted, 20);
+/// ```
+#[derive(Debug)]
+pub struct ExtendedKalmanFilter {
+    propagator: NumericalPropagator,
+    covariance: Matrix6,
+    /// Spectral density of the white acceleration noise in m²/s³
+    pub process_noise: f64,
+    /// Threshold of the normalized innovations of outliers
+    pub outlier_threshold: Option<f64>,
+    residuals: Vec<Residual>,
+}
+
+impl ExtendedKalmanFilter {
+    /// Constructs a filter from the initial state of the propagator and its covariance, without
+    /// process noise or outlier rejection.
+    pub fn new(propagator: NumericalPropagator, covariance: Matrix6) -> Self {
+        ExtendedKalmanFilter {
+            propagator,
+            covariance,
+            process_noise: 0.0,
+            outlier_threshold: None,
+            residuals: Vec::new(),
+        }
+    }
+
+    /// Sets the spectral density of the white acceleration noise in m²/s³.
+    pub fn with_process_noise(mut self, process_noise: f64) -> Self {
+        self.process_noise = process_noise;
+        self
+    }
+
+    /// Rejects the observations whose innovations exceed the given multiple of their predicted
+    /// standard deviations, usually 3 to 5.
+    pub fn with_outlier_threshold(mut self, threshold: f64) -> Self {
+        self.outlier_threshold = Some(threshold);
+        self
+    }
+
+    /// Returns the epoch of the current estimate.
+    pub fn epoch(&self) -> DateTime {
+        self.propagator.epoch
+    }
+
+    /// Returns the current estimate of the state.
+    pub fn state(&self) -> StateVector {
+        self.propagator.state
+    }
+
+    /// Returns the covariance of the current estimate.
+    pub fn covariance(&self) -> Covariance {
+        Covariance::new(
+            self.propagator.epoch,
+            self.propagator.state,
+            self.covariance,
+        )
+    }
+
+    /// Returns the propagator, whose initial state is the current estimate.
+    pub fn propagator(&self) -> &NumericalPropagator {
+        &self.propagator
+    }
+
+    /// Returns the residuals of the processed observations, before their updates.
+    pub fn residuals(&self) -> &[Residual] {
+        &self.residuals
+    }
+
+    pub fn statistics(&self) -> ResidualStatistics {
+        ResidualStatistics::new(&self.residuals)
+    }
+
+    /// Propagates the estimate to the instant of an observation and updates it, returning the
+    /// residual of the observation.
+    pub fn process(&mut self, observation: &Observation) -> Result<&Residual, DeterminationError> {
+        self.predict(&observation.dt)?;
+
+        let model = &observation.model;
+        let prediction = model.measure(&observation.dt, &self.propagator.state)?;
+        let innovation = model.residual(&observation.values, &prediction.values);
+        let noise = model.noise();
+        let h = &prediction.partials;
+        let m = innovation.len();
+
+        // Covariance of the innovations, S = H P Hᵀ + R
+        let ph: Vec<[f64; 6]> = h
+            .iter()
+            .map(|row| self.covariance.mul_vector(row))
+            .collect();
+        let mut s = vec![vec![0.0; m]; m];
+        for i in 0..m {
+            for j in 0..m {
+                s[i][j] = (0..6).map(|k| h[i][k] * ph[j][k]).sum();
+            }
+            s[i][i] += noise[i] * noise[i];
+        }
+        let normalized: Vec<f64> = (0..m).map(|i| innovation[i] / s[i][i].sqrt()).collect();
+        let rejected = self
+            .outlier_threshold
+            .is_some_and(|threshold| normalized.iter().any(|value| value.abs() > threshold));
+
+        if !rejected {
+            let inverse = invert(s).ok_or(DeterminationError::Singular)?;
+            // Kalman gain, K = P Hᵀ S⁻¹, one column per component
+            let gain: Vec<[f64; 6]> = (0..m)
+                .map(|j| std::array::from_fn(|k| (0..m).map(|i| ph[i][k] * inverse[i][j]).sum()))
+                .collect();
+            let mut state = to_array(&self.propagator.state);
+            let mut factor = Matrix6::identity();
+            for (column, residual) in gain.iter().zip(&innovation) {
+                for k in 0..6 {
+                    state[k] += column[k] * residual;
+                }
+            }
+            for (column, row) in gain.iter().zip(h) {
+                for i in 0..6 {
+                    for j in 0..6 {
+                        factor[(i, j)] -= column[i] * row[j];
+                    }
+                }
+            }
+            let mut noise_term = Matrix6::zeros();
+            for (column, sigma) in gain.iter().zip(&noise) {
+                for i in 0..6 {
+                    for j in 0..6 {
+                        noise_term[(i, j)] += column[i] * sigma * sigma * column[j];
+                    }
+                }
+            }
+            self.propagator.state = from_slice(&state);
+            self.covariance =
+                (factor * self.covariance * factor.transpose() + noise_term).symmetrize();
+        }
+
+        self.residuals.push(Residual {
+            dt: observation.dt,
+            values: innovation,
+            normalized,
+            rejected,
+        });
+        Ok(self.residuals.last().unwrap())
+    }
+
+    /// Propagates the estimate and its covariance to an instant.
+    pub fn predict(&mut self, dt: &DateTime) -> Result<(), DeterminationError> {
+        if *dt == self.propagator.epoch {
+            return Ok(());
+        }
+        let (state, transition) = self.propagator.propagate_with_transition(dt)?;
+        let duration = (*dt - self.propagator.epoch).total_seconds().abs();
+        let q = self.process_noise;
+        let block = |scale: f64| Matrix3::identity() * (q * scale);
+        let noise = Matrix6::from_blocks(
+            &block(duration.powi(3) / 3.0),
+            &block(duration.powi(2) / 2.0),
+            &block(duration.powi(2) / 2.0),
+            &block(duration),
+        );
+        self.covariance =
+            (transition * self.covariance * transition.transpose() + noise).symmetrize();
+        self.propagator.epoch = *dt;
+        self.propagator.state = state;
+        Ok(())
+    }
+}
+
+/// Inverts a small square matrix by Gauss-Jordan elimination with partial pivoting, returning
+/// `None` if it is singular.
+fn invert(mut a: Vec<Vec<f64>>) -> Option<Vec<Vec<f64>>> {
+    let n = a.len();
+    let mut result: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+    for column in 0..n {
+        let pivot =
+            (column..n).max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs()))?;
+        if a[pivot][column] == 0.0 || !a[pivot][column].is_finite() {
+            return None;
+        }
+        a.swap(column, pivot);
+        result.swap(column, pivot);
+        let scale = 1.0 / a[column][column];
+        for j in 0..n {
+            a[column][j] *= scale;
+            result[column][j] *= scale;
+        }
+        for i in 0..n {
+            if i != column {
+                let factor = a[i][column];
+                for j in 0..n {
+                    a[i][j] -= factor * a[column][j];
+                    result[i][j] -= factor * result[column][j];
+                }
+            }
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{epoch, guess, propagator, simulate, truth, Noise};
+    use super::*;
+    use crate::constants::EARTH_GRAVITATIONAL_PARAMETER;
+    use crate::orbit::propagation;
+
+    fn filter() -> ExtendedKalmanFilter {
+        let covariance = Matrix6::diagonal(&[1e8, 1e8, 1e8, 1e2, 1e2, 1e2]);
+        ExtendedKalmanFilter::new(propagator(guess()), covariance)
+    }
+
+    #[test]
+    fn filter_test() {
+        let mut observations = simulate(Some(&mut Noise::new()));
+        let outlier = observations.len() / 2 / 3 * 3;
+        observations[outlier].values[0] += 1e3;
+
+        let mut filter = filter().with_outlier_threshold(5.0);
+        for observation in &observations {
+            filter.process(observation).unwrap();
+        }
+        assert!(filter.residuals()[outlier].rejected);
+        let statistics = filter.statistics();
+        assert_eq!(
+            statistics.accepted + statistics.rejected,
+            observations.len()
+        );
+        assert!(statistics.rejected < 5);
+
+        let end = observations.last().unwrap().dt;
+        assert_eq!(filter.epoch(), end);
+        let expected =
+            propagation::universal(&truth(), EARTH_GRAVITATIONAL_PARAMETER, end - epoch()).unwrap();
+        let covariance = filter.covariance();
+        let sigmas = covariance.standard_deviations();
+        let error = [
+            filter.state().position - expected.position,
+            filter.state().velocity - expected.velocity,
+        ];
+        for i in 0..6 {
+            let error = error[i / 3][i % 3];
+            assert!(error.abs() < 4.0 * sigmas[i], "{i}: {error} {}", sigmas[i]);
+        }
+        assert!(sigmas[0] < 20.0);
+        assert_eq!(filter.propagator().state, filter.state());
+    }
+
+    #[test]
+    fn process_noise_test() {
+        let observations = simulate(None);
+        let mut quiet = filter();
+        let mut noisy = filter().with_process_noise(1e-6);
+        for observation in &observations[..30] {
+            quiet.process(observation).unwrap();
+            noisy.process(observation).unwrap();
+        }
+        // Between passes the process noise inflates the covariance
+        let dt = quiet.epoch() + crate::datetime::TimeDelta::hours(1.0);
+        quiet.predict(&dt).unwrap();
+        noisy.predict(&dt).unwrap();
+        let quiet = quiet.covariance().standard_deviations();
+        let noisy = noisy.covariance().standard_deviations();
+        assert!(noisy[0] > 1.5 * quiet[0], "{} {}", noisy[0], quiet[0]);
+    }
+
+    #[test]
+    fn invert_test() {
+        let a = vec![
+            vec![4.0, 1.0, 0.5],
+            vec![1.0, 3.0, 0.2],
+            vec![0.5, 0.2, 2.0],
+        ];
+        let inverse = invert(a.clone()).unwrap();
+        for (i, row) in a.iter().enumerate() {
+            for j in 0..3 {
+                let product: f64 = row.iter().zip(&inverse).map(|(a, b)| a * b[j]).sum();
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((product - expected).abs() < 1e-14);
+            }
+        }
+        assert!(invert(vec![vec![1.0, 2.0], vec![2.0, 4.0]]).is_none());
+    }
+}
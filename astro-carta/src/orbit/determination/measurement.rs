@@ -0,0 +1,554 @@
+use super::{DeterminationError, MeasurementModel, Prediction};
+use crate::constants::{EARTH_GRAVITATIONAL_PARAMETER, SPEED_OF_LIGHT};
+use crate::datetime::{DateTime, TimeDelta};
+use crate::ephemeris::StateVector;
+use crate::math::{self, Matrix3, Vector3};
+use crate::orbit::force::{BodyRotation, EarthRotation};
+use crate::orbit::station::GroundStation;
+
+/// Number of iterations of the light time, each gaining a factor v/c of about 1e-5
+const LIGHT_TIME_ITERATIONS: usize = 3;
+
+/// A leg of the light path between a station and the object
+struct Leg {
+    /// Light time in seconds
+    delay: f64,
+    /// Vector from the station to the object in meters
+    line_of_sight: Vector3,
+}
+
+impl Leg {
+    fn direction(&self) -> Vector3 {
+        self.line_of_sight / self.line_of_sight.norm()
+    }
+
+    /// Partial derivatives of the range with respect to the position of the object.
+    fn range_partials(&self) -> Vector3 {
+        self.direction()
+    }
+
+    /// Range rate for the given relative velocity, and its partial derivatives with respect to
+    /// the position of the object.
+    fn range_rate(&self, relative_velocity: &Vector3) -> (f64, Vector3) {
+        let u = self.direction();
+        let rate = u.dot(relative_velocity);
+        (
+            rate,
+            (*relative_velocity - u * rate) / self.line_of_sight.norm(),
+        )
+    }
+}
+
+/// Returns the state of the object at a delay before the time tag of its state.
+///
+/// The state is expanded to the second order in the delay with the central attraction of the
+/// Earth, leaving errors below a millimeter and a micrometer per second in geostationary orbit.
+fn retarded(state: &StateVector, delay: f64) -> StateVector {
+    let r = state.position.norm();
+    let acceleration = state.position * (-EARTH_GRAVITATIONAL_PARAMETER / (r * r * r));
+    StateVector {
+        position: state.position - state.velocity * delay + acceleration * (0.5 * delay * delay),
+        velocity: state.velocity - acceleration * delay,
+    }
+}
+
+/// Solves the light time from the object to a station receiving at the time tag.
+fn downlink(state: &StateVector, station: &StateVector) -> Result<Leg, DeterminationError> {
+    if state.position.norm() == 0.0 {
+        return Err(DeterminationError::Geometry);
+    }
+    let mut delay = 0.0;
+    let mut line_of_sight = state.position - station.position;
+    for _ in 0..LIGHT_TIME_ITERATIONS {
+        line_of_sight = retarded(state, delay).position - station.position;
+        delay = line_of_sight.norm() / SPEED_OF_LIGHT;
+    }
+    if delay == 0.0 {
+        return Err(DeterminationError::Geometry);
+    }
+    Ok(Leg {
+        delay,
+        line_of_sight,
+    })
+}
+
+/// Solves the light time from a station to the object, reached after a downlink delay, and
+/// returns it with the state of the station at the transmission.
+fn uplink(
+    position: &Vector3,
+    station: &GroundStation,
+    dt: &DateTime,
+    downlink: &Leg,
+) -> (Leg, StateVector) {
+    // The station is evaluated near the transmission, and moved along its velocity from there
+    let reference = 2.0 * downlink.delay;
+    let transmitter = station.state(&(*dt - TimeDelta::seconds(reference)));
+    let mut delay = downlink.delay;
+    let mut line_of_sight = downlink.line_of_sight;
+    for _ in 0..LIGHT_TIME_ITERATIONS {
+        let offset = reference - downlink.delay - delay;
+        line_of_sight = *position - (transmitter.position + transmitter.velocity * offset);
+        delay = line_of_sight.norm() / SPEED_OF_LIGHT;
+    }
+    (
+        Leg {
+            delay,
+            line_of_sight,
+        },
+        transmitter,
+    )
+}
+
+/// Returns the partials of a measurement with respect to the position at the time tag, and of
+/// the position retarded by the light time with respect to the velocity.
+fn row(position: &Vector3, delay: f64) -> [f64; 6] {
+    let velocity = *position * -delay;
+    [
+        position.x, position.y, position.z, velocity.x, velocity.y, velocity.z,
+    ]
+}
+
+/// Two-way range in meters, half the round-trip light time from a station times the speed of
+/// light
+///
+/// The time tag is the reception at the station. The troposphere and the ionosphere are not
+/// modeled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range {
+    pub station: GroundStation,
+    /// Standard deviation of the noise in meters
+    pub sigma: f64,
+}
+
+impl Range {
+    pub fn new(station: GroundStation, sigma: f64) -> Self {
+        Range { station, sigma }
+    }
+}
+
+impl MeasurementModel for Range {
+    fn dimension(&self) -> usize {
+        1
+    }
+
+    fn noise(&self) -> Vec<f64> {
+        vec![self.sigma]
+    }
+
+    fn measure(
+        &self,
+        dt: &DateTime,
+        state: &StateVector,
+    ) -> Result<Prediction, DeterminationError> {
+        let down = downlink(state, &self.station.state(dt))?;
+        let reflection = retarded(state, down.delay);
+        let (up, _) = uplink(&reflection.position, &self.station, dt, &down);
+        let partials = (down.range_partials() + up.range_partials()) * 0.5;
+        Ok(Prediction {
+            values: vec![0.5 * (down.line_of_sight.norm() + up.line_of_sight.norm())],
+            partials: vec![row(&partials, down.delay)],
+        })
+    }
+}
+
+/// Two-way range rate in m/s, the mean of the rates of the uplink and downlink ranges
+///
+/// The time tag is the reception at the station, and the rate is instantaneous rather than
+/// averaged over a count interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RangeRate {
+    pub station: GroundStation,
+    /// Standard deviation of the noise in m/s
+    pub sigma: f64,
+}
+
+impl RangeRate {
+    pub fn new(station: GroundStation, sigma: f64) -> Self {
+        RangeRate { station, sigma }
+    }
+}
+
+impl MeasurementModel for RangeRate {
+    fn dimension(&self) -> usize {
+        1
+    }
+
+    fn noise(&self) -> Vec<f64> {
+        vec![self.sigma]
+    }
+
+    fn measure(
+        &self,
+        dt: &DateTime,
+        state: &StateVector,
+    ) -> Result<Prediction, DeterminationError> {
+        let receiver = self.station.state(dt);
+        let down = downlink(state, &receiver)?;
+        let reflection = retarded(state, down.delay);
+        let (up, transmitter) = uplink(&reflection.position, &self.station, dt, &down);
+        let (down_rate, down_partials) =
+            down.range_rate(&(reflection.velocity - receiver.velocity));
+        let (up_rate, up_partials) = up.range_rate(&(reflection.velocity - transmitter.velocity));
+        let position = (down_partials + up_partials) * 0.5;
+        let velocity = (down.direction() + up.direction()) * 0.5 - position * down.delay;
+        Ok(Prediction {
+            values: vec![0.5 * (down_rate + up_rate)],
+            partials: vec![[
+                position.x, position.y, position.z, velocity.x, velocity.y, velocity.z,
+            ]],
+        })
+    }
+}
+
+/// Partial derivatives of the longitude and latitude of a vector, such as an azimuth or a
+/// right ascension and an elevation or a declination, with respect to the vector.
+///
+/// The longitude is measured from the y-axis towards the x-axis when `clockwise` is set, as an
+/// azimuth from the north towards the east, and from the x-axis towards the y-axis otherwise.
+fn angles(
+    vector: &Vector3,
+    clockwise: bool,
+) -> Result<([f64; 2], [Vector3; 2]), DeterminationError> {
+    let (x, y, z) = (vector.x, vector.y, vector.z);
+    let horizontal = x.hypot(y);
+    let squared = vector.dot(vector);
+    if horizontal == 0.0 {
+        return Err(DeterminationError::Geometry);
+    }
+    let latitude = z.atan2(horizontal);
+    let latitude_partials = Vector3::new(
+        -x * z / (squared * horizontal),
+        -y * z / (squared * horizontal),
+        horizontal / squared,
+    );
+    let (longitude, longitude_partials) = if clockwise {
+        (
+            math::normalize_angle(x.atan2(y)),
+            Vector3::new(y, -x, 0.0) / (horizontal * horizontal),
+        )
+    } else {
+        (
+            math::normalize_angle(y.atan2(x)),
+            Vector3::new(-y, x, 0.0) / (horizontal * horizontal),
+        )
+    };
+    Ok((
+        [longitude, latitude],
+        [longitude_partials, latitude_partials],
+    ))
+}
+
+/// Wraps the difference of the first components, angles in radians, into [-π, π).
+fn angle_residual(observed: &[f64], computed: &[f64]) -> Vec<f64> {
+    vec![
+        math::normalize_angle_signed(observed[0] - computed[0]),
+        observed[1] - computed[1],
+    ]
+}
+
+/// Geometric azimuth and elevation in radians of the object seen from a station, the azimuth
+/// measured from the north towards the east
+///
+/// The direction is corrected for the light time, but not for aberration or refraction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AzimuthElevation {
+    pub station: GroundStation,
+    /// Standard deviation of the noise of both angles in radians
+    pub sigma: f64,
+}
+
+impl AzimuthElevation {
+    pub fn new(station: GroundStation, sigma: f64) -> Self {
+        AzimuthElevation { station, sigma }
+    }
+}
+
+impl MeasurementModel for AzimuthElevation {
+    fn dimension(&self) -> usize {
+        2
+    }
+
+    fn noise(&self) -> Vec<f64> {
+        vec![self.sigma; 2]
+    }
+
+    fn measure(
+        &self,
+        dt: &DateTime,
+        state: &StateVector,
+    ) -> Result<Prediction, DeterminationError> {
+        let down = downlink(state, &self.station.state(dt))?;
+        let rotation = self.station.enu_rotation(dt);
+        let (values, partials) = angles(&(rotation * down.line_of_sight), true)?;
+        // The partials in local axes are rotated back to the GCRS
+        let inverse = rotation.transpose();
+        Ok(Prediction {
+            values: values.to_vec(),
+            partials: partials
+                .iter()
+                .map(|partials| row(&(inverse * *partials), down.delay))
+                .collect(),
+        })
+    }
+
+    fn residual(&self, observed: &[f64], computed: &[f64]) -> Vec<f64> {
+        angle_residual(observed, computed)
+    }
+}
+
+/// Geometric topocentric right ascension and declination in radians of the object seen from a
+/// station, in the axes of the GCRS
+///
+/// The direction is corrected for the light time, but not for aberration or refraction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RightAscensionDeclination {
+    pub station: GroundStation,
+    /// Standard deviation of the noise of both angles in radians
+    pub sigma: f64,
+}
+
+impl RightAscensionDeclination {
+    pub fn new(station: GroundStation, sigma: f64) -> Self {
+        RightAscensionDeclination { station, sigma }
+    }
+}
+
+impl MeasurementModel for RightAscensionDeclination {
+    fn dimension(&self) -> usize {
+        2
+    }
+
+    fn noise(&self) -> Vec<f64> {
+        vec![self.sigma; 2]
+    }
+
+    fn measure(
+        &self,
+        dt: &DateTime,
+        state: &StateVector,
+    ) -> Result<Prediction, DeterminationError> {
+        let down = downlink(state, &self.station.state(dt))?;
+        let (values, partials) = angles(&down.line_of_sight, false)?;
+        Ok(Prediction {
+            values: values.to_vec(),
+            partials: partials
+                .iter()
+                .map(|partials| row(partials, down.delay))
+                .collect(),
+        })
+    }
+
+    fn residual(&self, observed: &[f64], computed: &[f64]) -> Vec<f64> {
+        angle_residual(observed, computed)
+    }
+}
+
+/// Position in meters of the object given by a GNSS receiver on board
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GnssPosition {
+    /// Standard deviation of the noise of each coordinate in meters
+    pub sigma: f64,
+    /// Rotation of the Earth for positions in the ITRS, or `None` for positions in the GCRS
+    pub rotation: Option<EarthRotation>,
+}
+
+impl GnssPosition {
+    /// Constructs a model of positions in the GCRS.
+    pub fn new(sigma: f64) -> Self {
+        GnssPosition {
+            sigma,
+            rotation: None,
+        }
+    }
+
+    /// Sets the rotation of the Earth, for positions in the ITRS.
+    pub fn with_rotation(mut self, rotation: EarthRotation) -> Self {
+        self.rotation = Some(rotation);
+        self
+    }
+}
+
+impl MeasurementModel for GnssPosition {
+    fn dimension(&self) -> usize {
+        3
+    }
+
+    fn noise(&self) -> Vec<f64> {
+        vec![self.sigma; 3]
+    }
+
+    fn measure(
+        &self,
+        dt: &DateTime,
+        state: &StateVector,
+    ) -> Result<Prediction, DeterminationError> {
+        let rotation = match &self.rotation {
+            // The Earth rotation never fails
+            Some(rotation) => rotation.rotation_to_body_fixed(dt).unwrap(),
+            None => Matrix3::identity(),
+        };
+        Ok(Prediction {
+            values: (rotation * state.position).to_array().to_vec(),
+            partials: (0..3)
+                .map(|i| {
+                    let row = rotation.row(i);
+                    [row.x, row.y, row.z, 0.0, 0.0, 0.0]
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinates::Observer;
+    use crate::orbit::propagation;
+
+    fn station() -> GroundStation {
+        GroundStation::new(Observer::from_degrees(40.4314, -4.2481, 834.0))
+    }
+
+    fn epoch() -> DateTime {
+        DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap()
+    }
+
+    /// A geostationary object above the station, far enough for the light time to matter
+    fn state() -> StateVector {
+        let station = station().state(&epoch()).position;
+        let direction = (station / station.norm() + Vector3::new(0.1, -0.2, 0.0)).normalize();
+        let position = direction * 42_164e3;
+        let velocity = Vector3::new(0.0, 0.0, 1.0).cross(&direction).normalize() * 3_075.0;
+        StateVector { position, velocity }
+    }
+
+    fn partials_test(model: &dyn MeasurementModel, tolerance: f64) {
+        let (dt, state) = (epoch(), state());
+        let prediction = model.measure(&dt, &state).unwrap();
+        assert_eq!(prediction.values.len(), model.dimension());
+        assert_eq!(prediction.partials.len(), model.dimension());
+        for j in 0..6 {
+            let h = if j < 3 { 10.0 } else { 1e-2 };
+            let shifted = |sign: f64| {
+                let mut state = state;
+                if j < 3 {
+                    state.position[j] += sign * h;
+                } else {
+                    state.velocity[j - 3] += sign * h;
+                }
+                model.measure(&dt, &state).unwrap().values
+            };
+            let (forward, backward) = (shifted(1.0), shifted(-1.0));
+            let difference = model.residual(&forward, &backward);
+            for (i, (difference, partials)) in
+                difference.iter().zip(&prediction.partials).enumerate()
+            {
+                let expected = difference / (2.0 * h);
+                let scale = partials
+                    .iter()
+                    .fold(0.0f64, |max, value| max.max(value.abs()));
+                assert!(
+                    (partials[j] - expected).abs() < tolerance * scale,
+                    "{model:?} ({i}, {j}): {} != {expected}",
+                    partials[j]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn range_test() {
+        let (dt, state) = (epoch(), state());
+        let range = Range::new(station(), 1.0);
+        let computed = range.measure(&dt, &state).unwrap().values[0];
+
+        // The round trip, with the object at its position at the reflection and the station at
+        // its positions at the transmission and the reception
+        let station = station();
+        let mut delay = 2.0 * computed / SPEED_OF_LIGHT;
+        let mut down = 0.0;
+        for _ in 0..5 {
+            let reflection = propagation::universal(
+                &state,
+                EARTH_GRAVITATIONAL_PARAMETER,
+                TimeDelta::seconds(-down),
+            )
+            .unwrap()
+            .position;
+            down = (reflection - station.state(&dt).position).norm() / SPEED_OF_LIGHT;
+            let up = delay - down;
+            let transmission = station
+                .state(&(dt - TimeDelta::seconds(down + up)))
+                .position;
+            let up = (reflection - transmission).norm() / SPEED_OF_LIGHT;
+            delay = down + up;
+        }
+        assert!((computed - 0.5 * delay * SPEED_OF_LIGHT).abs() < 0.01);
+        let geometric = (state.position - station.state(&dt).position).norm();
+        assert!((computed - geometric).abs() > 1.0);
+        partials_test(&range, 1e-6);
+    }
+
+    #[test]
+    fn range_rate_test() {
+        let (dt, state) = (epoch(), state());
+        let model = RangeRate::new(station(), 1e-3);
+        let computed = model.measure(&dt, &state).unwrap().values[0];
+        let range = |dt: &DateTime| {
+            let state =
+                propagation::universal(&state, EARTH_GRAVITATIONAL_PARAMETER, *dt - epoch())
+                    .unwrap();
+            Range::new(station(), 1.0)
+                .measure(dt, &state)
+                .unwrap()
+                .values[0]
+        };
+        let h = TimeDelta::seconds(1.0);
+        let expected = (range(&(dt + h)) - range(&(dt - h))) / 2.0;
+        assert!((computed - expected).abs() < 1e-3, "{computed} {expected}");
+        partials_test(&model, 1e-5);
+    }
+
+    #[test]
+    fn angles_test() {
+        let (dt, state) = (epoch(), state());
+        let model = AzimuthElevation::new(station(), 1e-5);
+        let values = model.measure(&dt, &state).unwrap().values;
+        let enu =
+            model.station.enu_rotation(&dt) * (state.position - model.station.state(&dt).position);
+        // The light time shifts the direction by about v τ / ρ, 1e-5 radians
+        assert!((values[0] - math::normalize_angle(enu.x.atan2(enu.y))).abs() < 3e-5);
+        assert!((values[1] - enu.z.atan2(enu.x.hypot(enu.y))).abs() < 3e-5);
+        assert!(values[1] > 0.5);
+        partials_test(&model, 1e-5);
+
+        let model = RightAscensionDeclination::new(station(), 1e-5);
+        let values = model.measure(&dt, &state).unwrap().values;
+        let topocentric = state.position - model.station.state(&dt).position;
+        assert!((values[1] - (topocentric.z / topocentric.norm()).asin()).abs() < 3e-5);
+        partials_test(&model, 1e-5);
+
+        assert_eq!(
+            model.residual(&[0.1, 0.2], &[6.2, 0.1]),
+            vec![math::normalize_angle_signed(0.1 - 6.2), 0.2 - 0.1]
+        );
+    }
+
+    #[test]
+    fn gnss_test() {
+        let (dt, state) = (epoch(), state());
+        let model = GnssPosition::new(5.0);
+        assert_eq!(
+            model.measure(&dt, &state).unwrap().values,
+            state.position.to_array().to_vec()
+        );
+        let rotation = EarthRotation::default();
+        let model = model.with_rotation(rotation);
+        let itrs = rotation.rotation_to_body_fixed(&dt).unwrap() * state.position;
+        assert_eq!(
+            model.measure(&dt, &state).unwrap().values,
+            itrs.to_array().to_vec()
+        );
+        partials_test(&model, 1e-9);
+    }
+}
@@ -0,0 +1,496 @@
+use std::f64::consts::PI;
+
+use super::{kepler, OrbitError};
+use crate::datetime::DateTime;
+use crate::ephemeris::StateVector;
+use crate::math::{self, Matrix3, Vector3};
+
+/// Distance of the eccentricity from one below which a state is treated as parabolic
+const PARABOLIC_TOLERANCE: f64 = 1e-10;
+
+/// Eccentricity below which a state is treated as circular
+const CIRCULAR_TOLERANCE: f64 = 1e-11;
+
+/// Sine of the inclination below which a state is treated as equatorial
+const EQUATORIAL_TOLERANCE: f64 = 1e-11;
+
+/// Position of a body along its orbit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Anomaly {
+    /// True anomaly ν in radians
+    True(f64),
+    /// Mean anomaly M in radians
+    Mean(f64),
+    /// Eccentric anomaly E in radians; the hyperbolic anomaly H for hyperbolic orbits and the
+    /// parabolic anomaly D = tan(ν/2) for parabolic orbits
+    Eccentric(f64),
+}
+
+/// Classical orbital elements of a two-body orbit
+///
+/// The elements are relative to the reference frame of the state they describe, usually the
+/// equator and equinox of J2000. For hyperbolic orbits the semi-major axis is negative. For
+/// parabolic orbits, whose semi-major axis is infinite, `semi_major_axis` holds the periapsis
+/// distance instead.
+///
+/// Elements derived from a state follow the usual conventions for singular orbits: the
+/// ascending node is zero for equatorial orbits and the argument of periapsis is measured from
+/// the x-axis, and for circular orbits the argument of periapsis is zero and the true anomaly
+/// is the argument of latitude, or the true longitude if the orbit is also equatorial. The
+/// [`EquinoctialElements`](super::EquinoctialElements) and
+/// [`ModifiedEquinoctialElements`](super::ModifiedEquinoctialElements) avoid these
+/// singularities altogether.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::constants::EARTH_GRAVITATIONAL_PARAMETER;
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::KeplerianElements;
+///
+/// let state = StateVector {
+///     position: Vector3::new(7_000_000.0, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, 6_000.0, 4_000.0),
+/// };
+/// let epoch = DateTime::gregorian(2024, 1, 1, 0, 0, 0.0).unwrap();
+/// let elements = KeplerianElements::from_state(&state, EARTH_GRAVITATIONAL_PARAMETER, epoch).unwrap();
+/// assert!(elements.eccentricity < 1.0);
+/// let round_trip = elements.to_state().unwrap();
+/// assert!((round_trip.position - state.position).norm() < 1e-6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeplerianElements {
+    /// Epoch at which the anomaly applies
+    pub epoch: DateTime,
+    /// Gravitational parameter of the central body in m³/s²
+    pub gravitational_parameter: f64,
+    /// Semi-major axis in meters, negative for hyperbolic orbits; the periapsis distance for
+    /// parabolic orbits
+    pub semi_major_axis: f64,
+    /// Eccentricity
+    pub eccentricity: f64,
+    /// Inclination in radians
+    pub inclination: f64,
+    /// Right ascension of the ascending node in radians
+    pub ascending_node: f64,
+    /// Argument of periapsis in radians
+    pub argument_of_periapsis: f64,
+    /// Anomaly at the epoch
+    pub anomaly: Anomaly,
+}
+
+impl KeplerianElements {
+    /// Computes the elements of the orbit through a state.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - Position in meters and velocity in m/s relative to the central body.
+    /// * `gravitational_parameter` - Gravitational parameter of the central body in m³/s².
+    /// * `epoch` - Instant of the state.
+    pub fn from_state(
+        state: &StateVector,
+        gravitational_parameter: f64,
+        epoch: DateTime,
+    ) -> Result<Self, OrbitError> {
+        if !(gravitational_parameter > 0.0 && gravitational_parameter.is_finite()) {
+            return Err(OrbitError::InvalidGravitationalParameter);
+        }
+
+        let (r, v) = (state.position, state.velocity);
+        let (r_norm, v_norm) = (r.norm(), v.norm());
+        let momentum = r.cross(&v);
+        let momentum_norm = momentum.norm();
+        if momentum_norm <= 1e-14 * r_norm * v_norm || !momentum_norm.is_finite() {
+            return Err(OrbitError::DegenerateState);
+        }
+        let normal = momentum / momentum_norm;
+
+        let eccentricity_vector = (r * (v_norm * v_norm - gravitational_parameter / r_norm)
+            - v * r.dot(&v))
+            / gravitational_parameter;
+        let mut eccentricity = eccentricity_vector.norm();
+        let semi_latus_rectum = momentum_norm * momentum_norm / gravitational_parameter;
+
+        let node_vector = Vector3::new(-momentum.y, momentum.x, 0.0);
+        let node_norm = node_vector.norm();
+        let inclination = node_norm.atan2(momentum.z);
+        let (ascending_node, node) = if node_norm <= EQUATORIAL_TOLERANCE * momentum_norm {
+            (0.0, Vector3::new(1.0, 0.0, 0.0))
+        } else {
+            (
+                math::normalize_angle(node_vector.y.atan2(node_vector.x)),
+                node_vector / node_norm,
+            )
+        };
+
+        // Angle from a to b about the orbit normal
+        let angle = |a: &Vector3, b: &Vector3| a.cross(b).dot(&normal).atan2(a.dot(b));
+        let (argument_of_periapsis, true_anomaly) = if eccentricity < CIRCULAR_TOLERANCE {
+            eccentricity = 0.0;
+            (0.0, angle(&node, &r))
+        } else {
+            (
+                math::normalize_angle(angle(&node, &eccentricity_vector)),
+                angle(&eccentricity_vector, &r),
+            )
+        };
+
+        let semi_major_axis = if (eccentricity - 1.0).abs() < PARABOLIC_TOLERANCE {
+            eccentricity = 1.0;
+            semi_latus_rectum / 2.0
+        } else {
+            semi_latus_rectum / (1.0 - eccentricity * eccentricity)
+        };
+        let true_anomaly = if eccentricity < 1.0 {
+            math::normalize_angle(true_anomaly)
+        } else {
+            true_anomaly
+        };
+
+        Ok(KeplerianElements {
+            epoch,
+            gravitational_parameter,
+            semi_major_axis,
+            eccentricity,
+            inclination,
+            ascending_node,
+            argument_of_periapsis,
+            anomaly: Anomaly::True(true_anomaly),
+        })
+    }
+
+    /// Computes the state at the epoch, with position in meters and velocity in m/s.
+    pub fn to_state(&self) -> Result<StateVector, OrbitError> {
+        self.validate()?;
+
+        let p = self.semi_latus_rectum();
+        let e = self.eccentricity;
+        let (sin_v, cos_v) = self.true_anomaly().sin_cos();
+        let radius = p / (1.0 + e * cos_v);
+        let speed = (self.gravitational_parameter / p).sqrt();
+
+        let position = Vector3::new(radius * cos_v, radius * sin_v, 0.0);
+        let velocity = Vector3::new(-speed * sin_v, speed * (e + cos_v), 0.0);
+        let rotation = self.perifocal_rotation().transpose();
+
+        Ok(StateVector {
+            position: rotation * position,
+            velocity: rotation * velocity,
+        })
+    }
+
+    /// Checks that the elements describe a conic section and that the anomaly lies on it.
+    pub fn validate(&self) -> Result<(), OrbitError> {
+        if !(self.gravitational_parameter > 0.0 && self.gravitational_parameter.is_finite()) {
+            return Err(OrbitError::InvalidGravitationalParameter);
+        }
+        let (a, e) = (self.semi_major_axis, self.eccentricity);
+        if !(e >= 0.0 && e.is_finite()) {
+            return Err(OrbitError::InvalidElements(
+                "eccentricity must be non-negative",
+            ));
+        }
+        if !a.is_finite() || (e > 1.0 && a >= 0.0) || (e <= 1.0 && a <= 0.0) {
+            return Err(OrbitError::InvalidElements(
+                "semi-major axis must be positive for closed and parabolic orbits and negative for hyperbolic ones",
+            ));
+        }
+        if e >= 1.0 {
+            let limit = if e > 1.0 { (-1.0 / e).acos() } else { PI };
+            let true_anomaly = math::normalize_angle_signed(self.true_anomaly());
+            if true_anomaly.abs() >= limit {
+                return Err(OrbitError::InvalidElements(
+                    "true anomaly lies beyond the asymptotes",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the semi-latus rectum in meters.
+    pub fn semi_latus_rectum(&self) -> f64 {
+        if self.eccentricity == 1.0 {
+            2.0 * self.semi_major_axis
+        } else {
+            self.semi_major_axis * (1.0 - self.eccentricity * self.eccentricity)
+        }
+    }
+
+    /// Returns the periapsis distance in meters.
+    pub fn periapsis(&self) -> f64 {
+        self.semi_latus_rectum() / (1.0 + self.eccentricity)
+    }
+
+    /// Returns the apoapsis distance in meters, infinite for open orbits.
+    pub fn apoapsis(&self) -> f64 {
+        if self.eccentricity < 1.0 {
+            self.semi_major_axis * (1.0 + self.eccentricity)
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// Returns the mean motion in rad/s, the rate of the mean anomaly.
+    pub fn mean_motion(&self) -> f64 {
+        let mu = self.gravitational_parameter;
+        if self.eccentricity == 1.0 {
+            // Barker's equation with M = D + D³/3
+            2.0 * (mu / self.semi_latus_rectum().powi(3)).sqrt()
+        } else {
+            (mu / self.semi_major_axis.abs().powi(3)).sqrt()
+        }
+    }
+
+    /// Returns the orbital period in seconds, or `None` for open orbits.
+    pub fn period(&self) -> Option<f64> {
+        (self.eccentricity < 1.0).then(|| math::TWO_PI / self.mean_motion())
+    }
+
+    /// Returns the true anomaly in radians.
+    pub fn true_anomaly(&self) -> f64 {
+        match self.anomaly {
+            Anomaly::True(anomaly) => anomaly,
+            Anomaly::Mean(anomaly) => kepler::mean_to_true(anomaly, self.eccentricity),
+            Anomaly::Eccentric(anomaly) => kepler::eccentric_to_true(anomaly, self.eccentricity),
+        }
+    }
+
+    /// Returns the mean anomaly in radians.
+    pub fn mean_anomaly(&self) -> f64 {
+        match self.anomaly {
+            Anomaly::True(anomaly) => kepler::true_to_mean(anomaly, self.eccentricity),
+            Anomaly::Mean(anomaly) => anomaly,
+            Anomaly::Eccentric(anomaly) => kepler::eccentric_to_mean(anomaly, self.eccentricity),
+        }
+    }
+
+    /// Returns the eccentric, hyperbolic or parabolic anomaly in radians.
+    pub fn eccentric_anomaly(&self) -> f64 {
+        match self.anomaly {
+            Anomaly::True(anomaly) => kepler::true_to_eccentric(anomaly, self.eccentricity),
+            Anomaly::Mean(anomaly) => kepler::solve(anomaly, self.eccentricity),
+            Anomaly::Eccentric(anomaly) => anomaly,
+        }
+    }
+
+    /// Returns the rotation from the reference axes to perifocal axes, whose x-axis points to
+    /// periapsis and z-axis along the angular momentum.
+    pub fn perifocal_rotation(&self) -> Matrix3 {
+        Matrix3::rot_z(self.argument_of_periapsis)
+            * Matrix3::rot_x(self.inclination)
+            * Matrix3::rot_z(self.ascending_node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::EARTH_GRAVITATIONAL_PARAMETER;
+    use crate::datetime::TimeScale;
+
+    fn epoch() -> DateTime {
+        DateTime::from_days_since_j2000(8_800.0, TimeScale::TT)
+    }
+
+    fn state(position: [f64; 3], velocity: [f64; 3]) -> StateVector {
+        StateVector {
+            position: Vector3::from(position) * 1e3,
+            velocity: Vector3::from(velocity) * 1e3,
+        }
+    }
+
+    #[test]
+    fn from_state_test() {
+        // Vallado, Fundamentals of Astrodynamics and Applications, example 2-5
+        let state = state(
+            [6_524.834, 6_862.875, 6_448.296],
+            [4.901_327, 5.533_756, -1.976_341],
+        );
+        let elements = KeplerianElements::from_state(&state, 398_600.441_8e9, epoch()).unwrap();
+        assert!((elements.semi_latus_rectum() / 1e3 - 11_067.790).abs() < 1e-2);
+        assert!((elements.semi_major_axis / 1e3 - 36_127.343).abs() < 1e-2);
+        assert!((elements.eccentricity - 0.832_853).abs() < 1e-6);
+        assert!((elements.inclination.to_degrees() - 87.870).abs() < 1e-3);
+        assert!((elements.ascending_node.to_degrees() - 227.898).abs() < 1e-3);
+        assert!((elements.argument_of_periapsis.to_degrees() - 53.38).abs() < 1e-2);
+        assert!((elements.true_anomaly().to_degrees() - 92.335).abs() < 1e-3);
+        assert_eq!(elements.epoch, epoch());
+    }
+
+    #[test]
+    fn to_state_test() {
+        // Vallado, Fundamentals of Astrodynamics and Applications, example 2-6
+        let elements = KeplerianElements {
+            epoch: epoch(),
+            gravitational_parameter: 398_600.441_8e9,
+            semi_major_axis: 11_067.790e3 / (1.0 - 0.832_85_f64.powi(2)),
+            eccentricity: 0.832_85,
+            inclination: 87.87_f64.to_radians(),
+            ascending_node: 227.89_f64.to_radians(),
+            argument_of_periapsis: 53.38_f64.to_radians(),
+            anomaly: Anomaly::True(92.335_f64.to_radians()),
+        };
+        let expected = state(
+            [6_525.368, 6_861.532, 6_449.119],
+            [4.902_279, 5.533_140, -1.975_710],
+        );
+        let actual = elements.to_state().unwrap();
+        assert!((actual.position - expected.position).norm() < 5.0);
+        assert!((actual.velocity - expected.velocity).norm() < 5e-3);
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let mu = EARTH_GRAVITATIONAL_PARAMETER;
+        let circular = (mu / 7e6).sqrt();
+        let escape = (2.0 * mu / 7e6).sqrt();
+        let cases = [
+            // Elliptic, inclined
+            state([7_000.0, 1_000.0, -500.0], [-1.0, 7.5, 2.0]),
+            // Circular, inclined
+            StateVector {
+                position: Vector3::new(7e6, 0.0, 0.0),
+                velocity: Vector3::new(0.0, 0.6, 0.8) * circular,
+            },
+            // Circular, equatorial
+            StateVector {
+                position: Vector3::new(0.0, 7e6, 0.0),
+                velocity: Vector3::new(-circular, 0.0, 0.0),
+            },
+            // Elliptic, retrograde equatorial
+            state([-6_000.0, 3_000.0, 0.0], [2.0, 8.0, 0.0]),
+            // Parabolic
+            StateVector {
+                position: Vector3::new(7e6, 0.0, 0.0),
+                velocity: Vector3::new(0.0, 0.0, escape),
+            },
+            // Hyperbolic, outbound and inbound
+            state([7_000.0, -2_000.0, 3_000.0], [3.0, 11.0, -2.0]),
+            state([7_000.0, -2_000.0, 3_000.0], [-3.0, -11.0, 2.0]),
+        ];
+
+        for state in cases {
+            let elements = KeplerianElements::from_state(&state, mu, epoch()).unwrap();
+            let round_trip = elements.to_state().unwrap();
+            assert!(
+                (round_trip.position - state.position).norm() < 1e-6,
+                "{elements:?}"
+            );
+            assert!(
+                (round_trip.velocity - state.velocity).norm() < 1e-9,
+                "{elements:?}"
+            );
+
+            for anomaly in [
+                Anomaly::Mean(elements.mean_anomaly()),
+                Anomaly::Eccentric(elements.eccentric_anomaly()),
+            ] {
+                let elements = KeplerianElements {
+                    anomaly,
+                    ..elements
+                };
+                let round_trip = elements.to_state().unwrap();
+                assert!((round_trip.position - state.position).norm() < 1e-5);
+            }
+        }
+
+        let parabolic = KeplerianElements::from_state(&cases[4], mu, epoch()).unwrap();
+        assert_eq!(parabolic.eccentricity, 1.0);
+        assert!((parabolic.periapsis() - 7e6).abs() < 1e-3);
+        assert!((parabolic.semi_major_axis - 7e6).abs() < 1e-3);
+        let retrograde = KeplerianElements::from_state(&cases[3], mu, epoch()).unwrap();
+        assert_eq!(retrograde.inclination, PI);
+        assert_eq!(retrograde.ascending_node, 0.0);
+        let circular = KeplerianElements::from_state(&cases[2], mu, epoch()).unwrap();
+        assert_eq!(circular.eccentricity, 0.0);
+        assert!((circular.true_anomaly() - 0.5 * PI).abs() < 1e-15);
+    }
+
+    #[test]
+    fn derived_quantities_test() {
+        let elements = KeplerianElements {
+            epoch: epoch(),
+            gravitational_parameter: EARTH_GRAVITATIONAL_PARAMETER,
+            semi_major_axis: 26_560e3,
+            eccentricity: 0.7,
+            inclination: 1.1,
+            ascending_node: 0.3,
+            argument_of_periapsis: 4.7,
+            anomaly: Anomaly::Mean(0.2),
+        };
+        assert!((elements.period().unwrap() - 43_080.0).abs() < 10.0);
+        assert!((elements.periapsis() - 7_968e3).abs() < 1e-3);
+        assert!((elements.apoapsis() - 45_152e3).abs() < 1e-3);
+
+        // The state lies at the perifocal position given by the eccentric anomaly
+        let eccentric = elements.eccentric_anomaly();
+        let perifocal = elements.perifocal_rotation() * elements.to_state().unwrap().position;
+        let a = elements.semi_major_axis;
+        assert!((perifocal.x - a * (eccentric.cos() - 0.7)).abs() < 1e-6);
+        assert!((perifocal.y - a * (1.0 - 0.49_f64).sqrt() * eccentric.sin()).abs() < 1e-6);
+        assert!(perifocal.z.abs() < 1e-6);
+
+        let hyperbolic = KeplerianElements {
+            semi_major_axis: -20_000e3,
+            eccentricity: 1.5,
+            ..elements
+        };
+        assert_eq!(hyperbolic.period(), None);
+        assert_eq!(hyperbolic.apoapsis(), f64::INFINITY);
+        assert!((hyperbolic.periapsis() - 10_000e3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn invalid_test() {
+        let elements = KeplerianElements {
+            epoch: epoch(),
+            gravitational_parameter: EARTH_GRAVITATIONAL_PARAMETER,
+            semi_major_axis: -20_000e3,
+            eccentricity: 2.0,
+            inclination: 0.2,
+            ascending_node: 0.0,
+            argument_of_periapsis: 0.0,
+            anomaly: Anomaly::True(2.0),
+        };
+        assert!(elements.to_state().is_ok());
+        for invalid in [
+            KeplerianElements {
+                anomaly: Anomaly::True(2.1),
+                ..elements
+            },
+            KeplerianElements {
+                semi_major_axis: 20_000e3,
+                ..elements
+            },
+            KeplerianElements {
+                eccentricity: 0.5,
+                ..elements
+            },
+            KeplerianElements {
+                eccentricity: -0.1,
+                ..elements
+            },
+        ] {
+            assert!(matches!(
+                invalid.to_state(),
+                Err(OrbitError::InvalidElements(_))
+            ));
+        }
+        let elements = KeplerianElements {
+            gravitational_parameter: 0.0,
+            ..elements
+        };
+        assert_eq!(
+            elements.to_state(),
+            Err(OrbitError::InvalidGravitationalParameter)
+        );
+
+        let radial = state([7_000.0, 0.0, 0.0], [3.0, 0.0, 0.0]);
+        assert_eq!(
+            KeplerianElements::from_state(&radial, EARTH_GRAVITATIONAL_PARAMETER, epoch()),
+            Err(OrbitError::DegenerateState)
+        );
+    }
+}
@@ -0,0 +1,428 @@
+use super::{kepler, Anomaly, KeplerianElements, OrbitError};
+use crate::datetime::DateTime;
+use crate::ephemeris::StateVector;
+use crate::math::{self, Vector3};
+
+/// Equinoctial elements of a closed orbit (Broucke and Cefola, 1972)
+///
+/// The elements are nonsingular for circular and equatorial orbits and are defined with the
+/// direct retrograde factor, so they are singular only for retrograde equatorial orbits:
+///
+/// * `h = e sin(ω + Ω)`, `k = e cos(ω + Ω)`
+/// * `p = tan(i/2) sin Ω`, `q = tan(i/2) cos Ω`
+/// * `λ = M + ω + Ω`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EquinoctialElements {
+    /// Epoch at which the mean longitude applies
+    pub epoch: DateTime,
+    /// Gravitational parameter of the central body in m³/s²
+    pub gravitational_parameter: f64,
+    /// Semi-major axis in meters
+    pub semi_major_axis: f64,
+    /// Eccentricity vector component along the equinoctial y-axis
+    pub h: f64,
+    /// Eccentricity vector component along the equinoctial x-axis
+    pub k: f64,
+    /// Inclination vector component along the equinoctial y-axis
+    pub p: f64,
+    /// Inclination vector component along the equinoctial x-axis
+    pub q: f64,
+    /// Mean longitude in radians
+    pub mean_longitude: f64,
+}
+
+impl EquinoctialElements {
+    /// Computes the elements of the closed orbit through a state.
+    pub fn from_state(
+        state: &StateVector,
+        gravitational_parameter: f64,
+        epoch: DateTime,
+    ) -> Result<Self, OrbitError> {
+        EquinoctialElements::from_modified(&ModifiedEquinoctialElements::from_state(
+            state,
+            gravitational_parameter,
+            epoch,
+        )?)
+    }
+
+    /// Computes the state at the epoch, with position in meters and velocity in m/s.
+    pub fn to_state(&self) -> Result<StateVector, OrbitError> {
+        self.to_modified()?.to_state()
+    }
+
+    /// Converts modified equinoctial elements of a closed orbit to equinoctial elements.
+    pub fn from_modified(elements: &ModifiedEquinoctialElements) -> Result<Self, OrbitError> {
+        let e = elements.eccentricity();
+        if e >= 1.0 {
+            return Err(OrbitError::InvalidElements(
+                "equinoctial elements describe closed orbits only",
+            ));
+        }
+
+        let (h, k) = (elements.g, elements.f);
+        let periapsis = h.atan2(k);
+        let eccentric_longitude =
+            periapsis + kepler::true_to_eccentric(elements.true_longitude - periapsis, e);
+
+        Ok(EquinoctialElements {
+            epoch: elements.epoch,
+            gravitational_parameter: elements.gravitational_parameter,
+            semi_major_axis: elements.semi_latus_rectum / (1.0 - e * e),
+            h,
+            k,
+            p: elements.k,
+            q: elements.h,
+            mean_longitude: eccentric_longitude + h * eccentric_longitude.cos()
+                - k * eccentric_longitude.sin(),
+        })
+    }
+
+    /// Converts the elements to modified equinoctial elements.
+    pub fn to_modified(&self) -> Result<ModifiedEquinoctialElements, OrbitError> {
+        let e = self.eccentricity();
+        if !(e < 1.0 && self.semi_major_axis > 0.0) {
+            return Err(OrbitError::InvalidElements(
+                "equinoctial elements need a positive semi-major axis and an eccentricity below one",
+            ));
+        }
+
+        let periapsis = self.h.atan2(self.k);
+        let eccentric_longitude = kepler::solve_equinoctial(self.mean_longitude, self.h, self.k);
+
+        Ok(ModifiedEquinoctialElements {
+            epoch: self.epoch,
+            gravitational_parameter: self.gravitational_parameter,
+            semi_latus_rectum: self.semi_major_axis * (1.0 - e * e),
+            f: self.k,
+            g: self.h,
+            h: self.q,
+            k: self.p,
+            true_longitude: periapsis
+                + kepler::eccentric_to_true(eccentric_longitude - periapsis, e),
+        })
+    }
+
+    /// Converts classical elements of a closed orbit to equinoctial elements.
+    pub fn from_keplerian(elements: &KeplerianElements) -> Result<Self, OrbitError> {
+        EquinoctialElements::from_modified(&ModifiedEquinoctialElements::from_keplerian(elements))
+    }
+
+    /// Converts the elements to classical elements with a mean anomaly.
+    pub fn to_keplerian(&self) -> Result<KeplerianElements, OrbitError> {
+        let mut elements = self.to_modified()?.to_keplerian();
+        elements.anomaly = Anomaly::Mean(
+            self.mean_longitude - elements.argument_of_periapsis - elements.ascending_node,
+        );
+        Ok(elements)
+    }
+
+    /// Returns the eccentricity.
+    pub fn eccentricity(&self) -> f64 {
+        self.h.hypot(self.k)
+    }
+
+    /// Returns the inclination in radians.
+    pub fn inclination(&self) -> f64 {
+        2.0 * self.p.hypot(self.q).atan()
+    }
+
+    /// Returns the eccentric longitude `F = E + ω + Ω` in radians.
+    pub fn eccentric_longitude(&self) -> f64 {
+        kepler::solve_equinoctial(self.mean_longitude, self.h, self.k)
+    }
+}
+
+/// Modified equinoctial elements of an orbit of any eccentricity (Walker, Ireland and Owens,
+/// 1985)
+///
+/// The elements are nonsingular for circular, equatorial and parabolic orbits and singular
+/// only for retrograde equatorial orbits:
+///
+/// * `p = a (1 - e²)`, the semi-latus rectum
+/// * `f = e cos(ω + Ω)`, `g = e sin(ω + Ω)`
+/// * `h = tan(i/2) cos Ω`, `k = tan(i/2) sin Ω`
+/// * `L = Ω + ω + ν`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModifiedEquinoctialElements {
+    /// Epoch at which the true longitude applies
+    pub epoch: DateTime,
+    /// Gravitational parameter of the central body in m³/s²
+    pub gravitational_parameter: f64,
+    /// Semi-latus rectum in meters
+    pub semi_latus_rectum: f64,
+    /// Eccentricity vector component along the equinoctial x-axis
+    pub f: f64,
+    /// Eccentricity vector component along the equinoctial y-axis
+    pub g: f64,
+    /// Inclination vector component along the equinoctial x-axis
+    pub h: f64,
+    /// Inclination vector component along the equinoctial y-axis
+    pub k: f64,
+    /// True longitude in radians
+    pub true_longitude: f64,
+}
+
+impl ModifiedEquinoctialElements {
+    /// Computes the elements of the orbit through a state.
+    pub fn from_state(
+        state: &StateVector,
+        gravitational_parameter: f64,
+        epoch: DateTime,
+    ) -> Result<Self, OrbitError> {
+        if !(gravitational_parameter > 0.0 && gravitational_parameter.is_finite()) {
+            return Err(OrbitError::InvalidGravitationalParameter);
+        }
+
+        let (r, v) = (state.position, state.velocity);
+        let r_norm = r.norm();
+        let momentum = r.cross(&v);
+        let momentum_norm = momentum.norm();
+        if momentum_norm <= 1e-14 * r_norm * v.norm() || !momentum_norm.is_finite() {
+            return Err(OrbitError::DegenerateState);
+        }
+        let normal = momentum / momentum_norm;
+        if normal.z <= -1.0 + f64::EPSILON {
+            return Err(OrbitError::InvalidElements(
+                "equinoctial elements are singular for retrograde equatorial orbits",
+            ));
+        }
+
+        let h = -normal.y / (1.0 + normal.z);
+        let k = normal.x / (1.0 + normal.z);
+        let (f_axis, g_axis) = equinoctial_axes(h, k);
+        let eccentricity_vector = v.cross(&momentum) / gravitational_parameter - r / r_norm;
+
+        Ok(ModifiedEquinoctialElements {
+            epoch,
+            gravitational_parameter,
+            semi_latus_rectum: momentum_norm * momentum_norm / gravitational_parameter,
+            f: eccentricity_vector.dot(&f_axis),
+            g: eccentricity_vector.dot(&g_axis),
+            h,
+            k,
+            true_longitude: math::normalize_angle(r.dot(&g_axis).atan2(r.dot(&f_axis))),
+        })
+    }
+
+    /// Computes the state at the epoch, with position in meters and velocity in m/s.
+    pub fn to_state(&self) -> Result<StateVector, OrbitError> {
+        if !(self.gravitational_parameter > 0.0 && self.gravitational_parameter.is_finite()) {
+            return Err(OrbitError::InvalidGravitationalParameter);
+        }
+        let (sin_l, cos_l) = self.true_longitude.sin_cos();
+        let w = 1.0 + self.f * cos_l + self.g * sin_l;
+        if !(self.semi_latus_rectum > 0.0 && w > 0.0) {
+            return Err(OrbitError::InvalidElements(
+                "semi-latus rectum must be positive and the true longitude lie on the orbit",
+            ));
+        }
+
+        let radius = self.semi_latus_rectum / w;
+        let speed = (self.gravitational_parameter / self.semi_latus_rectum).sqrt();
+        let (f_axis, g_axis) = equinoctial_axes(self.h, self.k);
+
+        Ok(StateVector {
+            position: (f_axis * cos_l + g_axis * sin_l) * radius,
+            velocity: (f_axis * -(self.g + sin_l) + g_axis * (self.f + cos_l)) * speed,
+        })
+    }
+
+    /// Converts classical elements to modified equinoctial elements.
+    pub fn from_keplerian(elements: &KeplerianElements) -> Self {
+        let e = elements.eccentricity;
+        let periapsis = elements.argument_of_periapsis + elements.ascending_node;
+        let tangent = (0.5 * elements.inclination).tan();
+        let (sin_node, cos_node) = elements.ascending_node.sin_cos();
+
+        ModifiedEquinoctialElements {
+            epoch: elements.epoch,
+            gravitational_parameter: elements.gravitational_parameter,
+            semi_latus_rectum: elements.semi_latus_rectum(),
+            f: e * periapsis.cos(),
+            g: e * periapsis.sin(),
+            h: tangent * cos_node,
+            k: tangent * sin_node,
+            true_longitude: periapsis + elements.true_anomaly(),
+        }
+    }
+
+    /// Converts the elements to classical elements with a true anomaly.
+    ///
+    /// Singular angles are set to zero as in [`KeplerianElements::from_state`].
+    pub fn to_keplerian(&self) -> KeplerianElements {
+        let e = self.eccentricity();
+        let tangent = self.h.hypot(self.k);
+        let ascending_node = if tangent == 0.0 {
+            0.0
+        } else {
+            math::normalize_angle(self.k.atan2(self.h))
+        };
+        let periapsis = if e == 0.0 {
+            ascending_node
+        } else {
+            self.g.atan2(self.f)
+        };
+        let true_anomaly = self.true_longitude - periapsis;
+
+        KeplerianElements {
+            epoch: self.epoch,
+            gravitational_parameter: self.gravitational_parameter,
+            semi_major_axis: if e == 1.0 {
+                self.semi_latus_rectum / 2.0
+            } else {
+                self.semi_latus_rectum / (1.0 - e * e)
+            },
+            eccentricity: e,
+            inclination: 2.0 * tangent.atan(),
+            ascending_node,
+            argument_of_periapsis: math::normalize_angle(periapsis - ascending_node),
+            anomaly: Anomaly::True(if e < 1.0 {
+                math::normalize_angle(true_anomaly)
+            } else {
+                math::normalize_angle_signed(true_anomaly)
+            }),
+        }
+    }
+
+    /// Returns the eccentricity.
+    pub fn eccentricity(&self) -> f64 {
+        self.f.hypot(self.g)
+    }
+
+    /// Returns the inclination in radians.
+    pub fn inclination(&self) -> f64 {
+        2.0 * self.h.hypot(self.k).atan()
+    }
+}
+
+/// Returns the unit vectors along the x- and y-axes of the equinoctial frame, whose x-axis lies
+/// in the orbit plane at an angle Ω behind the ascending node.
+fn equinoctial_axes(h: f64, k: f64) -> (Vector3, Vector3) {
+    let s2 = 1.0 + h * h + k * k;
+    (
+        Vector3::new(1.0 - k * k + h * h, 2.0 * h * k, -2.0 * k) / s2,
+        Vector3::new(2.0 * h * k, 1.0 + k * k - h * h, 2.0 * h) / s2,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::EARTH_GRAVITATIONAL_PARAMETER;
+    use crate::datetime::TimeScale;
+
+    const MU: f64 = EARTH_GRAVITATIONAL_PARAMETER;
+
+    fn epoch() -> DateTime {
+        DateTime::from_days_since_j2000(-1_200.5, TimeScale::TT)
+    }
+
+    fn keplerian(a: f64, e: f64, i: f64, anomaly: f64) -> KeplerianElements {
+        KeplerianElements {
+            epoch: epoch(),
+            gravitational_parameter: MU,
+            semi_major_axis: a,
+            eccentricity: e,
+            inclination: i,
+            ascending_node: 2.2,
+            argument_of_periapsis: 0.7,
+            anomaly: Anomaly::True(anomaly),
+        }
+    }
+
+    #[test]
+    fn modified_test() {
+        for elements in [
+            keplerian(7_200e3, 0.01, 0.9, 1.3),
+            keplerian(42_164e3, 0.0, 0.0, 4.0),
+            keplerian(7_000e3, 1.0, 2.5, -1.0),
+            keplerian(-30_000e3, 1.8, 0.4, 1.5),
+        ] {
+            let state = elements.to_state().unwrap();
+            let modified = ModifiedEquinoctialElements::from_keplerian(&elements);
+            let from_state = ModifiedEquinoctialElements::from_state(&state, MU, epoch()).unwrap();
+            assert!((modified.semi_latus_rectum - from_state.semi_latus_rectum).abs() < 1e-6);
+            assert!((modified.f - from_state.f).abs() < 1e-12);
+            assert!((modified.g - from_state.g).abs() < 1e-12);
+            assert!((modified.h - from_state.h).abs() < 1e-12);
+            assert!((modified.k - from_state.k).abs() < 1e-12);
+            assert!(
+                math::normalize_angle_signed(modified.true_longitude - from_state.true_longitude)
+                    .abs()
+                    < 1e-12
+            );
+
+            let round_trip = from_state.to_state().unwrap();
+            assert!((round_trip.position - state.position).norm() < 1e-6);
+            assert!((round_trip.velocity - state.velocity).norm() < 1e-9);
+            let round_trip = modified.to_keplerian().to_state().unwrap();
+            assert!((round_trip.position - state.position).norm() < 1e-6);
+            assert!((modified.eccentricity() - elements.eccentricity).abs() < 1e-15);
+            assert!((modified.inclination() - elements.inclination).abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn near_singular_test() {
+        // The elements vary smoothly through circular equatorial orbits
+        let speed = (MU / 7e6).sqrt();
+        for tilt in [0.0, 1e-13, 1e-9] {
+            let state = StateVector {
+                position: Vector3::new(7e6, 0.0, 0.0),
+                velocity: Vector3::new(0.0, speed * (1.0 + tilt), speed * tilt),
+            };
+            let modified = ModifiedEquinoctialElements::from_state(&state, MU, epoch()).unwrap();
+            assert!(modified.f.abs() < 3.0 * tilt + 1e-15);
+            assert!(modified.g.abs() < 1e-15);
+            assert!((modified.h - tilt / 2.0).abs() < 1e-15);
+            assert!(modified.k.abs() < 1e-15);
+            assert!(modified.true_longitude.abs() < 1e-15);
+
+            let equinoctial = EquinoctialElements::from_state(&state, MU, epoch()).unwrap();
+            assert!((equinoctial.semi_major_axis - 7e6).abs() < 1e-3 + 2e7 * tilt);
+            let round_trip = equinoctial.to_state().unwrap();
+            assert!((round_trip.position - state.position).norm() < 1e-6);
+            assert!((round_trip.velocity - state.velocity).norm() < 1e-9);
+        }
+
+        let retrograde = StateVector {
+            position: Vector3::new(7e6, 0.0, 0.0),
+            velocity: Vector3::new(0.0, -speed, 0.0),
+        };
+        assert!(matches!(
+            ModifiedEquinoctialElements::from_state(&retrograde, MU, epoch()),
+            Err(OrbitError::InvalidElements(_))
+        ));
+    }
+
+    #[test]
+    fn equinoctial_test() {
+        let elements = KeplerianElements {
+            anomaly: Anomaly::Mean(5.1),
+            ..keplerian(26_600e3, 0.72, 1.1, 0.0)
+        };
+        let equinoctial = EquinoctialElements::from_keplerian(&elements).unwrap();
+        let longitude = 5.1 + 0.7 + 2.2;
+        assert!(math::normalize_angle_signed(equinoctial.mean_longitude - longitude).abs() < 1e-12);
+        assert!((equinoctial.h - 0.72 * 2.9_f64.sin()).abs() < 1e-15);
+        assert!((equinoctial.q - 0.55_f64.tan() * 2.2_f64.cos()).abs() < 1e-15);
+        assert!((equinoctial.semi_major_axis - 26_600e3).abs() < 1e-6);
+        assert!((equinoctial.inclination() - 1.1).abs() < 1e-15);
+        assert!(
+            math::normalize_angle_signed(
+                equinoctial.eccentric_longitude() - elements.eccentric_anomaly() - 2.9
+            )
+            .abs()
+                < 1e-12
+        );
+
+        let expected = elements.to_state().unwrap();
+        let state = equinoctial.to_state().unwrap();
+        assert!((state.position - expected.position).norm() < 1e-6);
+        let classical = equinoctial.to_keplerian().unwrap();
+        assert!((classical.to_state().unwrap().position - expected.position).norm() < 1e-6);
+
+        let hyperbolic = keplerian(-30_000e3, 1.8, 0.4, 1.5);
+        assert!(EquinoctialElements::from_keplerian(&hyperbolic).is_err());
+    }
+}
@@ -0,0 +1,525 @@
+use std::fmt;
+
+use super::force::radiation::apparent_angles;
+use super::propagation::Propagator;
+use super::station::GroundStation;
+use crate::constants::EARTH_EQUATORIAL_RADIUS;
+use crate::datetime::{DateTime, Interval, IntervalSet, TimeDelta};
+use crate::ephemeris::{Ephemeris, StateVector};
+use crate::math::roots;
+
+/// Error raised while searching for events
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventError {
+    /// The propagator failed to compute a state
+    Propagation(String),
+    /// An event function failed, such as when reading an ephemeris
+    Data(String),
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EventError::Propagation(message) => write!(f, "propagation failed: {message}"),
+            EventError::Data(message) => write!(f, "event function failed: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for EventError {}
+
+/// A function of time and state whose zeros are the events, positive inside the windows
+///
+/// Any closure `Fn(&DateTime, &StateVector) -> f64` is an event function.
+pub trait EventFunction {
+    fn value(&self, dt: &DateTime, state: &StateVector) -> Result<f64, EventError>;
+}
+
+impl<F: Fn(&DateTime, &StateVector) -> f64> EventFunction for F {
+    fn value(&self, dt: &DateTime, state: &StateVector) -> Result<f64, EventError> {
+        Ok(self(dt, state))
+    }
+}
+
+/// Direction in which an event function crosses zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// From negative to positive, entering a window
+    Increasing,
+    /// From positive to negative, leaving a window
+    Decreasing,
+}
+
+/// An instant at which an event function crosses zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Event {
+    pub dt: DateTime,
+    pub direction: Direction,
+}
+
+/// Detector of the zeros of an event function along the output of a propagator
+///
+/// The function is sampled at a fixed step, and each change of sign is refined with Brent's
+/// method. Two zeros closer than the step may go unnoticed, so the step should be shorter than
+/// the shortest window or gap sought.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::constants::EARTH_GRAVITATIONAL_PARAMETER;
+/// use astro_carta::datetime::{DateTime, TimeDelta};
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::events::{Direction, EventDetector, NodeCrossing};
+/// use astro_carta::orbit::TwoBody;
+///
+/// let state = StateVector {
+///     position: Vector3::new(7_000e3, 0.0, -1_000e3),
+///     velocity: Vector3::new(0.0, 6_000.0, 6_000.0),
+/// };
+/// let start = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let propagator = TwoBody::new(state, EARTH_GRAVITATIONAL_PARAMETER, start);
+/// let detector = EventDetector::new(TimeDelta::minutes(5.0));
+/// let end = start + TimeDelta::hours(3.0);
+/// let nodes = detector.find(&propagator, &NodeCrossing, &start, &end).unwrap();
+/// assert_eq!(nodes[0].direction, Direction::Increasing);
+///
+/// // The windows farther than 10000 km from the center of the Earth
+/// let far = |_: &DateTime, state: &StateVector| state.position.norm() - 10_000e3;
+/// let windows = detector.windows(&propagator, &far, &start, &end).unwrap();
+/// assert_eq!(windows.len(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventDetector {
+    /// Interval between the samples of the event function
+    pub step: TimeDelta,
+    /// Largest error on the instants of the events
+    pub tolerance: TimeDelta,
+}
+
+impl EventDetector {
+    /// Constructs a detector sampling at the given step, locating events within a microsecond.
+    pub fn new(step: TimeDelta) -> Self {
+        EventDetector {
+            step,
+            tolerance: TimeDelta::microseconds(1.0),
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: TimeDelta) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Finds the zeros of the event function between two instants, in chronological order.
+    pub fn find<P, G>(
+        &self,
+        propagator: &P,
+        function: &G,
+        start: &DateTime,
+        end: &DateTime,
+    ) -> Result<Vec<Event>, EventError>
+    where
+        P: Propagator,
+        G: EventFunction + ?Sized,
+    {
+        self.search(propagator, function, start, end)
+            .map(|(_, events)| events)
+    }
+
+    /// Returns the windows between two instants where the event function is positive.
+    pub fn windows<P, G>(
+        &self,
+        propagator: &P,
+        function: &G,
+        start: &DateTime,
+        end: &DateTime,
+    ) -> Result<IntervalSet, EventError>
+    where
+        P: Propagator,
+        G: EventFunction + ?Sized,
+    {
+        let (inside, events) = self.search(propagator, function, start, end)?;
+        let mut intervals = Vec::new();
+        let mut opening = inside.then_some(*start);
+        for event in events {
+            match event.direction {
+                Direction::Increasing => opening = Some(event.dt),
+                Direction::Decreasing => intervals.extend(
+                    opening
+                        .take()
+                        .and_then(|opening| Interval::new(opening, event.dt)),
+                ),
+            }
+        }
+        intervals.extend(opening.and_then(|opening| Interval::new(opening, *end)));
+        Ok(IntervalSet::from_intervals(intervals))
+    }
+
+    /// Returns whether the function is positive at the start, and the events.
+    fn search<P, G>(
+        &self,
+        propagator: &P,
+        function: &G,
+        start: &DateTime,
+        end: &DateTime,
+    ) -> Result<(bool, Vec<Event>), EventError>
+    where
+        P: Propagator,
+        G: EventFunction + ?Sized,
+    {
+        let evaluate = |offset: f64| -> Result<f64, EventError> {
+            let dt = *start + TimeDelta::seconds(offset);
+            let state = propagator
+                .propagate(&dt)
+                .map_err(|error| EventError::Propagation(error.to_string()))?;
+            function.value(&dt, &state)
+        };
+        let span = (*end - *start).total_seconds();
+        let step = self.step.total_seconds();
+        let tolerance = self.tolerance.total_seconds();
+        if step <= 0.0 {
+            return Err(EventError::Data("the step must be positive".to_string()));
+        }
+
+        let mut a = 0.0;
+        let mut fa = evaluate(a)?;
+        let inside = fa > 0.0;
+        let mut events = Vec::new();
+        while a < span {
+            let b = (a + step).min(span);
+            let fb = evaluate(b)?;
+            if (fa > 0.0) != (fb > 0.0) {
+                let root = roots::brent(evaluate, a, fa, b, fb, tolerance)?;
+                events.push(Event {
+                    dt: *start + TimeDelta::seconds(root),
+                    direction: if fb > 0.0 {
+                        Direction::Increasing
+                    } else {
+                        Direction::Decreasing
+                    },
+                });
+            }
+            (a, fa) = (b, fb);
+        }
+        Ok((inside, events))
+    }
+}
+
+/// Crossing of the equatorial plane, increasing at the ascending node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeCrossing;
+
+impl EventFunction for NodeCrossing {
+    fn value(&self, _dt: &DateTime, state: &StateVector) -> Result<f64, EventError> {
+        Ok(state.position.z)
+    }
+}
+
+/// Passage at an apsis, increasing at periapsis and decreasing at apoapsis
+///
+/// The function is the radial velocity times the distance, `r · v`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Apsis;
+
+impl EventFunction for Apsis {
+    fn value(&self, _dt: &DateTime, state: &StateVector) -> Result<f64, EventError> {
+        Ok(state.position.dot(&state.velocity))
+    }
+}
+
+/// Geometric elevation of the object above a threshold seen from a ground station, whose
+/// windows are the passes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Elevation {
+    pub station: GroundStation,
+    /// Smallest elevation in radians
+    pub threshold: f64,
+}
+
+impl Elevation {
+    pub fn new(station: GroundStation, threshold: f64) -> Self {
+        Elevation { station, threshold }
+    }
+}
+
+impl EventFunction for Elevation {
+    fn value(&self, dt: &DateTime, state: &StateVector) -> Result<f64, EventError> {
+        Ok(self.station.elevation(dt, &state.position) - self.threshold)
+    }
+}
+
+/// Boundary of the shadow of a central body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shadow {
+    /// Outer boundary of the penumbra, where the solar disk starts being hidden
+    Penumbra,
+    /// Boundary of the umbra, where the solar disk becomes fully hidden
+    Umbra,
+}
+
+/// Eclipse of the Sun by a spherical central body, positive in sunlight
+///
+/// The function is the apparent separation of the Sun and the body less the sum of their
+/// apparent radii at the penumbra, or less their difference at the umbra, so its windows
+/// are the periods outside the penumbra or the umbra. The position of the Sun relative to
+/// the central body is read from an ephemeris, in the axes of the states.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::rc::Rc;
+///
+/// use astro_carta::constants::EARTH_GRAVITATIONAL_PARAMETER;
+/// use astro_carta::datetime::{DateTime, TimeDelta};
+/// use astro_carta::ephemeris::spk::Spk;
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::events::{Eclipse, EventDetector, Shadow};
+/// use astro_carta::orbit::TwoBody;
+///
+/// let spk = Rc::new(Spk::open("de440.bsp").unwrap());
+/// let state = StateVector {
+///     position: Vector3::new(7_000e3, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, 7_546.0, 0.0),
+/// };
+/// let start = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let propagator = TwoBody::new(state, EARTH_GRAVITATIONAL_PARAMETER, start);
+/// let eclipse = Eclipse::new(spk, 399, Shadow::Umbra);
+/// let end = start + TimeDelta::days(1.0);
+/// let sunlit = EventDetector::new(TimeDelta::minutes(1.0))
+///     .windows(&propagator, &eclipse, &start, &end)
+///     .unwrap();
+/// let umbra = sunlit.complement(&astro_carta::datetime::Interval::new(start, end).unwrap());
+/// println!("{} s in the umbra", umbra.duration().total_seconds());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Eclipse<E> {
+    ephemeris: E,
+    /// NAIF ID of the central body
+    pub center: i32,
+    /// Radius of the central body in meters
+    pub radius: f64,
+    pub boundary: Shadow,
+}
+
+impl<E: Ephemeris> Eclipse<E> {
+    /// Constructs the eclipse function of the given central body, with the radius of the Earth.
+    pub fn new(ephemeris: E, center: i32, boundary: Shadow) -> Self {
+        Eclipse {
+            ephemeris,
+            center,
+            radius: EARTH_EQUATORIAL_RADIUS,
+            boundary,
+        }
+    }
+
+    /// Sets the radius of the central body in meters, for bodies other than the Earth.
+    pub fn with_radius(mut self, radius: f64) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn ephemeris(&self) -> &E {
+        &self.ephemeris
+    }
+}
+
+impl<E: Ephemeris> EventFunction for Eclipse<E> {
+    fn value(&self, dt: &DateTime, state: &StateVector) -> Result<f64, EventError> {
+        let sun = self
+            .ephemeris
+            .position(10, self.center, dt)
+            .map_err(|error| EventError::Data(error.to_string()))?;
+        let (a, b, c) = apparent_angles(&state.position, &sun, self.radius);
+        Ok(match self.boundary {
+            Shadow::Penumbra => c - (a + b),
+            Shadow::Umbra => c - (b - a),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{ASTRONOMICAL_UNIT, EARTH_GRAVITATIONAL_PARAMETER};
+    use crate::coordinates::Observer;
+    use crate::math::{Vector3, TWO_PI};
+    use crate::orbit::force::radiation::ShadowModel;
+    use crate::orbit::{kepler, Anomaly, KeplerianElements, TwoBody};
+
+    fn start() -> DateTime {
+        DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap()
+    }
+
+    fn elements() -> KeplerianElements {
+        KeplerianElements {
+            epoch: start(),
+            gravitational_parameter: EARTH_GRAVITATIONAL_PARAMETER,
+            semi_major_axis: 8_000e3,
+            eccentricity: 0.1,
+            inclination: 50f64.to_radians(),
+            ascending_node: 1.0,
+            argument_of_periapsis: 30f64.to_radians(),
+            anomaly: Anomaly::Mean(0.3),
+        }
+    }
+
+    /// Returns the instants at which the mean anomaly reaches the given value.
+    fn passages(elements: &KeplerianElements, mean_anomaly: f64, end: &DateTime) -> Vec<DateTime> {
+        let n = elements.mean_motion();
+        let period = TWO_PI / n;
+        let first = (mean_anomaly - elements.mean_anomaly()).rem_euclid(TWO_PI) / n;
+        (0..)
+            .map(|k| start() + TimeDelta::seconds(first + k as f64 * period))
+            .take_while(|dt| dt < end)
+            .collect()
+    }
+
+    fn assert_events(events: &[Event], expected: &[DateTime], direction: Direction) {
+        let events: Vec<_> = events.iter().filter(|e| e.direction == direction).collect();
+        assert_eq!(events.len(), expected.len());
+        for (event, expected) in events.iter().zip(expected) {
+            assert!((event.dt - *expected).total_seconds().abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn orbit_events_test() {
+        let elements = elements();
+        let propagator = TwoBody::from_elements(&elements).unwrap();
+        let end = start() + TimeDelta::hours(8.0);
+        let detector = EventDetector::new(TimeDelta::minutes(10.0));
+
+        let apsides = detector.find(&propagator, &Apsis, &start(), &end).unwrap();
+        assert_events(
+            &apsides,
+            &passages(&elements, 0.0, &end),
+            Direction::Increasing,
+        );
+        assert_events(
+            &apsides,
+            &passages(&elements, std::f64::consts::PI, &end),
+            Direction::Decreasing,
+        );
+
+        let e = elements.eccentricity;
+        let ascending = kepler::true_to_mean(-elements.argument_of_periapsis, e);
+        let descending =
+            kepler::true_to_mean(std::f64::consts::PI - elements.argument_of_periapsis, e);
+        let nodes = detector
+            .find(&propagator, &NodeCrossing, &start(), &end)
+            .unwrap();
+        assert_events(
+            &nodes,
+            &passages(&elements, ascending, &end),
+            Direction::Increasing,
+        );
+        assert_events(
+            &nodes,
+            &passages(&elements, descending, &end),
+            Direction::Decreasing,
+        );
+        assert!(nodes.windows(2).all(|pair| pair[0].dt < pair[1].dt));
+    }
+
+    #[test]
+    fn elevation_test() {
+        let elements = KeplerianElements {
+            semi_major_axis: 7_000e3,
+            eccentricity: 0.001,
+            inclination: 98f64.to_radians(),
+            ..elements()
+        };
+        let propagator = TwoBody::from_elements(&elements).unwrap();
+        let station = GroundStation::new(Observer::from_degrees(40.4314, -4.2481, 834.0));
+        let elevation = Elevation::new(station, 10f64.to_radians());
+        let end = start() + TimeDelta::days(1.0);
+        let windows = EventDetector::new(TimeDelta::minutes(1.0))
+            .windows(&propagator, &elevation, &start(), &end)
+            .unwrap();
+        assert!(windows.len() >= 2);
+        for window in &windows {
+            for dt in [window.start, window.end] {
+                let state = propagator.propagate(&dt).unwrap();
+                assert!(elevation.value(&dt, &state).unwrap().abs() < 1e-8);
+            }
+        }
+
+        // Sampling every ten seconds agrees with the windows
+        let mut dt = start();
+        while dt < end {
+            let state = propagator.propagate(&dt).unwrap();
+            let visible = elevation.value(&dt, &state).unwrap() > 0.0;
+            assert_eq!(visible, windows.contains(&dt));
+            dt = dt + TimeDelta::seconds(10.0);
+        }
+    }
+
+    /// The Sun at a fixed position relative to the central body
+    #[derive(Debug)]
+    struct FixedSun(Vector3);
+
+    impl Ephemeris for FixedSun {
+        type Error = EventError;
+
+        fn state(
+            &self,
+            _target: i32,
+            _center: i32,
+            _dt: &DateTime,
+        ) -> Result<StateVector, EventError> {
+            Ok(StateVector {
+                position: self.0,
+                velocity: Vector3::zeros(),
+            })
+        }
+    }
+
+    #[test]
+    fn eclipse_test() {
+        let sun = Vector3::new(ASTRONOMICAL_UNIT, 0.0, 0.0);
+        let elements = KeplerianElements {
+            inclination: 0.3,
+            ascending_node: 0.0,
+            ..elements()
+        };
+        let propagator = TwoBody::from_elements(&elements).unwrap();
+        let end = start() + TimeDelta::hours(8.0);
+        let span = Interval::new(start(), end).unwrap();
+        let detector = EventDetector::new(TimeDelta::minutes(2.0));
+        let outside = |boundary| {
+            detector
+                .windows(
+                    &propagator,
+                    &Eclipse::new(FixedSun(sun), 399, boundary),
+                    &start(),
+                    &end,
+                )
+                .unwrap()
+        };
+        let penumbra = outside(Shadow::Penumbra).complement(&span);
+        let umbra = outside(Shadow::Umbra).complement(&span);
+        assert!(penumbra.len() >= 2);
+        assert_eq!(umbra.len(), penumbra.len());
+        assert_eq!(umbra.intersection(&penumbra), umbra);
+        assert!(umbra.duration() < penumbra.duration());
+
+        // The lit fraction of the Sun agrees on both sides of the boundaries
+        let fraction = |dt: DateTime| {
+            let position = propagator.propagate(&dt).unwrap().position;
+            ShadowModel::Conical.fraction(&position, &sun, EARTH_EQUATORIAL_RADIUS)
+        };
+        let second = TimeDelta::seconds(1.0);
+        for (shadow, umbra) in penumbra.iter().zip(&umbra) {
+            assert_eq!(fraction(shadow.start - second), 1.0);
+            assert!(fraction(shadow.start + second) < 1.0);
+            assert!(fraction(umbra.start - second) > 0.0);
+            assert_eq!(fraction(umbra.start + second), 0.0);
+            assert_eq!(fraction(umbra.end - second), 0.0);
+            assert!(fraction(umbra.end + second) > 0.0);
+            assert!(fraction(shadow.end - second) < 1.0);
+            assert_eq!(fraction(shadow.end + second), 1.0);
+        }
+    }
+}
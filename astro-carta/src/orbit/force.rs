@@ -0,0 +1,161 @@
+use std::fmt;
+
+use crate::astrometry::{precession, sidereal};
+use crate::datetime::DateTime;
+use crate::ephemeris::pck::TextPck;
+use crate::ephemeris::StateVector;
+use crate::math::{Matrix3, Vector3};
+
+pub mod drag;
+pub mod gravity;
+pub mod radiation;
+pub mod third_body;
+
+/// Error raised when a force model cannot be evaluated
+#[derive(Debug, Clone, PartialEq)]
+pub enum ForceError {
+    /// The object lies at a singularity of the model, such as the center of attraction
+    Singular,
+    /// A source of data of the model, such as an ephemeris, failed at the given instant
+    Data(String),
+}
+
+impl fmt::Display for ForceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ForceError::Singular => write!(f, "force model evaluated at a singularity"),
+            ForceError::Data(message) => write!(f, "force model data unavailable: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ForceError {}
+
+/// A model of one of the accelerations acting on an orbiting object
+///
+/// States are given relative to the central body in an inertial frame, with position in meters
+/// and velocity in m/s. Models are summed by the
+/// [`NumericalPropagator`](super::numerical::NumericalPropagator).
+pub trait ForceModel: fmt::Debug {
+    /// Computes the acceleration in m/s² of an object in the given state at the given instant.
+    fn acceleration(&self, dt: &DateTime, state: &StateVector) -> Result<Vector3, ForceError>;
+
+    /// Computes the partial derivatives of the acceleration with respect to the position, in
+    /// 1/s², if the model provides them.
+    fn gradient(
+        &self,
+        _dt: &DateTime,
+        _state: &StateVector,
+    ) -> Result<Option<Matrix3>, ForceError> {
+        Ok(None)
+    }
+}
+
+/// Orientation of the body-fixed axes of a central body
+pub trait BodyRotation: fmt::Debug {
+    /// Returns the rotation from the inertial axes of the states to the body-fixed axes at the
+    /// given instant.
+    fn rotation_to_body_fixed(&self, dt: &DateTime) -> Result<Matrix3, ForceError>;
+}
+
+/// A constant orientation, such as the identity for a body whose axes are inertial
+impl BodyRotation for Matrix3 {
+    fn rotation_to_body_fixed(&self, _dt: &DateTime) -> Result<Matrix3, ForceError> {
+        Ok(*self)
+    }
+}
+
+/// Rotation of the Earth from the GCRS to the ITRS, neglecting polar motion
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EarthRotation {
+    /// UT1 - UTC in seconds
+    pub dut1: f64,
+}
+
+impl BodyRotation for EarthRotation {
+    fn rotation_to_body_fixed(&self, dt: &DateTime) -> Result<Matrix3, ForceError> {
+        Ok(
+            Matrix3::rot_z(sidereal::greenwich_apparent_sidereal_time(dt, self.dut1))
+                * precession::bias_precession_nutation_matrix(dt),
+        )
+    }
+}
+
+/// Rotation of a body from J2000 axes to its body-fixed axes, given by the constants of a
+/// text PCK
+#[derive(Debug, Clone, PartialEq)]
+pub struct PckRotation {
+    /// Kernel holding the pole and prime meridian constants of the body
+    pub pck: TextPck,
+    /// NAIF ID of the body
+    pub body: i32,
+}
+
+impl BodyRotation for PckRotation {
+    fn rotation_to_body_fixed(&self, dt: &DateTime) -> Result<Matrix3, ForceError> {
+        self.pck
+            .rotation_to_body_fixed(self.body, dt)
+            .map_err(|error| ForceError::Data(error.to_string()))
+    }
+}
+
+/// Attraction of a central body with a spherically symmetric mass distribution
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::constants::EARTH_GRAVITATIONAL_PARAMETER;
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::force::{ForceModel, PointMass};
+///
+/// let gravity = PointMass::new(EARTH_GRAVITATIONAL_PARAMETER);
+/// let state = StateVector {
+///     position: Vector3::new(7e6, 0.0, 0.0),
+///     velocity: Vector3::zeros(),
+/// };
+/// let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let acceleration = gravity.acceleration(&dt, &state).unwrap();
+/// assert!((acceleration.x + 8.134).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointMass {
+    /// Gravitational parameter of the central body in m³/s²
+    pub gravitational_parameter: f64,
+}
+
+impl PointMass {
+    /// Constructs the attraction of a body of the given gravitational parameter in m³/s².
+    pub fn new(gravitational_parameter: f64) -> Self {
+        PointMass {
+            gravitational_parameter,
+        }
+    }
+}
+
+impl ForceModel for PointMass {
+    fn acceleration(&self, _dt: &DateTime, state: &StateVector) -> Result<Vector3, ForceError> {
+        let r = state.position.norm();
+        if r == 0.0 {
+            return Err(ForceError::Singular);
+        }
+        Ok(state.position * (-self.gravitational_parameter / (r * r * r)))
+    }
+
+    fn gradient(&self, _dt: &DateTime, state: &StateVector) -> Result<Option<Matrix3>, ForceError> {
+        let r = state.position.norm();
+        if r == 0.0 {
+            return Err(ForceError::Singular);
+        }
+        let u = state.position / r;
+        let scale = self.gravitational_parameter / (r * r * r);
+        let mut gradient = Matrix3::identity();
+        for i in 0..3 {
+            for j in 0..3 {
+                gradient.elements[i][j] = scale * (3.0 * u[i] * u[j] - gradient.elements[i][j]);
+            }
+        }
+        Ok(Some(gradient))
+    }
+}
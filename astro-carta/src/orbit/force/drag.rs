@@ -0,0 +1,255 @@
+use std::fmt;
+
+use super::{BodyRotation, EarthRotation, ForceError, ForceModel};
+use crate::astrometry::sidereal;
+use crate::constants::EARTH_ANGULAR_VELOCITY;
+use crate::datetime::DateTime;
+use crate::ephemeris::{sun, StateVector};
+use crate::geodesy::{Ellipsoid, Geodetic};
+use crate::math::Vector3;
+
+pub mod exponential;
+pub mod harris_priester;
+pub mod jacchia;
+pub mod nrlmsise;
+pub mod space_weather;
+
+pub use exponential::Exponential;
+pub use harris_priester::HarrisPriester;
+pub use jacchia::Jacchia71;
+pub use nrlmsise::Nrlmsise00;
+pub use space_weather::SpaceWeather;
+
+/// A model of the mass density of the atmosphere of the Earth
+pub trait Atmosphere: fmt::Debug {
+    /// Computes the mass density in kg/m³ at the given instant and Earth-fixed position in
+    /// meters.
+    ///
+    /// `sun` is the Earth-fixed unit vector towards the Sun, which locates the diurnal bulge.
+    fn density(&self, dt: &DateTime, position: &Vector3, sun: &Vector3) -> Result<f64, ForceError>;
+}
+
+/// A flat plate of a satellite, exposed to the flow on the side of its normal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plate {
+    /// Area in m²
+    pub area: f64,
+    /// Outward unit normal in the local orbital frame, whose axes are radial, along-track and
+    /// normal to the orbital plane
+    pub normal: Vector3,
+    pub drag_coefficient: f64,
+}
+
+/// Shape of a satellite exposed to drag
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    /// A sphere, or an object whose cross-section does not depend on the direction of the flow
+    Cannonball {
+        /// Cross-sectional area in m²
+        area: f64,
+        drag_coefficient: f64,
+    },
+    /// Flat plates with a fixed attitude in the local orbital frame, such as the faces and
+    /// panels of a nadir-pointing satellite; a panel exposed on both sides is two plates.
+    Plates(Vec<Plate>),
+}
+
+impl Shape {
+    /// Returns the product of the drag coefficient and the area exposed to the flow in m², for
+    /// a state relative to the Earth in an inertial frame and a velocity relative to the
+    /// atmosphere.
+    pub fn drag_area(&self, state: &StateVector, relative_velocity: &Vector3) -> f64 {
+        match self {
+            Shape::Cannonball {
+                area,
+                drag_coefficient,
+            } => area * drag_coefficient,
+            Shape::Plates(plates) => {
+                let speed = relative_velocity.norm();
+                if speed == 0.0 {
+                    return 0.0;
+                }
+                let radial = state.position / state.position.norm();
+                let normal = state.position.cross(&state.velocity);
+                let normal = normal / normal.norm();
+                let along_track = normal.cross(&radial);
+                plates
+                    .iter()
+                    .map(|plate| {
+                        let direction = radial * plate.normal.x
+                            + along_track * plate.normal.y
+                            + normal * plate.normal.z;
+                        let cosine = direction.dot(relative_velocity) / speed;
+                        plate.area * plate.drag_coefficient * cosine.max(0.0)
+                    })
+                    .sum()
+            }
+        }
+    }
+}
+
+/// Atmospheric drag on a satellite orbiting the Earth
+///
+/// The atmosphere rotates with the Earth, and the acceleration is `-½ ρ (C_D A / m) |v| v`
+/// with the velocity `v` relative to the atmosphere.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::force::drag::{Drag, Exponential, Shape};
+/// use astro_carta::orbit::force::ForceModel;
+///
+/// let shape = Shape::Cannonball {
+///     area: 1.0,
+///     drag_coefficient: 2.2,
+/// };
+/// let drag = Drag::new(Exponential::default(), shape, 100.0);
+/// let state = StateVector {
+///     position: Vector3::new(6_778_137.0, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, 7_668.0, 0.0),
+/// };
+/// let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let acceleration = drag.acceleration(&dt, &state).unwrap();
+/// assert!(acceleration.y < 0.0 && acceleration.y > -1e-5);
+/// ```
+#[derive(Debug)]
+pub struct Drag {
+    atmosphere: Box<dyn Atmosphere>,
+    pub shape: Shape,
+    /// Mass of the satellite in kilograms
+    pub mass: f64,
+    /// Rotation of the Earth, which carries the atmosphere
+    pub rotation: EarthRotation,
+}
+
+impl Drag {
+    pub fn new(atmosphere: impl Atmosphere + 'static, shape: Shape, mass: f64) -> Self {
+        Drag {
+            atmosphere: Box::new(atmosphere),
+            shape,
+            mass,
+            rotation: EarthRotation::default(),
+        }
+    }
+
+    /// Sets the rotation of the Earth, to account for UT1 - UTC.
+    pub fn with_rotation(mut self, rotation: EarthRotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn atmosphere(&self) -> &dyn Atmosphere {
+        self.atmosphere.as_ref()
+    }
+}
+
+impl ForceModel for Drag {
+    fn acceleration(&self, dt: &DateTime, state: &StateVector) -> Result<Vector3, ForceError> {
+        if state.position.norm() == 0.0 {
+            return Err(ForceError::Singular);
+        }
+        let position = self.rotation.rotation_to_body_fixed(dt)? * state.position;
+        let density =
+            self.atmosphere
+                .density(dt, &position, &sun_direction(dt, self.rotation.dut1))?;
+        let rotation = Vector3::new(0.0, 0.0, EARTH_ANGULAR_VELOCITY);
+        let relative = state.velocity - rotation.cross(&state.position);
+        let area = self.shape.drag_area(state, &relative);
+        Ok(relative * (-0.5 * density * area / self.mass * relative.norm()))
+    }
+}
+
+/// Returns the Earth-fixed unit vector towards the Sun, from its low-precision position.
+fn sun_direction(dt: &DateTime, dut1: f64) -> Vector3 {
+    let equatorial = sun::position(dt).equatorial;
+    let hour_angle =
+        equatorial.right_ascension - sidereal::greenwich_apparent_sidereal_time(dt, dut1);
+    let (sin_declination, cos_declination) = equatorial.declination.sin_cos();
+    Vector3::new(
+        cos_declination * hour_angle.cos(),
+        cos_declination * hour_angle.sin(),
+        sin_declination,
+    )
+}
+
+/// Returns the geodetic coordinates of an Earth-fixed position on the WGS84 ellipsoid.
+fn geodetic(position: &Vector3) -> Geodetic {
+    Ellipsoid::WGS84.cartesian_to_geodetic(position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> StateVector {
+        StateVector {
+            position: Vector3::new(6_778_137.0, 0.0, 0.0),
+            velocity: Vector3::new(0.0, 6_000.0, 4_000.0),
+        }
+    }
+
+    #[test]
+    fn cannonball_test() {
+        let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+        let shape = Shape::Cannonball {
+            area: 2.0,
+            drag_coefficient: 2.2,
+        };
+        let drag = Drag::new(Exponential::default(), shape, 400.0);
+        let state = state();
+        let acceleration = drag.acceleration(&dt, &state).unwrap();
+
+        let relative =
+            state.velocity - Vector3::new(0.0, 0.0, EARTH_ANGULAR_VELOCITY).cross(&state.position);
+        let position = drag.rotation.rotation_to_body_fixed(&dt).unwrap() * state.position;
+        let density = Exponential::default()
+            .density(&dt, &position, &Vector3::zeros())
+            .unwrap();
+        let expected = relative * (-0.5 * density * 4.4 / 400.0 * relative.norm());
+        assert!((acceleration - expected).norm() < 1e-12 * expected.norm());
+
+        let zero = StateVector {
+            position: Vector3::zeros(),
+            velocity: Vector3::zeros(),
+        };
+        assert_eq!(drag.acceleration(&dt, &zero), Err(ForceError::Singular));
+    }
+
+    #[test]
+    fn plates_test() {
+        let state = state();
+        let relative = state.velocity;
+        let plate = |normal: Vector3| Plate {
+            area: 1.5,
+            normal,
+            drag_coefficient: 2.0,
+        };
+        let front = Shape::Plates(vec![plate(Vector3::new(0.0, 1.0, 0.0))]);
+        assert!((front.drag_area(&state, &relative) - 3.0).abs() < 1e-12);
+
+        // The radial faces are edge-on, and the rear face is in the wake
+        let others = Shape::Plates(vec![
+            plate(Vector3::new(1.0, 0.0, 0.0)),
+            plate(Vector3::new(-1.0, 0.0, 0.0)),
+            plate(Vector3::new(0.0, -1.0, 0.0)),
+        ]);
+        assert!(others.drag_area(&state, &relative).abs() < 1e-12);
+
+        let tilted = Shape::Plates(vec![plate(Vector3::new(0.0, 0.6, 0.8))]);
+        let cosine = Vector3::new(0.0, 0.6, 0.8).dot(&Vector3::new(0.0, 1.0, 0.0));
+        let area = tilted.drag_area(&state, &Vector3::new(0.0, 6_000.0, 4_000.0));
+        assert!((area - 3.0 * cosine).abs() < 1e-12);
+    }
+
+    #[test]
+    fn sun_direction_test() {
+        // Near the March equinox at noon at Greenwich the Sun lies close to the x-axis
+        let dt = DateTime::gregorian(2024, 3, 20, 12, 7, 0.0).unwrap();
+        let sun = sun_direction(&dt, 0.0);
+        assert!((sun.norm() - 1.0).abs() < 1e-12);
+        assert!(sun.x > 0.999, "{sun:?}");
+    }
+}
@@ -0,0 +1,156 @@
+use super::{geodetic, Atmosphere};
+use crate::datetime::DateTime;
+use crate::math::Vector3;
+use crate::orbit::force::ForceError;
+
+/// A layer of an exponential atmosphere
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExponentialLayer {
+    /// Altitude of the base of the layer in meters
+    pub base_altitude: f64,
+    /// Density at the base of the layer in kg/m³
+    pub base_density: f64,
+    /// Scale height in meters
+    pub scale_height: f64,
+}
+
+/// Base altitude in km, density in kg/m³ and scale height in km of the layers of the
+/// exponential model of Vallado (Fundamentals of Astrodynamics and Applications, Table 8-4)
+const VALLADO: [(f64, f64, f64); 28] = [
+    (0.0, 1.225, 7.249),
+    (25.0, 3.899e-2, 6.349),
+    (30.0, 1.774e-2, 6.682),
+    (40.0, 3.972e-3, 7.554),
+    (50.0, 1.057e-3, 8.382),
+    (60.0, 3.206e-4, 7.714),
+    (70.0, 8.770e-5, 6.549),
+    (80.0, 1.905e-5, 5.799),
+    (90.0, 3.396e-6, 5.382),
+    (100.0, 5.297e-7, 5.877),
+    (110.0, 9.661e-8, 7.263),
+    (120.0, 2.438e-8, 9.473),
+    (130.0, 8.484e-9, 12.636),
+    (140.0, 3.845e-9, 16.149),
+    (150.0, 2.070e-9, 22.523),
+    (180.0, 5.464e-10, 29.740),
+    (200.0, 2.789e-10, 37.105),
+    (250.0, 7.248e-11, 45.546),
+    (300.0, 2.418e-11, 53.628),
+    (350.0, 9.518e-12, 53.298),
+    (400.0, 3.725e-12, 58.515),
+    (450.0, 1.585e-12, 60.828),
+    (500.0, 6.967e-13, 63.822),
+    (600.0, 1.454e-13, 71.835),
+    (700.0, 3.614e-14, 88.667),
+    (800.0, 1.170e-14, 124.64),
+    (900.0, 5.245e-15, 181.05),
+    (1000.0, 3.019e-15, 268.00),
+];
+
+/// A static atmosphere whose density decreases exponentially with the altitude above the WGS84
+/// ellipsoid in each of a number of layers
+///
+/// The default layers are those of Vallado, from the ground to 1000 km; the last layer extends
+/// above its base, and the density below the first layer is that of the first layer.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::force::drag::{Atmosphere, Exponential};
+///
+/// let atmosphere = Exponential::default();
+/// let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let position = Vector3::new(6_378_137.0 + 400e3, 0.0, 0.0);
+/// let density = atmosphere.density(&dt, &position, &Vector3::zeros()).unwrap();
+/// assert!((density - 3.725e-12).abs() < 1e-15);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exponential {
+    layers: Vec<ExponentialLayer>,
+}
+
+impl Exponential {
+    /// Constructs an atmosphere from layers, which are sorted by base altitude.
+    pub fn new(mut layers: Vec<ExponentialLayer>) -> Self {
+        layers.sort_by(|a, b| a.base_altitude.total_cmp(&b.base_altitude));
+        Exponential { layers }
+    }
+
+    pub fn layers(&self) -> &[ExponentialLayer] {
+        &self.layers
+    }
+
+    /// Computes the density in kg/m³ at an altitude in meters.
+    pub fn density_at_altitude(&self, altitude: f64) -> f64 {
+        let index = self
+            .layers
+            .partition_point(|layer| layer.base_altitude <= altitude);
+        let Some(layer) = self.layers.get(index.saturating_sub(1)) else {
+            return 0.0;
+        };
+        let height = (altitude - layer.base_altitude).max(0.0);
+        layer.base_density * (-height / layer.scale_height).exp()
+    }
+}
+
+impl Default for Exponential {
+    fn default() -> Self {
+        Exponential::new(
+            VALLADO
+                .iter()
+                .map(|&(altitude, density, scale_height)| ExponentialLayer {
+                    base_altitude: altitude * 1e3,
+                    base_density: density,
+                    scale_height: scale_height * 1e3,
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Atmosphere for Exponential {
+    fn density(
+        &self,
+        _dt: &DateTime,
+        position: &Vector3,
+        _sun: &Vector3,
+    ) -> Result<f64, ForceError> {
+        Ok(self.density_at_altitude(geodetic(position).height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn density_test() {
+        let atmosphere = Exponential::default();
+        assert_eq!(atmosphere.layers().len(), 28);
+        assert_eq!(atmosphere.density_at_altitude(-100.0), 1.225);
+        assert_eq!(atmosphere.density_at_altitude(400e3), 3.725e-12);
+        let density = atmosphere.density_at_altitude(420e3);
+        assert!((density - 3.725e-12 * (-20.0f64 / 58.515).exp()).abs() < 1e-25);
+
+        // The layers are nearly continuous at their boundaries
+        for pair in atmosphere.layers().windows(2) {
+            let below = atmosphere.density_at_altitude(pair[1].base_altitude - 1e-6);
+            let ratio = below / pair[1].base_density;
+            assert!(
+                (ratio - 1.0).abs() < 0.25,
+                "{}: {ratio}",
+                pair[1].base_altitude
+            );
+        }
+
+        let single = Exponential::new(vec![ExponentialLayer {
+            base_altitude: 100e3,
+            base_density: 1e-6,
+            scale_height: 10e3,
+        }]);
+        assert_eq!(single.density_at_altitude(50e3), 1e-6);
+        assert!((single.density_at_altitude(110e3) - 1e-6 / std::f64::consts::E).abs() < 1e-20);
+    }
+}
@@ -0,0 +1,197 @@
+use super::{geodetic, Atmosphere};
+use crate::datetime::DateTime;
+use crate::math::Vector3;
+use crate::orbit::force::ForceError;
+
+/// Altitude in km with the minimum and maximum densities in kg/m³ of the Harris-Priester
+/// model for a mean solar flux (Montenbruck and Gill, Satellite Orbits, Table 3.8)
+const DENSITIES: [(f64, f64, f64); 50] = [
+    (100.0, 4.974e-07, 4.974e-07),
+    (120.0, 2.490e-08, 2.490e-08),
+    (130.0, 8.377e-09, 8.710e-09),
+    (140.0, 3.899e-09, 4.059e-09),
+    (150.0, 2.122e-09, 2.215e-09),
+    (160.0, 1.263e-09, 1.344e-09),
+    (170.0, 8.008e-10, 8.758e-10),
+    (180.0, 5.283e-10, 6.010e-10),
+    (190.0, 3.617e-10, 4.297e-10),
+    (200.0, 2.557e-10, 3.162e-10),
+    (210.0, 1.839e-10, 2.396e-10),
+    (220.0, 1.341e-10, 1.853e-10),
+    (230.0, 9.949e-11, 1.455e-10),
+    (240.0, 7.488e-11, 1.157e-10),
+    (250.0, 5.709e-11, 9.308e-11),
+    (260.0, 4.403e-11, 7.555e-11),
+    (270.0, 3.430e-11, 6.182e-11),
+    (280.0, 2.697e-11, 5.095e-11),
+    (290.0, 2.139e-11, 4.226e-11),
+    (300.0, 1.708e-11, 3.526e-11),
+    (320.0, 1.099e-11, 2.511e-11),
+    (340.0, 7.214e-12, 1.819e-11),
+    (360.0, 4.824e-12, 1.337e-11),
+    (380.0, 3.274e-12, 9.955e-12),
+    (400.0, 2.249e-12, 7.492e-12),
+    (420.0, 1.558e-12, 5.684e-12),
+    (440.0, 1.091e-12, 4.355e-12),
+    (460.0, 7.701e-13, 3.362e-12),
+    (480.0, 5.474e-13, 2.612e-12),
+    (500.0, 3.916e-13, 2.042e-12),
+    (520.0, 2.819e-13, 1.605e-12),
+    (540.0, 2.042e-13, 1.267e-12),
+    (560.0, 1.488e-13, 1.005e-12),
+    (580.0, 1.092e-13, 7.997e-13),
+    (600.0, 8.070e-14, 6.390e-13),
+    (620.0, 6.012e-14, 5.123e-13),
+    (640.0, 4.519e-14, 4.121e-13),
+    (660.0, 3.430e-14, 3.325e-13),
+    (680.0, 2.632e-14, 2.691e-13),
+    (700.0, 2.043e-14, 2.185e-13),
+    (720.0, 1.607e-14, 1.779e-13),
+    (740.0, 1.281e-14, 1.452e-13),
+    (760.0, 1.036e-14, 1.190e-13),
+    (780.0, 8.496e-15, 9.776e-14),
+    (800.0, 7.069e-15, 8.059e-14),
+    (840.0, 4.680e-15, 5.741e-14),
+    (880.0, 3.200e-15, 4.210e-14),
+    (920.0, 2.210e-15, 3.130e-14),
+    (960.0, 1.560e-15, 2.360e-14),
+    (1000.0, 1.150e-15, 1.810e-14),
+];
+
+/// Lag of the apex of the diurnal bulge behind the subsolar point in radians (30°)
+const LAG: f64 = std::f64::consts::PI / 6.0;
+
+/// The Harris-Priester atmosphere, with a diurnal bulge whose apex lags the Sun by 30° in
+/// longitude
+///
+/// Between the tabulated minimum and maximum densities, which are interpolated exponentially
+/// in altitude, the density varies as `cosⁿ(ψ/2)` of the angle `ψ` between the position and the
+/// apex. The exponent is 2 for low-inclination orbits and 6 for polar orbits. The model spans
+/// 100 to 1000 km: the density is zero above and an error below.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::force::drag::{Atmosphere, HarrisPriester};
+///
+/// let atmosphere = HarrisPriester::default();
+/// let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let position = Vector3::new(6_378_137.0 + 400e3, 0.0, 0.0);
+/// let day = atmosphere.density(&dt, &position, &Vector3::new(1.0, 0.0, 0.0)).unwrap();
+/// let night = atmosphere.density(&dt, &position, &Vector3::new(-1.0, 0.0, 0.0)).unwrap();
+/// assert!(day > 2.5 * night);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HarrisPriester {
+    /// Exponent of the cosine of the half angle from the apex of the bulge
+    pub exponent: f64,
+}
+
+impl HarrisPriester {
+    pub fn new(exponent: f64) -> Self {
+        HarrisPriester { exponent }
+    }
+
+    /// Computes the density in kg/m³ at an altitude in meters, for a cosine of the angle from
+    /// the apex of the bulge.
+    fn interpolate(&self, altitude: f64, cosine: f64) -> Option<f64> {
+        let altitude = altitude / 1e3;
+        let (first, last) = (DENSITIES[0], DENSITIES[DENSITIES.len() - 1]);
+        if altitude < first.0 {
+            return None;
+        }
+        if altitude > last.0 {
+            return Some(0.0);
+        }
+        let index = DENSITIES
+            .partition_point(|row| row.0 <= altitude)
+            .clamp(1, DENSITIES.len() - 1);
+        let (lower, upper) = (DENSITIES[index - 1], DENSITIES[index]);
+        let fraction = (altitude - lower.0) / (upper.0 - lower.0);
+        let minimum = lower.1 * (upper.1 / lower.1).powf(fraction);
+        let maximum = lower.2 * (upper.2 / lower.2).powf(fraction);
+        let bulge = (0.5 + 0.5 * cosine).max(0.0).powf(0.5 * self.exponent);
+        Some(minimum + (maximum - minimum) * bulge)
+    }
+}
+
+impl Default for HarrisPriester {
+    /// Returns the model with an exponent of 4, for orbits of intermediate inclination.
+    fn default() -> Self {
+        HarrisPriester::new(4.0)
+    }
+}
+
+impl Atmosphere for HarrisPriester {
+    fn density(
+        &self,
+        _dt: &DateTime,
+        position: &Vector3,
+        sun: &Vector3,
+    ) -> Result<f64, ForceError> {
+        let (sin_lag, cos_lag) = LAG.sin_cos();
+        let apex = Vector3::new(
+            cos_lag * sun.x - sin_lag * sun.y,
+            sin_lag * sun.x + cos_lag * sun.y,
+            sun.z,
+        );
+        let cosine = apex.dot(position) / position.norm();
+        let altitude = geodetic(position).height;
+        self.interpolate(altitude, cosine)
+            .ok_or_else(|| ForceError::Data(format!("altitude {altitude:.0} m below 100 km")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolation_test() {
+        let atmosphere = HarrisPriester::default();
+        for &(altitude, minimum, maximum) in &DENSITIES {
+            let (low, high) = (
+                atmosphere.interpolate(altitude * 1e3, -1.0).unwrap(),
+                atmosphere.interpolate(altitude * 1e3, 1.0).unwrap(),
+            );
+            assert!((low - minimum).abs() < 1e-15 * minimum);
+            assert!((high - maximum).abs() < 1e-15 * maximum);
+        }
+        // Exponential interpolation between 400 and 420 km
+        let density = atmosphere.interpolate(410e3, -1.0).unwrap();
+        assert!((density - (2.249e-12 * 1.558e-12f64).sqrt()).abs() < 1e-25);
+        let half = atmosphere.interpolate(400e3, 0.0).unwrap();
+        assert!((half - (2.249e-12 + (7.492e-12 - 2.249e-12) * 0.25)).abs() < 1e-24);
+
+        assert_eq!(atmosphere.interpolate(1001e3, 1.0), Some(0.0));
+        assert_eq!(atmosphere.interpolate(99e3, 1.0), None);
+    }
+
+    #[test]
+    fn bulge_test() {
+        let atmosphere = HarrisPriester::new(2.0);
+        let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+        let sun = Vector3::new(1.0, 0.0, 0.0);
+        let radius = 6_378_137.0 + 500e3;
+        // The apex lies 30° east of the subsolar point
+        let densities: Vec<_> = (0..12)
+            .map(|step| {
+                let longitude = (step as f64 * 30.0).to_radians();
+                let position = Vector3::new(longitude.cos(), longitude.sin(), 0.0) * radius;
+                atmosphere.density(&dt, &position, &sun).unwrap()
+            })
+            .collect();
+        let apex = densities
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        assert_eq!(apex.0, 1);
+        assert!((apex.1 - 2.042e-12).abs() < 1e-24);
+
+        let error = atmosphere.density(&dt, &Vector3::new(6_400e3, 0.0, 0.0), &sun);
+        assert!(matches!(error, Err(ForceError::Data(_))));
+    }
+}
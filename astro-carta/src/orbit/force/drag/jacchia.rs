@@ -0,0 +1,422 @@
+use std::f64::consts::{FRAC_PI_4, PI, TAU};
+
+use super::space_weather::ActivityIndices;
+use super::{geodetic, Atmosphere, SpaceWeather};
+use crate::datetime::{DateTime, TimeScale};
+use crate::math::{self, Vector3};
+use crate::orbit::force::ForceError;
+
+/// Universal gas constant in J/(mol K)
+const GAS_CONSTANT: f64 = 8.31432;
+/// Avogadro's number in 1/mol
+const AVOGADRO: f64 = 6.022_57e23;
+/// Mean molecular mass of air at sea level in g/mol
+const SEA_LEVEL_MASS: f64 = 28.960;
+/// Altitude of the lower boundary in km, with its temperature in K and density in kg/m³
+const LOWER_BOUNDARY: (f64, f64, f64) = (90.0, 183.0, 3.46e-6);
+/// Altitude of the inflection point of the temperature profile in km
+const INFLECTION: f64 = 125.0;
+/// Coefficients of the mean molecular mass in g/mol between 90 and 100 km, in powers of the
+/// altitude relative to 100 km
+const MEAN_MASS: [f64; 7] = [
+    28.15204, -0.085586, 1.284e-4, -1.0056e-5, -1.021e-5, 1.5044e-6, 9.9826e-8,
+];
+
+/// Constituents in diffusive equilibrium above 100 km
+#[derive(Clone, Copy)]
+enum Constituent {
+    Nitrogen,
+    Argon,
+    Helium,
+    Oxygen,
+    AtomicOxygen,
+}
+
+impl Constituent {
+    const ALL: [Constituent; 5] = [
+        Constituent::Nitrogen,
+        Constituent::Argon,
+        Constituent::Helium,
+        Constituent::Oxygen,
+        Constituent::AtomicOxygen,
+    ];
+
+    /// Returns the molecular mass in g/mol.
+    fn mass(self) -> f64 {
+        match self {
+            Constituent::Nitrogen => 28.0134,
+            Constituent::Argon => 39.948,
+            Constituent::Helium => 4.0026,
+            Constituent::Oxygen => 31.9988,
+            Constituent::AtomicOxygen => 15.9994,
+        }
+    }
+
+    /// Returns the thermal diffusion coefficient.
+    fn thermal_diffusion(self) -> f64 {
+        match self {
+            Constituent::Helium => -0.38,
+            _ => 0.0,
+        }
+    }
+
+    /// Returns the number density in 1/m³ at 100 km from the total number density and the
+    /// mean molecular mass, given the fractions by volume at sea level and the dissociation of
+    /// oxygen below 100 km.
+    fn number_density(self, total: f64, mean_mass: f64) -> f64 {
+        let ratio = mean_mass / SEA_LEVEL_MASS;
+        match self {
+            Constituent::Nitrogen => 0.78110 * ratio * total,
+            Constituent::Argon => 9.343e-3 * ratio * total,
+            Constituent::Helium => 1.289e-5 * ratio * total,
+            Constituent::Oxygen => (ratio * (1.0 + 0.20955) - 1.0) * total,
+            Constituent::AtomicOxygen => 2.0 * (1.0 - ratio) * total,
+        }
+    }
+}
+
+/// Mass of atomic hydrogen in g/mol
+const HYDROGEN_MASS: f64 = 1.00797;
+
+/// The Jacchia 1971 atmosphere
+///
+/// The exospheric temperature follows the solar flux, the diurnal bulge and the geomagnetic
+/// activity; the density is then integrated from 90 km, in mixing up to 100 km and in diffusive
+/// equilibrium of N₂, O₂, O, Ar, He and, above 500 km, H. The semiannual and
+/// seasonal-latitudinal variations of the density and of helium are included. Below 200 km the
+/// geomagnetic effect is the smaller heating and the density increase of Jacchia's model, so
+/// that the density is discontinuous at 200 km during storms. The model is not defined below
+/// 90 km.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::force::drag::space_weather::ActivityIndices;
+/// use astro_carta::orbit::force::drag::{Atmosphere, Jacchia71, SpaceWeather};
+///
+/// let quiet = Jacchia71::new(SpaceWeather::constant(ActivityIndices::constant(70.0, 70.0, 4.0)));
+/// let active = Jacchia71::new(SpaceWeather::constant(ActivityIndices::constant(250.0, 250.0, 4.0)));
+/// let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let position = Vector3::new(6_378_137.0 + 500e3, 0.0, 0.0);
+/// let sun = Vector3::new(0.0, 1.0, 0.0);
+/// let ratio = active.density(&dt, &position, &sun).unwrap() / quiet.density(&dt, &position, &sun).unwrap();
+/// assert!(ratio > 10.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Jacchia71 {
+    pub space_weather: SpaceWeather,
+}
+
+impl Jacchia71 {
+    pub fn new(space_weather: SpaceWeather) -> Self {
+        Jacchia71 { space_weather }
+    }
+
+    /// Computes the exospheric temperature in K without the geomagnetic effect, for the given
+    /// indices, geodetic latitude, declination of the Sun and hour angle of the Sun in radians.
+    pub fn exospheric_temperature(
+        indices: &ActivityIndices,
+        latitude: f64,
+        declination: f64,
+        hour_angle: f64,
+    ) -> f64 {
+        let nighttime_minimum =
+            379.0 + 3.24 * indices.f107_average + 1.3 * (indices.f107 - indices.f107_average);
+        let theta = (0.5 * (latitude + declination)).abs();
+        let eta = (0.5 * (latitude - declination)).abs();
+        let tau = math::normalize_angle_signed(
+            hour_angle - 37f64.to_radians()
+                + 6f64.to_radians() * (hour_angle + 43f64.to_radians()).sin(),
+        );
+        let (r, m, n) = (0.3, 2.2, 3.0);
+        let sin_theta = theta.sin().powf(m);
+        let local = nighttime_minimum * (1.0 + r * sin_theta);
+        local
+            * (1.0
+                + r * (eta.cos().powf(m) - sin_theta) / (1.0 + r * sin_theta)
+                    * (0.5 * tau).cos().powf(n))
+    }
+
+    /// Computes the density in kg/m³ of the static model at an altitude in km above 90 km, for
+    /// an exospheric temperature in K.
+    pub fn static_density(altitude: f64, exospheric_temperature: f64) -> f64 {
+        Profile::new(exospheric_temperature).density(altitude, 1.0)
+    }
+}
+
+impl Atmosphere for Jacchia71 {
+    fn density(&self, dt: &DateTime, position: &Vector3, sun: &Vector3) -> Result<f64, ForceError> {
+        let coordinates = geodetic(position);
+        let altitude = coordinates.height / 1e3;
+        if altitude < LOWER_BOUNDARY.0 {
+            return Err(ForceError::Data(format!(
+                "altitude {altitude:.1} km below the 90 km of Jacchia's model"
+            )));
+        }
+        let indices = self
+            .space_weather
+            .indices(dt)
+            .ok_or_else(|| ForceError::Data("space weather indices unavailable".to_string()))?;
+
+        let latitude = coordinates.latitude;
+        let declination = sun.z.clamp(-1.0, 1.0).asin();
+        let hour_angle = coordinates.longitude - sun.y.atan2(sun.x);
+        let kp = indices.kp;
+        let (heating, geomagnetic) = if altitude >= 200.0 {
+            (28.0 * kp + 0.03 * kp.exp(), 0.0)
+        } else {
+            (14.0 * kp + 0.02 * kp.exp(), 0.012 * kp + 1.2e-5 * kp.exp())
+        };
+        let temperature =
+            Jacchia71::exospheric_temperature(&indices, latitude, declination, hour_angle)
+                + heating;
+
+        // Years since 1958 January 1
+        let years = (dt.julian_date(TimeScale::UTC) - 2_436_204.5) / 365.2422;
+        let semiannual = {
+            let f = (5.876e-7 * altitude.powf(2.331) + 0.06328) * (-0.002868 * altitude).exp();
+            let tau =
+                years + 0.09544 * ((0.5 + 0.5 * (TAU * years + 6.035).sin()).powf(1.65) - 0.5);
+            let g = 0.02835
+                + 0.3817
+                    * (1.0 + 0.4671 * (TAU * tau + 4.137).sin())
+                    * (2.0 * TAU * tau + 4.259).sin();
+            f * g
+        };
+        let seasonal = {
+            let height = altitude - 90.0;
+            0.014
+                * height
+                * (-0.0013 * height * height).exp()
+                * latitude.signum()
+                * (TAU * years + 1.72).sin()
+                * latitude.sin().powi(2)
+        };
+        let helium = if declination == 0.0 {
+            0.0
+        } else {
+            let obliquity = 23.44f64.to_radians();
+            let angle = FRAC_PI_4 - 0.5 * latitude * declination.signum();
+            0.65 * (declination / obliquity).abs() * (angle.sin().powi(3) - 0.35355)
+        };
+
+        let density = Profile::new(temperature).density(altitude, 10f64.powf(helium));
+        Ok(density * 10f64.powf(semiannual + seasonal + geomagnetic))
+    }
+}
+
+/// Temperature profile of the static model for an exospheric temperature
+struct Profile {
+    exospheric: f64,
+    inflection: f64,
+    gradient: f64,
+    /// Coefficients of the third and fourth powers of the height below the inflection point
+    cubic: f64,
+    quartic: f64,
+}
+
+impl Profile {
+    fn new(exospheric: f64) -> Self {
+        let (z0, t0, _) = LOWER_BOUNDARY;
+        let inflection =
+            444.3807 + 0.02385 * exospheric - 392.8292 * (-0.0021357 * exospheric).exp();
+        let gradient = 1.9 * (inflection - t0) / (INFLECTION - z0);
+        // The temperature is the minimum of the mesopause at 90 km
+        let d = z0 - INFLECTION;
+        let a = t0 - inflection - gradient * d;
+        let quartic = (-gradient * d - 3.0 * a) / d.powi(4);
+        let cubic = (4.0 * a + gradient * d) / d.powi(3);
+        Profile {
+            exospheric,
+            inflection,
+            gradient,
+            cubic,
+            quartic,
+        }
+    }
+
+    /// Returns the temperature in K at an altitude in km.
+    fn temperature(&self, altitude: f64) -> f64 {
+        let height = altitude - INFLECTION;
+        if height <= 0.0 {
+            self.inflection
+                + height * (self.gradient + height * height * (self.cubic + height * self.quartic))
+        } else {
+            let amplitude = 2.0 / PI * (self.exospheric - self.inflection);
+            self.inflection
+                + amplitude
+                    * (self.gradient / amplitude * height * (1.0 + 4.5e-6 * height.powf(2.5)))
+                        .atan()
+        }
+    }
+
+    /// Integrates `f(z) g(z) / T(z)` in altitude between two altitudes in km.
+    fn integrate(&self, start: f64, end: f64, f: impl Fn(f64) -> f64) -> f64 {
+        let intervals = 2 * (((end - start) / 4.0).ceil() as usize).max(1);
+        let step = (end - start) / intervals as f64;
+        let integrand = |z: f64| f(z) * gravity(z) / self.temperature(z);
+        let interior: f64 = (1..intervals)
+            .map(|i| {
+                let weight = if i % 2 == 1 { 4.0 } else { 2.0 };
+                weight * integrand(start + i as f64 * step)
+            })
+            .sum();
+        (integrand(start) + interior + integrand(end)) * step / 3.0
+    }
+
+    /// Computes the density in kg/m³ at an altitude in km, multiplying the number density of
+    /// helium by a factor.
+    fn density(&self, altitude: f64, helium: f64) -> f64 {
+        let (z0, t0, rho0) = LOWER_BOUNDARY;
+        let mixing_top = altitude.min(100.0);
+        let mass = mean_mass(mixing_top);
+        // With the molecular mass in g/mol and the altitude in km, M g / (R T) dz needs no
+        // conversion
+        let exponent = self.integrate(z0, mixing_top, mean_mass) / GAS_CONSTANT;
+        let temperature = self.temperature(mixing_top);
+        let mixed = rho0 * t0 / temperature * mass / mean_mass(z0) * (-exponent).exp();
+        if altitude <= 100.0 {
+            return mixed;
+        }
+
+        let total = mixed * AVOGADRO / (mass * 1e-3);
+        let integral = self.integrate(100.0, altitude, |_| 1.0);
+        let ratio = temperature / self.temperature(altitude);
+        let mut mass_density = 0.0;
+        for constituent in Constituent::ALL {
+            let mut number = constituent.number_density(total, mass)
+                * ratio.powf(1.0 + constituent.thermal_diffusion())
+                * (-constituent.mass() * integral / GAS_CONSTANT).exp();
+            if let Constituent::Helium = constituent {
+                number *= helium;
+            }
+            mass_density += number * constituent.mass();
+        }
+        if altitude > 500.0 {
+            let temperature = self.temperature(500.0);
+            let log = temperature.log10();
+            let number = 1e6 * 10f64.powf(73.13 - 39.4 * log + 5.5 * log * log);
+            let integral = self.integrate(500.0, altitude, |_| 1.0);
+            mass_density += number
+                * (temperature / self.temperature(altitude)).powf(0.62)
+                * (-HYDROGEN_MASS * integral / GAS_CONSTANT).exp()
+                * HYDROGEN_MASS;
+        }
+        mass_density * 1e-3 / AVOGADRO
+    }
+}
+
+/// Returns the acceleration of gravity in m/s² at an altitude in km.
+fn gravity(altitude: f64) -> f64 {
+    9.80665 / (1.0 + altitude / 6356.766).powi(2)
+}
+
+/// Returns the mean molecular mass in g/mol between 90 and 100 km.
+fn mean_mass(altitude: f64) -> f64 {
+    let height = altitude - 100.0;
+    MEAN_MASS.iter().rev().fold(0.0, |sum, c| sum * height + c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temperature_test() {
+        for exospheric in [600.0, 1000.0, 1400.0] {
+            let profile = Profile::new(exospheric);
+            assert!((profile.temperature(90.0) - 183.0).abs() < 1e-9);
+            // Minimum at 90 km and continuous gradient at the inflection point
+            let h = 1e-4;
+            let slope = (profile.temperature(90.0 + h) - profile.temperature(90.0 - h)) / (2.0 * h);
+            assert!(slope.abs() < 1e-6);
+            let below = (profile.temperature(125.0) - profile.temperature(125.0 - h)) / h;
+            let above = (profile.temperature(125.0 + h) - profile.temperature(125.0)) / h;
+            assert!((below - profile.gradient).abs() < 1e-3);
+            assert!((above - profile.gradient).abs() < 1e-3);
+            assert!((profile.temperature(3000.0) - exospheric).abs() < 0.01 * exospheric);
+        }
+    }
+
+    #[test]
+    fn static_density_test() {
+        // Orders of magnitude of the tables of Jacchia (1971) for 1000 K
+        let cases = [
+            (90.0, 3.46e-6, 1e-12),
+            (100.0, 5.0e-7, 0.2),
+            (150.0, 2.0e-9, 0.2),
+            (200.0, 2.8e-10, 0.3),
+            (400.0, 3.0e-12, 0.5),
+            (800.0, 1.0e-14, 1.0),
+        ];
+        for (altitude, expected, tolerance) in cases {
+            let density = Jacchia71::static_density(altitude, 1000.0);
+            assert!(
+                (density / expected - 1.0).abs() <= tolerance,
+                "{altitude}: {density}"
+            );
+        }
+
+        // Continuity at the boundary of the diffusion region, and monotonic decrease
+        let below = Jacchia71::static_density(100.0 - 1e-6, 1000.0);
+        let above = Jacchia71::static_density(100.0 + 1e-6, 1000.0);
+        assert!((below / above - 1.0).abs() < 1e-4);
+        let mut previous = f64::INFINITY;
+        for altitude in (90..1500).step_by(10) {
+            let density = Jacchia71::static_density(altitude as f64, 1000.0);
+            assert!(density < previous);
+            previous = density;
+        }
+    }
+
+    #[test]
+    fn bulge_test() {
+        let indices = ActivityIndices::constant(150.0, 150.0, 0.0);
+        let minimum = 379.0 + 3.24 * 150.0;
+        // Nighttime minimum at the equator on an equinox, maximum in the afternoon
+        let temperatures: Vec<_> = (0..24)
+            .map(|hour| {
+                let hour_angle = (hour as f64 - 12.0) * 15f64.to_radians();
+                Jacchia71::exospheric_temperature(&indices, 0.0, 0.0, hour_angle)
+            })
+            .collect();
+        let (hottest, maximum) = temperatures
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        let (coldest, lowest) = temperatures
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        assert_eq!((hottest, coldest), (14, 3));
+        assert!((maximum / lowest - 1.3).abs() < 0.01, "{maximum} {lowest}");
+        assert!(*lowest >= minimum - 1e-9 && *lowest < minimum * 1.01);
+    }
+
+    #[test]
+    fn density_test() {
+        let dt = DateTime::gregorian(2024, 3, 20, 12, 0, 0.0).unwrap();
+        let quiet = Jacchia71::new(SpaceWeather::constant(ActivityIndices::constant(
+            150.0, 150.0, 0.0,
+        )));
+        let storm = Jacchia71::new(SpaceWeather::constant(ActivityIndices::constant(
+            150.0, 150.0, 300.0,
+        )));
+        let sun = Vector3::new(1.0, 0.0, 0.0);
+        let position = Vector3::new(6_378_137.0 + 400e3, 0.0, 0.0);
+        let day = quiet.density(&dt, &position, &sun).unwrap();
+        let night = quiet.density(&dt, &-position, &sun).unwrap();
+        assert!(day > 2.0 * night, "{day} {night}");
+        assert!(storm.density(&dt, &position, &sun).unwrap() > 2.0 * day);
+
+        let low = Vector3::new(6_378_137.0 + 80e3, 0.0, 0.0);
+        assert!(matches!(
+            quiet.density(&dt, &low, &sun),
+            Err(ForceError::Data(_))
+        ));
+    }
+}
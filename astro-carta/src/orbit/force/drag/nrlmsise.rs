@@ -0,0 +1,1376 @@
+use std::fmt;
+
+use super::{geodetic, Atmosphere, SpaceWeather};
+use crate::datetime::{DateTime, TimeScale};
+use crate::math::Vector3;
+use crate::orbit::force::ForceError;
+
+/// Error raised when reading the coefficients of NRLMSISE-00
+#[derive(Debug, Clone, PartialEq)]
+pub enum NrlmsiseError {
+    /// An array of coefficients is missing
+    MissingArray(&'static str),
+    /// An array of coefficients has a wrong length or an invalid number
+    InvalidArray(&'static str),
+}
+
+impl fmt::Display for NrlmsiseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NrlmsiseError::MissingArray(name) => write!(f, "missing array {name}"),
+            NrlmsiseError::InvalidArray(name) => write!(f, "invalid array {name}"),
+        }
+    }
+}
+
+impl std::error::Error for NrlmsiseError {}
+
+/// Coefficients of NRLMSISE-00, named as in the reference implementation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Nrlmsise00Coefficients {
+    /// Temperature
+    pub pt: [f64; 150],
+    /// Densities of He, O, N₂, the temperature at the lower boundary, O₂, Ar, H, N and
+    /// anomalous O
+    pub pd: [[f64; 150]; 9],
+    /// Gradient of the temperature at the lower boundary
+    pub ps: [f64; 150],
+    /// Turbopause and chemistry corrections
+    pub pdl: [[f64; 25]; 2],
+    /// Temperatures of the lower thermosphere
+    pub ptl: [[f64; 100]; 4],
+    /// Temperatures of the middle and lower atmosphere
+    pub pma: [[f64; 100]; 10],
+    /// Scales at the lower boundary: exospheric temperature, temperatures of the nodes, altitude
+    /// of the boundary and gradient
+    pub ptm: [f64; 10],
+    /// Scales of the densities of He, O, N₂, O₂, Ar, H, N and anomalous O at the lower boundary,
+    /// with the turbopause heights and the chemistry and turbopause correction parameters
+    pub pdm: [[f64; 10]; 8],
+    /// Middle atmosphere averages, scaling the temperatures of the nodes below the lower
+    /// boundary
+    pub pavgm: [f64; 10],
+}
+
+impl Nrlmsise00Coefficients {
+    /// Reads the coefficients from the arrays of the file `nrlmsise-00_data.c` of the C
+    /// implementation of the model.
+    pub fn parse_c(text: &str) -> Result<Self, NrlmsiseError> {
+        let text = strip_comments(text);
+        Ok(Nrlmsise00Coefficients {
+            pt: array(&text, "pt")?,
+            pd: rows(&text, "pd")?,
+            ps: array(&text, "ps")?,
+            pdl: rows(&text, "pdl")?,
+            ptl: rows(&text, "ptl")?,
+            pma: rows(&text, "pma")?,
+            ptm: array(&text, "ptm")?,
+            pdm: rows(&text, "pdm")?,
+            pavgm: array(&text, "pavgm")?,
+        })
+    }
+}
+
+/// Removes the comments of C source code.
+fn strip_comments(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut rest = text;
+    loop {
+        let block = rest.find("/*");
+        let line = rest.find("//");
+        match (block, line) {
+            (Some(start), None) => {
+                stripped.push_str(&rest[..start]);
+                rest = rest[start + 2..]
+                    .find("*/")
+                    .map_or("", |end| &rest[start + 2 + end + 2..]);
+            }
+            (Some(start), Some(line)) if start < line => {
+                stripped.push_str(&rest[..start]);
+                rest = rest[start + 2..]
+                    .find("*/")
+                    .map_or("", |end| &rest[start + 2 + end + 2..]);
+            }
+            (_, Some(start)) => {
+                stripped.push_str(&rest[..start]);
+                rest = rest[start..]
+                    .find('\n')
+                    .map_or("", |end| &rest[start + end..]);
+            }
+            (None, None) => {
+                stripped.push_str(rest);
+                return stripped;
+            }
+        }
+    }
+}
+
+/// Reads the numbers of the initializer of the C array with the given name.
+fn numbers(text: &str, name: &'static str) -> Result<Vec<f64>, NrlmsiseError> {
+    let start = text
+        .match_indices(name)
+        .map(|(index, _)| index)
+        .find(|&index| {
+            let before = text[..index].chars().next_back();
+            let after = text[index + name.len()..].trim_start().chars().next();
+            !before.is_some_and(|c| c.is_alphanumeric() || c == '_') && after == Some('[')
+        })
+        .ok_or(NrlmsiseError::MissingArray(name))?;
+    let text = &text[start..];
+    let initializer = text
+        .find('=')
+        .zip(text.find(';'))
+        .filter(|(equal, end)| equal < end)
+        .map(|(equal, end)| &text[equal + 1..end])
+        .ok_or(NrlmsiseError::InvalidArray(name))?;
+    initializer
+        .split(|c: char| c == '{' || c == '}' || c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse().map_err(|_| NrlmsiseError::InvalidArray(name)))
+        .collect()
+}
+
+fn array<const N: usize>(text: &str, name: &'static str) -> Result<[f64; N], NrlmsiseError> {
+    numbers(text, name)?
+        .try_into()
+        .map_err(|_| NrlmsiseError::InvalidArray(name))
+}
+
+fn rows<const M: usize, const N: usize>(
+    text: &str,
+    name: &'static str,
+) -> Result<[[f64; N]; M], NrlmsiseError> {
+    let numbers = numbers(text, name)?;
+    if numbers.len() != M * N {
+        return Err(NrlmsiseError::InvalidArray(name));
+    }
+    let mut rows = [[0.0; N]; M];
+    for (row, chunk) in rows.iter_mut().zip(numbers.chunks(N)) {
+        row.copy_from_slice(chunk);
+    }
+    Ok(rows)
+}
+
+/// Inputs of NRLMSISE-00
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MsisInput {
+    /// Day of the year, from 1
+    pub day_of_year: f64,
+    /// Seconds of the day in UT
+    pub seconds: f64,
+    /// Geodetic altitude in km
+    pub altitude: f64,
+    /// Geodetic latitude in degrees
+    pub latitude: f64,
+    /// Geodetic longitude in degrees
+    pub longitude: f64,
+    /// Local apparent solar time in hours
+    pub local_solar_time: f64,
+    /// Average of F10.7 over 81 days centered on the day
+    pub f107_average: f64,
+    /// F10.7 of the previous day
+    pub f107: f64,
+    /// Daily Ap
+    pub ap: f64,
+    /// History of 3-hour ap, as in [`ActivityIndices::ap_history`], which replaces the daily Ap
+    /// when given
+    ///
+    /// [`ActivityIndices::ap_history`]: super::space_weather::ActivityIndices::ap_history
+    pub ap_history: Option<[f64; 7]>,
+}
+
+/// Outputs of NRLMSISE-00, in SI units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MsisOutput {
+    /// Number density of He in 1/m³
+    pub helium: f64,
+    /// Number density of O in 1/m³
+    pub atomic_oxygen: f64,
+    /// Number density of N₂ in 1/m³
+    pub nitrogen: f64,
+    /// Number density of O₂ in 1/m³
+    pub oxygen: f64,
+    /// Number density of Ar in 1/m³
+    pub argon: f64,
+    /// Number density of H in 1/m³
+    pub hydrogen: f64,
+    /// Number density of N in 1/m³
+    pub atomic_nitrogen: f64,
+    /// Number density of anomalous O in 1/m³, hot oxygen and O⁺ significant above 500 km
+    pub anomalous_oxygen: f64,
+    /// Mass density of all the constituents but anomalous O in kg/m³
+    pub mass_density: f64,
+    /// Exospheric temperature in K
+    pub exospheric_temperature: f64,
+    /// Temperature at the altitude in K
+    pub temperature: f64,
+}
+
+impl MsisOutput {
+    /// Returns the mass density including anomalous O in kg/m³, the effective density for
+    /// drag.
+    pub fn effective_mass_density(&self) -> f64 {
+        self.mass_density + 1.66e-27 * 16.0 * self.anomalous_oxygen
+    }
+}
+
+/// The NRLMSISE-00 empirical atmosphere of Picone, Hedin, Drob and Aikin (2002)
+///
+/// This is a port of the C implementation by Dominik Brodowski of the Fortran model, whose
+/// coefficients are read from its file `nrlmsise-00_data.c`. As a drag atmosphere it uses the
+/// effective mass density, which includes anomalous oxygen, and the 3-hour ap history of the
+/// space weather.
+///
+/// # Examples
+///
+/// ```no_run
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::force::drag::nrlmsise::{MsisInput, Nrlmsise00Coefficients};
+/// use astro_carta::orbit::force::drag::{Nrlmsise00, SpaceWeather};
+///
+/// let text = std::fs::read_to_string("nrlmsise-00_data.c").unwrap();
+/// let coefficients = Nrlmsise00Coefficients::parse_c(&text).unwrap();
+/// let space_weather = SpaceWeather::parse_csv(&std::fs::read_to_string("SW-All.csv").unwrap()).unwrap();
+/// let model = Nrlmsise00::new(coefficients, space_weather);
+/// let output = model.calculate(&MsisInput {
+///     day_of_year: 172.0,
+///     seconds: 29000.0,
+///     altitude: 400.0,
+///     latitude: 60.0,
+///     longitude: -70.0,
+///     local_solar_time: 16.0,
+///     f107_average: 150.0,
+///     f107: 150.0,
+///     ap: 4.0,
+///     ap_history: None,
+/// });
+/// println!("{:.6e} kg/m³ at {:.1} K", output.mass_density, output.temperature);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Nrlmsise00 {
+    coefficients: Box<Nrlmsise00Coefficients>,
+    pub space_weather: SpaceWeather,
+    /// Switches of the variations, as in the reference implementation: 0 turns a variation
+    /// off, 1 on, and 2 keeps only its main effect; switch 0, for the units, is ignored, and
+    /// switch 9 uses the ap history when it is given.
+    pub switches: [i32; 24],
+}
+
+impl Nrlmsise00 {
+    pub fn new(coefficients: Nrlmsise00Coefficients, space_weather: SpaceWeather) -> Self {
+        Nrlmsise00 {
+            coefficients: Box::new(coefficients),
+            space_weather,
+            switches: [1; 24],
+        }
+    }
+
+    pub fn coefficients(&self) -> &Nrlmsise00Coefficients {
+        &self.coefficients
+    }
+
+    /// Evaluates the model, like `gtd7` of the reference implementation.
+    pub fn calculate(&self, input: &MsisInput) -> MsisOutput {
+        let mut model = Model::new(&self.coefficients, input, &self.switches);
+        let (d, t) = model.gtd7();
+        MsisOutput {
+            helium: d[0] * 1e6,
+            atomic_oxygen: d[1] * 1e6,
+            nitrogen: d[2] * 1e6,
+            oxygen: d[3] * 1e6,
+            argon: d[4] * 1e6,
+            hydrogen: d[6] * 1e6,
+            atomic_nitrogen: d[7] * 1e6,
+            anomalous_oxygen: d[8] * 1e6,
+            mass_density: d[5] * 1e3,
+            exospheric_temperature: t[0],
+            temperature: t[1],
+        }
+    }
+}
+
+impl Atmosphere for Nrlmsise00 {
+    fn density(
+        &self,
+        dt: &DateTime,
+        position: &Vector3,
+        _sun: &Vector3,
+    ) -> Result<f64, ForceError> {
+        let indices = self
+            .space_weather
+            .indices(dt)
+            .ok_or_else(|| ForceError::Data("space weather indices unavailable".to_string()))?;
+        let invalid = || ForceError::Data("date outside the calendar".to_string());
+        let (year, month, day, hour, minute, second) =
+            dt.to_gregorian(TimeScale::UTC).ok_or_else(invalid)?;
+        let january = DateTime::gregorian_with_scale(year, 1, 1, 0, 0, 0.0, TimeScale::UTC)
+            .ok_or_else(invalid)?;
+        let midnight = DateTime::gregorian_with_scale(year, month, day, 0, 0, 0.0, TimeScale::UTC)
+            .ok_or_else(invalid)?;
+        let day_of_year = ((midnight - january).total_seconds() / 86400.0).round() + 1.0;
+        let seconds = hour as f64 * 3600.0 + minute as f64 * 60.0 + second;
+
+        let coordinates = geodetic(position);
+        let longitude = coordinates.longitude.to_degrees();
+        let output = self.calculate(&MsisInput {
+            day_of_year,
+            seconds,
+            altitude: coordinates.height / 1e3,
+            latitude: coordinates.latitude.to_degrees(),
+            longitude,
+            local_solar_time: seconds / 3600.0 + longitude / 15.0,
+            f107_average: indices.f107_average,
+            f107: indices.f107,
+            ap: indices.ap,
+            ap_history: Some(indices.ap_history),
+        });
+        Ok(output.effective_mass_density())
+    }
+}
+
+/// Degrees to radians, as rounded in the model
+const DGTR: f64 = 1.74533e-2;
+/// Angular frequency of the year in radians per day
+const DR: f64 = 1.72142e-2;
+/// Angular frequency of the day in radians per hour
+const HR: f64 = 0.2618;
+/// Angular frequency of the day in radians per second
+const SR: f64 = 7.2722e-5;
+/// Gas constant in the units of the model
+const RGAS: f64 = 831.4;
+
+/// Nodes of the temperature profile of the troposphere and stratosphere in km
+const ZN3: [f64; 5] = [32.5, 20.0, 15.0, 10.0, 0.0];
+/// Nodes of the temperature profile of the stratosphere and mesosphere in km
+const ZN2: [f64; 4] = [72.5, 55.0, 45.0, 32.5];
+/// Altitude in km below which the densities are fully mixed
+const ZMIX: f64 = 62.5;
+
+/// Evaluation of the model, holding the state shared between its functions
+struct Model<'a> {
+    p: &'a Nrlmsise00Coefficients,
+    input: &'a MsisInput,
+    sw: [f64; 24],
+    swc: [f64; 24],
+    /// Gravity at the surface in cm/s² and effective radius of the Earth in km
+    gsurf: f64,
+    re: f64,
+    /// Associated Legendre polynomials of the latitude, indexed by order and degree
+    plg: [[f64; 9]; 4],
+    ctloc: f64,
+    stloc: f64,
+    c2tloc: f64,
+    s2tloc: f64,
+    c3tloc: f64,
+    s3tloc: f64,
+    /// Deviation of the average F10.7 from 150, and magnetic activity functions of the last
+    /// call of `globe7`
+    dfa: f64,
+    apdf: f64,
+    apt: f64,
+    /// Nodes and end gradients of the temperature profiles
+    tn1: [f64; 5],
+    tgn1: [f64; 2],
+    tn2: [f64; 4],
+    tgn2: [f64; 2],
+    tn3: [f64; 5],
+    tgn3: [f64; 2],
+    /// Mixed density of N₂ at the altitude
+    dm28: f64,
+}
+
+impl<'a> Model<'a> {
+    fn new(p: &'a Nrlmsise00Coefficients, input: &'a MsisInput, switches: &[i32; 24]) -> Self {
+        let mut sw = [0.0; 24];
+        let mut swc = [0.0; 24];
+        for (i, &switch) in switches.iter().enumerate() {
+            if i == 9 {
+                let switch = if switch != 0 && input.ap_history.is_some() {
+                    -1
+                } else {
+                    switch
+                };
+                sw[i] = switch as f64;
+                swc[i] = switch as f64;
+            } else {
+                sw[i] = if switch == 1 { 1.0 } else { 0.0 };
+                swc[i] = if switch > 0 { 1.0 } else { 0.0 };
+            }
+        }
+
+        // Latitude variation of gravity, none when switch 2 is off
+        let latitude = if sw[2] == 0.0 { 45.0 } else { input.latitude };
+        let c2 = (2.0 * DGTR * latitude).cos();
+        let gsurf = 980.616 * (1.0 - 0.0026373 * c2);
+        let re = 2.0 * gsurf / (3.085462e-6 + 2.27e-9 * c2) * 1e-5;
+
+        let c = (input.latitude * DGTR).sin();
+        let s = (input.latitude * DGTR).cos();
+        let (c2, s2) = (c * c, s * s);
+        let c4 = c2 * c2;
+        let mut plg = [[0.0; 9]; 4];
+        plg[0][1] = c;
+        plg[0][2] = 0.5 * (3.0 * c2 - 1.0);
+        plg[0][3] = 0.5 * (5.0 * c * c2 - 3.0 * c);
+        plg[0][4] = (35.0 * c4 - 30.0 * c2 + 3.0) / 8.0;
+        plg[0][5] = (63.0 * c2 * c2 * c - 70.0 * c2 * c + 15.0 * c) / 8.0;
+        plg[0][6] = (11.0 * c * plg[0][5] - 5.0 * plg[0][4]) / 6.0;
+        plg[1][1] = s;
+        plg[1][2] = 3.0 * c * s;
+        plg[1][3] = 1.5 * (5.0 * c2 - 1.0) * s;
+        plg[1][4] = 2.5 * (7.0 * c2 * c - 3.0 * c) * s;
+        plg[1][5] = 1.875 * (21.0 * c4 - 14.0 * c2 + 1.0) * s;
+        plg[1][6] = (11.0 * c * plg[1][5] - 6.0 * plg[1][4]) / 5.0;
+        plg[2][2] = 3.0 * s2;
+        plg[2][3] = 15.0 * s2 * c;
+        plg[2][4] = 7.5 * (7.0 * c2 - 1.0) * s2;
+        plg[2][5] = 3.0 * c * plg[2][4] - 2.0 * plg[2][3];
+        plg[2][6] = (11.0 * c * plg[2][5] - 7.0 * plg[2][4]) / 4.0;
+        plg[2][7] = (13.0 * c * plg[2][6] - 8.0 * plg[2][5]) / 5.0;
+        plg[3][3] = 15.0 * s2 * s;
+        plg[3][4] = 105.0 * s2 * s * c;
+        plg[3][5] = (9.0 * c * plg[3][4] - 7.0 * plg[3][3]) / 2.0;
+        plg[3][6] = (11.0 * c * plg[3][5] - 8.0 * plg[3][4]) / 3.0;
+
+        let tloc = input.local_solar_time;
+        Model {
+            p,
+            input,
+            sw,
+            swc,
+            gsurf,
+            re,
+            plg,
+            ctloc: (HR * tloc).cos(),
+            stloc: (HR * tloc).sin(),
+            c2tloc: (2.0 * HR * tloc).cos(),
+            s2tloc: (2.0 * HR * tloc).sin(),
+            c3tloc: (3.0 * HR * tloc).cos(),
+            s3tloc: (3.0 * HR * tloc).sin(),
+            dfa: 0.0,
+            apdf: 0.0,
+            apt: 0.0,
+            tn1: [0.0; 5],
+            tgn1: [0.0; 2],
+            tn2: [0.0; 4],
+            tgn2: [0.0; 2],
+            tn3: [0.0; 5],
+            tgn3: [0.0; 2],
+            dm28: 0.0,
+        }
+    }
+
+    /// Returns the geopotential height of `zz` above `zl`.
+    fn zeta(&self, zz: f64, zl: f64) -> f64 {
+        (zz - zl) * (self.re + zl) / (self.re + zz)
+    }
+
+    /// Returns the scale height in km.
+    fn scalh(&self, alt: f64, xm: f64, xtemp: f64) -> f64 {
+        let g = self.gsurf / (1.0 + alt / self.re).powi(2);
+        RGAS * xtemp / (g * xm)
+    }
+
+    /// Computes the variations of the upper thermosphere, `G(L)` of the model.
+    fn globe7(&mut self, p: &[f64]) -> f64 {
+        let input = self.input;
+        let (sw, swc, plg) = (&self.sw, &self.swc, &self.plg);
+        let tloc = input.local_solar_time;
+        let doy = input.day_of_year;
+        let mut t = [0.0; 15];
+
+        let cd32 = (DR * (doy - p[31])).cos();
+        let cd18 = (2.0 * DR * (doy - p[17])).cos();
+        let cd14 = (DR * (doy - p[13])).cos();
+        let cd39 = (2.0 * DR * (doy - p[38])).cos();
+
+        // F10.7 effect
+        let df = input.f107 - input.f107_average;
+        let dfa = input.f107_average - 150.0;
+        t[0] = p[19] * df * (1.0 + p[59] * dfa) + p[20] * df * df + p[21] * dfa + p[29] * dfa * dfa;
+        let f1 = 1.0 + (p[47] * dfa + p[19] * df + p[20] * df * df) * swc[1];
+        let f2 = 1.0 + (p[49] * dfa + p[19] * df + p[20] * df * df) * swc[1];
+
+        // Time independent
+        t[1] = (p[1] * plg[0][2] + p[2] * plg[0][4] + p[22] * plg[0][6])
+            + p[14] * plg[0][2] * dfa * swc[1]
+            + p[26] * plg[0][1];
+        // Symmetrical annual
+        t[2] = p[18] * cd32;
+        // Symmetrical semiannual
+        t[3] = (p[15] + p[16] * plg[0][2]) * cd18;
+        // Asymmetrical annual
+        t[4] = f1 * (p[9] * plg[0][1] + p[10] * plg[0][3]) * cd14;
+        // Asymmetrical semiannual
+        t[5] = p[37] * plg[0][1] * cd39;
+
+        // Diurnal
+        if sw[7] != 0.0 {
+            let t71 = p[11] * plg[1][2] * cd14 * swc[5];
+            let t72 = p[12] * plg[1][2] * cd14 * swc[5];
+            t[6] = f2
+                * ((p[3] * plg[1][1] + p[4] * plg[1][3] + p[27] * plg[1][5] + t71) * self.ctloc
+                    + (p[6] * plg[1][1] + p[7] * plg[1][3] + p[28] * plg[1][5] + t72) * self.stloc);
+        }
+        // Semidiurnal
+        if sw[8] != 0.0 {
+            let t81 = (p[23] * plg[2][3] + p[35] * plg[2][5]) * cd14 * swc[5];
+            let t82 = (p[33] * plg[2][3] + p[36] * plg[2][5]) * cd14 * swc[5];
+            t[7] = f2
+                * ((p[5] * plg[2][2] + p[41] * plg[2][4] + t81) * self.c2tloc
+                    + (p[8] * plg[2][2] + p[42] * plg[2][4] + t82) * self.s2tloc);
+        }
+        // Terdiurnal
+        if sw[14] != 0.0 {
+            t[13] = f2
+                * ((p[39] * plg[3][3] + (p[93] * plg[3][4] + p[46] * plg[3][6]) * cd14 * swc[5])
+                    * self.s3tloc
+                    + (p[40] * plg[3][3]
+                        + (p[94] * plg[3][4] + p[48] * plg[3][6]) * cd14 * swc[5])
+                        * self.c3tloc);
+        }
+
+        // Magnetic activity, from the ap history or the daily Ap
+        let mut apt = self.apt;
+        let mut apdf = self.apdf;
+        if sw[9] == -1.0 {
+            if p[51] != 0.0 {
+                let ex = (-10800.0 * p[51].abs() / (1.0 + p[138] * (45.0 - input.latitude.abs())))
+                    .exp()
+                    .min(0.99999);
+                let ap = input.ap_history.unwrap_or([input.ap; 7]);
+                apt = sg0(ex, p, &ap);
+                t[8] = apt
+                    * (p[50]
+                        + p[96] * plg[0][2]
+                        + p[54] * plg[0][4]
+                        + (p[125] * plg[0][1] + p[126] * plg[0][3] + p[127] * plg[0][5])
+                            * cd14
+                            * swc[5]
+                        + (p[128] * plg[1][1] + p[129] * plg[1][3] + p[130] * plg[1][5])
+                            * swc[7]
+                            * (HR * (tloc - p[131])).cos());
+            }
+        } else {
+            let apd = input.ap - 4.0;
+            let p44 = if p[43] < 0.0 { 1e-5 } else { p[43] };
+            let p45 = p[44];
+            apdf = apd + (p45 - 1.0) * (apd + ((-p44 * apd).exp() - 1.0) / p44);
+            if sw[9] != 0.0 {
+                t[8] = apdf
+                    * (p[32]
+                        + p[45] * plg[0][2]
+                        + p[34] * plg[0][4]
+                        + (p[100] * plg[0][1] + p[101] * plg[0][3] + p[102] * plg[0][5])
+                            * cd14
+                            * swc[5]
+                        + (p[121] * plg[1][1] + p[122] * plg[1][3] + p[123] * plg[1][5])
+                            * swc[7]
+                            * (HR * (tloc - p[124])).cos());
+            }
+        }
+
+        if sw[10] != 0.0 && input.longitude > -1000.0 {
+            let longitude = DGTR * input.longitude;
+            // Longitudinal
+            if sw[11] != 0.0 {
+                t[10] = (1.0 + p[80] * dfa * swc[1])
+                    * ((p[64] * plg[1][2]
+                        + p[65] * plg[1][4]
+                        + p[66] * plg[1][6]
+                        + p[103] * plg[1][1]
+                        + p[104] * plg[1][3]
+                        + p[105] * plg[1][5]
+                        + swc[5]
+                            * (p[109] * plg[1][1] + p[110] * plg[1][3] + p[111] * plg[1][5])
+                            * cd14)
+                        * longitude.cos()
+                        + (p[90] * plg[1][2]
+                            + p[91] * plg[1][4]
+                            + p[92] * plg[1][6]
+                            + p[106] * plg[1][1]
+                            + p[107] * plg[1][3]
+                            + p[108] * plg[1][5]
+                            + swc[5]
+                                * (p[112] * plg[1][1] + p[113] * plg[1][3] + p[114] * plg[1][5])
+                                * cd14)
+                            * longitude.sin());
+            }
+            // UT and mixed UT and longitude
+            if sw[12] != 0.0 {
+                t[11] = (1.0 + p[95] * plg[0][1])
+                    * (1.0 + p[81] * dfa * swc[1])
+                    * (1.0 + p[119] * plg[0][1] * swc[5] * cd14)
+                    * ((p[68] * plg[0][1] + p[69] * plg[0][3] + p[70] * plg[0][5])
+                        * (SR * (input.seconds - p[71])).cos());
+                t[11] += swc[11]
+                    * (p[76] * plg[2][3] + p[77] * plg[2][5] + p[78] * plg[2][7])
+                    * (SR * (input.seconds - p[79]) + 2.0 * longitude).cos()
+                    * (1.0 + p[137] * dfa * swc[1]);
+            }
+            // UT, longitude and magnetic activity
+            if sw[13] != 0.0 {
+                if sw[9] == -1.0 {
+                    if p[51] != 0.0 {
+                        t[12] = apt
+                            * swc[11]
+                            * (1.0 + p[132] * plg[0][1])
+                            * ((p[52] * plg[1][2] + p[98] * plg[1][4] + p[67] * plg[1][6])
+                                * (DGTR * (input.longitude - p[97])).cos())
+                            + apt
+                                * swc[11]
+                                * swc[5]
+                                * (p[133] * plg[1][1] + p[134] * plg[1][3] + p[135] * plg[1][5])
+                                * cd14
+                                * (DGTR * (input.longitude - p[136])).cos()
+                            + apt
+                                * swc[12]
+                                * (p[55] * plg[0][1] + p[56] * plg[0][3] + p[57] * plg[0][5])
+                                * (SR * (input.seconds - p[58])).cos();
+                    }
+                } else {
+                    t[12] = apdf
+                        * swc[11]
+                        * (1.0 + p[120] * plg[0][1])
+                        * ((p[60] * plg[1][2] + p[61] * plg[1][4] + p[62] * plg[1][6])
+                            * (DGTR * (input.longitude - p[63])).cos())
+                        + apdf
+                            * swc[11]
+                            * swc[5]
+                            * (p[115] * plg[1][1] + p[116] * plg[1][3] + p[117] * plg[1][5])
+                            * cd14
+                            * (DGTR * (input.longitude - p[118])).cos()
+                        + apdf
+                            * swc[12]
+                            * (p[83] * plg[0][1] + p[84] * plg[0][3] + p[85] * plg[0][5])
+                            * (SR * (input.seconds - p[75])).cos();
+                }
+            }
+        }
+
+        let tinf = p[30]
+            + t.iter()
+                .take(14)
+                .enumerate()
+                .map(|(i, t)| sw[i + 1].abs() * t)
+                .sum::<f64>();
+        self.dfa = dfa;
+        self.apt = apt;
+        self.apdf = apdf;
+        tinf
+    }
+
+    /// Computes the variations of the lower atmosphere.
+    fn glob7s(&self, p: &[f64]) -> f64 {
+        let input = self.input;
+        let (sw, swc, plg) = (&self.sw, &self.swc, &self.plg);
+        let doy = input.day_of_year;
+        let mut t = [0.0; 14];
+
+        let cd32 = (DR * (doy - p[31])).cos();
+        let cd18 = (2.0 * DR * (doy - p[17])).cos();
+        let cd14 = (DR * (doy - p[13])).cos();
+        let cd39 = (2.0 * DR * (doy - p[38])).cos();
+
+        // F10.7
+        t[0] = p[21] * self.dfa;
+        // Time independent
+        t[1] = p[1] * plg[0][2]
+            + p[2] * plg[0][4]
+            + p[22] * plg[0][6]
+            + p[26] * plg[0][1]
+            + p[14] * plg[0][3]
+            + p[59] * plg[0][5];
+        // Symmetrical annual
+        t[2] = (p[18] + p[47] * plg[0][2] + p[29] * plg[0][4]) * cd32;
+        // Symmetrical semiannual
+        t[3] = (p[15] + p[16] * plg[0][2] + p[30] * plg[0][4]) * cd18;
+        // Asymmetrical annual
+        t[4] = (p[9] * plg[0][1] + p[10] * plg[0][3] + p[20] * plg[0][5]) * cd14;
+        // Asymmetrical semiannual
+        t[5] = p[37] * plg[0][1] * cd39;
+
+        // Diurnal
+        if sw[7] != 0.0 {
+            let t71 = p[11] * plg[1][2] * cd14 * swc[5];
+            let t72 = p[12] * plg[1][2] * cd14 * swc[5];
+            t[6] = (p[3] * plg[1][1] + p[4] * plg[1][3] + t71) * self.ctloc
+                + (p[6] * plg[1][1] + p[7] * plg[1][3] + t72) * self.stloc;
+        }
+        // Semidiurnal
+        if sw[8] != 0.0 {
+            let t81 = (p[23] * plg[2][3] + p[35] * plg[2][5]) * cd14 * swc[5];
+            let t82 = (p[33] * plg[2][3] + p[36] * plg[2][5]) * cd14 * swc[5];
+            t[7] = (p[5] * plg[2][2] + p[41] * plg[2][4] + t81) * self.c2tloc
+                + (p[8] * plg[2][2] + p[42] * plg[2][4] + t82) * self.s2tloc;
+        }
+        // Terdiurnal
+        if sw[14] != 0.0 {
+            t[13] = p[39] * plg[3][3] * self.s3tloc + p[40] * plg[3][3] * self.c3tloc;
+        }
+
+        // Magnetic activity
+        if sw[9] == 1.0 {
+            t[8] = self.apdf * (p[32] + p[45] * plg[0][2] * swc[2]);
+        } else if sw[9] == -1.0 {
+            t[8] = p[50] * self.apt + p[96] * plg[0][2] * self.apt * swc[2];
+        }
+
+        // Longitudinal
+        if sw[10] != 0.0 && sw[11] != 0.0 && input.longitude > -1000.0 {
+            let longitude = DGTR * input.longitude;
+            t[10] = (1.0
+                + plg[0][1]
+                    * (p[80] * swc[5] * (DR * (doy - p[81])).cos()
+                        + p[85] * swc[6] * (2.0 * DR * (doy - p[86])).cos())
+                + p[83] * swc[3] * (DR * (doy - p[84])).cos()
+                + p[87] * swc[4] * (2.0 * DR * (doy - p[88])).cos())
+                * ((p[64] * plg[1][2]
+                    + p[65] * plg[1][4]
+                    + p[66] * plg[1][6]
+                    + p[74] * plg[1][1]
+                    + p[75] * plg[1][3]
+                    + p[76] * plg[1][5])
+                    * longitude.cos()
+                    + (p[90] * plg[1][2]
+                        + p[91] * plg[1][4]
+                        + p[92] * plg[1][6]
+                        + p[77] * plg[1][1]
+                        + p[78] * plg[1][3]
+                        + p[79] * plg[1][5])
+                        * longitude.sin());
+        }
+
+        t.iter().enumerate().map(|(i, t)| sw[i + 1].abs() * t).sum()
+    }
+
+    /// Computes the temperature and the density of the thermosphere, from the Bates profile
+    /// above `zn1[0]` and a spline below. Returns the density, or the temperature when `xm` is
+    /// zero, and the temperature.
+    #[allow(clippy::too_many_arguments)]
+    fn densu(
+        &mut self,
+        alt: f64,
+        dlb: f64,
+        tinf: f64,
+        tlb: f64,
+        xm: f64,
+        alpha: f64,
+        zlb: f64,
+        s2: f64,
+    ) -> (f64, f64) {
+        let zn1 = self.zn1();
+        let za = zn1[0];
+        let z = alt.max(za);
+        // Geopotential height above the lower boundary and Bates temperature
+        let zg2 = self.zeta(z, zlb);
+        let tt = tinf - (tinf - tlb) * (-s2 * zg2).exp();
+        let ta = tt;
+        let mut tz = tt;
+
+        let mut spline_state = None;
+        if alt < za {
+            // Temperature below za, with the gradient at za from the Bates profile
+            let dta = (tinf - ta) * s2 * ((self.re + zlb) / (self.re + za)).powi(2);
+            self.tgn1[0] = dta;
+            self.tn1[0] = ta;
+            let z = alt.max(zn1[4]);
+            let (z1, z2) = (zn1[0], zn1[4]);
+            let (t1, t2) = (self.tn1[0], self.tn1[4]);
+            let zg = self.zeta(z, z1);
+            let zgdif = self.zeta(z2, z1);
+            let mut xs = [0.0; 5];
+            let mut ys = [0.0; 5];
+            for k in 0..5 {
+                xs[k] = self.zeta(zn1[k], z1) / zgdif;
+                ys[k] = 1.0 / self.tn1[k];
+            }
+            let yd1 = -self.tgn1[0] / (t1 * t1) * zgdif;
+            let yd2 = -self.tgn1[1] / (t2 * t2) * zgdif * ((self.re + z2) / (self.re + z1)).powi(2);
+            let y2out = spline(&xs, &ys, yd1, yd2);
+            let x = zg / zgdif;
+            tz = 1.0 / splint(&xs, &ys, &y2out, x);
+            spline_state = Some((xs, ys, y2out, x, z1, zgdif, t1));
+        }
+        if xm == 0.0 {
+            return (tz, tz);
+        }
+
+        // Density above za
+        let glb = self.gsurf / (1.0 + zlb / self.re).powi(2);
+        let gamma = xm * glb / (s2 * RGAS * tinf);
+        let mut expl = (-s2 * gamma * zg2).exp();
+        if expl > 50.0 || tt <= 0.0 {
+            expl = 50.0;
+        }
+        let density = dlb * (tlb / tt).powf(1.0 + alpha + gamma) * expl;
+        let Some((xs, ys, y2out, x, z1, zgdif, t1)) = spline_state else {
+            return (density, tz);
+        };
+
+        // Density below za, integrating the spline of the inverse temperature
+        let glb = self.gsurf / (1.0 + z1 / self.re).powi(2);
+        let gamm = xm * glb * zgdif / RGAS;
+        let mut expl = gamm * splini(&xs, &ys, &y2out, x);
+        if expl > 50.0 || tz <= 0.0 {
+            expl = 50.0;
+        }
+        (density * (t1 / tz).powf(1.0 + alpha) * (-expl).exp(), tz)
+    }
+
+    /// Computes the temperature and the density of the lower atmosphere, below `zn2[0]`.
+    /// Returns the density and the temperature.
+    fn densm(&self, alt: f64, d0: f64, xm: f64) -> (f64, f64) {
+        let mut density = d0;
+        let mut tz = 0.0;
+        if alt > ZN2[0] {
+            return (density, tz);
+        }
+
+        // Stratosphere and mesosphere
+        let profiles: [(&[f64], &[f64], &[f64; 2]); 2] =
+            [(&ZN2, &self.tn2, &self.tgn2), (&ZN3, &self.tn3, &self.tgn3)];
+        for (zn, tn, tgn) in profiles {
+            if alt > zn[0] {
+                break;
+            }
+            let mn = zn.len();
+            let z = alt.max(zn[mn - 1]);
+            let (z1, z2) = (zn[0], zn[mn - 1]);
+            let (t1, t2) = (tn[0], tn[mn - 1]);
+            let zg = self.zeta(z, z1);
+            let zgdif = self.zeta(z2, z1);
+            let xs: Vec<_> = zn.iter().map(|&z| self.zeta(z, z1) / zgdif).collect();
+            let ys: Vec<_> = tn.iter().map(|t| 1.0 / t).collect();
+            let yd1 = -tgn[0] / (t1 * t1) * zgdif;
+            let yd2 = -tgn[1] / (t2 * t2) * zgdif * ((self.re + z2) / (self.re + z1)).powi(2);
+            let y2out = spline(&xs, &ys, yd1, yd2);
+            let x = zg / zgdif;
+            tz = 1.0 / splint(&xs, &ys, &y2out, x);
+            if xm != 0.0 {
+                let glb = self.gsurf / (1.0 + z1 / self.re).powi(2);
+                let gamm = xm * glb * zgdif / RGAS;
+                let expl = (gamm * splini(&xs, &ys, &y2out, x)).min(50.0);
+                density *= t1 / tz * (-expl).exp();
+            }
+        }
+        (density, tz)
+    }
+
+    /// Returns the nodes of the temperature profile of the lower thermosphere in km.
+    fn zn1(&self) -> [f64; 5] {
+        [self.p.pdl[1][15], 110.0, 100.0, 90.0, 72.5]
+    }
+
+    /// Evaluates the thermospheric part of the model at an altitude in km above 72.5 km.
+    /// Returns the densities in 1/cm³ of He, O, N₂, O₂, Ar, H, N and anomalous O, with the mass
+    /// density in g/cm³ at index 5, and the exospheric and local temperatures.
+    fn gts7(&mut self, z: f64) -> ([f64; 9], [f64; 2]) {
+        let p = self.p;
+        let sw = self.sw;
+        let input = self.input;
+        let zn1 = self.zn1();
+        let alpha = [-0.38, 0.0, 0.0, 0.0, 0.17, 0.0, -0.38, 0.0, 0.0];
+        let altl = [200.0, 300.0, 160.0, 250.0, 240.0, 450.0, 320.0, 450.0];
+        let mut d = [0.0; 9];
+
+        // The variations of the exospheric temperature are not important below zn1[0], and
+        // those of the gradient below zn1[4]
+        let tinf = if z > zn1[0] {
+            p.ptm[0] * p.pt[0] * (1.0 + sw[16] * self.globe7(&p.pt))
+        } else {
+            p.ptm[0] * p.pt[0]
+        };
+        let g0 = if z > zn1[4] {
+            p.ptm[3] * p.ps[0] * (1.0 + sw[19] * self.globe7(&p.ps))
+        } else {
+            p.ptm[3] * p.ps[0]
+        };
+        let tlb = p.ptm[1] * (1.0 + sw[17] * self.globe7(&p.pd[3])) * p.pd[3][0];
+        let s = g0 / (tinf - tlb);
+
+        // The variations of the temperature of the lower thermosphere are not significant for
+        // the density above 300 km
+        if z < 300.0 {
+            self.tn1[1] = p.ptm[6] * p.ptl[0][0] / (1.0 - sw[18] * self.glob7s(&p.ptl[0]));
+            self.tn1[2] = p.ptm[2] * p.ptl[1][0] / (1.0 - sw[18] * self.glob7s(&p.ptl[1]));
+            self.tn1[3] = p.ptm[7] * p.ptl[2][0] / (1.0 - sw[18] * self.glob7s(&p.ptl[2]));
+            self.tn1[4] = p.ptm[4] * p.ptl[3][0] / (1.0 - sw[18] * sw[20] * self.glob7s(&p.ptl[3]));
+            self.tgn1[1] = p.ptm[8]
+                * p.pma[8][0]
+                * (1.0 + sw[18] * sw[20] * self.glob7s(&p.pma[8]))
+                * self.tn1[4]
+                * self.tn1[4]
+                / (p.ptm[4] * p.ptl[3][0]).powi(2);
+        } else {
+            self.tn1[1] = p.ptm[6] * p.ptl[0][0];
+            self.tn1[2] = p.ptm[2] * p.ptl[1][0];
+            self.tn1[3] = p.ptm[7] * p.ptl[2][0];
+            self.tn1[4] = p.ptm[4] * p.ptl[3][0];
+            self.tgn1[1] = p.ptm[8] * p.pma[8][0] * self.tn1[4] * self.tn1[4]
+                / (p.ptm[4] * p.ptl[3][0]).powi(2);
+        }
+
+        let zlb = p.ptm[5];
+        let densu = |model: &mut Self, alt: f64, dlb: f64, xm: f64, alpha: f64| {
+            model.densu(alt, dlb, tinf, tlb, xm, alpha, zlb, s).0
+        };
+
+        // N₂ variation factor at the lower boundary, and variation of the turbopause height
+        let g28 = sw[21] * self.globe7(&p.pd[2]);
+        let zhf = p.pdl[1][24]
+            * (1.0
+                + sw[5]
+                    * p.pdl[0][24]
+                    * (DGTR * input.latitude).sin()
+                    * (DR * (input.day_of_year - p.pt[13])).cos());
+        let xmm = p.pdm[2][4];
+
+        // N₂: diffusive density at the lower boundary and at the altitude, mixed density at
+        // the turbopause and net density
+        let db28 = p.pdm[2][0] * g28.exp() * p.pd[2][0];
+        d[2] = densu(self, z, db28, 28.0, alpha[2]);
+        let zh28 = p.pdm[2][2] * zhf;
+        let zhm28 = p.pdm[2][3] * p.pdl[1][5];
+        let b28 = densu(self, zh28, db28, 28.0 - xmm, alpha[2] - 1.0);
+        if sw[15] != 0.0 && z <= altl[2] {
+            self.dm28 = densu(self, z, b28, xmm, alpha[2]);
+            d[2] = dnet(d[2], self.dm28, zhm28, xmm, 28.0);
+        }
+
+        // He
+        let g4 = sw[21] * self.globe7(&p.pd[0]);
+        let db04 = p.pdm[0][0] * g4.exp() * p.pd[0][0];
+        d[0] = densu(self, z, db04, 4.0, alpha[0]);
+        if sw[15] != 0.0 && z < altl[0] {
+            let b04 = densu(self, p.pdm[0][2], db04, 4.0 - xmm, alpha[0] - 1.0);
+            let dm04 = densu(self, z, b04, xmm, 0.0);
+            d[0] = dnet(d[0], dm04, zhm28, xmm, 4.0);
+            // Correction to the specified mixing ratio at the ground
+            let rl = (b28 * p.pdm[0][1] / b04).ln();
+            let zc04 = p.pdm[0][4] * p.pdl[1][0];
+            let hc04 = p.pdm[0][5] * p.pdl[1][1];
+            d[0] *= ccor(z, rl, hc04, zc04);
+        }
+
+        // O
+        let g16 = sw[21] * self.globe7(&p.pd[1]);
+        let db16 = p.pdm[1][0] * g16.exp() * p.pd[1][0];
+        d[1] = densu(self, z, db16, 16.0, alpha[1]);
+        if sw[15] != 0.0 && z <= altl[1] {
+            let b16 = densu(self, p.pdm[1][2], db16, 16.0 - xmm, alpha[1] - 1.0);
+            let dm16 = densu(self, z, b16, xmm, 0.0);
+            d[1] = dnet(d[1], dm16, zhm28, xmm, 16.0);
+            let rl = p.pdm[1][1]
+                * p.pdl[1][16]
+                * (1.0 + sw[1] * p.pdl[0][23] * (input.f107_average - 150.0));
+            let hc16 = p.pdm[1][5] * p.pdl[1][3];
+            let zc16 = p.pdm[1][4] * p.pdl[1][2];
+            let hc216 = p.pdm[1][5] * p.pdl[1][4];
+            d[1] *= ccor2(z, rl, hc16, zc16, hc216);
+            // Chemistry correction
+            let hcc16 = p.pdm[1][7] * p.pdl[1][13];
+            let zcc16 = p.pdm[1][6] * p.pdl[1][12];
+            let rc16 = p.pdm[1][3] * p.pdl[1][14];
+            d[1] *= ccor(z, rc16, hcc16, zcc16);
+        }
+
+        // O₂
+        let g32 = sw[21] * self.globe7(&p.pd[4]);
+        let db32 = p.pdm[3][0] * g32.exp() * p.pd[4][0];
+        d[3] = densu(self, z, db32, 32.0, alpha[3]);
+        if sw[15] != 0.0 {
+            if z <= altl[3] {
+                let b32 = densu(self, p.pdm[3][2], db32, 32.0 - xmm, alpha[3] - 1.0);
+                let dm32 = densu(self, z, b32, xmm, 0.0);
+                d[3] = dnet(d[3], dm32, zhm28, xmm, 32.0);
+                let rl = (b28 * p.pdm[3][1] / b32).ln();
+                let hc32 = p.pdm[3][5] * p.pdl[1][7];
+                let zc32 = p.pdm[3][4] * p.pdl[1][6];
+                d[3] *= ccor(z, rl, hc32, zc32);
+            }
+            // Correction for the departure from diffusive equilibrium above the lower boundary
+            let hcc32 = p.pdm[3][7] * p.pdl[1][22];
+            let hcc232 = p.pdm[3][7] * p.pdl[0][22];
+            let zcc32 = p.pdm[3][6] * p.pdl[1][21];
+            let rc32 = p.pdm[3][3]
+                * p.pdl[1][23]
+                * (1.0 + sw[1] * p.pdl[0][23] * (input.f107_average - 150.0));
+            d[3] *= ccor2(z, rc32, hcc32, zcc32, hcc232);
+        }
+
+        // Ar
+        let g40 = sw[21] * self.globe7(&p.pd[5]);
+        let db40 = p.pdm[4][0] * g40.exp() * p.pd[5][0];
+        d[4] = densu(self, z, db40, 40.0, alpha[4]);
+        if sw[15] != 0.0 && z <= altl[4] {
+            let b40 = densu(self, p.pdm[4][2], db40, 40.0 - xmm, alpha[4] - 1.0);
+            let dm40 = densu(self, z, b40, xmm, 0.0);
+            d[4] = dnet(d[4], dm40, zhm28, xmm, 40.0);
+            let rl = (b28 * p.pdm[4][1] / b40).ln();
+            let hc40 = p.pdm[4][5] * p.pdl[1][9];
+            let zc40 = p.pdm[4][4] * p.pdl[1][8];
+            d[4] *= ccor(z, rl, hc40, zc40);
+        }
+
+        // H
+        let g1 = sw[21] * self.globe7(&p.pd[6]);
+        let db01 = p.pdm[5][0] * g1.exp() * p.pd[6][0];
+        d[6] = densu(self, z, db01, 1.0, alpha[6]);
+        if sw[15] != 0.0 && z <= altl[6] {
+            let b01 = densu(self, p.pdm[5][2], db01, 1.0 - xmm, alpha[6] - 1.0);
+            let dm01 = densu(self, z, b01, xmm, 0.0);
+            d[6] = dnet(d[6], dm01, zhm28, xmm, 1.0);
+            let rl = (b28 * p.pdm[5][1] * p.pdl[1][17].abs() / b01).ln();
+            let hc01 = p.pdm[5][5] * p.pdl[1][11];
+            let zc01 = p.pdm[5][4] * p.pdl[1][10];
+            d[6] *= ccor(z, rl, hc01, zc01);
+            let hcc01 = p.pdm[5][7] * p.pdl[1][19];
+            let zcc01 = p.pdm[5][6] * p.pdl[1][18];
+            let rc01 = p.pdm[5][3] * p.pdl[1][20];
+            d[6] *= ccor(z, rc01, hcc01, zcc01);
+        }
+
+        // N
+        let g14 = sw[21] * self.globe7(&p.pd[7]);
+        let db14 = p.pdm[6][0] * g14.exp() * p.pd[7][0];
+        d[7] = densu(self, z, db14, 14.0, alpha[7]);
+        if sw[15] != 0.0 && z <= altl[7] {
+            let b14 = densu(self, p.pdm[6][2], db14, 14.0 - xmm, alpha[7] - 1.0);
+            let dm14 = densu(self, z, b14, xmm, 0.0);
+            d[7] = dnet(d[7], dm14, zhm28, xmm, 14.0);
+            let rl = (b28 * p.pdm[6][1] * p.pdl[0][2].abs() / b14).ln();
+            let hc14 = p.pdm[6][5] * p.pdl[0][1];
+            let zc14 = p.pdm[6][4] * p.pdl[0][0];
+            d[7] *= ccor(z, rl, hc14, zc14);
+            let hcc14 = p.pdm[6][7] * p.pdl[0][4];
+            let zcc14 = p.pdm[6][6] * p.pdl[0][3];
+            let rc14 = p.pdm[6][3] * p.pdl[0][5];
+            d[7] *= ccor(z, rc14, hcc14, zcc14);
+        }
+
+        // Anomalous O
+        let g16h = sw[21] * self.globe7(&p.pd[8]);
+        let db16h = p.pdm[7][0] * g16h.exp() * p.pd[8][0];
+        let tho = p.pdm[7][9] * p.pdl[0][6];
+        let dd = self.densu(z, db16h, tho, tho, 16.0, alpha[8], zlb, s).0;
+        let zsht = p.pdm[7][5];
+        let zmho = p.pdm[7][4];
+        let zsho = self.scalh(zmho, 16.0, tho);
+        d[8] = dd * (-zsht / zsho * ((-(z - zmho) / zsht).exp() - 1.0)).exp();
+
+        d[5] = mass_density(&d);
+        let (_, temperature) = self.densu(z.abs(), 1.0, tinf, tlb, 0.0, 0.0, zlb, s);
+        (d, [tinf, temperature])
+    }
+
+    /// Evaluates the model at the altitude of the input, in the units of `gts7`.
+    fn gtd7(&mut self) -> ([f64; 9], [f64; 2]) {
+        let p = self.p;
+        let sw = self.sw;
+        let alt = self.input.altitude;
+        let xmm = p.pdm[2][4];
+
+        // Thermosphere and mesosphere, above zn2[0]
+        let (thermosphere, temperatures) = self.gts7(alt.max(ZN2[0]));
+        if alt >= ZN2[0] {
+            return (thermosphere, temperatures);
+        }
+
+        // Lower mesosphere and upper stratosphere, between zn3[0] and zn2[0]: temperature at
+        // the nodes and gradients at the end nodes, the inverse temperature being a linear
+        // function of spherical harmonics
+        self.tgn2[0] = self.tgn1[1];
+        self.tn2[0] = self.tn1[4];
+        self.tn2[1] = p.pma[0][0] * p.pavgm[0] / (1.0 - sw[20] * self.glob7s(&p.pma[0]));
+        self.tn2[2] = p.pma[1][0] * p.pavgm[1] / (1.0 - sw[20] * self.glob7s(&p.pma[1]));
+        self.tn2[3] = p.pma[2][0] * p.pavgm[2] / (1.0 - sw[20] * sw[22] * self.glob7s(&p.pma[2]));
+        self.tgn2[1] = p.pavgm[8]
+            * p.pma[9][0]
+            * (1.0 + sw[20] * sw[22] * self.glob7s(&p.pma[9]))
+            * self.tn2[3]
+            * self.tn2[3]
+            / (p.pma[2][0] * p.pavgm[2]).powi(2);
+        self.tn3[0] = self.tn2[3];
+
+        // Lower stratosphere and troposphere, below zn3[0]
+        if alt < ZN3[0] {
+            self.tgn3[0] = self.tgn2[1];
+            for k in 1..5 {
+                let pma = &p.pma[k + 2];
+                self.tn3[k] = pma[0] * p.pavgm[k + 2] / (1.0 - sw[22] * self.glob7s(pma));
+            }
+            self.tgn3[1] = p.pma[7][0]
+                * p.pavgm[7]
+                * (1.0 + sw[22] * self.glob7s(&p.pma[7]))
+                * self.tn3[4]
+                * self.tn3[4]
+                / (p.pma[6][0] * p.pavgm[6]).powi(2);
+        }
+
+        // Linear transition to full mixing below zn2[0]
+        let dmc = if alt > ZMIX {
+            1.0 - (ZN2[0] - alt) / (ZN2[0] - ZMIX)
+        } else {
+            0.0
+        };
+        let dz28 = thermosphere[2];
+        let mut d = [0.0; 9];
+
+        // N₂
+        let dmr = thermosphere[2] / self.dm28 - 1.0;
+        d[2] = self.densm(alt, self.dm28, xmm).0 * (1.0 + dmr * dmc);
+        // He, O₂ and Ar, in the mixing ratios of the ground; no O, H, N nor anomalous O
+        for (i, j) in [(0, 0), (3, 3), (4, 4)] {
+            let dmr = thermosphere[i] / (dz28 * p.pdm[j][1]) - 1.0;
+            d[i] = d[2] * p.pdm[j][1] * (1.0 + dmr * dmc);
+        }
+        d[5] = mass_density(&d);
+
+        let (_, temperature) = self.densm(alt, 1.0, 0.0);
+        (d, [temperatures[0], temperature])
+    }
+}
+
+/// Returns the mass density in g/cm³ from the number densities in 1/cm³, without anomalous O.
+fn mass_density(d: &[f64; 9]) -> f64 {
+    1.66e-24
+        * (4.0 * d[0] + 16.0 * d[1] + 28.0 * d[2] + 32.0 * d[3] + 40.0 * d[4] + d[6] + 14.0 * d[7])
+}
+
+/// Magnetic activity function of a 3-hour ap.
+fn g0(a: f64, p: &[f64]) -> f64 {
+    let p24 = p[24].max(1e-4);
+    a - 4.0 + (p[25] - 1.0) * (a - 4.0 + ((-p24 * (a - 4.0)).exp() - 1.0) / p24)
+}
+
+fn sumex(ex: f64) -> f64 {
+    1.0 + (1.0 - ex.powi(19)) / (1.0 - ex) * ex.sqrt()
+}
+
+/// Weighted magnetic activity of the ap history.
+fn sg0(ex: f64, p: &[f64], ap: &[f64; 7]) -> f64 {
+    (g0(ap[1], p)
+        + (g0(ap[2], p) * ex
+            + g0(ap[3], p) * ex * ex
+            + g0(ap[4], p) * ex.powi(3)
+            + (g0(ap[5], p) * ex.powi(4) + g0(ap[6], p) * ex.powi(12)) * (1.0 - ex.powi(8))
+                / (1.0 - ex)))
+        / sumex(ex)
+}
+
+/// Chemistry and dissociation correction.
+fn ccor(alt: f64, r: f64, h1: f64, zh: f64) -> f64 {
+    let e = (alt - zh) / h1;
+    if e > 70.0 {
+        1.0
+    } else if e < -70.0 {
+        r.exp()
+    } else {
+        (r / (1.0 + e.exp())).exp()
+    }
+}
+
+/// Chemistry and dissociation correction with two scale heights.
+fn ccor2(alt: f64, r: f64, h1: f64, zh: f64, h2: f64) -> f64 {
+    let e1 = (alt - zh) / h1;
+    let e2 = (alt - zh) / h2;
+    if e1 > 70.0 || e2 > 70.0 {
+        1.0
+    } else if e1 < -70.0 && e2 < -70.0 {
+        r.exp()
+    } else {
+        (r / (1.0 + 0.5 * (e1.exp() + e2.exp()))).exp()
+    }
+}
+
+/// Combines the diffusive density `dd` and the mixed density `dm` into the turbopause.
+fn dnet(dd: f64, dm: f64, zhm: f64, xmm: f64, xm: f64) -> f64 {
+    if !(dm > 0.0 && dd > 0.0) {
+        if dd == 0.0 && dm == 0.0 {
+            return 1.0;
+        }
+        return if dm == 0.0 { dd } else { dm };
+    }
+    let a = zhm / (xmm - xm);
+    let ylog = a * (dm / dd).ln();
+    if ylog < -10.0 {
+        dd
+    } else if ylog > 10.0 {
+        dm
+    } else {
+        dd * (1.0 + ylog.exp()).powf(1.0 / a)
+    }
+}
+
+/// Computes the second derivatives of the cubic spline through the points, with the given
+/// first derivatives at the ends, a natural end for a derivative above 10³⁰.
+fn spline(x: &[f64], y: &[f64], yp1: f64, ypn: f64) -> Vec<f64> {
+    let n = x.len();
+    let mut y2 = vec![0.0; n];
+    let mut u = vec![0.0; n];
+    if yp1 <= 0.99e30 {
+        y2[0] = -0.5;
+        u[0] = 3.0 / (x[1] - x[0]) * ((y[1] - y[0]) / (x[1] - x[0]) - yp1);
+    }
+    for i in 1..n - 1 {
+        let sig = (x[i] - x[i - 1]) / (x[i + 1] - x[i - 1]);
+        let p = sig * y2[i - 1] + 2.0;
+        y2[i] = (sig - 1.0) / p;
+        u[i] = (6.0
+            * ((y[i + 1] - y[i]) / (x[i + 1] - x[i]) - (y[i] - y[i - 1]) / (x[i] - x[i - 1]))
+            / (x[i + 1] - x[i - 1])
+            - sig * u[i - 1])
+            / p;
+    }
+    let (qn, un) = if ypn > 0.99e30 {
+        (0.0, 0.0)
+    } else {
+        let h = x[n - 1] - x[n - 2];
+        (0.5, 3.0 / h * (ypn - (y[n - 1] - y[n - 2]) / h))
+    };
+    y2[n - 1] = (un - qn * u[n - 2]) / (qn * y2[n - 2] + 1.0);
+    for k in (0..n - 1).rev() {
+        y2[k] = y2[k] * y2[k + 1] + u[k];
+    }
+    y2
+}
+
+/// Interpolates the cubic spline at `x`.
+fn splint(xa: &[f64], ya: &[f64], y2a: &[f64], x: f64) -> f64 {
+    let (mut klo, mut khi) = (0, xa.len() - 1);
+    while khi - klo > 1 {
+        let k = (khi + klo) / 2;
+        if xa[k] > x {
+            khi = k;
+        } else {
+            klo = k;
+        }
+    }
+    let h = xa[khi] - xa[klo];
+    let a = (xa[khi] - x) / h;
+    let b = (x - xa[klo]) / h;
+    a * ya[klo]
+        + b * ya[khi]
+        + ((a * a * a - a) * y2a[klo] + (b * b * b - b) * y2a[khi]) * h * h / 6.0
+}
+
+/// Integrates the cubic spline from its first node to `x`.
+fn splini(xa: &[f64], ya: &[f64], y2a: &[f64], x: f64) -> f64 {
+    let n = xa.len();
+    let mut yi = 0.0;
+    let (mut klo, mut khi) = (0, 1);
+    while x > xa[klo] && khi < n {
+        let xx = if khi < n - 1 { x.min(xa[khi]) } else { x };
+        let h = xa[khi] - xa[klo];
+        let a = (xa[khi] - xx) / h;
+        let b = (xx - xa[klo]) / h;
+        let (a2, b2) = (a * a, b * b);
+        yi += ((1.0 - a2) * ya[klo] / 2.0
+            + b2 * ya[khi] / 2.0
+            + ((-(1.0 + a2 * a2) / 4.0 + a2 / 2.0) * y2a[klo]
+                + (b2 * b2 / 4.0 - b2 / 2.0) * y2a[khi])
+                * h
+                * h
+                / 6.0)
+            * h;
+        klo += 1;
+        khi += 1;
+    }
+    yi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes arrays of the given names and lengths in the layout of `nrlmsise-00_data.c`.
+    fn source(lengths: &[(&str, &str, usize)]) -> String {
+        let mut text = String::from("/* NRLMSISE-00 coefficients */\n#include \"nrlmsise-00.h\"\n");
+        for &(name, dimensions, length) in lengths {
+            text.push_str(&format!(
+                "/* {name} */\ndouble {name}{dimensions} = {{\n  {{\n"
+            ));
+            let values: Vec<_> = (0..length)
+                .map(|i| format!("{:.5E}", i as f64 * -0.5))
+                .collect();
+            for chunk in values.chunks(5) {
+                text.push_str(&format!("    {}, // values\n", chunk.join(", ")));
+            }
+            text.push_str("  }\n};\n");
+        }
+        text
+    }
+
+    const ARRAYS: [(&str, &str, usize); 10] = [
+        ("pt", "[150]", 150),
+        ("pd", "[9][150]", 1350),
+        ("ps", "[150]", 150),
+        ("pdl", "[2][25]", 50),
+        ("ptl", "[4][100]", 400),
+        ("pma", "[10][100]", 1000),
+        ("sam", "[100]", 100),
+        ("ptm", "[10]", 10),
+        ("pdm", "[8][10]", 80),
+        ("pavgm", "[10]", 10),
+    ];
+
+    #[test]
+    fn parse_test() {
+        let coefficients = Nrlmsise00Coefficients::parse_c(&source(&ARRAYS)).unwrap();
+        assert_eq!(coefficients.pt[3], -1.5);
+        assert_eq!(coefficients.pd[1][2], -76.0);
+        assert_eq!(coefficients.pdl[1][24], -24.5);
+        assert_eq!(coefficients.pma[9][99], -499.5);
+        assert_eq!(coefficients.pdm[7][9], -39.5);
+        assert_eq!(coefficients.pavgm[9], -4.5);
+
+        let mut arrays = ARRAYS;
+        arrays[8].2 = 79;
+        assert_eq!(
+            Nrlmsise00Coefficients::parse_c(&source(&arrays)),
+            Err(NrlmsiseError::InvalidArray("pdm"))
+        );
+        assert_eq!(
+            Nrlmsise00Coefficients::parse_c(&source(&ARRAYS[1..])),
+            Err(NrlmsiseError::MissingArray("pt"))
+        );
+        let text = source(&ARRAYS).replace("-1.50000E0", "-1.5x");
+        assert_eq!(
+            Nrlmsise00Coefficients::parse_c(&text),
+            Err(NrlmsiseError::InvalidArray("pt"))
+        );
+    }
+
+    #[test]
+    fn spline_test() {
+        // A spline with the derivatives of a cubic at its ends reproduces the cubic
+        let x = [0.0, 0.3, 0.5, 0.8, 1.0];
+        let y: Vec<_> = x.iter().map(|x: &f64| x.powi(3)).collect();
+        let y2 = spline(&x, &y, 0.0, 3.0);
+        for (x, y2) in x.iter().zip(&y2) {
+            assert!((y2 - 6.0 * x).abs() < 1e-12);
+        }
+        for t in [0.0, 0.1, 0.3, 0.65, 0.9, 1.0] {
+            assert!((splint(&x, &y, &y2, t) - t.powi(3)).abs() < 1e-12);
+            assert!((splini(&x, &y, &y2, t) - t.powi(4) / 4.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn corrections_test() {
+        assert_eq!(ccor(500.0, 0.5, 10.0, 100.0), 1.0);
+        assert_eq!(ccor(0.0, 0.5, 1.0, 100.0), 0.5f64.exp());
+        assert!((ccor(100.0, 0.5, 10.0, 100.0) - 0.25f64.exp()).abs() < 1e-15);
+        assert!((ccor2(100.0, 0.5, 10.0, 100.0, 20.0) - 0.25f64.exp()).abs() < 1e-15);
+
+        // The net density follows the mixed density below the turbopause and the diffusive
+        // density above
+        assert_eq!(dnet(1e10, 1e-10, 28.0, 28.95, 16.0), 1e10);
+        assert_eq!(dnet(1e-10, 1e10, 28.0, 28.95, 16.0), 1e10);
+        let net = dnet(2.0, 2.0, 28.0, 28.95, 16.0);
+        assert!((net - 2.0 * 2f64.powf((28.95 - 16.0) / 28.0)).abs() < 1e-12);
+        assert_eq!(dnet(0.0, 3.0, 28.0, 28.95, 16.0), 3.0);
+    }
+}
@@ -0,0 +1,396 @@
+use std::fmt;
+
+use crate::datetime::{DateTime, TimeDelta, TimeScale};
+
+/// Error raised when reading space weather indices
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpaceWeatherError {
+    /// A column of the header is missing
+    MissingColumn(&'static str),
+    /// A line could not be decoded, or its date does not follow the previous one
+    InvalidLine { line: usize },
+}
+
+impl fmt::Display for SpaceWeatherError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpaceWeatherError::MissingColumn(column) => write!(f, "missing column {column}"),
+            SpaceWeatherError::InvalidLine { line } => write!(f, "line {line}: invalid line"),
+        }
+    }
+}
+
+impl std::error::Error for SpaceWeatherError {}
+
+/// Solar and geomagnetic indices of one day
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyIndices {
+    /// Start of the day, at 0h UTC
+    pub date: DateTime,
+    /// Planetary Kp of the eight 3-hour intervals of the day, if given
+    pub kp: Option<[f64; 8]>,
+    /// Planetary ap of the eight 3-hour intervals of the day, if given
+    pub ap: Option<[f64; 8]>,
+    /// Daily planetary Ap
+    pub ap_average: f64,
+    /// Observed 10.7 cm solar radio flux in solar flux units
+    pub f107_observed: f64,
+    /// 10.7 cm solar radio flux adjusted to 1 AU
+    pub f107_adjusted: f64,
+    /// Average of the observed flux over 81 days centered on the day
+    pub f107_observed_center81: f64,
+    /// Average of the observed flux over the 81 days ending on the day
+    pub f107_observed_last81: f64,
+}
+
+/// Indices driving a model of the atmosphere at an instant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActivityIndices {
+    /// Observed F10.7 of the previous day
+    pub f107: f64,
+    /// Average of the observed F10.7 over 81 days centered on the day
+    pub f107_average: f64,
+    /// Daily Ap
+    pub ap: f64,
+    /// History of ap used by NRLMSISE-00: the daily Ap, the 3-hour ap of the current interval
+    /// and of the intervals 3, 6 and 9 hours before, and the averages of the eight 3-hour ap
+    /// from 12 to 33 and from 36 to 57 hours before
+    pub ap_history: [f64; 7],
+    /// 3-hour Kp 6.7 hours before the instant, the lag of the geomagnetic effect in Jacchia's
+    /// models
+    pub kp: f64,
+}
+
+impl ActivityIndices {
+    /// Constructs indices of constant activity from the daily and average F10.7 and Ap.
+    pub fn constant(f107: f64, f107_average: f64, ap: f64) -> Self {
+        ActivityIndices {
+            f107,
+            f107_average,
+            ap,
+            ap_history: [ap; 7],
+            kp: kp_from_ap(ap),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Source {
+    Table(Vec<DailyIndices>),
+    Constant(ActivityIndices),
+}
+
+/// Space weather indices, either tabulated by day or constant
+///
+/// Tables are read from the CSV files of CelesTrak (`SW-All.csv`), whose consecutive days are
+/// looked up in UTC.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::orbit::force::drag::SpaceWeather;
+///
+/// let text = "DATE,BSRN,ND,KP1,KP2,KP3,KP4,KP5,KP6,KP7,KP8,KP_SUM,AP1,AP2,AP3,AP4,AP5,AP6,AP7,AP8,AP_AVG,CP,C9,ISN,F10.7_OBS,F10.7_ADJ,F10.7_DATA_TYPE,F10.7_OBS_CENTER81,F10.7_OBS_LAST81,F10.7_ADJ_CENTER81,F10.7_ADJ_LAST81
+/// 2024-05-10,2600,24,33,40,50,70,83,83,87,90,537,18,27,48,132,236,236,300,400,175,2.2,9,196,203.7,199.5,OBS,174.4,167.2,171.0,164.0
+/// 2024-05-11,2600,25,87,87,80,83,73,57,47,40,553,300,300,207,236,111,56,39,27,160,2.2,9,214,195.1,191.1,OBS,173.8,167.9,170.4,164.7
+/// ";
+/// let weather = SpaceWeather::parse_csv(text).unwrap();
+/// let dt = DateTime::gregorian(2024, 5, 11, 7, 0, 0.0).unwrap();
+/// let day = weather.day(&dt).unwrap();
+/// assert_eq!(day.ap_average, 160.0);
+/// assert_eq!(day.kp.unwrap()[2], 8.0);
+/// // The history of ap used by NRLMSISE-00 reaches 57 hours back
+/// assert_eq!(weather.indices(&dt), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpaceWeather {
+    source: Source,
+}
+
+impl SpaceWeather {
+    /// Returns constant indices at every instant.
+    pub fn constant(indices: ActivityIndices) -> Self {
+        SpaceWeather {
+            source: Source::Constant(indices),
+        }
+    }
+
+    /// Parses a CSV file of CelesTrak, whose columns are identified by the header.
+    ///
+    /// The 3-hour Kp and ap may be empty, as in the predicted part of the files; they are then
+    /// replaced by the daily values.
+    pub fn parse_csv(text: &str) -> Result<Self, SpaceWeatherError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let header: Vec<_> = match lines.next() {
+            Some((_, line)) => line.split(',').map(str::trim).collect(),
+            None => return Err(SpaceWeatherError::MissingColumn("DATE")),
+        };
+        let column = |name: &'static str| {
+            header
+                .iter()
+                .position(|column| *column == name)
+                .ok_or(SpaceWeatherError::MissingColumn(name))
+        };
+        let columns = |names: [&'static str; 8]| -> Result<[usize; 8], SpaceWeatherError> {
+            let mut indices = [0; 8];
+            for (index, name) in indices.iter_mut().zip(names) {
+                *index = column(name)?;
+            }
+            Ok(indices)
+        };
+        let date_column = column("DATE")?;
+        let kp_columns = columns(["KP1", "KP2", "KP3", "KP4", "KP5", "KP6", "KP7", "KP8"])?;
+        let ap_columns = columns(["AP1", "AP2", "AP3", "AP4", "AP5", "AP6", "AP7", "AP8"])?;
+        let numbers = [
+            column("AP_AVG")?,
+            column("F10.7_OBS")?,
+            column("F10.7_ADJ")?,
+            column("F10.7_OBS_CENTER81")?,
+            column("F10.7_OBS_LAST81")?,
+        ];
+
+        let mut days: Vec<DailyIndices> = Vec::new();
+        for (index, line) in lines {
+            let invalid = SpaceWeatherError::InvalidLine { line: index + 1 };
+            let fields: Vec<_> = line.split(',').map(str::trim).collect();
+            let field = |column: usize| fields.get(column).copied().unwrap_or("");
+            let date = parse_date(field(date_column)).ok_or(invalid.clone())?;
+            if let Some(previous) = days.last() {
+                let step = (date - previous.date).total_seconds();
+                if !(86_400.0..=86_401.0).contains(&step) {
+                    return Err(invalid);
+                }
+            }
+            let slots = |columns: [usize; 8], scale: f64| -> Result<_, SpaceWeatherError> {
+                if columns.iter().all(|&column| field(column).is_empty()) {
+                    return Ok(None);
+                }
+                let mut values = [0.0; 8];
+                for (value, column) in values.iter_mut().zip(columns) {
+                    *value = field(column).parse::<f64>().map_err(|_| invalid.clone())? * scale;
+                }
+                Ok(Some(values))
+            };
+            let (kp, ap) = (slots(kp_columns, 0.1)?, slots(ap_columns, 1.0)?);
+            let mut values = [0.0; 5];
+            for (value, column) in values.iter_mut().zip(numbers) {
+                *value = field(column).parse().map_err(|_| invalid.clone())?;
+            }
+            let [ap_average, f107_observed, f107_adjusted, center81, last81] = values;
+            days.push(DailyIndices {
+                date,
+                kp,
+                ap,
+                ap_average,
+                f107_observed,
+                f107_adjusted,
+                f107_observed_center81: center81,
+                f107_observed_last81: last81,
+            });
+        }
+        Ok(SpaceWeather {
+            source: Source::Table(days),
+        })
+    }
+
+    /// Returns the tabulated days, which are empty for constant indices.
+    pub fn days(&self) -> &[DailyIndices] {
+        match &self.source {
+            Source::Table(days) => days,
+            Source::Constant(_) => &[],
+        }
+    }
+
+    /// Returns the indices of the day containing the instant.
+    pub fn day(&self, dt: &DateTime) -> Option<&DailyIndices> {
+        self.locate(dt).map(|(index, _)| &self.days()[index])
+    }
+
+    /// Returns the indices driving a model of the atmosphere at the given instant, or `None` if
+    /// the table does not cover the instant and the preceding three days.
+    pub fn indices(&self, dt: &DateTime) -> Option<ActivityIndices> {
+        let days = match &self.source {
+            Source::Table(days) => days,
+            Source::Constant(indices) => return Some(*indices),
+        };
+        let (index, slot) = self.locate(dt)?;
+        let day = &days[index];
+        let interval = (index * 8 + slot) as isize;
+        let ap = |offset: isize| -> Option<f64> {
+            let interval = usize::try_from(interval - offset).ok()?;
+            let day = &days[interval / 8];
+            Some(day.ap.map_or(day.ap_average, |ap| ap[interval % 8]))
+        };
+        let average = |start: isize| -> Option<f64> {
+            (start..start + 8)
+                .map(ap)
+                .sum::<Option<f64>>()
+                .map(|sum| sum / 8.0)
+        };
+
+        let lagged = *dt - TimeDelta::minutes(402.0);
+        let (kp_index, kp_slot) = self.locate(&lagged)?;
+        let kp_day = &days[kp_index];
+        Some(ActivityIndices {
+            f107: days[index.checked_sub(1)?].f107_observed,
+            f107_average: day.f107_observed_center81,
+            ap: day.ap_average,
+            ap_history: [
+                day.ap_average,
+                ap(0)?,
+                ap(1)?,
+                ap(2)?,
+                ap(3)?,
+                average(4)?,
+                average(12)?,
+            ],
+            kp: kp_day
+                .kp
+                .map_or(kp_from_ap(kp_day.ap_average), |kp| kp[kp_slot]),
+        })
+    }
+
+    /// Returns the index of the day containing the instant and the index of its 3-hour
+    /// interval.
+    fn locate(&self, dt: &DateTime) -> Option<(usize, usize)> {
+        let days = self.days();
+        let index = days.partition_point(|day| day.date <= *dt).checked_sub(1)?;
+        let seconds = (*dt - days[index].date).total_seconds();
+        let end = match days.get(index + 1) {
+            Some(next) => (next.date - days[index].date).total_seconds(),
+            None => 86_400.0,
+        };
+        (seconds < end).then(|| (index, ((seconds / 10_800.0) as usize).min(7)))
+    }
+}
+
+/// Parses a date written `YYYY-MM-DD` as its start in UTC.
+fn parse_date(text: &str) -> Option<DateTime> {
+    let mut parts = text.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    DateTime::gregorian_with_scale(year, month, day, 0, 0, 0.0, TimeScale::UTC)
+}
+
+/// Equivalent ap of the Kp indices 0o, 0+, 1-, 1o, ... 9o, by steps of one third
+const AP_OF_KP: [f64; 28] = [
+    0.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 9.0, 12.0, 15.0, 18.0, 22.0, 27.0, 32.0, 39.0, 48.0, 56.0,
+    67.0, 80.0, 94.0, 111.0, 132.0, 154.0, 179.0, 207.0, 236.0, 300.0, 400.0,
+];
+
+/// Converts an ap index to Kp by linear interpolation of the standard conversion table.
+pub fn kp_from_ap(ap: f64) -> f64 {
+    let index = AP_OF_KP
+        .partition_point(|&value| value <= ap)
+        .clamp(1, AP_OF_KP.len() - 1);
+    let (lower, upper) = (AP_OF_KP[index - 1], AP_OF_KP[index]);
+    let fraction = ((ap - lower) / (upper - lower)).clamp(0.0, 1.0);
+    (index - 1) as f64 / 3.0 + fraction / 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "DATE,BSRN,ND,KP1,KP2,KP3,KP4,KP5,KP6,KP7,KP8,KP_SUM,AP1,AP2,AP3,AP4,AP5,AP6,AP7,AP8,AP_AVG,CP,C9,ISN,F10.7_OBS,F10.7_ADJ,F10.7_DATA_TYPE,F10.7_OBS_CENTER81,F10.7_OBS_LAST81,F10.7_ADJ_CENTER81,F10.7_ADJ_LAST81";
+
+    fn table() -> String {
+        let mut text = format!("{HEADER}\n");
+        for day in 1..=4 {
+            let ap: Vec<_> = (0..8).map(|slot| (10 * day + slot).to_string()).collect();
+            text.push_str(&format!(
+                "2024-01-0{day},2596,1,10,10,10,10,10,10,10,{},80,{},{},0.5,2,100,{}.0,150.0,OBS,140.0,130.0,141.0,131.0\n",
+                10 * day,
+                ap.join(","),
+                10 * day + 4,
+                150 + day,
+            ));
+        }
+        text.push_str(
+            "2024-01-05,2596,1,,,,,,,,,,,,,,,,,,12,,,,160.0,160.0,PRD,141.0,131.0,141.0,131.0\n",
+        );
+        text
+    }
+
+    #[test]
+    fn parse_test() {
+        let weather = SpaceWeather::parse_csv(&table()).unwrap();
+        assert_eq!(weather.days().len(), 5);
+        let day = &weather.days()[1];
+        assert_eq!(day.kp.unwrap()[7], 2.0);
+        assert_eq!(day.ap.unwrap()[3], 23.0);
+        assert_eq!(day.f107_observed, 152.0);
+        assert_eq!(weather.days()[4].ap, None);
+
+        let dt = DateTime::gregorian(2024, 1, 4, 13, 30, 0.0).unwrap();
+        assert_eq!(weather.day(&dt).unwrap().ap_average, 44.0);
+        let indices = weather.indices(&dt).unwrap();
+        assert_eq!(indices.f107, 153.0);
+        assert_eq!(indices.f107_average, 140.0);
+        // 3-hour intervals back from the fifth of 2024-01-04
+        assert_eq!(&indices.ap_history[..5], &[44.0, 44.0, 43.0, 42.0, 41.0]);
+        // Intervals 12 to 33 hours before: 2024-01-04 00h back to 2024-01-03 03h
+        let expected = (40.0 + 37.0 + 36.0 + 35.0 + 34.0 + 33.0 + 32.0 + 31.0) / 8.0;
+        assert_eq!(indices.ap_history[5], expected);
+        // 6.7 hours before is in the third interval of the day
+        assert_eq!(indices.kp, 1.0);
+
+        // Too early for the ap history, and after the table
+        assert_eq!(
+            weather.indices(&DateTime::gregorian(2024, 1, 2, 12, 0, 0.0).unwrap()),
+            None
+        );
+        assert_eq!(
+            weather.indices(
+                &DateTime::gregorian_with_scale(2024, 1, 6, 0, 0, 0.0, TimeScale::UTC).unwrap()
+            ),
+            None
+        );
+        let predicted = weather
+            .indices(&DateTime::gregorian(2024, 1, 5, 12, 0, 0.0).unwrap())
+            .unwrap();
+        assert_eq!(predicted.ap_history[1], 12.0);
+        assert!((predicted.kp - kp_from_ap(12.0)).abs() < 1e-15);
+    }
+
+    #[test]
+    fn error_test() {
+        let text = table();
+        assert_eq!(
+            SpaceWeather::parse_csv(&text.replace("AP_AVG", "APAVG")),
+            Err(SpaceWeatherError::MissingColumn("AP_AVG"))
+        );
+        assert_eq!(
+            SpaceWeather::parse_csv(&text.replace("2024-01-03", "2024-01-07")),
+            Err(SpaceWeatherError::InvalidLine { line: 4 })
+        );
+        assert_eq!(
+            SpaceWeather::parse_csv(&text.replace("152.0", "x")),
+            Err(SpaceWeatherError::InvalidLine { line: 3 })
+        );
+    }
+
+    #[test]
+    fn constant_test() {
+        let indices = ActivityIndices::constant(150.0, 140.0, 15.0);
+        let weather = SpaceWeather::constant(indices);
+        let dt = DateTime::gregorian(2024, 1, 4, 13, 30, 0.0).unwrap();
+        assert_eq!(weather.indices(&dt), Some(indices));
+        assert_eq!(indices.kp, 3.0);
+        assert!(weather.days().is_empty());
+    }
+
+    #[test]
+    fn kp_from_ap_test() {
+        assert_eq!(kp_from_ap(0.0), 0.0);
+        assert_eq!(kp_from_ap(27.0), 4.0);
+        assert!((kp_from_ap(3.0) - 2.0 / 3.0).abs() < 1e-15);
+        assert!((kp_from_ap(300.0) - 26.0 / 3.0).abs() < 1e-15);
+        assert_eq!(kp_from_ap(500.0), 9.0);
+    }
+}
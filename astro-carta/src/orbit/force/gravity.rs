@@ -0,0 +1,817 @@
+use std::fmt;
+
+use super::{BodyRotation, ForceError, ForceModel};
+use crate::datetime::DateTime;
+use crate::ephemeris::StateVector;
+use crate::math::{Matrix3, Vector3};
+
+/// Error raised when reading a gravity field
+#[derive(Debug, Clone, PartialEq)]
+pub enum GravityError {
+    /// A mandatory keyword of the header is missing
+    MissingKeyword(&'static str),
+    /// A line could not be decoded
+    InvalidLine { line: usize },
+    /// The degree or order of a coefficient is out of range
+    InvalidDegree { line: usize },
+}
+
+impl fmt::Display for GravityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GravityError::MissingKeyword(keyword) => write!(f, "missing keyword {keyword}"),
+            GravityError::InvalidLine { line } => write!(f, "line {line}: invalid line"),
+            GravityError::InvalidDegree { line } => {
+                write!(f, "line {line}: degree or order out of range")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GravityError {}
+
+/// Spherical-harmonic expansion of the gravitational potential of a body
+///
+/// The potential at a body-fixed position of radius `r`, latitude `φ` and longitude `λ` is
+///
+/// `U = μ/r Σ (R/r)ⁿ P̄ₙₘ(sin φ) (C̄ₙₘ cos mλ + S̄ₙₘ sin mλ)`
+///
+/// with fully normalized coefficients and Legendre functions without the Condon-Shortley
+/// phase. The potential and its derivatives are evaluated with the normalized Cunningham
+/// recursion, which is stable up to high degree and has no singularity at the poles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GravityField {
+    /// Name of the model, if given
+    pub name: Option<String>,
+    /// Gravitational parameter μ in m³/s²
+    pub gravitational_parameter: f64,
+    /// Reference radius R in meters
+    pub radius: f64,
+    /// Tide system of the coefficients, such as `tide_free` or `zero_tide`, if given
+    pub tide_system: Option<String>,
+    degree: usize,
+    cosine: Vec<f64>,
+    sine: Vec<f64>,
+}
+
+impl GravityField {
+    /// Constructs the field of a point mass, whose coefficients up to the given degree are then
+    /// set with [`set_coefficients`](GravityField::set_coefficients).
+    pub fn new(gravitational_parameter: f64, radius: f64, degree: usize) -> Self {
+        let size = index(degree + 1, 0);
+        let mut cosine = vec![0.0; size];
+        cosine[0] = 1.0;
+        GravityField {
+            name: None,
+            gravitational_parameter,
+            radius,
+            tide_system: None,
+            degree,
+            cosine,
+            sine: vec![0.0; size],
+        }
+    }
+
+    /// Parses a model in the ICGEM `.gfc` format.
+    ///
+    /// The header must give `earth_gravity_constant` (or `gravity_constant`), `radius` and
+    /// `max_degree`. Coefficients are read from `gfc` lines and from `gfct` lines, of which the
+    /// value at the reference epoch is kept: the time-variable `trnd`, `acos` and `asin` terms
+    /// are ignored. Unnormalized coefficients are normalized.
+    pub fn parse_icgem(text: &str) -> Result<Self, GravityError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line));
+        let (mut name, mut gravitational_parameter, mut radius) = (None, None, None);
+        let (mut degree, mut tide_system, mut normalized) = (None, None, true);
+        for (number, line) in lines.by_ref() {
+            let mut tokens = line.split_whitespace();
+            let (Some(keyword), value) = (tokens.next(), tokens.next()) else {
+                continue;
+            };
+            let invalid = || GravityError::InvalidLine { line: number };
+            match (keyword, value) {
+                ("end_of_head", _) => break,
+                ("modelname", Some(value)) => name = Some(value.to_string()),
+                ("earth_gravity_constant" | "gravity_constant", Some(value)) => {
+                    gravitational_parameter = Some(parse_number(value).ok_or_else(invalid)?);
+                }
+                ("radius", Some(value)) => radius = Some(parse_number(value).ok_or_else(invalid)?),
+                ("max_degree", Some(value)) => degree = Some(value.parse().map_err(|_| invalid())?),
+                ("tide_system", Some(value)) => tide_system = Some(value.to_string()),
+                ("norm", Some(value)) => normalized = value != "unnormalized",
+                _ => {}
+            }
+        }
+
+        let mut field = GravityField::new(
+            gravitational_parameter
+                .ok_or(GravityError::MissingKeyword("earth_gravity_constant"))?,
+            radius.ok_or(GravityError::MissingKeyword("radius"))?,
+            degree.ok_or(GravityError::MissingKeyword("max_degree"))?,
+        );
+        field.name = name;
+        field.tide_system = tide_system;
+        for (number, line) in lines {
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                None | Some("trnd" | "acos" | "asin") => {}
+                Some("gfc" | "gfct") => {
+                    let values: Vec<_> = tokens.collect();
+                    field.read_coefficients(number, &values, normalized)?;
+                }
+                Some(_) => return Err(GravityError::InvalidLine { line: number }),
+            }
+        }
+        Ok(field)
+    }
+
+    /// Parses the coefficients of a model in the format of the EGM96 and EGM2008 distributions:
+    /// one line per coefficient with the degree, the order, the normalized C̄ and S̄ and
+    /// optionally their standard deviations.
+    ///
+    /// Fortran `D` exponents and numbers written without separating spaces are accepted. The
+    /// files give neither the gravitational parameter nor the reference radius of the model, nor
+    /// the coefficient C̄₀₀, which is set to one.
+    pub fn parse_egm(
+        text: &str,
+        gravitational_parameter: f64,
+        radius: f64,
+    ) -> Result<Self, GravityError> {
+        let mut rows = Vec::new();
+        let mut degree = 0;
+        for (index, line) in text.lines().enumerate() {
+            let values = split_numbers(line);
+            if values.is_empty() {
+                continue;
+            }
+            let n = values[0]
+                .parse::<usize>()
+                .map_err(|_| GravityError::InvalidLine { line: index + 1 })?;
+            degree = degree.max(n);
+            rows.push((index + 1, values));
+        }
+
+        let mut field = GravityField::new(gravitational_parameter, radius, degree);
+        for (line, values) in rows {
+            let values: Vec<_> = values.iter().map(String::as_str).collect();
+            field.read_coefficients(line, &values, true)?;
+        }
+        Ok(field)
+    }
+
+    /// Stores the coefficients of a data line starting with the degree, the order, C and S.
+    fn read_coefficients(
+        &mut self,
+        line: usize,
+        values: &[&str],
+        normalized: bool,
+    ) -> Result<(), GravityError> {
+        let invalid = GravityError::InvalidLine { line };
+        let [n, m, c, s, ..] = values else {
+            return Err(invalid);
+        };
+        let (n, m) = match (n.parse(), m.parse()) {
+            (Ok(n), Ok(m)) => (n, m),
+            _ => return Err(invalid),
+        };
+        let (Some(mut c), Some(mut s)) = (parse_number(c), parse_number(s)) else {
+            return Err(invalid);
+        };
+        if m > n || n > self.degree {
+            return Err(GravityError::InvalidDegree { line });
+        }
+        if !normalized {
+            let factor = normalization(n, m);
+            c /= factor;
+            s /= factor;
+        }
+        self.set_coefficients(n, m, c, s);
+        Ok(())
+    }
+
+    /// Returns the largest degree of the coefficients.
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Returns the fully normalized coefficients C̄ₙₘ and S̄ₙₘ, or `None` if the degree or the
+    /// order is out of range.
+    pub fn coefficients(&self, n: usize, m: usize) -> Option<(f64, f64)> {
+        (m <= n && n <= self.degree).then(|| (self.cosine[index(n, m)], self.sine[index(n, m)]))
+    }
+
+    /// Sets the fully normalized coefficients C̄ₙₘ and S̄ₙₘ, returning `false` if the degree or
+    /// the order is out of range.
+    pub fn set_coefficients(&mut self, n: usize, m: usize, cosine: f64, sine: f64) -> bool {
+        if m > n || n > self.degree {
+            return false;
+        }
+        self.cosine[index(n, m)] = cosine;
+        self.sine[index(n, m)] = sine;
+        true
+    }
+
+    /// Discards the coefficients above the given degree.
+    pub fn truncate(&mut self, degree: usize) {
+        if degree < self.degree {
+            self.degree = degree;
+            self.cosine.truncate(index(degree + 1, 0));
+            self.sine.truncate(index(degree + 1, 0));
+        }
+    }
+
+    /// Computes the potential in m²/s² at a body-fixed position in meters, up to the given
+    /// degree and order.
+    pub fn potential(&self, position: &Vector3, degree: usize, order: usize) -> f64 {
+        let (degree, order) = self.limits(degree, order);
+        let harmonics = Harmonics::new(position, self.radius, degree, order);
+        let mut potential = 0.0;
+        for n in 0..=degree {
+            for m in 0..=n.min(order) {
+                let i = index(n, m);
+                potential += self.cosine[i] * harmonics.v[i] + self.sine[i] * harmonics.w[i];
+            }
+        }
+        potential * self.gravitational_parameter / self.radius
+    }
+
+    /// Computes the acceleration in m/s² at a body-fixed position in meters, up to the given
+    /// degree and order.
+    pub fn acceleration(&self, position: &Vector3, degree: usize, order: usize) -> Vector3 {
+        let (degree, order) = self.limits(degree, order);
+        let harmonics = Harmonics::new(position, self.radius, degree + 1, order + 1);
+        let mut acceleration = Vector3::zeros();
+        self.for_each_term(degree, order, |term| {
+            for axis in 0..3 {
+                for partial in term.derivatives(axis, self.radius) {
+                    acceleration[axis] += harmonics.evaluate(&partial);
+                }
+            }
+        });
+        acceleration * (self.gravitational_parameter / self.radius)
+    }
+
+    /// Computes the partial derivatives of the acceleration with respect to a body-fixed
+    /// position in meters, in 1/s², up to the given degree and order.
+    pub fn gradient(&self, position: &Vector3, degree: usize, order: usize) -> Matrix3 {
+        let (degree, order) = self.limits(degree, order);
+        let harmonics = Harmonics::new(position, self.radius, degree + 2, order + 2);
+        let mut gradient = Matrix3::new([[0.0; 3]; 3]);
+        self.for_each_term(degree, order, |term| {
+            for i in 0..3 {
+                for first in term.derivatives(i, self.radius) {
+                    for j in i..3 {
+                        for second in first.derivatives(j, self.radius) {
+                            gradient.elements[i][j] += harmonics.evaluate(&second);
+                        }
+                    }
+                }
+            }
+        });
+        let scale = self.gravitational_parameter / self.radius;
+        for i in 0..3 {
+            for j in i..3 {
+                gradient.elements[i][j] *= scale;
+                gradient.elements[j][i] = gradient.elements[i][j];
+            }
+        }
+        gradient
+    }
+
+    fn limits(&self, degree: usize, order: usize) -> (usize, usize) {
+        let degree = degree.min(self.degree);
+        (degree, order.min(degree))
+    }
+
+    /// Calls the function with the terms C̄ₙₘV̄ₙₘ and S̄ₙₘW̄ₙₘ of the expansion.
+    fn for_each_term(&self, degree: usize, order: usize, mut function: impl FnMut(Term)) {
+        for n in 0..=degree {
+            for m in 0..=n.min(order) {
+                let i = index(n, m);
+                function(Term::new(self.cosine[i], n, m, false));
+                if m > 0 {
+                    function(Term::new(self.sine[i], n, m, true));
+                }
+            }
+        }
+    }
+}
+
+/// Attraction of a central body described by a spherical-harmonic gravity field
+///
+/// The field is evaluated in the body-fixed axes given by the rotation, and the acceleration is
+/// rotated back to the inertial axes of the states.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::force::gravity::{GravityField, SphericalHarmonics};
+/// use astro_carta::orbit::force::{EarthRotation, ForceModel};
+///
+/// let text = "begin_of_head
+/// modelname EGM2008
+/// earth_gravity_constant 0.3986004415E+15
+/// radius 0.63781363E+07
+/// max_degree 2
+/// norm fully_normalized
+/// end_of_head
+/// gfc 2 0 -0.484165143790815E-03 0.0
+/// gfc 2 2 0.243938357328313E-05 -0.140027370385934E-05
+/// ";
+/// let field = GravityField::parse_icgem(text).unwrap();
+/// let gravity = SphericalHarmonics::new(field, 2, 2, EarthRotation::default());
+///
+/// let state = StateVector {
+///     position: Vector3::new(0.0, 0.0, 7e6),
+///     velocity: Vector3::zeros(),
+/// };
+/// let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let acceleration = gravity.acceleration(&dt, &state).unwrap();
+/// // The flattening weakens the attraction above the poles
+/// assert!((acceleration.z + 8.1128).abs() < 1e-3);
+/// ```
+#[derive(Debug)]
+pub struct SphericalHarmonics {
+    field: GravityField,
+    degree: usize,
+    order: usize,
+    rotation: Box<dyn BodyRotation>,
+}
+
+impl SphericalHarmonics {
+    /// Constructs the model of a field truncated to the given degree and order, which are
+    /// limited to the degree of the field.
+    pub fn new(
+        mut field: GravityField,
+        degree: usize,
+        order: usize,
+        rotation: impl BodyRotation + 'static,
+    ) -> Self {
+        field.truncate(degree);
+        let (degree, order) = field.limits(degree, order);
+        SphericalHarmonics {
+            field,
+            degree,
+            order,
+            rotation: Box::new(rotation),
+        }
+    }
+
+    /// Returns the field, truncated to the degree of the model.
+    pub fn field(&self) -> &GravityField {
+        &self.field
+    }
+
+    /// Returns the maximum degree evaluated, at most that of the field.
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Returns the maximum order evaluated, at most the degree.
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    fn body_fixed(
+        &self,
+        dt: &DateTime,
+        state: &StateVector,
+    ) -> Result<(Matrix3, Vector3), ForceError> {
+        if state.position.norm() == 0.0 {
+            return Err(ForceError::Singular);
+        }
+        let rotation = self.rotation.rotation_to_body_fixed(dt)?;
+        Ok((rotation, rotation * state.position))
+    }
+}
+
+impl ForceModel for SphericalHarmonics {
+    fn acceleration(&self, dt: &DateTime, state: &StateVector) -> Result<Vector3, ForceError> {
+        let (rotation, position) = self.body_fixed(dt, state)?;
+        let acceleration = self.field.acceleration(&position, self.degree, self.order);
+        Ok(rotation.transpose() * acceleration)
+    }
+
+    fn gradient(&self, dt: &DateTime, state: &StateVector) -> Result<Option<Matrix3>, ForceError> {
+        let (rotation, position) = self.body_fixed(dt, state)?;
+        let gradient = self.field.gradient(&position, self.degree, self.order);
+        Ok(Some(rotation.transpose() * gradient * rotation))
+    }
+}
+
+/// Index of a degree and an order in the triangular arrays of coefficients.
+fn index(n: usize, m: usize) -> usize {
+    n * (n + 1) / 2 + m
+}
+
+/// Returns the factor between the fully normalized and the unnormalized Legendre functions,
+/// `√((2 - δₘ₀)(2n + 1)(n - m)!/(n + m)!)`.
+fn normalization(n: usize, m: usize) -> f64 {
+    let log_ratio: f64 = (n - m + 1..=n + m).map(|k| (k as f64).ln()).sum();
+    let kronecker = if m == 0 { 1.0 } else { 2.0 };
+    (kronecker * (2 * n + 1) as f64).sqrt() * (-0.5 * log_ratio).exp()
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    value.replace(['D', 'd'], "E").parse().ok()
+}
+
+/// Splits a line of numbers, some of which may be written without separating spaces.
+fn split_numbers(line: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    for c in line.chars() {
+        let glued =
+            matches!(c, '-' | '+') && current.ends_with(|p: char| p.is_ascii_digit() || p == '.');
+        if (c.is_whitespace() || glued) && !current.is_empty() {
+            values.push(std::mem::take(&mut current));
+        }
+        if !c.is_whitespace() {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        values.push(current);
+    }
+    values
+}
+
+/// A multiple of the normalized harmonic V̄ₙₘ, or W̄ₙₘ for a sine term
+#[derive(Debug, Clone, Copy)]
+struct Term {
+    coefficient: f64,
+    degree: usize,
+    order: usize,
+    sine: bool,
+}
+
+impl Term {
+    fn new(coefficient: f64, degree: usize, order: usize, sine: bool) -> Self {
+        Term {
+            coefficient,
+            degree,
+            order,
+            sine,
+        }
+    }
+
+    /// Returns the terms of the partial derivative along an axis.
+    ///
+    /// The derivatives of the unnormalized harmonics are combinations of harmonics of the next
+    /// degree (Cunningham, 1970), scaled here by the ratios of the normalization factors.
+    fn derivatives(&self, axis: usize, radius: f64) -> [Term; 2] {
+        let (n, m) = (self.degree as f64, self.order as f64);
+        let (degree, order, c) = (self.degree + 1, self.order, self.coefficient / radius);
+        let k = (2.0 * n + 1.0) / (2.0 * n + 3.0);
+        let zero = Term::new(0.0, degree, order, self.sine);
+        if axis == 2 {
+            let factor = -c * (k * (n + m + 1.0) * (n - m + 1.0)).sqrt();
+            return [Term::new(factor, degree, order, self.sine), zero];
+        }
+        if order == 0 {
+            if self.sine {
+                return [zero, zero];
+            }
+            let factor = -c * (0.5 * k * (n + 1.0) * (n + 2.0)).sqrt();
+            return [Term::new(factor, degree, 1, axis == 1), zero];
+        }
+
+        let plus = 0.5 * c * (k * (n + m + 1.0) * (n + m + 2.0)).sqrt();
+        let kronecker = if order == 1 { 2.0 } else { 1.0 };
+        let minus = 0.5 * c * (kronecker * k * (n - m + 1.0) * (n - m + 2.0)).sqrt();
+        let (plus, minus, sine) = match (axis, self.sine) {
+            (0, sine) => (-plus, minus, sine),
+            (_, false) => (-plus, -minus, true),
+            (_, true) => (plus, minus, false),
+        };
+        [
+            Term::new(plus, degree, order + 1, sine),
+            Term::new(minus, degree, order - 1, sine),
+        ]
+    }
+}
+
+/// Normalized Cunningham harmonics V̄ₙₘ and W̄ₙₘ at a body-fixed position
+struct Harmonics {
+    v: Vec<f64>,
+    w: Vec<f64>,
+}
+
+impl Harmonics {
+    fn new(position: &Vector3, radius: f64, degree: usize, order: usize) -> Self {
+        let r2 = position.dot(position);
+        let (x, y, z) = (
+            radius * position.x / r2,
+            radius * position.y / r2,
+            radius * position.z / r2,
+        );
+        let rho = radius * radius / r2;
+        let size = index(degree + 1, 0);
+        let (mut v, mut w) = (vec![0.0; size], vec![0.0; size]);
+        v[0] = radius / r2.sqrt();
+
+        for m in 0..=order.min(degree) {
+            if m > 0 {
+                let (previous, current) = (index(m - 1, m - 1), index(m, m));
+                let kronecker = if m == 1 { 2.0 } else { 1.0 };
+                let factor = (kronecker * (2 * m + 1) as f64 / (2 * m) as f64).sqrt();
+                v[current] = factor * (x * v[previous] - y * w[previous]);
+                w[current] = factor * (x * w[previous] + y * v[previous]);
+            }
+            for n in m + 1..=degree {
+                let (nf, mf) = (n as f64, m as f64);
+                let a = ((2.0 * nf - 1.0) * (2.0 * nf + 1.0) / ((nf - mf) * (nf + mf))).sqrt();
+                let i = index(n, m);
+                v[i] = a * z * v[index(n - 1, m)];
+                w[i] = a * z * w[index(n - 1, m)];
+                if n >= m + 2 {
+                    let b = ((2.0 * nf + 1.0) * (nf + mf - 1.0) * (nf - mf - 1.0)
+                        / ((2.0 * nf - 3.0) * (nf + mf) * (nf - mf)))
+                        .sqrt();
+                    v[i] -= b * rho * v[index(n - 2, m)];
+                    w[i] -= b * rho * w[index(n - 2, m)];
+                }
+            }
+        }
+        Harmonics { v, w }
+    }
+
+    fn evaluate(&self, term: &Term) -> f64 {
+        let i = index(term.degree, term.order);
+        term.coefficient * if term.sine { self.w[i] } else { self.v[i] }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{EARTH_EQUATORIAL_RADIUS, EARTH_GRAVITATIONAL_PARAMETER, EARTH_J2};
+    use crate::orbit::force::{EarthRotation, PointMass};
+
+    /// A field of degree 8 with arbitrary coefficients of realistic size
+    fn field() -> GravityField {
+        let mut field =
+            GravityField::new(EARTH_GRAVITATIONAL_PARAMETER, EARTH_EQUATORIAL_RADIUS, 8);
+        for n in 2..=8 {
+            for m in 0..=n {
+                let c = 1e-6 * ((7 * n + 3 * m) as f64).sin();
+                let s = if m == 0 {
+                    0.0
+                } else {
+                    1e-6 * ((5 * n + 11 * m) as f64).cos()
+                };
+                field.set_coefficients(n, m, c, s);
+            }
+        }
+        field
+    }
+
+    /// Sums the series of the potential with Legendre functions of the latitude.
+    fn legendre_potential(field: &GravityField, position: &Vector3) -> f64 {
+        let r = position.norm();
+        let sin_latitude = position.z / r;
+        let cos_latitude = position.x.hypot(position.y) / r;
+        let longitude = position.y.atan2(position.x);
+        let degree = field.degree();
+        let mut p = vec![0.0; index(degree + 1, 0)];
+        let mut potential = 0.0;
+        for m in 0..=degree {
+            p[index(m, m)] = (1..=m).map(|k| (2 * k - 1) as f64 * cos_latitude).product();
+            for n in m..=degree {
+                if n > m {
+                    let previous = if n >= m + 2 { p[index(n - 2, m)] } else { 0.0 };
+                    p[index(n, m)] = ((2 * n - 1) as f64 * sin_latitude * p[index(n - 1, m)]
+                        - (n + m - 1) as f64 * previous)
+                        / (n - m) as f64;
+                }
+                let (c, s) = field.coefficients(n, m).unwrap();
+                let (sin, cos) = (m as f64 * longitude).sin_cos();
+                potential += (field.radius / r).powi(n as i32)
+                    * normalization(n, m)
+                    * p[index(n, m)]
+                    * (c * cos + s * sin);
+            }
+        }
+        potential * field.gravitational_parameter / r
+    }
+
+    fn positions() -> [Vector3; 4] {
+        [
+            Vector3::new(7.0e6, 0.0, 0.0),
+            Vector3::new(-3.1e6, 4.2e6, -5.3e6),
+            Vector3::new(1.0, 2.0, 7.2e6),
+            Vector3::new(2.6e7, -1.3e7, 4.0e6),
+        ]
+    }
+
+    #[test]
+    fn potential_test() {
+        let field = field();
+        for position in positions() {
+            let potential = field.potential(&position, 8, 8);
+            let expected = legendre_potential(&field, &position);
+            assert!((potential - expected).abs() < 1e-12 * expected.abs());
+
+            let h = 0.5;
+            let acceleration = field.acceleration(&position, 8, 8);
+            for axis in 0..3 {
+                let (mut forward, mut backward) = (position, position);
+                forward[axis] += h;
+                backward[axis] -= h;
+                let derivative = (field.potential(&forward, 8, 8)
+                    - field.potential(&backward, 8, 8))
+                    / (2.0 * h);
+                assert!((acceleration[axis] - derivative).abs() < 1e-7, "{axis}");
+            }
+        }
+    }
+
+    #[test]
+    fn gradient_test() {
+        let field = field();
+        for position in positions() {
+            let gradient = field.gradient(&position, 8, 6);
+            let h = 1.0;
+            for axis in 0..3 {
+                let (mut forward, mut backward) = (position, position);
+                forward[axis] += h;
+                backward[axis] -= h;
+                let derivative = (field.acceleration(&forward, 8, 6)
+                    - field.acceleration(&backward, 8, 6))
+                    / (2.0 * h);
+                for row in 0..3 {
+                    let error = gradient[(row, axis)] - derivative[row];
+                    assert!(error.abs() < 1e-12, "{row} {axis}: {error}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn j2_test() {
+        let mut field =
+            GravityField::new(EARTH_GRAVITATIONAL_PARAMETER, EARTH_EQUATORIAL_RADIUS, 2);
+        field.set_coefficients(2, 0, -EARTH_J2 / 5f64.sqrt(), 0.0);
+        for position in positions() {
+            let r = position.norm();
+            let ratio = position.z * position.z / (r * r);
+            let factor = -1.5
+                * EARTH_J2
+                * EARTH_GRAVITATIONAL_PARAMETER
+                * EARTH_EQUATORIAL_RADIUS
+                * EARTH_EQUATORIAL_RADIUS
+                / r.powi(5);
+            let perturbation = Vector3::new(
+                factor * position.x * (1.0 - 5.0 * ratio),
+                factor * position.y * (1.0 - 5.0 * ratio),
+                factor * position.z * (3.0 - 5.0 * ratio),
+            );
+            let point_mass = position * (-EARTH_GRAVITATIONAL_PARAMETER / (r * r * r));
+            let expected = point_mass + perturbation;
+            let acceleration = field.acceleration(&position, 2, 0);
+            assert!((acceleration - expected).norm() < 1e-14 * expected.norm());
+            let central = field.acceleration(&position, 0, 0);
+            assert!((central - point_mass).norm() < 1e-15 * point_mass.norm());
+        }
+    }
+
+    #[test]
+    fn force_model_test() {
+        let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+        let rotation = EarthRotation { dut1: 0.1 };
+        let gravity = SphericalHarmonics::new(field(), 12, 6, rotation);
+        assert_eq!((gravity.degree(), gravity.order()), (8, 6));
+
+        let state = StateVector {
+            position: Vector3::new(-3.1e6, 4.2e6, -5.3e6),
+            velocity: Vector3::zeros(),
+        };
+        let matrix = rotation.rotation_to_body_fixed(&dt).unwrap();
+        let body_fixed = gravity
+            .field()
+            .acceleration(&(matrix * state.position), 8, 6);
+        let acceleration = gravity.acceleration(&dt, &state).unwrap();
+        assert!((matrix * acceleration - body_fixed).norm() < 1e-14);
+
+        let gradient = gravity.gradient(&dt, &state).unwrap().unwrap();
+        let expected = gravity.field().gradient(&(matrix * state.position), 8, 6);
+        let body_fixed_gradient = matrix * gradient * matrix.transpose();
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((body_fixed_gradient[(i, j)] - expected[(i, j)]).abs() < 1e-18);
+            }
+        }
+
+        let central = SphericalHarmonics::new(field(), 0, 0, Matrix3::identity());
+        let point_mass = PointMass::new(EARTH_GRAVITATIONAL_PARAMETER);
+        let expected = point_mass.gradient(&dt, &state).unwrap().unwrap();
+        let gradient = central.gradient(&dt, &state).unwrap().unwrap();
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((gradient[(i, j)] - expected[(i, j)]).abs() < 1e-20);
+            }
+        }
+        let zero = StateVector {
+            position: Vector3::zeros(),
+            velocity: Vector3::zeros(),
+        };
+        assert_eq!(gravity.acceleration(&dt, &zero), Err(ForceError::Singular));
+    }
+
+    #[test]
+    fn parse_icgem_test() {
+        let text = "generated for testing
+begin_of_head ==========================
+product_type          gravity_field
+modelname             TEST
+earth_gravity_constant  0.3986004415E+15
+radius                0.63781363E+07
+max_degree            3
+norm                  unnormalized
+tide_system           tide_free
+key   L    M         C                  S              sigma C     sigma S
+end_of_head ============================
+gfc   0    0  1.0D+00  0.0
+gfc   2    0 -1.08262668E-03  0.0  1e-10  0.0
+gfct  2    2  1.5744E-06 -9.0387E-07  0.0 0.0  20050101
+trnd  2    2  1.0E-11 1.0E-11 0.0 0.0
+acos  2    2  1.0E-11 1.0E-11 0.0 0.0 1.0
+gfc   3    1  2.1928E-06  2.6801E-07
+";
+        let field = GravityField::parse_icgem(text).unwrap();
+        assert_eq!(field.name.as_deref(), Some("TEST"));
+        assert_eq!(field.tide_system.as_deref(), Some("tide_free"));
+        assert_eq!(
+            (field.degree(), field.gravitational_parameter),
+            (3, 3.986004415e14)
+        );
+        assert_eq!(field.coefficients(0, 0), Some((1.0, 0.0)));
+        let (c20, _) = field.coefficients(2, 0).unwrap();
+        assert!((c20 * 5f64.sqrt() + 1.08262668e-3).abs() < 1e-15);
+        let (c22, s22) = field.coefficients(2, 2).unwrap();
+        assert!((c22 - 1.5744e-6 * 2.4f64.sqrt()).abs() < 1e-15);
+        assert!(s22 < 0.0);
+        assert_eq!(field.coefficients(3, 3), Some((0.0, 0.0)));
+        assert_eq!(field.coefficients(4, 0), None);
+
+        let cases = [
+            (
+                text.replace("radius ", "radios "),
+                GravityError::MissingKeyword("radius"),
+            ),
+            (
+                text.replace("gfc   3    1", "gfc   4    1"),
+                GravityError::InvalidDegree { line: 17 },
+            ),
+            (
+                text.replace("gfc   3    1", "gfc   1    3"),
+                GravityError::InvalidDegree { line: 17 },
+            ),
+            (
+                text.replace("-1.08262668E-03", "-1.08262668F-03"),
+                GravityError::InvalidLine { line: 13 },
+            ),
+            (
+                text.replace("trnd", "rate"),
+                GravityError::InvalidLine { line: 15 },
+            ),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(GravityField::parse_icgem(&text), Err(expected));
+        }
+    }
+
+    #[test]
+    fn parse_egm_test() {
+        let text = "    2    0-0.484165371736D-03 0.000000000000D+00 0.35610635D-10 0.00000000D+00
+    2    1-0.186987635955D-09 0.119528012031D-08 0.10000000D-11 0.10000000D-11
+    2    2 0.243914352398D-05-0.140016683654D-05 0.53739154D-10 0.54353269D-10
+    3    0  0.957254173792E-06   0.0
+";
+        let mut field = GravityField::parse_egm(text, 3.986004418e14, 6378137.0).unwrap();
+        assert_eq!(field.degree(), 3);
+        assert_eq!(field.coefficients(0, 0), Some((1.0, 0.0)));
+        assert_eq!(
+            field.coefficients(2, 2),
+            Some((0.243914352398e-5, -0.140016683654e-5))
+        );
+        assert_eq!(field.coefficients(2, 0), Some((-0.484165371736e-3, 0.0)));
+        assert_eq!(field.coefficients(3, 0), Some((0.957254173792e-6, 0.0)));
+        field.truncate(2);
+        assert_eq!(field.coefficients(3, 0), None);
+
+        assert_eq!(
+            GravityField::parse_egm(&text.replace("0.0\n", "\n"), 1.0, 1.0),
+            Err(GravityError::InvalidLine { line: 4 })
+        );
+        assert_eq!(
+            GravityField::parse_egm(&text.replace("    2    1", "    2    3"), 1.0, 1.0),
+            Err(GravityError::InvalidDegree { line: 2 })
+        );
+    }
+}
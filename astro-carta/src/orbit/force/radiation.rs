@@ -0,0 +1,294 @@
+use std::f64::consts::PI;
+use std::fmt;
+
+use super::{ForceError, ForceModel};
+use crate::constants::{
+    ASTRONOMICAL_UNIT, EARTH_EQUATORIAL_RADIUS, SOLAR_IRRADIANCE, SPEED_OF_LIGHT, SUN_RADIUS,
+};
+use crate::datetime::DateTime;
+use crate::ephemeris::{Ephemeris, StateVector};
+use crate::math::Vector3;
+
+/// Model of the shadow cast by the central body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowModel {
+    /// The object is always lit.
+    None,
+    /// The shadow is a cylinder behind the body, without penumbra.
+    Cylindrical,
+    /// The shadow is the umbra and penumbra cones of the Sun and the body, the lit fraction in
+    /// the penumbra being that of the solar disk seen past the limb of the body.
+    Conical,
+}
+
+impl ShadowModel {
+    /// Returns the fraction of the solar disk seen from a position, from 0 in the umbra to 1 in
+    /// full sunlight, given the position of the Sun relative to the body and the radius of the
+    /// body in meters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use astro_carta::constants::{ASTRONOMICAL_UNIT, EARTH_EQUATORIAL_RADIUS};
+    /// use astro_carta::math::Vector3;
+    /// use astro_carta::orbit::force::radiation::ShadowModel;
+    ///
+    /// let sun = Vector3::new(ASTRONOMICAL_UNIT, 0.0, 0.0);
+    /// let behind = Vector3::new(-7e6, 0.0, 0.0);
+    /// assert_eq!(ShadowModel::Conical.fraction(&behind, &sun, EARTH_EQUATORIAL_RADIUS), 0.0);
+    /// let beside = Vector3::new(-7e6, 7e6, 0.0);
+    /// assert_eq!(ShadowModel::Conical.fraction(&beside, &sun, EARTH_EQUATORIAL_RADIUS), 1.0);
+    /// ```
+    pub fn fraction(&self, position: &Vector3, sun: &Vector3, radius: f64) -> f64 {
+        let r = position.norm();
+        if r <= radius {
+            return match self {
+                ShadowModel::None => 1.0,
+                _ => 0.0,
+            };
+        }
+        match self {
+            ShadowModel::None => 1.0,
+            ShadowModel::Cylindrical => {
+                let direction = *sun / sun.norm();
+                let along = position.dot(&direction);
+                let across = (*position - direction * along).norm();
+                if along < 0.0 && across < radius {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+            ShadowModel::Conical => {
+                let (a, b, c) = apparent_angles(position, sun, radius);
+                if c >= a + b {
+                    1.0
+                } else if c <= b - a {
+                    0.0
+                } else if c <= a - b {
+                    // Annular eclipse, the body lying within the solar disk
+                    1.0 - (b * b) / (a * a)
+                } else {
+                    let x = (c * c + a * a - b * b) / (2.0 * c);
+                    let y = (a * a - x * x).max(0.0).sqrt();
+                    let area = a * a * (x / a).clamp(-1.0, 1.0).acos()
+                        + b * b * ((c - x) / b).clamp(-1.0, 1.0).acos()
+                        - c * y;
+                    (1.0 - area / (PI * a * a)).clamp(0.0, 1.0)
+                }
+            }
+        }
+    }
+}
+
+/// Returns the apparent radii of the Sun and the central body seen from the object, and their
+/// apparent separation, in radians.
+pub(crate) fn apparent_angles(position: &Vector3, sun: &Vector3, radius: f64) -> (f64, f64, f64) {
+    let r = position.norm();
+    let to_sun = *sun - *position;
+    let d = to_sun.norm();
+    let a = (SUN_RADIUS / d).min(1.0).asin();
+    let b = (radius / r).min(1.0).asin();
+    let c = (-position.dot(&to_sun) / (r * d)).clamp(-1.0, 1.0).acos();
+    (a, b, c)
+}
+
+/// Pressure of the solar radiation on a sphere, or cannonball, orbiting a central body
+///
+/// The acceleration is `-ν P (AU / d)² C_R (A / m) u` where `P` is the radiation pressure at
+/// one astronomical unit, `d` and `u` the distance and direction of the Sun from the object,
+/// and `ν` the lit fraction of the solar disk. The position of the Sun relative to the central
+/// body is read from an ephemeris, in the axes of the states.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::rc::Rc;
+///
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::ephemeris::spk::Spk;
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::force::radiation::{ShadowModel, SolarRadiationPressure};
+/// use astro_carta::orbit::force::ForceModel;
+///
+/// let spk = Rc::new(Spk::open("de440.bsp").unwrap());
+/// let srp = SolarRadiationPressure::new(spk, 399, 20.0, 1.3, 1_000.0)
+///     .with_shadow(ShadowModel::Conical);
+/// let state = StateVector {
+///     position: Vector3::new(42_164e3, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, 3_075.0, 0.0),
+/// };
+/// let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// println!("{:?}", srp.acceleration(&dt, &state).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolarRadiationPressure<E> {
+    ephemeris: E,
+    /// NAIF ID of the central body
+    pub center: i32,
+    /// Cross-sectional area in m²
+    pub area: f64,
+    /// Radiation pressure coefficient, 1 for a black body and 2 for a mirror
+    pub reflectivity: f64,
+    /// Mass of the object in kilograms
+    pub mass: f64,
+    pub shadow: ShadowModel,
+    /// Radius of the central body casting the shadow in meters
+    pub radius: f64,
+}
+
+impl<E: Ephemeris> SolarRadiationPressure<E> {
+    /// Constructs a model around the given central body, in the shadow of a cylindrical Earth.
+    pub fn new(ephemeris: E, center: i32, area: f64, reflectivity: f64, mass: f64) -> Self {
+        SolarRadiationPressure {
+            ephemeris,
+            center,
+            area,
+            reflectivity,
+            mass,
+            shadow: ShadowModel::Cylindrical,
+            radius: EARTH_EQUATORIAL_RADIUS,
+        }
+    }
+
+    pub fn with_shadow(mut self, shadow: ShadowModel) -> Self {
+        self.shadow = shadow;
+        self
+    }
+
+    /// Sets the radius of the central body in meters, for bodies other than the Earth.
+    pub fn with_radius(mut self, radius: f64) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn ephemeris(&self) -> &E {
+        &self.ephemeris
+    }
+}
+
+impl<E: Ephemeris + fmt::Debug> ForceModel for SolarRadiationPressure<E> {
+    fn acceleration(&self, dt: &DateTime, state: &StateVector) -> Result<Vector3, ForceError> {
+        let sun = self
+            .ephemeris
+            .position(10, self.center, dt)
+            .map_err(|error| ForceError::Data(error.to_string()))?;
+        let to_sun = sun - state.position;
+        let d = to_sun.norm();
+        if d == 0.0 {
+            return Err(ForceError::Singular);
+        }
+        let fraction = self.shadow.fraction(&state.position, &sun, self.radius);
+        if fraction == 0.0 {
+            return Ok(Vector3::zeros());
+        }
+        let pressure = SOLAR_IRRADIANCE / SPEED_OF_LIGHT * (ASTRONOMICAL_UNIT / d).powi(2);
+        Ok(to_sun * (-fraction * pressure * self.reflectivity * self.area / self.mass / d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The Sun at a fixed position relative to the central body
+    #[derive(Debug)]
+    struct FixedSun(Vector3);
+
+    impl Ephemeris for FixedSun {
+        type Error = ForceError;
+
+        fn state(
+            &self,
+            target: i32,
+            center: i32,
+            _dt: &DateTime,
+        ) -> Result<StateVector, ForceError> {
+            if (target, center) != (10, 399) {
+                return Err(ForceError::Data(format!(
+                    "no state of {target} from {center}"
+                )));
+            }
+            Ok(StateVector {
+                position: self.0,
+                velocity: Vector3::zeros(),
+            })
+        }
+    }
+
+    #[test]
+    fn acceleration_test() {
+        let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+        let sun = Vector3::new(ASTRONOMICAL_UNIT, 0.0, 0.0);
+        let srp = SolarRadiationPressure::new(FixedSun(sun), 399, 10.0, 1.5, 500.0);
+        let lit = StateVector {
+            position: Vector3::new(0.0, 7e6, 0.0),
+            velocity: Vector3::zeros(),
+        };
+        let acceleration = srp.acceleration(&dt, &lit).unwrap();
+        let distance = (sun - lit.position).norm();
+        let expected =
+            SOLAR_IRRADIANCE / SPEED_OF_LIGHT * (ASTRONOMICAL_UNIT / distance).powi(2) * 1.5 * 10.0
+                / 500.0;
+        assert!((acceleration.norm() / expected - 1.0).abs() < 1e-12);
+        assert!(acceleration.x < 0.0 && (acceleration.x / expected + 1.0).abs() < 1e-8);
+
+        let eclipsed = StateVector {
+            position: Vector3::new(-7e6, 1e6, 0.0),
+            velocity: Vector3::zeros(),
+        };
+        assert_eq!(srp.acceleration(&dt, &eclipsed).unwrap(), Vector3::zeros());
+        let srp = srp.with_shadow(ShadowModel::None);
+        assert!(srp.acceleration(&dt, &eclipsed).unwrap().norm() > 0.9 * expected);
+
+        let moon = SolarRadiationPressure::new(FixedSun(sun), 301, 10.0, 1.5, 500.0);
+        assert!(matches!(
+            moon.acceleration(&dt, &lit),
+            Err(ForceError::Data(_))
+        ));
+    }
+
+    #[test]
+    fn shadow_test() {
+        let sun = Vector3::new(ASTRONOMICAL_UNIT, 0.0, 0.0);
+        let radius = EARTH_EQUATORIAL_RADIUS;
+        let at = |y: f64| Vector3::new(-7e6, y, 0.0);
+        assert_eq!(
+            ShadowModel::Cylindrical.fraction(&at(radius - 1.0), &sun, radius),
+            0.0
+        );
+        assert_eq!(
+            ShadowModel::Cylindrical.fraction(&at(radius + 1.0), &sun, radius),
+            1.0
+        );
+        assert_eq!(
+            ShadowModel::Cylindrical.fraction(&Vector3::new(7e6, 0.0, 0.0), &sun, radius),
+            1.0
+        );
+
+        // The umbra narrows and the penumbra widens behind the Earth, with a lit fraction
+        // increasing across the penumbra
+        let conical = |y: f64| ShadowModel::Conical.fraction(&at(y), &sun, radius);
+        assert_eq!(conical(radius - 40e3), 0.0);
+        assert_eq!(conical(radius + 40e3), 1.0);
+        let half = conical(radius);
+        assert!((half - 0.5).abs() < 0.05, "{half}");
+        let mut previous = 0.0;
+        for step in -40..=40 {
+            let fraction = conical(radius + step as f64 * 1e3);
+            assert!(fraction >= previous);
+            previous = fraction;
+        }
+
+        // An annular eclipse seen from far behind a small body
+        let small = 1e5;
+        let far = Vector3::new(-0.5 * ASTRONOMICAL_UNIT, 0.0, 0.0);
+        let fraction = ShadowModel::Conical.fraction(&far, &sun, small);
+        let (a, b) = (
+            SUN_RADIUS / (1.5 * ASTRONOMICAL_UNIT),
+            small / (0.5 * ASTRONOMICAL_UNIT),
+        );
+        assert!((fraction - (1.0 - (b / a).powi(2))).abs() < 1e-9);
+    }
+}
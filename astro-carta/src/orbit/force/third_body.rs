@@ -0,0 +1,242 @@
+use std::fmt;
+
+use super::{ForceError, ForceModel};
+use crate::datetime::DateTime;
+use crate::ephemeris::{Ephemeris, StateVector};
+use crate::math::{Matrix3, Vector3};
+
+/// A perturbing body of a [`ThirdBody`] model
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Perturber {
+    /// NAIF ID of the body
+    pub body: i32,
+    /// Gravitational parameter in m³/s²
+    pub gravitational_parameter: f64,
+}
+
+/// Attraction of point masses such as the Sun, the Moon and the planets, relative to the
+/// central body
+///
+/// The positions of the bodies relative to the central body are read from an ephemeris at the
+/// instant of the state, so that the states must be given in its axes, usually the ICRF. The
+/// acceleration of each body is its attraction of the object minus its attraction of the
+/// central body.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::rc::Rc;
+///
+/// use astro_carta::constants::{MOON_GRAVITATIONAL_PARAMETER, SUN_GRAVITATIONAL_PARAMETER};
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::ephemeris::spk::Spk;
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::force::third_body::ThirdBody;
+/// use astro_carta::orbit::force::ForceModel;
+///
+/// let spk = Rc::new(Spk::open("de440.bsp").unwrap());
+/// let third_body = ThirdBody::new(spk, 399)
+///     .with_body(10, SUN_GRAVITATIONAL_PARAMETER)
+///     .with_body(301, MOON_GRAVITATIONAL_PARAMETER);
+/// let state = StateVector {
+///     position: Vector3::new(42_164e3, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, 3_075.0, 0.0),
+/// };
+/// let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// println!("{:?}", third_body.acceleration(&dt, &state).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThirdBody<E> {
+    ephemeris: E,
+    /// NAIF ID of the central body
+    pub center: i32,
+    pub bodies: Vec<Perturber>,
+}
+
+impl<E: Ephemeris> ThirdBody<E> {
+    /// Constructs a model without perturbing bodies around the given central body.
+    pub fn new(ephemeris: E, center: i32) -> Self {
+        ThirdBody {
+            ephemeris,
+            center,
+            bodies: Vec::new(),
+        }
+    }
+
+    /// Adds a perturbing body with the given NAIF ID and gravitational parameter in m³/s².
+    pub fn with_body(mut self, body: i32, gravitational_parameter: f64) -> Self {
+        self.bodies.push(Perturber {
+            body,
+            gravitational_parameter,
+        });
+        self
+    }
+
+    pub fn ephemeris(&self) -> &E {
+        &self.ephemeris
+    }
+
+    /// Returns the positions of the perturbing bodies relative to the central body.
+    fn positions(&self, dt: &DateTime) -> Result<Vec<(f64, Vector3)>, ForceError> {
+        self.bodies
+            .iter()
+            .map(|perturber| {
+                self.ephemeris
+                    .position(perturber.body, self.center, dt)
+                    .map(|position| (perturber.gravitational_parameter, position))
+                    .map_err(|error| ForceError::Data(error.to_string()))
+            })
+            .collect()
+    }
+}
+
+impl<E: Ephemeris + fmt::Debug> ForceModel for ThirdBody<E> {
+    fn acceleration(&self, dt: &DateTime, state: &StateVector) -> Result<Vector3, ForceError> {
+        let mut acceleration = Vector3::zeros();
+        for (gravitational_parameter, body) in self.positions(dt)? {
+            let relative = body - state.position;
+            let (d, r) = (relative.norm(), body.norm());
+            if d == 0.0 || r == 0.0 {
+                return Err(ForceError::Singular);
+            }
+            acceleration += (relative / (d * d * d) - body / (r * r * r)) * gravitational_parameter;
+        }
+        Ok(acceleration)
+    }
+
+    fn gradient(&self, dt: &DateTime, state: &StateVector) -> Result<Option<Matrix3>, ForceError> {
+        let mut gradient = Matrix3::new([[0.0; 3]; 3]);
+        for (gravitational_parameter, body) in self.positions(dt)? {
+            let relative = state.position - body;
+            let d = relative.norm();
+            if d == 0.0 {
+                return Err(ForceError::Singular);
+            }
+            let u = relative / d;
+            let scale = gravitational_parameter / (d * d * d);
+            for i in 0..3 {
+                for j in 0..3 {
+                    let identity = if i == j { 1.0 } else { 0.0 };
+                    gradient.elements[i][j] += scale * (3.0 * u[i] * u[j] - identity);
+                }
+            }
+        }
+        Ok(Some(gradient))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{
+        ASTRONOMICAL_UNIT, EARTH_GRAVITATIONAL_PARAMETER, MOON_GRAVITATIONAL_PARAMETER,
+        SUN_GRAVITATIONAL_PARAMETER,
+    };
+
+    /// Fixed positions of bodies relative to the solar system barycenter
+    #[derive(Debug)]
+    struct Fixed(Vec<(i32, Vector3)>);
+
+    impl Ephemeris for Fixed {
+        type Error = ForceError;
+
+        fn state(
+            &self,
+            target: i32,
+            center: i32,
+            _dt: &DateTime,
+        ) -> Result<StateVector, ForceError> {
+            let position = |body| {
+                self.0
+                    .iter()
+                    .find(|(id, _)| *id == body)
+                    .map(|(_, position)| *position)
+                    .ok_or_else(|| ForceError::Data(format!("no body {body}")))
+            };
+            Ok(StateVector {
+                position: position(target)? - position(center)?,
+                velocity: Vector3::zeros(),
+            })
+        }
+    }
+
+    fn model() -> ThirdBody<Fixed> {
+        let earth = Vector3::new(ASTRONOMICAL_UNIT, 0.0, 0.0);
+        let ephemeris = Fixed(vec![
+            (10, Vector3::zeros()),
+            (399, earth),
+            (301, earth + Vector3::new(0.0, 384_400e3, 0.0)),
+        ]);
+        ThirdBody::new(ephemeris, 399)
+            .with_body(10, SUN_GRAVITATIONAL_PARAMETER)
+            .with_body(301, MOON_GRAVITATIONAL_PARAMETER)
+    }
+
+    #[test]
+    fn acceleration_test() {
+        let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+        let model = model();
+        let state = StateVector {
+            position: Vector3::new(0.0, 0.0, 42_164e3),
+            velocity: Vector3::zeros(),
+        };
+        // Perpendicular to the directions of the bodies, the tidal accelerations compress the
+        // orbit by μ r / (d² + r²)^(3/2)
+        let acceleration = model.acceleration(&dt, &state).unwrap();
+        let r = 42_164e3f64;
+        let expected = -r
+            * (SUN_GRAVITATIONAL_PARAMETER / (ASTRONOMICAL_UNIT.powi(2) + r * r).powf(1.5)
+                + MOON_GRAVITATIONAL_PARAMETER / (384_400e3f64.powi(2) + r * r).powf(1.5));
+        assert!((acceleration.z / expected - 1.0).abs() < 1e-9);
+        assert!(acceleration.x.abs() < 1e-3 * expected.abs());
+
+        // Along the direction of the Moon, the tide stretches the orbit by 2 μ r / d³
+        let state = StateVector {
+            position: Vector3::new(0.0, 42_164e3, 0.0),
+            velocity: Vector3::zeros(),
+        };
+        let moon =
+            ThirdBody::new(model.ephemeris, 399).with_body(301, MOON_GRAVITATIONAL_PARAMETER);
+        let acceleration = moon.acceleration(&dt, &state).unwrap();
+        let tidal = 2.0 * MOON_GRAVITATIONAL_PARAMETER * 42_164e3 / 384_400e3f64.powi(3);
+        assert!((acceleration.y / tidal - 1.0).abs() < 0.25);
+        assert!(acceleration.y > tidal);
+
+        let missing =
+            ThirdBody::new(Fixed(vec![(399, Vector3::zeros())]), 399).with_body(5, 1.267e17);
+        assert!(matches!(
+            missing.acceleration(&dt, &state),
+            Err(ForceError::Data(_))
+        ));
+    }
+
+    #[test]
+    fn gradient_test() {
+        let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+        let model = model();
+        let state = StateVector {
+            position: Vector3::new(7e6, -2e6, 3e6),
+            velocity: Vector3::zeros(),
+        };
+        let gradient = model.gradient(&dt, &state).unwrap().unwrap();
+        let h = 1e3;
+        for j in 0..3 {
+            let mut step = Vector3::zeros();
+            step[j] = h;
+            let shifted = |sign: f64| StateVector {
+                position: state.position + step * sign,
+                velocity: Vector3::zeros(),
+            };
+            let difference = (model.acceleration(&dt, &shifted(1.0)).unwrap()
+                - model.acceleration(&dt, &shifted(-1.0)).unwrap())
+                / (2.0 * h);
+            for i in 0..3 {
+                assert!((gradient[(i, j)] - difference[i]).abs() < 1e-18);
+            }
+        }
+        // The tidal gradient is tiny next to the central attraction
+        let central = EARTH_GRAVITATIONAL_PARAMETER / state.position.norm().powi(3);
+        assert!(gradient[(0, 0)].abs() < 1e-5 * central);
+    }
+}
@@ -0,0 +1,990 @@
+use super::force::ForceError;
+use super::numerical::PropagationError;
+use crate::datetime::TimeDelta;
+
+/// Safety factor applied to the optimal step size
+const SAFETY: f64 = 0.9;
+
+/// A Runge-Kutta method with its step-size control
+///
+/// Times are in seconds and steps are given as [`TimeDelta`]s. The adaptive methods control
+/// the local error with the embedded formulas of Dormand and Prince, as implemented by Hairer,
+/// Nørsett and Wanner in DOPRI5 and DOP853.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::orbit::integrator::{Integrator, StepControl};
+///
+/// // Exponential growth, y' = y, integrated over one unit of time
+/// let integrator = Integrator::DormandPrince853(StepControl::new(1e-12, 1e-12));
+/// let solution = integrator
+///     .integrate(0.0, &[1.0], 1.0, true, |_, y, dy| {
+///         dy[0] = y[0];
+///         Ok(())
+///     })
+///     .unwrap();
+/// assert!((solution.state()[0] - 1f64.exp()).abs() < 1e-11);
+/// assert!((solution.evaluate(0.5).unwrap()[0] - 0.5f64.exp()).abs() < 1e-11);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Integrator {
+    /// Classical fourth-order method with a fixed step
+    Rk4 { step: TimeDelta },
+    /// Fifth-order method with a fourth-order error estimate and a fourth-order interpolant
+    DormandPrince54(StepControl),
+    /// Eighth-order method with fifth- and third-order error estimates and a seventh-order
+    /// interpolant
+    DormandPrince853(StepControl),
+}
+
+/// Tolerances and bounds of the step size of an adaptive integrator
+///
+/// The error of each component of the state is weighted by
+/// `absolute_tolerance + relative_tolerance * |y|`, and a step is accepted when the root mean
+/// square of the weighted errors does not exceed one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepControl {
+    pub relative_tolerance: f64,
+    pub absolute_tolerance: f64,
+    /// First step, estimated from the derivatives at the start if not given
+    pub initial_step: Option<TimeDelta>,
+    /// Smallest step allowed before the integration fails
+    pub min_step: TimeDelta,
+    /// Largest step allowed, the whole span if not given
+    pub max_step: Option<TimeDelta>,
+    /// Largest number of steps, accepted or rejected, before the integration fails
+    pub max_steps: usize,
+}
+
+impl StepControl {
+    /// Constructs a step control with the given tolerances and default bounds.
+    pub fn new(relative_tolerance: f64, absolute_tolerance: f64) -> Self {
+        StepControl {
+            relative_tolerance,
+            absolute_tolerance,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for StepControl {
+    fn default() -> Self {
+        StepControl {
+            relative_tolerance: 1e-10,
+            absolute_tolerance: 1e-6,
+            initial_step: None,
+            min_step: TimeDelta::microseconds(1.0),
+            max_step: None,
+            max_steps: 100_000,
+        }
+    }
+}
+
+/// Butcher tableau of an explicit method
+///
+/// The row of `a` following the stages of a step holds the weights of the solution, so that
+/// the next stage is the derivative at the end of the step, reused as the first stage of the
+/// next one. Further rows are the stages of the interpolant.
+struct Tableau {
+    c: &'static [f64],
+    a: &'static [&'static [f64]],
+    /// Number of stages of a step
+    stages: usize,
+    /// Order of the error estimate plus one, the exponent of the step-size controller
+    exponent: f64,
+    /// Bounds of the ratio between successive steps
+    min_factor: f64,
+    max_factor: f64,
+}
+
+const RK4: Tableau = Tableau {
+    c: &[0.0, 0.5, 0.5, 1.0, 1.0],
+    a: &[
+        &[],
+        &[0.5],
+        &[0.0, 0.5],
+        &[0.0, 0.0, 1.0],
+        &[1.0 / 6.0, 1.0 / 3.0, 1.0 / 3.0, 1.0 / 6.0],
+    ],
+    stages: 4,
+    exponent: 5.0,
+    min_factor: 1.0,
+    max_factor: 1.0,
+};
+
+const DOPRI5: Tableau = Tableau {
+    c: &[0.0, 0.2, 0.3, 0.8, 8.0 / 9.0, 1.0, 1.0],
+    a: &[
+        &[],
+        &[0.2],
+        &[3.0 / 40.0, 9.0 / 40.0],
+        &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+        &[
+            19372.0 / 6561.0,
+            -25360.0 / 2187.0,
+            64448.0 / 6561.0,
+            -212.0 / 729.0,
+        ],
+        &[
+            9017.0 / 3168.0,
+            -355.0 / 33.0,
+            46732.0 / 5247.0,
+            49.0 / 176.0,
+            -5103.0 / 18656.0,
+        ],
+        &[
+            35.0 / 384.0,
+            0.0,
+            500.0 / 1113.0,
+            125.0 / 192.0,
+            -2187.0 / 6784.0,
+            11.0 / 84.0,
+        ],
+    ],
+    stages: 6,
+    exponent: 5.0,
+    min_factor: 0.2,
+    max_factor: 10.0,
+};
+
+/// Differences between the fifth- and fourth-order weights of DOPRI5
+const DOPRI5_E: [f64; 7] = [
+    71.0 / 57600.0,
+    0.0,
+    -71.0 / 16695.0,
+    71.0 / 1920.0,
+    -17253.0 / 339200.0,
+    22.0 / 525.0,
+    -1.0 / 40.0,
+];
+
+/// Weights of the last coefficient of the interpolant of DOPRI5
+const DOPRI5_D: [f64; 7] = [
+    -12715105075.0 / 11282082432.0,
+    0.0,
+    87487479700.0 / 32700410799.0,
+    -10690763975.0 / 1880347072.0,
+    701980252875.0 / 199316789632.0,
+    -1453857185.0 / 822651844.0,
+    69997945.0 / 29380423.0,
+];
+
+const DOP853: Tableau = Tableau {
+    c: &DOP853_C,
+    a: &DOP853_A,
+    stages: 12,
+    exponent: 8.0,
+    min_factor: 0.333,
+    max_factor: 6.0,
+};
+
+// Coefficients of DOP853, verified against the order conditions up to the eighth order for the
+// solution and the seventh for the interpolant
+const DOP853_C: [f64; 16] = [
+    0.0,
+    0.05260015195876773,
+    0.0789002279381516,
+    0.1183503419072274,
+    0.2816496580927726,
+    0.3333333333333333,
+    0.25,
+    0.3076923076923077,
+    0.6512820512820513,
+    0.6,
+    0.8571428571428571,
+    1.0,
+    1.0,
+    0.1,
+    0.2,
+    0.7777777777777778,
+];
+const DOP853_A: [&[f64]; 16] = [
+    &[],
+    &[0.05260015195876773],
+    &[0.0197250569845379, 0.0591751709536137],
+    &[0.02958758547680685, 0.0, 0.08876275643042054],
+    &[
+        0.2413651341592667,
+        0.0,
+        -0.8845494793282861,
+        0.924834003261792,
+    ],
+    &[
+        0.037037037037037035,
+        0.0,
+        0.0,
+        0.17082860872947386,
+        0.12546768756682242,
+    ],
+    &[
+        0.037109375,
+        0.0,
+        0.0,
+        0.17025221101954405,
+        0.06021653898045596,
+        -0.017578125,
+    ],
+    &[
+        0.03709200011850479,
+        0.0,
+        0.0,
+        0.17038392571223998,
+        0.10726203044637328,
+        -0.015319437748624402,
+        0.008273789163814023,
+    ],
+    &[
+        0.6241109587160757,
+        0.0,
+        0.0,
+        -3.3608926294469414,
+        -0.868219346841726,
+        27.59209969944671,
+        20.154067550477894,
+        -43.48988418106996,
+    ],
+    &[
+        0.47766253643826434,
+        0.0,
+        0.0,
+        -2.4881146199716677,
+        -0.590290826836843,
+        21.230051448181193,
+        15.279233632882423,
+        -33.28821096898486,
+        -0.020331201708508627,
+    ],
+    &[
+        -0.9371424300859873,
+        0.0,
+        0.0,
+        5.186372428844064,
+        1.0914373489967295,
+        -8.149787010746927,
+        -18.52006565999696,
+        22.739487099350505,
+        2.4936055526796523,
+        -3.0467644718982196,
+    ],
+    &[
+        2.273310147516538,
+        0.0,
+        0.0,
+        -10.53449546673725,
+        -2.0008720582248625,
+        -17.9589318631188,
+        27.94888452941996,
+        -2.8589982771350235,
+        -8.87285693353063,
+        12.360567175794303,
+        0.6433927460157636,
+    ],
+    &[
+        0.054293734116568765,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        4.450312892752409,
+        1.8915178993145003,
+        -5.801203960010585,
+        0.3111643669578199,
+        -0.1521609496625161,
+        0.20136540080403034,
+        0.04471061572777259,
+    ],
+    &[
+        0.056167502283047954,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.25350021021662483,
+        -0.2462390374708025,
+        -0.12419142326381637,
+        0.15329179827876568,
+        0.00820105229563469,
+        0.007567897660545699,
+        -0.008298,
+    ],
+    &[
+        0.03183464816350214,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.028300909672366776,
+        0.053541988307438566,
+        -0.05492374857139099,
+        0.0,
+        0.0,
+        -0.00010834732869724932,
+        0.0003825710908356584,
+        -0.00034046500868740456,
+        0.1413124436746325,
+    ],
+    &[
+        -0.42889630158379194,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        -4.697621415361164,
+        7.683421196062599,
+        4.06898981839711,
+        0.3567271874552811,
+        0.0,
+        0.0,
+        0.0,
+        -0.0013990241651590145,
+        2.9475147891527724,
+        -9.15095847217987,
+    ],
+];
+const DOP853_E3: [f64; 12] = [
+    -0.18980075407240762,
+    0.0,
+    0.0,
+    0.0,
+    0.0,
+    4.450312892752409,
+    1.8915178993145003,
+    -5.801203960010585,
+    -0.42268232132379197,
+    -0.1521609496625161,
+    0.20136540080403034,
+    0.022651792198360825,
+];
+const DOP853_E5: [f64; 12] = [
+    0.01312004499419488,
+    0.0,
+    0.0,
+    0.0,
+    0.0,
+    -1.2251564463762044,
+    -0.4957589496572502,
+    1.6643771824549864,
+    -0.35032884874997366,
+    0.3341791187130175,
+    0.08192320648511571,
+    -0.022355307863886294,
+];
+const DOP853_D: [[f64; 16]; 4] = [
+    [
+        -8.428938276109013,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.5667149535193777,
+        -3.0689499459498917,
+        2.38466765651207,
+        2.117034582445028,
+        -0.871391583777973,
+        2.2404374302607883,
+        0.6315787787694688,
+        -0.08899033645133331,
+        18.148505520854727,
+        -9.194632392478356,
+        -4.436036387594894,
+    ],
+    [
+        10.427508642579134,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        242.28349177525817,
+        165.20045171727028,
+        -374.5467547226902,
+        -22.113666853125306,
+        7.733432668472264,
+        -30.674084731089398,
+        -9.332130526430229,
+        15.697238121770845,
+        -31.139403219565178,
+        -9.35292435884448,
+        35.81684148639408,
+    ],
+    [
+        19.985053242002433,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        -387.0373087493518,
+        -189.17813819516758,
+        527.8081592054236,
+        -11.57390253995963,
+        6.8812326946963,
+        -1.0006050966910838,
+        0.7777137798053443,
+        -2.778205752353508,
+        -60.19669523126412,
+        84.32040550667716,
+        11.99229113618279,
+    ],
+    [
+        -25.69393346270375,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        -154.18974869023643,
+        -231.5293791760455,
+        357.6391179106141,
+        93.40532418362432,
+        -37.45832313645163,
+        104.0996495089623,
+        29.8402934266605,
+        -43.53345659001114,
+        96.32455395918828,
+        -39.17726167561544,
+        -149.72683625798564,
+    ],
+];
+
+/// Solution of an initial value problem, with its interpolant if it was requested
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution {
+    start: f64,
+    time: f64,
+    state: Vec<f64>,
+    steps: Vec<Step>,
+}
+
+/// An accepted step with the coefficients of its interpolant
+#[derive(Debug, Clone, PartialEq)]
+struct Step {
+    time: f64,
+    step: f64,
+    /// Coefficients `c₀, c₁, …` of `c₀ + s(c₁ + (1 - s)(c₂ + s(c₃ + …)))` for each component,
+    /// with `s` the fraction of the step
+    coefficients: Vec<Vec<f64>>,
+}
+
+impl Step {
+    fn contains(&self, time: f64) -> bool {
+        let end = self.time + self.step;
+        time >= self.time.min(end) && time <= self.time.max(end)
+    }
+
+    fn evaluate(&self, time: f64) -> Vec<f64> {
+        let s = (time - self.time) / self.step;
+        let mut state = self.coefficients[self.coefficients.len() - 1].clone();
+        for (index, coefficients) in self.coefficients.iter().enumerate().rev().skip(1) {
+            let factor = if index % 2 == 0 { s } else { 1.0 - s };
+            for (value, coefficient) in state.iter_mut().zip(coefficients) {
+                *value = coefficient + factor * *value;
+            }
+        }
+        state
+    }
+}
+
+impl Solution {
+    /// Returns the time of the initial state.
+    pub fn start(&self) -> f64 {
+        self.start
+    }
+
+    /// Returns the time of the final state.
+    pub fn end(&self) -> f64 {
+        self.time
+    }
+
+    /// Returns the final state.
+    pub fn state(&self) -> &[f64] {
+        &self.state
+    }
+
+    /// Returns the number of accepted steps, if the interpolant was requested.
+    pub fn steps(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Interpolates the state at a time between the start and the end, or returns `None` if
+    /// the time is outside this span or the interpolant was not requested.
+    pub fn evaluate(&self, time: f64) -> Option<Vec<f64>> {
+        if time == self.time {
+            return Some(self.state.clone());
+        }
+        let forward = self.time >= self.start;
+        let index = self.steps.partition_point(|step| {
+            let end = step.time + step.step;
+            if forward {
+                end < time
+            } else {
+                end > time
+            }
+        });
+        let step = self.steps.get(index)?;
+        step.contains(time).then(|| step.evaluate(time))
+    }
+}
+
+impl Integrator {
+    /// Integrates `y' = f(t, y)` from `start` to `end`, backwards if `end` precedes `start`.
+    ///
+    /// The derivative function writes `f(t, y)` into its last argument. With `dense`, the
+    /// solution keeps the interpolant of every step, at the cost of three more evaluations per
+    /// step for DOP853.
+    pub fn integrate<F>(
+        &self,
+        start: f64,
+        state: &[f64],
+        end: f64,
+        dense: bool,
+        mut derivative: F,
+    ) -> Result<Solution, PropagationError>
+    where
+        F: FnMut(f64, &[f64], &mut [f64]) -> Result<(), ForceError>,
+    {
+        let mut solution = Solution {
+            start,
+            time: start,
+            state: state.to_vec(),
+            steps: Vec::new(),
+        };
+        if end == start {
+            return Ok(solution);
+        }
+
+        // A fixed step is an adaptive one whose error estimate is always zero
+        let (tableau, control) = match *self {
+            Integrator::Rk4 { step } => {
+                if step <= TimeDelta::new(0) {
+                    return Err(PropagationError::StepSizeUnderflow);
+                }
+                let control = StepControl {
+                    initial_step: Some(step),
+                    min_step: TimeDelta::new(0),
+                    max_step: Some(step),
+                    max_steps: usize::MAX,
+                    ..Default::default()
+                };
+                (&RK4, control)
+            }
+            Integrator::DormandPrince54(control) => (&DOPRI5, control),
+            Integrator::DormandPrince853(control) => (&DOP853, control),
+        };
+        let direction = (end - start).signum();
+        let span = (end - start).abs();
+
+        let mut k = vec![vec![0.0; state.len()]; tableau.a.len()];
+        derivative(start, state, &mut k[0])?;
+
+        let min_step = control.min_step.total_seconds();
+        let max_step = control.max_step.map_or(span, |step| step.total_seconds());
+        let mut step = match control.initial_step {
+            Some(step) => step.total_seconds(),
+            None => initial_step(
+                tableau,
+                &control,
+                start,
+                state,
+                &k[0],
+                direction,
+                &mut derivative,
+            )?,
+        }
+        .min(max_step);
+
+        let mut rejected = false;
+        for _ in 0..control.max_steps {
+            let (time, y) = (solution.time, &solution.state);
+            let remaining = (end - solution.time).abs();
+            let last = step >= remaining;
+            let h = direction * if last { remaining } else { step };
+
+            let y_new = stages(
+                tableau,
+                time,
+                y,
+                h,
+                &mut k,
+                1..=tableau.stages,
+                &mut derivative,
+            )?;
+            let error = match self {
+                Integrator::Rk4 { .. } => 0.0,
+                Integrator::DormandPrince54(_) => dopri5_error(&control, y, &y_new, h, &k),
+                Integrator::DormandPrince853(_) => dop853_error(&control, y, &y_new, h, &k),
+            };
+
+            // An error that is not a number, from a state that blew up, rejects the step
+            let factor = if error.is_nan() {
+                tableau.min_factor
+            } else {
+                (SAFETY * error.powf(-1.0 / tableau.exponent))
+                    .clamp(tableau.min_factor, tableau.max_factor)
+            };
+            if error > 1.0 || error.is_nan() {
+                step = h.abs() * factor;
+                rejected = true;
+                if step < min_step {
+                    return Err(PropagationError::StepSizeUnderflow);
+                }
+                continue;
+            }
+
+            if dense {
+                let coefficients =
+                    interpolant(tableau, time, y, &y_new, h, &mut k, &mut derivative)?;
+                solution.steps.push(Step {
+                    time,
+                    step: h,
+                    coefficients,
+                });
+            }
+            solution.time = if last { end } else { time + h };
+            solution.state = y_new;
+            k.swap(0, tableau.stages);
+            if last {
+                return Ok(solution);
+            }
+
+            let factor = if rejected { factor.min(1.0) } else { factor };
+            rejected = false;
+            step = (h.abs() * factor).min(max_step);
+            if step < min_step {
+                return Err(PropagationError::StepSizeUnderflow);
+            }
+        }
+        Err(PropagationError::TooManySteps)
+    }
+}
+
+/// Evaluates the given stages of a step, returning the state of the last one.
+fn stages<F>(
+    tableau: &Tableau,
+    time: f64,
+    y: &[f64],
+    h: f64,
+    k: &mut [Vec<f64>],
+    range: std::ops::RangeInclusive<usize>,
+    derivative: &mut F,
+) -> Result<Vec<f64>, ForceError>
+where
+    F: FnMut(f64, &[f64], &mut [f64]) -> Result<(), ForceError>,
+{
+    let mut state = y.to_vec();
+    for stage in range {
+        state.copy_from_slice(y);
+        for (weight, slope) in tableau.a[stage].iter().zip(k.iter()) {
+            if *weight != 0.0 {
+                for (value, slope) in state.iter_mut().zip(slope) {
+                    *value += h * weight * slope;
+                }
+            }
+        }
+        derivative(time + tableau.c[stage] * h, &state, &mut k[stage])?;
+    }
+    Ok(state)
+}
+
+/// Returns the weight of each component in the error norm.
+fn scales<'a>(
+    control: &'a StepControl,
+    y: &'a [f64],
+    y_new: &'a [f64],
+) -> impl Iterator<Item = f64> + 'a {
+    y.iter().zip(y_new).map(|(value, new)| {
+        control.absolute_tolerance + control.relative_tolerance * value.abs().max(new.abs())
+    })
+}
+
+fn dopri5_error(control: &StepControl, y: &[f64], y_new: &[f64], h: f64, k: &[Vec<f64>]) -> f64 {
+    let sum: f64 = scales(control, y, y_new)
+        .enumerate()
+        .map(|(component, scale)| {
+            let error: f64 = DOPRI5_E
+                .iter()
+                .zip(k)
+                .map(|(weight, slope)| weight * slope[component])
+                .sum();
+            (h * error / scale).powi(2)
+        })
+        .sum();
+    (sum / y.len() as f64).sqrt()
+}
+
+/// Combines the fifth- and third-order error estimates of DOP853 as Hairer does, which keeps
+/// the estimate reliable for large steps.
+fn dop853_error(control: &StepControl, y: &[f64], y_new: &[f64], h: f64, k: &[Vec<f64>]) -> f64 {
+    let (mut error5, mut error3) = (0.0, 0.0);
+    for (component, scale) in scales(control, y, y_new).enumerate() {
+        let (mut sum5, mut sum3) = (0.0, 0.0);
+        for (stage, slope) in k.iter().take(12).enumerate() {
+            sum5 += DOP853_E5[stage] * slope[component];
+            sum3 += DOP853_E3[stage] * slope[component];
+        }
+        error5 += (sum5 / scale).powi(2);
+        error3 += (sum3 / scale).powi(2);
+    }
+    let denominator = error5 + 0.01 * error3;
+    if denominator <= 0.0 {
+        return 0.0;
+    }
+    h.abs() * error5 / (y.len() as f64 * denominator).sqrt()
+}
+
+/// Computes the coefficients of the interpolant of an accepted step, whose stages and final
+/// derivative are in `k`.
+fn interpolant<F>(
+    tableau: &Tableau,
+    time: f64,
+    y: &[f64],
+    y_new: &[f64],
+    h: f64,
+    k: &mut [Vec<f64>],
+    derivative: &mut F,
+) -> Result<Vec<Vec<f64>>, ForceError>
+where
+    F: FnMut(f64, &[f64], &mut [f64]) -> Result<(), ForceError>,
+{
+    let last = tableau.stages;
+    if tableau.a.len() > last + 1 {
+        stages(
+            tableau,
+            time,
+            y,
+            h,
+            k,
+            last + 1..=tableau.a.len() - 1,
+            derivative,
+        )?;
+    }
+
+    // Cubic Hermite interpolation between the ends of the step
+    let difference: Vec<f64> = y_new.iter().zip(y).map(|(new, old)| new - old).collect();
+    let slope: Vec<f64> = difference
+        .iter()
+        .zip(&k[0])
+        .map(|(difference, start)| h * start - difference)
+        .collect();
+    let curvature = difference
+        .iter()
+        .zip(&slope)
+        .zip(&k[last])
+        .map(|((difference, slope), end)| difference - h * end - slope)
+        .collect();
+    let mut coefficients = vec![y.to_vec(), difference, slope, curvature];
+
+    // Higher-order corrections from the stages
+    let weights: &[&[f64]] = match tableau.stages {
+        6 => &[&DOPRI5_D],
+        12 => &[&DOP853_D[0], &DOP853_D[1], &DOP853_D[2], &DOP853_D[3]],
+        _ => &[],
+    };
+    for weights in weights {
+        let mut coefficient = vec![0.0; y.len()];
+        for (weight, slope) in weights.iter().zip(k.iter()) {
+            for (value, slope) in coefficient.iter_mut().zip(slope) {
+                *value += h * weight * slope;
+            }
+        }
+        coefficients.push(coefficient);
+    }
+    Ok(coefficients)
+}
+
+/// Estimates the first step from the derivatives at the start (Hairer, Nørsett and Wanner,
+/// section II.4).
+fn initial_step<F>(
+    tableau: &Tableau,
+    control: &StepControl,
+    start: f64,
+    state: &[f64],
+    slope: &[f64],
+    direction: f64,
+    derivative: &mut F,
+) -> Result<f64, ForceError>
+where
+    F: FnMut(f64, &[f64], &mut [f64]) -> Result<(), ForceError>,
+{
+    let scales: Vec<f64> = scales(control, state, state).collect();
+    let norm = |values: &mut dyn Iterator<Item = f64>| -> f64 {
+        values
+            .zip(&scales)
+            .map(|(value, scale)| (value / scale).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    };
+    let state_norm = norm(&mut state.iter().copied());
+    let slope_norm = norm(&mut slope.iter().copied());
+    let step = if state_norm <= 1e-5 || slope_norm <= 1e-5 {
+        1e-6
+    } else {
+        0.01 * state_norm / slope_norm
+    };
+
+    let trial: Vec<f64> = state
+        .iter()
+        .zip(slope)
+        .map(|(value, slope)| value + direction * step * slope)
+        .collect();
+    let mut trial_slope = vec![0.0; state.len()];
+    derivative(start + direction * step, &trial, &mut trial_slope)?;
+    let curvature = norm(&mut trial_slope.iter().zip(slope).map(|(new, old)| new - old)) / step;
+
+    let largest = curvature.max(slope_norm);
+    let estimate = if largest <= 1e-15 {
+        (step * 1e-3).max(1e-6)
+    } else {
+        (0.01 / largest).powf(1.0 / tableau.exponent)
+    };
+    Ok((100.0 * step).min(estimate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Harmonic oscillator y'' = -y, whose solution from (1, 0) is (cos t, -sin t)
+    fn oscillator(_: f64, y: &[f64], dy: &mut [f64]) -> Result<(), ForceError> {
+        dy[0] = y[1];
+        dy[1] = -y[0];
+        Ok(())
+    }
+
+    fn error(integrator: Integrator, end: f64) -> f64 {
+        let solution = integrator
+            .integrate(0.0, &[1.0, 0.0], end, false, oscillator)
+            .unwrap();
+        (solution.state()[0] - end.cos()).abs() + (solution.state()[1] + end.sin()).abs()
+    }
+
+    #[test]
+    fn rk4_order_test() {
+        let coarse = error(
+            Integrator::Rk4 {
+                step: TimeDelta::milliseconds(100.0),
+            },
+            10.0,
+        );
+        let fine = error(
+            Integrator::Rk4 {
+                step: TimeDelta::milliseconds(50.0),
+            },
+            10.0,
+        );
+        // Halving the step divides the error of a fourth-order method by 16
+        assert!((coarse / fine - 16.0).abs() < 1.0, "{coarse} {fine}");
+        assert!(fine < 1e-6);
+    }
+
+    #[test]
+    fn adaptive_test() {
+        for tolerance in [1e-6, 1e-9, 1e-12] {
+            let control = StepControl::new(tolerance, tolerance);
+            for integrator in [
+                Integrator::DormandPrince54(control),
+                Integrator::DormandPrince853(control),
+            ] {
+                // The global error stays within a few hundred times the local tolerance
+                let error = error(integrator, 20.0);
+                assert!(error < 300.0 * tolerance, "{integrator:?}: {error}");
+            }
+        }
+
+        // The higher-order method takes much larger steps for the same tolerance
+        let control = StepControl::new(1e-12, 1e-12);
+        let steps = |integrator: Integrator| {
+            integrator
+                .integrate(0.0, &[1.0, 0.0], 20.0, true, oscillator)
+                .unwrap()
+                .steps()
+        };
+        let (low, high) = (
+            steps(Integrator::DormandPrince54(control)),
+            steps(Integrator::DormandPrince853(control)),
+        );
+        assert!(high * 5 < low, "{low} {high}");
+    }
+
+    #[test]
+    fn dense_output_test() {
+        let cases = [
+            (
+                Integrator::Rk4 {
+                    step: TimeDelta::milliseconds(10.0),
+                },
+                1e-7,
+            ),
+            (
+                Integrator::DormandPrince54(StepControl::new(1e-10, 1e-10)),
+                1e-8,
+            ),
+            (
+                Integrator::DormandPrince853(StepControl::new(1e-12, 1e-12)),
+                1e-10,
+            ),
+        ];
+        for (integrator, tolerance) in cases {
+            for end in [7.0, -7.0] {
+                let solution = integrator
+                    .integrate(0.0, &[1.0, 0.0], end, true, oscillator)
+                    .unwrap();
+                assert_eq!(solution.end(), end);
+                for index in 0..=700 {
+                    let time = end * index as f64 / 700.0;
+                    let state = solution.evaluate(time).unwrap();
+                    let error = (state[0] - time.cos()).abs() + (state[1] + time.sin()).abs();
+                    assert!(error < tolerance, "{integrator:?} at {time}: {error}");
+                }
+                assert_eq!(solution.evaluate(end * 1.01), None);
+                assert_eq!(solution.evaluate(-end * 0.01), None);
+            }
+        }
+    }
+
+    #[test]
+    fn failure_test() {
+        let control = StepControl {
+            max_steps: 10,
+            ..StepControl::new(1e-12, 1e-12)
+        };
+        let result = Integrator::DormandPrince853(control).integrate(
+            0.0,
+            &[1.0, 0.0],
+            100.0,
+            false,
+            oscillator,
+        );
+        assert_eq!(result, Err(PropagationError::TooManySteps));
+
+        // A solution blowing up in finite time, y' = y², from y(0) = 1 until t = 1
+        let result = Integrator::DormandPrince54(StepControl::default()).integrate(
+            0.0,
+            &[1.0],
+            2.0,
+            false,
+            |_, y, dy| {
+                dy[0] = y[0] * y[0];
+                Ok(())
+            },
+        );
+        assert_eq!(result, Err(PropagationError::StepSizeUnderflow));
+
+        let result = Integrator::Rk4 {
+            step: TimeDelta::new(0),
+        }
+        .integrate(0.0, &[1.0, 0.0], 1.0, false, oscillator);
+        assert_eq!(result, Err(PropagationError::StepSizeUnderflow));
+
+        let result = Integrator::Rk4 {
+            step: TimeDelta::seconds(1.0),
+        }
+        .integrate(0.0, &[1.0], 5.0, false, |time, _, _| {
+            if time > 2.0 {
+                Err(ForceError::Singular)
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(result, Err(PropagationError::Force(ForceError::Singular)));
+    }
+}
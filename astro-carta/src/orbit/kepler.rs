@@ -0,0 +1,271 @@
+use std::f64::consts::PI;
+
+use crate::math;
+
+/// Maximum number of Newton iterations before falling back on the bracketing interval
+const MAX_ITERATIONS: usize = 100;
+
+/// Relative tolerance on the anomaly at which the iterations stop
+const TOLERANCE: f64 = 1e-15;
+
+/// Solves Kepler's equation `M = E - e sin E` for the eccentric anomaly of an elliptic orbit.
+///
+/// The result lies in the same turn as `mean_anomaly`. Newton iterations are safeguarded by
+/// bisection, so the solver converges for every eccentricity in [0, 1), including the
+/// difficult region of small mean anomalies at eccentricities close to one.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::orbit::kepler;
+///
+/// // Meeus, Astronomical Algorithms, example 30.a
+/// let e = kepler::solve_elliptic(5_f64.to_radians(), 0.1);
+/// assert!((e.to_degrees() - 5.554_589_253).abs() < 1e-9);
+/// ```
+pub fn solve_elliptic(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    let turn = mean_anomaly - math::normalize_angle_signed(mean_anomaly);
+    let m = mean_anomaly - turn;
+    let sign = if m < 0.0 { -1.0 } else { 1.0 };
+    let m = m.abs();
+
+    // E - e sin E is increasing and E lies between M and π for M in [0, π]
+    let (mut low, mut high) = (m, PI);
+    let mut e = if eccentricity < 0.8 {
+        m + eccentricity * m.sin()
+    } else {
+        // Series start for the cusp of highly eccentric orbits
+        (m + (6.0 * m).cbrt() * eccentricity).min(PI)
+    };
+
+    for _ in 0..MAX_ITERATIONS {
+        let f = e - eccentricity * e.sin() - m;
+        if f > 0.0 {
+            high = e;
+        } else {
+            low = e;
+        }
+
+        let derivative = 1.0 - eccentricity * e.cos();
+        let mut next = e - f / derivative;
+        if !(low..=high).contains(&next) || derivative == 0.0 {
+            next = 0.5 * (low + high);
+        }
+        if (next - e).abs() <= TOLERANCE * next.abs().max(1.0) {
+            e = next;
+            break;
+        }
+        e = next;
+    }
+
+    turn + sign * e
+}
+
+/// Solves the hyperbolic Kepler equation `M = e sinh H - H` for the hyperbolic anomaly.
+///
+/// The eccentricity must be greater than one.
+pub fn solve_hyperbolic(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    let sign = if mean_anomaly < 0.0 { -1.0 } else { 1.0 };
+    let m = mean_anomaly.abs();
+
+    // Bounds from sinh H ≥ H: (e - 1) sinh H ≤ M ≤ e sinh H
+    let (mut low, mut high) = (
+        (m / eccentricity).asinh(),
+        (m / (eccentricity - 1.0)).asinh(),
+    );
+    let mut h = if m < 6.0 * eccentricity {
+        (m / eccentricity).asinh()
+    } else {
+        (2.0 * m / eccentricity).ln()
+    }
+    .clamp(low, high);
+
+    for _ in 0..MAX_ITERATIONS {
+        let f = eccentricity * h.sinh() - h - m;
+        if f > 0.0 {
+            high = h;
+        } else {
+            low = h;
+        }
+
+        let mut next = h - f / (eccentricity * h.cosh() - 1.0);
+        if !(low..=high).contains(&next) {
+            next = 0.5 * (low + high);
+        }
+        if (next - h).abs() <= TOLERANCE * next.abs().max(1.0) {
+            h = next;
+            break;
+        }
+        h = next;
+    }
+
+    sign * h
+}
+
+/// Solves Barker's equation `M = D + D³/3` for the parabolic anomaly.
+pub fn solve_parabolic(mean_anomaly: f64) -> f64 {
+    // With D = 2 sinh x the equation becomes sinh 3x = 3M/2
+    2.0 * ((1.5 * mean_anomaly).asinh() / 3.0).sinh()
+}
+
+/// Solves Kepler's equation for the eccentric, hyperbolic or parabolic anomaly of an orbit
+/// of any eccentricity.
+///
+/// For hyperbolic orbits the eccentric anomaly is the hyperbolic anomaly H and the mean
+/// anomaly is `e sinh H - H`. For parabolic orbits it is the parabolic anomaly `D = tan(ν/2)`
+/// and the mean anomaly is Barker's `D + D³/3`. The other functions of this module follow the
+/// same conventions.
+pub fn solve(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    if eccentricity < 1.0 {
+        solve_elliptic(mean_anomaly, eccentricity)
+    } else if eccentricity > 1.0 {
+        solve_hyperbolic(mean_anomaly, eccentricity)
+    } else {
+        solve_parabolic(mean_anomaly)
+    }
+}
+
+/// Converts an eccentric, hyperbolic or parabolic anomaly to the true anomaly.
+///
+/// For elliptic orbits the result lies in the same turn as the eccentric anomaly.
+pub fn eccentric_to_true(eccentric_anomaly: f64, eccentricity: f64) -> f64 {
+    if eccentricity < 1.0 {
+        let turn = eccentric_anomaly - math::normalize_angle_signed(eccentric_anomaly);
+        let (sin_e, cos_e) = eccentric_anomaly.sin_cos();
+        let beta = (1.0 - eccentricity * eccentricity).sqrt();
+        turn + (beta * sin_e).atan2(cos_e - eccentricity)
+    } else if eccentricity > 1.0 {
+        let factor = ((eccentricity + 1.0) / (eccentricity - 1.0)).sqrt();
+        2.0 * (factor * (0.5 * eccentric_anomaly).tanh()).atan()
+    } else {
+        2.0 * eccentric_anomaly.atan()
+    }
+}
+
+/// Converts a true anomaly to the eccentric, hyperbolic or parabolic anomaly.
+///
+/// For hyperbolic orbits the true anomaly must lie between the asymptotes,
+/// `|ν| < acos(-1/e)`.
+pub fn true_to_eccentric(true_anomaly: f64, eccentricity: f64) -> f64 {
+    if eccentricity < 1.0 {
+        let turn = true_anomaly - math::normalize_angle_signed(true_anomaly);
+        let (sin_v, cos_v) = true_anomaly.sin_cos();
+        let beta = (1.0 - eccentricity * eccentricity).sqrt();
+        turn + (beta * sin_v).atan2(eccentricity + cos_v)
+    } else if eccentricity > 1.0 {
+        let factor = ((eccentricity - 1.0) / (eccentricity + 1.0)).sqrt();
+        2.0 * (factor * (0.5 * true_anomaly).tan()).atanh()
+    } else {
+        (0.5 * true_anomaly).tan()
+    }
+}
+
+/// Converts an eccentric, hyperbolic or parabolic anomaly to the mean anomaly.
+pub fn eccentric_to_mean(eccentric_anomaly: f64, eccentricity: f64) -> f64 {
+    if eccentricity < 1.0 {
+        eccentric_anomaly - eccentricity * eccentric_anomaly.sin()
+    } else if eccentricity > 1.0 {
+        eccentricity * eccentric_anomaly.sinh() - eccentric_anomaly
+    } else {
+        eccentric_anomaly + eccentric_anomaly.powi(3) / 3.0
+    }
+}
+
+/// Converts a mean anomaly to the true anomaly.
+pub fn mean_to_true(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    eccentric_to_true(solve(mean_anomaly, eccentricity), eccentricity)
+}
+
+/// Converts a true anomaly to the mean anomaly.
+pub fn true_to_mean(true_anomaly: f64, eccentricity: f64) -> f64 {
+    eccentric_to_mean(true_to_eccentric(true_anomaly, eccentricity), eccentricity)
+}
+
+/// Solves the equinoctial form of Kepler's equation `λ = F + h cos F - k sin F` for the
+/// eccentric longitude F, given the mean longitude λ and the equinoctial eccentricity
+/// components `h = e sin ϖ` and `k = e cos ϖ`.
+///
+/// The result differs from `mean_longitude` by at most the eccentricity.
+pub fn solve_equinoctial(mean_longitude: f64, h: f64, k: f64) -> f64 {
+    // With F = E + ϖ this is Kepler's equation, which avoids the singularity of ϖ as e → 0
+    // since then F = λ whatever the value of ϖ
+    let eccentricity = h.hypot(k);
+    let periapsis = h.atan2(k);
+    periapsis + solve_elliptic(mean_longitude - periapsis, eccentricity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elliptic_test() {
+        for &e in &[0.0, 0.01, 0.3, 0.7, 0.9, 0.99, 0.999_999] {
+            for i in -200..=200 {
+                let m = i as f64 * 0.05 + 1e-7 * (i % 3) as f64;
+                let anomaly = solve_elliptic(m, e);
+                let residual = anomaly - e * anomaly.sin() - m;
+                assert!(
+                    residual.abs() < 1e-13,
+                    "e = {e}, M = {m}: residual {residual}"
+                );
+            }
+        }
+
+        // Small mean anomalies close to the parabolic limit
+        let anomaly = solve_elliptic(1e-9, 0.999_999_9);
+        assert!((anomaly - 0.999_999_9 * anomaly.sin() - 1e-9).abs() < 1e-17);
+    }
+
+    #[test]
+    fn hyperbolic_test() {
+        for &e in &[1.000_001, 1.01, 1.5, 3.0, 50.0] {
+            for &m in &[-1e4, -30.0, -1.0, -1e-8, 0.0, 1e-6, 0.5, 2.0, 100.0, 1e6] {
+                let anomaly = solve_hyperbolic(m, e);
+                let residual = (e * anomaly.sinh() - anomaly - m) / m.abs().max(1.0);
+                assert!(
+                    residual.abs() < 1e-13,
+                    "e = {e}, M = {m}: residual {residual}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parabolic_test() {
+        for &m in &[-50.0, -1.0, 0.0, 0.3, 7.0, 1e5] {
+            let d = solve_parabolic(m);
+            assert!((d + d.powi(3) / 3.0 - m).abs() < 1e-12 * m.abs().max(1.0));
+        }
+    }
+
+    #[test]
+    fn anomaly_round_trip_test() {
+        for &e in &[0.0_f64, 0.2, 0.95, 1.0, 1.2, 4.0] {
+            let limit = if e > 1.0 { (-1.0 / e).acos() } else { PI };
+            for i in -9..=9 {
+                let v = 0.99 * limit * i as f64 / 9.0;
+                let m = true_to_mean(v, e);
+                assert!((mean_to_true(m, e) - v).abs() < 1e-10, "e = {e}, v = {v}");
+                let anomaly = true_to_eccentric(v, e);
+                assert!((eccentric_to_true(anomaly, e) - v).abs() < 1e-12);
+            }
+        }
+
+        // Elliptic anomalies keep their turn
+        assert!(
+            (mean_to_true(4.0 * PI + 1.0, 0.3) - 4.0 * PI - mean_to_true(1.0, 0.3)).abs() < 1e-12
+        );
+    }
+
+    #[test]
+    fn equinoctial_test() {
+        let (e, periapsis) = (0.4_f64, 1.1_f64);
+        let (h, k) = (e * periapsis.sin(), e * periapsis.cos());
+        for &mean_longitude in &[0.0, 1.0, 3.0, 5.5, 9.0] {
+            let f = solve_equinoctial(mean_longitude, h, k);
+            assert!((f + h * f.cos() - k * f.sin() - mean_longitude).abs() < 1e-13);
+        }
+        assert_eq!(solve_equinoctial(2.5, 0.0, 0.0), 2.5);
+    }
+}
@@ -0,0 +1,687 @@
+use std::f64::consts::PI;
+use std::fmt;
+
+use super::odm::format_epoch;
+use crate::datetime::{DateTime, TimeDelta, TimeScale};
+use crate::ephemeris::Ephemeris;
+use crate::math::Vector3;
+
+/// Largest number of iterations on the Lagrange variable
+const MAX_ITERATIONS: usize = 35;
+
+/// Change of the Lagrange variable below which the iterations stop
+const TOLERANCE: f64 = 1e-11;
+
+/// Error raised while solving a Lambert problem
+#[derive(Debug, Clone, PartialEq)]
+pub enum LambertError {
+    /// The gravitational parameter is not positive
+    InvalidGravitationalParameter,
+    /// The time of flight is not positive
+    InvalidTimeOfFlight,
+    /// A position is zero, or the positions are collinear so the plane of the transfer is
+    /// undefined
+    DegenerateGeometry,
+    /// The iterations on the Lagrange variable did not converge
+    NoConvergence,
+    /// The ephemeris failed to give the state of a body
+    Ephemeris(String),
+    /// An instant to be written falls outside the years 1 to 9999
+    InvalidEpoch(DateTime),
+}
+
+impl fmt::Display for LambertError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LambertError::InvalidGravitationalParameter => {
+                write!(f, "gravitational parameter must be positive")
+            }
+            LambertError::InvalidTimeOfFlight => write!(f, "time of flight must be positive"),
+            LambertError::DegenerateGeometry => {
+                write!(f, "the positions do not define the plane of the transfer")
+            }
+            LambertError::NoConvergence => write!(f, "Lambert solver failed to converge"),
+            LambertError::Ephemeris(message) => write!(f, "ephemeris failed: {message}"),
+            LambertError::InvalidEpoch(dt) => write!(
+                f,
+                "instant at JD {} TDB outside the years 1 to 9999",
+                dt.julian_date(TimeScale::TDB)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LambertError {}
+
+/// Sense of motion of a transfer around the z-axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Motion {
+    /// Counterclockwise seen from the north, with positive angular momentum along z
+    Prograde,
+    Retrograde,
+}
+
+/// One of the two transfers with the same number of complete revolutions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Branch {
+    /// The transfer with the smaller Lagrange variable `x`
+    Left,
+    /// The transfer with the larger Lagrange variable `x`
+    Right,
+}
+
+/// A transfer between two positions in the time of flight
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LambertSolution {
+    /// Number of complete revolutions before the arrival
+    pub revolutions: u32,
+    /// Branch of the multiple-revolution transfers, `None` for direct transfers
+    pub branch: Option<Branch>,
+    /// Velocity at the departure in m/s
+    pub departure: Vector3,
+    /// Velocity at the arrival in m/s
+    pub arrival: Vector3,
+}
+
+/// Solver of Lambert's problem with the algorithm of Izzo (2015)
+///
+/// The problem is solved for every number of complete revolutions that fits in the time of
+/// flight, up to an optional limit: one direct transfer, then two transfers per number of
+/// revolutions.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::TimeDelta;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::lambert::Lambert;
+///
+/// // Example 5.2 of Curtis, Orbital Mechanics for Engineering Students
+/// let r1 = Vector3::new(5_000e3, 10_000e3, 2_100e3);
+/// let r2 = Vector3::new(-14_600e3, 2_500e3, 7_000e3);
+/// let solutions = Lambert::new(3.986e14).solve(&r1, &r2, TimeDelta::hours(1.0)).unwrap();
+/// assert_eq!(solutions.len(), 1);
+/// let expected = Vector3::new(-5_992.5, 1_925.4, 3_245.6);
+/// assert!((solutions[0].departure - expected).norm() < 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lambert {
+    /// Gravitational parameter of the central body in m³/s²
+    pub gravitational_parameter: f64,
+    pub motion: Motion,
+    /// Largest number of complete revolutions, unlimited if `None`
+    pub max_revolutions: Option<u32>,
+}
+
+impl Lambert {
+    /// Constructs a solver of prograde transfers with any number of revolutions.
+    pub fn new(gravitational_parameter: f64) -> Self {
+        Lambert {
+            gravitational_parameter,
+            motion: Motion::Prograde,
+            max_revolutions: None,
+        }
+    }
+
+    pub fn with_motion(mut self, motion: Motion) -> Self {
+        self.motion = motion;
+        self
+    }
+
+    pub fn with_max_revolutions(mut self, max_revolutions: u32) -> Self {
+        self.max_revolutions = Some(max_revolutions);
+        self
+    }
+
+    /// Solves for the transfers from `r1` to `r2` in meters in the time of flight, the direct
+    /// transfer first, then the left and right transfers by increasing number of revolutions.
+    pub fn solve(
+        &self,
+        r1: &Vector3,
+        r2: &Vector3,
+        time_of_flight: TimeDelta,
+    ) -> Result<Vec<LambertSolution>, LambertError> {
+        let mu = self.gravitational_parameter;
+        if !(mu > 0.0 && mu.is_finite()) {
+            return Err(LambertError::InvalidGravitationalParameter);
+        }
+        let tof = time_of_flight.total_seconds();
+        if tof <= 0.0 {
+            return Err(LambertError::InvalidTimeOfFlight);
+        }
+        let (r1_norm, r2_norm) = (r1.norm(), r2.norm());
+        let chord = (*r2 - *r1).norm();
+        let normal = r1.cross(r2);
+        if r1_norm == 0.0 || r2_norm == 0.0 || normal.norm() <= 1e-12 * r1_norm * r2_norm {
+            return Err(LambertError::DegenerateGeometry);
+        }
+
+        let s = 0.5 * (r1_norm + r2_norm + chord);
+        let (u1, u2) = (*r1 / r1_norm, *r2 / r2_norm);
+        let mut h = normal.normalize();
+        let mut lambda = (1.0 - (chord / s).min(1.0)).sqrt();
+        // The transfer angle exceeds π when the angular momentum of the short way points south
+        if h.z < 0.0 {
+            lambda = -lambda;
+            h = -h;
+        }
+        let (mut t1, mut t2) = (h.cross(&u1), h.cross(&u2));
+        if self.motion == Motion::Retrograde {
+            lambda = -lambda;
+            t1 = -t1;
+            t2 = -t2;
+        }
+
+        // Non-dimensional time of flight
+        let t = (2.0 * mu / (s * s * s)).sqrt() * tof;
+        let mut max_revolutions = (t / PI).floor() as u32;
+        let t00 = lambda.acos() + lambda * (1.0 - lambda * lambda).sqrt();
+        if max_revolutions > 0 && t < t00 + max_revolutions as f64 * PI {
+            let x_min = minimum_time(lambda, max_revolutions)?;
+            if t < time_of_flight_at(x_min, lambda, max_revolutions) {
+                max_revolutions -= 1;
+            }
+        }
+        if let Some(limit) = self.max_revolutions {
+            max_revolutions = max_revolutions.min(limit);
+        }
+
+        let gamma = (mu * s / 2.0).sqrt();
+        let rho = (r1_norm - r2_norm) / chord;
+        let sigma = (1.0 - rho * rho).max(0.0).sqrt();
+        let velocities = |x: f64| {
+            let y = compute_y(x, lambda);
+            let radial1 = gamma * ((lambda * y - x) - rho * (lambda * y + x)) / r1_norm;
+            let radial2 = -gamma * ((lambda * y - x) + rho * (lambda * y + x)) / r2_norm;
+            let transverse1 = gamma * sigma * (y + lambda * x) / r1_norm;
+            let transverse2 = gamma * sigma * (y + lambda * x) / r2_norm;
+            (
+                u1 * radial1 + t1 * transverse1,
+                u2 * radial2 + t2 * transverse2,
+            )
+        };
+
+        let mut solutions = Vec::with_capacity(1 + 2 * max_revolutions as usize);
+        for revolutions in 0..=max_revolutions {
+            let branches: &[Option<Branch>] = if revolutions == 0 {
+                &[None]
+            } else {
+                &[Some(Branch::Left), Some(Branch::Right)]
+            };
+            for &branch in branches {
+                let guess = initial_guess(t, lambda, revolutions, branch);
+                let x = householder(guess, t, lambda, revolutions)?;
+                let (departure, arrival) = velocities(x);
+                solutions.push(LambertSolution {
+                    revolutions,
+                    branch,
+                    departure,
+                    arrival,
+                });
+            }
+        }
+        Ok(solutions)
+    }
+
+    /// Computes the direct transfers from a body to another, both read from an ephemeris
+    /// relative to the central body, over grids of departure and arrival instants.
+    ///
+    /// The transfers with the fewest revolutions allowed and the smallest sum of hyperbolic
+    /// excess speeds are kept. Points with no transfer, such as those arriving before the
+    /// departure or with collinear positions, are `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use astro_carta::constants::SUN_GRAVITATIONAL_PARAMETER;
+    /// use astro_carta::datetime::{DateTime, DateTimeRange, TimeDelta};
+    /// use astro_carta::ephemeris::spk::Spk;
+    /// use astro_carta::orbit::lambert::Lambert;
+    ///
+    /// let spk = Spk::open("de440.bsp").unwrap();
+    /// let start = DateTime::gregorian(2026, 9, 1, 0, 0, 0.0).unwrap();
+    /// let departures: Vec<_> =
+    ///     DateTimeRange::new(start, start + TimeDelta::days(120.0), TimeDelta::days(2.0)).collect();
+    /// let first = start + TimeDelta::days(150.0);
+    /// let arrivals: Vec<_> =
+    ///     DateTimeRange::new(first, first + TimeDelta::days(300.0), TimeDelta::days(4.0)).collect();
+    /// let porkchop = Lambert::new(SUN_GRAVITATIONAL_PARAMETER)
+    ///     .with_max_revolutions(0)
+    ///     .porkchop(&spk, 10, 3, 4, &departures, &arrivals)
+    ///     .unwrap();
+    /// std::fs::write("earth-mars.csv", porkchop.to_csv().unwrap()).unwrap();
+    /// ```
+    pub fn porkchop<E: Ephemeris>(
+        &self,
+        ephemeris: &E,
+        center: i32,
+        origin: i32,
+        target: i32,
+        departures: &[DateTime],
+        arrivals: &[DateTime],
+    ) -> Result<Porkchop, LambertError> {
+        let states = |body: i32, epochs: &[DateTime]| {
+            epochs
+                .iter()
+                .map(|dt| {
+                    ephemeris
+                        .state(body, center, dt)
+                        .map_err(|error| LambertError::Ephemeris(error.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let origins = states(origin, departures)?;
+        let targets = states(target, arrivals)?;
+
+        let mut points = Vec::with_capacity(departures.len() * arrivals.len());
+        for (departure, origin) in departures.iter().zip(&origins) {
+            for (arrival, target) in arrivals.iter().zip(&targets) {
+                let solutions = if arrival > departure {
+                    self.solve(&origin.position, &target.position, *arrival - *departure)
+                        .unwrap_or_default()
+                } else {
+                    Vec::new()
+                };
+                let point = solutions
+                    .iter()
+                    .map(|solution| PorkchopPoint {
+                        departure: *departure,
+                        arrival: *arrival,
+                        revolutions: solution.revolutions,
+                        departure_excess: solution.departure - origin.velocity,
+                        arrival_excess: solution.arrival - target.velocity,
+                    })
+                    .min_by(|a, b| a.total().total_cmp(&b.total()));
+                points.push(point);
+            }
+        }
+        Ok(Porkchop {
+            departures: departures.to_vec(),
+            arrivals: arrivals.to_vec(),
+            points,
+        })
+    }
+}
+
+/// A transfer of a porkchop plot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PorkchopPoint {
+    pub departure: DateTime,
+    pub arrival: DateTime,
+    pub revolutions: u32,
+    /// Hyperbolic excess velocity at the departure in m/s, relative to the origin body
+    pub departure_excess: Vector3,
+    /// Hyperbolic excess velocity at the arrival in m/s, relative to the target body
+    pub arrival_excess: Vector3,
+}
+
+impl PorkchopPoint {
+    pub fn time_of_flight(&self) -> TimeDelta {
+        self.arrival - self.departure
+    }
+
+    /// Returns the characteristic energy of the departure in m²/s², the square of the excess
+    /// speed.
+    pub fn c3(&self) -> f64 {
+        self.departure_excess.dot(&self.departure_excess)
+    }
+
+    /// Returns the sum of the departure and arrival excess speeds in m/s.
+    pub fn total(&self) -> f64 {
+        self.departure_excess.norm() + self.arrival_excess.norm()
+    }
+}
+
+/// Transfers over a grid of departure and arrival instants
+#[derive(Debug, Clone, PartialEq)]
+pub struct Porkchop {
+    pub departures: Vec<DateTime>,
+    pub arrivals: Vec<DateTime>,
+    /// Transfers by departure then arrival
+    pub points: Vec<Option<PorkchopPoint>>,
+}
+
+impl Porkchop {
+    /// Returns the transfer at the given indices of departure and arrival.
+    pub fn get(&self, departure: usize, arrival: usize) -> Option<&PorkchopPoint> {
+        if departure >= self.departures.len() || arrival >= self.arrivals.len() {
+            return None;
+        }
+        self.points[departure * self.arrivals.len() + arrival].as_ref()
+    }
+
+    /// Returns the transfer with the smallest sum of excess speeds.
+    pub fn best(&self) -> Option<&PorkchopPoint> {
+        self.points
+            .iter()
+            .flatten()
+            .min_by(|a, b| a.total().total_cmp(&b.total()))
+    }
+
+    /// Writes the transfers as CSV, one row per point of the grid with a transfer
+    ///
+    /// Instants are in TDB, times of flight in days, the characteristic energy in km²/s² and
+    /// speeds in km/s. Writing fails if an instant falls outside the years 1 to 9999.
+    pub fn to_csv(&self) -> Result<String, LambertError> {
+        let format =
+            |dt: &DateTime| format_epoch(dt, TimeScale::TDB).ok_or(LambertError::InvalidEpoch(*dt));
+        let mut text = String::from(
+            "departure,arrival,time_of_flight_d,revolutions,c3_km2_s2,\
+             departure_excess_km_s,arrival_excess_km_s\n",
+        );
+        for point in self.points.iter().flatten() {
+            text.push_str(&format!(
+                "{},{},{:.4},{},{:.6},{:.6},{:.6}\n",
+                format(&point.departure)?,
+                format(&point.arrival)?,
+                point.time_of_flight().total_seconds() / 86_400.0,
+                point.revolutions,
+                point.c3() / 1e6,
+                point.departure_excess.norm() / 1e3,
+                point.arrival_excess.norm() / 1e3,
+            ));
+        }
+        Ok(text)
+    }
+}
+
+fn compute_y(x: f64, lambda: f64) -> f64 {
+    (1.0 - lambda * lambda * (1.0 - x * x)).sqrt()
+}
+
+/// Gauss hypergeometric function 2F1(3, 1, 5/2, x), of the time of flight near parabolic
+/// transfers
+fn hypergeometric(x: f64) -> f64 {
+    if x >= 1.0 {
+        return f64::INFINITY;
+    }
+    let (mut sum, mut term) = (1.0, 1.0);
+    for i in 0..1_000 {
+        let i = i as f64;
+        term *= (3.0 + i) * (1.0 + i) / (2.5 + i) * x / (i + 1.0);
+        let previous = sum;
+        sum += term;
+        if sum == previous {
+            break;
+        }
+    }
+    sum
+}
+
+/// Non-dimensional time of flight as a function of the Lagrange variable `x`.
+fn time_of_flight_at(x: f64, lambda: f64, revolutions: u32) -> f64 {
+    let y = compute_y(x, lambda);
+    if revolutions == 0 && 0.6f64.sqrt() < x && x < 1.4f64.sqrt() {
+        // Battin's series, avoiding the cancellation near parabolic transfers
+        let eta = y - lambda * x;
+        let s1 = 0.5 * (1.0 - lambda - x * eta);
+        let q = 4.0 / 3.0 * hypergeometric(s1);
+        return 0.5 * (eta.powi(3) * q + 4.0 * lambda * eta);
+    }
+    let psi = if (-1.0..1.0).contains(&x) {
+        (x * y + lambda * (1.0 - x * x)).clamp(-1.0, 1.0).acos()
+    } else if x > 1.0 {
+        ((y - x * lambda) * (x * x - 1.0).sqrt()).asinh()
+    } else {
+        0.0
+    };
+    ((psi + revolutions as f64 * PI) / (1.0 - x * x).abs().sqrt() - x + lambda * y) / (1.0 - x * x)
+}
+
+/// Returns the first three derivatives of the time of flight with respect to `x`.
+fn derivatives(x: f64, t: f64, lambda: f64) -> (f64, f64, f64) {
+    let y = compute_y(x, lambda);
+    let l2 = lambda * lambda;
+    let l3 = l2 * lambda;
+    let l5 = l3 * l2;
+    let d1 = (3.0 * t * x - 2.0 + 2.0 * l3 * x / y) / (1.0 - x * x);
+    let d2 = (3.0 * t + 5.0 * x * d1 + 2.0 * (1.0 - l2) * l3 / y.powi(3)) / (1.0 - x * x);
+    let d3 = (7.0 * x * d2 + 8.0 * d1 - 6.0 * (1.0 - l2) * l5 * x / y.powi(5)) / (1.0 - x * x);
+    (d1, d2, d3)
+}
+
+/// Finds the Lagrange variable of the shortest time of flight with the given number of
+/// revolutions, with Halley's method on the derivative.
+fn minimum_time(lambda: f64, revolutions: u32) -> Result<f64, LambertError> {
+    if lambda == 1.0 {
+        return Ok(0.0);
+    }
+    let mut x = 0.1;
+    for _ in 0..MAX_ITERATIONS {
+        let t = time_of_flight_at(x, lambda, revolutions);
+        let (d1, d2, d3) = derivatives(x, t, lambda);
+        if d2 == 0.0 {
+            return Err(LambertError::NoConvergence);
+        }
+        let next = x - 2.0 * d1 * d2 / (2.0 * d2 * d2 - d1 * d3);
+        if (next - x).abs() < TOLERANCE {
+            return Ok(next);
+        }
+        x = next;
+    }
+    Err(LambertError::NoConvergence)
+}
+
+fn initial_guess(t: f64, lambda: f64, revolutions: u32, branch: Option<Branch>) -> f64 {
+    if revolutions == 0 {
+        let t0 = lambda.acos() + lambda * (1.0 - lambda * lambda).sqrt();
+        let t1 = 2.0 * (1.0 - lambda.powi(3)) / 3.0;
+        if t >= t0 {
+            (t0 / t).powf(2.0 / 3.0) - 1.0
+        } else if t < t1 {
+            2.5 * t1 / t * (t1 - t) / (1.0 - lambda.powi(5)) + 1.0
+        } else {
+            (2f64.ln() * (t / t0).ln() / (t1 / t0).ln()).exp() - 1.0
+        }
+    } else {
+        let m = revolutions as f64 * PI;
+        let guess = |ratio: f64| {
+            let power = ratio.powf(2.0 / 3.0);
+            (power - 1.0) / (power + 1.0)
+        };
+        match branch {
+            Some(Branch::Right) => guess(8.0 * t / m),
+            _ => guess((m + PI) / (8.0 * t)),
+        }
+    }
+}
+
+/// Solves the time of flight equation for `x` with Householder's quartic method.
+fn householder(mut x: f64, t: f64, lambda: f64, revolutions: u32) -> Result<f64, LambertError> {
+    for _ in 0..MAX_ITERATIONS {
+        let time = time_of_flight_at(x, lambda, revolutions);
+        let f = time - t;
+        let (d1, d2, d3) = derivatives(x, time, lambda);
+        let next = x - f * (d1 * d1 - f * d2 / 2.0) / (d1 * (d1 * d1 - f * d2) + d3 * f * f / 6.0);
+        if !next.is_finite() {
+            return Err(LambertError::NoConvergence);
+        }
+        if (next - x).abs() < TOLERANCE {
+            return Ok(next);
+        }
+        x = next;
+    }
+    Err(LambertError::NoConvergence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{
+        ASTRONOMICAL_UNIT, EARTH_GRAVITATIONAL_PARAMETER, SUN_GRAVITATIONAL_PARAMETER,
+    };
+    use crate::ephemeris::StateVector;
+    use crate::orbit::propagation;
+
+    /// Checks that every solution flies from `r1` to `r2` in the time of flight.
+    fn check(solutions: &[LambertSolution], r1: &Vector3, r2: &Vector3, tof: TimeDelta, mu: f64) {
+        for solution in solutions {
+            let start = StateVector {
+                position: *r1,
+                velocity: solution.departure,
+            };
+            let end = propagation::universal(&start, mu, tof).unwrap();
+            assert!((end.position - *r2).norm() < 1e-6 * r2.norm());
+            assert!((end.velocity - solution.arrival).norm() < 1e-6 * solution.arrival.norm());
+        }
+    }
+
+    #[test]
+    fn direct_test() {
+        let mu = EARTH_GRAVITATIONAL_PARAMETER;
+        let r1 = Vector3::new(7_000e3, 0.0, 0.0);
+        let lambert = Lambert::new(mu).with_max_revolutions(0);
+        // Elliptic, near-parabolic and hyperbolic transfers, short and long way
+        for (r2, minutes) in [
+            (Vector3::new(0.0, 9_000e3, 1_000e3), 30.0),
+            (Vector3::new(-20_000e3, 5_000e3, 0.0), 45.0),
+            (Vector3::new(-20_000e3, -5_000e3, 3_000e3), 300.0),
+            (Vector3::new(5_000e3, 30_000e3, 0.0), 20.0),
+            (Vector3::new(5_000e3, -8_000e3, 0.0), 10.0),
+        ] {
+            let tof = TimeDelta::minutes(minutes);
+            for motion in [Motion::Prograde, Motion::Retrograde] {
+                let solutions = lambert.with_motion(motion).solve(&r1, &r2, tof).unwrap();
+                assert_eq!(solutions.len(), 1);
+                assert_eq!(solutions[0].branch, None);
+                check(&solutions, &r1, &r2, tof, mu);
+                let h = r1.cross(&solutions[0].departure);
+                assert_eq!(h.z > 0.0, motion == Motion::Prograde);
+            }
+        }
+    }
+
+    #[test]
+    fn multiple_revolutions_test() {
+        let mu = EARTH_GRAVITATIONAL_PARAMETER;
+        let r1 = Vector3::new(7_000e3, 0.0, 0.0);
+        let r2 = Vector3::new(-3_000e3, 7_500e3, 500e3);
+        let tof = TimeDelta::hours(8.0);
+        let solutions = Lambert::new(mu).solve(&r1, &r2, tof).unwrap();
+        assert!(solutions.len() >= 5 && solutions.len() % 2 == 1);
+        check(&solutions, &r1, &r2, tof, mu);
+        for pair in solutions[1..].chunks(2) {
+            assert_eq!(pair[0].revolutions, pair[1].revolutions);
+            assert_eq!(pair[0].branch, Some(Branch::Left));
+            assert_eq!(pair[1].branch, Some(Branch::Right));
+            assert!((pair[0].departure - pair[1].departure).norm() > 1.0);
+        }
+        let limited = Lambert::new(mu)
+            .with_max_revolutions(1)
+            .solve(&r1, &r2, tof);
+        assert_eq!(limited.unwrap()[..], solutions[..3]);
+
+        // The period of the orbit through both positions bounds the revolutions
+        let short = Lambert::new(mu)
+            .solve(&r1, &r2, TimeDelta::minutes(90.0))
+            .unwrap();
+        assert_eq!(short.len(), 1);
+    }
+
+    #[test]
+    fn error_test() {
+        let lambert = Lambert::new(EARTH_GRAVITATIONAL_PARAMETER);
+        let r1 = Vector3::new(7_000e3, 0.0, 0.0);
+        let tof = TimeDelta::hours(1.0);
+        assert_eq!(
+            lambert.solve(&r1, &(r1 * -2.0), tof),
+            Err(LambertError::DegenerateGeometry)
+        );
+        assert_eq!(
+            lambert.solve(&r1, &Vector3::zeros(), tof),
+            Err(LambertError::DegenerateGeometry)
+        );
+        let r2 = Vector3::new(0.0, 7_000e3, 0.0);
+        assert_eq!(
+            lambert.solve(&r1, &r2, TimeDelta::new(0)),
+            Err(LambertError::InvalidTimeOfFlight)
+        );
+        assert_eq!(
+            Lambert::new(-1.0).solve(&r1, &r2, tof),
+            Err(LambertError::InvalidGravitationalParameter)
+        );
+    }
+
+    /// Two planets on circular coplanar orbits around the Sun
+    struct Planets;
+
+    impl Ephemeris for Planets {
+        type Error = LambertError;
+
+        fn state(
+            &self,
+            target: i32,
+            center: i32,
+            dt: &DateTime,
+        ) -> Result<StateVector, LambertError> {
+            let radius = match (target, center) {
+                (3, 10) => ASTRONOMICAL_UNIT,
+                (4, 10) => 1.524 * ASTRONOMICAL_UNIT,
+                _ => return Err(LambertError::Ephemeris(format!("no body {target}"))),
+            };
+            let speed = (SUN_GRAVITATIONAL_PARAMETER / radius).sqrt();
+            let t = (*dt - DateTime::gregorian(2026, 1, 1, 0, 0, 0.0).unwrap()).total_seconds();
+            let angle = speed / radius * t + if target == 4 { 0.75 } else { 0.0 };
+            let (sin, cos) = angle.sin_cos();
+            Ok(StateVector {
+                position: Vector3::new(cos, sin, 0.0) * radius,
+                velocity: Vector3::new(-sin, cos, 0.0) * speed,
+            })
+        }
+    }
+
+    #[test]
+    fn porkchop_test() {
+        let start = DateTime::gregorian(2026, 1, 1, 0, 0, 0.0).unwrap();
+        let departures: Vec<_> = (0..30)
+            .map(|i| start + TimeDelta::days(10.0 * i as f64))
+            .collect();
+        let arrivals: Vec<_> = (0..30)
+            .map(|i| start + TimeDelta::days(100.0 + 15.0 * i as f64))
+            .collect();
+        let porkchop = Lambert::new(SUN_GRAVITATIONAL_PARAMETER)
+            .with_max_revolutions(0)
+            .porkchop(&Planets, 10, 3, 4, &departures, &arrivals)
+            .unwrap();
+        assert_eq!(porkchop.points.len(), 900);
+        assert!(porkchop.get(29, 0).is_none());
+        assert!(porkchop.get(0, 30).is_none());
+        let point = porkchop.get(0, 10).unwrap();
+        assert_eq!(
+            (point.departure, point.arrival),
+            (departures[0], arrivals[10])
+        );
+
+        // The best transfer approaches the Hohmann transfer
+        let (r1, r2) = (ASTRONOMICAL_UNIT, 1.524 * ASTRONOMICAL_UNIT);
+        let mu = SUN_GRAVITATIONAL_PARAMETER;
+        let a = 0.5 * (r1 + r2);
+        let hohmann = ((mu * (2.0 / r1 - 1.0 / a)).sqrt() - (mu / r1).sqrt())
+            + ((mu / r2).sqrt() - (mu * (2.0 / r2 - 1.0 / a)).sqrt());
+        let best = porkchop.best().unwrap();
+        assert!(best.total() > 0.99 * hohmann && best.total() < 1.2 * hohmann);
+        let days = best.time_of_flight().total_seconds() / 86_400.0;
+        assert!((days - 259.0).abs() < 40.0);
+
+        let csv = porkchop.to_csv().unwrap();
+        assert_eq!(
+            csv.lines().count(),
+            porkchop.points.iter().flatten().count() + 1
+        );
+        assert!(csv.starts_with("departure,arrival,time_of_flight_d"));
+        let mut ancient = porkchop.clone();
+        let dt = DateTime::from_julian_date(1e6, TimeScale::TDB);
+        ancient
+            .points
+            .iter_mut()
+            .flatten()
+            .next()
+            .unwrap()
+            .departure = dt;
+        assert_eq!(ancient.to_csv(), Err(LambertError::InvalidEpoch(dt)));
+        assert!(matches!(
+            Lambert::new(mu).porkchop(&Planets, 10, 3, 5, &departures, &arrivals),
+            Err(LambertError::Ephemeris(_))
+        ));
+    }
+}
@@ -0,0 +1,397 @@
+use std::fmt;
+
+use super::propagation::Propagator;
+use super::station::GroundStation;
+use crate::constants::{EARTH_GRAVITATIONAL_PARAMETER, SPEED_OF_LIGHT};
+use crate::datetime::{DateTime, TimeDelta};
+use crate::ephemeris::StateVector;
+use crate::math::Vector3;
+
+/// Largest number of iterations of the light time
+const LIGHT_TIME_ITERATIONS: usize = 10;
+
+/// Change of the light time in seconds below which its iteration stops, a tenth of a millimeter
+const LIGHT_TIME_TOLERANCE: f64 = 1e-4 / SPEED_OF_LIGHT;
+
+/// Error raised while computing a radio link
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkError {
+    /// The propagator failed to compute the state of the spacecraft
+    Propagation(String),
+    /// The transmitter and the receiver coincide
+    Geometry,
+    /// The light time did not converge
+    NoConvergence,
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LinkError::Propagation(message) => write!(f, "propagation failed: {message}"),
+            LinkError::Geometry => write!(f, "the transmitter and the receiver coincide"),
+            LinkError::NoConvergence => write!(f, "light time did not converge"),
+        }
+    }
+}
+
+impl std::error::Error for LinkError {}
+
+/// A signal traveling from a transmitter to a receiver, with states geocentric in the GCRS
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Leg {
+    /// Instant of transmission, to the nanosecond
+    pub transmit: DateTime,
+    /// Instant of reception
+    pub receive: DateTime,
+    /// State of the transmitter at the transmission
+    pub transmitter: StateVector,
+    /// State of the receiver at the reception
+    pub receiver: StateVector,
+    /// Light time in seconds, including the Shapiro delay of the Earth
+    pub delay: f64,
+}
+
+impl Leg {
+    /// Returns the distance traveled by the signal in meters, the light time times the speed of
+    /// light.
+    pub fn range(&self) -> f64 {
+        self.delay * SPEED_OF_LIGHT
+    }
+
+    /// Returns the ratio of the received to the transmitted frequency, each measured by a clock
+    /// carried by its terminal.
+    ///
+    /// The ratio combines the first-order Doppler shift of the coordinate frequency with the
+    /// rates of the proper times of the terminals, `dτ/dt = √(1 - 2U/c² - v²/c²)` in the
+    /// potential `U = μ/r` of a spherical Earth.
+    pub fn frequency_ratio(&self) -> f64 {
+        let c = SPEED_OF_LIGHT;
+        let line_of_sight = self.receiver.position - self.transmitter.position;
+        let direction = line_of_sight / line_of_sight.norm();
+        let doppler = (1.0 - direction.dot(&self.receiver.velocity) / c)
+            / (1.0 - direction.dot(&self.transmitter.velocity) / c);
+        doppler * proper_rate(&self.transmitter) / proper_rate(&self.receiver)
+    }
+}
+
+/// Rate of the proper time of a clock in the given state relative to the geocentric coordinate
+/// time.
+fn proper_rate(state: &StateVector) -> f64 {
+    let c2 = SPEED_OF_LIGHT * SPEED_OF_LIGHT;
+    let potential = EARTH_GRAVITATIONAL_PARAMETER / state.position.norm();
+    (1.0 - 2.0 * potential / c2 - state.velocity.dot(&state.velocity) / c2).sqrt()
+}
+
+/// Returns the Shapiro delay in meters of a signal between two positions around the Earth.
+fn shapiro(transmitter: &Vector3, receiver: &Vector3) -> f64 {
+    let (r1, r2) = (transmitter.norm(), receiver.norm());
+    let distance = (*receiver - *transmitter).norm();
+    2.0 * EARTH_GRAVITATIONAL_PARAMETER / (SPEED_OF_LIGHT * SPEED_OF_LIGHT)
+        * ((r1 + r2 + distance) / (r1 + r2 - distance)).ln()
+}
+
+/// Solves the light time to a receiver in a known state at the reception, from a transmitter
+/// whose state is a function of time.
+fn solve<F>(receive: DateTime, receiver: StateVector, mut transmitter: F) -> Result<Leg, LinkError>
+where
+    F: FnMut(&DateTime) -> Result<StateVector, LinkError>,
+{
+    // The instant is rounded to the nanosecond, and the state moved to the exact delay
+    let mut locate = |delay: f64| -> Result<(DateTime, StateVector), LinkError> {
+        let transmit = receive - TimeDelta::seconds(delay);
+        let mut state = transmitter(&transmit)?;
+        let residual = delay - (receive - transmit).total_seconds();
+        state.position -= state.velocity * residual;
+        Ok((transmit, state))
+    };
+    let mut delay = 0.0;
+    for _ in 0..LIGHT_TIME_ITERATIONS {
+        let (_, state) = locate(delay)?;
+        let distance = (receiver.position - state.position).norm();
+        if distance == 0.0 {
+            return Err(LinkError::Geometry);
+        }
+        let next = (distance + shapiro(&state.position, &receiver.position)) / SPEED_OF_LIGHT;
+        if (next - delay).abs() < LIGHT_TIME_TOLERANCE {
+            let (transmit, transmitter) = locate(next)?;
+            return Ok(Leg {
+                transmit,
+                receive,
+                transmitter,
+                receiver,
+                delay: next,
+            });
+        }
+        delay = next;
+    }
+    Err(LinkError::NoConvergence)
+}
+
+/// Solves the light time of a signal from the spacecraft to a station receiving it at the
+/// given instant.
+///
+/// The propagator gives the geocentric state of the spacecraft in the GCRS.
+pub fn downlink<P: Propagator>(
+    propagator: &P,
+    station: &GroundStation,
+    receive: &DateTime,
+) -> Result<Leg, LinkError> {
+    solve(*receive, station.state(receive), |dt| {
+        propagator
+            .propagate(dt)
+            .map_err(|error| LinkError::Propagation(error.to_string()))
+    })
+}
+
+/// Solves the light time of a signal from a station to the spacecraft, received in the given
+/// state at the given instant.
+pub fn uplink(
+    station: &GroundStation,
+    receive: &DateTime,
+    spacecraft: &StateVector,
+) -> Result<Leg, LinkError> {
+    solve(*receive, *spacecraft, |dt| Ok(station.state(dt)))
+}
+
+/// Mode of a radio link
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LinkMode {
+    /// The spacecraft transmits at the given frequency in Hz and the station receives.
+    OneWay { frequency: f64 },
+    /// The station transmits at the uplink frequency in Hz, and the spacecraft transponds it
+    /// coherently with the turnaround ratio, such as 240/221 in S-band, back to the station.
+    TwoWay { uplink: f64, turnaround: f64 },
+}
+
+/// Predicted range and frequency of a link at an instant of reception by the station
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Predict {
+    /// Instant of reception at the station
+    pub dt: DateTime,
+    /// Light time in seconds, over the round trip for a two-way link
+    pub light_time: f64,
+    /// Range in meters, half the round trip for a two-way link
+    pub range: f64,
+    /// Range rate in m/s equivalent to the Doppler shift, relativistic effects included
+    pub range_rate: f64,
+    /// Received frequency in Hz
+    pub frequency: f64,
+    /// Received frequency less the frequency that would be received without motion, in Hz
+    pub doppler: f64,
+}
+
+/// Predictor of the range and Doppler shift of the link between a ground station and a
+/// propagated spacecraft
+///
+/// Light times are iterated on the propagated states to a tenth of a millimeter, including the
+/// Shapiro delay of the Earth, and frequencies include the special and general relativistic
+/// rates of the clocks of the terminals. The propagator gives the geocentric state of the
+/// spacecraft in the GCRS.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::constants::EARTH_GRAVITATIONAL_PARAMETER;
+/// use astro_carta::coordinates::Observer;
+/// use astro_carta::datetime::{DateTime, DateTimeRange, TimeDelta};
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::link::DopplerPredictor;
+/// use astro_carta::orbit::station::GroundStation;
+/// use astro_carta::orbit::TwoBody;
+///
+/// let state = StateVector {
+///     position: Vector3::new(7_078e3, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, -1_040.0, 7_432.0),
+/// };
+/// let start = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let propagator = TwoBody::new(state, EARTH_GRAVITATIONAL_PARAMETER, start);
+/// let station = GroundStation::new(Observer::from_degrees(40.4314, -4.2481, 834.0));
+/// let predictor = DopplerPredictor::two_way(station, 2_025e6, 240.0 / 221.0);
+/// let epochs = DateTimeRange::new(start, start + TimeDelta::minutes(10.0), TimeDelta::minutes(1.0));
+/// for predict in predictor.predicts(&propagator, epochs).unwrap() {
+///     // The two-way Doppler shift in S-band stays within ±120 kHz in low Earth orbit
+///     assert!(predict.doppler.abs() < 1.2e5);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DopplerPredictor {
+    pub station: GroundStation,
+    pub mode: LinkMode,
+}
+
+impl DopplerPredictor {
+    /// Constructs a predictor of the downlink of a spacecraft transmitting at the given
+    /// frequency in Hz.
+    pub fn one_way(station: GroundStation, frequency: f64) -> Self {
+        DopplerPredictor {
+            station,
+            mode: LinkMode::OneWay { frequency },
+        }
+    }
+
+    /// Constructs a predictor of a coherent two-way link, from the uplink frequency in Hz and
+    /// the turnaround ratio of the transponder.
+    pub fn two_way(station: GroundStation, uplink: f64, turnaround: f64) -> Self {
+        DopplerPredictor {
+            station,
+            mode: LinkMode::TwoWay { uplink, turnaround },
+        }
+    }
+
+    /// Predicts the link received by the station at the given instant.
+    pub fn predict<P: Propagator>(
+        &self,
+        propagator: &P,
+        dt: &DateTime,
+    ) -> Result<Predict, LinkError> {
+        let down = downlink(propagator, &self.station, dt)?;
+        let (light_time, legs, ratio, nominal) = match self.mode {
+            LinkMode::OneWay { frequency } => (down.delay, 1.0, down.frequency_ratio(), frequency),
+            LinkMode::TwoWay {
+                uplink: frequency,
+                turnaround,
+            } => {
+                let up = uplink(&self.station, &down.transmit, &down.transmitter)?;
+                (
+                    up.delay + down.delay,
+                    2.0,
+                    up.frequency_ratio() * down.frequency_ratio(),
+                    frequency * turnaround,
+                )
+            }
+        };
+        Ok(Predict {
+            dt: *dt,
+            light_time,
+            range: light_time * SPEED_OF_LIGHT / legs,
+            range_rate: SPEED_OF_LIGHT * (1.0 - ratio) / legs,
+            frequency: nominal * ratio,
+            doppler: nominal * (ratio - 1.0),
+        })
+    }
+
+    /// Predicts the link at a sequence of instants of reception, such as a
+    /// [`DateTimeRange`](crate::datetime::DateTimeRange).
+    pub fn predicts<P, I>(&self, propagator: &P, epochs: I) -> Result<Vec<Predict>, LinkError>
+    where
+        P: Propagator,
+        I: IntoIterator<Item = DateTime>,
+    {
+        epochs
+            .into_iter()
+            .map(|dt| self.predict(propagator, &dt))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinates::Observer;
+    use crate::orbit::TwoBody;
+
+    fn start() -> DateTime {
+        DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap()
+    }
+
+    /// A geostationary-transfer orbit, with ranges up to tens of thousands of kilometers
+    fn propagator() -> TwoBody {
+        let state = StateVector {
+            position: Vector3::new(-6_678e3, 0.0, 0.0),
+            velocity: Vector3::new(0.0, -8_800.0, 3_800.0),
+        };
+        TwoBody::new(state, EARTH_GRAVITATIONAL_PARAMETER, start())
+    }
+
+    fn station() -> GroundStation {
+        GroundStation::new(Observer::from_degrees(-35.4014, 148.9817, 680.0))
+    }
+
+    #[test]
+    fn light_time_test() {
+        let propagator = propagator();
+        let receive = start() + TimeDelta::hours(3.0);
+        let leg = downlink(&propagator, &station(), &receive).unwrap();
+        assert!(leg.range() > 1e7);
+
+        // The light time closes the triangle of the transmitter and the receiver
+        let exact = leg.receive - leg.transmit;
+        assert!((exact.total_seconds() - leg.delay).abs() < 1e-9);
+        let transmitter = propagator.propagate(&leg.transmit).unwrap();
+        assert!((transmitter.position - leg.transmitter.position).norm() < 1e-4);
+        let distance = (leg.receiver.position - leg.transmitter.position).norm();
+        let delay = shapiro(&leg.transmitter.position, &leg.receiver.position);
+        assert!(delay > 0.01 && delay < 0.1);
+        assert!((leg.range() - distance - delay).abs() < 2e-4);
+
+        // The uplink back to the spacecraft arrives at the transmission of the downlink
+        let up = uplink(&station(), &leg.transmit, &leg.transmitter).unwrap();
+        assert!((up.delay - leg.delay).abs() < 1e-3);
+        assert_eq!(up.receiver, leg.transmitter);
+        assert_eq!(
+            uplink(&station(), &receive, &station().state(&receive)),
+            Err(LinkError::Geometry)
+        );
+    }
+
+    #[test]
+    fn doppler_test() {
+        let propagator = propagator();
+        let predictor = DopplerPredictor::one_way(station(), 8.4e9);
+        let dt = start() + TimeDelta::hours(3.0);
+        let h = 0.5;
+        let range = |seconds: f64| {
+            predictor
+                .predict(&propagator, &(dt + TimeDelta::seconds(seconds)))
+                .unwrap()
+                .range
+        };
+        let predict = predictor.predict(&propagator, &dt).unwrap();
+        let derivative = (range(h) - range(-h)) / (2.0 * h);
+
+        // The range rate differs from the rate of the range by the rates of the clocks
+        let leg = downlink(&propagator, &station(), &dt).unwrap();
+        let clocks =
+            SPEED_OF_LIGHT * (1.0 - proper_rate(&leg.transmitter) / proper_rate(&leg.receiver));
+        assert!(clocks.abs() > 0.01);
+        assert!((predict.range_rate - derivative - clocks).abs() < 1e-4);
+        assert!((predict.frequency - 8.4e9 - predict.doppler).abs() < 1e-3);
+        assert!((predict.doppler + 8.4e9 * predict.range_rate / SPEED_OF_LIGHT).abs() < 1e-3);
+    }
+
+    #[test]
+    fn two_way_test() {
+        let propagator = propagator();
+        let dt = start() + TimeDelta::hours(3.0);
+        let one_way = DopplerPredictor::one_way(station(), 2.2e9)
+            .predict(&propagator, &dt)
+            .unwrap();
+        let turnaround = 240.0 / 221.0;
+        let two_way = DopplerPredictor::two_way(station(), 2.2e9 / turnaround, turnaround)
+            .predict(&propagator, &dt)
+            .unwrap();
+        // The uplink and downlink are nearly symmetric, while the clock rates cancel out
+        assert!((two_way.light_time - 2.0 * one_way.light_time).abs() < 1e-3);
+        assert!((two_way.range - one_way.range).abs() < 300.0);
+        let geometric = one_way.range_rate
+            - SPEED_OF_LIGHT
+                * (1.0 - {
+                    let leg = downlink(&propagator, &station(), &dt).unwrap();
+                    proper_rate(&leg.transmitter) / proper_rate(&leg.receiver)
+                });
+        assert!((two_way.range_rate - geometric).abs() < 0.5);
+        assert!((two_way.frequency - 2.2e9 - two_way.doppler).abs() < 1e-3);
+
+        let epochs = crate::datetime::DateTimeRange::new(
+            dt,
+            dt + TimeDelta::minutes(5.0),
+            TimeDelta::minutes(1.0),
+        );
+        let predicts = DopplerPredictor::one_way(station(), 2.2e9)
+            .predicts(&propagator, epochs)
+            .unwrap();
+        assert_eq!(predicts.len(), 6);
+        assert_eq!(predicts[0], one_way);
+    }
+}
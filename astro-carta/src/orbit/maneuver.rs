@@ -0,0 +1,702 @@
+use std::f64::consts::PI;
+use std::fmt;
+
+use super::force::ForceError;
+use crate::constants::STANDARD_GRAVITY;
+use crate::datetime::{DateTime, TimeDelta};
+use crate::ephemeris::StateVector;
+use crate::math::{Matrix3, Vector3};
+
+/// Error raised while constructing or planning a maneuver
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ManeuverError {
+    /// The gravitational parameter is not positive
+    InvalidGravitationalParameter,
+    /// The state has zero position or zero angular momentum
+    DegenerateState,
+    /// The target of the maneuver cannot be reached, such as a negative radius
+    InvalidTarget(&'static str),
+    /// The thrust direction of a burn is zero or not finite
+    InvalidDirection,
+}
+
+impl fmt::Display for ManeuverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ManeuverError::InvalidGravitationalParameter => {
+                write!(f, "gravitational parameter must be positive")
+            }
+            ManeuverError::DegenerateState => {
+                write!(f, "state has no angular momentum, the orbit is degenerate")
+            }
+            ManeuverError::InvalidTarget(reason) => write!(f, "invalid maneuver target: {reason}"),
+            ManeuverError::InvalidDirection => {
+                write!(f, "thrust direction must be a finite nonzero vector")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ManeuverError {}
+
+/// Axes in which the delta-v or the thrust direction of a maneuver is given
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ManeuverFrame {
+    /// The inertial axes of the states
+    #[default]
+    Inertial,
+    /// Radial, transverse and normal axes, along the position and the angular momentum
+    Rtn,
+    /// Velocity, normal and binormal axes, along the velocity and the angular momentum
+    Vnb,
+}
+
+impl ManeuverFrame {
+    /// Returns the rotation from these axes to the inertial axes, whose columns are the axes
+    /// at the given state.
+    pub fn rotation(&self, state: &StateVector) -> Result<Matrix3, ForceError> {
+        let momentum = state.position.cross(&state.velocity);
+        let (r, v, h) = (
+            state.position.norm(),
+            state.velocity.norm(),
+            momentum.norm(),
+        );
+        let axes = match self {
+            ManeuverFrame::Inertial => return Ok(Matrix3::identity()),
+            _ if r == 0.0 || v == 0.0 || h == 0.0 => return Err(ForceError::Singular),
+            ManeuverFrame::Rtn => {
+                let (radial, normal) = (state.position / r, momentum / h);
+                [radial, normal.cross(&radial), normal]
+            }
+            ManeuverFrame::Vnb => {
+                let (velocity, normal) = (state.velocity / v, momentum / h);
+                [velocity, normal, velocity.cross(&normal)]
+            }
+        };
+        Ok(Matrix3::from_rows(&axes[0], &axes[1], &axes[2]).transpose())
+    }
+}
+
+/// An instantaneous change of velocity
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::maneuver::{ImpulsiveManeuver, ManeuverFrame};
+///
+/// let epoch = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let state = StateVector {
+///     position: Vector3::new(7e6, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, 7.5e3, 0.0),
+/// };
+/// // A prograde burn of 10 m/s
+/// let maneuver = ImpulsiveManeuver::new(epoch, Vector3::new(10.0, 0.0, 0.0))
+///     .with_frame(ManeuverFrame::Vnb);
+/// let after = maneuver.apply(&state).unwrap();
+/// assert!((after.velocity.y - 7_510.0).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImpulsiveManeuver {
+    /// Instant of the maneuver
+    pub epoch: DateTime,
+    /// Change of velocity in m/s, in the axes of the frame
+    pub delta_v: Vector3,
+    /// Axes of the delta-v, taken at the state before the maneuver
+    pub frame: ManeuverFrame,
+}
+
+impl ImpulsiveManeuver {
+    /// Constructs a maneuver whose delta-v is given in inertial axes.
+    pub fn new(epoch: DateTime, delta_v: Vector3) -> Self {
+        ImpulsiveManeuver {
+            epoch,
+            delta_v,
+            frame: ManeuverFrame::Inertial,
+        }
+    }
+
+    /// Gives the delta-v in the axes of the frame instead of the inertial axes.
+    pub fn with_frame(mut self, frame: ManeuverFrame) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    /// Returns the state just after the maneuver, from the state just before.
+    pub fn apply(&self, state: &StateVector) -> Result<StateVector, ForceError> {
+        Ok(StateVector {
+            position: state.position,
+            velocity: state.velocity + self.frame.rotation(state)? * self.delta_v,
+        })
+    }
+
+    /// Returns the state just before the maneuver, from the state just after.
+    ///
+    /// The axes of the frame depend on the state before the maneuver, so the state is found by
+    /// fixed-point iteration.
+    pub fn revert(&self, state: &StateVector) -> Result<StateVector, ForceError> {
+        let mut before = *state;
+        for _ in 0..10 {
+            let velocity = state.velocity - self.frame.rotation(&before)? * self.delta_v;
+            let change = (velocity - before.velocity).norm();
+            before.velocity = velocity;
+            if self.frame == ManeuverFrame::Inertial || change <= 1e-12 * velocity.norm() {
+                break;
+            }
+        }
+        Ok(before)
+    }
+}
+
+/// A burn of constant thrust in a fixed direction of its frame, whose mass decreases at the
+/// rate set by the specific impulse
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::{DateTime, TimeDelta};
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::maneuver::{FiniteBurn, ManeuverFrame};
+///
+/// let start = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// // A 400 N engine of 320 s on a 1500 kg spacecraft, burning for 50 m/s along the velocity
+/// let burn = FiniteBurn::from_delta_v(start, 50.0, 400.0, 320.0, 1_500.0, Vector3::new(1.0, 0.0, 0.0))
+///     .unwrap()
+///     .with_frame(ManeuverFrame::Vnb);
+/// assert!((burn.delta_v() - 50.0).abs() < 1e-9);
+/// assert!((burn.duration.total_seconds() - 186.0).abs() < 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FiniteBurn {
+    /// Instant of ignition
+    pub start: DateTime,
+    /// Time from the ignition to the cutoff
+    pub duration: TimeDelta,
+    /// Thrust in newtons
+    pub thrust: f64,
+    /// Specific impulse in seconds
+    pub specific_impulse: f64,
+    /// Mass at ignition in kilograms
+    pub mass: f64,
+    /// Unit vector of the thrust, in the axes of the frame
+    pub direction: Vector3,
+    /// Axes of the direction, which turn with the state during the burn
+    pub frame: ManeuverFrame,
+}
+
+impl FiniteBurn {
+    /// Constructs a burn in a direction of the inertial axes, normalized if needed.
+    pub fn new(
+        start: DateTime,
+        duration: TimeDelta,
+        thrust: f64,
+        specific_impulse: f64,
+        mass: f64,
+        direction: Vector3,
+    ) -> Result<Self, ManeuverError> {
+        let norm = direction.norm();
+        if !(norm > 0.0 && norm.is_finite()) {
+            return Err(ManeuverError::InvalidDirection);
+        }
+        Ok(FiniteBurn {
+            start,
+            duration,
+            thrust,
+            specific_impulse,
+            mass,
+            direction: direction / norm,
+            frame: ManeuverFrame::Inertial,
+        })
+    }
+
+    /// Constructs a burn lasting as long as needed to reach the given ideal delta-v in m/s,
+    /// from the rocket equation.
+    pub fn from_delta_v(
+        start: DateTime,
+        delta_v: f64,
+        thrust: f64,
+        specific_impulse: f64,
+        mass: f64,
+        direction: Vector3,
+    ) -> Result<Self, ManeuverError> {
+        let burn = FiniteBurn::new(
+            start,
+            TimeDelta::new(0),
+            thrust,
+            specific_impulse,
+            mass,
+            direction,
+        )?;
+        let exhaust = specific_impulse * STANDARD_GRAVITY;
+        let propellant = mass * (1.0 - (-delta_v / exhaust).exp());
+        Ok(FiniteBurn {
+            duration: TimeDelta::seconds(propellant / burn.mass_flow()),
+            ..burn
+        })
+    }
+
+    /// Gives the direction in the axes of the frame instead of the inertial axes.
+    pub fn with_frame(mut self, frame: ManeuverFrame) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    /// Returns the instant of cutoff.
+    pub fn end(&self) -> DateTime {
+        self.start + self.duration
+    }
+
+    /// Returns whether the engine is firing at the given instant, from the ignition included to
+    /// the cutoff excluded.
+    pub fn contains(&self, dt: &DateTime) -> bool {
+        self.start <= *dt && *dt < self.end()
+    }
+
+    /// Returns the rate of consumption of propellant in kg/s.
+    pub fn mass_flow(&self) -> f64 {
+        self.thrust / (self.specific_impulse * STANDARD_GRAVITY)
+    }
+
+    /// Returns the mass in kilograms at the given instant, constant before the ignition and
+    /// after the cutoff.
+    pub fn mass_at(&self, dt: &DateTime) -> f64 {
+        let elapsed = (*dt - self.start)
+            .total_seconds()
+            .clamp(0.0, self.duration.total_seconds());
+        self.mass - self.mass_flow() * elapsed
+    }
+
+    /// Returns the mass at the cutoff in kilograms.
+    pub fn final_mass(&self) -> f64 {
+        self.mass_at(&self.end())
+    }
+
+    /// Returns the ideal delta-v of the burn in m/s, from the rocket equation.
+    pub fn delta_v(&self) -> f64 {
+        self.specific_impulse * STANDARD_GRAVITY * (self.mass / self.final_mass()).ln()
+    }
+
+    /// Computes the acceleration of the thrust in m/s² on an object in the given state while
+    /// the engine is firing.
+    pub fn acceleration(&self, dt: &DateTime, state: &StateVector) -> Result<Vector3, ForceError> {
+        let mass = self.mass_at(dt);
+        if mass <= 0.0 {
+            return Err(ForceError::Singular);
+        }
+        Ok(self.frame.rotation(state)? * self.direction * (self.thrust / mass))
+    }
+}
+
+/// A maneuver performed during a numerical propagation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Maneuver {
+    Impulsive(ImpulsiveManeuver),
+    Finite(FiniteBurn),
+}
+
+impl From<ImpulsiveManeuver> for Maneuver {
+    fn from(maneuver: ImpulsiveManeuver) -> Self {
+        Maneuver::Impulsive(maneuver)
+    }
+}
+
+impl From<FiniteBurn> for Maneuver {
+    fn from(burn: FiniteBurn) -> Self {
+        Maneuver::Finite(burn)
+    }
+}
+
+/// A sequence of impulsive maneuvers reaching a target orbit
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManeuverPlan {
+    /// Maneuvers in chronological order, with their delta-v in inertial axes
+    pub maneuvers: Vec<ImpulsiveManeuver>,
+    /// Time from the first to the last maneuver
+    pub transfer_time: TimeDelta,
+}
+
+impl ManeuverPlan {
+    /// Returns the sum of the magnitudes of the delta-v in m/s.
+    pub fn total_delta_v(&self) -> f64 {
+        self.maneuvers
+            .iter()
+            .map(|maneuver| maneuver.delta_v.norm())
+            .sum()
+    }
+}
+
+/// Returns the distance and the unit vector along the direction of motion of a circular orbit
+/// through the state.
+fn circular_axes(
+    state: &StateVector,
+    gravitational_parameter: f64,
+) -> Result<(f64, Vector3), ManeuverError> {
+    if !(gravitational_parameter > 0.0 && gravitational_parameter.is_finite()) {
+        return Err(ManeuverError::InvalidGravitationalParameter);
+    }
+    let momentum = state.position.cross(&state.velocity);
+    let (r, h) = (state.position.norm(), momentum.norm());
+    if r == 0.0 || h == 0.0 {
+        return Err(ManeuverError::DegenerateState);
+    }
+    let radial = state.position / r;
+    Ok((r, (momentum / h).cross(&radial)))
+}
+
+/// Speed in m/s on an orbit of the given semi-major axis at the given distance.
+fn vis_viva(gravitational_parameter: f64, distance: f64, semi_major_axis: f64) -> f64 {
+    (gravitational_parameter * (2.0 / distance - 1.0 / semi_major_axis)).sqrt()
+}
+
+/// Half the period of an orbit of the given semi-major axis.
+fn half_period(gravitational_parameter: f64, semi_major_axis: f64) -> TimeDelta {
+    TimeDelta::seconds(PI * (semi_major_axis.powi(3) / gravitational_parameter).sqrt())
+}
+
+/// Plans a Hohmann transfer from the current distance to a circular orbit of the given radius
+/// in meters, in the plane of the state.
+///
+/// The first burn, at the epoch, enters the transfer ellipse from the current velocity, so an
+/// orbit that is not exactly circular is corrected at once. The second burn circularizes half a
+/// revolution later.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::constants::EARTH_GRAVITATIONAL_PARAMETER;
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::maneuver;
+///
+/// let mu = EARTH_GRAVITATIONAL_PARAMETER;
+/// let r = 6_678e3;
+/// let state = StateVector {
+///     position: Vector3::new(r, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, (mu / r).sqrt(), 0.0),
+/// };
+/// let epoch = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let plan = maneuver::hohmann(epoch, &state, 42_164e3, mu).unwrap();
+/// assert!((plan.total_delta_v() - 3_893.0).abs() < 1.0);
+/// assert!((plan.transfer_time.total_seconds() / 3_600.0 - 5.27).abs() < 0.01);
+/// ```
+pub fn hohmann(
+    epoch: DateTime,
+    state: &StateVector,
+    radius: f64,
+    gravitational_parameter: f64,
+) -> Result<ManeuverPlan, ManeuverError> {
+    let mu = gravitational_parameter;
+    let (r, along) = circular_axes(state, mu)?;
+    if !(radius > 0.0 && radius.is_finite()) {
+        return Err(ManeuverError::InvalidTarget("radius must be positive"));
+    }
+    let a = 0.5 * (r + radius);
+    let transfer_time = half_period(mu, a);
+    let departure = along * vis_viva(mu, r, a) - state.velocity;
+    // The second burn takes place opposite, where the direction of motion is reversed
+    let arrival = -along * ((mu / radius).sqrt() - vis_viva(mu, radius, a));
+    Ok(ManeuverPlan {
+        maneuvers: vec![
+            ImpulsiveManeuver::new(epoch, departure),
+            ImpulsiveManeuver::new(epoch + transfer_time, arrival),
+        ],
+        transfer_time,
+    })
+}
+
+/// Plans a bi-elliptic transfer from the current distance to a circular orbit of the given
+/// radius in meters through an intermediate apoapsis, in the plane of the state.
+///
+/// The transfer takes longer than a Hohmann transfer, but needs less delta-v when the ratio of
+/// the radii exceeds about 11.94 and the intermediate apoapsis is high enough.
+pub fn bi_elliptic(
+    epoch: DateTime,
+    state: &StateVector,
+    intermediate: f64,
+    radius: f64,
+    gravitational_parameter: f64,
+) -> Result<ManeuverPlan, ManeuverError> {
+    let mu = gravitational_parameter;
+    let (r, along) = circular_axes(state, mu)?;
+    if !(radius > 0.0 && radius.is_finite()) {
+        return Err(ManeuverError::InvalidTarget("radius must be positive"));
+    }
+    if !(intermediate >= r.max(radius) && intermediate.is_finite()) {
+        return Err(ManeuverError::InvalidTarget(
+            "the intermediate apoapsis must lie beyond both orbits",
+        ));
+    }
+    let (a1, a2) = (0.5 * (r + intermediate), 0.5 * (intermediate + radius));
+    let (first, second) = (half_period(mu, a1), half_period(mu, a2));
+    Ok(ManeuverPlan {
+        maneuvers: vec![
+            ImpulsiveManeuver::new(epoch, along * vis_viva(mu, r, a1) - state.velocity),
+            ImpulsiveManeuver::new(
+                epoch + first,
+                -along * (vis_viva(mu, intermediate, a2) - vis_viva(mu, intermediate, a1)),
+            ),
+            ImpulsiveManeuver::new(
+                epoch + first + second,
+                along * ((mu / radius).sqrt() - vis_viva(mu, radius, a2)),
+            ),
+        ],
+        transfer_time: first + second,
+    })
+}
+
+/// Plans a change of the plane of the orbit by rotating the velocity about the position by the
+/// given angle in radians, positive counterclockwise seen from outside.
+///
+/// At a node of the orbit, the rotation changes the inclination alone. The delta-v is
+/// `2 v sin(θ/2)` for a speed `v`.
+pub fn plane_change(
+    epoch: DateTime,
+    state: &StateVector,
+    angle: f64,
+) -> Result<ImpulsiveManeuver, ManeuverError> {
+    let r = state.position.norm();
+    if r == 0.0 {
+        return Err(ManeuverError::DegenerateState);
+    }
+    let axis = state.position / r;
+    let v = state.velocity;
+    let (sin, cos) = angle.sin_cos();
+    // Rodrigues' rotation formula
+    let rotated = v * cos + axis.cross(&v) * sin + axis * (axis.dot(&v) * (1.0 - cos));
+    Ok(ImpulsiveManeuver::new(epoch, rotated - v))
+}
+
+/// Plans a phasing maneuver on a circular orbit to meet a target on the same orbit, leading by
+/// the given angle in radians, after the given number of revolutions on a phasing orbit.
+///
+/// A target ahead is reached on a lower and faster phasing orbit, a target behind, with a
+/// negative angle, on a higher one.
+pub fn phasing(
+    epoch: DateTime,
+    state: &StateVector,
+    angle: f64,
+    revolutions: u32,
+    gravitational_parameter: f64,
+) -> Result<ManeuverPlan, ManeuverError> {
+    let mu = gravitational_parameter;
+    let (r, along) = circular_axes(state, mu)?;
+    if revolutions == 0 {
+        return Err(ManeuverError::InvalidTarget(
+            "at least one revolution is needed",
+        ));
+    }
+    let k = revolutions as f64;
+    let motion = (mu / r.powi(3)).sqrt();
+    let period = (2.0 * PI * k - angle) / (motion * k);
+    if period <= 0.0 {
+        return Err(ManeuverError::InvalidTarget(
+            "the target is too far ahead for the revolutions",
+        ));
+    }
+    let a = (mu * (period / (2.0 * PI)).powi(2)).cbrt();
+    // The periapsis of a lower phasing orbit must stay above the center
+    if 2.0 * a - r <= 0.0 {
+        return Err(ManeuverError::InvalidTarget(
+            "the phasing orbit reaches the center",
+        ));
+    }
+    let transfer_time = TimeDelta::seconds(k * period);
+    let speed = vis_viva(mu, r, a);
+    Ok(ManeuverPlan {
+        maneuvers: vec![
+            ImpulsiveManeuver::new(epoch, along * speed - state.velocity),
+            ImpulsiveManeuver::new(epoch + transfer_time, along * ((mu / r).sqrt() - speed)),
+        ],
+        transfer_time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::EARTH_GRAVITATIONAL_PARAMETER;
+    use crate::orbit::propagation;
+    use crate::orbit::KeplerianElements;
+
+    const MU: f64 = EARTH_GRAVITATIONAL_PARAMETER;
+
+    fn epoch() -> DateTime {
+        DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap()
+    }
+
+    /// A circular orbit of the given radius inclined at 30°
+    fn circular(radius: f64) -> StateVector {
+        let speed = (MU / radius).sqrt();
+        let inclination = 30f64.to_radians();
+        StateVector {
+            position: Vector3::new(radius, 0.0, 0.0),
+            velocity: Vector3::new(0.0, inclination.cos(), inclination.sin()) * speed,
+        }
+    }
+
+    /// Flies a plan from the state at the epoch, returning the state after the last maneuver.
+    fn fly(state: &StateVector, plan: &ManeuverPlan) -> StateVector {
+        let mut state = *state;
+        let mut dt = epoch();
+        for maneuver in &plan.maneuvers {
+            state = propagation::universal(&state, MU, maneuver.epoch - dt).unwrap();
+            state = maneuver.apply(&state).unwrap();
+            dt = maneuver.epoch;
+        }
+        state
+    }
+
+    fn assert_circular(state: &StateVector, radius: f64) {
+        let elements = KeplerianElements::from_state(state, MU, epoch()).unwrap();
+        assert!((state.position.norm() / radius - 1.0).abs() < 1e-9);
+        assert!(elements.eccentricity < 1e-9);
+        assert!((elements.inclination - 30f64.to_radians()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn frame_test() {
+        let state = circular(7e6);
+        let rtn = ManeuverFrame::Rtn.rotation(&state).unwrap();
+        let vnb = ManeuverFrame::Vnb.rotation(&state).unwrap();
+        let radial = rtn * Vector3::new(1.0, 0.0, 0.0);
+        assert!((radial - Vector3::new(1.0, 0.0, 0.0)).norm() < 1e-15);
+        // On a circular orbit, the transverse axis is the velocity, the normal axes agree and the
+        // binormal points outwards
+        assert!(
+            (rtn * Vector3::new(0.0, 1.0, 0.0) - vnb * Vector3::new(1.0, 0.0, 0.0)).norm() < 1e-15
+        );
+        assert!(
+            (rtn * Vector3::new(0.0, 0.0, 1.0) - vnb * Vector3::new(0.0, 1.0, 0.0)).norm() < 1e-15
+        );
+        assert!((vnb * Vector3::new(0.0, 0.0, 1.0) - radial).norm() < 1e-15);
+        let still = StateVector {
+            position: state.position,
+            velocity: Vector3::zeros(),
+        };
+        assert_eq!(
+            ManeuverFrame::Vnb.rotation(&still),
+            Err(ForceError::Singular)
+        );
+
+        let maneuver = ImpulsiveManeuver::new(epoch(), Vector3::new(100.0, -50.0, 20.0))
+            .with_frame(ManeuverFrame::Vnb);
+        let after = maneuver.apply(&state).unwrap();
+        let before = maneuver.revert(&after).unwrap();
+        assert!((before.velocity - state.velocity).norm() < 1e-9);
+    }
+
+    #[test]
+    fn hohmann_test() {
+        for (from, to) in [(6_678e3, 42_164e3), (26_000e3, 7_000e3)] {
+            let state = circular(from);
+            let plan = hohmann(epoch(), &state, to, MU).unwrap();
+            assert_eq!(plan.maneuvers.len(), 2);
+            let a = 0.5 * (from + to);
+            let expected = ((MU / from).sqrt() - vis_viva(MU, from, a)).abs()
+                + ((MU / to).sqrt() - vis_viva(MU, to, a)).abs();
+            assert!((plan.total_delta_v() - expected).abs() < 1e-9);
+            assert_circular(&fly(&state, &plan), to);
+        }
+        assert!(hohmann(epoch(), &circular(7e6), -1.0, MU).is_err());
+    }
+
+    #[test]
+    fn bi_elliptic_test() {
+        let (from, to) = (7_000e3, 105_000e3);
+        let state = circular(from);
+        let plan = bi_elliptic(epoch(), &state, 210_000e3, to, MU).unwrap();
+        assert_eq!(plan.maneuvers.len(), 3);
+        assert_circular(&fly(&state, &plan), to);
+        // Beyond a ratio of 15.58, any bi-elliptic transfer beats the Hohmann transfer
+        let direct = hohmann(epoch(), &state, to, MU).unwrap();
+        assert!(plan.total_delta_v() < direct.total_delta_v());
+        assert!(plan.transfer_time > direct.transfer_time);
+        assert_eq!(
+            bi_elliptic(epoch(), &state, 50_000e3, to, MU),
+            Err(ManeuverError::InvalidTarget(
+                "the intermediate apoapsis must lie beyond both orbits"
+            ))
+        );
+    }
+
+    #[test]
+    fn plane_change_test() {
+        let state = circular(7e6);
+        let angle = 10f64.to_radians();
+        let maneuver = plane_change(epoch(), &state, angle).unwrap();
+        let speed = state.velocity.norm();
+        assert!((maneuver.delta_v.norm() - 2.0 * speed * (angle / 2.0).sin()).abs() < 1e-9);
+        let after = maneuver.apply(&state).unwrap();
+        let elements = KeplerianElements::from_state(&after, MU, epoch()).unwrap();
+        assert!((elements.inclination - 40f64.to_radians()).abs() < 1e-12);
+        assert!((after.velocity.norm() - speed).abs() < 1e-9);
+    }
+
+    #[test]
+    fn phasing_test() {
+        let radius = 7_000e3;
+        let state = circular(radius);
+        let period = 2.0 * PI * (radius.powi(3) / MU).sqrt();
+        for (angle, revolutions) in [(0.5, 1), (-0.3, 2), (1.0, 3)] {
+            let plan = phasing(epoch(), &state, angle, revolutions, MU).unwrap();
+            // The target leads by the angle on the same orbit
+            let lead = TimeDelta::seconds(angle / (2.0 * PI) * period);
+            let target = propagation::universal(&state, MU, lead).unwrap();
+            let after = fly(&state, &plan);
+            let target = propagation::universal(&target, MU, plan.transfer_time).unwrap();
+            assert!((after.position - target.position).norm() < 1e-3);
+            assert!((after.velocity - target.velocity).norm() < 1e-6);
+            assert_eq!(
+                plan.transfer_time > TimeDelta::seconds(period * revolutions as f64),
+                angle < 0.0
+            );
+        }
+        assert!(phasing(epoch(), &state, 0.5, 0, MU).is_err());
+        assert!(phasing(epoch(), &state, 6.0, 1, MU).is_err());
+    }
+
+    #[test]
+    fn finite_burn_test() {
+        let burn = FiniteBurn::new(
+            epoch(),
+            TimeDelta::minutes(10.0),
+            500.0,
+            300.0,
+            2_000.0,
+            Vector3::new(0.0, 2.0, 0.0),
+        )
+        .unwrap();
+        assert_eq!(burn.direction, Vector3::new(0.0, 1.0, 0.0));
+        let flow = 500.0 / (300.0 * STANDARD_GRAVITY);
+        assert!((burn.final_mass() - (2_000.0 - 600.0 * flow)).abs() < 1e-9);
+        assert_eq!(burn.mass_at(&(epoch() - TimeDelta::hours(1.0))), 2_000.0);
+        assert_eq!(
+            burn.mass_at(&(epoch() + TimeDelta::hours(1.0))),
+            burn.final_mass()
+        );
+        assert!(burn.contains(&epoch()) && !burn.contains(&burn.end()));
+        let acceleration = burn.acceleration(&epoch(), &circular(7e6)).unwrap();
+        assert!((acceleration.y - 0.25).abs() < 1e-15);
+
+        let same = FiniteBurn::from_delta_v(
+            epoch(),
+            burn.delta_v(),
+            500.0,
+            300.0,
+            2_000.0,
+            burn.direction,
+        )
+        .unwrap();
+        assert!((same.duration - burn.duration).total_seconds().abs() < 1e-6);
+
+        for direction in [Vector3::zeros(), Vector3::new(f64::NAN, 0.0, 0.0)] {
+            assert_eq!(
+                FiniteBurn::new(epoch(), burn.duration, 500.0, 300.0, 2_000.0, direction),
+                Err(ManeuverError::InvalidDirection)
+            );
+        }
+    }
+}
@@ -0,0 +1,806 @@
+use std::fmt;
+
+use super::force::{ForceError, ForceModel};
+use super::integrator::{Integrator, Solution};
+use super::maneuver::{FiniteBurn, Maneuver};
+use super::Propagator;
+use crate::datetime::{DateTime, TimeDelta};
+use crate::ephemeris::StateVector;
+use crate::math::{Matrix3, Matrix6, Vector3};
+
+/// Step of the finite differences of the force models without a gradient, relative to the
+/// norms of the position and the velocity
+const DIFFERENCE_STEP: f64 = 1e-5;
+
+/// Error raised by a numerical propagation
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropagationError {
+    /// A force model could not be evaluated
+    Force(ForceError),
+    /// The step size fell below the smallest step allowed, or a fixed step is not positive
+    StepSizeUnderflow,
+    /// The largest number of steps was reached before the end of the propagation
+    TooManySteps,
+    /// The instant lies outside the span of a trajectory
+    OutOfRange,
+}
+
+impl fmt::Display for PropagationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PropagationError::Force(error) => write!(f, "{error}"),
+            PropagationError::StepSizeUnderflow => write!(f, "step size too small"),
+            PropagationError::TooManySteps => write!(f, "too many integration steps"),
+            PropagationError::OutOfRange => write!(f, "instant outside the propagated span"),
+        }
+    }
+}
+
+impl std::error::Error for PropagationError {}
+
+impl From<ForceError> for PropagationError {
+    fn from(error: ForceError) -> Self {
+        PropagationError::Force(error)
+    }
+}
+
+/// Propagates a state by numerical integration of the sum of force models
+///
+/// The state is relative to the central body in an inertial frame, and each call of
+/// [`Propagator::propagate`] integrates from the epoch. To sample many instants, integrate once
+/// with [`trajectory`](NumericalPropagator::trajectory) and interpolate its dense output.
+///
+/// Maneuvers added with [`with_maneuver`](NumericalPropagator::with_maneuver) split the
+/// integration at their epochs. The initial state precedes the impulses at the epoch, and the
+/// state at the epoch of an impulse is the state just after it, forwards and backwards.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::constants::EARTH_GRAVITATIONAL_PARAMETER;
+/// use astro_carta::datetime::{DateTime, TimeDelta};
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::force::PointMass;
+/// use astro_carta::orbit::integrator::{Integrator, StepControl};
+/// use astro_carta::orbit::numerical::NumericalPropagator;
+/// use astro_carta::orbit::{propagation, Propagator};
+///
+/// let epoch = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let state = StateVector {
+///     position: Vector3::new(7e6, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, 7.0e3, 2.5e3),
+/// };
+/// let propagator = NumericalPropagator::new(
+///     epoch,
+///     state,
+///     Integrator::DormandPrince853(StepControl::new(1e-12, 1e-6)),
+/// )
+/// .with_force(PointMass::new(EARTH_GRAVITATIONAL_PARAMETER));
+///
+/// let end = epoch + TimeDelta::hours(3.0);
+/// let trajectory = propagator.trajectory(&end).unwrap();
+/// let dt = epoch + TimeDelta::minutes(100.0);
+/// let expected = propagation::universal(&state, EARTH_GRAVITATIONAL_PARAMETER, dt - epoch).unwrap();
+/// assert!((trajectory.propagate(&dt).unwrap().position - expected.position).norm() < 1e-2);
+/// ```
+#[derive(Debug)]
+pub struct NumericalPropagator {
+    /// Epoch of the initial state
+    pub epoch: DateTime,
+    /// Initial state, with position in meters and velocity in m/s
+    pub state: StateVector,
+    pub integrator: Integrator,
+    forces: Vec<Box<dyn ForceModel>>,
+    maneuvers: Vec<Maneuver>,
+}
+
+impl NumericalPropagator {
+    /// Constructs a propagator without any force, to which models are added with
+    /// [`with_force`](NumericalPropagator::with_force).
+    pub fn new(epoch: DateTime, state: StateVector, integrator: Integrator) -> Self {
+        NumericalPropagator {
+            epoch,
+            state,
+            integrator,
+            forces: Vec::new(),
+            maneuvers: Vec::new(),
+        }
+    }
+
+    /// Adds a force model.
+    pub fn with_force(mut self, force: impl ForceModel + 'static) -> Self {
+        self.forces.push(Box::new(force));
+        self
+    }
+
+    /// Returns the force models.
+    pub fn forces(&self) -> &[Box<dyn ForceModel>] {
+        &self.forces
+    }
+
+    /// Adds an impulsive maneuver or a finite burn.
+    ///
+    /// The mass of a finite burn decreases from its own initial mass, whatever the other burns.
+    pub fn with_maneuver(mut self, maneuver: impl Into<Maneuver>) -> Self {
+        self.maneuvers.push(maneuver.into());
+        self
+    }
+
+    /// Returns the maneuvers.
+    pub fn maneuvers(&self) -> &[Maneuver] {
+        &self.maneuvers
+    }
+
+    /// Computes the sum of the accelerations in m/s² acting on the object in the given state,
+    /// including the thrust of the finite burns firing at the given instant.
+    pub fn acceleration(&self, dt: &DateTime, state: &StateVector) -> Result<Vector3, ForceError> {
+        self.total_acceleration(dt, state, &self.burns(dt))
+    }
+
+    /// Returns the finite burns firing at the given instant.
+    fn burns(&self, dt: &DateTime) -> Vec<&FiniteBurn> {
+        self.maneuvers
+            .iter()
+            .filter_map(|maneuver| match maneuver {
+                Maneuver::Finite(burn) if burn.contains(dt) => Some(burn),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn total_acceleration(
+        &self,
+        dt: &DateTime,
+        state: &StateVector,
+        burns: &[&FiniteBurn],
+    ) -> Result<Vector3, ForceError> {
+        let mut acceleration = Vector3::zeros();
+        for force in &self.forces {
+            acceleration += force.acceleration(dt, state)?;
+        }
+        for burn in burns {
+            acceleration += burn.acceleration(dt, state)?;
+        }
+        Ok(acceleration)
+    }
+
+    /// Computes the partial derivatives of the time derivative of the state, position and
+    /// velocity, with respect to the state.
+    ///
+    /// The matrix is `[[0, I], [∂a/∂r, ∂a/∂v]]`. The acceleration of a model that provides its
+    /// [`gradient`](ForceModel::gradient) is taken to be independent of the velocity, and the
+    /// partials of the other models and of the thrust of the finite burns firing at the given
+    /// instant are computed by central differences.
+    pub fn jacobian(&self, dt: &DateTime, state: &StateVector) -> Result<Matrix6, ForceError> {
+        self.total_jacobian(dt, state, &self.burns(dt))
+    }
+
+    fn total_jacobian(
+        &self,
+        dt: &DateTime,
+        state: &StateVector,
+        burns: &[&FiniteBurn],
+    ) -> Result<Matrix6, ForceError> {
+        let mut position = Matrix3::new([[0.0; 3]; 3]);
+        let mut velocity = Matrix3::new([[0.0; 3]; 3]);
+        for force in &self.forces {
+            if let Some(gradient) = force.gradient(dt, state)? {
+                position = position + gradient;
+                continue;
+            }
+            differences(state, &mut position, &mut velocity, |state| {
+                force.acceleration(dt, state)
+            })?;
+        }
+        for burn in burns {
+            differences(state, &mut position, &mut velocity, |state| {
+                burn.acceleration(dt, state)
+            })?;
+        }
+        Ok(Matrix6::from_blocks(
+            &Matrix3::new([[0.0; 3]; 3]),
+            &Matrix3::identity(),
+            &position,
+            &velocity,
+        ))
+    }
+
+    /// Integrates from the epoch to the given instant, keeping the dense output of every step.
+    pub fn trajectory(&self, end: &DateTime) -> Result<Trajectory, PropagationError> {
+        Ok(Trajectory {
+            epoch: self.epoch,
+            segments: self.integrate(end, true, false)?,
+        })
+    }
+
+    /// Integrates the state with the variational equations from the epoch to the given
+    /// instant, returning the state and the state transition matrix `∂x(t)/∂x(t₀)`.
+    ///
+    /// The delta-v of an impulsive maneuver is taken to be independent of the state, so the
+    /// transition matrix is continuous across impulses.
+    pub fn propagate_with_transition(
+        &self,
+        dt: &DateTime,
+    ) -> Result<(StateVector, Matrix6), PropagationError> {
+        let segments = self.integrate(dt, false, true)?;
+        let state = final_state(&segments);
+        Ok((from_slice(state), transition_from_slice(state)))
+    }
+
+    /// Integrates the state with the variational equations from the epoch to the given
+    /// instant, keeping the dense output of every step.
+    pub fn variational_trajectory(
+        &self,
+        end: &DateTime,
+    ) -> Result<VariationalTrajectory, PropagationError> {
+        Ok(VariationalTrajectory {
+            trajectory: Trajectory {
+                epoch: self.epoch,
+                segments: self.integrate(end, true, true)?,
+            },
+        })
+    }
+
+    /// Integrates the state, followed by the 36 elements of the state transition matrix in
+    /// row-major order if `variational` is set.
+    ///
+    /// The integration is split into segments at the impulses and at the ignitions and cutoffs
+    /// of the burns, so that no step straddles a discontinuity. Forwards, an impulse at the end
+    /// adds a segment of zero length holding the state after it.
+    fn integrate(
+        &self,
+        end: &DateTime,
+        dense: bool,
+        variational: bool,
+    ) -> Result<Vec<Solution>, PropagationError> {
+        let mut state = to_array(&self.state).to_vec();
+        if variational {
+            state.extend(Matrix6::identity().elements.iter().flatten());
+        }
+        let duration = (*end - self.epoch).total_seconds();
+        let sign = if duration < 0.0 { -1.0 } else { 1.0 };
+        let offset = |dt: &DateTime| (*dt - self.epoch).total_seconds();
+
+        let mut boundaries: Vec<f64> = self
+            .maneuvers
+            .iter()
+            .flat_map(|maneuver| match maneuver {
+                Maneuver::Impulsive(impulse) => vec![offset(&impulse.epoch)],
+                Maneuver::Finite(burn) => vec![offset(&burn.start), offset(&burn.end())],
+            })
+            .filter(|time| sign * time > 0.0 && sign * time < sign * duration)
+            .collect();
+        boundaries.push(duration);
+        boundaries.sort_by(|a, b| (sign * a).total_cmp(&(sign * b)));
+        boundaries.dedup();
+
+        let mut segments = Vec::with_capacity(boundaries.len() + 1);
+        let mut start = 0.0;
+        for end in boundaries {
+            // Forwards, the impulses at the start of the segment are applied before it, and
+            // backwards, the impulses at the start of the segment after the epoch are reverted
+            if sign > 0.0 || start != 0.0 {
+                self.impulses(&mut state, start, sign > 0.0)?;
+            }
+            let middle = self.epoch + TimeDelta::seconds(0.5 * (start + end));
+            let burns = self.burns(&middle);
+            let solution =
+                self.integrator
+                    .integrate(start, &state, end, dense, |time, y, dy| {
+                        let state = from_slice(y);
+                        let dt = self.epoch + TimeDelta::seconds(time);
+                        let acceleration = self.total_acceleration(&dt, &state, &burns)?;
+                        dy[..3].copy_from_slice(&y[3..6]);
+                        dy[3..6].copy_from_slice(&acceleration.to_array());
+                        if variational {
+                            let derivative = self.total_jacobian(&dt, &state, &burns)?
+                                * transition_from_slice(y);
+                            for (dy, value) in
+                                dy[6..].iter_mut().zip(derivative.elements.iter().flatten())
+                            {
+                                *dy = *value;
+                            }
+                        }
+                        Ok(())
+                    })?;
+            state = solution.state().to_vec();
+            segments.push(solution);
+            start = end;
+        }
+
+        if sign > 0.0 {
+            let before = state.clone();
+            self.impulses(&mut state, duration, true)?;
+            if state != before {
+                segments.push(self.integrator.integrate(
+                    duration,
+                    &state,
+                    duration,
+                    dense,
+                    |_, _, _| Ok(()),
+                )?);
+            }
+        }
+        Ok(segments)
+    }
+
+    /// Applies the impulses at the given time to the state, or reverts them.
+    fn impulses(&self, y: &mut [f64], time: f64, apply: bool) -> Result<(), ForceError> {
+        let mut state = from_slice(y);
+        for maneuver in &self.maneuvers {
+            if let Maneuver::Impulsive(impulse) = maneuver {
+                if (impulse.epoch - self.epoch).total_seconds() == time {
+                    state = if apply {
+                        impulse.apply(&state)?
+                    } else {
+                        impulse.revert(&state)?
+                    };
+                }
+            }
+        }
+        y[..6].copy_from_slice(&to_array(&state));
+        Ok(())
+    }
+}
+
+impl Propagator for NumericalPropagator {
+    type Error = PropagationError;
+
+    fn propagate(&self, dt: &DateTime) -> Result<StateVector, Self::Error> {
+        Ok(from_slice(final_state(&self.integrate(dt, false, false)?)))
+    }
+}
+
+/// States of a numerical propagation over a span of time, interpolated by the dense output of
+/// the integrator
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trajectory {
+    epoch: DateTime,
+    /// Solutions between consecutive maneuvers, in the order of integration
+    segments: Vec<Solution>,
+}
+
+impl Trajectory {
+    /// Returns the instant of the initial state.
+    pub fn start(&self) -> DateTime {
+        self.epoch
+    }
+
+    /// Returns the instant of the final state.
+    pub fn end(&self) -> DateTime {
+        self.epoch + TimeDelta::seconds(self.segments[self.segments.len() - 1].end())
+    }
+
+    /// Returns the final state.
+    pub fn final_state(&self) -> StateVector {
+        from_slice(final_state(&self.segments))
+    }
+
+    /// Returns the number of integration steps.
+    pub fn steps(&self) -> usize {
+        self.segments.iter().map(Solution::steps).sum()
+    }
+
+    /// Interpolates the integrated vector at the given instant, just after the impulse if one
+    /// takes place at this instant.
+    fn evaluate(&self, dt: &DateTime) -> Option<Vec<f64>> {
+        let time = (*dt - self.epoch).total_seconds();
+        self.segments
+            .iter()
+            .filter(|segment| {
+                let (start, end) = (segment.start(), segment.end());
+                time >= start.min(end) && time <= start.max(end)
+            })
+            .max_by(|a, b| a.start().min(a.end()).total_cmp(&b.start().min(b.end())))
+            .and_then(|segment| segment.evaluate(time))
+    }
+}
+
+impl Propagator for Trajectory {
+    type Error = PropagationError;
+
+    fn propagate(&self, dt: &DateTime) -> Result<StateVector, Self::Error> {
+        self.evaluate(dt)
+            .map(|y| from_slice(&y))
+            .ok_or(PropagationError::OutOfRange)
+    }
+}
+
+/// States and state transition matrices of a numerical propagation over a span of time,
+/// interpolated by the dense output of the integrator
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariationalTrajectory {
+    trajectory: Trajectory,
+}
+
+impl VariationalTrajectory {
+    /// Returns the trajectory of the states.
+    pub fn trajectory(&self) -> &Trajectory {
+        &self.trajectory
+    }
+
+    /// Returns the final state transition matrix.
+    pub fn final_transition(&self) -> Matrix6 {
+        transition_from_slice(final_state(&self.trajectory.segments))
+    }
+
+    /// Interpolates the state transition matrix from the epoch to the given instant.
+    pub fn transition(&self, dt: &DateTime) -> Result<Matrix6, PropagationError> {
+        self.trajectory
+            .evaluate(dt)
+            .map(|y| transition_from_slice(&y))
+            .ok_or(PropagationError::OutOfRange)
+    }
+}
+
+impl Propagator for VariationalTrajectory {
+    type Error = PropagationError;
+
+    fn propagate(&self, dt: &DateTime) -> Result<StateVector, Self::Error> {
+        self.trajectory.propagate(dt)
+    }
+}
+
+pub(crate) fn to_array(state: &StateVector) -> [f64; 6] {
+    let (r, v) = (state.position, state.velocity);
+    [r.x, r.y, r.z, v.x, v.y, v.z]
+}
+
+pub(crate) fn from_slice(y: &[f64]) -> StateVector {
+    StateVector {
+        position: Vector3::new(y[0], y[1], y[2]),
+        velocity: Vector3::new(y[3], y[4], y[5]),
+    }
+}
+
+/// Adds the partials of an acceleration with respect to the position and the velocity,
+/// computed by central differences.
+fn differences<F>(
+    state: &StateVector,
+    position: &mut Matrix3,
+    velocity: &mut Matrix3,
+    acceleration: F,
+) -> Result<(), ForceError>
+where
+    F: Fn(&StateVector) -> Result<Vector3, ForceError>,
+{
+    let mut y = to_array(state);
+    for j in 0..6 {
+        let h = DIFFERENCE_STEP
+            * if j < 3 {
+                state.position.norm()
+            } else {
+                state.velocity.norm()
+            }
+            .max(1.0);
+        let value = y[j];
+        y[j] = value + h;
+        let forward = acceleration(&from_slice(&y))?;
+        y[j] = value - h;
+        let backward = acceleration(&from_slice(&y))?;
+        y[j] = value;
+        let partials = if j < 3 {
+            &mut *position
+        } else {
+            &mut *velocity
+        };
+        for i in 0..3 {
+            partials[(i, j % 3)] += (forward[i] - backward[i]) / (2.0 * h);
+        }
+    }
+    Ok(())
+}
+
+fn final_state(segments: &[Solution]) -> &[f64] {
+    segments[segments.len() - 1].state()
+}
+
+fn transition_from_slice(y: &[f64]) -> Matrix6 {
+    Matrix6::new(std::array::from_fn(|i| {
+        std::array::from_fn(|j| y[6 + 6 * i + j])
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::EARTH_GRAVITATIONAL_PARAMETER;
+    use crate::constants::STANDARD_GRAVITY;
+    use crate::orbit::force::PointMass;
+    use crate::orbit::integrator::StepControl;
+    use crate::orbit::maneuver::{self, FiniteBurn, ImpulsiveManeuver, ManeuverFrame};
+    use crate::orbit::propagation;
+
+    fn propagator(integrator: Integrator) -> NumericalPropagator {
+        let epoch = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+        // A Molniya-like orbit, eccentric enough for the step size to vary widely
+        let state = StateVector {
+            position: Vector3::new(7.0e6, 0.0, 0.0),
+            velocity: Vector3::new(0.0, 5.0e3, 8.5e3),
+        };
+        NumericalPropagator::new(epoch, state, integrator)
+            .with_force(PointMass::new(EARTH_GRAVITATIONAL_PARAMETER))
+    }
+
+    #[test]
+    fn two_body_test() {
+        let cases = [
+            (
+                Integrator::Rk4 {
+                    step: TimeDelta::seconds(5.0),
+                },
+                1.0,
+            ),
+            (
+                Integrator::DormandPrince54(StepControl::new(1e-12, 1e-6)),
+                1.0,
+            ),
+            (
+                Integrator::DormandPrince853(StepControl::new(1e-13, 1e-6)),
+                1e-2,
+            ),
+        ];
+        for (integrator, tolerance) in cases {
+            let propagator = propagator(integrator);
+            let end = propagator.epoch + TimeDelta::days(1.0);
+            let expected = propagation::universal(
+                &propagator.state,
+                EARTH_GRAVITATIONAL_PARAMETER,
+                end - propagator.epoch,
+            )
+            .unwrap();
+            let state = propagator.propagate(&end).unwrap();
+            let error = (state.position - expected.position).norm();
+            assert!(error < tolerance, "{integrator:?}: {error}");
+
+            let trajectory = propagator.trajectory(&end).unwrap();
+            assert_eq!(trajectory.final_state(), state);
+            assert_eq!(trajectory.end(), end);
+        }
+    }
+
+    #[test]
+    fn trajectory_test() {
+        let propagator = propagator(Integrator::DormandPrince853(StepControl::new(1e-13, 1e-6)));
+        let start = propagator.epoch;
+        let end = start - TimeDelta::hours(12.0);
+        let trajectory = propagator.trajectory(&end).unwrap();
+        assert_eq!(trajectory.start(), start);
+
+        for minutes in (0..=720).step_by(7) {
+            let dt = start - TimeDelta::minutes(minutes as f64);
+            let expected = propagation::universal(
+                &propagator.state,
+                EARTH_GRAVITATIONAL_PARAMETER,
+                dt - start,
+            )
+            .unwrap();
+            let state = trajectory.propagate(&dt).unwrap();
+            assert!((state.position - expected.position).norm() < 1e-2);
+            assert!((state.velocity - expected.velocity).norm() < 1e-5);
+        }
+        assert_eq!(
+            trajectory.propagate(&(start + TimeDelta::seconds(1.0))),
+            Err(PropagationError::OutOfRange)
+        );
+    }
+
+    /// A velocity-dependent acceleration without a gradient, so that its partials are computed
+    /// by finite differences
+    #[derive(Debug)]
+    struct Damping(f64);
+
+    impl ForceModel for Damping {
+        fn acceleration(&self, _dt: &DateTime, state: &StateVector) -> Result<Vector3, ForceError> {
+            Ok(state.velocity * (-self.0 * state.velocity.norm()))
+        }
+    }
+
+    #[test]
+    fn transition_test() {
+        let integrator = Integrator::DormandPrince853(StepControl::new(1e-12, 1e-8));
+        for damping in [None, Some(Damping(1e-12))] {
+            let mut propagator = propagator(integrator);
+            if let Some(damping) = damping {
+                propagator = propagator.with_force(damping);
+            }
+            let end = propagator.epoch + TimeDelta::hours(2.0);
+            let (state, transition) = propagator.propagate_with_transition(&end).unwrap();
+            assert!((state.position - propagator.propagate(&end).unwrap().position).norm() < 1e-3);
+
+            // Each column is the sensitivity of the final state to a component of the initial
+            // state, compared with central differences of the propagation
+            let initial = propagator.state;
+            for j in 0..6 {
+                let h = if j < 3 { 1.0 } else { 1e-3 };
+                let mut shifted = |sign: f64| {
+                    let mut y = to_array(&initial);
+                    y[j] += sign * h;
+                    propagator.state = from_slice(&y);
+                    to_array(&propagator.propagate(&end).unwrap())
+                };
+                let (forward, backward) = (shifted(1.0), shifted(-1.0));
+                for i in 0..6 {
+                    let difference = (forward[i] - backward[i]) / (2.0 * h);
+                    let scale: f64 = if i < 3 { 1.0 } else { 1e-3 };
+                    assert!(
+                        (transition[(i, j)] - difference).abs()
+                            < 1e-5 * scale.max(difference.abs()),
+                        "({i}, {j}): {} != {difference}",
+                        transition[(i, j)]
+                    );
+                }
+            }
+            propagator.state = initial;
+
+            let trajectory = propagator.variational_trajectory(&end).unwrap();
+            assert_eq!(trajectory.final_transition(), transition);
+            let mid = propagator.epoch + TimeDelta::minutes(47.0);
+            let (state, expected) = propagator.propagate_with_transition(&mid).unwrap();
+            let interpolated = trajectory.transition(&mid).unwrap();
+            assert!((trajectory.propagate(&mid).unwrap().position - state.position).norm() < 1e-3);
+            for i in 0..6 {
+                for j in 0..6 {
+                    let error = interpolated[(i, j)] - expected[(i, j)];
+                    assert!(error.abs() < 1e-6 * expected[(i, j)].abs().max(1.0));
+                }
+            }
+            assert_eq!(
+                trajectory.transition(&(end + TimeDelta::seconds(1.0))),
+                Err(PropagationError::OutOfRange)
+            );
+        }
+    }
+
+    #[test]
+    fn force_error_test() {
+        let epoch = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+        let propagator = NumericalPropagator::new(
+            epoch,
+            StateVector {
+                position: Vector3::zeros(),
+                velocity: Vector3::zeros(),
+            },
+            Integrator::DormandPrince54(StepControl::default()),
+        )
+        .with_force(PointMass::new(EARTH_GRAVITATIONAL_PARAMETER));
+        assert_eq!(
+            propagator.propagate(&(epoch + TimeDelta::seconds(1.0))),
+            Err(PropagationError::Force(ForceError::Singular))
+        );
+        assert_eq!(propagator.forces().len(), 1);
+    }
+
+    #[test]
+    fn impulsive_maneuver_test() {
+        let mu = EARTH_GRAVITATIONAL_PARAMETER;
+        let epoch = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+        let state = StateVector {
+            position: Vector3::new(6_778e3, 0.0, 0.0),
+            velocity: Vector3::new(0.0, (mu / 6_778e3).sqrt(), 0.0),
+        };
+        let plan = maneuver::hohmann(epoch, &state, 26_560e3, mu).unwrap();
+        let mut propagator = NumericalPropagator::new(
+            epoch,
+            state,
+            Integrator::DormandPrince853(StepControl::new(1e-13, 1e-6)),
+        )
+        .with_force(PointMass::new(mu));
+        for maneuver in &plan.maneuvers {
+            propagator = propagator.with_maneuver(*maneuver);
+        }
+        assert_eq!(propagator.maneuvers().len(), 2);
+
+        // The states at the epochs of the impulses follow them
+        let arrival = epoch + plan.transfer_time;
+        let after = propagator.propagate(&arrival).unwrap();
+        assert!((after.position.norm() - 26_560e3).abs() < 1e-1);
+        assert!((after.velocity.norm() - (mu / 26_560e3).sqrt()).abs() < 1e-5);
+
+        let end = arrival + TimeDelta::hours(3.0);
+        let trajectory = propagator.trajectory(&end).unwrap();
+        let expected = propagation::universal(&after, mu, end - arrival).unwrap();
+        assert!((trajectory.final_state().position - expected.position).norm() < 1e-1);
+        assert_eq!(trajectory.propagate(&arrival).unwrap(), after);
+        let transfer = plan.maneuvers[0].apply(&state).unwrap();
+        assert!((trajectory.propagate(&epoch).unwrap().velocity - transfer.velocity).norm() < 1e-9);
+
+        // Backwards from the final state, the impulses are reverted to the initial state
+        let backward =
+            NumericalPropagator::new(end, trajectory.final_state(), propagator.integrator)
+                .with_force(PointMass::new(mu))
+                .with_maneuver(plan.maneuvers[0])
+                .with_maneuver(plan.maneuvers[1]);
+        let trajectory = backward.trajectory(&epoch).unwrap();
+        assert!((trajectory.final_state().position - transfer.position).norm() < 1e-1);
+        assert!((trajectory.final_state().velocity - transfer.velocity).norm() < 1e-4);
+        let middle = epoch + TimeDelta::hours(2.0);
+        assert!(
+            (trajectory.propagate(&middle).unwrap().position
+                - propagator.propagate(&middle).unwrap().position)
+                .norm()
+                < 1e-1
+        );
+        // The variational equations are split at the same impulses
+        let (state, _) = propagator.propagate_with_transition(&end).unwrap();
+        assert!((state.position - expected.position).norm() < 1e-1);
+    }
+
+    #[test]
+    fn finite_burn_test() {
+        let epoch = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+        let state = StateVector {
+            position: Vector3::new(7e6, 0.0, 0.0),
+            velocity: Vector3::new(0.0, 7.5e3, 0.0),
+        };
+        let burn = FiniteBurn::new(
+            epoch + TimeDelta::minutes(1.0),
+            TimeDelta::minutes(5.0),
+            1_000.0,
+            300.0,
+            1_000.0,
+            Vector3::new(0.0, 1.0, 0.0),
+        )
+        .unwrap();
+        let propagator = NumericalPropagator::new(
+            epoch,
+            state,
+            Integrator::DormandPrince853(StepControl::new(1e-13, 1e-9)),
+        )
+        .with_maneuver(burn);
+
+        // Without any force, the burn adds the delta-v of the rocket equation
+        let end = epoch + TimeDelta::minutes(10.0);
+        let trajectory = propagator.trajectory(&end).unwrap();
+        let delta_v = 300.0 * STANDARD_GRAVITY * (1_000.0 / burn.final_mass()).ln();
+        assert!((trajectory.final_state().velocity.y - 7.5e3 - delta_v).abs() < 1e-6);
+        assert_eq!(
+            trajectory
+                .propagate(&(epoch + TimeDelta::seconds(30.0)))
+                .unwrap()
+                .velocity,
+            state.velocity
+        );
+        let firing = epoch + TimeDelta::minutes(2.0);
+        assert_eq!(
+            propagator.acceleration(&firing, &state).unwrap(),
+            burn.acceleration(&firing, &state).unwrap()
+        );
+        assert_eq!(
+            propagator.acceleration(&end, &state).unwrap(),
+            Vector3::zeros()
+        );
+
+        // Along the velocity, the thrust turns with the orbit
+        let mu = EARTH_GRAVITATIONAL_PARAMETER;
+        let state = StateVector {
+            position: state.position,
+            velocity: Vector3::new(0.0, (mu / 7e6).sqrt(), 0.0),
+        };
+        let burn = FiniteBurn::from_delta_v(
+            epoch,
+            100.0,
+            1_000.0,
+            300.0,
+            1_000.0,
+            Vector3::new(1.0, 0.0, 0.0),
+        )
+        .unwrap()
+        .with_frame(ManeuverFrame::Vnb);
+        let propagator = NumericalPropagator::new(epoch, state, propagator.integrator)
+            .with_force(PointMass::new(mu))
+            .with_maneuver(burn);
+        let after = propagator.propagate(&burn.end()).unwrap();
+        let impulse = ImpulsiveManeuver::new(epoch, Vector3::new(100.0, 0.0, 0.0))
+            .with_frame(ManeuverFrame::Vnb)
+            .apply(&state)
+            .unwrap();
+        let energy =
+            |state: &StateVector| 0.5 * state.velocity.norm().powi(2) - mu / state.position.norm();
+        // A short burn is close to the impulse of the same delta-v
+        assert!((energy(&after) / energy(&impulse) - 1.0).abs() < 1e-3);
+        let (_, transition) = propagator.propagate_with_transition(&burn.end()).unwrap();
+        assert!(transition[(3, 3)].is_finite());
+    }
+}
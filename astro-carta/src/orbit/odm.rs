@@ -0,0 +1,878 @@
+pub mod oem;
+pub mod omm;
+pub mod opm;
+
+mod kvn;
+mod xml;
+
+pub use oem::{Oem, OemSegment, OemState};
+pub use omm::{MeanOrbitSize, Omm, TleParameters};
+pub use opm::{Maneuver, Opm};
+
+use std::fmt;
+
+use crate::datetime::{DateTime, TimeDelta, TimeScale};
+
+/// Number of meters in a kilometer, the length unit of orbit data messages
+const METERS_PER_KILOMETER: f64 = 1_000.0;
+
+/// Version written into the messages produced by this crate
+const VERSION: &str = "3.0";
+
+/// Versions of the orbit data message standard accepted by the readers
+const SUPPORTED_VERSIONS: [&str; 3] = ["1.0", "2.0", "3.0"];
+
+/// Names of the 21 elements of the lower triangle of a covariance matrix, row by row
+const COVARIANCE_KEYWORDS: [&str; 21] = [
+    "CX_X",
+    "CY_X",
+    "CY_Y",
+    "CZ_X",
+    "CZ_Y",
+    "CZ_Z",
+    "CX_DOT_X",
+    "CX_DOT_Y",
+    "CX_DOT_Z",
+    "CX_DOT_X_DOT",
+    "CY_DOT_X",
+    "CY_DOT_Y",
+    "CY_DOT_Z",
+    "CY_DOT_X_DOT",
+    "CY_DOT_Y_DOT",
+    "CZ_DOT_X",
+    "CZ_DOT_Y",
+    "CZ_DOT_Z",
+    "CZ_DOT_X_DOT",
+    "CZ_DOT_Y_DOT",
+    "CZ_DOT_Z_DOT",
+];
+
+/// Error raised while reading or writing a CCSDS orbit data message
+///
+/// Line numbers are those of the message text, starting at 1.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OdmError {
+    /// The XML form of the message is not well formed
+    InvalidXml { line: usize },
+    /// The message declares a version of the standard this reader does not support
+    UnsupportedVersion { line: usize },
+    /// A mandatory keyword is absent, reported at the line where it was expected
+    MissingKeyword { line: usize, keyword: &'static str },
+    /// A keyword is unknown, repeated or out of order
+    UnexpectedKeyword { line: usize, keyword: String },
+    /// The value of a keyword could not be decoded
+    InvalidValue { line: usize, keyword: String },
+    /// The time system is not one of the time scales of the crate
+    UnsupportedTimeSystem { line: usize, name: String },
+    /// An ephemeris or covariance data line has the wrong number of values, a value that is not
+    /// a number or an epoch out of order
+    InvalidData { line: usize },
+    /// An epoch to be written falls outside the years 1 to 9999
+    InvalidEpoch { keyword: &'static str },
+}
+
+impl fmt::Display for OdmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OdmError::InvalidXml { line } => write!(f, "line {line}: malformed XML"),
+            OdmError::UnsupportedVersion { line } => {
+                write!(f, "line {line}: unsupported message version")
+            }
+            OdmError::MissingKeyword { line, keyword } => {
+                write!(f, "line {line}: missing keyword {keyword}")
+            }
+            OdmError::UnexpectedKeyword { line, keyword } => {
+                write!(f, "line {line}: unexpected keyword {keyword}")
+            }
+            OdmError::InvalidValue { line, keyword } => {
+                write!(f, "line {line}: invalid value of {keyword}")
+            }
+            OdmError::UnsupportedTimeSystem { line, name } => {
+                write!(f, "line {line}: unsupported time system {name}")
+            }
+            OdmError::InvalidData { line } => write!(f, "line {line}: invalid data line"),
+            OdmError::InvalidEpoch { keyword } => {
+                write!(f, "{keyword}: epoch outside the years 1 to 9999")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OdmError {}
+
+/// Header common to all orbit data messages
+#[derive(Debug, Clone, PartialEq)]
+pub struct OdmHeader {
+    /// Comments preceding the creation date
+    pub comments: Vec<String>,
+    /// Creation date of the message, in UTC
+    pub creation_date: DateTime,
+    /// Creating agency or operator
+    pub originator: String,
+    /// Identifier of the message, introduced in version 3 of the standard
+    pub message_id: Option<String>,
+}
+
+/// Metadata common to all orbit data messages
+#[derive(Debug, Clone, PartialEq)]
+pub struct OdmMetadata {
+    /// Comments preceding the object name
+    pub comments: Vec<String>,
+    /// Name of the object
+    pub object_name: String,
+    /// Identifier of the object, usually its international designator such as `1998-067A`
+    pub object_id: String,
+    /// Origin of the reference frame, such as `EARTH`
+    pub center_name: String,
+    /// Name of the reference frame, such as `EME2000`, `GCRF` or `TEME`
+    pub ref_frame: String,
+    /// Epoch of the reference frame, if it is not intrinsic to its definition
+    pub ref_frame_epoch: Option<DateTime>,
+    /// Time scale of all the epochs of the message but its creation date
+    pub time_system: TimeScale,
+}
+
+/// Physical parameters of a spacecraft, each of which is optional
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpacecraftParameters {
+    /// Comments preceding the parameters
+    pub comments: Vec<String>,
+    /// Mass in kg
+    pub mass: Option<f64>,
+    /// Area exposed to solar radiation pressure in m²
+    pub solar_rad_area: Option<f64>,
+    /// Solar radiation pressure coefficient
+    pub solar_rad_coeff: Option<f64>,
+    /// Area exposed to atmospheric drag in m²
+    pub drag_area: Option<f64>,
+    /// Drag coefficient
+    pub drag_coeff: Option<f64>,
+}
+
+/// Covariance of a position and velocity state
+///
+/// The matrix is symmetric and ordered as x, y, z, vx, vy, vz, in m², m²/s and m²/s².
+#[derive(Debug, Clone, PartialEq)]
+pub struct OdmCovariance {
+    /// Comments preceding the covariance
+    pub comments: Vec<String>,
+    /// Epoch of the covariance, only present in ephemeris messages
+    pub epoch: Option<DateTime>,
+    /// Reference frame of the covariance, if it differs from that of the metadata
+    pub ref_frame: Option<String>,
+    /// Covariance matrix
+    pub matrix: [[f64; 6]; 6],
+}
+
+/// A keyword entry of a message, the common form of the KVN and XML encodings
+#[derive(Debug, Clone, PartialEq)]
+enum Entry {
+    /// A `KEYWORD = value` pair, or a comment with the keyword `COMMENT`
+    Pair {
+        line: usize,
+        key: String,
+        value: String,
+    },
+    /// A block delimiter such as `META_START`
+    Marker { line: usize, name: String },
+    /// A line of whitespace-separated values, such as an ephemeris record
+    Data { line: usize, values: Vec<String> },
+}
+
+impl Entry {
+    fn line(&self) -> usize {
+        match self {
+            Entry::Pair { line, .. } | Entry::Marker { line, .. } | Entry::Data { line, .. } => {
+                *line
+            }
+        }
+    }
+}
+
+/// Splits a message in either encoding into its entries.
+fn entries(text: &str) -> Result<Vec<Entry>, OdmError> {
+    if text.trim_start().starts_with('<') {
+        xml::entries(text)
+    } else {
+        Ok(kvn::entries(text))
+    }
+}
+
+/// A reader of the entries of a message in their mandated order
+struct Cursor {
+    entries: Vec<Entry>,
+    index: usize,
+    last_line: usize,
+}
+
+impl Cursor {
+    fn new(text: &str) -> Result<Self, OdmError> {
+        let entries = entries(text)?;
+        let last_line = entries.last().map_or(1, Entry::line);
+        Ok(Cursor {
+            entries,
+            index: 0,
+            last_line,
+        })
+    }
+
+    fn peek(&self) -> Option<&Entry> {
+        self.entries.get(self.index)
+    }
+
+    /// Returns the line of the next entry, or of the last one at the end of the message.
+    fn line(&self) -> usize {
+        self.peek().map_or(self.last_line, Entry::line)
+    }
+
+    /// Returns the error for an entry that is not allowed at the current position.
+    fn unexpected(&self) -> OdmError {
+        match self.peek() {
+            Some(Entry::Pair { line, key, .. }) => OdmError::UnexpectedKeyword {
+                line: *line,
+                keyword: key.clone(),
+            },
+            Some(Entry::Marker { line, name }) => OdmError::UnexpectedKeyword {
+                line: *line,
+                keyword: name.clone(),
+            },
+            Some(Entry::Data { line, .. }) => OdmError::InvalidData { line: *line },
+            None => OdmError::InvalidData {
+                line: self.last_line,
+            },
+        }
+    }
+
+    /// Fails unless all entries have been read.
+    fn finish(&self) -> Result<(), OdmError> {
+        match self.peek() {
+            Some(_) => Err(self.unexpected()),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads the comments at the current position.
+    fn comments(&mut self) -> Vec<String> {
+        let mut comments = Vec::new();
+        while let Some((_, comment)) = self.optional("COMMENT") {
+            comments.push(comment);
+        }
+        comments
+    }
+
+    /// Reads the pair with the given keyword if it comes next.
+    fn optional(&mut self, keyword: &str) -> Option<(usize, String)> {
+        match self.peek() {
+            Some(Entry::Pair { line, key, value }) if key == keyword => {
+                let pair = (*line, value.clone());
+                self.index += 1;
+                Some(pair)
+            }
+            _ => None,
+        }
+    }
+
+    /// Reads the pair with the given keyword, which must come next.
+    fn required(&mut self, keyword: &'static str) -> Result<(usize, String), OdmError> {
+        self.optional(keyword).ok_or_else(|| {
+            // A keyword that belongs elsewhere is reported as such rather than as a gap
+            if matches!(self.peek(), Some(Entry::Pair { key, .. }) if key == "COMMENT") {
+                self.unexpected()
+            } else {
+                OdmError::MissingKeyword {
+                    line: self.line(),
+                    keyword,
+                }
+            }
+        })
+    }
+
+    /// Reads a block delimiter if it comes next.
+    fn marker(&mut self, name: &str) -> bool {
+        let found =
+            matches!(self.peek(), Some(Entry::Marker { name: marker, .. }) if marker == name);
+        if found {
+            self.index += 1;
+        }
+        found
+    }
+
+    /// Reads a block delimiter, which must come next.
+    fn expect_marker(&mut self, name: &'static str) -> Result<(), OdmError> {
+        if self.marker(name) {
+            Ok(())
+        } else {
+            Err(OdmError::MissingKeyword {
+                line: self.line(),
+                keyword: name,
+            })
+        }
+    }
+
+    /// Reads a data line if it comes next.
+    fn data(&mut self) -> Option<(usize, Vec<String>)> {
+        match self.peek() {
+            Some(Entry::Data { line, values }) => {
+                let data = (*line, values.clone());
+                self.index += 1;
+                Some(data)
+            }
+            _ => None,
+        }
+    }
+
+    fn required_number(&mut self, keyword: &'static str) -> Result<f64, OdmError> {
+        let (line, value) = self.required(keyword)?;
+        number(line, keyword, &value)
+    }
+
+    fn optional_number(&mut self, keyword: &'static str) -> Result<Option<f64>, OdmError> {
+        self.optional(keyword)
+            .map(|(line, value)| number(line, keyword, &value))
+            .transpose()
+    }
+
+    fn required_integer<T: std::str::FromStr>(
+        &mut self,
+        keyword: &'static str,
+    ) -> Result<T, OdmError> {
+        let (line, value) = self.required(keyword)?;
+        value.parse().map_err(|_| invalid(line, keyword))
+    }
+
+    fn required_epoch(
+        &mut self,
+        keyword: &'static str,
+        scale: TimeScale,
+    ) -> Result<DateTime, OdmError> {
+        let (line, value) = self.required(keyword)?;
+        parse_epoch(&value, scale).ok_or_else(|| invalid(line, keyword))
+    }
+
+    fn optional_epoch(
+        &mut self,
+        keyword: &'static str,
+        scale: TimeScale,
+    ) -> Result<Option<DateTime>, OdmError> {
+        self.optional(keyword)
+            .map(|(line, value)| parse_epoch(&value, scale).ok_or_else(|| invalid(line, keyword)))
+            .transpose()
+    }
+
+    /// Reads the version keyword, such as `CCSDS_OPM_VERS`, and the header of a message.
+    fn header(&mut self, keyword: &'static str) -> Result<OdmHeader, OdmError> {
+        let (line, version) = self.required(keyword)?;
+        if !SUPPORTED_VERSIONS.contains(&version.as_str()) {
+            return Err(OdmError::UnsupportedVersion { line });
+        }
+
+        Ok(OdmHeader {
+            comments: self.comments(),
+            creation_date: self.required_epoch("CREATION_DATE", TimeScale::UTC)?,
+            originator: self.required("ORIGINATOR")?.1,
+            message_id: self.optional("MESSAGE_ID").map(|(_, value)| value),
+        })
+    }
+
+    /// Reads the metadata keywords common to all messages.
+    fn metadata(&mut self) -> Result<OdmMetadata, OdmError> {
+        let comments = self.comments();
+        let object_name = self.required("OBJECT_NAME")?.1;
+        let object_id = self.required("OBJECT_ID")?.1;
+        let center_name = self.required("CENTER_NAME")?.1;
+        let ref_frame = self.required("REF_FRAME")?.1;
+        // The reference frame epoch precedes the time system it is expressed in
+        let ref_frame_epoch = self.optional("REF_FRAME_EPOCH");
+        let (line, name) = self.required("TIME_SYSTEM")?;
+        let time_system =
+            time_scale(&name).ok_or(OdmError::UnsupportedTimeSystem { line, name })?;
+        let ref_frame_epoch = ref_frame_epoch
+            .map(|(line, value)| {
+                parse_epoch(&value, time_system).ok_or_else(|| invalid(line, "REF_FRAME_EPOCH"))
+            })
+            .transpose()?;
+
+        Ok(OdmMetadata {
+            comments,
+            object_name,
+            object_id,
+            center_name,
+            ref_frame,
+            ref_frame_epoch,
+            time_system,
+        })
+    }
+
+    /// Reads the comments opening an optional block if the block follows them, that is if the
+    /// next keyword is one of the given ones.
+    fn block(&mut self, keywords: &[&str]) -> Option<Vec<String>> {
+        let start = self.index;
+        let comments = self.comments();
+        match self.peek() {
+            Some(Entry::Pair { key, .. }) if keywords.contains(&key.as_str()) => Some(comments),
+            _ => {
+                self.index = start;
+                None
+            }
+        }
+    }
+
+    /// Reads the optional spacecraft parameters.
+    fn spacecraft(&mut self) -> Result<Option<SpacecraftParameters>, OdmError> {
+        let keywords = [
+            "MASS",
+            "SOLAR_RAD_AREA",
+            "SOLAR_RAD_COEFF",
+            "DRAG_AREA",
+            "DRAG_COEFF",
+        ];
+        let Some(comments) = self.block(&keywords) else {
+            return Ok(None);
+        };
+        Ok(Some(SpacecraftParameters {
+            comments,
+            mass: self.optional_number("MASS")?,
+            solar_rad_area: self.optional_number("SOLAR_RAD_AREA")?,
+            solar_rad_coeff: self.optional_number("SOLAR_RAD_COEFF")?,
+            drag_area: self.optional_number("DRAG_AREA")?,
+            drag_coeff: self.optional_number("DRAG_COEFF")?,
+        }))
+    }
+
+    /// Reads the optional covariance of a parameter or mean elements message, given as keyword
+    /// pairs and, in XML, delimited by markers.
+    fn keyword_covariance(&mut self, scale: TimeScale) -> Result<Option<OdmCovariance>, OdmError> {
+        let delimited = self.marker("COVARIANCE_START");
+        let covariance = match self.block(&["COV_REF_FRAME", COVARIANCE_KEYWORDS[0]]) {
+            Some(comments) => Some(self.covariance(comments, scale, false)?),
+            None if delimited => return Err(self.unexpected()),
+            None => None,
+        };
+        if delimited {
+            self.expect_marker("COVARIANCE_STOP")?;
+        }
+        Ok(covariance)
+    }
+
+    /// Reads a covariance matrix, given either as keyword pairs or as six rows of its lower
+    /// triangle, converting it from kilometers to meters.
+    fn covariance(
+        &mut self,
+        comments: Vec<String>,
+        scale: TimeScale,
+        with_epoch: bool,
+    ) -> Result<OdmCovariance, OdmError> {
+        let epoch = if with_epoch {
+            Some(self.required_epoch("EPOCH", scale)?)
+        } else {
+            None
+        };
+        let ref_frame = self.optional("COV_REF_FRAME").map(|(_, value)| value);
+
+        let mut lower = Vec::with_capacity(21);
+        if matches!(self.peek(), Some(Entry::Data { .. })) {
+            for row in 1..=6 {
+                let line = self.line();
+                let (line, values) = self.data().ok_or(OdmError::InvalidData { line })?;
+                if values.len() != row {
+                    return Err(OdmError::InvalidData { line });
+                }
+                for value in values {
+                    lower.push(
+                        number(line, "covariance", &value)
+                            .map_err(|_| OdmError::InvalidData { line })?,
+                    );
+                }
+            }
+        } else {
+            for keyword in COVARIANCE_KEYWORDS {
+                lower.push(self.required_number(keyword)?);
+            }
+        }
+
+        let mut matrix = [[0.0; 6]; 6];
+        let indices = (0..6).flat_map(|row| (0..=row).map(move |column| (row, column)));
+        for ((row, column), value) in indices.zip(lower) {
+            matrix[row][column] = value * METERS_PER_KILOMETER.powi(2);
+            matrix[column][row] = matrix[row][column];
+        }
+
+        Ok(OdmCovariance {
+            comments,
+            epoch,
+            ref_frame,
+            matrix,
+        })
+    }
+}
+
+fn invalid(line: usize, keyword: &str) -> OdmError {
+    OdmError::InvalidValue {
+        line,
+        keyword: keyword.to_string(),
+    }
+}
+
+fn number(line: usize, keyword: &str, value: &str) -> Result<f64, OdmError> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| invalid(line, keyword))
+}
+
+/// Maps a CCSDS time system name onto a time scale.
+fn time_scale(name: &str) -> Option<TimeScale> {
+    match name {
+        "TAI" => Some(TimeScale::TAI),
+        "TT" => Some(TimeScale::TT),
+        "TDB" => Some(TimeScale::TDB),
+        "UTC" => Some(TimeScale::UTC),
+        "GPS" => Some(TimeScale::GPS),
+        _ => None,
+    }
+}
+
+/// Parses a CCSDS epoch read in the given time scale, either as `YYYY-MM-DDThh:mm:ss[.d]` or as
+/// `YYYY-DDDThh:mm:ss[.d]`, optionally followed by `Z`.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::{DateTime, TimeScale};
+/// use astro_carta::orbit::odm;
+///
+/// let epoch = odm::parse_epoch("2024-060T18:00:00.5Z", TimeScale::UTC).unwrap();
+/// let expected = DateTime::gregorian_with_scale(2024, 2, 29, 18, 0, 0.5, TimeScale::UTC).unwrap();
+/// assert_eq!(epoch, expected);
+/// ```
+pub fn parse_epoch(text: &str, scale: TimeScale) -> Option<DateTime> {
+    let text = text.trim();
+    let text = text.strip_suffix('Z').unwrap_or(text);
+    let (date, time) = text.split_once('T')?;
+    let digits = |field: &str| -> Option<u64> {
+        if field.is_empty() || !field.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        field.parse().ok()
+    };
+
+    let mut time_fields = time.splitn(3, ':');
+    let hour = digits(time_fields.next()?)?;
+    let minute = digits(time_fields.next()?)?;
+    let seconds = time_fields.next()?;
+    let (second, nanoseconds) = match seconds.split_once('.') {
+        // The fraction of a second is read to the nanosecond exactly, rather than through a float
+        Some((second, fraction)) => {
+            digits(fraction)?;
+            let truncated = &fraction[..fraction.len().min(9)];
+            (digits(second)?, digits(&format!("{truncated:0<9}"))?)
+        }
+        None => (digits(seconds)?, 0),
+    };
+    if hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    let mut date_fields = date.split('-');
+    let year = digits(date_fields.next()?)?;
+    let start = match (date_fields.next(), date_fields.next(), date_fields.next()) {
+        (Some(month), Some(day), None) if month.len() == 2 && day.len() == 2 => {
+            DateTime::gregorian_with_scale(
+                year,
+                digits(month)? as u8,
+                digits(day)? as u8,
+                hour as u8,
+                minute as u8,
+                second as f64,
+                scale,
+            )?
+        }
+        (Some(day), None, None) if day.len() == 3 => {
+            DateTime::from_day_of_year(year, digits(day)? as f64, scale)?
+                + TimeDelta::seconds((hour * 3600 + minute * 60 + second) as f64)
+        }
+        _ => return None,
+    };
+
+    Some(start + TimeDelta::new(nanoseconds as i128))
+}
+
+/// Formats an epoch as `YYYY-MM-DDThh:mm:ss.ddd` in the given time scale, with as many decimals
+/// as needed to represent it to the nanosecond, or returns `None` if it falls outside the years 1
+/// to 9999.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::{DateTime, TimeScale};
+/// use astro_carta::orbit::odm;
+///
+/// let epoch = DateTime::gregorian_with_scale(2024, 2, 29, 18, 0, 0.25, TimeScale::UTC).unwrap();
+/// assert_eq!(
+///     odm::format_epoch(&epoch, TimeScale::UTC).as_deref(),
+///     Some("2024-02-29T18:00:00.250")
+/// );
+/// ```
+pub fn format_epoch(epoch: &DateTime, scale: TimeScale) -> Option<String> {
+    let (year, month, day, hour, minute, second) = epoch.to_gregorian(scale)?;
+    if year > 9999 {
+        return None;
+    }
+    let nanoseconds = (second * 1e9).round() as u64;
+    let mut fraction = format!("{:09}", nanoseconds % 1_000_000_000);
+    while fraction.len() > 3 && fraction.ends_with('0') {
+        fraction.pop();
+    }
+    Some(format!(
+        "{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{:02}.{fraction}",
+        nanoseconds / 1_000_000_000
+    ))
+}
+
+/// Formats a number so that it reads back to the same value, with an exponent for very large
+/// or small magnitudes.
+fn format_number(value: f64) -> String {
+    let magnitude = value.abs();
+    if value == 0.0 || (1e-4..1e9).contains(&magnitude) {
+        format!("{value}")
+    } else {
+        format!("{value:e}")
+    }
+}
+
+/// A node of the message tree from which both encodings are written
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    /// A keyword with its value and units
+    Field {
+        key: &'static str,
+        value: String,
+        units: Option<&'static str>,
+    },
+    /// A comment line
+    Comment(String),
+    /// A group of nodes, an element in XML
+    Group {
+        tag: Option<&'static str>,
+        layout: Layout,
+        children: Vec<Node>,
+    },
+}
+
+/// How the children of a group are laid out in KVN
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Layout {
+    /// One keyword per line
+    Inline,
+    /// One keyword per line between two delimiters
+    Block(&'static str, &'static str),
+    /// The values on one line, as an ephemeris record
+    Line,
+    /// The values of a lower triangular matrix, one row per line
+    Rows,
+}
+
+/// A message ready to be written, with the header and the segments of metadata and data
+#[derive(Debug, Clone, PartialEq)]
+struct Document {
+    /// Kind of message, such as `OPM`
+    kind: &'static str,
+    header: Vec<Node>,
+    segments: Vec<Vec<Node>>,
+}
+
+impl Document {
+    fn to_kvn(&self) -> String {
+        kvn::render(self)
+    }
+
+    fn to_xml(&self) -> String {
+        xml::render(self)
+    }
+}
+
+fn field(key: &'static str, value: impl ToString) -> Node {
+    Node::Field {
+        key,
+        value: value.to_string(),
+        units: None,
+    }
+}
+
+fn number_field(key: &'static str, value: f64, units: Option<&'static str>) -> Node {
+    Node::Field {
+        key,
+        value: format_number(value),
+        units,
+    }
+}
+
+fn epoch_field(key: &'static str, epoch: &DateTime, scale: TimeScale) -> Result<Node, OdmError> {
+    let value = format_epoch(epoch, scale).ok_or(OdmError::InvalidEpoch { keyword: key })?;
+    Ok(field(key, value))
+}
+
+fn comment_nodes(comments: &[String]) -> impl Iterator<Item = Node> + '_ {
+    comments.iter().cloned().map(Node::Comment)
+}
+
+fn group(tag: &'static str, children: Vec<Node>) -> Node {
+    Node::Group {
+        tag: Some(tag),
+        layout: Layout::Inline,
+        children,
+    }
+}
+
+impl OdmHeader {
+    fn nodes(&self) -> Result<Vec<Node>, OdmError> {
+        let mut nodes: Vec<_> = comment_nodes(&self.comments).collect();
+        nodes.push(epoch_field(
+            "CREATION_DATE",
+            &self.creation_date,
+            TimeScale::UTC,
+        )?);
+        nodes.push(field("ORIGINATOR", &self.originator));
+        if let Some(message_id) = &self.message_id {
+            nodes.push(field("MESSAGE_ID", message_id));
+        }
+        Ok(nodes)
+    }
+}
+
+impl OdmMetadata {
+    /// Returns the metadata group, with the keywords specific to a kind of message appended.
+    fn node(&self, layout: Layout, extra: Vec<Node>) -> Result<Node, OdmError> {
+        let mut children: Vec<_> = comment_nodes(&self.comments).collect();
+        children.push(field("OBJECT_NAME", &self.object_name));
+        children.push(field("OBJECT_ID", &self.object_id));
+        children.push(field("CENTER_NAME", &self.center_name));
+        children.push(field("REF_FRAME", &self.ref_frame));
+        if let Some(epoch) = &self.ref_frame_epoch {
+            children.push(epoch_field("REF_FRAME_EPOCH", epoch, self.time_system)?);
+        }
+        children.push(field("TIME_SYSTEM", self.time_system.name()));
+        children.extend(extra);
+        Ok(Node::Group {
+            tag: Some("metadata"),
+            layout,
+            children,
+        })
+    }
+}
+
+impl SpacecraftParameters {
+    fn node(&self) -> Node {
+        let mut children: Vec<_> = comment_nodes(&self.comments).collect();
+        let parameters = [
+            ("MASS", self.mass, "kg"),
+            ("SOLAR_RAD_AREA", self.solar_rad_area, "m**2"),
+            ("SOLAR_RAD_COEFF", self.solar_rad_coeff, ""),
+            ("DRAG_AREA", self.drag_area, "m**2"),
+            ("DRAG_COEFF", self.drag_coeff, ""),
+        ];
+        for (key, value, units) in parameters {
+            if let Some(value) = value {
+                children.push(number_field(
+                    key,
+                    value,
+                    Some(units).filter(|units| !units.is_empty()),
+                ));
+            }
+        }
+        group("spacecraftParameters", children)
+    }
+}
+
+impl OdmCovariance {
+    /// Returns the covariance group, in kilometers, with the matrix as keyword pairs or, in the
+    /// KVN form of ephemeris messages, as rows.
+    fn node(&self, scale: TimeScale, rows: bool) -> Result<Node, OdmError> {
+        let mut children: Vec<_> = comment_nodes(&self.comments).collect();
+        if let Some(epoch) = &self.epoch {
+            children.push(epoch_field("EPOCH", epoch, scale)?);
+        }
+        if let Some(ref_frame) = &self.ref_frame {
+            children.push(field("COV_REF_FRAME", ref_frame));
+        }
+
+        let mut keywords = COVARIANCE_KEYWORDS.iter();
+        let mut elements = Vec::with_capacity(21);
+        for row in 0..6 {
+            for column in 0..=row {
+                let units = match (row < 3, column < 3) {
+                    (true, true) => "km**2",
+                    (false, false) => "km**2/s**2",
+                    _ => "km**2/s",
+                };
+                let value = self.matrix[row][column] / METERS_PER_KILOMETER.powi(2);
+                elements.push(number_field(keywords.next().unwrap(), value, Some(units)));
+            }
+        }
+        if rows {
+            children.push(Node::Group {
+                tag: None,
+                layout: Layout::Rows,
+                children: elements,
+            });
+        } else {
+            children.extend(elements);
+        }
+        Ok(group("covarianceMatrix", children))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epoch_test() {
+        let expected =
+            DateTime::gregorian_with_scale(2000, 6, 3, 5, 33, 0.0, TimeScale::UTC).unwrap();
+        let epoch = parse_epoch("2000-06-03T05:33:00.123456789", TimeScale::UTC).unwrap();
+        assert_eq!(epoch - expected, TimeDelta::new(123_456_789));
+        assert_eq!(
+            parse_epoch("2000-155T05:33:00", TimeScale::UTC),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_epoch("2000-06-03T05:33:00Z", TimeScale::UTC),
+            Some(expected)
+        );
+        assert_eq!(
+            format_epoch(&epoch, TimeScale::UTC).as_deref(),
+            Some("2000-06-03T05:33:00.123456789")
+        );
+        assert_eq!(
+            format_epoch(&expected, TimeScale::UTC).as_deref(),
+            Some("2000-06-03T05:33:00.000")
+        );
+        let ancient = DateTime::from_julian_date(1_000_000.0, TimeScale::UTC);
+        assert_eq!(format_epoch(&ancient, TimeScale::UTC), None);
+        let distant = DateTime::from_julian_date(6_000_000.0, TimeScale::UTC);
+        assert_eq!(format_epoch(&distant, TimeScale::UTC), None);
+
+        for text in [
+            "2000-06-03",
+            "2000-06-03T05:33",
+            "2000-6-03T05:33:00",
+            "2000-06-03T24:00:00",
+            "2000-06-03T05:33:00.1x",
+            "2000-367T00:00:00",
+            "2000-06-03T05:33:-1",
+        ] {
+            assert_eq!(parse_epoch(text, TimeScale::UTC), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn format_number_test() {
+        for value in [0.0, 1.0, -6655.9942, 3.331e-4, 1.2e-12, 2.5e15, -4.5e-7] {
+            assert_eq!(format_number(value).parse::<f64>().unwrap(), value);
+        }
+        assert_eq!(format_number(1.2e-12), "1.2e-12");
+        assert_eq!(format_number(-6655.9942), "-6655.9942");
+    }
+}
@@ -0,0 +1,167 @@
+use std::fmt::Write;
+
+use super::{Document, Entry, Layout, Node, VERSION};
+
+/// Splits a message in the keyword = value notation into its entries.
+pub(super) fn entries(text: &str) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    for (index, content) in text.lines().enumerate() {
+        let line = index + 1;
+        let content = content.trim();
+        if content.is_empty() {
+            continue;
+        }
+
+        let comment = content
+            .strip_prefix("COMMENT")
+            .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace));
+        let entry = if let Some(comment) = comment {
+            Entry::Pair {
+                line,
+                key: "COMMENT".to_string(),
+                value: comment.trim().to_string(),
+            }
+        } else if let Some((key, value)) = content.split_once('=') {
+            // Units in square brackets are informative only
+            let value = value.trim();
+            let value = match value.rfind('[') {
+                Some(start) if value.ends_with(']') => value[..start].trim_end(),
+                _ => value,
+            };
+            Entry::Pair {
+                line,
+                key: key.trim().to_string(),
+                value: value.to_string(),
+            }
+        } else if content.ends_with("_START") || content.ends_with("_STOP") {
+            Entry::Marker {
+                line,
+                name: content.to_string(),
+            }
+        } else {
+            Entry::Data {
+                line,
+                values: content.split_whitespace().map(str::to_string).collect(),
+            }
+        };
+        entries.push(entry);
+    }
+    entries
+}
+
+/// Writes a message in the keyword = value notation.
+pub(super) fn render(document: &Document) -> String {
+    let mut text = String::new();
+    write_field(
+        &mut text,
+        &format!("CCSDS_{}_VERS", document.kind),
+        VERSION,
+        None,
+    );
+    for node in &document.header {
+        write_node(&mut text, node);
+    }
+    for segment in &document.segments {
+        for node in segment {
+            write_node(&mut text, node);
+        }
+    }
+    text
+}
+
+fn write_field(text: &mut String, key: &str, value: &str, units: Option<&str>) {
+    let _ = write!(text, "{key:<20} = {value}");
+    if let Some(units) = units {
+        let _ = write!(text, " [{units}]");
+    }
+    text.push('\n');
+}
+
+fn write_node(text: &mut String, node: &Node) {
+    match node {
+        Node::Field { key, value, units } => write_field(text, key, value, *units),
+        Node::Comment(comment) => {
+            let _ = writeln!(text, "COMMENT {comment}");
+        }
+        Node::Group {
+            layout, children, ..
+        } => match layout {
+            Layout::Inline => {
+                text.push('\n');
+                for child in children {
+                    write_node(text, child);
+                }
+            }
+            Layout::Block(start, stop) => {
+                let _ = writeln!(text, "\n{start}");
+                for child in children {
+                    write_node(text, child);
+                }
+                let _ = writeln!(text, "{stop}");
+            }
+            Layout::Line => {
+                let values: Vec<_> = children.iter().filter_map(value).collect();
+                let _ = writeln!(text, "{}", values.join(" "));
+            }
+            Layout::Rows => {
+                let mut values = children.iter().filter_map(value);
+                for row in 1..=6 {
+                    let row: Vec<_> = values.by_ref().take(row).collect();
+                    let _ = writeln!(text, "{}", row.join(" "));
+                }
+            }
+        },
+    }
+}
+
+fn value(node: &Node) -> Option<&str> {
+    match node {
+        Node::Field { value, .. } => Some(value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_test() {
+        let text = "CCSDS_OEM_VERS = 2.0\n\nCOMMENT  Free text = kept\nX = 6655.9942 [km]\nMETA_START\n2000-06-03T05:33:00 1 2 3\nCOMMENTARY = 1\n";
+        assert_eq!(
+            entries(text),
+            [
+                Entry::Pair {
+                    line: 1,
+                    key: "CCSDS_OEM_VERS".to_string(),
+                    value: "2.0".to_string()
+                },
+                Entry::Pair {
+                    line: 3,
+                    key: "COMMENT".to_string(),
+                    value: "Free text = kept".to_string()
+                },
+                Entry::Pair {
+                    line: 4,
+                    key: "X".to_string(),
+                    value: "6655.9942".to_string()
+                },
+                Entry::Marker {
+                    line: 5,
+                    name: "META_START".to_string()
+                },
+                Entry::Data {
+                    line: 6,
+                    values: ["2000-06-03T05:33:00", "1", "2", "3"]
+                        .map(str::to_string)
+                        .to_vec()
+                },
+                Entry::Pair {
+                    line: 7,
+                    key: "COMMENTARY".to_string(),
+                    value: "1".to_string()
+                },
+            ]
+        );
+    }
+}
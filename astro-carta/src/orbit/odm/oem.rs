@@ -0,0 +1,387 @@
+use super::opm::vector_nodes;
+use super::{
+    comment_nodes, epoch_field, field, group, invalid, number, parse_epoch, Cursor, Document,
+    Layout, Node, OdmCovariance, OdmError, OdmHeader, OdmMetadata, METERS_PER_KILOMETER,
+};
+use crate::datetime::{DateTime, TimeScale};
+use crate::ephemeris::StateVector;
+use crate::math::Vector3;
+
+/// A state of an orbit ephemeris message
+#[derive(Debug, Clone, PartialEq)]
+pub struct OemState {
+    pub epoch: DateTime,
+    /// Position in meters and velocity in m/s
+    pub state: StateVector,
+    /// Acceleration in m/s², if given
+    pub acceleration: Option<Vector3>,
+}
+
+/// A segment of an orbit ephemeris message: states in one reference frame and time system,
+/// with their optional covariances
+#[derive(Debug, Clone, PartialEq)]
+pub struct OemSegment {
+    pub metadata: OdmMetadata,
+    /// Start of the time span covered by the segment
+    pub start_time: DateTime,
+    /// Start of the time span recommended for use, if narrower
+    pub useable_start_time: Option<DateTime>,
+    /// End of the time span recommended for use, if narrower
+    pub useable_stop_time: Option<DateTime>,
+    /// End of the time span covered by the segment
+    pub stop_time: DateTime,
+    /// Recommended interpolation method, such as `HERMITE` or `LAGRANGE`
+    pub interpolation: Option<String>,
+    /// Recommended interpolation degree
+    pub interpolation_degree: Option<u32>,
+    /// Comments of the data section
+    pub comments: Vec<String>,
+    /// States in increasing order of epoch
+    pub states: Vec<OemState>,
+    pub covariances: Vec<OdmCovariance>,
+}
+
+/// A CCSDS orbit ephemeris message (OEM): tabulated states of an object, in one or more
+/// segments
+///
+/// Values are converted to the units of the crate: meters, m/s and m/s². The readers check that
+/// the states of each segment are in increasing order and within its start and stop times.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::orbit::odm::Oem;
+///
+/// let text = "CCSDS_OEM_VERS = 2.0
+/// CREATION_DATE = 1996-11-04T17:22:31
+/// ORIGINATOR = NASA/JPL
+///
+/// META_START
+/// OBJECT_NAME = MARS GLOBAL SURVEYOR
+/// OBJECT_ID = 1996-062A
+/// CENTER_NAME = MARS BARYCENTER
+/// REF_FRAME = EME2000
+/// TIME_SYSTEM = UTC
+/// START_TIME = 1996-12-18T12:00:00.331
+/// STOP_TIME = 1996-12-28T21:28:00.331
+/// INTERPOLATION = HERMITE
+/// INTERPOLATION_DEGREE = 7
+/// META_STOP
+///
+/// 1996-12-18T12:00:00.331 2789.619 -280.045 -1746.755 4.73372 -2.49586 -1.04195
+/// 1996-12-18T12:01:00.331 2783.419 -308.143 -1877.071 5.18604 -2.42124 -1.99608
+/// ";
+/// let oem = Oem::parse(text).unwrap();
+/// let segment = &oem.segments[0];
+/// assert_eq!(segment.states.len(), 2);
+/// assert!((segment.states[1].state.position.y + 308_143.0).abs() < 1e-6);
+///
+/// let round_trip = Oem::parse(&oem.to_xml().unwrap()).unwrap();
+/// assert_eq!(round_trip.segments[0].states.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Oem {
+    pub header: OdmHeader,
+    pub segments: Vec<OemSegment>,
+}
+
+impl Oem {
+    /// Parses a message in either the KVN or the XML notation.
+    pub fn parse(text: &str) -> Result<Self, OdmError> {
+        let mut cursor = Cursor::new(text)?;
+        let header = cursor.header("CCSDS_OEM_VERS")?;
+        let mut segments = vec![cursor.segment()?];
+        while cursor.peek().is_some() {
+            segments.push(cursor.segment()?);
+        }
+        Ok(Oem { header, segments })
+    }
+
+    /// Writes the message in the KVN notation, failing if an epoch falls outside the years 1 to
+    /// 9999.
+    pub fn to_kvn(&self) -> Result<String, OdmError> {
+        Ok(self.document()?.to_kvn())
+    }
+
+    /// Writes the message in the XML notation, failing if an epoch falls outside the years 1 to
+    /// 9999.
+    pub fn to_xml(&self) -> Result<String, OdmError> {
+        Ok(self.document()?.to_xml())
+    }
+
+    fn document(&self) -> Result<Document, OdmError> {
+        Ok(Document {
+            kind: "OEM",
+            header: self.header.nodes()?,
+            segments: self
+                .segments
+                .iter()
+                .map(OemSegment::nodes)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl OemSegment {
+    fn nodes(&self) -> Result<Vec<Node>, OdmError> {
+        let scale = self.metadata.time_system;
+        let mut extra = vec![epoch_field("START_TIME", &self.start_time, scale)?];
+        if let Some(epoch) = &self.useable_start_time {
+            extra.push(epoch_field("USEABLE_START_TIME", epoch, scale)?);
+        }
+        if let Some(epoch) = &self.useable_stop_time {
+            extra.push(epoch_field("USEABLE_STOP_TIME", epoch, scale)?);
+        }
+        extra.push(epoch_field("STOP_TIME", &self.stop_time, scale)?);
+        if let Some(interpolation) = &self.interpolation {
+            extra.push(field("INTERPOLATION", interpolation));
+        }
+        if let Some(degree) = self.interpolation_degree {
+            extra.push(field("INTERPOLATION_DEGREE", degree));
+        }
+        let metadata = self
+            .metadata
+            .node(Layout::Block("META_START", "META_STOP"), extra)?;
+
+        let mut data: Vec<_> = comment_nodes(&self.comments).collect();
+        for state in &self.states {
+            let mut children = vec![epoch_field("EPOCH", &state.epoch, scale)?];
+            children.extend(vector_nodes(["X", "Y", "Z"], &state.state.position, "km"));
+            children.extend(vector_nodes(
+                ["X_DOT", "Y_DOT", "Z_DOT"],
+                &state.state.velocity,
+                "km/s",
+            ));
+            if let Some(acceleration) = &state.acceleration {
+                children.extend(vector_nodes(
+                    ["X_DDOT", "Y_DDOT", "Z_DDOT"],
+                    acceleration,
+                    "km/s**2",
+                ));
+            }
+            data.push(Node::Group {
+                tag: Some("stateVector"),
+                layout: Layout::Line,
+                children,
+            });
+        }
+        if !self.covariances.is_empty() {
+            data.push(Node::Group {
+                tag: None,
+                layout: Layout::Block("COVARIANCE_START", "COVARIANCE_STOP"),
+                children: self
+                    .covariances
+                    .iter()
+                    .map(|covariance| covariance.node(scale, true))
+                    .collect::<Result<_, _>>()?,
+            });
+        }
+
+        Ok(vec![metadata, group("data", data)])
+    }
+}
+
+impl Cursor {
+    /// Reads a segment of an ephemeris message, from its metadata to its last covariance.
+    fn segment(&mut self) -> Result<OemSegment, OdmError> {
+        self.expect_marker("META_START")?;
+        let metadata = self.metadata()?;
+        let scale = metadata.time_system;
+        let start_time = self.required_epoch("START_TIME", scale)?;
+        let mut useable_times = [None, None];
+        for (time, keyword) in useable_times
+            .iter_mut()
+            .zip(["USEABLE_START_TIME", "USEABLE_STOP_TIME"])
+        {
+            let line = self.line();
+            *time = self.optional_epoch(keyword, scale)?;
+            if time.is_some_and(|epoch| epoch < start_time) {
+                return Err(invalid(line, keyword));
+            }
+        }
+        let [useable_start_time, useable_stop_time] = useable_times;
+        let line = self.line();
+        let stop_time = self.required_epoch("STOP_TIME", scale)?;
+        let useable_after_stop = useable_times
+            .iter()
+            .flatten()
+            .any(|epoch| *epoch > stop_time);
+        if stop_time < start_time || useable_after_stop {
+            return Err(invalid(line, "STOP_TIME"));
+        }
+        let interpolation = self.optional("INTERPOLATION").map(|(_, value)| value);
+        let interpolation_degree = match self.optional("INTERPOLATION_DEGREE") {
+            Some((line, value)) => Some(
+                value
+                    .parse()
+                    .map_err(|_| invalid(line, "INTERPOLATION_DEGREE"))?,
+            ),
+            None => None,
+        };
+        self.expect_marker("META_STOP")?;
+
+        let comments = self.comments();
+        let mut states: Vec<OemState> = Vec::new();
+        while let Some((line, values)) = self.data() {
+            let state = ephemeris_state(line, &values, scale)?;
+            let increasing = states.last().is_none_or(|last| state.epoch > last.epoch);
+            if !increasing || state.epoch < start_time || state.epoch > stop_time {
+                return Err(OdmError::InvalidData { line });
+            }
+            states.push(state);
+        }
+
+        let mut covariances = Vec::new();
+        while self.marker("COVARIANCE_START") {
+            while let Some(comments) = self.block(&["EPOCH"]) {
+                covariances.push(self.covariance(comments, scale, true)?);
+            }
+            self.expect_marker("COVARIANCE_STOP")?;
+        }
+
+        Ok(OemSegment {
+            metadata,
+            start_time,
+            useable_start_time,
+            useable_stop_time,
+            stop_time,
+            interpolation,
+            interpolation_degree,
+            comments,
+            states,
+            covariances,
+        })
+    }
+}
+
+/// Decodes an ephemeris data line: an epoch followed by a position and a velocity in km and
+/// km/s, and optionally an acceleration in km/s².
+fn ephemeris_state(line: usize, values: &[String], scale: TimeScale) -> Result<OemState, OdmError> {
+    if values.len() != 7 && values.len() != 10 {
+        return Err(OdmError::InvalidData { line });
+    }
+    let epoch = parse_epoch(&values[0], scale).ok_or(OdmError::InvalidData { line })?;
+    let mut components = Vec::with_capacity(9);
+    for value in &values[1..] {
+        let component = number(line, "data", value).map_err(|_| OdmError::InvalidData { line })?;
+        components.push(component * METERS_PER_KILOMETER);
+    }
+    let vector = |start: usize| {
+        Vector3::new(
+            components[start],
+            components[start + 1],
+            components[start + 2],
+        )
+    };
+
+    Ok(OemState {
+        epoch,
+        state: StateVector {
+            position: vector(0),
+            velocity: vector(3),
+        },
+        acceleration: (components.len() == 9).then(|| vector(6)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "CCSDS_OEM_VERS = 2.0
+CREATION_DATE = 1996-11-04T17:22:31
+ORIGINATOR = NASA/JPL
+META_START
+OBJECT_NAME = MARS GLOBAL SURVEYOR
+OBJECT_ID = 1996-062A
+CENTER_NAME = MARS BARYCENTER
+REF_FRAME = EME2000
+TIME_SYSTEM = UTC
+START_TIME = 1996-12-18T12:00:00.331
+USEABLE_START_TIME = 1996-12-18T12:10:00.331
+USEABLE_STOP_TIME = 1996-12-28T21:23:00.331
+STOP_TIME = 1996-12-28T21:28:00.331
+INTERPOLATION = HERMITE
+INTERPOLATION_DEGREE = 7
+META_STOP
+1996-12-18T12:00:00.331 2789.619 -280.045 -1746.755 4.73372 -2.49586 -1.04195
+1996-12-18T12:01:00.331 2783.419 -308.143 -1877.071 5.18604 -2.42124 -1.99608 0.001 0.002 0.003
+1996-12-28T21:28:00.331 -3881.024 563.959 -682.773 -3.28827 -3.66735 1.63861
+";
+
+    #[test]
+    fn round_trip_test() {
+        let oem = Oem::parse(TEXT).unwrap();
+        let segment = &oem.segments[0];
+        assert_eq!(segment.states.len(), 3);
+        assert_eq!(
+            segment.states[1].acceleration,
+            Some(Vector3::new(1.0, 2.0, 3.0))
+        );
+        assert!(segment.useable_start_time.is_some());
+
+        for text in [oem.to_kvn().unwrap(), oem.to_xml().unwrap()] {
+            let parsed = Oem::parse(&text).unwrap();
+            assert_eq!(parsed.header, oem.header);
+            assert_eq!(parsed.segments.len(), 1);
+            let (parsed, expected) = (&parsed.segments[0], segment);
+            assert_eq!(parsed.metadata, expected.metadata);
+            assert_eq!(parsed.start_time, expected.start_time);
+            assert_eq!(parsed.useable_start_time, expected.useable_start_time);
+            assert_eq!(parsed.useable_stop_time, expected.useable_stop_time);
+            assert_eq!(parsed.stop_time, expected.stop_time);
+            assert_eq!(parsed.interpolation, expected.interpolation);
+            assert_eq!(parsed.interpolation_degree, expected.interpolation_degree);
+            assert_eq!(parsed.states.len(), expected.states.len());
+            for (state, expected) in parsed.states.iter().zip(&expected.states) {
+                assert_eq!(state.epoch, expected.epoch);
+                assert!((state.state.position - expected.state.position).norm() < 1e-6);
+                assert!((state.state.velocity - expected.state.velocity).norm() < 1e-9);
+                match (state.acceleration, expected.acceleration) {
+                    (Some(acceleration), Some(expected)) => {
+                        assert!((acceleration - expected).norm() < 1e-12)
+                    }
+                    (acceleration, expected) => assert_eq!(acceleration, expected),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn error_test() {
+        assert_eq!(
+            Oem::parse(&TEXT.replace("STOP_TIME = 1996-12-28T21:28:00.331\n", "")).unwrap_err(),
+            OdmError::MissingKeyword {
+                line: 13,
+                keyword: "STOP_TIME",
+            }
+        );
+        assert_eq!(
+            Oem::parse(&TEXT.replace(
+                "START_TIME = 1996-12-18T12:00:00.331",
+                "START_TIME = 1996-12-18"
+            ))
+            .unwrap_err(),
+            OdmError::InvalidValue {
+                line: 10,
+                keyword: "START_TIME".to_string(),
+            }
+        );
+        assert_eq!(
+            Oem::parse(&TEXT.replace("1996-12-18T12:01:00.331", "1996-12-18T12:01:00.3x1"))
+                .unwrap_err(),
+            OdmError::InvalidData { line: 18 }
+        );
+
+        let mut oem = Oem::parse(TEXT).unwrap();
+        oem.segments[0].states[2].epoch = DateTime::from_julian_date(6_000_000.0, TimeScale::UTC);
+        let expected = OdmError::InvalidEpoch { keyword: "EPOCH" };
+        assert_eq!(oem.to_kvn().unwrap_err(), expected);
+        assert_eq!(oem.to_xml().unwrap_err(), expected);
+        oem.segments[0].stop_time = oem.segments[0].states[2].epoch;
+        let expected = OdmError::InvalidEpoch {
+            keyword: "STOP_TIME",
+        };
+        assert_eq!(oem.to_kvn().unwrap_err(), expected);
+    }
+}
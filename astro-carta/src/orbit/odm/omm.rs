@@ -0,0 +1,416 @@
+use super::{
+    comment_nodes, epoch_field, field, group, invalid, number_field, Cursor, Document, Layout,
+    OdmCovariance, OdmError, OdmHeader, OdmMetadata, SpacecraftParameters, METERS_PER_KILOMETER,
+};
+use crate::datetime::DateTime;
+use crate::orbit::tle::Tle;
+
+/// Size of the mean orbit of an orbit mean-elements message
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MeanOrbitSize {
+    /// Mean semi-major axis in meters
+    SemiMajorAxis(f64),
+    /// Mean motion in revolutions per day, as used with SGP4
+    MeanMotion(f64),
+}
+
+/// Parameters of an orbit mean-elements message fitted for the SGP4 model
+#[derive(Debug, Clone, PartialEq)]
+pub struct TleParameters {
+    /// Comments preceding the parameters
+    pub comments: Vec<String>,
+    /// Ephemeris type, zero for SGP4/SDP4
+    pub ephemeris_type: u8,
+    /// Classification, such as `U` for unclassified
+    pub classification_type: char,
+    /// NORAD catalog number
+    pub norad_cat_id: u32,
+    /// Element set number
+    pub element_set_no: u32,
+    /// Revolution number at the epoch
+    pub rev_at_epoch: u32,
+    /// Drag term B* in inverse Earth radii
+    pub bstar: f64,
+    /// First derivative of the mean motion divided by two in rev/day²
+    pub mean_motion_dot: f64,
+    /// Second derivative of the mean motion divided by six in rev/day³
+    pub mean_motion_ddot: f64,
+}
+
+/// A CCSDS orbit mean-elements message (OMM): mean elements of an object at one epoch, such as
+/// a two-line element set
+///
+/// Values are converted to the units of the crate: meters and radians, with mean motions kept
+/// in revolutions per day.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::orbit::odm::Omm;
+/// use astro_carta::orbit::sgp4::Sgp4;
+///
+/// let text = "CCSDS_OMM_VERS = 2.0
+/// CREATION_DATE = 2007-065T16:00:00
+/// ORIGINATOR = NOAA/USA
+/// OBJECT_NAME = GOES 9
+/// OBJECT_ID = 1995-025A
+/// CENTER_NAME = EARTH
+/// REF_FRAME = TEME
+/// TIME_SYSTEM = UTC
+/// MEAN_ELEMENT_THEORY = SGP/SGP4
+/// EPOCH = 2007-064T10:34:41.4264
+/// MEAN_MOTION = 1.00273272 [rev/day]
+/// ECCENTRICITY = 0.0005013
+/// INCLINATION = 3.0539 [deg]
+/// RA_OF_ASC_NODE = 81.7939 [deg]
+/// ARG_OF_PERICENTER = 249.2363 [deg]
+/// MEAN_ANOMALY = 150.1602 [deg]
+/// GM = 398600.8 [km**3/s**2]
+/// EPHEMERIS_TYPE = 0
+/// CLASSIFICATION_TYPE = U
+/// NORAD_CAT_ID = 23581
+/// ELEMENT_SET_NO = 0925
+/// REV_AT_EPOCH = 4316
+/// BSTAR = 0.0001 [1/ER]
+/// MEAN_MOTION_DOT = -0.00000113 [rev/day**2]
+/// MEAN_MOTION_DDOT = 0.0 [rev/day**3]
+/// ";
+/// let omm = Omm::parse(text).unwrap();
+/// let tle = omm.to_tle().unwrap();
+/// assert_eq!(tle.satellite_number, 23581);
+/// assert_eq!(tle.international_designator, "95025A");
+/// assert!(Sgp4::new(&tle).unwrap().is_deep_space());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Omm {
+    pub header: OdmHeader,
+    pub metadata: OdmMetadata,
+    /// Theory of the mean elements, such as `SGP4`
+    pub mean_element_theory: String,
+    /// Comments of the data section
+    pub comments: Vec<String>,
+    /// Epoch of the mean elements
+    pub epoch: DateTime,
+    pub size: MeanOrbitSize,
+    pub eccentricity: f64,
+    /// Inclination in radians
+    pub inclination: f64,
+    /// Right ascension of the ascending node in radians
+    pub right_ascension: f64,
+    /// Argument of pericenter in radians
+    pub argument_of_pericenter: f64,
+    /// Mean anomaly in radians
+    pub mean_anomaly: f64,
+    /// Gravitational parameter in m³/s²
+    pub gravitational_parameter: Option<f64>,
+    pub spacecraft: Option<SpacecraftParameters>,
+    pub tle_parameters: Option<TleParameters>,
+    pub covariance: Option<OdmCovariance>,
+}
+
+impl Omm {
+    /// Parses a message in either the KVN or the XML notation.
+    pub fn parse(text: &str) -> Result<Self, OdmError> {
+        let mut cursor = Cursor::new(text)?;
+        let header = cursor.header("CCSDS_OMM_VERS")?;
+        let delimited = cursor.marker("META_START");
+        let metadata = cursor.metadata()?;
+        let mean_element_theory = cursor.required("MEAN_ELEMENT_THEORY")?.1;
+        if delimited {
+            cursor.expect_marker("META_STOP")?;
+        }
+        let scale = metadata.time_system;
+
+        let comments = cursor.comments();
+        let epoch = cursor.required_epoch("EPOCH", scale)?;
+        let size = match cursor.optional_number("SEMI_MAJOR_AXIS")? {
+            Some(axis) => MeanOrbitSize::SemiMajorAxis(axis * METERS_PER_KILOMETER),
+            None => MeanOrbitSize::MeanMotion(cursor.required_number("MEAN_MOTION")?),
+        };
+        let eccentricity = cursor.required_number("ECCENTRICITY")?;
+        let inclination = cursor.required_number("INCLINATION")?.to_radians();
+        let right_ascension = cursor.required_number("RA_OF_ASC_NODE")?.to_radians();
+        let argument_of_pericenter = cursor.required_number("ARG_OF_PERICENTER")?.to_radians();
+        let mean_anomaly = cursor.required_number("MEAN_ANOMALY")?.to_radians();
+        let gravitational_parameter = cursor
+            .optional_number("GM")?
+            .map(|gm| gm * METERS_PER_KILOMETER.powi(3));
+        let spacecraft = cursor.spacecraft()?;
+
+        let tle_parameters =
+            match cursor.block(&["EPHEMERIS_TYPE", "CLASSIFICATION_TYPE", "NORAD_CAT_ID"]) {
+                Some(comments) => Some(cursor.tle_parameters(comments)?),
+                None => None,
+            };
+        let covariance = cursor.keyword_covariance(scale)?;
+        cursor.finish()?;
+
+        Ok(Omm {
+            header,
+            metadata,
+            mean_element_theory,
+            comments,
+            epoch,
+            size,
+            eccentricity,
+            inclination,
+            right_ascension,
+            argument_of_pericenter,
+            mean_anomaly,
+            gravitational_parameter,
+            spacecraft,
+            tle_parameters,
+            covariance,
+        })
+    }
+
+    /// Writes the message in the KVN notation, failing if an epoch falls outside the years 1 to
+    /// 9999.
+    pub fn to_kvn(&self) -> Result<String, OdmError> {
+        Ok(self.document()?.to_kvn())
+    }
+
+    /// Writes the message in the XML notation, failing if an epoch falls outside the years 1 to
+    /// 9999.
+    pub fn to_xml(&self) -> Result<String, OdmError> {
+        Ok(self.document()?.to_xml())
+    }
+
+    /// Returns the two-line element set carried by the message, or `None` if the message has no
+    /// TLE parameters or no mean motion.
+    ///
+    /// The international designator is derived from an object identifier of the form
+    /// `YYYY-NNNP`, and left empty otherwise.
+    pub fn to_tle(&self) -> Option<Tle> {
+        let parameters = self.tle_parameters.as_ref()?;
+        let MeanOrbitSize::MeanMotion(mean_motion) = self.size else {
+            return None;
+        };
+        let id = &self.metadata.object_id;
+        let international_designator = match (id.get(2..4), id.get(4..5), id.get(5..)) {
+            (Some(year), Some("-"), Some(launch)) if id.len() > 5 => format!("{year}{launch}"),
+            _ => String::new(),
+        };
+
+        Some(Tle {
+            name: Some(self.metadata.object_name.clone()),
+            satellite_number: parameters.norad_cat_id,
+            classification: parameters.classification_type,
+            international_designator,
+            epoch: self.epoch,
+            mean_motion_dot: parameters.mean_motion_dot,
+            mean_motion_ddot: parameters.mean_motion_ddot,
+            bstar: parameters.bstar,
+            ephemeris_type: parameters.ephemeris_type,
+            element_set_number: parameters.element_set_no,
+            inclination: self.inclination,
+            right_ascension: self.right_ascension,
+            eccentricity: self.eccentricity,
+            argument_of_perigee: self.argument_of_pericenter,
+            mean_anomaly: self.mean_anomaly,
+            mean_motion,
+            revolution_number: parameters.rev_at_epoch,
+        })
+    }
+
+    fn document(&self) -> Result<Document, OdmError> {
+        let scale = self.metadata.time_system;
+        let mut elements: Vec<_> = comment_nodes(&self.comments).collect();
+        elements.push(epoch_field("EPOCH", &self.epoch, scale)?);
+        elements.push(match self.size {
+            MeanOrbitSize::SemiMajorAxis(axis) => {
+                number_field("SEMI_MAJOR_AXIS", axis / METERS_PER_KILOMETER, Some("km"))
+            }
+            MeanOrbitSize::MeanMotion(motion) => {
+                number_field("MEAN_MOTION", motion, Some("rev/day"))
+            }
+        });
+        elements.push(number_field("ECCENTRICITY", self.eccentricity, None));
+        let angles = [
+            ("INCLINATION", self.inclination),
+            ("RA_OF_ASC_NODE", self.right_ascension),
+            ("ARG_OF_PERICENTER", self.argument_of_pericenter),
+            ("MEAN_ANOMALY", self.mean_anomaly),
+        ];
+        for (key, angle) in angles {
+            elements.push(number_field(key, angle.to_degrees(), Some("deg")));
+        }
+        if let Some(gm) = self.gravitational_parameter {
+            elements.push(number_field(
+                "GM",
+                gm / METERS_PER_KILOMETER.powi(3),
+                Some("km**3/s**2"),
+            ));
+        }
+
+        let mut data = vec![group("meanElements", elements)];
+        if let Some(spacecraft) = &self.spacecraft {
+            data.push(spacecraft.node());
+        }
+        if let Some(parameters) = &self.tle_parameters {
+            let mut children: Vec<_> = comment_nodes(&parameters.comments).collect();
+            children.extend([
+                field("EPHEMERIS_TYPE", parameters.ephemeris_type),
+                field("CLASSIFICATION_TYPE", parameters.classification_type),
+                field("NORAD_CAT_ID", parameters.norad_cat_id),
+                field("ELEMENT_SET_NO", parameters.element_set_no),
+                field("REV_AT_EPOCH", parameters.rev_at_epoch),
+                number_field("BSTAR", parameters.bstar, Some("1/ER")),
+                number_field(
+                    "MEAN_MOTION_DOT",
+                    parameters.mean_motion_dot,
+                    Some("rev/day**2"),
+                ),
+                number_field(
+                    "MEAN_MOTION_DDOT",
+                    parameters.mean_motion_ddot,
+                    Some("rev/day**3"),
+                ),
+            ]);
+            data.push(group("tleParameters", children));
+        }
+        if let Some(covariance) = &self.covariance {
+            data.push(covariance.node(scale, false)?);
+        }
+
+        Ok(Document {
+            kind: "OMM",
+            header: self.header.nodes()?,
+            segments: vec![vec![
+                self.metadata.node(
+                    Layout::Inline,
+                    vec![field("MEAN_ELEMENT_THEORY", &self.mean_element_theory)],
+                )?,
+                group("data", data),
+            ]],
+        })
+    }
+}
+
+impl Cursor {
+    fn tle_parameters(&mut self, comments: Vec<String>) -> Result<TleParameters, OdmError> {
+        let ephemeris_type = match self.optional("EPHEMERIS_TYPE") {
+            Some((line, value)) => value.parse().map_err(|_| invalid(line, "EPHEMERIS_TYPE"))?,
+            None => 0,
+        };
+        let classification_type = match self.optional("CLASSIFICATION_TYPE") {
+            Some((line, value)) => {
+                let mut chars = value.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(classification), None) => classification,
+                    _ => return Err(invalid(line, "CLASSIFICATION_TYPE")),
+                }
+            }
+            None => 'U',
+        };
+
+        Ok(TleParameters {
+            comments,
+            ephemeris_type,
+            classification_type,
+            norad_cat_id: self.required_integer("NORAD_CAT_ID")?,
+            element_set_no: self.required_integer("ELEMENT_SET_NO")?,
+            rev_at_epoch: self.required_integer("REV_AT_EPOCH")?,
+            bstar: self.required_number("BSTAR")?,
+            mean_motion_dot: self.required_number("MEAN_MOTION_DOT")?,
+            mean_motion_ddot: self.required_number("MEAN_MOTION_DDOT")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datetime::{TimeDelta, TimeScale};
+
+    const TEXT: &str = "CCSDS_OMM_VERS = 2.0
+CREATION_DATE = 2007-065T16:00:00
+ORIGINATOR = NOAA/USA
+OBJECT_NAME = GOES 9
+OBJECT_ID = 1995-025A
+CENTER_NAME = EARTH
+REF_FRAME = TEME
+TIME_SYSTEM = UTC
+MEAN_ELEMENT_THEORY = SGP/SGP4
+EPOCH = 2007-064T10:34:41.4264
+MEAN_MOTION = 1.00273272 [rev/day]
+ECCENTRICITY = 0.0005013
+INCLINATION = 3.0539 [deg]
+RA_OF_ASC_NODE = 81.7939 [deg]
+ARG_OF_PERICENTER = 249.2363 [deg]
+MEAN_ANOMALY = 150.1602 [deg]
+GM = 398600.8 [km**3/s**2]
+EPHEMERIS_TYPE = 0
+CLASSIFICATION_TYPE = U
+NORAD_CAT_ID = 23581
+ELEMENT_SET_NO = 0925
+REV_AT_EPOCH = 4316
+BSTAR = 0.0001 [1/ER]
+MEAN_MOTION_DOT = -0.00000113 [rev/day**2]
+MEAN_MOTION_DDOT = 0.0 [rev/day**3]
+";
+
+    #[test]
+    fn round_trip_test() {
+        let omm = Omm::parse(TEXT).unwrap();
+        assert_eq!(
+            omm.epoch,
+            DateTime::gregorian_with_scale(2007, 3, 5, 10, 34, 41.0, TimeScale::UTC).unwrap()
+                + TimeDelta::new(426_400_000)
+        );
+        assert_eq!(omm.size, MeanOrbitSize::MeanMotion(1.00273272));
+
+        for text in [omm.to_kvn().unwrap(), omm.to_xml().unwrap()] {
+            let parsed = Omm::parse(&text).unwrap();
+            assert_eq!(parsed.header, omm.header);
+            assert_eq!(parsed.metadata, omm.metadata);
+            assert_eq!(parsed.mean_element_theory, omm.mean_element_theory);
+            assert_eq!(parsed.epoch, omm.epoch);
+            assert_eq!(parsed.size, omm.size);
+            assert_eq!(parsed.eccentricity, omm.eccentricity);
+            for (angle, expected) in [
+                (parsed.inclination, omm.inclination),
+                (parsed.right_ascension, omm.right_ascension),
+                (parsed.argument_of_pericenter, omm.argument_of_pericenter),
+                (parsed.mean_anomaly, omm.mean_anomaly),
+            ] {
+                assert!((angle - expected).abs() < 1e-12);
+            }
+            let gm = parsed.gravitational_parameter.unwrap();
+            assert!((gm - omm.gravitational_parameter.unwrap()).abs() < 1e-3);
+            assert_eq!(parsed.tle_parameters, omm.tle_parameters);
+        }
+    }
+
+    #[test]
+    fn error_test() {
+        assert_eq!(
+            Omm::parse(&TEXT.replace("MEAN_ELEMENT_THEORY = SGP/SGP4\n", "")).unwrap_err(),
+            OdmError::MissingKeyword {
+                line: 9,
+                keyword: "MEAN_ELEMENT_THEORY",
+            }
+        );
+        assert_eq!(
+            Omm::parse(&TEXT.replace("NORAD_CAT_ID = 23581\n", "")).unwrap_err(),
+            OdmError::MissingKeyword {
+                line: 20,
+                keyword: "NORAD_CAT_ID",
+            }
+        );
+        assert_eq!(
+            Omm::parse(&TEXT.replace("2007-064T10:34:41.4264", "2007-366T10:34:41.4264"))
+                .unwrap_err(),
+            OdmError::InvalidValue {
+                line: 10,
+                keyword: "EPOCH".to_string(),
+            }
+        );
+
+        let mut omm = Omm::parse(TEXT).unwrap();
+        omm.epoch = DateTime::from_julian_date(6_000_000.0, TimeScale::UTC);
+        let expected = OdmError::InvalidEpoch { keyword: "EPOCH" };
+        assert_eq!(omm.to_kvn().unwrap_err(), expected);
+        assert_eq!(omm.to_xml().unwrap_err(), expected);
+    }
+}
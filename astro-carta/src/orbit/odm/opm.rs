@@ -0,0 +1,387 @@
+use super::{
+    comment_nodes, epoch_field, field, group, number_field, Cursor, Document, Layout, Node,
+    OdmCovariance, OdmError, OdmHeader, OdmMetadata, SpacecraftParameters, METERS_PER_KILOMETER,
+};
+use crate::datetime::DateTime;
+use crate::ephemeris::StateVector;
+use crate::math::Vector3;
+use crate::orbit::{Anomaly, KeplerianElements};
+
+/// An impulsive or finite maneuver of an orbit parameter message
+#[derive(Debug, Clone, PartialEq)]
+pub struct Maneuver {
+    /// Comments preceding the maneuver
+    pub comments: Vec<String>,
+    /// Epoch of the ignition
+    pub epoch_ignition: DateTime,
+    /// Duration in seconds, zero for an impulsive maneuver
+    pub duration: f64,
+    /// Change of mass in kg, negative or zero
+    pub delta_mass: f64,
+    /// Reference frame of the velocity change, such as `RTN` or `EME2000`
+    pub ref_frame: String,
+    /// Velocity change in m/s
+    pub delta_v: Vector3,
+}
+
+/// A CCSDS orbit parameter message (OPM): the state of an object at one epoch, with optional
+/// osculating elements, spacecraft parameters, covariance and planned maneuvers
+///
+/// Values are converted to the units of the crate: meters, m/s and radians.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::orbit::odm::Opm;
+///
+/// let text = "CCSDS_OPM_VERS = 2.0
+/// CREATION_DATE = 2000-06-03T05:33:00.000
+/// ORIGINATOR = GSOC
+/// OBJECT_NAME = EUTELSAT W4
+/// OBJECT_ID = 2000-028A
+/// CENTER_NAME = EARTH
+/// REF_FRAME = TOD
+/// TIME_SYSTEM = UTC
+/// EPOCH = 2006-06-03T00:00:00.000
+/// X = 6655.9942 [km]
+/// Y = -40218.5751 [km]
+/// Z = -82.9177 [km]
+/// X_DOT = 3.11548208 [km/s]
+/// Y_DOT = 0.47042605 [km/s]
+/// Z_DOT = -0.00101495 [km/s]
+/// ";
+/// let opm = Opm::parse(text).unwrap();
+/// assert_eq!(opm.metadata.object_name, "EUTELSAT W4");
+/// assert!((opm.state.position.x - 6_655_994.2).abs() < 1e-6);
+///
+/// let round_trip = Opm::parse(&opm.to_xml().unwrap()).unwrap();
+/// assert!((round_trip.state.velocity - opm.state.velocity).norm() < 1e-9);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Opm {
+    pub header: OdmHeader,
+    pub metadata: OdmMetadata,
+    /// Comments of the data section
+    pub comments: Vec<String>,
+    /// Epoch of the state
+    pub epoch: DateTime,
+    /// Position in meters and velocity in m/s in the reference frame of the metadata
+    pub state: StateVector,
+    /// Osculating elements at the epoch
+    pub keplerian: Option<KeplerianElements>,
+    pub spacecraft: Option<SpacecraftParameters>,
+    pub covariance: Option<OdmCovariance>,
+    pub maneuvers: Vec<Maneuver>,
+}
+
+impl Opm {
+    /// Parses a message in either the KVN or the XML notation.
+    pub fn parse(text: &str) -> Result<Self, OdmError> {
+        let mut cursor = Cursor::new(text)?;
+        let header = cursor.header("CCSDS_OPM_VERS")?;
+        let delimited = cursor.marker("META_START");
+        let metadata = cursor.metadata()?;
+        if delimited {
+            cursor.expect_marker("META_STOP")?;
+        }
+        let scale = metadata.time_system;
+
+        let mut comments = cursor.comments();
+        let epoch = cursor.required_epoch("EPOCH", scale)?;
+        let state = StateVector {
+            position: cursor.vector(["X", "Y", "Z"])?,
+            velocity: cursor.vector(["X_DOT", "Y_DOT", "Z_DOT"])?,
+        };
+
+        let keplerian = match cursor.block(&["SEMI_MAJOR_AXIS"]) {
+            Some(block_comments) => {
+                comments.extend(block_comments);
+                Some(cursor.keplerian(epoch)?)
+            }
+            None => None,
+        };
+        let spacecraft = cursor.spacecraft()?;
+        let covariance = cursor.keyword_covariance(scale)?;
+
+        let mut maneuvers = Vec::new();
+        while let Some(comments) = cursor.block(&["MAN_EPOCH_IGNITION"]) {
+            maneuvers.push(Maneuver {
+                comments,
+                epoch_ignition: cursor.required_epoch("MAN_EPOCH_IGNITION", scale)?,
+                duration: cursor.required_number("MAN_DURATION")?,
+                delta_mass: cursor.required_number("MAN_DELTA_MASS")?,
+                ref_frame: cursor.required("MAN_REF_FRAME")?.1,
+                delta_v: cursor.vector(["MAN_DV_1", "MAN_DV_2", "MAN_DV_3"])?,
+            });
+        }
+        cursor.finish()?;
+
+        Ok(Opm {
+            header,
+            metadata,
+            comments,
+            epoch,
+            state,
+            keplerian,
+            spacecraft,
+            covariance,
+            maneuvers,
+        })
+    }
+
+    /// Writes the message in the KVN notation, failing if an epoch falls outside the years 1 to
+    /// 9999.
+    pub fn to_kvn(&self) -> Result<String, OdmError> {
+        Ok(self.document()?.to_kvn())
+    }
+
+    /// Writes the message in the XML notation, failing if an epoch falls outside the years 1 to
+    /// 9999.
+    pub fn to_xml(&self) -> Result<String, OdmError> {
+        Ok(self.document()?.to_xml())
+    }
+
+    fn document(&self) -> Result<Document, OdmError> {
+        let scale = self.metadata.time_system;
+        let mut state: Vec<_> = comment_nodes(&self.comments).collect();
+        state.push(epoch_field("EPOCH", &self.epoch, scale)?);
+        state.extend(vector_nodes(["X", "Y", "Z"], &self.state.position, "km"));
+        state.extend(vector_nodes(
+            ["X_DOT", "Y_DOT", "Z_DOT"],
+            &self.state.velocity,
+            "km/s",
+        ));
+
+        let mut data = vec![group("stateVector", state)];
+        if let Some(elements) = &self.keplerian {
+            data.push(keplerian_node(elements));
+        }
+        if let Some(spacecraft) = &self.spacecraft {
+            data.push(spacecraft.node());
+        }
+        if let Some(covariance) = &self.covariance {
+            data.push(covariance.node(scale, false)?);
+        }
+        for maneuver in &self.maneuvers {
+            let mut children: Vec<_> = comment_nodes(&maneuver.comments).collect();
+            children.push(epoch_field(
+                "MAN_EPOCH_IGNITION",
+                &maneuver.epoch_ignition,
+                scale,
+            )?);
+            children.push(number_field("MAN_DURATION", maneuver.duration, Some("s")));
+            children.push(number_field(
+                "MAN_DELTA_MASS",
+                maneuver.delta_mass,
+                Some("kg"),
+            ));
+            children.push(field("MAN_REF_FRAME", &maneuver.ref_frame));
+            children.extend(vector_nodes(
+                ["MAN_DV_1", "MAN_DV_2", "MAN_DV_3"],
+                &maneuver.delta_v,
+                "km/s",
+            ));
+            data.push(group("maneuverParameters", children));
+        }
+
+        Ok(Document {
+            kind: "OPM",
+            header: self.header.nodes()?,
+            segments: vec![vec![
+                self.metadata.node(Layout::Inline, Vec::new())?,
+                group("data", data),
+            ]],
+        })
+    }
+}
+
+impl Cursor {
+    /// Reads three components in kilometers into a vector in meters.
+    pub(super) fn vector(&mut self, keywords: [&'static str; 3]) -> Result<Vector3, OdmError> {
+        let mut vector = Vector3::zeros();
+        for (index, keyword) in keywords.into_iter().enumerate() {
+            vector[index] = self.required_number(keyword)? * METERS_PER_KILOMETER;
+        }
+        Ok(vector)
+    }
+
+    /// Reads the osculating Keplerian elements of a parameter message.
+    fn keplerian(&mut self, epoch: DateTime) -> Result<KeplerianElements, OdmError> {
+        let semi_major_axis = self.required_number("SEMI_MAJOR_AXIS")? * METERS_PER_KILOMETER;
+        let eccentricity = self.required_number("ECCENTRICITY")?;
+        let inclination = self.required_number("INCLINATION")?.to_radians();
+        let ascending_node = self.required_number("RA_OF_ASC_NODE")?.to_radians();
+        let argument_of_periapsis = self.required_number("ARG_OF_PERICENTER")?.to_radians();
+        let anomaly = match self.optional_number("TRUE_ANOMALY")? {
+            Some(anomaly) => Anomaly::True(anomaly.to_radians()),
+            None => Anomaly::Mean(self.required_number("MEAN_ANOMALY")?.to_radians()),
+        };
+        let gravitational_parameter = self.required_number("GM")? * METERS_PER_KILOMETER.powi(3);
+
+        Ok(KeplerianElements {
+            epoch,
+            gravitational_parameter,
+            semi_major_axis,
+            eccentricity,
+            inclination,
+            ascending_node,
+            argument_of_periapsis,
+            anomaly,
+        })
+    }
+}
+
+/// Returns the fields of a vector in meters written in kilometers.
+pub(super) fn vector_nodes(
+    keywords: [&'static str; 3],
+    vector: &Vector3,
+    units: &'static str,
+) -> impl Iterator<Item = Node> {
+    let values = [vector.x, vector.y, vector.z];
+    keywords
+        .into_iter()
+        .zip(values)
+        .map(move |(key, value)| number_field(key, value / METERS_PER_KILOMETER, Some(units)))
+}
+
+fn keplerian_node(elements: &KeplerianElements) -> Node {
+    let anomaly = match elements.anomaly {
+        Anomaly::Mean(anomaly) => number_field("MEAN_ANOMALY", anomaly.to_degrees(), Some("deg")),
+        _ => number_field(
+            "TRUE_ANOMALY",
+            elements.true_anomaly().to_degrees(),
+            Some("deg"),
+        ),
+    };
+    group(
+        "keplerianElements",
+        vec![
+            number_field(
+                "SEMI_MAJOR_AXIS",
+                elements.semi_major_axis / METERS_PER_KILOMETER,
+                Some("km"),
+            ),
+            number_field("ECCENTRICITY", elements.eccentricity, None),
+            number_field(
+                "INCLINATION",
+                elements.inclination.to_degrees(),
+                Some("deg"),
+            ),
+            number_field(
+                "RA_OF_ASC_NODE",
+                elements.ascending_node.to_degrees(),
+                Some("deg"),
+            ),
+            number_field(
+                "ARG_OF_PERICENTER",
+                elements.argument_of_periapsis.to_degrees(),
+                Some("deg"),
+            ),
+            anomaly,
+            number_field(
+                "GM",
+                elements.gravitational_parameter / METERS_PER_KILOMETER.powi(3),
+                Some("km**3/s**2"),
+            ),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datetime::TimeScale;
+
+    const TEXT: &str = "CCSDS_OPM_VERS = 2.0
+CREATION_DATE = 2000-06-03T05:33:00.000
+ORIGINATOR = GSOC
+OBJECT_NAME = EUTELSAT W4
+OBJECT_ID = 2000-028A
+CENTER_NAME = EARTH
+REF_FRAME = TOD
+TIME_SYSTEM = UTC
+EPOCH = 2006-06-03T00:00:00.000
+X = 6655.9942 [km]
+Y = -40218.5751 [km]
+Z = -82.9177 [km]
+X_DOT = 3.11548208 [km/s]
+Y_DOT = 0.47042605 [km/s]
+Z_DOT = -0.00101495 [km/s]
+SEMI_MAJOR_AXIS = 41399.5123 [km]
+ECCENTRICITY = 0.020842611
+INCLINATION = 0.117746 [deg]
+RA_OF_ASC_NODE = 17.604721 [deg]
+ARG_OF_PERICENTER = 218.242943 [deg]
+TRUE_ANOMALY = 41.922339 [deg]
+GM = 398600.4415 [km**3/s**2]
+MAN_EPOCH_IGNITION = 2006-06-03T09:00:34.1
+MAN_DURATION = 132.60 [s]
+MAN_DELTA_MASS = -18.418 [kg]
+MAN_REF_FRAME = EME2000
+MAN_DV_1 = -0.02325700 [km/s]
+MAN_DV_2 = 0.01683160 [km/s]
+MAN_DV_3 = -0.00893444 [km/s]
+";
+
+    #[test]
+    fn round_trip_test() {
+        let opm = Opm::parse(TEXT).unwrap();
+        assert_eq!(
+            opm.epoch,
+            DateTime::gregorian_with_scale(2006, 6, 3, 0, 0, 0.0, TimeScale::UTC).unwrap()
+        );
+        assert_eq!(opm.maneuvers.len(), 1);
+
+        for text in [opm.to_kvn().unwrap(), opm.to_xml().unwrap()] {
+            let parsed = Opm::parse(&text).unwrap();
+            assert_eq!(parsed.header, opm.header);
+            assert_eq!(parsed.metadata, opm.metadata);
+            assert_eq!(parsed.epoch, opm.epoch);
+            assert!((parsed.state.position - opm.state.position).norm() < 1e-6);
+            assert!((parsed.state.velocity - opm.state.velocity).norm() < 1e-9);
+            let (elements, expected) = (
+                parsed.keplerian.as_ref().unwrap(),
+                opm.keplerian.as_ref().unwrap(),
+            );
+            assert!((elements.semi_major_axis - expected.semi_major_axis).abs() < 1e-6);
+            assert!((elements.true_anomaly() - expected.true_anomaly()).abs() < 1e-12);
+            assert_eq!(parsed.maneuvers.len(), 1);
+            let (maneuver, expected) = (&parsed.maneuvers[0], &opm.maneuvers[0]);
+            assert_eq!(maneuver.epoch_ignition, expected.epoch_ignition);
+            assert_eq!(maneuver.duration, expected.duration);
+            assert!((maneuver.delta_v - expected.delta_v).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn error_test() {
+        assert_eq!(
+            Opm::parse(&TEXT.replace("Y = -40218.5751 [km]\n", "")).unwrap_err(),
+            OdmError::MissingKeyword {
+                line: 11,
+                keyword: "Y",
+            }
+        );
+        assert_eq!(
+            Opm::parse(&TEXT.replace("MAN_DURATION = 132.60 [s]\n", "")).unwrap_err(),
+            OdmError::MissingKeyword {
+                line: 24,
+                keyword: "MAN_DURATION",
+            }
+        );
+        assert_eq!(
+            Opm::parse(&TEXT.replace("2006-06-03T00:00:00.000", "2006-06-31T00:00:00.000"))
+                .unwrap_err(),
+            OdmError::InvalidValue {
+                line: 9,
+                keyword: "EPOCH".to_string(),
+            }
+        );
+
+        let mut opm = Opm::parse(TEXT).unwrap();
+        opm.epoch = DateTime::from_julian_date(1_000_000.0, TimeScale::UTC);
+        let expected = OdmError::InvalidEpoch { keyword: "EPOCH" };
+        assert_eq!(opm.to_kvn().unwrap_err(), expected);
+        assert_eq!(opm.to_xml().unwrap_err(), expected);
+    }
+}
@@ -0,0 +1,425 @@
+use std::fmt::Write;
+
+use super::{Document, Entry, Node, OdmError, VERSION};
+
+/// Keywords of an ephemeris record, in the order of its values
+const STATE_KEYWORDS: [&str; 10] = [
+    "EPOCH", "X", "Y", "Z", "X_DOT", "Y_DOT", "Z_DOT", "X_DDOT", "Y_DDOT", "Z_DDOT",
+];
+
+/// An XML element with the lines on which it starts and ends
+#[derive(Debug, Clone, PartialEq)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+    line: usize,
+    end_line: usize,
+}
+
+/// A parser of the subset of XML used by orbit data messages: elements, attributes, text,
+/// character references, comments and processing instructions
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    line: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self) -> OdmError {
+        OdmError::InvalidXml { line: self.line }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    /// Advances by `length` bytes, counting lines.
+    fn advance(&mut self, length: usize) {
+        let skipped = &self.text[self.position..self.position + length];
+        self.line += skipped.matches('\n').count();
+        self.position += length;
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.advance(rest.len() - rest.trim_start().len());
+    }
+
+    /// Skips up to and including the given delimiter.
+    fn skip_past(&mut self, delimiter: &str) -> Result<(), OdmError> {
+        let end = self.rest().find(delimiter).ok_or_else(|| self.error())?;
+        self.advance(end + delimiter.len());
+        Ok(())
+    }
+
+    /// Skips whitespace, comments, processing instructions and declarations.
+    fn skip_misc(&mut self) -> Result<(), OdmError> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, OdmError> {
+        let rest = self.rest();
+        let length = rest
+            .find(|c: char| c.is_whitespace() || "/>=".contains(c))
+            .unwrap_or(rest.len());
+        if length == 0 {
+            return Err(self.error());
+        }
+        self.advance(length);
+        Ok(rest[..length].to_string())
+    }
+
+    fn element(&mut self) -> Result<Element, OdmError> {
+        let line = self.line;
+        if !self.rest().starts_with('<') {
+            return Err(self.error());
+        }
+        self.advance(1);
+        let name = self.name()?;
+
+        let mut attributes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.advance(2);
+                return Ok(Element {
+                    name,
+                    attributes,
+                    children: Vec::new(),
+                    text: String::new(),
+                    line,
+                    end_line: self.line,
+                });
+            }
+            if rest.starts_with('>') {
+                self.advance(1);
+                break;
+            }
+            let attribute = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.error());
+            }
+            self.advance(1);
+            self.skip_whitespace();
+            let quote = self
+                .rest()
+                .chars()
+                .next()
+                .filter(|c| *c == '"' || *c == '\'');
+            let quote = quote.ok_or_else(|| self.error())?;
+            self.advance(1);
+            let end = self.rest().find(quote).ok_or_else(|| self.error())?;
+            let value = unescape(&self.rest()[..end]).ok_or_else(|| self.error())?;
+            self.advance(end + 1);
+            attributes.push((attribute, value));
+        }
+
+        let mut children = Vec::new();
+        let mut text = String::new();
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.advance(2);
+                if self.name()? != name {
+                    return Err(self.error());
+                }
+                self.skip_whitespace();
+                if !self.rest().starts_with('>') {
+                    return Err(self.error());
+                }
+                self.advance(1);
+                return Ok(Element {
+                    name,
+                    attributes,
+                    children,
+                    text,
+                    line,
+                    end_line: self.line,
+                });
+            } else if rest.starts_with("<!--") || rest.starts_with("<?") {
+                self.skip_misc()?;
+            } else if rest.starts_with('<') {
+                children.push(self.element()?);
+            } else if rest.is_empty() {
+                return Err(self.error());
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                text.push_str(&unescape(&rest[..end]).ok_or_else(|| self.error())?);
+                self.advance(end);
+            }
+        }
+    }
+}
+
+/// Replaces the predefined entities and character references of XML text.
+fn unescape(text: &str) -> Option<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        let end = rest[start..].find(';')? + start;
+        let character = match &rest[start + 1..end] {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            reference => {
+                let code = match reference.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => reference.strip_prefix('#')?.parse().ok()?,
+                };
+                char::from_u32(code)?
+            }
+        };
+        result.push(character);
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    Some(result)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Splits a message in the XML notation into the entries of its KVN equivalent.
+///
+/// The `id` and `version` attributes of the root element become the version keyword, the
+/// metadata and covariance elements are delimited as in KVN and, in ephemeris messages, each
+/// state vector element becomes a data line.
+pub(super) fn entries(text: &str) -> Result<Vec<Entry>, OdmError> {
+    let mut parser = Parser {
+        text,
+        position: 0,
+        line: 1,
+    };
+    parser.skip_misc()?;
+    let root = parser.element()?;
+    parser.skip_misc()?;
+    if !parser.rest().is_empty() {
+        return Err(parser.error());
+    }
+
+    let mut entries = Vec::new();
+    let attribute = |name: &str| {
+        root.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.clone())
+    };
+    if let (Some(id), Some(version)) = (attribute("id"), attribute("version")) {
+        entries.push(Entry::Pair {
+            line: root.line,
+            key: id,
+            value: version,
+        });
+    }
+    let ephemeris = root.name == "oem";
+    for child in &root.children {
+        flatten(child, ephemeris, &mut entries)?;
+    }
+    Ok(entries)
+}
+
+fn flatten(element: &Element, ephemeris: bool, entries: &mut Vec<Entry>) -> Result<(), OdmError> {
+    let markers = match element.name.as_str() {
+        "metadata" => Some(("META_START", "META_STOP")),
+        "covarianceMatrix" => Some(("COVARIANCE_START", "COVARIANCE_STOP")),
+        "stateVector" if ephemeris => return state(element, entries),
+        _ if element.children.is_empty() => {
+            entries.push(Entry::Pair {
+                line: element.line,
+                key: element.name.clone(),
+                value: element.text.trim().to_string(),
+            });
+            return Ok(());
+        }
+        _ => None,
+    };
+
+    if let Some((start, _)) = markers {
+        entries.push(Entry::Marker {
+            line: element.line,
+            name: start.to_string(),
+        });
+    }
+    for child in &element.children {
+        flatten(child, ephemeris, entries)?;
+    }
+    if let Some((_, stop)) = markers {
+        entries.push(Entry::Marker {
+            line: element.end_line,
+            name: stop.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Converts a state vector element of an ephemeris message into a data line.
+fn state(element: &Element, entries: &mut Vec<Entry>) -> Result<(), OdmError> {
+    if element.children.len() != 7 && element.children.len() != 10 {
+        return Err(OdmError::InvalidData { line: element.line });
+    }
+    let mut values = Vec::with_capacity(element.children.len());
+    for (child, keyword) in element.children.iter().zip(STATE_KEYWORDS) {
+        if child.name != keyword {
+            return Err(OdmError::UnexpectedKeyword {
+                line: child.line,
+                keyword: child.name.clone(),
+            });
+        }
+        values.push(child.text.trim().to_string());
+    }
+    entries.push(Entry::Data {
+        line: element.line,
+        values,
+    });
+    Ok(())
+}
+
+/// Writes a message in the XML notation.
+pub(super) fn render(document: &Document) -> String {
+    let root = document.kind.to_lowercase();
+    let mut text = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        text,
+        "<{root} id=\"CCSDS_{}_VERS\" version=\"{VERSION}\">",
+        document.kind
+    );
+    text.push_str("  <header>\n");
+    for node in &document.header {
+        write_node(&mut text, node, 2);
+    }
+    text.push_str("  </header>\n  <body>\n");
+    for segment in &document.segments {
+        text.push_str("    <segment>\n");
+        for node in segment {
+            write_node(&mut text, node, 3);
+        }
+        text.push_str("    </segment>\n");
+    }
+    let _ = writeln!(text, "  </body>\n</{root}>");
+    text
+}
+
+fn write_node(text: &mut String, node: &Node, depth: usize) {
+    let indent = "  ".repeat(depth);
+    match node {
+        Node::Field { key, value, units } => {
+            let _ = write!(text, "{indent}<{key}");
+            if let Some(units) = units {
+                let _ = write!(text, " units=\"{}\"", escape(units));
+            }
+            let _ = writeln!(text, ">{}</{key}>", escape(value));
+        }
+        Node::Comment(comment) => {
+            let _ = writeln!(text, "{indent}<COMMENT>{}</COMMENT>", escape(comment));
+        }
+        Node::Group {
+            tag: Some(tag),
+            children,
+            ..
+        } => {
+            let _ = writeln!(text, "{indent}<{tag}>");
+            for child in children {
+                write_node(text, child, depth + 1);
+            }
+            let _ = writeln!(text, "{indent}</{tag}>");
+        }
+        Node::Group {
+            tag: None,
+            children,
+            ..
+        } => {
+            for child in children {
+                write_node(text, child, depth);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_test() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- An ephemeris -->
+<oem id="CCSDS_OEM_VERS" version="2.0">
+  <header><COMMENT>A &amp; B &#x3C;</COMMENT></header>
+  <body><segment>
+    <metadata><OBJECT_NAME>SAT</OBJECT_NAME></metadata>
+    <data>
+      <stateVector>
+        <EPOCH>2000-01-01T00:00:00</EPOCH>
+        <X units="km">1</X><Y>2</Y><Z>3</Z>
+        <X_DOT>4</X_DOT><Y_DOT>5</Y_DOT><Z_DOT>6</Z_DOT>
+      </stateVector>
+      <EMPTY/>
+    </data>
+  </segment></body>
+</oem>
+"#;
+        let entries = entries(text).unwrap();
+        let pair = |line: usize, key: &str, value: &str| Entry::Pair {
+            line,
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        let marker = |line: usize, name: &str| Entry::Marker {
+            line,
+            name: name.to_string(),
+        };
+        assert_eq!(
+            entries,
+            [
+                pair(3, "CCSDS_OEM_VERS", "2.0"),
+                pair(4, "COMMENT", "A & B <"),
+                marker(6, "META_START"),
+                pair(6, "OBJECT_NAME", "SAT"),
+                marker(6, "META_STOP"),
+                Entry::Data {
+                    line: 8,
+                    values: ["2000-01-01T00:00:00", "1", "2", "3", "4", "5", "6"]
+                        .map(str::to_string)
+                        .to_vec()
+                },
+                pair(13, "EMPTY", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_test() {
+        for (text, line) in [
+            ("<opm>\n<A>1</B>\n</opm>", 2),
+            ("<opm>\n<A>1</A>\n", 3),
+            ("<opm>\n<A x=1>1</A>\n</opm>", 2),
+            ("<opm>\n<A>&bogus;</A>\n</opm>", 2),
+            ("<opm></opm>\n<opm></opm>", 2),
+        ] {
+            assert_eq!(entries(text), Err(OdmError::InvalidXml { line }), "{text}");
+        }
+    }
+}
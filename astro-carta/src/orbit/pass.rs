@@ -0,0 +1,582 @@
+use std::fmt;
+
+use super::events::{EventDetector, EventError, EventFunction};
+use super::odm::format_epoch;
+use super::propagation::Propagator;
+use super::station::{GroundStation, LookAngles};
+use crate::datetime::{DateTime, Interval, TimeDelta, TimeScale};
+use crate::ephemeris::StateVector;
+use crate::math::{self, roots, TWO_PI};
+
+/// Half-width in seconds of the central differences of the elevation
+const DIFFERENCE_STEP: f64 = 0.5;
+
+/// Error raised while writing a report of passes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportError {
+    /// A time falls outside the years 1 to 9999, which the reports cannot write
+    InvalidTime(DateTime),
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReportError::InvalidTime(dt) => write!(
+                f,
+                "time at JD {} UTC outside the years 1 to 9999",
+                dt.julian_date(TimeScale::UTC)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReportError {}
+
+/// Smallest elevation of a station depending on the azimuth, from the terrain or buildings
+/// around it
+///
+/// The elevation is interpolated linearly in azimuth between the points of the table, wrapping
+/// around the north.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::orbit::pass::TerrainMask;
+///
+/// let mask = TerrainMask::from_degrees(&[(0.0, 5.0), (90.0, 15.0), (270.0, 5.0)]).unwrap();
+/// assert!((mask.elevation(45f64.to_radians()).to_degrees() - 10.0).abs() < 1e-12);
+/// assert!((mask.elevation(315f64.to_radians()).to_degrees() - 5.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerrainMask {
+    /// Azimuths in [0, 2π) in increasing order, with the elevations in radians
+    points: Vec<(f64, f64)>,
+}
+
+impl TerrainMask {
+    /// Constructs a mask of the same elevation in radians in every direction.
+    pub fn constant(elevation: f64) -> Self {
+        TerrainMask {
+            points: vec![(0.0, elevation)],
+        }
+    }
+
+    /// Constructs a mask from azimuths and elevations in radians, in any order, or returns
+    /// `None` if the table is empty or holds values that are not finite.
+    pub fn new(points: Vec<(f64, f64)>) -> Option<Self> {
+        if points.is_empty() || points.iter().any(|(a, e)| !a.is_finite() || !e.is_finite()) {
+            return None;
+        }
+        let mut points: Vec<_> = points
+            .into_iter()
+            .map(|(azimuth, elevation)| (math::normalize_angle(azimuth), elevation))
+            .collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Some(TerrainMask { points })
+    }
+
+    /// Constructs a mask from azimuths and elevations in degrees.
+    pub fn from_degrees(points: &[(f64, f64)]) -> Option<Self> {
+        TerrainMask::new(
+            points
+                .iter()
+                .map(|(azimuth, elevation)| (azimuth.to_radians(), elevation.to_radians()))
+                .collect(),
+        )
+    }
+
+    /// Returns the points of the table, azimuths and elevations in radians.
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// Returns the smallest elevation in radians at an azimuth in radians.
+    pub fn elevation(&self, azimuth: f64) -> f64 {
+        let azimuth = math::normalize_angle(azimuth);
+        let n = self.points.len();
+        let index = self.points.partition_point(|point| point.0 <= azimuth);
+        let previous = self.points[(index + n - 1) % n];
+        let next = self.points[index % n];
+        let span = (next.0 - previous.0).rem_euclid(TWO_PI);
+        if span == 0.0 {
+            return previous.1;
+        }
+        let fraction = (azimuth - previous.0).rem_euclid(TWO_PI) / span;
+        previous.1 + fraction * (next.1 - previous.1)
+    }
+}
+
+impl Default for TerrainMask {
+    /// Returns the mask of the horizon.
+    fn default() -> Self {
+        TerrainMask::constant(0.0)
+    }
+}
+
+/// Elevation of the object above the terrain mask of a station, positive when visible
+#[derive(Debug, Clone, PartialEq)]
+pub struct Visibility {
+    pub station: GroundStation,
+    pub mask: TerrainMask,
+}
+
+impl EventFunction for Visibility {
+    fn value(&self, dt: &DateTime, state: &StateVector) -> Result<f64, EventError> {
+        let look = self.station.look_angles(dt, state);
+        Ok(look.elevation - self.mask.elevation(look.azimuth))
+    }
+}
+
+/// An object seen from a station at an instant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackPoint {
+    pub dt: DateTime,
+    pub look: LookAngles,
+}
+
+/// A pass of an object over a station, from its acquisition of signal to its loss of signal
+///
+/// Passes in progress at the start or the end of a prediction are cut there.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pass {
+    /// Acquisition of signal, when the object rises above the mask
+    pub aos: TrackPoint,
+    /// Time of closest approach, when the range is smallest
+    pub tca: TrackPoint,
+    /// Culmination, when the elevation is largest
+    pub culmination: TrackPoint,
+    /// Loss of signal, when the object sets below the mask
+    pub los: TrackPoint,
+    /// Samples from the acquisition to the loss of signal
+    pub track: Vec<TrackPoint>,
+}
+
+impl Pass {
+    pub fn duration(&self) -> TimeDelta {
+        self.los.dt - self.aos.dt
+    }
+
+    pub fn interval(&self) -> Interval {
+        Interval {
+            start: self.aos.dt,
+            end: self.los.dt,
+        }
+    }
+
+    /// Returns the largest elevation in radians.
+    pub fn max_elevation(&self) -> f64 {
+        self.culmination.look.elevation
+    }
+}
+
+/// Predictor of the passes of an object over a ground station
+///
+/// The acquisitions and losses of signal are located by an [`EventDetector`] on the elevation
+/// above the terrain mask, so passes shorter than the step may be missed. The times of closest
+/// approach and culmination are located within a millisecond.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::constants::EARTH_GRAVITATIONAL_PARAMETER;
+/// use astro_carta::coordinates::Observer;
+/// use astro_carta::datetime::{DateTime, TimeDelta};
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::pass::{self, PassPredictor, TerrainMask};
+/// use astro_carta::orbit::station::GroundStation;
+/// use astro_carta::orbit::TwoBody;
+///
+/// let state = StateVector {
+///     position: Vector3::new(7_078e3, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, -1_040.0, 7_432.0),
+/// };
+/// let start = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let propagator = TwoBody::new(state, EARTH_GRAVITATIONAL_PARAMETER, start);
+/// let station = GroundStation::new(Observer::from_degrees(40.4314, -4.2481, 834.0));
+/// let predictor = PassPredictor::new(station)
+///     .with_mask(TerrainMask::constant(5f64.to_radians()));
+/// let end = start + TimeDelta::days(1.0);
+/// let passes = predictor.passes(&propagator, &start, &end).unwrap();
+/// assert!(!passes.is_empty());
+/// for pass in &passes {
+///     assert!(pass.aos.dt < pass.tca.dt && pass.tca.dt < pass.los.dt);
+///     assert!(pass.max_elevation() > 5f64.to_radians());
+/// }
+/// println!("{}", pass::summary_csv(&passes).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PassPredictor {
+    pub station: GroundStation,
+    pub mask: TerrainMask,
+    /// Interval between the samples of the elevation while searching for passes
+    pub step: TimeDelta,
+    /// Interval between the points of the tracks, which hold only the acquisition and loss of
+    /// signal if it is not positive
+    pub track_step: TimeDelta,
+}
+
+impl PassPredictor {
+    /// Constructs a predictor above the horizon, searching every minute and sampling the tracks
+    /// every ten seconds.
+    pub fn new(station: GroundStation) -> Self {
+        PassPredictor {
+            station,
+            mask: TerrainMask::default(),
+            step: TimeDelta::minutes(1.0),
+            track_step: TimeDelta::seconds(10.0),
+        }
+    }
+
+    pub fn with_mask(mut self, mask: TerrainMask) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    pub fn with_step(mut self, step: TimeDelta) -> Self {
+        self.step = step;
+        self
+    }
+
+    pub fn with_track_step(mut self, track_step: TimeDelta) -> Self {
+        self.track_step = track_step;
+        self
+    }
+
+    /// Predicts the passes of the object between two instants.
+    pub fn passes<P: Propagator>(
+        &self,
+        propagator: &P,
+        start: &DateTime,
+        end: &DateTime,
+    ) -> Result<Vec<Pass>, EventError> {
+        let visibility = Visibility {
+            station: self.station,
+            mask: self.mask.clone(),
+        };
+        let windows = EventDetector::new(self.step).windows(propagator, &visibility, start, end)?;
+        windows
+            .iter()
+            .filter(|window| window.duration() > TimeDelta::new(0))
+            .map(|window| self.pass(propagator, window))
+            .collect()
+    }
+
+    fn pass<P: Propagator>(&self, propagator: &P, window: &Interval) -> Result<Pass, EventError> {
+        let point = |dt: DateTime| -> Result<TrackPoint, EventError> {
+            let state = propagator
+                .propagate(&dt)
+                .map_err(|error| EventError::Propagation(error.to_string()))?;
+            Ok(TrackPoint {
+                dt,
+                look: self.station.look_angles(&dt, &state),
+            })
+        };
+        let at = |offset: f64| window.start + TimeDelta::seconds(offset);
+        let span = window.duration().total_seconds();
+
+        // The range is smallest where the range rate turns positive
+        let range_rate = |offset: f64| point(at(offset)).map(|point| point.look.range_rate);
+        let tca = maximum(|offset| range_rate(offset).map(|rate| -rate), span)?;
+        // The elevation is largest where its rate turns negative
+        let elevation = |offset: f64| point(at(offset)).map(|point| point.look.elevation);
+        let culmination = maximum(
+            |offset| {
+                Ok(
+                    (elevation(offset + DIFFERENCE_STEP)? - elevation(offset - DIFFERENCE_STEP)?)
+                        / (2.0 * DIFFERENCE_STEP),
+                )
+            },
+            span,
+        )?;
+
+        let step = self.track_step.total_seconds();
+        let mut track = Vec::new();
+        let mut offset = 0.0;
+        while offset < span {
+            track.push(point(at(offset))?);
+            if step <= 0.0 {
+                break;
+            }
+            offset += step;
+        }
+        track.push(point(window.end)?);
+
+        Ok(Pass {
+            aos: track[0],
+            tca: point(at(tca))?,
+            culmination: point(at(culmination))?,
+            los: track[track.len() - 1],
+            track,
+        })
+    }
+}
+
+/// Returns the offset in seconds in [0, span] where a function is largest, given its rate of
+/// change, assuming it increases then decreases.
+fn maximum<F>(mut rate: F, span: f64) -> Result<f64, EventError>
+where
+    F: FnMut(f64) -> Result<f64, EventError>,
+{
+    let (start, end) = (rate(0.0)?, rate(span)?);
+    if start <= 0.0 {
+        Ok(0.0)
+    } else if end >= 0.0 {
+        Ok(span)
+    } else {
+        roots::brent(rate, 0.0, start, span, end, 1e-3)
+    }
+}
+
+fn format_time(dt: &DateTime) -> Result<String, ReportError> {
+    let text = format_epoch(dt, TimeScale::UTC).ok_or(ReportError::InvalidTime(*dt))?;
+    Ok(format!("{text}Z"))
+}
+
+/// Writes a summary of the passes as CSV, one row per pass
+///
+/// Times are in UTC, angles in degrees, ranges in kilometers and durations in seconds. Writing
+/// fails if a time falls outside the years 1 to 9999.
+pub fn summary_csv(passes: &[Pass]) -> Result<String, ReportError> {
+    let mut text = String::from(
+        "aos,tca,culmination,los,duration_s,max_elevation_deg,aos_azimuth_deg,\
+         culmination_azimuth_deg,los_azimuth_deg,min_range_km\n",
+    );
+    for pass in passes {
+        text.push_str(&format!(
+            "{},{},{},{},{:.3},{:.4},{:.4},{:.4},{:.4},{:.3}\n",
+            format_time(&pass.aos.dt)?,
+            format_time(&pass.tca.dt)?,
+            format_time(&pass.culmination.dt)?,
+            format_time(&pass.los.dt)?,
+            pass.duration().total_seconds(),
+            pass.max_elevation().to_degrees(),
+            pass.aos.look.azimuth.to_degrees(),
+            pass.culmination.look.azimuth.to_degrees(),
+            pass.los.look.azimuth.to_degrees(),
+            pass.tca.look.range / 1e3,
+        ));
+    }
+    Ok(text)
+}
+
+/// Writes the tracks of the passes as CSV, one row per point numbered by pass from 1
+///
+/// Times are in UTC, angles in degrees, ranges in kilometers and range rates in km/s. Writing
+/// fails if a time falls outside the years 1 to 9999.
+pub fn track_csv(passes: &[Pass]) -> Result<String, ReportError> {
+    let mut text = String::from("pass,time,azimuth_deg,elevation_deg,range_km,range_rate_km_s\n");
+    for (index, pass) in passes.iter().enumerate() {
+        for point in &pass.track {
+            text.push_str(&format!(
+                "{},{},{:.4},{:.4},{:.3},{:.6}\n",
+                index + 1,
+                format_time(&point.dt)?,
+                point.look.azimuth.to_degrees(),
+                point.look.elevation.to_degrees(),
+                point.look.range / 1e3,
+                point.look.range_rate / 1e3,
+            ));
+        }
+    }
+    Ok(text)
+}
+
+fn json_point(point: &TrackPoint) -> Result<String, ReportError> {
+    Ok(format!(
+        "{{\"time\":\"{}\",\"azimuth_deg\":{:.4},\"elevation_deg\":{:.4},\"range_km\":{:.3},\
+         \"range_rate_km_s\":{:.6}}}",
+        format_time(&point.dt)?,
+        point.look.azimuth.to_degrees(),
+        point.look.elevation.to_degrees(),
+        point.look.range / 1e3,
+        point.look.range_rate / 1e3,
+    ))
+}
+
+/// Writes the passes with their tracks as a JSON document, one pass per line
+///
+/// Times are in UTC, angles in degrees, ranges in kilometers and range rates in km/s. Writing
+/// fails if a time falls outside the years 1 to 9999.
+pub fn json(passes: &[Pass]) -> Result<String, ReportError> {
+    let passes: Vec<String> = passes
+        .iter()
+        .map(|pass| {
+            let track: Vec<String> = pass
+                .track
+                .iter()
+                .map(json_point)
+                .collect::<Result<_, _>>()?;
+            Ok(format!(
+                "{{\"aos\":{},\"tca\":{},\"culmination\":{},\"los\":{},\"duration_s\":{:.3},\
+                 \"track\":[{}]}}",
+                json_point(&pass.aos)?,
+                json_point(&pass.tca)?,
+                json_point(&pass.culmination)?,
+                json_point(&pass.los)?,
+                pass.duration().total_seconds(),
+                track.join(","),
+            ))
+        })
+        .collect::<Result<_, _>>()?;
+    if passes.is_empty() {
+        Ok("{\"passes\":[]}\n".to_string())
+    } else {
+        Ok(format!("{{\"passes\":[\n{}\n]}}\n", passes.join(",\n")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::EARTH_GRAVITATIONAL_PARAMETER;
+    use crate::coordinates::Observer;
+    use crate::orbit::events::Elevation;
+    use crate::orbit::{Anomaly, KeplerianElements, TwoBody};
+
+    fn start() -> DateTime {
+        DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap()
+    }
+
+    /// A sun-synchronous orbit at 700 km
+    fn propagator() -> TwoBody {
+        TwoBody::from_elements(&KeplerianElements {
+            epoch: start(),
+            gravitational_parameter: EARTH_GRAVITATIONAL_PARAMETER,
+            semi_major_axis: 7_078e3,
+            eccentricity: 0.001,
+            inclination: 98.2f64.to_radians(),
+            ascending_node: 0.5,
+            argument_of_periapsis: 0.0,
+            anomaly: Anomaly::Mean(0.0),
+        })
+        .unwrap()
+    }
+
+    fn station() -> GroundStation {
+        GroundStation::new(Observer::from_degrees(40.4314, -4.2481, 834.0))
+    }
+
+    #[test]
+    fn mask_test() {
+        let mask = TerrainMask::from_degrees(&[(350.0, 20.0), (10.0, 10.0), (180.0, 0.0)]).unwrap();
+        let degrees = |azimuth: f64| mask.elevation(azimuth.to_radians()).to_degrees();
+        assert!((degrees(0.0) - 15.0).abs() < 1e-12);
+        assert!((degrees(-5.0) - 17.5).abs() < 1e-12);
+        assert!((degrees(95.0) - 5.0).abs() < 1e-12);
+        assert!((degrees(265.0) - 10.0).abs() < 1e-12);
+        assert!((degrees(350.0) - 20.0).abs() < 1e-12);
+        assert_eq!(TerrainMask::constant(0.1).elevation(4.0), 0.1);
+        assert_eq!(TerrainMask::new(Vec::new()), None);
+        assert_eq!(TerrainMask::new(vec![(0.0, f64::NAN)]), None);
+    }
+
+    #[test]
+    fn passes_test() {
+        let propagator = propagator();
+        let threshold = 5f64.to_radians();
+        let end = start() + TimeDelta::days(1.0);
+        let passes = PassPredictor::new(station())
+            .with_mask(TerrainMask::constant(threshold))
+            .passes(&propagator, &start(), &end)
+            .unwrap();
+        let windows = EventDetector::new(TimeDelta::minutes(1.0))
+            .windows(
+                &propagator,
+                &Elevation::new(station(), threshold),
+                &start(),
+                &end,
+            )
+            .unwrap();
+        assert!(passes.len() >= 4);
+        assert_eq!(passes.len(), windows.len());
+
+        for (pass, window) in passes.iter().zip(&windows) {
+            assert!((pass.aos.dt - window.start).total_seconds().abs() < 1e-5);
+            assert!((pass.los.dt - window.end).total_seconds().abs() < 1e-5);
+            assert!((pass.aos.look.elevation - threshold).abs() < 1e-8);
+            assert!((pass.los.look.elevation - threshold).abs() < 1e-8);
+            assert!(pass.aos.look.range_rate < 0.0 && pass.los.look.range_rate > 0.0);
+            // The range accelerates by about 50 m/s² at the closest approach
+            assert!(pass.tca.look.range_rate.abs() < 0.1);
+            for point in &pass.track {
+                assert!(point.look.range >= pass.tca.look.range - 1e-3);
+                assert!(point.look.elevation <= pass.max_elevation() + 1e-9);
+                assert!(point.look.elevation >= threshold - 1e-8);
+            }
+            let steps = pass
+                .track
+                .windows(2)
+                .map(|p| (p[1].dt - p[0].dt).total_seconds());
+            assert!(steps.into_iter().all(|step| step > 0.0 && step <= 10.0));
+        }
+    }
+
+    #[test]
+    fn masked_passes_test() {
+        let propagator = propagator();
+        let end = start() + TimeDelta::days(1.0);
+        let open = PassPredictor::new(station())
+            .passes(&propagator, &start(), &end)
+            .unwrap();
+        // A ridge rising to 30° in the south
+        let mask =
+            TerrainMask::from_degrees(&[(90.0, 0.0), (150.0, 30.0), (210.0, 30.0), (270.0, 0.0)])
+                .unwrap();
+        let masked = PassPredictor::new(station())
+            .with_mask(mask.clone())
+            .passes(&propagator, &start(), &end)
+            .unwrap();
+        assert!(!masked.is_empty());
+        let duration = |passes: &[Pass]| {
+            passes
+                .iter()
+                .map(|pass| pass.duration().total_seconds())
+                .sum::<f64>()
+        };
+        assert!(duration(&masked) < duration(&open));
+        for pass in &masked {
+            for point in [&pass.aos, &pass.los] {
+                let limit = mask.elevation(point.look.azimuth);
+                assert!((point.look.elevation - limit).abs() < 1e-8);
+            }
+        }
+    }
+
+    #[test]
+    fn report_test() {
+        let propagator = propagator();
+        let end = start() + TimeDelta::days(1.0);
+        let passes = PassPredictor::new(station())
+            .with_track_step(TimeDelta::minutes(1.0))
+            .passes(&propagator, &start(), &end)
+            .unwrap();
+
+        let summary = summary_csv(&passes).unwrap();
+        let lines: Vec<_> = summary.lines().collect();
+        assert_eq!(lines.len(), passes.len() + 1);
+        assert!(lines[0].starts_with("aos,tca,culmination,los,duration_s"));
+        assert!(lines.iter().all(|line| line.split(',').count() == 10));
+        assert!(lines[1].starts_with(&format_time(&passes[0].aos.dt).unwrap()));
+        assert!(lines[1].contains("Z,"));
+
+        let points: usize = passes.iter().map(|pass| pass.track.len()).sum();
+        assert_eq!(track_csv(&passes).unwrap().lines().count(), points + 1);
+
+        let json = json(&passes).unwrap();
+        assert!(json.starts_with("{\"passes\":[\n{\"aos\":{\"time\":\"2024-06-01T"));
+        assert!(json.ends_with("]}\n"));
+        assert_eq!(json.matches("\"tca\"").count(), passes.len());
+        assert_eq!(json.matches("\"time\"").count(), points + 4 * passes.len());
+        assert_eq!(json.matches('{').count(), json.matches('}').count());
+        assert_eq!(super::json(&[]).unwrap(), "{\"passes\":[]}\n");
+
+        let mut ancient = passes.clone();
+        let dt = DateTime::from_julian_date(1e6, TimeScale::UTC);
+        ancient[0].los.dt = dt;
+        assert_eq!(summary_csv(&ancient), Err(ReportError::InvalidTime(dt)));
+        assert_eq!(super::json(&ancient), Err(ReportError::InvalidTime(dt)));
+        ancient[0].track[0].dt = dt;
+        assert_eq!(track_csv(&ancient), Err(ReportError::InvalidTime(dt)));
+    }
+}
@@ -0,0 +1,540 @@
+use super::{Anomaly, KeplerianElements, OrbitError};
+use crate::datetime::{DateTime, TimeDelta};
+use crate::ephemeris::StateVector;
+use crate::math;
+
+/// Maximum number of Laguerre iterations of the universal Kepler equation
+const MAX_ITERATIONS: usize = 50;
+
+/// Order of the Laguerre-Conway iteration
+const LAGUERRE_ORDER: f64 = 5.0;
+
+/// A source of the state of an orbiting body at arbitrary instants
+pub trait Propagator {
+    /// Error raised when a state cannot be computed
+    type Error: std::error::Error;
+
+    /// Computes the state at the given instant, with position in meters and velocity in m/s.
+    fn propagate(&self, dt: &DateTime) -> Result<StateVector, Self::Error>;
+
+    /// Returns an iterator over the states at a sequence of instants, such as a
+    /// [`DateTimeRange`](crate::datetime::DateTimeRange).
+    fn states<I>(&self, epochs: I) -> States<'_, Self, I::IntoIter>
+    where
+        Self: Sized,
+        I: IntoIterator<Item = DateTime>,
+    {
+        States {
+            propagator: self,
+            epochs: epochs.into_iter(),
+        }
+    }
+}
+
+/// An iterator over the states of a propagator at a sequence of instants
+#[derive(Debug, Clone)]
+pub struct States<'a, P, I> {
+    propagator: &'a P,
+    epochs: I,
+}
+
+impl<P: Propagator, I: Iterator<Item = DateTime>> Iterator for States<'_, P, I> {
+    type Item = (DateTime, Result<StateVector, P::Error>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let dt = self.epochs.next()?;
+        Some((dt, self.propagator.propagate(&dt)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.epochs.size_hint()
+    }
+}
+
+/// Propagates a state along its two-body orbit with the universal-variable formulation of
+/// Kepler's equation.
+///
+/// The formulation holds for elliptic, parabolic and hyperbolic orbits alike, and for
+/// rectilinear ones. The universal anomaly is found by Laguerre-Conway iteration.
+///
+/// # Arguments
+///
+/// * `state` - Position in meters and velocity in m/s relative to the central body.
+/// * `gravitational_parameter` - Gravitational parameter of the central body in m³/s².
+/// * `duration` - Time of flight, negative to propagate backwards.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::constants::EARTH_GRAVITATIONAL_PARAMETER;
+/// use astro_carta::datetime::TimeDelta;
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::propagation;
+///
+/// // A circular orbit comes back to its starting point after one period
+/// let radius: f64 = 7e6;
+/// let speed = (EARTH_GRAVITATIONAL_PARAMETER / radius).sqrt();
+/// let state = StateVector {
+///     position: Vector3::new(radius, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, speed, 0.0),
+/// };
+/// let period = std::f64::consts::TAU * radius / speed;
+/// let half = propagation::universal(&state, EARTH_GRAVITATIONAL_PARAMETER, TimeDelta::seconds(period / 2.0)).unwrap();
+/// assert!((half.position + state.position).norm() < 1e-3);
+/// ```
+pub fn universal(
+    state: &StateVector,
+    gravitational_parameter: f64,
+    duration: TimeDelta,
+) -> Result<StateVector, OrbitError> {
+    if !(gravitational_parameter > 0.0 && gravitational_parameter.is_finite()) {
+        return Err(OrbitError::InvalidGravitationalParameter);
+    }
+    let (r0, v0) = (state.position, state.velocity);
+    let r0_norm = r0.norm();
+    if r0_norm == 0.0 || !r0_norm.is_finite() {
+        return Err(OrbitError::DegenerateState);
+    }
+
+    let mut t = duration.total_seconds();
+    if t == 0.0 {
+        return Ok(*state);
+    }
+
+    let sqrt_mu = gravitational_parameter.sqrt();
+    let sigma0 = r0.dot(&v0) / sqrt_mu;
+    // Reciprocal of the semi-major axis
+    let alpha = 2.0 / r0_norm - v0.dot(&v0) / gravitational_parameter;
+
+    // Whole revolutions of a closed orbit are removed to keep the universal anomaly small
+    if alpha > 0.0 {
+        t %= math::TWO_PI / (sqrt_mu * alpha.powf(1.5));
+    }
+
+    let mut chi = if alpha > 0.0 {
+        sqrt_mu * t * alpha
+    } else {
+        // Asymptotic start for hyperbolic orbits (Vallado, algorithm 8), which would otherwise
+        // need many iterations in the exponential regime
+        let a = 1.0 / alpha;
+        let sign = t.signum();
+        let argument = -2.0 * gravitational_parameter * alpha * t
+            / (r0.dot(&v0)
+                + sign * (-gravitational_parameter * a).sqrt() * (1.0 - r0_norm * alpha));
+        let start = sign * (-a).sqrt() * argument.ln();
+        if start.is_finite() && argument > 1.0 {
+            start
+        } else {
+            sqrt_mu * t / r0_norm
+        }
+    };
+    let radial = 1.0 - alpha * r0_norm;
+    let mut converged = false;
+    for _ in 0..MAX_ITERATIONS {
+        let z = alpha * chi * chi;
+        let (c, s) = stumpff(z);
+        let f = sigma0 * chi * chi * c + radial * chi.powi(3) * s + r0_norm * chi - sqrt_mu * t;
+        let df = sigma0 * chi * (1.0 - z * s) + radial * chi * chi * c + r0_norm;
+        let ddf = sigma0 * (1.0 - z * c) + radial * chi * (1.0 - z * s);
+
+        let n = LAGUERRE_ORDER;
+        let root = ((n - 1.0).powi(2) * df * df - n * (n - 1.0) * f * ddf)
+            .abs()
+            .sqrt();
+        let delta = n * f / (df + df.signum() * root);
+        chi -= delta;
+        if !chi.is_finite() {
+            break;
+        }
+        if delta.abs() <= 1e-14 * chi.abs().max(r0_norm.sqrt() * 1e-3) {
+            converged = true;
+            break;
+        }
+    }
+    if !converged {
+        return Err(OrbitError::NoConvergence);
+    }
+
+    let z = alpha * chi * chi;
+    let (c, s) = stumpff(z);
+    let chi2 = chi * chi;
+    let f = 1.0 - chi2 * c / r0_norm;
+    let g = t - chi2 * chi * s / sqrt_mu;
+    let position = r0 * f + v0 * g;
+    let r_norm = position.norm();
+    let g_dot = 1.0 - chi2 * c / r_norm;
+    let f_dot = sqrt_mu / (r_norm * r0_norm) * chi * (z * s - 1.0);
+
+    Ok(StateVector {
+        position,
+        velocity: r0 * f_dot + v0 * g_dot,
+    })
+}
+
+/// Evaluates the Stumpff functions C(z) and S(z).
+fn stumpff(z: f64) -> (f64, f64) {
+    if z.abs() < 1.0 {
+        // Series C = Σ (-z)^k / (2k + 2)!, S = Σ (-z)^k / (2k + 3)!, which avoid the
+        // cancellation of the closed forms near zero
+        let (mut c, mut s) = (0.0, 0.0);
+        let (mut c_term, mut s_term) = (0.5, 1.0 / 6.0);
+        for k in 0..12 {
+            c += c_term;
+            s += s_term;
+            let k = k as f64;
+            c_term *= -z / ((2.0 * k + 3.0) * (2.0 * k + 4.0));
+            s_term *= -z / ((2.0 * k + 4.0) * (2.0 * k + 5.0));
+        }
+        (c, s)
+    } else if z > 0.0 {
+        let root = z.sqrt();
+        ((1.0 - root.cos()) / z, (root - root.sin()) / (z * root))
+    } else {
+        let root = (-z).sqrt();
+        ((root.cosh() - 1.0) / -z, (root.sinh() - root) / (-z * root))
+    }
+}
+
+/// Two-body propagation of a state by the universal-variable formulation
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::constants::EARTH_GRAVITATIONAL_PARAMETER;
+/// use astro_carta::datetime::{DateTime, DateTimeRange, TimeDelta};
+/// use astro_carta::ephemeris::StateVector;
+/// use astro_carta::math::Vector3;
+/// use astro_carta::orbit::{Propagator, TwoBody};
+///
+/// let epoch = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let state = StateVector {
+///     position: Vector3::new(7e6, 0.0, 0.0),
+///     velocity: Vector3::new(0.0, 7.6e3, 1e3),
+/// };
+/// let orbit = TwoBody::new(state, EARTH_GRAVITATIONAL_PARAMETER, epoch);
+/// let range = DateTimeRange::new(epoch, epoch + TimeDelta::hours(2.0), TimeDelta::minutes(10.0));
+/// for (_, state) in orbit.states(range) {
+///     assert!(state.unwrap().position.norm() >= 7e6 - 1e-3);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TwoBody {
+    /// State at the epoch
+    pub state: StateVector,
+    /// Gravitational parameter of the central body in m³/s²
+    pub gravitational_parameter: f64,
+    /// Epoch of the state
+    pub epoch: DateTime,
+}
+
+impl TwoBody {
+    /// Constructs a two-body propagator from a state at an epoch.
+    pub fn new(state: StateVector, gravitational_parameter: f64, epoch: DateTime) -> Self {
+        TwoBody {
+            state,
+            gravitational_parameter,
+            epoch,
+        }
+    }
+
+    /// Constructs a two-body propagator from orbital elements.
+    pub fn from_elements(elements: &KeplerianElements) -> Result<Self, OrbitError> {
+        Ok(TwoBody::new(
+            elements.to_state()?,
+            elements.gravitational_parameter,
+            elements.epoch,
+        ))
+    }
+}
+
+impl Propagator for TwoBody {
+    type Error = OrbitError;
+
+    fn propagate(&self, dt: &DateTime) -> Result<StateVector, OrbitError> {
+        universal(&self.state, self.gravitational_parameter, *dt - self.epoch)
+    }
+}
+
+/// Propagation of mean elements under the secular effect of the J2 zonal harmonic
+///
+/// The semi-major axis, eccentricity and inclination stay constant while the ascending node,
+/// the argument of periapsis and the mean anomaly drift at constant first-order rates. The
+/// elements are taken as mean elements; short-period oscillations are not modeled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct J2Secular {
+    elements: KeplerianElements,
+    node_rate: f64,
+    periapsis_rate: f64,
+    mean_anomaly_rate: f64,
+}
+
+impl J2Secular {
+    /// Constructs a J2 secular propagator.
+    ///
+    /// # Arguments
+    ///
+    /// * `elements` - Mean elements of a closed orbit at the epoch.
+    /// * `j2` - Unnormalized second zonal harmonic of the central body.
+    /// * `equatorial_radius` - Reference radius of the harmonic in meters.
+    pub fn new(
+        elements: KeplerianElements,
+        j2: f64,
+        equatorial_radius: f64,
+    ) -> Result<Self, OrbitError> {
+        elements.validate()?;
+        if elements.eccentricity >= 1.0 {
+            return Err(OrbitError::InvalidElements(
+                "J2 secular propagation needs a closed orbit",
+            ));
+        }
+
+        let e2 = elements.eccentricity * elements.eccentricity;
+        let n = elements.mean_motion();
+        let ratio = equatorial_radius / elements.semi_latus_rectum();
+        let k = 1.5 * j2 * ratio * ratio * n;
+        let sin2_i = elements.inclination.sin().powi(2);
+
+        Ok(J2Secular {
+            elements,
+            node_rate: -k * elements.inclination.cos(),
+            periapsis_rate: k * (2.0 - 2.5 * sin2_i),
+            mean_anomaly_rate: n + k * (1.0 - e2).sqrt() * (1.0 - 1.5 * sin2_i),
+        })
+    }
+
+    /// Returns the mean elements at the epoch.
+    pub fn elements(&self) -> &KeplerianElements {
+        &self.elements
+    }
+
+    /// Returns the rate of the right ascension of the ascending node in rad/s.
+    pub fn node_rate(&self) -> f64 {
+        self.node_rate
+    }
+
+    /// Returns the rate of the argument of periapsis in rad/s.
+    pub fn periapsis_rate(&self) -> f64 {
+        self.periapsis_rate
+    }
+
+    /// Returns the rate of the mean anomaly in rad/s, including the J2 correction to the mean
+    /// motion.
+    pub fn mean_anomaly_rate(&self) -> f64 {
+        self.mean_anomaly_rate
+    }
+
+    /// Returns the mean elements at the given instant, with a mean anomaly.
+    pub fn elements_at(&self, dt: &DateTime) -> KeplerianElements {
+        let t = (*dt - self.elements.epoch).total_seconds();
+        KeplerianElements {
+            epoch: *dt,
+            ascending_node: math::normalize_angle(
+                self.elements.ascending_node + self.node_rate * t,
+            ),
+            argument_of_periapsis: math::normalize_angle(
+                self.elements.argument_of_periapsis + self.periapsis_rate * t,
+            ),
+            anomaly: Anomaly::Mean(math::normalize_angle(
+                self.elements.mean_anomaly() + self.mean_anomaly_rate * t,
+            )),
+            ..self.elements
+        }
+    }
+}
+
+impl Propagator for J2Secular {
+    type Error = OrbitError;
+
+    fn propagate(&self, dt: &DateTime) -> Result<StateVector, OrbitError> {
+        self.elements_at(dt).to_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{EARTH_EQUATORIAL_RADIUS, EARTH_GRAVITATIONAL_PARAMETER, EARTH_J2};
+    use crate::datetime::{DateTimeRange, TimeScale};
+    use crate::math::Vector3;
+
+    const MU: f64 = EARTH_GRAVITATIONAL_PARAMETER;
+
+    fn epoch() -> DateTime {
+        DateTime::from_days_since_j2000(9_000.25, TimeScale::TT)
+    }
+
+    fn elements(a: f64, e: f64, anomaly: f64) -> KeplerianElements {
+        KeplerianElements {
+            epoch: epoch(),
+            gravitational_parameter: MU,
+            semi_major_axis: a,
+            eccentricity: e,
+            inclination: 0.9,
+            ascending_node: 4.0,
+            argument_of_periapsis: 1.2,
+            anomaly: Anomaly::True(anomaly),
+        }
+    }
+
+    #[test]
+    fn stumpff_test() {
+        for &z in &[-30.0, -1.0 - 1e-12, -0.5, 0.0, 1e-8, 0.999_999, 1.0, 25.0] {
+            let (c, s) = stumpff(z);
+            let (closed_c, closed_s) = if z > 0.0 {
+                let root = f64::sqrt(z);
+                ((1.0 - root.cos()) / z, (root - root.sin()) / (z * root))
+            } else if z < 0.0 {
+                let root = f64::sqrt(-z);
+                ((root.cosh() - 1.0) / -z, (root.sinh() - root) / (-z * root))
+            } else {
+                (0.5, 1.0 / 6.0)
+            };
+            let tolerance = if z.abs() < 1e-4 { 1e-8 } else { 1e-14 };
+            assert!((c - closed_c).abs() < tolerance, "z = {z}");
+            assert!((s - closed_s).abs() < tolerance, "z = {z}");
+        }
+    }
+
+    #[test]
+    fn kepler_test() {
+        // The universal formulation agrees with the propagation of the mean anomaly
+        for elements in [
+            elements(7_000e3, 0.001, 0.3),
+            elements(26_600e3, 0.74, 3.0),
+            elements(-12_000e3, 1.6, -1.0),
+            elements(8_000e3, 1.0, -2.0),
+        ] {
+            let state = elements.to_state().unwrap();
+            let n = elements.mean_motion();
+            for hours in [-30.0, -1.0, 0.25, 2.0, 50.0] {
+                let duration = TimeDelta::hours(hours);
+                let mut expected = elements;
+                expected.anomaly =
+                    Anomaly::Mean(elements.mean_anomaly() + n * duration.total_seconds());
+                let expected = expected.to_state().unwrap();
+
+                let actual = universal(&state, MU, duration).unwrap();
+                let scale = expected.position.norm();
+                assert!(
+                    (actual.position - expected.position).norm() < 1e-9 * scale,
+                    "{elements:?} after {hours} h"
+                );
+                assert!(
+                    (actual.velocity - expected.velocity).norm() < 1e-9 * expected.velocity.norm()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn conservation_test() {
+        for elements in [
+            elements(42_164e3, 0.0, 0.0),
+            elements(40_000e3, 0.9, 0.1),
+            elements(-50_000e3, 1.1, 0.5),
+        ] {
+            let orbit = TwoBody::from_elements(&elements).unwrap();
+            let energy = |state: &StateVector| {
+                0.5 * state.velocity.dot(&state.velocity) - MU / state.position.norm()
+            };
+            let momentum = |state: &StateVector| state.position.cross(&state.velocity);
+            let (energy0, momentum0) = (energy(&orbit.state), momentum(&orbit.state));
+
+            let range = DateTimeRange::new(
+                epoch() - TimeDelta::days(3.0),
+                epoch() + TimeDelta::days(10.0),
+                TimeDelta::minutes(37.0),
+            );
+            let mut count = 0;
+            for (dt, state) in orbit.states(range) {
+                let state = state.unwrap();
+                assert!(
+                    ((energy(&state) - energy0) / energy0).abs() < 1e-10,
+                    "{dt:?}"
+                );
+                assert!((momentum(&state) - momentum0).norm() < 1e-10 * momentum0.norm());
+                count += 1;
+            }
+            assert_eq!(count, 506);
+        }
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let state = StateVector {
+            position: Vector3::new(-4e6, 5e6, 2e6),
+            velocity: Vector3::new(-5e3, -4e3, 3e3),
+        };
+        let forward = universal(&state, MU, TimeDelta::days(3.7)).unwrap();
+        let back = universal(&forward, MU, TimeDelta::days(-3.7)).unwrap();
+        assert!((back.position - state.position).norm() < 1e-4);
+        assert!((back.velocity - state.velocity).norm() < 1e-7);
+        assert_eq!(universal(&state, MU, TimeDelta::new(0)), Ok(state));
+
+        // Radial trajectories have no angular momentum but still propagate
+        let radial = StateVector {
+            position: Vector3::new(7e6, 0.0, 0.0),
+            velocity: Vector3::new(12e3, 0.0, 0.0),
+        };
+        let later = universal(&radial, MU, TimeDelta::hours(1.0)).unwrap();
+        assert!(later.position.y.abs() < 1e-6 && later.position.x > 7e6);
+
+        let zero = StateVector {
+            position: Vector3::zeros(),
+            velocity: Vector3::zeros(),
+        };
+        assert_eq!(
+            universal(&zero, MU, TimeDelta::hours(1.0)),
+            Err(OrbitError::DegenerateState)
+        );
+    }
+
+    #[test]
+    fn j2_secular_test() {
+        // Sun-synchronous orbit at 700 km: the node follows the mean Sun
+        let a = EARTH_EQUATORIAL_RADIUS + 700e3;
+        let e: f64 = 0.001;
+        let n = (MU / a.powi(3)).sqrt();
+        let p = a * (1.0 - e * e);
+        let sun_rate = math::TWO_PI / (365.242_2 * 86_400.0);
+        let cos_i = -sun_rate / (1.5 * EARTH_J2 * (EARTH_EQUATORIAL_RADIUS / p).powi(2) * n);
+        assert!((cos_i.acos().to_degrees() - 98.19).abs() < 0.01);
+
+        let elements = KeplerianElements {
+            inclination: cos_i.acos(),
+            ..elements(a, e, 0.0)
+        };
+        let orbit = J2Secular::new(elements, EARTH_J2, EARTH_EQUATORIAL_RADIUS).unwrap();
+        assert!((orbit.node_rate() - sun_rate).abs() < 1e-15);
+
+        let later = orbit.elements_at(&(epoch() + TimeDelta::days(100.0)));
+        let expected_node = math::normalize_angle(4.0 + 100.0 * 0.985_647_36_f64.to_radians());
+        assert!((later.ascending_node - expected_node).abs() < 1e-6);
+        assert_eq!(later.semi_major_axis, a);
+        assert_eq!(later.inclination, elements.inclination);
+
+        // The apsides are frozen at the critical inclination
+        let critical = KeplerianElements {
+            inclination: (1.0_f64 / 5.0).sqrt().acos(),
+            ..elements
+        };
+        let orbit = J2Secular::new(critical, EARTH_J2, EARTH_EQUATORIAL_RADIUS).unwrap();
+        assert!(orbit.periapsis_rate().abs() < 1e-20);
+
+        // Without J2 the propagation reduces to two-body motion
+        let orbit = J2Secular::new(elements, 0.0, EARTH_EQUATORIAL_RADIUS).unwrap();
+        let two_body = TwoBody::from_elements(&elements).unwrap();
+        let dt = epoch() + TimeDelta::hours(7.3);
+        let difference =
+            orbit.propagate(&dt).unwrap().position - two_body.propagate(&dt).unwrap().position;
+        assert!(difference.norm() < 1e-5);
+
+        let hyperbolic = KeplerianElements {
+            semi_major_axis: -9e6,
+            eccentricity: 1.5,
+            ..elements
+        };
+        assert!(J2Secular::new(hyperbolic, EARTH_J2, EARTH_EQUATORIAL_RADIUS).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use crate::coordinates::Observer;
+use crate::datetime::DateTime;
+use crate::ephemeris::StateVector;
+use crate::geodesy::local::enu_rotation;
+use crate::math::{self, Matrix3, Vector3};
+use crate::orbit::force::{BodyRotation, EarthRotation};
+
+/// Geometric look angles, range and range rate of an object seen from a ground station,
+/// without light time or refraction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LookAngles {
+    /// Azimuth in radians in [0, 2π), clockwise from the north
+    pub azimuth: f64,
+    /// Elevation in radians above the horizon
+    pub elevation: f64,
+    /// Distance in meters
+    pub range: f64,
+    /// Rate of change of the distance in m/s, positive when receding
+    pub range_rate: f64,
+}
+
+/// A ground station tracking orbiting objects, whose states are geocentric in the GCRS
+///
+/// Polar motion is neglected.
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::constants::EARTH_ANGULAR_VELOCITY;
+/// use astro_carta::coordinates::Observer;
+/// use astro_carta::datetime::DateTime;
+/// use astro_carta::orbit::station::GroundStation;
+///
+/// let station = GroundStation::new(Observer::from_degrees(40.4314, -4.2481, 834.0));
+/// let dt = DateTime::gregorian(2024, 6, 1, 0, 0, 0.0).unwrap();
+/// let state = station.state(&dt);
+/// assert!((state.position.norm() - 6.370e6).abs() < 1e4);
+/// let itrs = station.observer.geocentric_position();
+/// let speed = EARTH_ANGULAR_VELOCITY * itrs.x.hypot(itrs.y);
+/// assert!((state.velocity.norm() - speed).abs() < 1e-6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GroundStation {
+    pub observer: Observer,
+    /// UT1 - UTC in seconds
+    pub dut1: f64,
+}
+
+impl GroundStation {
+    pub fn new(observer: Observer) -> Self {
+        GroundStation {
+            observer,
+            dut1: 0.0,
+        }
+    }
+
+    /// Sets UT1 - UTC in seconds.
+    pub fn with_dut1(mut self, dut1: f64) -> Self {
+        self.dut1 = dut1;
+        self
+    }
+
+    /// Computes the geocentric position (m) and velocity (m/s) of the station in the GCRS.
+    pub fn state(&self, dt: &DateTime) -> StateVector {
+        let (position, velocity) = self.observer.gcrs_position_velocity(dt, self.dut1);
+        StateVector { position, velocity }
+    }
+
+    /// Returns the rotation from the GCRS to the east-north-up axes of the station.
+    pub fn enu_rotation(&self, dt: &DateTime) -> Matrix3 {
+        let rotation = EarthRotation { dut1: self.dut1 };
+        // The Earth rotation never fails
+        enu_rotation(self.observer.latitude, self.observer.longitude)
+            * rotation.rotation_to_body_fixed(dt).unwrap()
+    }
+
+    /// Computes the geometric elevation in radians of an object at the given position in the
+    /// GCRS, without light time or refraction.
+    pub fn elevation(&self, dt: &DateTime, position: &Vector3) -> f64 {
+        let local = self.enu_rotation(dt) * (*position - self.state(dt).position);
+        (local.z / local.norm()).clamp(-1.0, 1.0).asin()
+    }
+
+    /// Computes the geometric look angles of an object in the given state in the GCRS.
+    pub fn look_angles(&self, dt: &DateTime, state: &StateVector) -> LookAngles {
+        let station = self.state(dt);
+        let line_of_sight = state.position - station.position;
+        let range = line_of_sight.norm();
+        let local = self.enu_rotation(dt) * line_of_sight;
+        LookAngles {
+            azimuth: math::normalize_angle(local.x.atan2(local.y)),
+            elevation: (local.z / range).clamp(-1.0, 1.0).asin(),
+            range,
+            range_rate: line_of_sight.dot(&(state.velocity - station.velocity)) / range,
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use std::fmt;
+
+use crate::datetime::{DateTime, TimeScale};
+
+/// Length of a line of a two-line element set, including the checksum
+const LINE_LENGTH: usize = 69;
+
+/// Error raised while parsing a two-line element set
+#[derive(Debug, Clone, PartialEq)]
+pub enum TleError {
+    /// A line is shorter than 69 characters or is not ASCII
+    InvalidLength { line: usize },
+    /// A line does not start with the expected line number
+    InvalidLineNumber { line: usize },
+    /// The checksum in column 69 does not match the line
+    InvalidChecksum { line: usize },
+    /// A field could not be decoded
+    InvalidField { line: usize, field: &'static str },
+    /// The satellite numbers of the two lines differ
+    MismatchedSatelliteNumber { line: usize },
+    /// The text ends before the second line of an element set
+    MissingLine { line: usize },
+}
+
+impl fmt::Display for TleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TleError::InvalidLength { line } => write!(f, "line {line}: invalid TLE line length"),
+            TleError::InvalidLineNumber { line } => {
+                write!(f, "line {line}: unexpected TLE line number")
+            }
+            TleError::InvalidChecksum { line } => write!(f, "line {line}: checksum mismatch"),
+            TleError::InvalidField { line, field } => write!(f, "line {line}: invalid {field}"),
+            TleError::MismatchedSatelliteNumber { line } => {
+                write!(f, "line {line}: satellite number differs from line 1")
+            }
+            TleError::MissingLine { line } => write!(f, "line {line}: missing TLE line 2"),
+        }
+    }
+}
+
+impl std::error::Error for TleError {}
+
+/// A two-line mean element set in the format of the US Space Force
+///
+/// Angles are stored in radians. The mean elements are only meaningful with the SGP4/SDP4
+/// model they were fitted for, see [`Sgp4`](super::sgp4::Sgp4).
+///
+/// # Examples
+///
+/// ```
+/// use astro_carta::datetime::{DateTime, TimeScale};
+/// use astro_carta::orbit::tle::Tle;
+///
+/// let tle = Tle::parse(
+///     "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753",
+///     "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667",
+/// )
+/// .unwrap();
+/// assert_eq!(tle.satellite_number, 5);
+/// assert_eq!(tle.international_designator, "58002B");
+/// assert!((tle.eccentricity - 0.1859667).abs() < 1e-12);
+/// let epoch = DateTime::gregorian_with_scale(2000, 6, 27, 18, 50, 19.733_568, TimeScale::UTC).unwrap();
+/// assert!((tle.epoch - epoch).total_seconds().abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tle {
+    /// Name from the title line of a three-line element set
+    pub name: Option<String>,
+    /// NORAD catalog number, with Alpha-5 numbers above 99 999 decoded
+    pub satellite_number: u32,
+    /// Classification, such as `U` for unclassified
+    pub classification: char,
+    /// International designator: launch year, launch number and piece
+    pub international_designator: String,
+    /// Epoch of the elements
+    pub epoch: DateTime,
+    /// First derivative of the mean motion divided by two in rev/day²
+    pub mean_motion_dot: f64,
+    /// Second derivative of the mean motion divided by six in rev/day³
+    pub mean_motion_ddot: f64,
+    /// Drag term B* in inverse Earth radii
+    pub bstar: f64,
+    /// Ephemeris type, zero for SGP4/SDP4 elements
+    pub ephemeris_type: u8,
+    /// Element set number
+    pub element_set_number: u32,
+    /// Inclination in radians
+    pub inclination: f64,
+    /// Right ascension of the ascending node in radians
+    pub right_ascension: f64,
+    /// Eccentricity
+    pub eccentricity: f64,
+    /// Argument of perigee in radians
+    pub argument_of_perigee: f64,
+    /// Mean anomaly in radians
+    pub mean_anomaly: f64,
+    /// Mean motion in revolutions per day
+    pub mean_motion: f64,
+    /// Revolution number at the epoch
+    pub revolution_number: u32,
+}
+
+impl Tle {
+    /// Parses a two-line element set, validating the checksums.
+    pub fn parse(line1: &str, line2: &str) -> Result<Self, TleError> {
+        Tle::parse_lines(None, line1, line2, 1)
+    }
+
+    /// Parses a three-line element set whose first line is the satellite name.
+    pub fn parse_3le(name: &str, line1: &str, line2: &str) -> Result<Self, TleError> {
+        Tle::parse_lines(Some(name), line1, line2, 2)
+    }
+
+    /// Parses every two- and three-line element set in a text, skipping blank lines.
+    ///
+    /// A line that does not start with `1 ` or `2 ` is taken as the title of the element set
+    /// that follows it; a leading `0 ` on a title line is removed.
+    pub fn parse_all(text: &str) -> Result<Vec<Self>, TleError> {
+        let mut sets = Vec::new();
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim_end()))
+            .filter(|(_, line)| !line.is_empty());
+
+        while let Some((number, line)) = lines.next() {
+            let (name, line1, first) = if line.starts_with("1 ") {
+                (None, line, number)
+            } else {
+                let (number, line1) = lines
+                    .next()
+                    .ok_or(TleError::MissingLine { line: number + 1 })?;
+                let name = line.strip_prefix("0 ").unwrap_or(line).trim();
+                (Some(name), line1, number)
+            };
+            let (_, line2) = lines
+                .next()
+                .ok_or(TleError::MissingLine { line: first + 1 })?;
+            sets.push(Tle::parse_lines(name, line1, line2, first)?);
+        }
+
+        Ok(sets)
+    }
+
+    /// Parses the two element lines, the first of which is at line number `first` of its text.
+    fn parse_lines(
+        name: Option<&str>,
+        line1: &str,
+        line2: &str,
+        first: usize,
+    ) -> Result<Self, TleError> {
+        let line1 = check_line(line1.trim_end(), b'1', first)?;
+        let line2 = check_line(line2.trim_end(), b'2', first + 1)?;
+        let field1 = |range: std::ops::Range<usize>| line1[range].trim();
+        let field2 = |range: std::ops::Range<usize>| line2[range].trim();
+        let invalid1 = |field| TleError::InvalidField { line: first, field };
+        let invalid2 = |field| TleError::InvalidField {
+            line: first + 1,
+            field,
+        };
+
+        let number = satellite_number(&line1[2..7]).ok_or(invalid1("satellite number"))?;
+        if satellite_number(&line2[2..7]) != Some(number) {
+            return Err(TleError::MismatchedSatelliteNumber { line: first + 1 });
+        }
+
+        let year: u64 = field1(18..20).parse().map_err(|_| invalid1("epoch year"))?;
+        // Two-digit years from 57 refer to the twentieth century, the start of the catalog
+        let year = if year < 57 { 2000 + year } else { 1900 + year };
+        let day: f64 = field1(20..32).parse().map_err(|_| invalid1("epoch day"))?;
+        let epoch =
+            DateTime::from_day_of_year(year, day, TimeScale::UTC).ok_or(invalid1("epoch day"))?;
+
+        let angle = |range, field| {
+            field2(range)
+                .parse::<f64>()
+                .map(f64::to_radians)
+                .map_err(|_| invalid2(field))
+        };
+
+        Ok(Tle {
+            name: name.map(str::to_string),
+            satellite_number: number,
+            classification: line1.as_bytes()[7] as char,
+            international_designator: field1(9..17).to_string(),
+            epoch,
+            mean_motion_dot: field1(33..43)
+                .parse()
+                .map_err(|_| invalid1("mean motion derivative"))?,
+            mean_motion_ddot: exponential(&line1[44..52])
+                .ok_or(invalid1("mean motion second derivative"))?,
+            bstar: exponential(&line1[53..61]).ok_or(invalid1("drag term"))?,
+            ephemeris_type: match field1(62..63) {
+                "" => 0,
+                text => text.parse().map_err(|_| invalid1("ephemeris type"))?,
+            },
+            element_set_number: match field1(64..68) {
+                "" => 0,
+                text => text.parse().map_err(|_| invalid1("element set number"))?,
+            },
+            inclination: angle(8..16, "inclination")?,
+            right_ascension: angle(17..25, "right ascension")?,
+            eccentricity: format!("0.{}", field2(26..33))
+                .parse()
+                .map_err(|_| invalid2("eccentricity"))?,
+            argument_of_perigee: angle(34..42, "argument of perigee")?,
+            mean_anomaly: angle(43..51, "mean anomaly")?,
+            mean_motion: field2(52..63)
+                .parse()
+                .map_err(|_| invalid2("mean motion"))?,
+            revolution_number: match field2(63..68) {
+                "" => 0,
+                text => text.parse().map_err(|_| invalid2("revolution number"))?,
+            },
+        })
+    }
+}
+
+/// Checks the length, line number and checksum of an element line.
+fn check_line(text: &str, number: u8, line: usize) -> Result<&str, TleError> {
+    if text.len() < LINE_LENGTH || !text.is_ascii() {
+        return Err(TleError::InvalidLength { line });
+    }
+    let bytes = text.as_bytes();
+    if bytes[0] != number || bytes[1] != b' ' {
+        return Err(TleError::InvalidLineNumber { line });
+    }
+
+    // Digits count for their value and minus signs for one, modulo ten
+    let sum: u32 = bytes[..LINE_LENGTH - 1]
+        .iter()
+        .map(|&byte| match byte {
+            b'0'..=b'9' => (byte - b'0') as u32,
+            b'-' => 1,
+            _ => 0,
+        })
+        .sum();
+    if bytes[LINE_LENGTH - 1] != b'0' + (sum % 10) as u8 {
+        return Err(TleError::InvalidChecksum { line });
+    }
+
+    Ok(&text[..LINE_LENGTH])
+}
+
+/// Decodes a satellite number, including the Alpha-5 scheme in which a leading letter stands
+/// for 10 to 33, skipping I and O.
+fn satellite_number(field: &str) -> Option<u32> {
+    let field = field.trim_start();
+    let first = field.chars().next()?;
+    if first.is_ascii_uppercase() && first != 'I' && first != 'O' {
+        let skipped = (first > 'I') as u32 + (first > 'O') as u32;
+        let high = first as u32 - 'A' as u32 + 10 - skipped;
+        let low: u32 = field[1..].parse().ok()?;
+        Some(high * 10_000 + low)
+    } else {
+        field.parse().ok()
+    }
+}
+
+/// Decodes a field with an assumed leading decimal point and a power of ten exponent, such as
+/// `-11606-4` for -0.11606e-4.
+fn exponential(field: &str) -> Option<f64> {
+    let field = field.trim();
+    if field.is_empty() {
+        return Some(0.0);
+    }
+    let split = field.len().checked_sub(2)?;
+    let (mantissa, exponent) = field.split_at(split);
+    let (sign, digits) = match mantissa.strip_prefix('-') {
+        Some(digits) => (-1.0, digits),
+        None => (1.0, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+    };
+    if digits.is_empty()
+        || !digits
+            .bytes()
+            .all(|byte| byte.is_ascii_digit() || byte == b' ')
+    {
+        return None;
+    }
+    let mantissa: f64 = format!("0.{}", digits.trim()).parse().ok()?;
+    let exponent: i32 = exponent.trim_start_matches('+').parse().ok()?;
+    Some(sign * mantissa * 10_f64.powi(exponent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE1: &str = "1 06251U 62025E   06176.82412014  .00008885  00000-0  12808-3 0  3985";
+    const LINE2: &str = "2 06251  58.0579  54.0425 0030035 139.1568 221.1854 15.56387291  6336";
+
+    #[test]
+    fn parse_test() {
+        let tle = Tle::parse(LINE1, LINE2).unwrap();
+        assert_eq!(tle.name, None);
+        assert_eq!(tle.satellite_number, 6251);
+        assert_eq!(tle.classification, 'U');
+        assert_eq!(tle.international_designator, "62025E");
+        assert_eq!(tle.mean_motion_dot, 0.000_088_85);
+        assert_eq!(tle.mean_motion_ddot, 0.0);
+        assert!((tle.bstar - 0.128_08e-3).abs() < 1e-18);
+        assert_eq!(tle.ephemeris_type, 0);
+        assert_eq!(tle.element_set_number, 398);
+        assert!((tle.inclination.to_degrees() - 58.0579).abs() < 1e-12);
+        assert!((tle.right_ascension.to_degrees() - 54.0425).abs() < 1e-12);
+        assert_eq!(tle.eccentricity, 0.003_003_5);
+        assert!((tle.argument_of_perigee.to_degrees() - 139.1568).abs() < 1e-12);
+        assert!((tle.mean_anomaly.to_degrees() - 221.1854).abs() < 1e-12);
+        assert_eq!(tle.mean_motion, 15.563_872_91);
+        assert_eq!(tle.revolution_number, 633);
+
+        let epoch = DateTime::from_day_of_year(2006, 176.824_120_14, TimeScale::UTC).unwrap();
+        assert_eq!(tle.epoch, epoch);
+        let (year, month, day, hour, minute, second) =
+            tle.epoch.to_gregorian(TimeScale::UTC).unwrap();
+        assert_eq!((year, month, day, hour, minute), (2006, 6, 25, 19, 46));
+        assert!((second - 43.980_096).abs() < 1e-3);
+    }
+
+    #[test]
+    fn fields_test() {
+        assert_eq!(exponential(" 00000-0"), Some(0.0));
+        assert_eq!(exponential("-11606-4"), Some(-0.116_06e-4));
+        assert_eq!(exponential(" 28098-4"), Some(0.280_98e-4));
+        assert_eq!(exponential("+50000+1"), Some(5.0));
+        assert_eq!(exponential("1x345-1"), None);
+
+        assert_eq!(satellite_number("00005"), Some(5));
+        assert_eq!(satellite_number("A0000"), Some(100_000));
+        assert_eq!(satellite_number("H9999"), Some(179_999));
+        assert_eq!(satellite_number("J0001"), Some(180_001));
+        assert_eq!(satellite_number("Z9999"), Some(339_999));
+        assert_eq!(satellite_number("I0000"), None);
+    }
+
+    #[test]
+    fn parse_all_test() {
+        let text = format!(
+            "\nISS (ZARYA)\n{LINE1}\n{LINE2}\n\n{LINE1}\n{LINE2}\n0 DEB\n{LINE1}\n{LINE2}\n"
+        );
+        let sets = Tle::parse_all(&text).unwrap();
+        assert_eq!(sets.len(), 3);
+        assert_eq!(sets[0].name.as_deref(), Some("ISS (ZARYA)"));
+        assert_eq!(sets[1].name, None);
+        assert_eq!(sets[2].name.as_deref(), Some("DEB"));
+        assert_eq!(
+            Tle::parse_3le("X", LINE1, LINE2).unwrap().name.as_deref(),
+            Some("X")
+        );
+
+        let truncated = format!("NAME\n{LINE1}\n");
+        assert_eq!(
+            Tle::parse_all(&truncated),
+            Err(TleError::MissingLine { line: 3 })
+        );
+    }
+
+    #[test]
+    fn errors_test() {
+        let corrupt = LINE1.replace("06176.824", "06176.825");
+        assert_eq!(
+            Tle::parse(&corrupt, LINE2),
+            Err(TleError::InvalidChecksum { line: 1 })
+        );
+        assert_eq!(
+            Tle::parse(&LINE1[..60], LINE2),
+            Err(TleError::InvalidLength { line: 1 })
+        );
+        assert_eq!(
+            Tle::parse(LINE2, LINE1),
+            Err(TleError::InvalidLineNumber { line: 1 })
+        );
+
+        let other = "2 06252  58.0579  54.0425 0030035 139.1568 221.1854 15.56387291  6337";
+        assert_eq!(
+            Tle::parse(LINE1, other),
+            Err(TleError::MismatchedSatelliteNumber { line: 2 })
+        );
+
+        let bad_field = "2 06251  58.0579  54.04x5 0030035 139.1568 221.1854 15.56387291  6334";
+        assert_eq!(
+            Tle::parse_all(&format!("\n\n{LINE1}\n{bad_field}")),
+            Err(TleError::InvalidField {
+                line: 4,
+                field: "right ascension"
+            })
+        );
+    }
+}
@@ -1 +1,15 @@
 use astro_carta::datetime;
+
+#[test]
+fn gregorian_test() {
+    let dt = datetime::DateTime::gregorian(2024, 2, 29, 12, 30, 15.25).unwrap();
+    assert_eq!(
+        dt.to_gregorian(datetime::TimeScale::TAI),
+        Some((2024, 2, 29, 12, 30, 15.25))
+    );
+
+    assert!(datetime::DateTime::gregorian(2023, 2, 29, 0, 0, 0.0).is_none());
+    assert!(datetime::DateTime::gregorian(2024, 1, 1, 24, 0, 0.0).is_none());
+    assert!(datetime::DateTime::gregorian(2024, 1, 1, 0, 60, 0.0).is_none());
+    assert!(datetime::DateTime::gregorian(2024, 1, 1, 0, 0, 60.0).is_none());
+}
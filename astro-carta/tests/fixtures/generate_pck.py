@@ -0,0 +1,98 @@
+#!/usr/bin/env python3
+"""Generates synthetic.bpc, a small binary PCK file exercising segment types 2 and 3.
+
+Every segment encodes Euler angles (phi, delta, w) as quadratic polynomials in TDB seconds since
+J2000 that the Chebyshev records reproduce exactly, so tests can compare against the
+polynomials directly. The coefficients must be kept in sync with tests/pck.rs.
+"""
+
+import struct
+
+RECORD_SIZE = 1024
+WORDS_PER_RECORD = RECORD_SIZE // 8
+FTPSTR = b"FTPSTR:\r:\n:\r\n:\r\x00:\x81:\x10\xce:ENDFTP"
+
+START = -864_000.0
+END = 864_000.0
+SPAN = END - START
+
+# Polynomial coefficients in radians and seconds, lowest degree first, per angle
+EARTH = [[0.1, 2e-9, 1e-16], [1.5, -3e-10, 2e-17], [4.0, 7.292115e-5, 0.0]]
+MOON = [[0.05, 1e-9, -2e-16], [0.4, 5e-10, 1e-16], [1.2, 2.6617e-6, 3e-16]]
+
+
+def chebyshev_quadratic(axis, midpoint, radius):
+    """Chebyshev coefficients of a + b t + c t^2 with t = midpoint + radius s."""
+    a, b, c = axis
+    return [
+        a + b * midpoint + c * midpoint**2 + c * radius**2 / 2,
+        (b + 2 * c * midpoint) * radius,
+        c * radius**2 / 2,
+    ]
+
+
+def type2(polynomial, records):
+    interval = SPAN / records
+    data = []
+    for index in range(records):
+        midpoint = START + (index + 0.5) * interval
+        radius = interval / 2
+        data += [midpoint, radius]
+        for axis in polynomial:
+            data += chebyshev_quadratic(axis, midpoint, radius)
+    return data + [START, interval, 2 + 9, records]
+
+
+def type3(polynomial, records):
+    interval = SPAN / records
+    data = []
+    for index in range(records):
+        midpoint = START + (index + 0.5) * interval
+        radius = interval / 2
+        data += [midpoint, radius]
+        for axis in polynomial:
+            data += chebyshev_quadratic(axis, midpoint, radius)
+        for a, b, c in polynomial:
+            data += [b + 2 * c * midpoint, 2 * c * radius, 0.0]
+    return data + [START, interval, 2 + 18, records]
+
+
+def main():
+    segments = [
+        ("ITRF93 TYPE 2", 3000, 1, 2, type2(EARTH, 4)),
+        ("MOON_PA TYPE 3", 31006, 17, 3, type3(MOON, 3)),
+        ("MOON_ME TYPE 20", 31007, 1, 20, [0.0]),
+    ]
+
+    address = 3 * WORDS_PER_RECORD + 1
+    summaries = b""
+    names = b""
+    data = b""
+    for name, frame, inertial_frame, data_type, words in segments:
+        start_address = address
+        address += len(words)
+        summaries += struct.pack(
+            "<2d5i4x", START, END, frame, inertial_frame, data_type, start_address, address - 1
+        )
+        names += name.encode().ljust(40)
+        data += struct.pack(f"<{len(words)}d", *words)
+
+    file_record = (
+        b"DAF/PCK "
+        + struct.pack("<2i", 2, 5)
+        + b"astro-carta synthetic PCK fixture".ljust(60)
+        + struct.pack("<3i", 2, 2, address)
+        + b"LTL-IEEE"
+        + b"\0" * 603
+        + FTPSTR
+    ).ljust(RECORD_SIZE, b"\0")
+    summary_record = (struct.pack("<3d", 0.0, 0.0, len(segments)) + summaries).ljust(RECORD_SIZE, b"\0")
+    name_record = names.ljust(RECORD_SIZE, b" ")
+    padding = -len(data) % RECORD_SIZE
+
+    with open("synthetic.bpc", "wb") as output:
+        output.write(file_record + summary_record + name_record + data + b"\0" * padding)
+
+
+if __name__ == "__main__":
+    main()
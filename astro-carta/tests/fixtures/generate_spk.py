@@ -0,0 +1,165 @@
+#!/usr/bin/env python3
+"""Generates synthetic.bsp, a small SPK file exercising segment types 2, 3, 9, 13 and 21.
+
+All segments are in the J2000 frame except that of the spacecraft, in ECLIPJ2000.
+
+Every segment encodes a polynomial in TDB seconds since J2000 that its interpolation scheme
+reproduces exactly, so tests can compare against the polynomials directly. The coefficients
+must be kept in sync with tests/spk.rs.
+"""
+
+import math
+import struct
+
+RECORD_SIZE = 1024
+WORDS_PER_RECORD = RECORD_SIZE // 8
+FTPSTR = b"FTPSTR:\r:\n:\r\n:\r\x00:\x81:\x10\xce:ENDFTP"
+
+START = -864_000.0
+END = 864_000.0
+SPAN = END - START
+
+# Polynomial coefficients in km and seconds, lowest degree first, per axis
+EMB = [[1.2e8, 15.0, 1e-6], [-8.0e7, 25.0, -2e-6], [3.0e7, 10.0, 5e-7]]
+EARTH = [[3000.0, -0.01, 1e-9], [-4000.0, 0.012, 2e-9], [1000.0, 0.0, -1e-9]]
+MOON = [[-2.0e5, -5e-3, 2e-10, 1e-17], [3.0e5, -3e-3, -1.5e-10, 2e-17], [1.0e4, 8e-4, 5e-11, -5e-18]]
+SUN = [
+    [-1.0e6, 1e-2, 1e-9, 1e-16, 1e-22, 1e-28],
+    [5.0e5, -2e-2, 2e-9, -1e-16, 2e-22, -1e-28],
+    [2.0e4, 1e-3, -1e-9, 5e-17, -1e-22, 3e-28],
+]
+ASTEROID = [[4.0e8, 5.0, 1e-7, 3e-14], [-1.0e8, -12.0, 2e-7, -1e-14], [2.0e7, 1.5, -5e-8, 2e-14]]
+SPACECRAFT = [[5.0e5, 0.3, 1e-8], [-2.0e5, 0.1, -2e-8], [4.0e4, -0.05, 1e-8]]
+
+# NAIF identifiers of the reference frames
+J2000 = 1
+ECLIPJ2000 = 17
+
+
+def evaluate(coefficients, t, derivative=0):
+    total = 0.0
+    for power, c in enumerate(coefficients):
+        if power >= derivative:
+            factor = math.prod(range(power - derivative + 1, power + 1))
+            total += factor * c * t ** (power - derivative)
+    return total
+
+
+def state(polynomial, t):
+    return [evaluate(axis, t) for axis in polynomial] + [evaluate(axis, t, 1) for axis in polynomial]
+
+
+def chebyshev_quadratic(axis, midpoint, radius):
+    """Chebyshev coefficients of a + b t + c t^2 with t = midpoint + radius s."""
+    a, b, c = axis
+    return [
+        a + b * midpoint + c * midpoint**2 + c * radius**2 / 2,
+        (b + 2 * c * midpoint) * radius,
+        c * radius**2 / 2,
+    ]
+
+
+def type2(polynomial, records):
+    interval = SPAN / records
+    data = []
+    for index in range(records):
+        midpoint = START + (index + 0.5) * interval
+        radius = interval / 2
+        data += [midpoint, radius]
+        for axis in polynomial:
+            data += chebyshev_quadratic(axis, midpoint, radius)
+    return data + [START, interval, 2 + 9, records]
+
+
+def type3(polynomial, records):
+    interval = SPAN / records
+    data = []
+    for index in range(records):
+        midpoint = START + (index + 0.5) * interval
+        radius = interval / 2
+        data += [midpoint, radius]
+        for axis in polynomial:
+            data += chebyshev_quadratic(axis, midpoint, radius)
+        for a, b, c in polynomial:
+            data += [b + 2 * c * midpoint, 2 * c * radius, 0.0]
+    return data + [START, interval, 2 + 18, records]
+
+
+def uneven_epochs(count):
+    # Monotonic but irregular spacing that still spans the whole interval
+    return [
+        START + SPAN * (u + 0.02 * math.sin(2 * math.pi * u) / (2 * math.pi))
+        for u in (i / (count - 1) for i in range(count))
+    ]
+
+
+def discrete(polynomial, count, window):
+    epochs = uneven_epochs(count)
+    data = []
+    for t in epochs:
+        data += state(polynomial, t)
+    data += epochs
+    data += [epochs[100 * k - 1] for k in range(1, (count - 1) // 100 + 1)]
+    return data + [window - 1, count]
+
+
+def type21(polynomial, records, max_dimension=15, step=3600.0):
+    finals = [START + SPAN * (k + 1) / records for k in range(records)]
+    data = []
+    for final in finals:
+        values = state(polynomial, final)
+        steps = [step] + [0.0] * (max_dimension - 1)
+        reference = []
+        for axis in range(3):
+            reference += [values[axis], values[axis + 3]]
+        differences = []
+        for axis in polynomial:
+            column = [evaluate(axis, final, 2), step * evaluate(axis, final, 3)]
+            differences += column + [0.0] * (max_dimension - 2)
+        data += [final] + steps + reference + differences + [3.0, 2.0, 2.0, 2.0]
+    data += finals
+    data += [finals[100 * k - 1] for k in range(1, records // 100 + 1)]
+    return data + [max_dimension, records]
+
+
+def main():
+    segments = [
+        ("EMB TYPE 2", 3, 0, J2000, 2, type2(EMB, 4)),
+        ("EARTH TYPE 3", 399, 3, J2000, 3, type3(EARTH, 2)),
+        ("MOON TYPE 9", 301, 3, J2000, 9, discrete(MOON, 250, 4)),
+        ("SUN TYPE 13", 10, 0, J2000, 13, discrete(SUN, 150, 4)),
+        ("ASTEROID TYPE 21", 2000001, 10, J2000, 21, type21(ASTEROID, 4)),
+        ("MARS TYPE 1", 499, 0, J2000, 1, [0.0]),
+        ("SPACECRAFT TYPE 2 ECLIPJ2000", -200, 3, ECLIPJ2000, 2, type2(SPACECRAFT, 2)),
+    ]
+
+    address = 3 * WORDS_PER_RECORD + 1
+    summaries = b""
+    names = b""
+    data = b""
+    for name, target, center, frame, data_type, words in segments:
+        start_address = address
+        address += len(words)
+        summaries += struct.pack("<2d6i", START, END, target, center, frame, data_type, start_address, address - 1)
+        names += name.encode().ljust(40)
+        data += struct.pack(f"<{len(words)}d", *words)
+
+    file_record = (
+        b"DAF/SPK "
+        + struct.pack("<2i", 2, 6)
+        + b"astro-carta synthetic SPK fixture".ljust(60)
+        + struct.pack("<3i", 2, 2, address)
+        + b"LTL-IEEE"
+        + b"\0" * 603
+        + FTPSTR
+    ).ljust(RECORD_SIZE, b"\0")
+    summary_record = (struct.pack("<3d", 0.0, 0.0, len(segments)) + summaries).ljust(RECORD_SIZE, b"\0")
+    name_record = names.ljust(RECORD_SIZE, b" ")
+    padding = -len(data) % RECORD_SIZE
+
+    with open("synthetic.bsp", "wb") as output:
+        output.write(file_record + summary_record + name_record + data + b"\0" * padding)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,120 @@
+use astro_carta::orbit::force::drag::nrlmsise::{MsisInput, MsisOutput, Nrlmsise00Coefficients};
+use astro_carta::orbit::force::drag::space_weather::ActivityIndices;
+use astro_carta::orbit::force::drag::{Nrlmsise00, SpaceWeather};
+
+/// The inputs of `nrlmsise-00_test.c`, each case differing from the first in one input
+fn inputs() -> [MsisInput; 17] {
+    let base = MsisInput {
+        day_of_year: 172.0,
+        seconds: 29000.0,
+        altitude: 400.0,
+        latitude: 60.0,
+        longitude: -70.0,
+        local_solar_time: 16.0,
+        f107_average: 150.0,
+        f107: 150.0,
+        ap: 4.0,
+        ap_history: None,
+    };
+    let mut inputs = [base; 17];
+    inputs[1].day_of_year = 81.0;
+    inputs[2].seconds = 75000.0;
+    inputs[2].altitude = 1000.0;
+    inputs[3].altitude = 100.0;
+    inputs[4].latitude = 0.0;
+    inputs[5].longitude = 0.0;
+    inputs[6].local_solar_time = 4.0;
+    inputs[7].f107_average = 70.0;
+    inputs[8].f107 = 180.0;
+    inputs[9].ap = 40.0;
+    for (index, altitude) in [(10, 0.0), (11, 10.0), (12, 30.0), (13, 50.0), (14, 70.0)] {
+        inputs[index].altitude = altitude;
+    }
+    inputs[15].ap_history = Some([100.0; 7]);
+    inputs[16].altitude = 100.0;
+    inputs[16].ap_history = Some([100.0; 7]);
+    inputs
+}
+
+// Outputs of `nrlmsise-00_test.c`, in the units of the C implementation: the number densities
+// of He, O, N₂, O₂, Ar in 1/cm³, the mass density in g/cm³, the number densities of H, N and
+// anomalous O in 1/cm³, then the exospheric temperature and the temperature in K
+#[rustfmt::skip]
+const OUTPUTS: [[f64; 11]; 17] = [
+    [6.665177e+05, 1.138806e+08, 1.998211e+07, 4.022764e+05, 3.557465e+03, 4.074714e-15,
+     3.475312e+04, 4.095913e+06, 2.667273e+04, 1.250540e+03, 1.241416e+03],
+    [3.407293e+06, 1.586333e+08, 1.391117e+07, 3.262560e+05, 1.559618e+03, 5.001846e-15,
+     4.854208e+04, 4.380967e+06, 6.956682e+03, 1.166754e+03, 1.161710e+03],
+    [1.123767e+05, 6.934130e+04, 4.247105e+01, 1.322750e-01, 2.618848e-05, 2.756772e-18,
+     2.016750e+04, 5.741256e+03, 2.374394e+04, 1.239892e+03, 1.239891e+03],
+    [5.411554e+07, 1.918893e+11, 6.115826e+12, 1.225201e+12, 6.023212e+10, 3.584426e-10,
+     1.059880e+07, 2.615737e+05, 2.819879e-42, 1.027318e+03, 2.068878e+02],
+    [1.851122e+06, 1.476555e+08, 1.579356e+07, 2.633795e+05, 1.588781e+03, 4.809630e-15,
+     5.816167e+04, 5.478984e+06, 1.264446e+03, 1.212396e+03, 1.208135e+03],
+    [8.673095e+05, 1.278862e+08, 1.822577e+07, 2.922214e+05, 2.402962e+03, 4.355866e-15,
+     3.686389e+04, 3.897276e+06, 2.667273e+04, 1.220146e+03, 1.212712e+03],
+    [5.776251e+05, 6.979139e+07, 1.236814e+07, 2.492868e+05, 1.405739e+03, 2.470651e-15,
+     5.291986e+04, 1.069814e+06, 2.667273e+04, 1.116385e+03, 1.112999e+03],
+    [3.740304e+05, 4.782720e+07, 5.240380e+06, 1.759875e+05, 5.501649e+02, 1.571889e-15,
+     8.896776e+04, 1.979741e+06, 9.121815e+03, 1.031247e+03, 1.024848e+03],
+    [6.748339e+05, 1.245315e+08, 2.369010e+07, 4.911583e+05, 4.578781e+03, 4.564420e-15,
+     3.244595e+04, 5.370833e+06, 2.667273e+04, 1.306052e+03, 1.293374e+03],
+    [5.528601e+05, 1.198041e+08, 3.495798e+07, 9.339618e+05, 1.096255e+04, 4.974543e-15,
+     2.686428e+04, 4.889974e+06, 2.805445e+04, 1.361868e+03, 1.347389e+03],
+    [1.375488e+14, 0.000000e+00, 2.049687e+19, 5.498695e+18, 2.451733e+17, 1.261066e-03,
+     0.000000e+00, 0.000000e+00, 0.000000e+00, 1.027318e+03, 2.814648e+02],
+    [4.427443e+13, 0.000000e+00, 6.597567e+18, 1.769929e+18, 7.891680e+16, 4.059139e-04,
+     0.000000e+00, 0.000000e+00, 0.000000e+00, 1.027318e+03, 2.274180e+02],
+    [2.127829e+12, 0.000000e+00, 3.170791e+17, 8.506280e+16, 3.792741e+15, 1.950822e-05,
+     0.000000e+00, 0.000000e+00, 0.000000e+00, 1.027318e+03, 2.374389e+02],
+    [1.412184e+11, 0.000000e+00, 2.104370e+16, 5.645392e+15, 2.517142e+14, 1.294709e-06,
+     0.000000e+00, 0.000000e+00, 0.000000e+00, 1.027318e+03, 2.795551e+02],
+    [1.254884e+10, 0.000000e+00, 1.874533e+15, 4.923051e+14, 2.239685e+13, 1.147668e-07,
+     0.000000e+00, 0.000000e+00, 0.000000e+00, 1.027318e+03, 2.190732e+02],
+    [5.196477e+05, 1.274494e+08, 4.850450e+07, 1.720838e+06, 2.354487e+04, 5.881940e-15,
+     2.500078e+04, 6.279210e+06, 2.667273e+04, 1.426412e+03, 1.408608e+03],
+    [4.260860e+07, 1.241342e+11, 4.929562e+12, 1.048407e+12, 4.993465e+10, 2.914304e-10,
+     8.831229e+06, 2.252516e+05, 2.415246e-42, 1.027318e+03, 1.934071e+02],
+];
+
+/// Returns the outputs in the order and units of the C implementation.
+fn c_outputs(output: &MsisOutput) -> [f64; 11] {
+    [
+        output.helium * 1e-6,
+        output.atomic_oxygen * 1e-6,
+        output.nitrogen * 1e-6,
+        output.oxygen * 1e-6,
+        output.argon * 1e-6,
+        output.mass_density * 1e-3,
+        output.hydrogen * 1e-6,
+        output.atomic_nitrogen * 1e-6,
+        output.anomalous_oxygen * 1e-6,
+        output.exospheric_temperature,
+        output.temperature,
+    ]
+}
+
+#[test]
+fn reference_test() {
+    // The coefficients of the C implementation are not distributed with the crate
+    let Some(path) = std::env::var_os("NRLMSISE_DATA") else {
+        eprintln!("NRLMSISE_DATA is not set, skipping the reference outputs");
+        return;
+    };
+    let text = std::fs::read_to_string(path).unwrap();
+    let coefficients = Nrlmsise00Coefficients::parse_c(&text).unwrap();
+    let indices = ActivityIndices::constant(150.0, 150.0, 4.0);
+    let model = Nrlmsise00::new(coefficients, SpaceWeather::constant(indices));
+
+    for (case, (input, expected)) in inputs().iter().zip(OUTPUTS).enumerate() {
+        let computed = c_outputs(&model.calculate(input));
+        for (index, (computed, expected)) in computed.into_iter().zip(expected).enumerate() {
+            // The outputs are printed to seven significant digits
+            assert!(
+                (computed - expected).abs() <= 1e-6 * expected.abs(),
+                "case {}: output {index} is {computed:e}, expected {expected:e}",
+                case + 1
+            );
+        }
+    }
+}
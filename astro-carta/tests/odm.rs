@@ -0,0 +1,300 @@
+use astro_carta::datetime::{DateTime, TimeDelta, TimeScale};
+use astro_carta::orbit::odm::{OdmError, Oem, Omm, Opm};
+use astro_carta::orbit::Anomaly;
+
+const OPM: &str = include_str!("fixtures/example.opm");
+const OEM: &str = include_str!("fixtures/example.oem");
+
+fn assert_close(actual: f64, expected: f64) {
+    let tolerance = 1e-12 * expected.abs().max(1.0);
+    assert!(
+        (actual - expected).abs() <= tolerance,
+        "{actual} != {expected}"
+    );
+}
+
+fn assert_opm_eq(actual: &Opm, expected: &Opm) {
+    assert_eq!(actual.header, expected.header);
+    assert_eq!(actual.metadata, expected.metadata);
+    assert_eq!(actual.comments, expected.comments);
+    assert_eq!(actual.epoch, expected.epoch);
+    for index in 0..3 {
+        assert_close(actual.state.position[index], expected.state.position[index]);
+        assert_close(actual.state.velocity[index], expected.state.velocity[index]);
+    }
+    let (elements, expected_elements) = (
+        actual.keplerian.as_ref().unwrap(),
+        expected.keplerian.as_ref().unwrap(),
+    );
+    assert_close(elements.semi_major_axis, expected_elements.semi_major_axis);
+    assert_close(elements.true_anomaly(), expected_elements.true_anomaly());
+    assert_eq!(actual.spacecraft, expected.spacecraft);
+
+    let (covariance, expected_covariance) = (
+        actual.covariance.as_ref().unwrap(),
+        expected.covariance.as_ref().unwrap(),
+    );
+    assert_eq!(covariance.ref_frame, expected_covariance.ref_frame);
+    for (row, expected_row) in covariance.matrix.iter().zip(&expected_covariance.matrix) {
+        for (value, expected_value) in row.iter().zip(expected_row) {
+            assert_close(*value, *expected_value);
+        }
+    }
+
+    assert_eq!(actual.maneuvers.len(), expected.maneuvers.len());
+    for (maneuver, expected_maneuver) in actual.maneuvers.iter().zip(&expected.maneuvers) {
+        assert_eq!(maneuver.comments, expected_maneuver.comments);
+        assert_eq!(maneuver.epoch_ignition, expected_maneuver.epoch_ignition);
+        assert_eq!(maneuver.ref_frame, expected_maneuver.ref_frame);
+        assert!((maneuver.delta_v - expected_maneuver.delta_v).norm() < 1e-12);
+    }
+}
+
+#[test]
+fn opm_test() {
+    let opm = Opm::parse(OPM).unwrap();
+    assert_eq!(opm.header.message_id.as_deref(), Some("OPM-201113719185"));
+    assert_eq!(opm.metadata.time_system, TimeScale::UTC);
+    assert_eq!(
+        opm.metadata.ref_frame_epoch,
+        DateTime::gregorian_with_scale(2006, 6, 3, 0, 0, 0.0, TimeScale::UTC)
+    );
+    assert_eq!(opm.comments, ["State Vector", "Keplerian elements"]);
+
+    let elements = opm.keplerian.as_ref().unwrap();
+    assert_close(elements.semi_major_axis, 41_399_512.3);
+    assert_close(elements.gravitational_parameter, 3.986004415e14);
+    assert_eq!(elements.anomaly, Anomaly::True(41.922339f64.to_radians()));
+    assert_eq!(opm.spacecraft.as_ref().unwrap().mass, Some(1913.0));
+
+    let covariance = opm.covariance.as_ref().unwrap();
+    assert_eq!(covariance.ref_frame.as_deref(), Some("RTN"));
+    assert_close(covariance.matrix[0][1], 461.8);
+    assert_close(covariance.matrix[1][0], 461.8);
+    assert_close(covariance.matrix[5][5], 6.224e-4);
+
+    assert_eq!(opm.maneuvers.len(), 2);
+    let maneuver = &opm.maneuvers[0];
+    assert_eq!(
+        maneuver.comments,
+        ["2 planned maneuvers", "First maneuver: AMF-3"]
+    );
+    assert_eq!(
+        maneuver.epoch_ignition,
+        DateTime::gregorian_with_scale(2000, 6, 3, 9, 0, 34.0, TimeScale::UTC).unwrap()
+            + TimeDelta::new(100_000_000)
+    );
+    assert_close(maneuver.delta_v.x, -23.257);
+
+    assert_opm_eq(&Opm::parse(&opm.to_kvn().unwrap()).unwrap(), &opm);
+    assert_opm_eq(&Opm::parse(&opm.to_xml().unwrap()).unwrap(), &opm);
+
+    // An epoch before the year 1 cannot be written
+    let mut ancient = opm.clone();
+    ancient.maneuvers[1].epoch_ignition = DateTime::from_julian_date(1e6, TimeScale::UTC);
+    for written in [ancient.to_kvn(), ancient.to_xml()] {
+        assert_eq!(
+            written,
+            Err(OdmError::InvalidEpoch {
+                keyword: "MAN_EPOCH_IGNITION"
+            })
+        );
+    }
+}
+
+#[test]
+fn omm_test() {
+    let text = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<omm id=\"CCSDS_OMM_VERS\" version=\"2.0\">
+  <header>
+    <CREATION_DATE>2007-065T16:00:00</CREATION_DATE>
+    <ORIGINATOR>NOAA/USA</ORIGINATOR>
+  </header>
+  <body>
+    <segment>
+      <metadata>
+        <OBJECT_NAME>GOES-9</OBJECT_NAME>
+        <OBJECT_ID>1995-025A</OBJECT_ID>
+        <CENTER_NAME>EARTH</CENTER_NAME>
+        <REF_FRAME>TEME</REF_FRAME>
+        <TIME_SYSTEM>UTC</TIME_SYSTEM>
+        <MEAN_ELEMENT_THEORY>SGP4</MEAN_ELEMENT_THEORY>
+      </metadata>
+      <data>
+        <meanElements>
+          <EPOCH>2007-064T10:34:41.4264</EPOCH>
+          <MEAN_MOTION>1.00273272</MEAN_MOTION>
+          <ECCENTRICITY>0.0005013</ECCENTRICITY>
+          <INCLINATION>3.0539</INCLINATION>
+          <RA_OF_ASC_NODE>81.7939</RA_OF_ASC_NODE>
+          <ARG_OF_PERICENTER>249.2363</ARG_OF_PERICENTER>
+          <MEAN_ANOMALY>150.1602</MEAN_ANOMALY>
+        </meanElements>
+        <tleParameters>
+          <NORAD_CAT_ID>23581</NORAD_CAT_ID>
+          <ELEMENT_SET_NO>0925</ELEMENT_SET_NO>
+          <REV_AT_EPOCH>4316</REV_AT_EPOCH>
+          <BSTAR units=\"1/ER\">0.0001</BSTAR>
+          <MEAN_MOTION_DOT>-0.00000113</MEAN_MOTION_DOT>
+          <MEAN_MOTION_DDOT>0.0</MEAN_MOTION_DDOT>
+        </tleParameters>
+      </data>
+    </segment>
+  </body>
+</omm>
+";
+    let omm = Omm::parse(text).unwrap();
+    assert_eq!(omm.mean_element_theory, "SGP4");
+    assert_eq!(
+        omm.epoch,
+        DateTime::gregorian_with_scale(2007, 3, 5, 10, 34, 41.0, TimeScale::UTC).unwrap()
+            + TimeDelta::new(426_400_000)
+    );
+    let parameters = omm.tle_parameters.as_ref().unwrap();
+    assert_eq!(parameters.classification_type, 'U');
+    assert_eq!(parameters.element_set_no, 925);
+
+    for round_trip in [omm.to_kvn().unwrap(), omm.to_xml().unwrap()] {
+        let parsed = Omm::parse(&round_trip).unwrap();
+        assert_eq!(parsed.epoch, omm.epoch);
+        assert_eq!(parsed.size, omm.size);
+        assert_eq!(parsed.tle_parameters, omm.tle_parameters);
+        assert_close(parsed.mean_anomaly, omm.mean_anomaly);
+    }
+}
+
+#[test]
+fn oem_test() {
+    let oem = Oem::parse(OEM).unwrap();
+    assert_eq!(oem.segments.len(), 2);
+    let segment = &oem.segments[0];
+    assert_eq!(segment.interpolation.as_deref(), Some("HERMITE"));
+    assert_eq!(segment.interpolation_degree, Some(7));
+    assert_eq!(segment.states.len(), 4);
+    let acceleration = segment.states[0].acceleration.unwrap();
+    assert_close(acceleration.z, 1.0);
+    assert_close(segment.states[0].state.position.y, -63_042.0);
+    assert_eq!(segment.covariances.len(), 2);
+    assert_close(segment.covariances[1].matrix[0][0], 344.24505);
+    assert_eq!(oem.segments[1].states[1].acceleration, None);
+
+    for round_trip in [oem.to_kvn().unwrap(), oem.to_xml().unwrap()] {
+        let parsed = Oem::parse(&round_trip).unwrap();
+        assert_eq!(parsed.header, oem.header);
+        for (segment, expected) in parsed.segments.iter().zip(&oem.segments) {
+            assert_eq!(segment.metadata, expected.metadata);
+            assert_eq!(segment.useable_stop_time, expected.useable_stop_time);
+            assert_eq!(segment.comments, expected.comments);
+            assert_eq!(segment.states.len(), expected.states.len());
+            for (state, expected_state) in segment.states.iter().zip(&expected.states) {
+                assert_eq!(state.epoch, expected_state.epoch);
+                assert!((state.state.position - expected_state.state.position).norm() < 1e-9);
+                assert_eq!(
+                    state.acceleration.is_some(),
+                    expected_state.acceleration.is_some()
+                );
+            }
+            assert_eq!(segment.covariances.len(), expected.covariances.len());
+            for (covariance, expected_covariance) in
+                segment.covariances.iter().zip(&expected.covariances)
+            {
+                assert_eq!(covariance.epoch, expected_covariance.epoch);
+                assert_close(covariance.matrix[5][4], expected_covariance.matrix[5][4]);
+            }
+        }
+    }
+}
+
+#[test]
+fn error_test() {
+    let replace = |text: &str, from: &str, to: &str| {
+        assert!(text.contains(from), "{from}");
+        text.replacen(from, to, 1)
+    };
+
+    let cases = [
+        (
+            Opm::parse(&replace(OPM, "TIME_SYSTEM = UTC", "TIME_SYSTEM = MET")).unwrap_err(),
+            OdmError::UnsupportedTimeSystem {
+                line: 13,
+                name: "MET".to_string(),
+            },
+        ),
+        (
+            Opm::parse(&replace(OPM, "T00:00:00.000\nX", "T00:00:60.000\nX")).unwrap_err(),
+            OdmError::InvalidValue {
+                line: 17,
+                keyword: "EPOCH".to_string(),
+            },
+        ),
+        (
+            Opm::parse(&replace(OPM, "Y = -40218.5751 [km]\n", "")).unwrap_err(),
+            OdmError::MissingKeyword {
+                line: 19,
+                keyword: "Y",
+            },
+        ),
+        (
+            Opm::parse(&replace(
+                OPM,
+                "CCSDS_OPM_VERS = 3.0",
+                "CCSDS_OPM_VERS = 4.0",
+            ))
+            .unwrap_err(),
+            OdmError::UnsupportedVersion { line: 1 },
+        ),
+        (
+            Opm::parse(&replace(OPM, "DRAG_COEFF = 2.300", "DRAG_COEFF = 2,3")).unwrap_err(),
+            OdmError::InvalidValue {
+                line: 39,
+                keyword: "DRAG_COEFF".to_string(),
+            },
+        ),
+        (
+            Opm::parse(&format!("{OPM}USER_DEFINED_X = 1\n")).unwrap_err(),
+            OdmError::UnexpectedKeyword {
+                line: 82,
+                keyword: "USER_DEFINED_X".to_string(),
+            },
+        ),
+        (
+            Oem::parse(&replace(
+                OEM,
+                "1996-12-28T21:59:02.267",
+                "1996-12-28T22:59:02.267",
+            ))
+            .unwrap_err(),
+            OdmError::InvalidData { line: 23 },
+        ),
+        (
+            Oem::parse(&replace(OEM, " 0.88535\n1996", " \n1996")).unwrap_err(),
+            OdmError::InvalidData { line: 56 },
+        ),
+        (
+            Oem::parse(&replace(
+                OEM,
+                "-4.2212341e-04 3.2319319e-04",
+                "-4.2212341e-04",
+            ))
+            .unwrap_err(),
+            OdmError::InvalidData { line: 31 },
+        ),
+        (
+            Oem::parse(&replace(
+                OEM,
+                "START_TIME = 1996-12-28T21:29:07.267",
+                "START_TIME = 1996-12-28T21:30:00",
+            ))
+            .unwrap_err(),
+            OdmError::InvalidData { line: 21 },
+        ),
+    ];
+    for (error, expected) in cases {
+        assert_eq!(error, expected);
+    }
+
+    let xml = Opm::parse(OPM).unwrap().to_xml().unwrap();
+    let error = Opm::parse(&xml.replace("</ORIGINATOR>", "</ORIGIN>")).unwrap_err();
+    assert!(matches!(error, OdmError::InvalidXml { line: 6 }), "{error}");
+    assert_eq!(error.to_string(), "line 6: malformed XML");
+}
@@ -0,0 +1,147 @@
+use astro_carta::datetime::{DateTime, TimeScale};
+use astro_carta::ephemeris::pck::{BinaryPck, PckError};
+use astro_carta::math::{Matrix3, Vector3};
+
+// Euler angle polynomials encoded in tests/fixtures/synthetic.bpc by generate_pck.py, in radians
+// and TDB seconds since J2000, lowest degree first
+const EARTH: [[f64; 3]; 3] = [
+    [0.1, 2e-9, 1e-16],
+    [1.5, -3e-10, 2e-17],
+    [4.0, 7.292115e-5, 0.0],
+];
+const MOON: [[f64; 3]; 3] = [
+    [0.05, 1e-9, -2e-16],
+    [0.4, 5e-10, 1e-16],
+    [1.2, 2.6617e-6, 3e-16],
+];
+
+/// Obliquity of the ecliptic at J2000.0 in radians
+const OBLIQUITY: f64 = 84_381.448 / 3_600.0 * std::f64::consts::PI / 180.0;
+
+fn open() -> BinaryPck<std::io::BufReader<std::fs::File>> {
+    BinaryPck::open(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/synthetic.bpc"
+    ))
+    .unwrap()
+}
+
+/// Evaluates the Euler angles and their rates at TDB seconds since J2000.
+fn angles(polynomial: [[f64; 3]; 3], t: f64) -> ([f64; 3], [f64; 3]) {
+    (
+        polynomial.map(|[a, b, c]| a + b * t + c * t * t),
+        polynomial.map(|[_, b, c]| b + 2.0 * c * t),
+    )
+}
+
+fn rotation([phi, delta, w]: [f64; 3]) -> Matrix3 {
+    Matrix3::rot_z(w) * Matrix3::rot_x(delta) * Matrix3::rot_z(phi)
+}
+
+fn assert_matrix_close(actual: Matrix3, expected: Matrix3) {
+    for i in 0..3 {
+        let error = (actual.row(i) - expected.row(i)).norm();
+        assert!(error < 1e-12, "row {i} error {error}");
+    }
+}
+
+fn epochs() -> Vec<DateTime> {
+    [-10.0, -3.7, 0.0, 1.234_567, 6.5, 10.0]
+        .iter()
+        .map(|&days| DateTime::from_days_since_j2000(days, TimeScale::TDB))
+        .collect()
+}
+
+#[test]
+fn segments_test() {
+    let pck = open();
+    let segments = pck.segments();
+    assert_eq!(segments.len(), 3);
+    assert_eq!(segments[0].name, "ITRF93 TYPE 2");
+    assert_eq!(
+        segments
+            .iter()
+            .map(|segment| (segment.frame, segment.inertial_frame, segment.data_type))
+            .collect::<Vec<_>>(),
+        [(3000, 1, 2), (31006, 17, 3), (31007, 1, 20)]
+    );
+    assert_eq!(segments[1].start, -864_000.0);
+    assert_eq!(segments[1].end, 864_000.0);
+}
+
+#[test]
+fn euler_angles_test() {
+    let pck = open();
+    for dt in epochs() {
+        let t = dt.days_since_j2000(TimeScale::TDB) * 86_400.0;
+        for (frame, polynomial) in [(3000, EARTH), (31006, MOON)] {
+            let (values, rates, _) = pck.euler_angles(frame, &dt).unwrap();
+            let (expected_values, expected_rates) = angles(polynomial, t);
+            for i in 0..3 {
+                assert!((values[i] - expected_values[i]).abs() < 1e-12);
+                assert!((rates[i] - expected_rates[i]).abs() < 1e-17);
+            }
+        }
+    }
+}
+
+#[test]
+fn rotation_test() {
+    let pck = open();
+    for dt in epochs() {
+        let t = dt.days_since_j2000(TimeScale::TDB) * 86_400.0;
+
+        let earth = pck.rotation_to_body_fixed(3000, &dt).unwrap();
+        assert_matrix_close(earth, rotation(angles(EARTH, t).0));
+        assert_matrix_close(
+            pck.rotation_to_inertial(3000, &dt).unwrap() * earth,
+            Matrix3::identity(),
+        );
+
+        // Lunar angles are relative to the ecliptic, so the ecliptic pole maps through them
+        let moon = pck.rotation_to_body_fixed(31006, &dt).unwrap();
+        assert_matrix_close(
+            moon,
+            rotation(angles(MOON, t).0) * Matrix3::rot_x(OBLIQUITY),
+        );
+        let ecliptic_pole = Vector3::from_spherical(
+            -std::f64::consts::FRAC_PI_2,
+            std::f64::consts::FRAC_PI_2 - OBLIQUITY,
+        );
+        let expected = rotation(angles(MOON, t).0) * Vector3::new(0.0, 0.0, 1.0);
+        assert!((moon * ecliptic_pole - expected).norm() < 1e-12);
+    }
+}
+
+#[test]
+fn errors_test() {
+    let pck = open();
+    let dt = DateTime::from_days_since_j2000(0.0, TimeScale::TDB);
+    assert!(matches!(
+        pck.rotation_to_body_fixed(31007, &dt),
+        Err(PckError::UnsupportedType {
+            frame: 31007,
+            data_type: 20
+        })
+    ));
+    assert!(matches!(
+        pck.rotation_to_body_fixed(10_013, &dt),
+        Err(PckError::NoCoverage { frame: 10_013 })
+    ));
+
+    let outside = DateTime::from_days_since_j2000(11.0, TimeScale::TDB);
+    assert!(matches!(
+        pck.rotation_to_body_fixed(3000, &outside),
+        Err(PckError::NoCoverage { frame: 3000 })
+    ));
+
+    let spk = std::fs::read(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/synthetic.bsp"
+    ))
+    .unwrap();
+    assert!(matches!(
+        BinaryPck::from_reader(std::io::Cursor::new(spk)),
+        Err(PckError::NotPck)
+    ));
+}
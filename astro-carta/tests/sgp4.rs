@@ -0,0 +1,117 @@
+use astro_carta::datetime::TimeDelta;
+use astro_carta::orbit::sgp4::Sgp4;
+use astro_carta::orbit::tle::Tle;
+use astro_carta::orbit::Propagator;
+
+// TEME states in km and km/s from the verification output of Vallado et al. (2006),
+// Revisiting Spacetrack Report #3, as (satellite, minutes since epoch, position, velocity)
+const CASES: [(u32, f64, [f64; 3], [f64; 3]); 12] = [
+    (
+        5,
+        0.0,
+        [7022.46529266, -1400.08296755, 0.03995155],
+        [1.893841015, 6.405893759, 4.534807250],
+    ),
+    (
+        5,
+        360.0,
+        [-7154.03120202, -3783.17682504, -3536.19412294],
+        [4.741887409, -4.151817765, -2.093935425],
+    ),
+    (
+        5,
+        720.0,
+        [-7134.59340119, 6531.68641334, 3260.27186483],
+        [-4.113793027, -2.911922039, -2.557327851],
+    ),
+    (
+        5,
+        1080.0,
+        [5568.53901181, 4492.06992591, 3863.87641983],
+        [-4.209106476, 5.159719888, 2.744852980],
+    ),
+    (
+        6251,
+        0.0,
+        [3988.31022699, 5498.96657235, 0.90055879],
+        [-3.290032738, 2.357652820, 6.496623475],
+    ),
+    (
+        6251,
+        120.0,
+        [-3935.69800083, 409.10980837, 5471.33577327],
+        [-3.374784183, -6.635211043, -1.942056221],
+    ),
+    (
+        6251,
+        240.0,
+        [-1675.12766915, -5683.30432352, -3286.21510937],
+        [5.282496925, 1.508674259, -5.354872978],
+    ),
+    (
+        6251,
+        360.0,
+        [4993.62642836, 2890.54969900, -3600.40145627],
+        [0.347333429, 5.707031557, 5.070699638],
+    ),
+    (
+        11801,
+        0.0,
+        [7473.37102491, 428.94748312, 5828.74846783],
+        [5.107155391, 6.444680305, -0.186133297],
+    ),
+    (
+        88888,
+        0.0,
+        [2328.96975262, -5995.22051338, 1719.97297192],
+        [2.912073281, -0.983417956, -7.090816210],
+    ),
+    // A geosynchronous orbit, integrated through the synchronous resonance
+    (
+        28626,
+        0.0,
+        [42080.71852213, -2646.86387436, 0.81851294],
+        [0.193105177, 3.068688251, 0.000438449],
+    ),
+    (
+        28626,
+        120.0,
+        [37740.00085593, 18802.76872802, 3.45512584],
+        [-1.371035206, 2.752105932, 0.000336883],
+    ),
+];
+
+fn load() -> Vec<Tle> {
+    let text = std::fs::read_to_string(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/verification.tle"
+    ))
+    .unwrap();
+    Tle::parse_all(&text).unwrap()
+}
+
+#[test]
+fn verification_test() {
+    let sets = load();
+    assert_eq!(sets.len(), 6);
+    assert_eq!(sets[0].name.as_deref(), Some("VANGUARD 1"));
+
+    for (satellite, minutes, position, velocity) in CASES {
+        let tle = sets
+            .iter()
+            .find(|tle| tle.satellite_number == satellite)
+            .unwrap();
+        let sgp4 = Sgp4::new(tle).unwrap();
+        assert_eq!(sgp4.is_deep_space(), matches!(satellite, 11801 | 28626));
+
+        let state = sgp4.propagate_minutes(minutes).unwrap();
+        for i in 0..3 {
+            assert!((state.position[i] / 1e3 - position[i]).abs() < 1e-7);
+            assert!((state.velocity[i] / 1e3 - velocity[i]).abs() < 1e-9);
+        }
+
+        let epoch = tle.epoch + TimeDelta::minutes(minutes);
+        let state = sgp4.propagate(&epoch).unwrap();
+        assert!((state.position.x / 1e3 - position[0]).abs() < 1e-7);
+    }
+}
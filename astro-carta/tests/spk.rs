@@ -0,0 +1,239 @@
+use astro_carta::datetime::{DateTime, TimeScale};
+use astro_carta::ephemeris::spk::{Spk, SpkError};
+use astro_carta::ephemeris::{Ephemeris, StateVector};
+use astro_carta::math::Vector3;
+
+// Polynomials encoded in tests/fixtures/synthetic.bsp by generate_spk.py, in km and TDB
+// seconds since J2000, lowest degree first
+const EMB: [&[f64]; 3] = [
+    &[1.2e8, 15.0, 1e-6],
+    &[-8.0e7, 25.0, -2e-6],
+    &[3.0e7, 10.0, 5e-7],
+];
+const EARTH: [&[f64]; 3] = [
+    &[3000.0, -0.01, 1e-9],
+    &[-4000.0, 0.012, 2e-9],
+    &[1000.0, 0.0, -1e-9],
+];
+const MOON: [&[f64]; 3] = [
+    &[-2.0e5, -5e-3, 2e-10, 1e-17],
+    &[3.0e5, -3e-3, -1.5e-10, 2e-17],
+    &[1.0e4, 8e-4, 5e-11, -5e-18],
+];
+const SUN: [&[f64]; 3] = [
+    &[-1.0e6, 1e-2, 1e-9, 1e-16, 1e-22, 1e-28],
+    &[5.0e5, -2e-2, 2e-9, -1e-16, 2e-22, -1e-28],
+    &[2.0e4, 1e-3, -1e-9, 5e-17, -1e-22, 3e-28],
+];
+const ASTEROID: [&[f64]; 3] = [
+    &[4.0e8, 5.0, 1e-7, 3e-14],
+    &[-1.0e8, -12.0, 2e-7, -1e-14],
+    &[2.0e7, 1.5, -5e-8, 2e-14],
+];
+// In ECLIPJ2000, unlike the other segments
+const SPACECRAFT: [&[f64]; 3] = [
+    &[5.0e5, 0.3, 1e-8],
+    &[-2.0e5, 0.1, -2e-8],
+    &[4.0e4, -0.05, 1e-8],
+];
+
+fn open() -> Spk<std::io::BufReader<std::fs::File>> {
+    Spk::open(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/synthetic.bsp"
+    ))
+    .unwrap()
+}
+
+/// Evaluates a polynomial state in meters at TDB seconds since J2000.
+fn polynomial_state(polynomial: [&[f64]; 3], t: f64) -> StateVector {
+    let axis = |coefficients: &[f64]| {
+        let value: f64 = coefficients.iter().rev().fold(0.0, |sum, c| sum * t + c);
+        let rate: f64 = coefficients
+            .iter()
+            .enumerate()
+            .skip(1)
+            .rev()
+            .fold(0.0, |sum, (power, c)| sum * t + power as f64 * c);
+        (value * 1e3, rate * 1e3)
+    };
+    let [x, y, z] = polynomial.map(axis);
+    StateVector {
+        position: Vector3::new(x.0, y.0, z.0),
+        velocity: Vector3::new(x.1, y.1, z.1),
+    }
+}
+
+fn assert_close(actual: StateVector, expected: StateVector) {
+    let position_error = (actual.position - expected.position).norm();
+    let velocity_error = (actual.velocity - expected.velocity).norm();
+    assert!(position_error < 1e-3, "position error {position_error} m");
+    assert!(velocity_error < 1e-8, "velocity error {velocity_error} m/s");
+}
+
+fn epochs() -> Vec<DateTime> {
+    [-10.0, -7.3, -5.0, -0.01, 0.0, 2.345_678, 4.999_9, 9.5, 10.0]
+        .iter()
+        .map(|&days| DateTime::from_days_since_j2000(days, TimeScale::TDB))
+        .collect()
+}
+
+fn seconds(dt: &DateTime) -> f64 {
+    dt.days_since_j2000(TimeScale::TDB) * 86_400.0
+}
+
+#[test]
+fn segments_test() {
+    let spk = open();
+    let segments = spk.segments();
+    assert_eq!(segments.len(), 7);
+    assert_eq!(segments[0].name, "EMB TYPE 2");
+    assert_eq!(
+        segments
+            .iter()
+            .map(|segment| (segment.target, segment.center, segment.data_type))
+            .collect::<Vec<_>>(),
+        [
+            (3, 0, 2),
+            (399, 3, 3),
+            (301, 3, 9),
+            (10, 0, 13),
+            (2_000_001, 10, 21),
+            (499, 0, 1),
+            (-200, 3, 2)
+        ]
+    );
+    assert_eq!(segments[2].frame, 1);
+    assert_eq!(segments[6].frame, 17);
+    assert!((segments[0].start_epoch().days_since_j2000(TimeScale::TDB) + 10.0).abs() < 1e-9);
+    assert!((segments[0].end_epoch().days_since_j2000(TimeScale::TDB) - 10.0).abs() < 1e-9);
+}
+
+#[test]
+fn chebyshev_segments_test() {
+    let spk = open();
+    for dt in epochs() {
+        let t = seconds(&dt);
+        assert_close(spk.state(3, 0, &dt).unwrap(), polynomial_state(EMB, t));
+        assert_close(spk.state(399, 3, &dt).unwrap(), polynomial_state(EARTH, t));
+    }
+}
+
+#[test]
+fn interpolated_segments_test() {
+    let spk = open();
+    for dt in epochs() {
+        let t = seconds(&dt);
+        assert_close(spk.state(301, 3, &dt).unwrap(), polynomial_state(MOON, t));
+        assert_close(spk.state(10, 0, &dt).unwrap(), polynomial_state(SUN, t));
+    }
+}
+
+#[test]
+fn difference_array_segment_test() {
+    let spk = open();
+    for dt in epochs() {
+        let t = seconds(&dt);
+        assert_close(
+            spk.state(2_000_001, 10, &dt).unwrap(),
+            polynomial_state(ASTEROID, t),
+        );
+    }
+}
+
+#[test]
+fn chain_test() {
+    let spk = open();
+    for dt in epochs() {
+        let t = seconds(&dt);
+        let emb = polynomial_state(EMB, t);
+        let earth = polynomial_state(EARTH, t);
+        let moon = polynomial_state(MOON, t);
+
+        // Through the common ancestor, the Earth-Moon barycenter
+        assert_close(spk.state(301, 399, &dt).unwrap(), moon - earth);
+        assert_close(spk.state(399, 301, &dt).unwrap(), earth - moon);
+
+        // Through the solar system barycenter
+        let asteroid = polynomial_state(ASTEROID, t) + polynomial_state(SUN, t);
+        assert_close(
+            spk.state(2_000_001, 399, &dt).unwrap(),
+            asteroid - earth - emb,
+        );
+        assert_close(spk.state(399, 0, &dt).unwrap(), earth + emb);
+        assert_eq!(
+            spk.position(399, 0, &dt).unwrap(),
+            spk.state(399, 0, &dt).unwrap().position
+        );
+
+        let zero = spk.state(399, 399, &dt).unwrap();
+        assert_eq!(zero.position, Vector3::zeros());
+    }
+}
+
+#[test]
+fn errors_test() {
+    let spk = open();
+    let dt = DateTime::from_days_since_j2000(0.0, TimeScale::TDB);
+    assert!(matches!(
+        spk.state(499, 0, &dt),
+        Err(SpkError::UnsupportedType {
+            target: 499,
+            data_type: 1
+        })
+    ));
+    assert!(matches!(
+        spk.state(12_345, 0, &dt),
+        Err(SpkError::NoPath {
+            target: 12_345,
+            center: 0
+        })
+    ));
+
+    let outside = DateTime::from_days_since_j2000(11.0, TimeScale::TDB);
+    assert!(matches!(
+        spk.state(301, 399, &outside),
+        Err(SpkError::NoPath { .. })
+    ));
+
+    // The spacecraft is given in ECLIPJ2000 and the other bodies in J2000
+    let t = seconds(&dt);
+    assert_close(
+        spk.state(-200, 3, &dt).unwrap(),
+        polynomial_state(SPACECRAFT, t),
+    );
+    for (target, center) in [(-200, 399), (399, -200), (-200, 301), (-200, 0)] {
+        assert!(matches!(
+            spk.state(target, center, &dt),
+            Err(SpkError::FrameMismatch { target: t, center: c }) if t == target && c == center
+        ));
+    }
+
+    // A NaN record count in the directory of the Chebyshev segment of the Earth-Moon
+    // barycenter, which holds 4 records of 11 words
+    let mut bytes = std::fs::read(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/synthetic.bsp"
+    ))
+    .unwrap();
+    let directory: Vec<u8> = [11.0f64, 4.0]
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .collect();
+    let offset = bytes
+        .windows(16)
+        .position(|window| window == directory.as_slice())
+        .unwrap();
+    bytes[offset + 8..offset + 16].copy_from_slice(&f64::NAN.to_le_bytes());
+    let corrupt = Spk::from_reader(std::io::Cursor::new(bytes)).unwrap();
+    assert!(matches!(
+        corrupt.state(3, 0, &dt),
+        Err(SpkError::InvalidSegment {
+            target: 3,
+            center: 0
+        })
+    ));
+
+    let not_daf = std::io::Cursor::new(vec![0u8; 2048]);
+    assert!(Spk::from_reader(not_daf).is_err());
+}
@@ -0,0 +1,144 @@
+use std::f64::consts::TAU;
+use std::path::PathBuf;
+
+use astro_carta::datetime::{DateTime, TimeScale};
+use astro_carta::ephemeris::vsop87::{Planet, Vsop87};
+
+const PLANETS: [Planet; 8] = [
+    Planet::Mercury,
+    Planet::Venus,
+    Planet::Earth,
+    Planet::Mars,
+    Planet::Jupiter,
+    Planet::Saturn,
+    Planet::Uranus,
+    Planet::Neptune,
+];
+
+/// Directory holding the official IMCCE files, such as `VSOP87B.nep` and `vsop87.chk`, which are
+/// too large to be distributed with the crate
+fn official_directory() -> Option<PathBuf> {
+    std::env::var_os("VSOP87_DIR").map(PathBuf::from)
+}
+
+/// A check value of `vsop87.chk`: the version, the planet, the Julian date in TDB and the three
+/// coordinates
+struct CheckValue {
+    version: char,
+    planet: Planet,
+    julian_date: f64,
+    coordinates: [f64; 3],
+}
+
+/// Parses the check file, a header line such as `VSOP87D  MERCURY   JD2451545.0  01/01/2000 12h
+/// TDB` followed by the coordinates and their rates, each as a name, a value and a unit.
+fn parse_check_values(text: &str) -> Vec<CheckValue> {
+    let mut values = Vec::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (Some(version), Some(name), Some(julian_date)) = (
+            fields
+                .first()
+                .and_then(|field| field.strip_prefix("VSOP87")),
+            fields.get(1),
+            fields.get(2).and_then(|field| field.strip_prefix("JD")),
+        ) else {
+            continue;
+        };
+        // The main version, in elliptic elements, is not evaluated by the crate
+        let (Some(version), Some(planet)) = (
+            version
+                .chars()
+                .next()
+                .filter(|code| ('A'..='D').contains(code)),
+            PLANETS
+                .into_iter()
+                .find(|planet| planet.name().eq_ignore_ascii_case(name)),
+        ) else {
+            continue;
+        };
+        let numbers: Vec<f64> = lines
+            .next()
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter_map(|field| field.parse().ok())
+            .collect();
+        values.push(CheckValue {
+            version,
+            planet,
+            julian_date: julian_date.parse().unwrap(),
+            coordinates: [numbers[0], numbers[1], numbers[2]],
+        });
+    }
+    values
+}
+
+#[test]
+fn official_check_values_test() {
+    let Some(directory) = official_directory() else {
+        eprintln!("VSOP87_DIR is not set, skipping the official check values");
+        return;
+    };
+    let check = std::fs::read_to_string(directory.join("vsop87.chk")).unwrap();
+    let values = parse_check_values(&check);
+
+    let mut checked = 0;
+    for version in ['A', 'B', 'C', 'D'] {
+        for planet in PLANETS {
+            let path = directory.join(format!("VSOP87{version}.{}", planet.file_extension()));
+            let Ok(text) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let theory = Vsop87::parse(&text).unwrap();
+            assert_eq!(theory.planet(), planet);
+            for value in values
+                .iter()
+                .filter(|value| value.version == version && value.planet == planet)
+            {
+                let dt = DateTime::from_julian_date(value.julian_date, TimeScale::TDB);
+                let coordinates = theory.coordinates(&dt);
+                for (index, (computed, expected)) in
+                    coordinates.iter().zip(value.coordinates).enumerate()
+                {
+                    // The check values are given to 1e-10, longitudes reduced to [0, 2π)
+                    let difference = if theory.version().is_spherical() && index == 0 {
+                        (computed - expected + TAU / 2.0).rem_euclid(TAU) - TAU / 2.0
+                    } else {
+                        computed - expected
+                    };
+                    assert!(
+                        difference.abs() < 1e-9,
+                        "VSOP87{version} {} at JD {}: coordinate {index} is {computed}, \
+                         expected {expected}",
+                        planet.name(),
+                        value.julian_date
+                    );
+                }
+                checked += 1;
+            }
+        }
+    }
+    assert!(checked > 0, "no check value matched an official file");
+}
+
+#[test]
+fn parse_check_values_test() {
+    // Synthetic values in the layout of the official file
+    let text = "\
+ VSOP87D  MERCURY   JD2451545.0  01/01/2000 12h TDB
+ l  1.2345678901 rad     b -0.0123456789 rad     r  0.4567890123 au
+ l'  0.0712345678 rad/d  b' -0.0001234567 rad/d  r'  0.0012345678 au/d
+ VSOP87   MERCURY   JD2451545.0  01/01/2000 12h TDB
+ a  0.3870000000 au      l  1.0000000000 rad     k  0.0400000000
+";
+    let values = parse_check_values(text);
+    assert_eq!(values.len(), 1);
+    assert_eq!(values[0].version, 'D');
+    assert_eq!(values[0].planet, Planet::Mercury);
+    assert_eq!(values[0].julian_date, 2_451_545.0);
+    assert_eq!(
+        values[0].coordinates,
+        [1.234_567_890_1, -0.012_345_678_9, 0.456_789_012_3]
+    );
+}